@@ -0,0 +1,39 @@
+/* Generated by citadel::errors::codes::c_header(). Do not edit. */
+#ifndef CITADEL_ERRORS_H
+#define CITADEL_ERRORS_H
+
+#define CITADEL_OK 0x0000
+#define CITADEL_E_VERIFICATION_FAILED 0x0101 /* verification failed */
+#define CITADEL_E_DECRYPTION_FAILED 0x0102 /* decryption failed */
+#define CITADEL_E_INVALID_CIPHERTEXT 0x0103 /* invalid ciphertext */
+#define CITADEL_E_KEY_ENCAPSULATION_FAILED 0x0104 /* key encapsulation failed */
+#define CITADEL_E_INTERNAL_FAILURE 0x0105 /* internal process failed */
+#define CITADEL_E_OPERATION_FAILED 0x0106 /* cryptographic operation failed */
+#define CITADEL_E_RANDOMNESS_UNAVAILABLE 0x0107 /* randomness unavailable */
+#define CITADEL_E_SIGNER_UNAVAILABLE 0x0108 /* signer unavailable */
+#define CITADEL_E_RANDOMNESS_FAILURE 0x0109 /* randomness health test failed */
+#define CITADEL_E_INVALID_KEY_LENGTH 0x0201 /* invalid key length for algorithm */
+#define CITADEL_E_INVALID_SIGNATURE_LENGTH 0x0202 /* invalid signature length */
+#define CITADEL_E_INVALID_CIPHERTEXT_LENGTH 0x0203 /* invalid ciphertext length */
+#define CITADEL_E_INVALID_PUBLIC_KEY_LENGTH 0x0204 /* invalid public key length */
+#define CITADEL_E_INVALID_SECRET_KEY_LENGTH 0x0205 /* invalid secret key length */
+#define CITADEL_E_INVALID_SHARED_SECRET_LENGTH 0x0206 /* invalid shared secret length */
+#define CITADEL_E_INVALID_PLAINTEXT_LENGTH 0x0207 /* invalid plaintext length */
+#define CITADEL_E_INVALID_NONCE_LENGTH 0x0208 /* invalid nonce length */
+#define CITADEL_E_INVALID_TAG_LENGTH 0x0209 /* invalid authentication tag length */
+#define CITADEL_E_BUFFER_TOO_SMALL 0x020a /* output buffer too small */
+#define CITADEL_E_UNSUPPORTED_ALGORITHM 0x020b /* algorithm not supported */
+#define CITADEL_E_UNSUPPORTED_HYBRID_MODE 0x020c /* hybrid mode not supported */
+#define CITADEL_E_INVALID_PARAMETER_SET 0x020d /* invalid parameter set */
+#define CITADEL_E_INVALID_ALGORITHM_IDENTIFIER 0x020e /* invalid algorithm identifier */
+#define CITADEL_E_CONTEXT_TOO_LONG 0x020f /* context string exceeds maximum length */
+#define CITADEL_E_ASSOCIATED_DATA_TOO_LONG 0x0210 /* associated data exceeds maximum length */
+#define CITADEL_E_FEATURE_NOT_ENABLED 0x0211 /* feature not enabled at compile time */
+#define CITADEL_E_INVALID_STATE 0x0212 /* invalid state for operation */
+#define CITADEL_E_INVALID_ENCODING 0x0213 /* malformed encoded input */
+#define CITADEL_E_PARSE_BUDGET_EXCEEDED 0x0214 /* input exceeds parse budget */
+#define CITADEL_E_NONCE_EXHAUSTED 0x0215 /* nonce sequence exhausted */
+#define CITADEL_E_DEADLINE_EXCEEDED 0x0216 /* operation deadline exceeded */
+#define CITADEL_E_CANCELLED 0x0217 /* operation cancelled */
+
+#endif /* CITADEL_ERRORS_H */
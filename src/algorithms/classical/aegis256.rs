@@ -0,0 +1,538 @@
+//! AEGIS-256 (draft-irtf-cfrg-aegis-aead), with a 256-bit tag.
+//!
+//! An AES-round-based AEAD: one AES round per 16 bytes per state word,
+//! no key schedule, and six independent rounds per update, which pipeline
+//! well on CPUs with AES instructions.
+//!
+//! # Backends
+//!
+//! - AES-NI (`x86_64`), selected at runtime when the CPU supports it
+//! - Portable, built on the table-free round in [`aes256`](super::aes256);
+//!   correct everywhere but far slower
+//!
+//! # Nonces
+//!
+//! Nonces are 256 bits, so random nonces are safe for any practical number
+//! of messages. Reusing a nonce with the same key leaks the XOR of
+//! plaintexts and allows forgeries, as with GCM.
+
+use super::aes256;
+use crate::consts;
+use crate::errors::{CryptoError, MisuseError, Result};
+use crate::internal::traits::AeadCipher;
+use crate::memory::constant_time_eq;
+use crate::nonce::Nonce;
+use crate::r#unsafe::memory::zeroize_volatile;
+
+/// Key size in bytes.
+pub(crate) const KEY_SIZE: usize = consts::Aegis256::KEY_SIZE;
+/// Nonce size in bytes.
+pub(crate) const NONCE_SIZE: usize = consts::Aegis256::NONCE_SIZE;
+/// Tag size in bytes.
+pub(crate) const TAG_SIZE: usize = consts::Aegis256::TAG_SIZE;
+/// Longest plaintext and associated data accepted, `2^61 - 1` bytes.
+pub(crate) const MAX_INPUT_SIZE: u64 = (1 << 61) - 1;
+
+const BLOCK_SIZE: usize = 16;
+const C0: [u8; BLOCK_SIZE] = [
+    0x00, 0x01, 0x01, 0x02, 0x03, 0x05, 0x08, 0x0d, 0x15, 0x22, 0x37, 0x59, 0x90, 0xe9, 0x79, 0x62,
+];
+const C1: [u8; BLOCK_SIZE] = [
+    0xdb, 0x3d, 0x18, 0x55, 0x6d, 0xc2, 0x2f, 0xf1, 0x20, 0x11, 0x31, 0x42, 0x73, 0xb5, 0x28, 0xdd,
+];
+
+/// A 128-bit state word on one backend.
+trait Block: Copy {
+    fn load(bytes: &[u8; BLOCK_SIZE]) -> Self;
+    fn store(self) -> [u8; BLOCK_SIZE];
+    fn zero() -> Self;
+    fn xor(self, other: Self) -> Self;
+    fn and(self, other: Self) -> Self;
+    /// One AES round of `self` with `round_key`.
+    fn aes_round(self, round_key: Self) -> Self;
+}
+
+#[derive(Clone, Copy)]
+struct Portable(u128);
+
+impl Block for Portable {
+    #[inline(always)]
+    fn load(bytes: &[u8; BLOCK_SIZE]) -> Self {
+        Self(u128::from_le_bytes(*bytes))
+    }
+
+    #[inline(always)]
+    fn store(self) -> [u8; BLOCK_SIZE] {
+        self.0.to_le_bytes()
+    }
+
+    #[inline(always)]
+    fn zero() -> Self {
+        Self(0)
+    }
+
+    #[inline(always)]
+    fn xor(self, other: Self) -> Self {
+        Self(self.0 ^ other.0)
+    }
+
+    #[inline(always)]
+    fn and(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    #[inline(always)]
+    fn aes_round(self, round_key: Self) -> Self {
+        Self(aes256::round(self.0, round_key.0))
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod aesni {
+    use core::arch::x86_64::{
+        __m128i, _mm_aesenc_si128, _mm_and_si128, _mm_loadu_si128, _mm_setzero_si128,
+        _mm_storeu_si128, _mm_xor_si128,
+    };
+
+    use super::{BLOCK_SIZE, Block};
+
+    /// Only constructed inside functions compiled with the `aes` target
+    /// feature, after runtime detection.
+    #[derive(Clone, Copy)]
+    pub(super) struct AesNi(__m128i);
+
+    impl Block for AesNi {
+        #[inline(always)]
+        fn load(bytes: &[u8; BLOCK_SIZE]) -> Self {
+            // SAFETY: `bytes` is 16 readable bytes; unaligned load
+            Self(unsafe { _mm_loadu_si128(bytes.as_ptr().cast()) })
+        }
+
+        #[inline(always)]
+        fn store(self) -> [u8; BLOCK_SIZE] {
+            let mut bytes = [0u8; BLOCK_SIZE];
+            // SAFETY: `bytes` is 16 writable bytes; unaligned store
+            unsafe { _mm_storeu_si128(bytes.as_mut_ptr().cast(), self.0) };
+            bytes
+        }
+
+        #[inline(always)]
+        fn zero() -> Self {
+            // SAFETY: SSE2 is part of the x86_64 baseline
+            Self(unsafe { _mm_setzero_si128() })
+        }
+
+        #[inline(always)]
+        fn xor(self, other: Self) -> Self {
+            // SAFETY: SSE2 is part of the x86_64 baseline
+            Self(unsafe { _mm_xor_si128(self.0, other.0) })
+        }
+
+        #[inline(always)]
+        fn and(self, other: Self) -> Self {
+            // SAFETY: SSE2 is part of the x86_64 baseline
+            Self(unsafe { _mm_and_si128(self.0, other.0) })
+        }
+
+        #[inline(always)]
+        fn aes_round(self, round_key: Self) -> Self {
+            // SAFETY: `AesNi` values only exist on the AES-NI code path,
+            // which is entered after runtime feature detection
+            Self(unsafe { _mm_aesenc_si128(self.0, round_key.0) })
+        }
+    }
+
+    /// # Safety
+    ///
+    /// The CPU must support AES-NI.
+    #[target_feature(enable = "aes")]
+    pub(super) unsafe fn seal(
+        key: &[u8; super::KEY_SIZE],
+        nonce: &[u8; super::NONCE_SIZE],
+        plaintext: &[u8],
+        associated_data: &[u8],
+        output: &mut [u8],
+    ) {
+        super::seal::<AesNi>(key, nonce, plaintext, associated_data, output)
+    }
+
+    /// # Safety
+    ///
+    /// The CPU must support AES-NI.
+    #[target_feature(enable = "aes")]
+    pub(super) unsafe fn open(
+        key: &[u8; super::KEY_SIZE],
+        nonce: &[u8; super::NONCE_SIZE],
+        ciphertext: &[u8],
+        associated_data: &[u8],
+        output: &mut [u8],
+    ) -> [u8; super::TAG_SIZE] {
+        super::open::<AesNi>(key, nonce, ciphertext, associated_data, output)
+    }
+
+    pub(super) fn is_available() -> bool {
+        std::arch::is_x86_feature_detected!("aes")
+    }
+}
+
+/// The six-word AEGIS-256 state, zeroized on drop.
+struct State<B: Block>([B; 6]);
+
+impl<B: Block> State<B> {
+    #[inline(always)]
+    fn new(key: &[u8; KEY_SIZE], nonce: &[u8; NONCE_SIZE]) -> Self {
+        let k0 = B::load(key[..16].try_into().expect("16 bytes"));
+        let k1 = B::load(key[16..].try_into().expect("16 bytes"));
+        let n0 = B::load(nonce[..16].try_into().expect("16 bytes"));
+        let n1 = B::load(nonce[16..].try_into().expect("16 bytes"));
+        let (c0, c1) = (B::load(&C0), B::load(&C1));
+
+        let mut state = Self([k0.xor(n0), k1.xor(n1), c1, c0, k0.xor(c0), k1.xor(c1)]);
+        for _ in 0..4 {
+            state.update(k0);
+            state.update(k1);
+            state.update(k0.xor(n0));
+            state.update(k1.xor(n1));
+        }
+        state
+    }
+
+    #[inline(always)]
+    fn update(&mut self, message: B) {
+        let s = self.0;
+        self.0 = [
+            s[5].aes_round(s[0].xor(message)),
+            s[0].aes_round(s[1]),
+            s[1].aes_round(s[2]),
+            s[2].aes_round(s[3]),
+            s[3].aes_round(s[4]),
+            s[4].aes_round(s[5]),
+        ];
+    }
+
+    #[inline(always)]
+    fn keystream(&self) -> B {
+        let s = &self.0;
+        s[1].xor(s[4]).xor(s[5]).xor(s[2].and(s[3]))
+    }
+
+    #[inline(always)]
+    fn absorb(&mut self, data: &[u8]) {
+        let mut blocks = data.chunks_exact(BLOCK_SIZE);
+        for block in &mut blocks {
+            self.update(B::load(block.try_into().expect("16 bytes")));
+        }
+        let rest = blocks.remainder();
+        if !rest.is_empty() {
+            let mut padded = [0u8; BLOCK_SIZE];
+            padded[..rest.len()].copy_from_slice(rest);
+            self.update(B::load(&padded));
+        }
+    }
+
+    /// XOR `input` with the keystream into `output`, absorbing the
+    /// plaintext side (`input` when encrypting, `output` when decrypting).
+    #[inline(always)]
+    fn crypt(&mut self, input: &[u8], output: &mut [u8], decrypting: bool) {
+        for (input, output) in input.chunks(BLOCK_SIZE).zip(output.chunks_mut(BLOCK_SIZE)) {
+            let mut block = [0u8; BLOCK_SIZE];
+            block[..input.len()].copy_from_slice(input);
+            let mut out = B::load(&block).xor(self.keystream()).store();
+            output.copy_from_slice(&out[..input.len()]);
+            if decrypting {
+                // Absorb the zero-padded plaintext, not the keystream tail
+                out[input.len()..].fill(0);
+                self.update(B::load(&out));
+            } else {
+                self.update(B::load(&block));
+            }
+            zeroize_block(&mut block);
+            zeroize_block(&mut out);
+        }
+    }
+
+    #[inline(always)]
+    fn finalize(mut self, associated_data_len: usize, message_len: usize) -> [u8; TAG_SIZE] {
+        let mut lengths = [0u8; BLOCK_SIZE];
+        lengths[..8].copy_from_slice(&(associated_data_len as u64 * 8).to_le_bytes());
+        lengths[8..].copy_from_slice(&(message_len as u64 * 8).to_le_bytes());
+        let t = self.0[3].xor(B::load(&lengths));
+        for _ in 0..7 {
+            self.update(t);
+        }
+        let s = &self.0;
+        let mut tag = [0u8; TAG_SIZE];
+        tag[..16].copy_from_slice(&s[0].xor(s[1]).xor(s[2]).store());
+        tag[16..].copy_from_slice(&s[3].xor(s[4]).xor(s[5]).store());
+        tag
+    }
+}
+
+impl<B: Block> Drop for State<B> {
+    fn drop(&mut self) {
+        for word in self.0.iter_mut() {
+            // SAFETY: `word` is a valid, exclusively borrowed state word
+            unsafe { core::ptr::write_volatile(word, B::zero()) };
+        }
+    }
+}
+
+fn zeroize_block(block: &mut [u8; BLOCK_SIZE]) {
+    // SAFETY: `block` is exclusively borrowed
+    unsafe { zeroize_volatile(block) };
+}
+
+/// Encrypt into `output` (ciphertext then tag); lengths already checked.
+#[inline(always)]
+fn seal<B: Block>(
+    key: &[u8; KEY_SIZE],
+    nonce: &[u8; NONCE_SIZE],
+    plaintext: &[u8],
+    associated_data: &[u8],
+    output: &mut [u8],
+) {
+    let mut state = State::<B>::new(key, nonce);
+    state.absorb(associated_data);
+    let (ciphertext, tag) = output.split_at_mut(plaintext.len());
+    state.crypt(plaintext, ciphertext, false);
+    tag.copy_from_slice(&state.finalize(associated_data.len(), plaintext.len()));
+}
+
+/// Decrypt the ciphertext part into `output` and return the expected tag;
+/// lengths already checked.
+#[inline(always)]
+fn open<B: Block>(
+    key: &[u8; KEY_SIZE],
+    nonce: &[u8; NONCE_SIZE],
+    ciphertext: &[u8],
+    associated_data: &[u8],
+    output: &mut [u8],
+) -> [u8; TAG_SIZE] {
+    let mut state = State::<B>::new(key, nonce);
+    state.absorb(associated_data);
+    state.crypt(ciphertext, output, true);
+    state.finalize(associated_data.len(), ciphertext.len())
+}
+
+/// AEGIS-256 over raw byte arrays.
+pub(crate) struct Aegis256;
+
+impl Aegis256 {
+    fn check_lengths(plaintext_len: usize, associated_data_len: usize) -> Result<()> {
+        if plaintext_len as u64 > MAX_INPUT_SIZE {
+            return Err(MisuseError::InvalidPlaintextLength.into());
+        }
+        if associated_data_len as u64 > MAX_INPUT_SIZE {
+            return Err(MisuseError::AssociatedDataTooLong.into());
+        }
+        Ok(())
+    }
+
+    /// Encrypt `plaintext` into `output` (`plaintext.len() + TAG_SIZE`
+    /// bytes, ciphertext then tag).
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::BufferTooSmall`: If `output` has the wrong length
+    /// - `MisuseError::InvalidPlaintextLength`: If `plaintext` exceeds
+    ///   `2^61 - 1` bytes
+    /// - `MisuseError::AssociatedDataTooLong`: If `associated_data` exceeds
+    ///   `2^61 - 1` bytes
+    pub(crate) fn encrypt_with(
+        key: &[u8; KEY_SIZE],
+        nonce: &[u8; NONCE_SIZE],
+        plaintext: &[u8],
+        associated_data: &[u8],
+        output: &mut [u8],
+    ) -> Result<()> {
+        Self::check_lengths(plaintext.len(), associated_data.len())?;
+        if output.len() != plaintext.len() + TAG_SIZE {
+            return Err(MisuseError::BufferTooSmall.into());
+        }
+        #[cfg(feature = "nonce-reuse-debug")]
+        crate::nonce::reuse::record("AEGIS-256", key, nonce);
+
+        #[cfg(target_arch = "x86_64")]
+        if aesni::is_available() {
+            // SAFETY: AES-NI support was just detected
+            unsafe { aesni::seal(key, nonce, plaintext, associated_data, output) };
+            return Ok(());
+        }
+        seal::<Portable>(key, nonce, plaintext, associated_data, output);
+        Ok(())
+    }
+
+    /// Verify and decrypt `ciphertext` (ciphertext then tag) into `output`
+    /// (`ciphertext.len() - TAG_SIZE` bytes).
+    ///
+    /// On failure `output` is zeroized.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::DecryptionFailed`: If the tag does not verify
+    /// - `MisuseError::InvalidCiphertextLength`: If `ciphertext` is shorter
+    ///   than a tag
+    /// - `MisuseError::BufferTooSmall`: If `output` has the wrong length
+    /// - `MisuseError::AssociatedDataTooLong`: If `associated_data` exceeds
+    ///   `2^61 - 1` bytes
+    pub(crate) fn decrypt_with(
+        key: &[u8; KEY_SIZE],
+        nonce: &[u8; NONCE_SIZE],
+        ciphertext: &[u8],
+        associated_data: &[u8],
+        output: &mut [u8],
+    ) -> Result<()> {
+        let Some(body_len) = ciphertext.len().checked_sub(TAG_SIZE) else {
+            return Err(MisuseError::InvalidCiphertextLength.into());
+        };
+        Self::check_lengths(body_len, associated_data.len())?;
+        if output.len() != body_len {
+            return Err(MisuseError::BufferTooSmall.into());
+        }
+        let (body, tag) = ciphertext.split_at(body_len);
+
+        #[cfg(target_arch = "x86_64")]
+        let expected = if aesni::is_available() {
+            // SAFETY: AES-NI support was just detected
+            unsafe { aesni::open(key, nonce, body, associated_data, output) }
+        } else {
+            open::<Portable>(key, nonce, body, associated_data, output)
+        };
+        #[cfg(not(target_arch = "x86_64"))]
+        let expected = open::<Portable>(key, nonce, body, associated_data, output);
+
+        if !constant_time_eq(&expected, tag) {
+            // SAFETY: `output` is exclusively borrowed
+            unsafe { zeroize_volatile(output) };
+            return Err(CryptoError::DecryptionFailed.into());
+        }
+        Ok(())
+    }
+}
+
+impl AeadCipher<KEY_SIZE, NONCE_SIZE, TAG_SIZE> for Aegis256 {
+    fn encrypt(
+        &self,
+        key: &[u8; KEY_SIZE],
+        nonce: Nonce<NONCE_SIZE>,
+        plaintext: &[u8],
+        associated_data: &[u8],
+        output: &mut [u8],
+    ) -> Result<()> {
+        Self::encrypt_with(key, nonce.as_bytes(), plaintext, associated_data, output)
+    }
+
+    fn decrypt(
+        &self,
+        key: &[u8; KEY_SIZE],
+        nonce: &[u8; NONCE_SIZE],
+        ciphertext: &[u8],
+        associated_data: &[u8],
+        output: &mut [u8],
+    ) -> Result<()> {
+        Self::decrypt_with(key, nonce, ciphertext, associated_data, output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unhex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn seal_portable(key: &[u8; 32], nonce: &[u8; 32], plaintext: &[u8], ad: &[u8]) -> Vec<u8> {
+        let mut output = vec![0u8; plaintext.len() + TAG_SIZE];
+        seal::<Portable>(key, nonce, plaintext, ad, &mut output);
+        output
+    }
+
+    #[test]
+    fn draft_test_vector_1() {
+        let mut key = [0u8; 32];
+        key[..2].copy_from_slice(&[0x10, 0x01]);
+        let mut nonce = [0u8; 32];
+        nonce[..3].copy_from_slice(&[0x10, 0x00, 0x02]);
+        let expected = unhex(
+            "754fc3d8c973246dcc6d741412a4b236\
+             1181a1d18091082bf0266f66297d167d2e68b845f61a3b0527d31fc7b7b89f13",
+        );
+
+        let mut output = [0u8; 16 + TAG_SIZE];
+        Aegis256::encrypt_with(&key, &nonce, &[0u8; 16], b"", &mut output).unwrap();
+        assert_eq!(output[..], expected[..]);
+        assert_eq!(seal_portable(&key, &nonce, &[0u8; 16], b""), expected);
+    }
+
+    #[test]
+    fn partial_blocks() {
+        let key: [u8; 32] = core::array::from_fn(|i| i as u8);
+        let nonce: [u8; 32] = core::array::from_fn(|i| 32 + i as u8);
+        let plaintext: Vec<u8> = (0..37).collect();
+        let ad: Vec<u8> = (0..13).collect();
+        let expected = unhex(
+            "7c5eb006d82eb2043d7ad9e1d31e2eaaa07b0e0d7c56a03c8599393d2e22b15b718e4f9ef2\
+             469a949f0921b141d72389cf98b5d4412a5f6d31f683c4ea9550359d2a791e17",
+        );
+
+        let mut output = vec![0u8; plaintext.len() + TAG_SIZE];
+        Aegis256::encrypt_with(&key, &nonce, &plaintext, &ad, &mut output).unwrap();
+        assert_eq!(output, expected);
+        assert_eq!(seal_portable(&key, &nonce, &plaintext, &ad), expected);
+
+        let mut decrypted = vec![0u8; plaintext.len()];
+        Aegis256::decrypt_with(&key, &nonce, &output, &ad, &mut decrypted).unwrap();
+        assert_eq!(decrypted, plaintext);
+        let mut portable = vec![0u8; plaintext.len()];
+        let tag = open::<Portable>(&key, &nonce, &output[..37], &ad, &mut portable);
+        assert_eq!(portable, plaintext);
+        assert_eq!(tag[..], expected[37..]);
+
+        let mut empty = [0u8; TAG_SIZE];
+        // A second vector under the same key and nonce, on purpose
+        #[cfg(feature = "nonce-reuse-debug")]
+        crate::nonce::reuse::clear();
+        Aegis256::encrypt_with(&key, &nonce, b"", b"", &mut empty).unwrap();
+        assert_eq!(
+            empty[..],
+            unhex("d4bab08275c24dab8be76e523d9e7c006dadbca263d7d70ebe4b2d0915332545")[..]
+        );
+    }
+
+    #[test]
+    fn rejects_tampering_and_clears_output() {
+        let key = [7u8; 32];
+        let nonce = [9u8; 32];
+        let mut ciphertext = vec![0u8; 20 + TAG_SIZE];
+        Aegis256::encrypt_with(&key, &nonce, &[0x55; 20], b"ad", &mut ciphertext).unwrap();
+
+        let mut output = [0xaa; 20];
+        for index in [0, 19, 20, 20 + TAG_SIZE - 1] {
+            let mut tampered = ciphertext.clone();
+            tampered[index] ^= 1;
+            assert_eq!(
+                Aegis256::decrypt_with(&key, &nonce, &tampered, b"ad", &mut output)
+                    .unwrap_err()
+                    .crypto(),
+                Some(CryptoError::DecryptionFailed)
+            );
+            assert_eq!(output, [0u8; 20]);
+        }
+        assert_eq!(
+            Aegis256::decrypt_with(&key, &nonce, &ciphertext, b"ae", &mut output)
+                .unwrap_err()
+                .crypto(),
+            Some(CryptoError::DecryptionFailed)
+        );
+        assert_eq!(
+            Aegis256::decrypt_with(&key, &nonce, &ciphertext[..TAG_SIZE - 1], b"", &mut [])
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::InvalidCiphertextLength)
+        );
+    }
+}
@@ -0,0 +1,270 @@
+//! AES-256 block cipher (FIPS 197).
+//!
+//! Used as the block function of counter-based modes, which only need the
+//! forward cipher, and of AES key wrap, which also needs the inverse.
+//!
+//! # Side Channels
+//!
+//! There are no lookup tables. The S-box is computed as inversion in
+//! GF(2^8) (`x^254`) followed by the affine map, on all 16 state bytes at
+//! once packed into a `u128`, with branch-free multiplication. This is
+//! slower than table-based AES but does not leak the key or data through
+//! cache timing.
+
+use crate::r#unsafe::memory::zeroize_words;
+
+/// Key size in bytes.
+pub(crate) const KEY_SIZE: usize = 32;
+/// Block size in bytes.
+pub(crate) const BLOCK_SIZE: usize = 16;
+
+const ROUNDS: usize = 14;
+
+/// Byte `i` of a block lives in bits `8i..8i+8`.
+type State = u128;
+
+const LOW_BITS: u128 = 0x0101_0101_0101_0101_0101_0101_0101_0101;
+
+/// Multiply every byte by `x` in GF(2^8).
+#[inline]
+fn xtime(a: State) -> State {
+    let carries = (a >> 7) & LOW_BITS;
+    ((a << 1) & !LOW_BITS) ^ (carries * 0x1b)
+}
+
+/// Byte-wise product in GF(2^8).
+#[inline]
+fn gf_mul(mut a: State, b: State) -> State {
+    let mut product = 0;
+    for bit in 0..8 {
+        // 0xff in every byte whose multiplier has this bit set
+        product ^= a & (((b >> bit) & LOW_BITS) * 0xff);
+        a = xtime(a);
+    }
+    product
+}
+
+/// Byte-wise rotate left by `n` bits.
+#[inline]
+fn rotl_bytes(a: State, n: u32) -> State {
+    let high = LOW_BITS * ((0xff << (8 - n)) & 0xff);
+    ((a << n) & !(LOW_BITS * ((1 << n) - 1))) | ((a & high) >> (8 - n))
+}
+
+/// The AES S-box applied to every byte.
+fn sub_bytes(x: State) -> State {
+    // x^254 = x^-1 (and 0 -> 0)
+    let x2 = gf_mul(x, x);
+    let x3 = gf_mul(x2, x);
+    let x6 = gf_mul(x3, x3);
+    let x12 = gf_mul(x6, x6);
+    let x15 = gf_mul(x12, x3);
+    let x30 = gf_mul(x15, x15);
+    let x60 = gf_mul(x30, x30);
+    let x120 = gf_mul(x60, x60);
+    let x240 = gf_mul(x120, x120);
+    let x252 = gf_mul(x240, x12);
+    let inverse = gf_mul(x252, x2);
+
+    inverse
+        ^ rotl_bytes(inverse, 1)
+        ^ rotl_bytes(inverse, 2)
+        ^ rotl_bytes(inverse, 3)
+        ^ rotl_bytes(inverse, 4)
+        ^ (LOW_BITS * 0x63)
+}
+
+/// The inverse S-box applied to every byte.
+fn inv_sub_bytes(x: State) -> State {
+    let affine_inverse = rotl_bytes(x, 1) ^ rotl_bytes(x, 3) ^ rotl_bytes(x, 6) ^ (LOW_BITS * 0x05);
+    // Inversion is its own inverse
+    let x = affine_inverse;
+    let x2 = gf_mul(x, x);
+    let x3 = gf_mul(x2, x);
+    let x6 = gf_mul(x3, x3);
+    let x12 = gf_mul(x6, x6);
+    let x15 = gf_mul(x12, x3);
+    let x30 = gf_mul(x15, x15);
+    let x60 = gf_mul(x30, x30);
+    let x120 = gf_mul(x60, x60);
+    let x240 = gf_mul(x120, x120);
+    let x252 = gf_mul(x240, x12);
+    gf_mul(x252, x2)
+}
+
+fn shift_rows(state: State) -> State {
+    let bytes = state.to_le_bytes();
+    let mut shifted = [0u8; BLOCK_SIZE];
+    for column in 0..4 {
+        for row in 0..4 {
+            shifted[4 * column + row] = bytes[4 * ((column + row) % 4) + row];
+        }
+    }
+    State::from_le_bytes(shifted)
+}
+
+fn inv_shift_rows(state: State) -> State {
+    let bytes = state.to_le_bytes();
+    let mut shifted = [0u8; BLOCK_SIZE];
+    for column in 0..4 {
+        for row in 0..4 {
+            shifted[4 * ((column + row) % 4) + row] = bytes[4 * column + row];
+        }
+    }
+    State::from_le_bytes(shifted)
+}
+
+/// Rotate every column (32-bit word) so row `r + 1` moves to row `r`.
+#[inline]
+fn rotate_columns(a: State) -> State {
+    const WORD_LOW: u128 = 0x00ff_ffff_00ff_ffff_00ff_ffff_00ff_ffff;
+    ((a >> 8) & WORD_LOW) | ((a << 24) & !WORD_LOW)
+}
+
+fn mix_columns(a: State) -> State {
+    // b_r = 2 (a_r + a_{r+1}) + a_{r+1} + a_{r+2} + a_{r+3}
+    let a1 = rotate_columns(a);
+    let a2 = rotate_columns(a1);
+    let a3 = rotate_columns(a2);
+    xtime(a ^ a1) ^ a1 ^ a2 ^ a3
+}
+
+fn inv_mix_columns(a: State) -> State {
+    // b_r = 14 a_r + 11 a_{r+1} + 13 a_{r+2} + 9 a_{r+3}
+    let a1 = rotate_columns(a);
+    let a2 = rotate_columns(a1);
+    let a3 = rotate_columns(a2);
+    gf_mul(a, LOW_BITS * 0x0e)
+        ^ gf_mul(a1, LOW_BITS * 0x0b)
+        ^ gf_mul(a2, LOW_BITS * 0x0d)
+        ^ gf_mul(a3, LOW_BITS * 0x09)
+}
+
+/// One full AES round (`SubBytes`, `ShiftRows`, `MixColumns`,
+/// `AddRoundKey`), as x86 `AESENC` computes it.
+///
+/// Also the building block of AES-based permutations such as AEGIS.
+#[inline]
+pub(crate) fn round(state: State, round_key: State) -> State {
+    mix_columns(shift_rows(sub_bytes(state))) ^ round_key
+}
+
+/// An expanded AES-256 key, zeroized on drop.
+pub(crate) struct Aes256 {
+    round_keys: [State; ROUNDS + 1],
+}
+
+impl Aes256 {
+    /// Expand `key`.
+    pub(crate) fn new(key: &[u8; KEY_SIZE]) -> Self {
+        let mut words = [0u32; 4 * (ROUNDS + 1)];
+        for (word, chunk) in words.iter_mut().zip(key.chunks_exact(4)) {
+            *word = u32::from_le_bytes(chunk.try_into().expect("4-byte chunk"));
+        }
+        let mut rcon = 1u8;
+        for i in 8..words.len() {
+            let mut temp = words[i - 1];
+            if i % 8 == 0 {
+                temp = sub_word(temp.rotate_right(8)) ^ rcon as u32;
+                rcon = xtime(rcon as State) as u8;
+            } else if i % 8 == 4 {
+                temp = sub_word(temp);
+            }
+            words[i] = words[i - 8] ^ temp;
+        }
+
+        let mut round_keys = [0; ROUNDS + 1];
+        for (round_key, chunk) in round_keys.iter_mut().zip(words.chunks_exact(4)) {
+            *round_key = chunk
+                .iter()
+                .rev()
+                .fold(0, |acc, &word| (acc << 32) | word as State);
+        }
+        // SAFETY: `words` is a local array about to go out of scope
+        unsafe { zeroize_words(&mut words) };
+        Self { round_keys }
+    }
+
+    /// Encrypt one block in place.
+    pub(crate) fn encrypt_block(&self, block: &mut [u8; BLOCK_SIZE]) {
+        let mut state = State::from_le_bytes(*block) ^ self.round_keys[0];
+        for &round_key in &self.round_keys[1..ROUNDS] {
+            state = round(state, round_key);
+        }
+        state = shift_rows(sub_bytes(state)) ^ self.round_keys[ROUNDS];
+        *block = state.to_le_bytes();
+    }
+
+    /// Decrypt one block in place.
+    pub(crate) fn decrypt_block(&self, block: &mut [u8; BLOCK_SIZE]) {
+        let mut state = State::from_le_bytes(*block) ^ self.round_keys[ROUNDS];
+        for &round_key in self.round_keys[1..ROUNDS].iter().rev() {
+            state = inv_mix_columns(inv_sub_bytes(inv_shift_rows(state)) ^ round_key);
+        }
+        state = inv_sub_bytes(inv_shift_rows(state)) ^ self.round_keys[0];
+        *block = state.to_le_bytes();
+    }
+}
+
+fn sub_word(word: u32) -> u32 {
+    sub_bytes(word as State) as u32
+}
+
+impl Drop for Aes256 {
+    fn drop(&mut self) {
+        // SAFETY: `round_keys` is owned and exclusively borrowed
+        unsafe { zeroize_words(&mut self.round_keys) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unhex<const N: usize>(hex: &str) -> [u8; N] {
+        let bytes: Vec<u8> = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect();
+        bytes.try_into().unwrap()
+    }
+
+    #[test]
+    fn sbox_matches_fips197() {
+        let input = State::from_le_bytes([
+            0x00, 0x01, 0x10, 0x53, 0x7f, 0x80, 0xff, 0xc9, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66,
+            0x77, 0x88,
+        ]);
+        assert_eq!(
+            sub_bytes(input).to_le_bytes(),
+            [
+                0x63, 0x7c, 0xca, 0xed, 0xd2, 0xcd, 0x16, 0xdd, 0x82, 0x93, 0xc3, 0x1b, 0xfc, 0x33,
+                0xf5, 0xc4
+            ]
+        );
+    }
+
+    #[test]
+    fn fips197_appendix_c3() {
+        let cipher = Aes256::new(&unhex(
+            "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+        ));
+        let mut block = unhex("00112233445566778899aabbccddeeff");
+        cipher.encrypt_block(&mut block);
+        assert_eq!(block, unhex::<16>("8ea2b7ca516745bfeafc49904b496089"));
+        cipher.decrypt_block(&mut block);
+        assert_eq!(block, unhex::<16>("00112233445566778899aabbccddeeff"));
+    }
+
+    #[test]
+    fn fips197_key_expansion_tail() {
+        // Last round key of the FIPS 197 Appendix A.3 expansion
+        let cipher = Aes256::new(&unhex(
+            "603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4",
+        ));
+        assert_eq!(
+            cipher.round_keys[ROUNDS].to_le_bytes(),
+            unhex::<16>("fe4890d1e6188d0b046df344706c631e")
+        );
+    }
+}
@@ -0,0 +1,412 @@
+//! AEAD_AES_256_GCM_SIV (RFC 8452).
+//!
+//! Nonce-misuse-resistant AEAD: the tag is a PRF of the key, nonce,
+//! associated data and plaintext, and doubles as the CTR initial counter.
+//! Repeating a nonce only reveals whether two (associated data, plaintext)
+//! pairs were identical; it does not leak plaintext or enable forgeries the
+//! way it does for GCM.
+//!
+//! # Side Channels
+//!
+//! - AES uses the table-free implementation in [`aes256`](super::aes256)
+//! - POLYVAL multiplies with masks instead of branches or tables
+//! - Tags are compared with `constant_time_eq`; on failure the output
+//!   buffer is zeroized
+
+use super::aes256::{Aes256, BLOCK_SIZE};
+use crate::consts;
+use crate::errors::{CryptoError, MisuseError, Result};
+use crate::internal::traits::AeadCipher;
+use crate::memory::{SensitiveBytes, constant_time_eq};
+use crate::nonce::Nonce;
+use crate::r#unsafe::memory::{zeroize_volatile, zeroize_words};
+
+/// Key size in bytes.
+pub(crate) const KEY_SIZE: usize = consts::Aes256GcmSiv::KEY_SIZE;
+/// Nonce size in bytes.
+pub(crate) const NONCE_SIZE: usize = consts::Aes256GcmSiv::NONCE_SIZE;
+/// Tag size in bytes.
+pub(crate) const TAG_SIZE: usize = consts::Aes256GcmSiv::TAG_SIZE;
+/// Longest plaintext and associated data accepted, 2^36 bytes.
+pub(crate) const MAX_INPUT_SIZE: u64 = 1 << 36;
+
+/// POLYVAL field element: bit `i` is the coefficient of `x^i`.
+type Element = u128;
+
+/// `a * b * x^-128` in GF(2^128) modulo `x^128 + x^127 + x^126 + x^121 + 1`.
+fn dot(a: Element, b: Element) -> Element {
+    // Carry-less 256-bit product
+    let (mut high, mut low) = (0u128, 0u128);
+    for i in 0..128 {
+        let mask = 0u128.wrapping_sub((b >> i) & 1);
+        low ^= (a << i) & mask;
+        if i > 0 {
+            high ^= (a >> (128 - i)) & mask;
+        }
+    }
+    // Divide by x^128: clear the lowest bit by adding the modulus, shift
+    const MODULUS_LOW: u128 = (1 << 127) | (1 << 126) | (1 << 121) | 1;
+    for _ in 0..128 {
+        let mask = 0u128.wrapping_sub(low & 1);
+        low ^= MODULUS_LOW & mask;
+        high ^= 1 & mask;
+        low = (low >> 1) | (high << 127);
+        high >>= 1;
+    }
+    low
+}
+
+/// Incremental POLYVAL.
+struct Polyval {
+    key: Element,
+    accumulator: Element,
+}
+
+impl Polyval {
+    fn new(key: &[u8; BLOCK_SIZE]) -> Self {
+        Self {
+            key: Element::from_le_bytes(*key),
+            accumulator: 0,
+        }
+    }
+
+    /// Absorb `data`, zero-padded to a whole number of blocks.
+    fn update_padded(&mut self, data: &[u8]) {
+        for chunk in data.chunks(BLOCK_SIZE) {
+            let mut block = [0u8; BLOCK_SIZE];
+            block[..chunk.len()].copy_from_slice(chunk);
+            self.accumulator = dot(self.accumulator ^ Element::from_le_bytes(block), self.key);
+        }
+    }
+
+    fn finalize(self) -> [u8; BLOCK_SIZE] {
+        self.accumulator.to_le_bytes()
+    }
+}
+
+impl Drop for Polyval {
+    fn drop(&mut self) {
+        // SAFETY: both fields are owned and exclusively borrowed
+        unsafe { zeroize_words(core::slice::from_mut(&mut self.key)) };
+        unsafe { zeroize_words(core::slice::from_mut(&mut self.accumulator)) };
+    }
+}
+
+/// AES-256-GCM-SIV over raw byte arrays.
+pub(crate) struct Aes256GcmSiv;
+
+impl Aes256GcmSiv {
+    /// Derive the per-nonce authentication and encryption keys.
+    fn derive_keys(
+        key: &[u8; KEY_SIZE],
+        nonce: &[u8; NONCE_SIZE],
+    ) -> (SensitiveBytes<BLOCK_SIZE>, Aes256) {
+        let cipher = Aes256::new(key);
+        let mut derived = SensitiveBytes::<{ 6 * 8 }>::zeroed();
+        for (i, chunk) in derived.as_bytes_mut().chunks_exact_mut(8).enumerate() {
+            let mut block = [0u8; BLOCK_SIZE];
+            block[..4].copy_from_slice(&(i as u32).to_le_bytes());
+            block[4..].copy_from_slice(nonce);
+            cipher.encrypt_block(&mut block);
+            chunk.copy_from_slice(&block[..8]);
+            zeroize_volatile_block(&mut block);
+        }
+
+        let mut authentication_key = SensitiveBytes::<BLOCK_SIZE>::zeroed();
+        authentication_key
+            .as_bytes_mut()
+            .copy_from_slice(&derived.as_bytes()[..16]);
+        let mut encryption_key = SensitiveBytes::<KEY_SIZE>::zeroed();
+        encryption_key
+            .as_bytes_mut()
+            .copy_from_slice(&derived.as_bytes()[16..]);
+        (authentication_key, Aes256::new(encryption_key.as_bytes()))
+    }
+
+    fn tag(
+        authentication_key: &SensitiveBytes<BLOCK_SIZE>,
+        encryption: &Aes256,
+        nonce: &[u8; NONCE_SIZE],
+        plaintext: &[u8],
+        associated_data: &[u8],
+    ) -> [u8; TAG_SIZE] {
+        let mut polyval = Polyval::new(authentication_key.as_bytes());
+        polyval.update_padded(associated_data);
+        polyval.update_padded(plaintext);
+        let mut lengths = [0u8; BLOCK_SIZE];
+        lengths[..8].copy_from_slice(&(associated_data.len() as u64 * 8).to_le_bytes());
+        lengths[8..].copy_from_slice(&(plaintext.len() as u64 * 8).to_le_bytes());
+        polyval.update_padded(&lengths);
+
+        let mut tag = polyval.finalize();
+        for (byte, nonce_byte) in tag.iter_mut().zip(nonce.iter()) {
+            *byte ^= nonce_byte;
+        }
+        tag[15] &= 0x7f;
+        encryption.encrypt_block(&mut tag);
+        tag
+    }
+
+    /// XOR `data` with the keystream starting at counter block `tag`.
+    fn apply_keystream(encryption: &Aes256, tag: &[u8; TAG_SIZE], data: &mut [u8]) {
+        let mut counter_block = *tag;
+        counter_block[15] |= 0x80;
+        let mut counter = u32::from_le_bytes(counter_block[..4].try_into().expect("4 bytes"));
+        for chunk in data.chunks_mut(BLOCK_SIZE) {
+            counter_block[..4].copy_from_slice(&counter.to_le_bytes());
+            let mut keystream = counter_block;
+            encryption.encrypt_block(&mut keystream);
+            for (byte, key_byte) in chunk.iter_mut().zip(keystream.iter()) {
+                *byte ^= key_byte;
+            }
+            zeroize_volatile_block(&mut keystream);
+            counter = counter.wrapping_add(1);
+        }
+    }
+
+    fn check_lengths(plaintext_len: usize, associated_data_len: usize) -> Result<()> {
+        if plaintext_len as u64 > MAX_INPUT_SIZE {
+            return Err(MisuseError::InvalidPlaintextLength.into());
+        }
+        if associated_data_len as u64 > MAX_INPUT_SIZE {
+            return Err(MisuseError::AssociatedDataTooLong.into());
+        }
+        Ok(())
+    }
+
+    /// Encrypt `plaintext` into `output` (`plaintext.len() + TAG_SIZE`
+    /// bytes, ciphertext then tag).
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::BufferTooSmall`: If `output` has the wrong length
+    /// - `MisuseError::InvalidPlaintextLength`: If `plaintext` exceeds 2^36
+    ///   bytes
+    /// - `MisuseError::AssociatedDataTooLong`: If `associated_data` exceeds
+    ///   2^36 bytes
+    pub(crate) fn encrypt_with(
+        key: &[u8; KEY_SIZE],
+        nonce: &[u8; NONCE_SIZE],
+        plaintext: &[u8],
+        associated_data: &[u8],
+        output: &mut [u8],
+    ) -> Result<()> {
+        Self::check_lengths(plaintext.len(), associated_data.len())?;
+        if output.len() != plaintext.len() + TAG_SIZE {
+            return Err(MisuseError::BufferTooSmall.into());
+        }
+        #[cfg(feature = "nonce-reuse-debug")]
+        crate::nonce::reuse::record("AES-256-GCM-SIV", key, nonce);
+
+        let (authentication_key, encryption) = Self::derive_keys(key, nonce);
+        let tag = Self::tag(
+            &authentication_key,
+            &encryption,
+            nonce,
+            plaintext,
+            associated_data,
+        );
+        let (ciphertext, tag_out) = output.split_at_mut(plaintext.len());
+        ciphertext.copy_from_slice(plaintext);
+        Self::apply_keystream(&encryption, &tag, ciphertext);
+        tag_out.copy_from_slice(&tag);
+        Ok(())
+    }
+
+    /// Decrypt and verify `ciphertext` (ciphertext then tag) into `output`
+    /// (`ciphertext.len() - TAG_SIZE` bytes).
+    ///
+    /// On failure `output` is zeroized.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::DecryptionFailed`: If the tag does not verify
+    /// - `MisuseError::InvalidCiphertextLength`: If `ciphertext` is shorter
+    ///   than a tag or longer than 2^36 bytes plus a tag
+    /// - `MisuseError::AssociatedDataTooLong`: If `associated_data` exceeds
+    ///   2^36 bytes
+    /// - `MisuseError::BufferTooSmall`: If `output` has the wrong length
+    pub(crate) fn decrypt_with(
+        key: &[u8; KEY_SIZE],
+        nonce: &[u8; NONCE_SIZE],
+        ciphertext: &[u8],
+        associated_data: &[u8],
+        output: &mut [u8],
+    ) -> Result<()> {
+        let Some(body_len) = ciphertext.len().checked_sub(TAG_SIZE) else {
+            return Err(MisuseError::InvalidCiphertextLength.into());
+        };
+        Self::check_lengths(body_len, associated_data.len()).map_err(|error| {
+            if error.misuse() == Some(MisuseError::InvalidPlaintextLength) {
+                MisuseError::InvalidCiphertextLength.into()
+            } else {
+                error
+            }
+        })?;
+        if output.len() != body_len {
+            return Err(MisuseError::BufferTooSmall.into());
+        }
+
+        let (body, tag) = ciphertext.split_at(body_len);
+        let tag: &[u8; TAG_SIZE] = tag.try_into().expect("split at TAG_SIZE");
+        let (authentication_key, encryption) = Self::derive_keys(key, nonce);
+        output.copy_from_slice(body);
+        Self::apply_keystream(&encryption, tag, output);
+        let expected = Self::tag(
+            &authentication_key,
+            &encryption,
+            nonce,
+            output,
+            associated_data,
+        );
+        if !constant_time_eq(&expected, tag) {
+            // SAFETY: `output` is exclusively borrowed
+            unsafe { zeroize_volatile(output) };
+            return Err(CryptoError::DecryptionFailed.into());
+        }
+        Ok(())
+    }
+}
+
+fn zeroize_volatile_block(block: &mut [u8; BLOCK_SIZE]) {
+    // SAFETY: `block` is exclusively borrowed
+    unsafe { zeroize_volatile(block) };
+}
+
+impl AeadCipher<KEY_SIZE, NONCE_SIZE, TAG_SIZE> for Aes256GcmSiv {
+    fn encrypt(
+        &self,
+        key: &[u8; KEY_SIZE],
+        nonce: Nonce<NONCE_SIZE>,
+        plaintext: &[u8],
+        associated_data: &[u8],
+        output: &mut [u8],
+    ) -> Result<()> {
+        Self::encrypt_with(key, nonce.as_bytes(), plaintext, associated_data, output)
+    }
+
+    fn decrypt(
+        &self,
+        key: &[u8; KEY_SIZE],
+        nonce: &[u8; NONCE_SIZE],
+        ciphertext: &[u8],
+        associated_data: &[u8],
+        output: &mut [u8],
+    ) -> Result<()> {
+        Self::decrypt_with(key, nonce, ciphertext, associated_data, output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unhex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn polyval_rfc8452_appendix_a() {
+        let mut polyval = Polyval::new(
+            &unhex("25629347589242761d31f826ba4b757b")
+                .try_into()
+                .unwrap(),
+        );
+        polyval.update_padded(&unhex(
+            "4f4f95668c83dfb6401762bb2d01a262d1a24ddd2721d006bbe45f20d3c9f362",
+        ));
+        assert_eq!(
+            polyval.finalize().to_vec(),
+            unhex("f7a3b47b846119fae5b7866cf5e5b77e")
+        );
+    }
+
+    /// RFC 8452 Appendix C.2 (AEAD_AES_256_GCM_SIV).
+    /// Vectors and the misuse test below repeat a key and nonce on purpose.
+    fn forget_nonces() {
+        #[cfg(feature = "nonce-reuse-debug")]
+        crate::nonce::reuse::clear();
+    }
+
+    #[test]
+    fn rfc8452_vectors() {
+        let key: [u8; KEY_SIZE] =
+            unhex("0100000000000000000000000000000000000000000000000000000000000000")
+                .try_into()
+                .unwrap();
+        let nonce: [u8; NONCE_SIZE] = unhex("030000000000000000000000").try_into().unwrap();
+        for (plaintext, aad, expected) in [
+            ("", "", "07f5f4169bbf55a8400cd47ea6fd400f"),
+            (
+                "0100000000000000",
+                "",
+                "c2ef328e5c71c83b843122130f7364b761e0b97427e3df28",
+            ),
+            (
+                "02000000000000000000000000000000",
+                "01",
+                "c91545823cc24f17dbb0e9e807d5ec17b292d28ff61189e8e49f3875ef91aff7",
+            ),
+            (
+                "0300000000000000000000000000000004000000",
+                "010000000000000000000000",
+                "5460c194f55ca4152080fb5f6f3c736a22fe0507fef687499f7cb2eada059bf0efad9079",
+            ),
+        ] {
+            let (plaintext, aad, expected) = (unhex(plaintext), unhex(aad), unhex(expected));
+            let mut ciphertext = vec![0u8; plaintext.len() + TAG_SIZE];
+            forget_nonces();
+            Aes256GcmSiv::encrypt_with(&key, &nonce, &plaintext, &aad, &mut ciphertext).unwrap();
+            assert_eq!(ciphertext, expected);
+
+            let mut decrypted = vec![0u8; plaintext.len()];
+            Aes256GcmSiv::decrypt_with(&key, &nonce, &ciphertext, &aad, &mut decrypted).unwrap();
+            assert_eq!(decrypted, plaintext);
+        }
+    }
+
+    #[test]
+    fn rejects_tampering_and_clears_output() {
+        let key = [7u8; KEY_SIZE];
+        let nonce = [9u8; NONCE_SIZE];
+        let mut ciphertext = [0u8; 5 + TAG_SIZE];
+        Aes256GcmSiv::encrypt_with(&key, &nonce, b"hello", b"aad", &mut ciphertext).unwrap();
+
+        let mut output = [0xffu8; 5];
+        ciphertext[0] ^= 1;
+        assert_eq!(
+            Aes256GcmSiv::decrypt_with(&key, &nonce, &ciphertext, b"aad", &mut output)
+                .unwrap_err()
+                .crypto(),
+            Some(CryptoError::DecryptionFailed)
+        );
+        assert_eq!(output, [0u8; 5]);
+
+        ciphertext[0] ^= 1;
+        assert!(
+            Aes256GcmSiv::decrypt_with(&key, &nonce, &ciphertext, b"aaD", &mut output).is_err()
+        );
+        assert!(Aes256GcmSiv::decrypt_with(&key, &nonce, &ciphertext[..15], b"", &mut []).is_err());
+        assert!(Aes256GcmSiv::encrypt_with(&key, &nonce, b"hello", b"", &mut [0u8; 20]).is_err());
+    }
+
+    #[test]
+    fn nonce_reuse_is_deterministic() {
+        let key = [1u8; KEY_SIZE];
+        let nonce = [2u8; NONCE_SIZE];
+        let mut first = [0u8; 3 + TAG_SIZE];
+        let mut second = [0u8; 3 + TAG_SIZE];
+        let mut other = [0u8; 3 + TAG_SIZE];
+        Aes256GcmSiv::encrypt_with(&key, &nonce, b"abc", b"", &mut first).unwrap();
+        forget_nonces();
+        Aes256GcmSiv::encrypt_with(&key, &nonce, b"abc", b"", &mut second).unwrap();
+        forget_nonces();
+        Aes256GcmSiv::encrypt_with(&key, &nonce, b"abd", b"", &mut other).unwrap();
+        assert_eq!(first, second);
+        // A different plaintext gets a different tag, hence keystream
+        assert_ne!(first[..3], other[..3]);
+    }
+}
@@ -0,0 +1,183 @@
+//! AES-256 key wrap (RFC 3394, NIST SP 800-38F KW).
+//!
+//! Deterministic authenticated encryption of key material under a
+//! key-encryption key: the output is the input plus one 8-byte integrity
+//! block. Only for wrapping uniformly random keys; it has no nonce, so
+//! wrapping the same key twice gives the same output.
+//!
+//! # Side Channels
+//!
+//! - AES uses the table-free implementation in [`aes256`](super::aes256)
+//! - The integrity check is compared with `constant_time_eq`; on failure
+//!   the output buffer is zeroized
+
+use super::aes256::{Aes256, BLOCK_SIZE, KEY_SIZE};
+use crate::errors::{CryptoError, MisuseError, Result};
+use crate::memory::constant_time_eq;
+use crate::r#unsafe::memory::zeroize_volatile;
+
+/// Size of the integrity block added by wrapping.
+pub(crate) const OVERHEAD: usize = 8;
+
+/// Shortest key accepted for wrapping, two 64-bit blocks.
+pub(crate) const MIN_KEY_SIZE: usize = 16;
+
+/// RFC 3394 default initial value.
+const DEFAULT_IV: [u8; 8] = [0xa6; 8];
+
+const HALF_BLOCK: usize = BLOCK_SIZE / 2;
+
+/// Wrap `key` under `kek` into `out`, which must be `key.len() + OVERHEAD`
+/// bytes.
+///
+/// # Errors
+///
+/// - `MisuseError::InvalidKeyLength`: If `key` is shorter than
+///   [`MIN_KEY_SIZE`] or not a multiple of 8 bytes
+/// - `MisuseError::BufferTooSmall`: If `out` has the wrong length
+pub(crate) fn wrap(kek: &[u8; KEY_SIZE], key: &[u8], out: &mut [u8]) -> Result<()> {
+    if key.len() < MIN_KEY_SIZE || !key.len().is_multiple_of(HALF_BLOCK) {
+        return Err(MisuseError::InvalidKeyLength.into());
+    }
+    if out.len() != key.len() + OVERHEAD {
+        return Err(MisuseError::BufferTooSmall.into());
+    }
+
+    let cipher = Aes256::new(kek);
+    let blocks = (key.len() / HALF_BLOCK) as u64;
+    let (a, r) = out.split_at_mut(OVERHEAD);
+    a.copy_from_slice(&DEFAULT_IV);
+    r.copy_from_slice(key);
+
+    let mut block = [0u8; BLOCK_SIZE];
+    for j in 0..6 {
+        for (i, r_i) in r.chunks_exact_mut(HALF_BLOCK).enumerate() {
+            block[..HALF_BLOCK].copy_from_slice(a);
+            block[HALF_BLOCK..].copy_from_slice(r_i);
+            cipher.encrypt_block(&mut block);
+            let t = blocks * j + i as u64 + 1;
+            for (a_byte, (b_byte, t_byte)) in a.iter_mut().zip(block.iter().zip(t.to_be_bytes())) {
+                *a_byte = b_byte ^ t_byte;
+            }
+            r_i.copy_from_slice(&block[HALF_BLOCK..]);
+        }
+    }
+    // SAFETY: `block` is an exclusively borrowed local
+    unsafe { zeroize_volatile(&mut block) };
+    Ok(())
+}
+
+/// Unwrap `wrapped` under `kek` into `out`, which must be
+/// `wrapped.len() - OVERHEAD` bytes.
+///
+/// # Errors
+///
+/// - `CryptoError::DecryptionFailed`: If the integrity check fails
+/// - `MisuseError::InvalidCiphertextLength`: If `wrapped` is shorter than
+///   a wrapped [`MIN_KEY_SIZE`] key or not a multiple of 8 bytes
+/// - `MisuseError::BufferTooSmall`: If `out` has the wrong length
+pub(crate) fn unwrap(kek: &[u8; KEY_SIZE], wrapped: &[u8], out: &mut [u8]) -> Result<()> {
+    if wrapped.len() < MIN_KEY_SIZE + OVERHEAD || !wrapped.len().is_multiple_of(HALF_BLOCK) {
+        return Err(MisuseError::InvalidCiphertextLength.into());
+    }
+    if out.len() + OVERHEAD != wrapped.len() {
+        return Err(MisuseError::BufferTooSmall.into());
+    }
+
+    let cipher = Aes256::new(kek);
+    let blocks = (out.len() / HALF_BLOCK) as u64;
+    let mut a = [0u8; OVERHEAD];
+    a.copy_from_slice(&wrapped[..OVERHEAD]);
+    out.copy_from_slice(&wrapped[OVERHEAD..]);
+
+    let mut block = [0u8; BLOCK_SIZE];
+    for j in (0..6).rev() {
+        for (i, r_i) in out.chunks_exact_mut(HALF_BLOCK).enumerate().rev() {
+            let t = blocks * j + i as u64 + 1;
+            for (b_byte, (a_byte, t_byte)) in block.iter_mut().zip(a.iter().zip(t.to_be_bytes())) {
+                *b_byte = a_byte ^ t_byte;
+            }
+            block[HALF_BLOCK..].copy_from_slice(r_i);
+            cipher.decrypt_block(&mut block);
+            a.copy_from_slice(&block[..HALF_BLOCK]);
+            r_i.copy_from_slice(&block[HALF_BLOCK..]);
+        }
+    }
+    // SAFETY: `block` is an exclusively borrowed local
+    unsafe { zeroize_volatile(&mut block) };
+
+    if !constant_time_eq(&a, &DEFAULT_IV) {
+        // SAFETY: `out` is exclusively borrowed
+        unsafe { zeroize_volatile(out) };
+        return Err(CryptoError::DecryptionFailed.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unhex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn kek() -> [u8; KEY_SIZE] {
+        unhex("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f")
+            .try_into()
+            .unwrap()
+    }
+
+    #[test]
+    fn rfc3394_vectors() {
+        // Sections 4.3 and 4.6: 128- and 256-bit key data under a 256-bit KEK
+        for (key, expected) in [
+            (
+                "00112233445566778899aabbccddeeff",
+                "64e8c3f9ce0f5ba263e9777905818a2a93c8191e7d6e8ae7",
+            ),
+            (
+                "00112233445566778899aabbccddeeff000102030405060708090a0b0c0d0e0f",
+                "28c9f404c4b810f4cbccb35cfb87f8263f5786e2d80ed326cbc7f0e71a99f43bfb988b9b7a02dd21",
+            ),
+        ] {
+            let key = unhex(key);
+            let mut wrapped = vec![0u8; key.len() + OVERHEAD];
+            wrap(&kek(), &key, &mut wrapped).unwrap();
+            assert_eq!(wrapped, unhex(expected));
+
+            let mut unwrapped = vec![0u8; key.len()];
+            unwrap(&kek(), &wrapped, &mut unwrapped).unwrap();
+            assert_eq!(unwrapped, key);
+        }
+    }
+
+    #[test]
+    fn rejects_tampering_and_bad_lengths() {
+        let mut wrapped = [0u8; 40];
+        wrap(&kek(), &[7u8; 32], &mut wrapped).unwrap();
+        wrapped[20] ^= 1;
+        let mut out = [0xffu8; 32];
+        assert_eq!(
+            unwrap(&kek(), &wrapped, &mut out).unwrap_err().crypto(),
+            Some(CryptoError::DecryptionFailed)
+        );
+        assert_eq!(out, [0u8; 32]);
+
+        assert_eq!(
+            wrap(&kek(), &[0u8; 12], &mut [0u8; 20])
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::InvalidKeyLength)
+        );
+        assert_eq!(
+            unwrap(&kek(), &[0u8; 16], &mut [0u8; 8])
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::InvalidCiphertextLength)
+        );
+    }
+}
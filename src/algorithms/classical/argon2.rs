@@ -0,0 +1,412 @@
+//! Argon2id (RFC 9106), version 0x13.
+//!
+//! Memory-hard password hashing. Lanes are filled one after another in
+//! each slice, which gives the same output as a parallel implementation.
+//!
+//! # Side Channels
+//!
+//! The first half of the first pass uses data-independent addressing;
+//! the rest indexes memory with data derived from the password, as
+//! Argon2id specifies.
+
+use super::blake2b::{self, Blake2b};
+use crate::errors::{MisuseError, Result};
+use crate::r#unsafe::memory::zeroize_words;
+
+/// Largest working memory accepted, in bytes.
+pub(crate) const MAX_MEMORY: u64 = 1 << 30;
+
+/// Most passes accepted.
+///
+/// Bounds the work a stored hash can demand.
+pub(crate) const MAX_PASSES: u32 = 64;
+
+const VERSION: u32 = 0x13;
+/// Argon2 type `y` of Argon2id.
+const ARGON2ID: u32 = 2;
+const BLOCK_WORDS: usize = 128;
+const BLOCK_BYTES: usize = 8 * BLOCK_WORDS;
+const SLICES: usize = 4;
+
+type Block = [u64; BLOCK_WORDS];
+
+/// Validated Argon2id cost parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Params {
+    memory_kib: u32,
+    passes: u32,
+    lanes: u32,
+}
+
+impl Params {
+    /// 64 MiB, 2 passes, 1 lane.
+    pub(crate) const INTERACTIVE: Self = Self {
+        memory_kib: 1 << 16,
+        passes: 2,
+        lanes: 1,
+    };
+    /// 256 MiB, 3 passes, 1 lane.
+    pub(crate) const MODERATE: Self = Self {
+        memory_kib: 1 << 18,
+        passes: 3,
+        lanes: 1,
+    };
+    /// 1 GiB, 4 passes, 1 lane.
+    pub(crate) const SENSITIVE: Self = Self {
+        memory_kib: 1 << 20,
+        passes: 4,
+        lanes: 1,
+    };
+
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidParameterSet`: If `passes` or `lanes` is
+    ///   zero, `passes` exceeds [`MAX_PASSES`], `lanes` exceeds `2^24 - 1`,
+    ///   memory is below 8 KiB per lane or above [`MAX_MEMORY`]
+    pub(crate) fn new(memory_kib: u32, passes: u32, lanes: u32) -> Result<Self> {
+        let valid = (1..=MAX_PASSES).contains(&passes)
+            && (1..1 << 24).contains(&lanes)
+            && u64::from(memory_kib) >= 8 * u64::from(lanes)
+            && u64::from(memory_kib) * 1024 <= MAX_MEMORY;
+        if !valid {
+            return Err(MisuseError::InvalidParameterSet.into());
+        }
+        Ok(Self {
+            memory_kib,
+            passes,
+            lanes,
+        })
+    }
+
+    pub(crate) fn memory_kib(&self) -> u32 {
+        self.memory_kib
+    }
+
+    pub(crate) fn passes(&self) -> u32 {
+        self.passes
+    }
+
+    pub(crate) fn lanes(&self) -> u32 {
+        self.lanes
+    }
+}
+
+/// `H'`: BLAKE2b extended to any output length.
+fn h_prime(out: &mut [u8], parts: &[&[u8]]) {
+    let length = (out.len() as u32).to_le_bytes();
+    if out.len() <= blake2b::MAX_OUTPUT_LEN {
+        let mut hasher = Blake2b::new(out.len());
+        hasher.update(&length);
+        for part in parts {
+            hasher.update(part);
+        }
+        hasher.finalize_into(out);
+        return;
+    }
+
+    let mut hasher = Blake2b::new(64);
+    hasher.update(&length);
+    for part in parts {
+        hasher.update(part);
+    }
+    let mut v = [0u8; 64];
+    hasher.finalize_into(&mut v);
+    out[..32].copy_from_slice(&v[..32]);
+    let mut written = 32;
+    while out.len() - written > 64 {
+        v = Blake2b::digest(&[&v]);
+        out[written..written + 32].copy_from_slice(&v[..32]);
+        written += 32;
+    }
+    let mut hasher = Blake2b::new(out.len() - written);
+    hasher.update(&v);
+    hasher.finalize_into(&mut out[written..]);
+    // SAFETY: `v` is a local array about to go out of scope
+    unsafe { zeroize_words(&mut v) };
+}
+
+/// BlaMka's multiply-add: `x + y + 2 * lo32(x) * lo32(y)`.
+#[inline(always)]
+fn fbla(x: u64, y: u64) -> u64 {
+    let product = (x & 0xffff_ffff).wrapping_mul(y & 0xffff_ffff);
+    x.wrapping_add(y).wrapping_add(product.wrapping_mul(2))
+}
+
+#[inline(always)]
+fn gb(block: &mut Block, a: usize, b: usize, c: usize, d: usize) {
+    let (mut va, mut vb, mut vc, mut vd) = (block[a], block[b], block[c], block[d]);
+    va = fbla(va, vb);
+    vd = (vd ^ va).rotate_right(32);
+    vc = fbla(vc, vd);
+    vb = (vb ^ vc).rotate_right(24);
+    va = fbla(va, vb);
+    vd = (vd ^ va).rotate_right(16);
+    vc = fbla(vc, vd);
+    vb = (vb ^ vc).rotate_right(63);
+    (block[a], block[b], block[c], block[d]) = (va, vb, vc, vd);
+}
+
+/// The permutation `P` on the sixteen words at `v`.
+#[inline(always)]
+fn permute(block: &mut Block, v: [usize; 16]) {
+    gb(block, v[0], v[4], v[8], v[12]);
+    gb(block, v[1], v[5], v[9], v[13]);
+    gb(block, v[2], v[6], v[10], v[14]);
+    gb(block, v[3], v[7], v[11], v[15]);
+    gb(block, v[0], v[5], v[10], v[15]);
+    gb(block, v[1], v[6], v[11], v[12]);
+    gb(block, v[2], v[7], v[8], v[13]);
+    gb(block, v[3], v[4], v[9], v[14]);
+}
+
+/// The compression function `G(x, y)`.
+fn compress(x: &Block, y: &Block) -> Block {
+    let mut r = [0u64; BLOCK_WORDS];
+    for (r, (x, y)) in r.iter_mut().zip(x.iter().zip(y.iter())) {
+        *r = x ^ y;
+    }
+    let mut q = r;
+    for row in 0..8 {
+        permute(&mut q, core::array::from_fn(|i| 16 * row + i));
+    }
+    for column in 0..8 {
+        permute(
+            &mut q,
+            core::array::from_fn(|i| 2 * column + 16 * (i / 2) + i % 2),
+        );
+    }
+    for (q, r) in q.iter_mut().zip(r.iter()) {
+        *q ^= r;
+    }
+    // SAFETY: `r` is a local array about to go out of scope
+    unsafe { zeroize_words(&mut r) };
+    q
+}
+
+/// Position of the segment being filled.
+struct Segment {
+    pass: usize,
+    slice: usize,
+    lane: usize,
+}
+
+/// Shape of the memory matrix.
+struct Geometry {
+    lanes: usize,
+    lane_len: usize,
+    segment_len: usize,
+}
+
+impl Geometry {
+    /// Index within the reference lane of the block `J1` selects.
+    fn reference_index(&self, at: &Segment, index: usize, j1: u32, same_lane: bool) -> usize {
+        let area = if at.pass == 0 {
+            if at.slice == 0 || same_lane {
+                at.slice * self.segment_len + index - 1
+            } else if index == 0 {
+                at.slice * self.segment_len - 1
+            } else {
+                at.slice * self.segment_len
+            }
+        } else if same_lane {
+            self.lane_len - self.segment_len + index - 1
+        } else if index == 0 {
+            self.lane_len - self.segment_len - 1
+        } else {
+            self.lane_len - self.segment_len
+        };
+        let x = (u64::from(j1) * u64::from(j1)) >> 32;
+        let relative = area - 1 - ((area as u64 * x) >> 32) as usize;
+        let start = if at.pass == 0 || at.slice == SLICES - 1 {
+            0
+        } else {
+            (at.slice + 1) * self.segment_len
+        };
+        (start + relative) % self.lane_len
+    }
+}
+
+fn fill_segment(memory: &mut [Block], geometry: &Geometry, params: &Params, at: &Segment) {
+    let data_independent = at.pass == 0 && at.slice < SLICES / 2;
+    let zero = [0u64; BLOCK_WORDS];
+    let mut input = [0u64; BLOCK_WORDS];
+    let mut addresses = [0u64; BLOCK_WORDS];
+    let next_addresses = |input: &mut Block, addresses: &mut Block| {
+        input[6] += 1;
+        *addresses = compress(&zero, &compress(&zero, input));
+    };
+    if data_independent {
+        input[..6].copy_from_slice(&[
+            at.pass as u64,
+            at.lane as u64,
+            at.slice as u64,
+            memory.len() as u64,
+            u64::from(params.passes),
+            u64::from(ARGON2ID),
+        ]);
+    }
+
+    let first = if at.pass == 0 && at.slice == 0 { 2 } else { 0 };
+    if data_independent && first != 0 {
+        next_addresses(&mut input, &mut addresses);
+    }
+    for index in first..geometry.segment_len {
+        let current = at.lane * geometry.lane_len + at.slice * geometry.segment_len + index;
+        let previous = if current.is_multiple_of(geometry.lane_len) {
+            current + geometry.lane_len - 1
+        } else {
+            current - 1
+        };
+        let pseudo_random = if data_independent {
+            if index.is_multiple_of(BLOCK_WORDS) {
+                next_addresses(&mut input, &mut addresses);
+            }
+            addresses[index % BLOCK_WORDS]
+        } else {
+            memory[previous][0]
+        };
+
+        let reference_lane = if at.pass == 0 && at.slice == 0 {
+            at.lane
+        } else {
+            (pseudo_random >> 32) as usize % geometry.lanes
+        };
+        let reference_index =
+            geometry.reference_index(at, index, pseudo_random as u32, reference_lane == at.lane);
+        let reference = reference_lane * geometry.lane_len + reference_index;
+
+        let mut block = compress(&memory[previous], &memory[reference]);
+        if at.pass == 0 {
+            memory[current] = block;
+        } else {
+            for (word, new) in memory[current].iter_mut().zip(block.iter()) {
+                *word ^= new;
+            }
+        }
+        // SAFETY: `block` is a local array about to go out of scope
+        unsafe { zeroize_words(&mut block) };
+    }
+}
+
+/// Fill `output` with Argon2id of `password` and `salt`, keyed by the
+/// optional `secret` and bound to the optional `associated` data.
+///
+/// The caller validates that `output` is 4 bytes to 1 MiB long.
+pub(crate) fn argon2id(
+    password: &[u8],
+    salt: &[u8],
+    secret: &[u8],
+    associated: &[u8],
+    params: &Params,
+    output: &mut [u8],
+) {
+    let lanes = params.lanes as usize;
+    let blocks = params.memory_kib as usize / (SLICES * lanes) * SLICES * lanes;
+    let geometry = Geometry {
+        lanes,
+        lane_len: blocks / lanes,
+        segment_len: blocks / lanes / SLICES,
+    };
+
+    let le = |value: usize| (value as u32).to_le_bytes();
+    let mut h0 = Blake2b::digest(&[
+        &params.lanes.to_le_bytes(),
+        &(output.len() as u32).to_le_bytes(),
+        &params.memory_kib.to_le_bytes(),
+        &params.passes.to_le_bytes(),
+        &VERSION.to_le_bytes(),
+        &ARGON2ID.to_le_bytes(),
+        &le(password.len()),
+        password,
+        &le(salt.len()),
+        salt,
+        &le(secret.len()),
+        secret,
+        &le(associated.len()),
+        associated,
+    ]);
+
+    let mut memory = vec![[0u64; BLOCK_WORDS]; blocks];
+    let mut bytes = [0u8; BLOCK_BYTES];
+    for lane in 0..lanes {
+        for column in 0..2 {
+            h_prime(&mut bytes, &[&h0, &le(column), &le(lane)]);
+            let block = &mut memory[lane * geometry.lane_len + column];
+            for (word, chunk) in block.iter_mut().zip(bytes.chunks_exact(8)) {
+                *word = u64::from_le_bytes(chunk.try_into().expect("8 bytes"));
+            }
+        }
+    }
+
+    for pass in 0..params.passes as usize {
+        for slice in 0..SLICES {
+            for lane in 0..lanes {
+                let at = Segment { pass, slice, lane };
+                fill_segment(&mut memory, &geometry, params, &at);
+            }
+        }
+    }
+
+    let mut last = memory[geometry.lane_len - 1];
+    for lane in 1..lanes {
+        for (word, other) in last
+            .iter_mut()
+            .zip(memory[(lane + 1) * geometry.lane_len - 1].iter())
+        {
+            *word ^= other;
+        }
+    }
+    for (chunk, word) in bytes.chunks_exact_mut(8).zip(last.iter()) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+    h_prime(output, &[&bytes]);
+
+    // SAFETY: all are owned buffers about to go out of scope
+    unsafe {
+        zeroize_words(memory.as_flattened_mut());
+        zeroize_words(&mut last);
+        zeroize_words(&mut bytes);
+        zeroize_words(&mut h0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn rfc9106_vector() {
+        // Section 5.3: m = 32 KiB, t = 3, p = 4, with secret and data
+        let mut tag = [0u8; 32];
+        argon2id(
+            &[0x01; 32],
+            &[0x02; 16],
+            &[0x03; 8],
+            &[0x04; 12],
+            &Params::new(32, 3, 4).unwrap(),
+            &mut tag,
+        );
+        assert_eq!(
+            hex(&tag),
+            "0d640df58d78766c08c037a34a8b53c9d01ef0452d75b65eb52520e96b01e659"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_parameters() {
+        for (memory_kib, passes, lanes) in [
+            (8, 0, 1),
+            (8, 1, 0),
+            (15, 1, 2),
+            (1 << 20 | 1, 1, 1),
+            (64, MAX_PASSES + 1, 1),
+        ] {
+            assert!(Params::new(memory_kib, passes, lanes).is_err());
+        }
+        assert!(Params::new(8, 1, 1).is_ok());
+    }
+}
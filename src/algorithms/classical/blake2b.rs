@@ -0,0 +1,207 @@
+//! BLAKE2b (RFC 7693), unkeyed.
+//!
+//! Crate-internal; the hash inside Argon2.
+
+use crate::r#unsafe::memory::zeroize_words;
+
+const BLOCK_LEN: usize = 128;
+
+/// Longest digest in bytes.
+pub(crate) const MAX_OUTPUT_LEN: usize = 64;
+
+const IV: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+const SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+#[inline(always)]
+fn g(v: &mut [u64; 16], a: usize, b: usize, c: usize, d: usize, x: u64, y: u64) {
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+    v[d] = (v[d] ^ v[a]).rotate_right(32);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(24);
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(63);
+}
+
+/// Incremental BLAKE2b state.
+#[derive(Clone)]
+pub(crate) struct Blake2b {
+    h: [u64; 8],
+    /// Bytes compressed so far.
+    counter: u128,
+    buffer: [u8; BLOCK_LEN],
+    buffered: usize,
+    output_len: usize,
+}
+
+impl Blake2b {
+    /// A hasher with an `output_len`-byte digest, `1..=64`.
+    pub(crate) fn new(output_len: usize) -> Self {
+        debug_assert!((1..=MAX_OUTPUT_LEN).contains(&output_len));
+        let mut h = IV;
+        h[0] ^= 0x0101_0000 ^ output_len as u64;
+        Self {
+            h,
+            counter: 0,
+            buffer: [0u8; BLOCK_LEN],
+            buffered: 0,
+            output_len,
+        }
+    }
+
+    fn compress(&mut self, last: bool) {
+        let mut m = [0u64; 16];
+        for (word, bytes) in m.iter_mut().zip(self.buffer.chunks_exact(8)) {
+            *word = u64::from_le_bytes(bytes.try_into().expect("8 bytes"));
+        }
+        let mut v = [0u64; 16];
+        v[..8].copy_from_slice(&self.h);
+        v[8..].copy_from_slice(&IV);
+        v[12] ^= self.counter as u64;
+        v[13] ^= (self.counter >> 64) as u64;
+        if last {
+            v[14] = !v[14];
+        }
+        for s in SIGMA.iter().cycle().take(12) {
+            g(&mut v, 0, 4, 8, 12, m[s[0]], m[s[1]]);
+            g(&mut v, 1, 5, 9, 13, m[s[2]], m[s[3]]);
+            g(&mut v, 2, 6, 10, 14, m[s[4]], m[s[5]]);
+            g(&mut v, 3, 7, 11, 15, m[s[6]], m[s[7]]);
+            g(&mut v, 0, 5, 10, 15, m[s[8]], m[s[9]]);
+            g(&mut v, 1, 6, 11, 12, m[s[10]], m[s[11]]);
+            g(&mut v, 2, 7, 8, 13, m[s[12]], m[s[13]]);
+            g(&mut v, 3, 4, 9, 14, m[s[14]], m[s[15]]);
+        }
+        for (i, h) in self.h.iter_mut().enumerate() {
+            *h ^= v[i] ^ v[i + 8];
+        }
+        // SAFETY: both are local arrays about to go out of scope
+        unsafe {
+            zeroize_words(&mut m);
+            zeroize_words(&mut v);
+        }
+    }
+
+    pub(crate) fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            // The final block is compressed by `finalize_into`, so only
+            // compress a full buffer once more input arrives
+            if self.buffered == BLOCK_LEN {
+                self.counter += BLOCK_LEN as u128;
+                self.compress(false);
+                self.buffered = 0;
+            }
+            let take = (BLOCK_LEN - self.buffered).min(data.len());
+            self.buffer[self.buffered..self.buffered + take].copy_from_slice(&data[..take]);
+            self.buffered += take;
+            data = &data[take..];
+        }
+    }
+
+    /// Fill `out`, which must be exactly the configured output length.
+    pub(crate) fn finalize_into(mut self, out: &mut [u8]) {
+        debug_assert_eq!(out.len(), self.output_len);
+        self.counter += self.buffered as u128;
+        self.buffer[self.buffered..].fill(0);
+        self.compress(true);
+        for (bytes, word) in out.chunks_mut(8).zip(self.h.iter()) {
+            bytes.copy_from_slice(&word.to_le_bytes()[..bytes.len()]);
+        }
+    }
+
+    /// BLAKE2b-512 of the concatenation of `parts`.
+    pub(crate) fn digest(parts: &[&[u8]]) -> [u8; 64] {
+        let mut hasher = Self::new(64);
+        for part in parts {
+            hasher.update(part);
+        }
+        let mut out = [0u8; 64];
+        hasher.finalize_into(&mut out);
+        out
+    }
+}
+
+impl Drop for Blake2b {
+    fn drop(&mut self) {
+        // SAFETY: both are owned, exclusively borrowed arrays
+        unsafe {
+            zeroize_words(&mut self.h);
+            zeroize_words(&mut self.buffer);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn rfc7693_vector() {
+        assert_eq!(
+            hex(&Blake2b::digest(&[b"abc"])),
+            "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d1\
+             7d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923"
+        );
+    }
+
+    #[test]
+    fn block_boundaries_and_short_outputs() {
+        // hashlib.blake2b(bytes(i % 251 for i in range(len)), digest_size=...)
+        let data: Vec<u8> = (0..300).map(|i| (i % 251) as u8).collect();
+        let cases: &[(usize, usize, &str)] = &[
+            (
+                0,
+                64,
+                "786a02f742015903c6c6fd852552d272912f4740e15847618a86e217f71f5419\
+                     d25e1031afee585313896444934eb04b903a685b1448b755d56f701afe9be2ce",
+            ),
+            (
+                128,
+                32,
+                "c3582f71ebb2be66fa5dd750f80baae97554f3b015663c8be377cfcb2488c1d1",
+            ),
+            (129, 20, "a7bf25f1599102ab631e3052e8303a2c097d1a7e"),
+            (
+                300,
+                64,
+                "3a482b7748b0bdc43c3d00c080890c10e57a9aa5618f78b86067eb7eaae4942a\
+                       cd96d827accbc16958364ae5b0df6105bbd3b15445092eba1137b5f69c1070f1",
+            ),
+        ];
+        for &(len, output_len, expected) in cases {
+            let mut hasher = Blake2b::new(output_len);
+            let (a, b) = data[..len].split_at(len / 3);
+            hasher.update(a);
+            hasher.update(b);
+            let mut out = vec![0u8; output_len];
+            hasher.finalize_into(&mut out);
+            assert_eq!(hex(&out), expected, "length {len}");
+        }
+    }
+}
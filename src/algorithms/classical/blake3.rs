@@ -0,0 +1,525 @@
+//! BLAKE3 hash (unkeyed mode).
+//!
+//! Crate-internal; the public `hash::Blake3` wraps it. Input is split into
+//! 1 KiB chunks that form a binary tree, so whole subtrees can be hashed
+//! independently. With the `parallel` feature, [`Hasher::update_parallel`]
+//! hashes them on the rayon thread pool.
+//!
+//! The chaining-value stack merges lazily, as in the reference
+//! implementation, so the root node is never compressed before finalize.
+
+use crate::r#unsafe::memory::zeroize_words;
+
+const IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const MSG_PERMUTATION: [usize; 16] = [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
+
+const CHUNK_START: u32 = 1 << 0;
+const CHUNK_END: u32 = 1 << 1;
+const PARENT: u32 = 1 << 2;
+const ROOT: u32 = 1 << 3;
+
+const BLOCK_LEN: usize = 64;
+const CHUNK_LEN: usize = 1024;
+/// Enough for 2^54 chunks, the most a 64-bit byte count can reach.
+const MAX_DEPTH: usize = 54;
+
+#[inline(always)]
+fn g(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize, x: u32, y: u32) {
+    state[a] = state[a].wrapping_add(state[b]).wrapping_add(x);
+    state[d] = (state[d] ^ state[a]).rotate_right(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_right(12);
+    state[a] = state[a].wrapping_add(state[b]).wrapping_add(y);
+    state[d] = (state[d] ^ state[a]).rotate_right(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_right(7);
+}
+
+fn compress(
+    cv: &[u32; 8],
+    block: &[u32; 16],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+) -> [u32; 16] {
+    let mut state = [
+        cv[0],
+        cv[1],
+        cv[2],
+        cv[3],
+        cv[4],
+        cv[5],
+        cv[6],
+        cv[7],
+        IV[0],
+        IV[1],
+        IV[2],
+        IV[3],
+        counter as u32,
+        (counter >> 32) as u32,
+        block_len,
+        flags,
+    ];
+    let mut m = *block;
+    for round in 0..7 {
+        g(&mut state, 0, 4, 8, 12, m[0], m[1]);
+        g(&mut state, 1, 5, 9, 13, m[2], m[3]);
+        g(&mut state, 2, 6, 10, 14, m[4], m[5]);
+        g(&mut state, 3, 7, 11, 15, m[6], m[7]);
+        g(&mut state, 0, 5, 10, 15, m[8], m[9]);
+        g(&mut state, 1, 6, 11, 12, m[10], m[11]);
+        g(&mut state, 2, 7, 8, 13, m[12], m[13]);
+        g(&mut state, 3, 4, 9, 14, m[14], m[15]);
+        if round < 6 {
+            m = core::array::from_fn(|i| m[MSG_PERMUTATION[i]]);
+        }
+    }
+    for i in 0..8 {
+        state[i] ^= state[i + 8];
+        state[i + 8] ^= cv[i];
+    }
+    // SAFETY: `m` is a local array about to go out of scope
+    unsafe { zeroize_words(&mut m) };
+    state
+}
+
+fn first_8(words: [u32; 16]) -> [u32; 8] {
+    core::array::from_fn(|i| words[i])
+}
+
+fn block_words(bytes: &[u8; BLOCK_LEN]) -> [u32; 16] {
+    core::array::from_fn(|i| u32::from_le_bytes(bytes[4 * i..4 * i + 4].try_into().unwrap()))
+}
+
+/// A node that has not been compressed yet: either the chaining value of
+/// an inner node or, finalized with `ROOT`, the output.
+struct Output {
+    input_cv: [u32; 8],
+    block: [u32; 16],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+}
+
+impl Output {
+    fn chaining_value(&self) -> [u32; 8] {
+        first_8(compress(
+            &self.input_cv,
+            &self.block,
+            self.counter,
+            self.block_len,
+            self.flags,
+        ))
+    }
+
+    fn root_bytes(&self, out: &mut [u8]) {
+        for (counter, block) in out.chunks_mut(2 * 32).enumerate() {
+            let words = compress(
+                &self.input_cv,
+                &self.block,
+                counter as u64,
+                self.block_len,
+                self.flags | ROOT,
+            );
+            for (bytes, word) in block.chunks_mut(4).zip(words.iter()) {
+                bytes.copy_from_slice(&word.to_le_bytes()[..bytes.len()]);
+            }
+        }
+    }
+}
+
+impl Drop for Output {
+    fn drop(&mut self) {
+        // SAFETY: owned, exclusively borrowed arrays
+        unsafe {
+            zeroize_words(&mut self.input_cv);
+            zeroize_words(&mut self.block);
+        }
+    }
+}
+
+fn parent_output(left: &[u32; 8], right: &[u32; 8]) -> Output {
+    let mut block = [0u32; 16];
+    block[..8].copy_from_slice(left);
+    block[8..].copy_from_slice(right);
+    Output {
+        input_cv: IV,
+        block,
+        counter: 0,
+        block_len: BLOCK_LEN as u32,
+        flags: PARENT,
+    }
+}
+
+#[derive(Clone)]
+struct ChunkState {
+    cv: [u32; 8],
+    chunk_counter: u64,
+    block: [u8; BLOCK_LEN],
+    block_len: usize,
+    blocks_compressed: usize,
+}
+
+impl ChunkState {
+    fn new(chunk_counter: u64) -> Self {
+        Self {
+            cv: IV,
+            chunk_counter,
+            block: [0u8; BLOCK_LEN],
+            block_len: 0,
+            blocks_compressed: 0,
+        }
+    }
+
+    fn len(&self) -> usize {
+        BLOCK_LEN * self.blocks_compressed + self.block_len
+    }
+
+    fn start_flag(&self) -> u32 {
+        if self.blocks_compressed == 0 {
+            CHUNK_START
+        } else {
+            0
+        }
+    }
+
+    fn update(&mut self, mut input: &[u8]) {
+        while !input.is_empty() {
+            if self.block_len == BLOCK_LEN {
+                let words = block_words(&self.block);
+                self.cv = first_8(compress(
+                    &self.cv,
+                    &words,
+                    self.chunk_counter,
+                    BLOCK_LEN as u32,
+                    self.start_flag(),
+                ));
+                self.blocks_compressed += 1;
+                self.block = [0u8; BLOCK_LEN];
+                self.block_len = 0;
+            }
+            let take = (BLOCK_LEN - self.block_len).min(input.len());
+            self.block[self.block_len..self.block_len + take].copy_from_slice(&input[..take]);
+            self.block_len += take;
+            input = &input[take..];
+        }
+    }
+
+    fn output(&self) -> Output {
+        Output {
+            input_cv: self.cv,
+            block: block_words(&self.block),
+            counter: self.chunk_counter,
+            block_len: self.block_len as u32,
+            flags: self.start_flag() | CHUNK_END,
+        }
+    }
+}
+
+impl Drop for ChunkState {
+    fn drop(&mut self) {
+        // SAFETY: owned, exclusively borrowed arrays
+        unsafe {
+            zeroize_words(&mut self.cv);
+            zeroize_words(&mut self.block);
+        }
+    }
+}
+
+/// Run `left` and `right`, on separate threads when `parallel` is set and
+/// the `parallel` feature is enabled.
+fn join<A: Send, B: Send>(
+    parallel: bool,
+    left: impl FnOnce() -> A + Send,
+    right: impl FnOnce() -> B + Send,
+) -> (A, B) {
+    #[cfg(feature = "parallel")]
+    if parallel {
+        return rayon::join(left, right);
+    }
+    let _ = parallel;
+    (left(), right())
+}
+
+/// Below this size a subtree is not worth a task on the thread pool.
+const MIN_PARALLEL_LEN: usize = 16 * CHUNK_LEN;
+
+/// Chaining value of the complete subtree over `input`, a power-of-two
+/// number of chunks starting at `chunk_counter`.
+fn subtree_cv(input: &[u8], chunk_counter: u64, parallel: bool) -> [u32; 8] {
+    if input.len() <= CHUNK_LEN {
+        let mut chunk = ChunkState::new(chunk_counter);
+        chunk.update(input);
+        return chunk.output().chaining_value();
+    }
+    let (left, right) = input.split_at(input.len() / 2);
+    let right_counter = chunk_counter + (left.len() / CHUNK_LEN) as u64;
+    let (left_cv, right_cv) = join(
+        parallel && input.len() > MIN_PARALLEL_LEN,
+        || subtree_cv(left, chunk_counter, parallel),
+        || subtree_cv(right, right_counter, parallel),
+    );
+    parent_output(&left_cv, &right_cv).chaining_value()
+}
+
+/// Incremental BLAKE3 state.
+#[derive(Clone)]
+pub(crate) struct Hasher {
+    chunk: ChunkState,
+    cv_stack: [[u32; 8]; MAX_DEPTH],
+    cv_stack_len: usize,
+}
+
+impl Hasher {
+    pub(crate) fn new() -> Self {
+        Self {
+            chunk: ChunkState::new(0),
+            cv_stack: [[0u32; 8]; MAX_DEPTH],
+            cv_stack_len: 0,
+        }
+    }
+
+    fn pop_cv(&mut self) -> [u32; 8] {
+        self.cv_stack_len -= 1;
+        self.cv_stack[self.cv_stack_len]
+    }
+
+    /// Merge completed subtrees until the stack holds one entry per set
+    /// bit of `total_chunks`.
+    fn merge_cv_stack(&mut self, total_chunks: u64) {
+        let target = total_chunks.count_ones() as usize;
+        while self.cv_stack_len > target {
+            let right = self.pop_cv();
+            let left = self.pop_cv();
+            self.cv_stack[self.cv_stack_len] = parent_output(&left, &right).chaining_value();
+            self.cv_stack_len += 1;
+        }
+    }
+
+    fn push_cv(&mut self, cv: &[u32; 8], chunk_counter: u64) {
+        self.merge_cv_stack(chunk_counter);
+        self.cv_stack[self.cv_stack_len] = *cv;
+        self.cv_stack_len += 1;
+    }
+
+    pub(crate) fn update(&mut self, input: &[u8]) {
+        self.update_with(input, false);
+    }
+
+    /// Like [`update`](Self::update), hashing large aligned subtrees on
+    /// the rayon thread pool.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn update_parallel(&mut self, input: &[u8]) {
+        self.update_with(input, true);
+    }
+
+    fn update_with(&mut self, mut input: &[u8], parallel: bool) {
+        // Finish a partial chunk first
+        if self.chunk.len() > 0 {
+            let take = (CHUNK_LEN - self.chunk.len()).min(input.len());
+            self.chunk.update(&input[..take]);
+            input = &input[take..];
+            if input.is_empty() {
+                return;
+            }
+            let cv = self.chunk.output().chaining_value();
+            let counter = self.chunk.chunk_counter;
+            self.push_cv(&cv, counter);
+            self.chunk = ChunkState::new(counter + 1);
+        }
+
+        // Whole subtrees, keeping at least one byte back for the final
+        // chunk so the root is never pushed
+        while input.len() > CHUNK_LEN {
+            let counter = self.chunk.chunk_counter;
+            let mut subtree_len = 1usize << (usize::BITS - 1 - input.len().leading_zeros());
+            while ((subtree_len / CHUNK_LEN) as u64 - 1) & counter != 0 {
+                subtree_len /= 2;
+            }
+            let subtree_chunks = (subtree_len / CHUNK_LEN) as u64;
+            if subtree_chunks <= 1 {
+                let cv = subtree_cv(&input[..subtree_len], counter, parallel);
+                self.push_cv(&cv, counter);
+            } else {
+                // Push the two halves unmerged: if this is the last
+                // subtree, its parent may be the root
+                let (left, right) = input[..subtree_len].split_at(subtree_len / 2);
+                let right_counter = counter + subtree_chunks / 2;
+                let (left_cv, right_cv) = join(
+                    parallel && subtree_len > MIN_PARALLEL_LEN,
+                    || subtree_cv(left, counter, parallel),
+                    || subtree_cv(right, right_counter, parallel),
+                );
+                self.push_cv(&left_cv, counter);
+                self.push_cv(&right_cv, right_counter);
+            }
+            self.chunk = ChunkState::new(counter + subtree_chunks);
+            input = &input[subtree_len..];
+        }
+
+        if !input.is_empty() {
+            self.chunk.update(input);
+            self.merge_cv_stack(self.chunk.chunk_counter);
+        }
+    }
+
+    fn root_output(&self) -> Output {
+        if self.cv_stack_len == 0 {
+            return self.chunk.output();
+        }
+        let mut remaining = self.cv_stack_len;
+        let mut output = if self.chunk.len() > 0 {
+            self.chunk.output()
+        } else {
+            remaining -= 2;
+            parent_output(&self.cv_stack[remaining], &self.cv_stack[remaining + 1])
+        };
+        while remaining > 0 {
+            remaining -= 1;
+            output = parent_output(&self.cv_stack[remaining], &output.chaining_value());
+        }
+        output
+    }
+
+    /// Fill `out` with the extendable output; its first 32 bytes are the
+    /// digest.
+    pub(crate) fn finalize_xof(&self, out: &mut [u8]) {
+        self.root_output().root_bytes(out);
+    }
+
+    pub(crate) fn finalize(&self) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        self.finalize_xof(&mut out);
+        out
+    }
+}
+
+impl Drop for Hasher {
+    fn drop(&mut self) {
+        // SAFETY: `cv_stack` is an owned, exclusively borrowed array
+        unsafe { zeroize_words(self.cv_stack.as_flattened_mut()) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    fn input(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    /// Official test-vector lengths and digests (input byte i is i % 251).
+    const VECTORS: &[(usize, &str)] = &[
+        (
+            0,
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262",
+        ),
+        (
+            1,
+            "2d3adedff11b61f14c886e35afa036736dcd87a74d27b5c1510225d0f592e213",
+        ),
+        (
+            1023,
+            "10108970eeda3eb932baac1428c7a2163b0e924c9a9e25b35bba72b28f70bd11",
+        ),
+        (
+            1024,
+            "42214739f095a406f3fc83deb889744ac00df831c10daa55189b5d121c855af7",
+        ),
+        (
+            1025,
+            "d00278ae47eb27b34faecf67b4fe263f82d5412916c1ffd97c8cb7fb814b8444",
+        ),
+        (
+            2048,
+            "e776b6028c7cd22a4d0ba182a8bf62205d2ef576467e838ed6f2529b85fba24a",
+        ),
+        (
+            2049,
+            "5f4d72f40d7a5f82b15ca2b2e44b1de3c2ef86c426c95c1af0b6879522563030",
+        ),
+        (
+            3072,
+            "b98cb0ff3623be03326b373de6b9095218513e64f1ee2edd2525c7ad1e5cffd2",
+        ),
+        (
+            3073,
+            "7124b49501012f81cc7f11ca069ec9226cecb8a2c850cfe644e327d22d3e1cd3",
+        ),
+        (
+            4096,
+            "015094013f57a5277b59d8475c0501042c0b642e531b0a1c8f58d2163229e969",
+        ),
+        (
+            4097,
+            "9b4052b38f1c5fc8b1f9ff7ac7b27cd242487b3d890d15c96a1c25b8aa0fb995",
+        ),
+        (
+            8193,
+            "bab6c09cb8ce8cf459261398d2e7aef35700bf488116ceb94a36d0f5f1b7bc3b",
+        ),
+        (
+            31744,
+            "62b6960e1a44bcc1eb1a611a8d6235b6b4b78f32e7abc4fb4c6cdcce94895c47",
+        ),
+        (
+            102400,
+            "bc3e3d41a1146b069abffad3c0d44860cf664390afce4d9661f7902e7943e085",
+        ),
+    ];
+
+    #[test]
+    fn official_vectors() {
+        for &(len, expected) in VECTORS {
+            let mut hasher = Hasher::new();
+            hasher.update(&input(len));
+            assert_eq!(hex(&hasher.finalize()), expected, "length {len}");
+        }
+    }
+
+    #[test]
+    fn extended_output() {
+        // Bytes 64..131 of the XOF output for 1025 bytes of input
+        let mut hasher = Hasher::new();
+        hasher.update(&input(1025));
+        let mut out = [0u8; 131];
+        hasher.finalize_xof(&mut out);
+        assert_eq!(&out[..32], hasher.finalize());
+        assert_eq!(
+            hex(&out[64..]),
+            "e332b0ef84b409108cda080e6269ed4b3e2c3f7d722aa4cdc98d16deb554e562\
+             7be8f955c98e1d5f9565a9194cad0c4285f93700062d9595adb992ae68ff1280\
+             0ab67a"
+        );
+    }
+
+    #[test]
+    fn split_updates_match() {
+        let data = input(102400);
+        let expected = VECTORS.last().unwrap().1;
+        for split in [1, 1000, 1024, 1025, 4096, 50000, 65536, 102399] {
+            let mut hasher = Hasher::new();
+            hasher.update(&data[..split]);
+            hasher.update(&data[split..]);
+            assert_eq!(hex(&hasher.finalize()), expected, "split {split}");
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_matches_serial() {
+        let data = input(102400);
+        let mut hasher = Hasher::new();
+        hasher.update(&data[..3000]);
+        hasher.update_parallel(&data[3000..]);
+        assert_eq!(hex(&hasher.finalize()), VECTORS.last().unwrap().1);
+    }
+}
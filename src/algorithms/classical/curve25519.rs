@@ -0,0 +1,260 @@
+//! Arithmetic in GF(2^255 - 19), shared by X25519 and Ed25519.
+//!
+//! Elements are five 51-bit limbs. Every operation is branch-free and
+//! returns limbs below 2^52, which keeps all products within `u128`.
+
+use core::ops::{Add, Mul, Sub};
+
+const MASK_51: u64 = (1 << 51) - 1;
+
+/// 4p, added before subtracting so limbs never underflow.
+const FOUR_P: [u64; 5] = [
+    4 * ((1 << 51) - 19),
+    4 * ((1 << 51) - 1),
+    4 * ((1 << 51) - 1),
+    4 * ((1 << 51) - 1),
+    4 * ((1 << 51) - 1),
+];
+
+/// An element of GF(2^255 - 19), not necessarily fully reduced.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct FieldElement(pub(crate) [u64; 5]);
+
+impl FieldElement {
+    pub(crate) const ZERO: Self = Self([0; 5]);
+    pub(crate) const ONE: Self = Self([1, 0, 0, 0, 0]);
+
+    /// Decode 32 little-endian bytes, ignoring the top bit.
+    pub(crate) fn from_bytes(bytes: &[u8; 32]) -> Self {
+        let load = |offset: usize| {
+            let mut word = [0u8; 8];
+            word.copy_from_slice(&bytes[offset..offset + 8]);
+            u64::from_le_bytes(word)
+        };
+        Self([
+            load(0) & MASK_51,
+            (load(6) >> 3) & MASK_51,
+            (load(12) >> 6) & MASK_51,
+            (load(19) >> 1) & MASK_51,
+            (load(24) >> 12) & MASK_51,
+        ])
+    }
+
+    /// Encode as 32 little-endian bytes, fully reduced.
+    pub(crate) fn to_bytes(self) -> [u8; 32] {
+        let mut limbs = Self::carry(self.0.map(u128::from)).0;
+
+        // limbs < 2^51 except limbs[0] < 2^51 + 2^18 * 19; subtract p
+        // once if the value is at least p.
+        let mut q = (limbs[0] + 19) >> 51;
+        for limb in &limbs[1..] {
+            q = (limb + q) >> 51;
+        }
+        limbs[0] += 19 * q;
+        for i in 0..4 {
+            limbs[i + 1] += limbs[i] >> 51;
+            limbs[i] &= MASK_51;
+        }
+        limbs[4] &= MASK_51;
+
+        let mut out = [0u8; 32];
+        let mut acc = 0u128;
+        let mut bits = 0;
+        let mut index = 0;
+        for limb in limbs {
+            acc |= u128::from(limb) << bits;
+            bits += 51;
+            while bits >= 8 {
+                out[index] = acc as u8;
+                acc >>= 8;
+                bits -= 8;
+                index += 1;
+            }
+        }
+        out[index] = acc as u8;
+        out
+    }
+
+    /// Propagate carries, folding the top carry back in with factor 19.
+    #[inline]
+    fn carry(mut c: [u128; 5]) -> Self {
+        for i in 0..4 {
+            c[i + 1] += c[i] >> 51;
+            c[i] &= u128::from(MASK_51);
+        }
+        c[0] += (c[4] >> 51) * 19;
+        c[4] &= u128::from(MASK_51);
+        c[1] += c[0] >> 51;
+        c[0] &= u128::from(MASK_51);
+        Self(c.map(|limb| limb as u64))
+    }
+
+    pub(crate) fn square(self) -> Self {
+        self * self
+    }
+
+    /// Multiply by a small constant.
+    pub(crate) fn mul_small(self, k: u32) -> Self {
+        Self::carry(self.0.map(|limb| u128::from(limb) * u128::from(k)))
+    }
+
+    /// `-self`.
+    pub(crate) fn neg(self) -> Self {
+        Self::ZERO - self
+    }
+
+    /// Whether the fully reduced value is odd (the RFC 8032 sign bit).
+    pub(crate) fn is_negative(self) -> u8 {
+        self.to_bytes()[0] & 1
+    }
+
+    /// `self^((p - 5) / 8)`, used for square roots.
+    pub(crate) fn pow_p58(self) -> Self {
+        // (p - 5) / 8 = 2^252 - 3: every bit of 0..252 set except bit 1.
+        let mut result = Self::ONE;
+        for bit in (0..252).rev() {
+            result = result.square();
+            if bit != 1 {
+                result = result * self;
+            }
+        }
+        result
+    }
+
+    /// Multiplicative inverse, `self^(p - 2)`; zero maps to zero.
+    pub(crate) fn invert(self) -> Self {
+        // p - 2 = 2^255 - 21: every bit of 0..255 set except bits 2 and 4.
+        let mut result = Self::ONE;
+        for bit in (0..255).rev() {
+            result = result.square();
+            if bit != 2 && bit != 4 {
+                result = result * self;
+            }
+        }
+        result
+    }
+
+    /// `b` if `choice` is 1, else `a`, in constant time.
+    #[inline]
+    pub(crate) fn conditional_select(a: &Self, b: &Self, choice: u8) -> Self {
+        let mask = 0u64.wrapping_sub(u64::from(choice & 1));
+        let mut out = *a;
+        for (x, y) in out.0.iter_mut().zip(b.0.iter()) {
+            *x ^= mask & (*x ^ *y);
+        }
+        out
+    }
+
+    /// Swap `a` and `b` if `choice` is 1, in constant time.
+    #[inline]
+    pub(crate) fn conditional_swap(a: &mut Self, b: &mut Self, choice: u8) {
+        let mask = 0u64.wrapping_sub(u64::from(choice & 1));
+        for (x, y) in a.0.iter_mut().zip(b.0.iter_mut()) {
+            let t = mask & (*x ^ *y);
+            *x ^= t;
+            *y ^= t;
+        }
+    }
+}
+
+impl Add for FieldElement {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        let mut c = [0u128; 5];
+        for (i, limb) in c.iter_mut().enumerate() {
+            *limb = u128::from(self.0[i]) + u128::from(rhs.0[i]);
+        }
+        Self::carry(c)
+    }
+}
+
+impl Sub for FieldElement {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        let mut c = [0u128; 5];
+        for (i, limb) in c.iter_mut().enumerate() {
+            *limb = u128::from(self.0[i]) + u128::from(FOUR_P[i]) - u128::from(rhs.0[i]);
+        }
+        Self::carry(c)
+    }
+}
+
+impl Mul for FieldElement {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let a = self.0.map(u128::from);
+        let b = rhs.0.map(u128::from);
+        let b19 = b.map(|limb| limb * 19);
+
+        Self::carry([
+            a[0] * b[0] + a[1] * b19[4] + a[2] * b19[3] + a[3] * b19[2] + a[4] * b19[1],
+            a[0] * b[1] + a[1] * b[0] + a[2] * b19[4] + a[3] * b19[3] + a[4] * b19[2],
+            a[0] * b[2] + a[1] * b[1] + a[2] * b[0] + a[3] * b19[4] + a[4] * b19[3],
+            a[0] * b[3] + a[1] * b[2] + a[2] * b[1] + a[3] * b[0] + a[4] * b19[4],
+            a[0] * b[4] + a[1] * b[3] + a[2] * b[2] + a[3] * b[1] + a[4] * b[0],
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn element(n: u64) -> FieldElement {
+        let mut bytes = [0u8; 32];
+        bytes[..8].copy_from_slice(&n.to_le_bytes());
+        FieldElement::from_bytes(&bytes)
+    }
+
+    #[test]
+    fn encoding_round_trips_and_reduces() {
+        let mut bytes = [0u8; 32];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = (i as u8).wrapping_mul(37);
+        }
+        bytes[31] &= 0x7f;
+        assert_eq!(FieldElement::from_bytes(&bytes).to_bytes(), bytes);
+
+        // p itself encodes as zero; p + 1 as one.
+        let mut p = [0xffu8; 32];
+        p[0] = 0xed;
+        p[31] = 0x7f;
+        assert_eq!(FieldElement::from_bytes(&p).to_bytes(), [0u8; 32]);
+        p[0] = 0xee;
+        assert_eq!(
+            FieldElement::from_bytes(&p).to_bytes(),
+            FieldElement::ONE.to_bytes()
+        );
+    }
+
+    #[test]
+    fn arithmetic_identities() {
+        let a = element(123_456_789);
+        let b = element(987_654_321);
+        assert_eq!(
+            (a * b).to_bytes(),
+            element(123_456_789 * 987_654_321).to_bytes()
+        );
+        assert_eq!((a - b + b).to_bytes(), a.to_bytes());
+        assert_eq!((FieldElement::ZERO - a + a).to_bytes(), [0u8; 32]);
+        assert_eq!((a * a.invert()).to_bytes(), FieldElement::ONE.to_bytes());
+        assert_eq!((a + a.neg()).to_bytes(), [0u8; 32]);
+        assert_eq!(a.is_negative(), 1);
+        assert_eq!(a.neg().is_negative(), 0);
+        assert_eq!(
+            FieldElement::conditional_select(&a, &b, 1).to_bytes(),
+            b.to_bytes()
+        );
+        // (a^((p - 5) / 8))^8 * a^5 = a^p = a
+        let a5 = a.square().square() * a;
+        let p58 = a.pow_p58().square().square().square();
+        assert_eq!((p58 * a5).to_bytes(), a.to_bytes());
+        assert_eq!(
+            a.mul_small(121_665).to_bytes(),
+            (a * element(121_665)).to_bytes()
+        );
+    }
+}
@@ -0,0 +1,252 @@
+//! HMAC_DRBG and CTR_DRBG (NIST SP 800-90A Rev. 1) state machines.
+//!
+//! HMAC_DRBG uses HMAC-SHA-512; CTR_DRBG uses AES-256 without a derivation
+//! function, so its entropy input must be full-entropy. Both target a
+//! 256-bit security strength. Reseed scheduling and entropy gathering live
+//! in `api::drbg`.
+
+use super::aes256::{self, Aes256};
+use super::hmac::Hmac;
+use super::sha512::Sha512;
+use crate::r#unsafe::memory::zeroize_words;
+
+/// Largest output of one generate request in bytes (2^19 bits).
+pub(crate) const MAX_REQUEST_SIZE: usize = 1 << 16;
+
+/// One DRBG mechanism.
+pub(crate) trait Mechanism: Sized {
+    /// Entropy bytes drawn at instantiation, nonce included.
+    const INSTANTIATE_ENTROPY: usize;
+    /// Entropy bytes drawn per reseed.
+    const RESEED_ENTROPY: usize;
+    /// Longest personalization string or additional input, if bounded.
+    const MAX_INPUT: Option<usize>;
+
+    fn instantiate(entropy: &[u8], personalization: &[u8]) -> Self;
+    fn reseed(&mut self, entropy: &[u8], additional: &[u8]);
+    /// Fill `out`, at most [`MAX_REQUEST_SIZE`] bytes.
+    fn generate(&mut self, out: &mut [u8], additional: &[u8]);
+}
+
+/// HMAC_DRBG state over HMAC-SHA-512.
+pub(crate) struct HmacDrbg {
+    key: [u8; 64],
+    value: [u8; 64],
+}
+
+impl HmacDrbg {
+    /// `V = HMAC(K, V)`.
+    fn next_value(&mut self) {
+        let mut mac = Hmac::<Sha512, 64>::new(&self.key);
+        mac.update(&self.value);
+        self.value = mac.finalize();
+    }
+
+    /// `HMAC_DRBG_Update` over the concatenation of `provided`.
+    fn update(&mut self, provided: &[&[u8]]) {
+        let empty = provided.iter().all(|part| part.is_empty());
+        for separator in [0x00u8, 0x01] {
+            let mut mac = Hmac::<Sha512, 64>::new(&self.key);
+            mac.update(&self.value);
+            mac.update(&[separator]);
+            for part in provided {
+                mac.update(part);
+            }
+            self.key = mac.finalize();
+            self.next_value();
+            if empty {
+                return;
+            }
+        }
+    }
+}
+
+impl Mechanism for HmacDrbg {
+    // 256-bit entropy input and 128-bit nonce
+    const INSTANTIATE_ENTROPY: usize = 48;
+    const RESEED_ENTROPY: usize = 32;
+    const MAX_INPUT: Option<usize> = None;
+
+    fn instantiate(entropy: &[u8], personalization: &[u8]) -> Self {
+        let mut drbg = Self {
+            key: [0x00; 64],
+            value: [0x01; 64],
+        };
+        drbg.update(&[entropy, personalization]);
+        drbg
+    }
+
+    fn reseed(&mut self, entropy: &[u8], additional: &[u8]) {
+        self.update(&[entropy, additional]);
+    }
+
+    fn generate(&mut self, out: &mut [u8], additional: &[u8]) {
+        debug_assert!(out.len() <= MAX_REQUEST_SIZE);
+        if !additional.is_empty() {
+            self.update(&[additional]);
+        }
+        for chunk in out.chunks_mut(64) {
+            self.next_value();
+            chunk.copy_from_slice(&self.value[..chunk.len()]);
+        }
+        self.update(&[additional]);
+    }
+}
+
+impl Drop for HmacDrbg {
+    fn drop(&mut self) {
+        // SAFETY: both are owned, exclusively borrowed arrays
+        unsafe {
+            zeroize_words(&mut self.key);
+            zeroize_words(&mut self.value);
+        }
+    }
+}
+
+/// CTR_DRBG seed length: key plus block.
+const SEED_LEN: usize = aes256::KEY_SIZE + aes256::BLOCK_SIZE;
+
+/// CTR_DRBG state over AES-256, without a derivation function.
+pub(crate) struct CtrDrbg {
+    cipher: Aes256,
+    counter: u128,
+}
+
+impl CtrDrbg {
+    fn next_block(&mut self) -> [u8; aes256::BLOCK_SIZE] {
+        self.counter = self.counter.wrapping_add(1);
+        let mut block = self.counter.to_be_bytes();
+        self.cipher.encrypt_block(&mut block);
+        block
+    }
+
+    /// `CTR_DRBG_Update` with `provided` zero-padded to the seed length.
+    fn update(&mut self, provided: &[u8]) {
+        debug_assert!(provided.len() <= SEED_LEN);
+        let mut temp = [0u8; SEED_LEN];
+        for chunk in temp.chunks_exact_mut(aes256::BLOCK_SIZE) {
+            chunk.copy_from_slice(&self.next_block());
+        }
+        for (t, p) in temp.iter_mut().zip(provided) {
+            *t ^= p;
+        }
+        let (key, counter) = temp.split_at(aes256::KEY_SIZE);
+        self.cipher = Aes256::new(key.try_into().expect("key-sized half"));
+        self.counter = u128::from_be_bytes(counter.try_into().expect("block-sized half"));
+        // SAFETY: `temp` is a local array about to go out of scope
+        unsafe { zeroize_words(&mut temp) };
+    }
+
+    /// `entropy` XOR `input` zero-padded, for instantiate and reseed.
+    fn seed_material(entropy: &[u8], input: &[u8]) -> [u8; SEED_LEN] {
+        let mut seed = [0u8; SEED_LEN];
+        seed.copy_from_slice(entropy);
+        for (s, i) in seed.iter_mut().zip(input) {
+            *s ^= i;
+        }
+        seed
+    }
+}
+
+impl Mechanism for CtrDrbg {
+    const INSTANTIATE_ENTROPY: usize = SEED_LEN;
+    const RESEED_ENTROPY: usize = SEED_LEN;
+    const MAX_INPUT: Option<usize> = Some(SEED_LEN);
+
+    fn instantiate(entropy: &[u8], personalization: &[u8]) -> Self {
+        let mut drbg = Self {
+            cipher: Aes256::new(&[0u8; aes256::KEY_SIZE]),
+            counter: 0,
+        };
+        drbg.reseed(entropy, personalization);
+        drbg
+    }
+
+    fn reseed(&mut self, entropy: &[u8], additional: &[u8]) {
+        let mut seed = Self::seed_material(entropy, additional);
+        self.update(&seed);
+        // SAFETY: `seed` is a local array about to go out of scope
+        unsafe { zeroize_words(&mut seed) };
+    }
+
+    fn generate(&mut self, out: &mut [u8], additional: &[u8]) {
+        debug_assert!(out.len() <= MAX_REQUEST_SIZE);
+        if !additional.is_empty() {
+            self.update(additional);
+        }
+        for chunk in out.chunks_mut(aes256::BLOCK_SIZE) {
+            let mut block = self.next_block();
+            chunk.copy_from_slice(&block[..chunk.len()]);
+            // SAFETY: `block` is a local array about to go out of scope
+            unsafe { zeroize_words(&mut block) };
+        }
+        self.update(additional);
+    }
+}
+
+impl Drop for CtrDrbg {
+    fn drop(&mut self) {
+        // SAFETY: `counter` is owned and exclusively borrowed; the cipher
+        // zeroizes its own round keys
+        unsafe { zeroize_words(core::slice::from_mut(&mut self.counter)) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// Entropy stream `0, 1, ..., 250, 0, 1, ...`.
+    fn entropy(offset: usize, len: usize) -> Vec<u8> {
+        (offset..offset + len).map(|i| (i % 251) as u8).collect()
+    }
+
+    /// Instantiate, generate twice (the second time with additional input),
+    /// reseed, generate again; 80 bytes per request. Expected outputs from an independent
+    /// Python implementation of SP 800-90A.
+    fn run<M: Mechanism>(expected: [&str; 3]) {
+        let mut drbg = M::instantiate(&entropy(0, M::INSTANTIATE_ENTROPY), b"citadel test");
+        let mut out = [0u8; 80];
+        drbg.generate(&mut out, b"");
+        assert_eq!(hex(&out), expected[0]);
+        drbg.generate(&mut out, b"more");
+        assert_eq!(hex(&out), expected[1]);
+        drbg.reseed(&entropy(M::INSTANTIATE_ENTROPY, M::RESEED_ENTROPY), b"");
+        drbg.generate(&mut out, b"");
+        assert_eq!(hex(&out), expected[2]);
+    }
+
+    #[test]
+    fn hmac_drbg_sha512() {
+        run::<HmacDrbg>([
+            "908b24e3c2dce893dd8ecf0d26584a574856dae4a177bfb72f66a6d9d58dc476\
+             12c3e98f5fe47cf6a3b84f209c9240d4d843740bb51dd9025185570ea5423015\
+             5183c85d6c1603dbd7830cb0a064a7da",
+            "7c4514fbbf26e7920c3cf023c495eb33a4679e41df0c837b03ae0efdca3d490a\
+             7c74224bac744440d6ed08de55d9c34bda1dfa7809ba130b763dfdca3827ed28\
+             c7a236a8ca61644974486107bdb903a6",
+            "a0b9ac78801e45b8e7af9ef068ae3fc08c8b473a986249cd4832f4187af25cd6\
+             82d76b0e208aca38a303880374fb1c254dbc941a66d065cf5ddbd206be1b4d3a\
+             69836602a8e468cb89dd2586f34f9ebd",
+        ]);
+    }
+
+    #[test]
+    fn ctr_drbg_aes256() {
+        run::<CtrDrbg>([
+            "767b585301ece8698a7dc5aacf8c6441745cc668fc496d62b5877c026c8cabe2\
+             ae1dc1b8119de99c9d76461b5b58dd5ef921fafc5bbaf1535f4e74cc78ce203d\
+             159a48aede1b9f423c158f554d385757",
+            "0e35e7316e1e944ebba5b8d8d452fc185abe94e71fb4fe79697415c40da22ef0\
+             660b943211867a0515ae59efb2cc06dcc7e428204f568379139139edb30efc67\
+             cd5f71a1fcd48e2af90162799a79d275",
+            "4361e4ba00f4d1c78cb110bf3cfedc91cf1be0356caa348ab36e5de48f687b91\
+             dba73b9f7460e5da19832de810349db3ba012b8ba45dd0cf3c2e51d7bced7d90\
+             a72e39bfa98e16fa1a85c0b68d9d558c",
+        ]);
+    }
+}
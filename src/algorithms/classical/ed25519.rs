@@ -0,0 +1,645 @@
+//! Ed25519 signatures (RFC 8032).
+//!
+//! Extended twisted Edwards coordinates over the shared GF(2^255 - 19)
+//! arithmetic. Scalar multiplication is a constant-time double-and-add
+//! with conditional selection; scalar reduction modulo the group order is
+//! a constant-time shift-and-subtract. Verification is cofactorless and
+//! rejects non-canonical `S` and public key encodings.
+
+use super::curve25519::FieldElement;
+use super::sha512::Sha512;
+use crate::consts;
+use crate::errors::{CryptoError, MisuseError, Result};
+use crate::internal::ct;
+#[cfg(feature = "signing")]
+use crate::internal::entropy;
+#[cfg(feature = "signing")]
+use crate::internal::traits::SignatureScheme;
+use crate::internal::traits::SignatureVerifier;
+#[cfg(feature = "signing")]
+use crate::memory::SensitiveBytes;
+#[cfg(feature = "signing")]
+use crate::r#unsafe::memory::zeroize_words;
+
+/// Public key size in bytes.
+pub(crate) const PUBLIC_KEY_SIZE: usize = consts::Ed25519::PUBLIC_KEY_SIZE;
+/// Secret key (seed) size in bytes.
+pub(crate) const SECRET_KEY_SIZE: usize = consts::Ed25519::SECRET_KEY_SIZE;
+/// Signature size in bytes.
+pub(crate) const SIGNATURE_SIZE: usize = consts::Ed25519::SIGNATURE_SIZE;
+
+/// Curve constant d = -121665 / 121666.
+const D: FieldElement = FieldElement([
+    0x34dca135978a3,
+    0x1a8283b156ebd,
+    0x5e7a26001c029,
+    0x739c663a03cbb,
+    0x52036cee2b6ff,
+]);
+
+/// 2d.
+const D2: FieldElement = FieldElement([
+    0x69b9426b2f159,
+    0x35050762add7a,
+    0x3cf44c0038052,
+    0x6738cc7407977,
+    0x2406d9dc56dff,
+]);
+
+/// A square root of -1.
+const SQRT_M1: FieldElement = FieldElement([
+    0x61b274a0ea0b0,
+    0x0d5a5fc8f189d,
+    0x7ef5e9cbd0c60,
+    0x78595a6804c9e,
+    0x2b8324804fc1d,
+]);
+
+/// The base point B.
+const BASE_POINT: EdwardsPoint = EdwardsPoint {
+    x: FieldElement([
+        0x62d608f25d51a,
+        0x412a4b4f6592a,
+        0x75b7171a4b31d,
+        0x1ff60527118fe,
+        0x216936d3cd6e5,
+    ]),
+    y: FieldElement([
+        0x6666666666658,
+        0x4cccccccccccc,
+        0x1999999999999,
+        0x3333333333333,
+        0x6666666666666,
+    ]),
+    z: FieldElement::ONE,
+    t: FieldElement([
+        0x68ab3a5b7dda3,
+        0x00eea2a5eadbb,
+        0x2af8df483c27e,
+        0x332b375274732,
+        0x67875f0fd78b7,
+    ]),
+};
+
+/// Group order L = 2^252 + 27742317777372353535851937790883648493, as
+/// little-endian 64-bit limbs.
+const L: [u64; 4] = [
+    0x5812631a5cf5d3ed,
+    0x14def9dea2f79cd6,
+    0x0000000000000000,
+    0x1000000000000000,
+];
+
+/// A point in extended coordinates: x = X/Z, y = Y/Z, xy = T/Z.
+#[derive(Clone, Copy)]
+struct EdwardsPoint {
+    x: FieldElement,
+    y: FieldElement,
+    z: FieldElement,
+    t: FieldElement,
+}
+
+impl EdwardsPoint {
+    const IDENTITY: Self = Self {
+        x: FieldElement::ZERO,
+        y: FieldElement::ONE,
+        z: FieldElement::ONE,
+        t: FieldElement::ZERO,
+    };
+
+    fn add(&self, other: &Self) -> Self {
+        let a = (self.y - self.x) * (other.y - other.x);
+        let b = (self.y + self.x) * (other.y + other.x);
+        let c = self.t * D2 * other.t;
+        let d = (self.z + self.z) * other.z;
+        let e = b - a;
+        let f = d - c;
+        let g = d + c;
+        let h = b + a;
+        Self {
+            x: e * f,
+            y: g * h,
+            z: f * g,
+            t: e * h,
+        }
+    }
+
+    fn double(&self) -> Self {
+        let a = self.x.square();
+        let b = self.y.square();
+        let c = self.z.square().mul_small(2);
+        let h = a + b;
+        let e = h - (self.x + self.y).square();
+        let g = a - b;
+        let f = c + g;
+        Self {
+            x: e * f,
+            y: g * h,
+            z: f * g,
+            t: e * h,
+        }
+    }
+
+    fn neg(&self) -> Self {
+        Self {
+            x: self.x.neg(),
+            y: self.y,
+            z: self.z,
+            t: self.t.neg(),
+        }
+    }
+
+    fn conditional_select(a: &Self, b: &Self, choice: u8) -> Self {
+        Self {
+            x: FieldElement::conditional_select(&a.x, &b.x, choice),
+            y: FieldElement::conditional_select(&a.y, &b.y, choice),
+            z: FieldElement::conditional_select(&a.z, &b.z, choice),
+            t: FieldElement::conditional_select(&a.t, &b.t, choice),
+        }
+    }
+
+    /// `[scalar]self` for a 256-bit little-endian scalar, in constant time.
+    fn mul(&self, scalar: &[u8; 32]) -> Self {
+        let mut acc = Self::IDENTITY;
+        for i in (0..256).rev() {
+            acc = acc.double();
+            let bit = (scalar[i / 8] >> (i % 8)) & 1;
+            acc = Self::conditional_select(&acc, &acc.add(self), bit);
+        }
+        acc
+    }
+
+    fn compress(&self) -> [u8; 32] {
+        let z_inv = self.z.invert();
+        let x = self.x * z_inv;
+        let mut bytes = (self.y * z_inv).to_bytes();
+        bytes[31] |= x.is_negative() << 7;
+        bytes
+    }
+
+    /// RFC 8032 §5.1.3 decoding. Rejects non-canonical y and x = 0 with
+    /// the sign bit set. Not constant time; only used on public data.
+    fn decompress(bytes: &[u8; 32]) -> Option<Self> {
+        let sign = bytes[31] >> 7;
+        let y = FieldElement::from_bytes(bytes);
+        let mut canonical = *bytes;
+        canonical[31] &= 0x7f;
+        if y.to_bytes() != canonical {
+            return None;
+        }
+
+        let yy = y.square();
+        let u = yy - FieldElement::ONE;
+        let v = yy * D + FieldElement::ONE;
+        let v3 = v.square() * v;
+        let mut x = u * v3 * (u * v3.square() * v).pow_p58();
+
+        let vxx = v * x.square();
+        if vxx.to_bytes() != u.to_bytes() {
+            if vxx.to_bytes() != u.neg().to_bytes() {
+                return None;
+            }
+            x = x * SQRT_M1;
+        }
+        if x.to_bytes() == [0u8; 32] && sign == 1 {
+            return None;
+        }
+        if x.is_negative() != sign {
+            x = x.neg();
+        }
+        Some(Self {
+            x,
+            y,
+            z: FieldElement::ONE,
+            t: x * y,
+        })
+    }
+
+    #[cfg(feature = "signing")]
+    fn zeroize(&mut self) {
+        for element in [&mut self.x, &mut self.y, &mut self.z, &mut self.t] {
+            // SAFETY: the limb array is exclusively borrowed
+            unsafe { zeroize_words(&mut element.0) };
+        }
+    }
+}
+
+/// `value mod L` for a little-endian integer of up to 512 bits, in
+/// constant time.
+fn reduce(value: &[u64; 8]) -> [u64; 4] {
+    let mut rem = [0u64; 4];
+    for i in (0..512).rev() {
+        // rem < L < 2^253, so the shift cannot overflow
+        let bit = (value[i / 64] >> (i % 64)) & 1;
+        let mut carry = bit;
+        for limb in rem.iter_mut() {
+            let next = *limb >> 63;
+            *limb = (*limb << 1) | carry;
+            carry = next;
+        }
+
+        let mut diff = [0u64; 4];
+        let mut borrow = 0u64;
+        for (j, d) in diff.iter_mut().enumerate() {
+            let (t, b1) = rem[j].overflowing_sub(L[j]);
+            let (t, b2) = t.overflowing_sub(borrow);
+            *d = t;
+            borrow = u64::from(b1 | b2);
+        }
+        // Keep the difference when there was no borrow (rem >= L).
+        let mask = borrow.wrapping_sub(1);
+        for (r, d) in rem.iter_mut().zip(diff) {
+            *r ^= mask & (*r ^ d);
+        }
+    }
+    rem
+}
+
+fn limbs_from_bytes<const N: usize, const W: usize>(bytes: &[u8; N]) -> [u64; W] {
+    let mut limbs = [0u64; W];
+    for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+        *limb = u64::from_le_bytes(chunk.try_into().unwrap());
+    }
+    limbs
+}
+
+fn limbs_to_bytes(limbs: &[u64; 4]) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    for (chunk, limb) in bytes.chunks_exact_mut(8).zip(limbs) {
+        chunk.copy_from_slice(&limb.to_le_bytes());
+    }
+    bytes
+}
+
+/// Reduce a 64-byte hash output modulo L.
+fn reduce_hash(hash: &[u8; 64]) -> [u8; 32] {
+    limbs_to_bytes(&reduce(&limbs_from_bytes(hash)))
+}
+
+/// `(a * b + c) mod L`, in constant time.
+#[cfg(feature = "signing")]
+fn mul_add(a: &[u8; 32], b: &[u8; 32], c: &[u8; 32]) -> [u8; 32] {
+    let a: [u64; 4] = limbs_from_bytes(a);
+    let b: [u64; 4] = limbs_from_bytes(b);
+    let c: [u64; 4] = limbs_from_bytes(c);
+
+    let mut wide = [0u64; 8];
+    for i in 0..4 {
+        let mut carry = 0u128;
+        for j in 0..4 {
+            let t = u128::from(a[i]) * u128::from(b[j]) + u128::from(wide[i + j]) + carry;
+            wide[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        wide[i + 4] = carry as u64;
+    }
+    let mut carry = 0u128;
+    for (i, limb) in wide.iter_mut().enumerate() {
+        let t = u128::from(*limb) + u128::from(*c.get(i).unwrap_or(&0)) + carry;
+        *limb = t as u64;
+        carry = t >> 64;
+    }
+
+    let out = limbs_to_bytes(&reduce(&wide));
+    // SAFETY: `wide` is an owned local
+    unsafe { zeroize_words(&mut wide) };
+    out
+}
+
+/// Whether a 32-byte little-endian scalar is below L, in constant time.
+fn is_canonical_scalar(bytes: &[u8; 32]) -> bool {
+    ct::is_canonical_le(bytes, &limbs_to_bytes(&L))
+}
+
+/// Expanded secret key: clamped scalar and nonce prefix.
+#[cfg(feature = "signing")]
+struct ExpandedSecret {
+    scalar: SensitiveBytes<32>,
+    prefix: SensitiveBytes<32>,
+}
+
+#[cfg(feature = "signing")]
+impl ExpandedSecret {
+    fn from_seed(seed: &[u8; SECRET_KEY_SIZE]) -> Self {
+        let hash = SensitiveBytes::new(Sha512::digest(seed));
+        let mut scalar = SensitiveBytes::<32>::zeroed();
+        let mut prefix = SensitiveBytes::<32>::zeroed();
+        scalar
+            .as_bytes_mut()
+            .copy_from_slice(&hash.as_bytes()[..32]);
+        prefix
+            .as_bytes_mut()
+            .copy_from_slice(&hash.as_bytes()[32..]);
+
+        let s = scalar.as_bytes_mut();
+        s[0] &= 248;
+        s[31] &= 127;
+        s[31] |= 64;
+        Self { scalar, prefix }
+    }
+
+    fn public_key(&self) -> [u8; PUBLIC_KEY_SIZE] {
+        let mut point = BASE_POINT.mul(self.scalar.as_bytes());
+        let encoded = point.compress();
+        point.zeroize();
+        encoded
+    }
+}
+
+/// `SHA-512(parts...) mod L`.
+fn hash_to_scalar(parts: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Sha512::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    reduce_hash(&hasher.finalize())
+}
+
+/// `dom2(1, "")` (RFC 8032 §5.1): prefixed to both hashes in Ed25519ph,
+/// so its signatures never verify as plain Ed25519 ones. Plain Ed25519 has
+/// an empty prefix.
+const PREHASH_DOMAIN: &[u8] = b"SigEd25519 no Ed25519 collisions\x01\x00";
+
+/// Ed25519 over raw byte arrays.
+pub(crate) struct Ed25519;
+
+impl Ed25519 {
+    /// Public key for a 32-byte seed.
+    #[cfg(feature = "signing")]
+    pub(crate) fn public_key(secret_key: &[u8; SECRET_KEY_SIZE]) -> [u8; PUBLIC_KEY_SIZE] {
+        ExpandedSecret::from_seed(secret_key).public_key()
+    }
+
+    /// Whether `public_key` decodes to a curve point.
+    pub(crate) fn is_valid_public_key(public_key: &[u8; PUBLIC_KEY_SIZE]) -> bool {
+        EdwardsPoint::decompress(public_key).is_some()
+    }
+
+    /// Deterministic RFC 8032 signature.
+    #[cfg(feature = "signing")]
+    pub(crate) fn sign_with(secret_key: &[u8; SECRET_KEY_SIZE], message: &[u8]) -> [u8; 64] {
+        Self::sign_in_domain(secret_key, b"", message)
+    }
+
+    /// Deterministic Ed25519ph signature over the SHA-512 `digest` of a
+    /// message, with an empty context.
+    #[cfg(feature = "signing")]
+    pub(crate) fn sign_prehashed(
+        secret_key: &[u8; SECRET_KEY_SIZE],
+        digest: &[u8; 64],
+    ) -> [u8; 64] {
+        Self::sign_in_domain(secret_key, PREHASH_DOMAIN, digest)
+    }
+
+    #[cfg(feature = "signing")]
+    fn sign_in_domain(
+        secret_key: &[u8; SECRET_KEY_SIZE],
+        domain: &[u8],
+        message: &[u8],
+    ) -> [u8; 64] {
+        let expanded = ExpandedSecret::from_seed(secret_key);
+        let public_key = expanded.public_key();
+
+        let r = SensitiveBytes::new(hash_to_scalar(&[
+            domain,
+            expanded.prefix.as_bytes(),
+            message,
+        ]));
+        let mut r_point = BASE_POINT.mul(r.as_bytes());
+        let r_encoded = r_point.compress();
+        r_point.zeroize();
+
+        let k = hash_to_scalar(&[domain, &r_encoded, &public_key, message]);
+        let s = mul_add(&k, expanded.scalar.as_bytes(), r.as_bytes());
+
+        let mut signature = [0u8; SIGNATURE_SIZE];
+        signature[..32].copy_from_slice(&r_encoded);
+        signature[32..].copy_from_slice(&s);
+        signature
+    }
+
+    /// Cofactorless RFC 8032 verification.
+    pub(crate) fn verify_with(
+        public_key: &[u8; PUBLIC_KEY_SIZE],
+        message: &[u8],
+        signature: &[u8; SIGNATURE_SIZE],
+    ) -> bool {
+        Self::verify_in_domain(public_key, b"", message, signature)
+    }
+
+    /// Cofactorless Ed25519ph verification against the SHA-512 `digest` of
+    /// a message, with an empty context.
+    pub(crate) fn verify_prehashed(
+        public_key: &[u8; PUBLIC_KEY_SIZE],
+        digest: &[u8; 64],
+        signature: &[u8; SIGNATURE_SIZE],
+    ) -> bool {
+        Self::verify_in_domain(public_key, PREHASH_DOMAIN, digest, signature)
+    }
+
+    fn verify_in_domain(
+        public_key: &[u8; PUBLIC_KEY_SIZE],
+        domain: &[u8],
+        message: &[u8],
+        signature: &[u8; SIGNATURE_SIZE],
+    ) -> bool {
+        let Some(a) = EdwardsPoint::decompress(public_key) else {
+            return false;
+        };
+        let r_encoded: &[u8; 32] = signature[..32].try_into().unwrap();
+        let s: &[u8; 32] = signature[32..].try_into().unwrap();
+        if !is_canonical_scalar(s) {
+            return false;
+        }
+
+        let k = hash_to_scalar(&[domain, r_encoded, public_key, message]);
+        let check = BASE_POINT.mul(s).add(&a.neg().mul(&k));
+        check.compress() == *r_encoded
+    }
+}
+
+impl SignatureVerifier<PUBLIC_KEY_SIZE, SIGNATURE_SIZE> for Ed25519 {
+    fn verify(
+        &self,
+        public_key: &[u8; PUBLIC_KEY_SIZE],
+        message: &[u8],
+        signature: &[u8; SIGNATURE_SIZE],
+    ) -> Result<()> {
+        if !Self::is_valid_public_key(public_key) {
+            return Err(MisuseError::InvalidEncoding.into());
+        }
+        if !Self::verify_with(public_key, message, signature) {
+            return Err(CryptoError::VerificationFailed.into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "signing")]
+impl SignatureScheme<PUBLIC_KEY_SIZE, SECRET_KEY_SIZE, SIGNATURE_SIZE> for Ed25519 {
+    fn generate_keypair(&self) -> Result<([u8; PUBLIC_KEY_SIZE], [u8; SECRET_KEY_SIZE])> {
+        let mut seed = SensitiveBytes::<SECRET_KEY_SIZE>::zeroed();
+        entropy::fill(seed.as_bytes_mut())?;
+        Ok((Self::public_key(seed.as_bytes()), seed.into_inner()))
+    }
+
+    fn sign(
+        &self,
+        secret_key: &[u8; SECRET_KEY_SIZE],
+        message: &[u8],
+    ) -> Result<[u8; SIGNATURE_SIZE]> {
+        Ok(Self::sign_with(secret_key, message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unhex<const N: usize>(hex: &str) -> [u8; N] {
+        let bytes: Vec<u8> = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect();
+        bytes.try_into().unwrap()
+    }
+
+    /// RFC 8032 §7.1, tests 1-3: (seed, public key, message, signature).
+    const VECTORS: [(&str, &str, &str, &str); 3] = [
+        (
+            "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+            "",
+            "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e06522490155\
+             5fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+        ),
+        (
+            "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+            "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+            "72",
+            "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da\
+             085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00",
+        ),
+        (
+            "c5aa8df43f9f837bedb7442f31dcb7b166d38535076f094b85ce3a2e0b4458f7",
+            "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025",
+            "af82",
+            "6291d657deec24024827e69c3abe01a30ce548a284743a445e3680d7db5ac3ac\
+             18ff9b538d16f290ae67f760984dc6594a7c15e9716ed28dc027beceea1ec40a",
+        ),
+    ];
+
+    fn message(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn rfc8032_vectors() {
+        let scheme = Ed25519;
+        for (seed, public_key, msg, signature) in VECTORS {
+            let public_key = unhex::<32>(public_key);
+            let signature = unhex::<64>(signature);
+            let msg = message(msg);
+            #[cfg(feature = "signing")]
+            {
+                let seed = unhex::<32>(seed);
+                assert_eq!(Ed25519::public_key(&seed), public_key);
+                assert_eq!(scheme.sign(&seed, &msg).unwrap(), signature);
+            }
+            #[cfg(not(feature = "signing"))]
+            let _ = seed;
+            scheme.verify(&public_key, &msg, &signature).unwrap();
+        }
+    }
+
+    /// RFC 8032 §7.3: Ed25519ph over "abc".
+    #[test]
+    fn rfc8032_prehash_vector() {
+        let public_key =
+            unhex::<32>("ec172b93ad5e563bf4932c70e1245034c35467ef2efd4d64ebf819683467e2bf");
+        let signature = unhex::<64>(
+            "98a70222f0b8121aa9d30f813d683f809e462b469c7ff87639499bb94e6dae41\
+             31f85042463c2a355a2003d062adf5aaa10b8c61e636062aaad11c2a26083406",
+        );
+        let digest = Sha512::digest(b"abc");
+        #[cfg(feature = "signing")]
+        {
+            let seed =
+                unhex::<32>("833fe62409237b9d62ec77587520911e9a759cec1d19755b7da901b96dca3d42");
+            assert_eq!(Ed25519::sign_prehashed(&seed, &digest), signature);
+        }
+        assert!(Ed25519::verify_prehashed(&public_key, &digest, &signature));
+        // The domain separates the two schemes
+        assert!(!Ed25519::verify_with(&public_key, &digest, &signature));
+    }
+
+    #[cfg(feature = "signing")]
+    #[test]
+    fn rejects_tampering() {
+        let scheme = Ed25519;
+        let (public_key, secret_key) = scheme.generate_keypair().unwrap();
+        let signature = scheme.sign(&secret_key, b"message").unwrap();
+        assert!(scheme.verify(&public_key, b"message", &signature).is_ok());
+        assert_eq!(
+            scheme.verify(&public_key, b"massage", &signature),
+            Err(CryptoError::VerificationFailed.into())
+        );
+
+        let mut tampered = signature;
+        tampered[0] ^= 1;
+        assert!(scheme.verify(&public_key, b"message", &tampered).is_err());
+    }
+
+    #[test]
+    fn rejects_non_canonical_s() {
+        let (_, public_key, msg, signature) = VECTORS[0];
+        let public_key = unhex::<32>(public_key);
+        let mut signature = unhex::<64>(signature);
+        let msg = message(msg);
+
+        // S + L verifies under a lax check but must be rejected.
+        let s: [u8; 32] = signature[32..].try_into().unwrap();
+        let mut s_plus_l: [u64; 4] = limbs_from_bytes(&s);
+        let mut carry = 0u128;
+        for (limb, l) in s_plus_l.iter_mut().zip(L) {
+            let t = u128::from(*limb) + u128::from(l) + carry;
+            *limb = t as u64;
+            carry = t >> 64;
+        }
+        signature[32..].copy_from_slice(&limbs_to_bytes(&s_plus_l));
+        assert!(!Ed25519::verify_with(&public_key, &msg, &signature));
+    }
+
+    #[test]
+    fn scalar_arithmetic() {
+        let mut l_bytes = limbs_to_bytes(&L);
+        assert!(!is_canonical_scalar(&l_bytes));
+        l_bytes[0] -= 1;
+        assert!(is_canonical_scalar(&l_bytes));
+
+        #[cfg(feature = "signing")]
+        {
+            // (L - 1) * (L - 1) + 0 = 1 mod L
+            let mut one = [0u8; 32];
+            one[0] = 1;
+            assert_eq!(mul_add(&l_bytes, &l_bytes, &[0u8; 32]), one);
+            // 0 * x + (L - 1) = L - 1
+            assert_eq!(mul_add(&[0u8; 32], &one, &l_bytes), l_bytes);
+        }
+    }
+
+    #[test]
+    fn point_encoding_round_trips() {
+        let encoded = BASE_POINT.compress();
+        let decoded = EdwardsPoint::decompress(&encoded).unwrap();
+        assert_eq!(decoded.compress(), encoded);
+        assert_eq!(
+            BASE_POINT.double().compress(),
+            BASE_POINT.add(&BASE_POINT).compress()
+        );
+        assert!(EdwardsPoint::decompress(&[0xff; 32]).is_none());
+    }
+}
@@ -0,0 +1,106 @@
+//! HKDF (RFC 5869).
+//!
+//! Crate-internal core of the public HKDF types, generic over any hash
+//! [`Hmac`] accepts.
+
+use super::hmac::{Hmac, HmacHash};
+use crate::r#unsafe::memory::zeroize_words;
+
+/// HKDF-Extract: HMAC of `ikm` keyed by `salt`.
+///
+/// An empty salt is equivalent to RFC 5869's default of `N` zero bytes,
+/// since HMAC zero-pads its key.
+pub(crate) fn extract<H: HmacHash<N>, const N: usize>(salt: &[u8], ikm: &[u8]) -> [u8; N] {
+    let mut hmac = Hmac::<H, N>::new(salt);
+    hmac.update(ikm);
+    hmac.finalize()
+}
+
+/// HKDF-Expand: fill `okm` from `prk` and `info`.
+///
+/// The caller validates that `okm` is at most `255 * N` bytes.
+pub(crate) fn expand<H: HmacHash<N>, const N: usize>(prk: &[u8], info: &[u8], okm: &mut [u8]) {
+    debug_assert!(okm.len() <= 255 * N);
+    let keyed = Hmac::<H, N>::new(prk);
+    let mut t = [0u8; N];
+    for (index, chunk) in okm.chunks_mut(N).enumerate() {
+        let mut hmac = keyed.clone();
+        if index > 0 {
+            hmac.update(&t);
+        }
+        hmac.update(info);
+        hmac.update(&[index as u8 + 1]);
+        t = hmac.finalize();
+        chunk.copy_from_slice(&t[..chunk.len()]);
+    }
+    // SAFETY: `t` is a local array about to go out of scope
+    unsafe { zeroize_words(&mut t) };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::classical::keccak::Sha3;
+    use crate::algorithms::classical::sha256::Sha256;
+    use crate::algorithms::classical::sha384::Sha384;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    fn hkdf<H: HmacHash<N>, const N: usize>(
+        salt: &[u8],
+        ikm: &[u8],
+        info: &[u8],
+        len: usize,
+    ) -> (String, String) {
+        let prk = extract::<H, N>(salt, ikm);
+        let mut okm = vec![0u8; len];
+        expand::<H, N>(&prk, info, &mut okm);
+        (hex(&prk), hex(&okm))
+    }
+
+    #[test]
+    fn rfc5869_vectors() {
+        // Test case 1
+        let salt: Vec<u8> = (0..13).collect();
+        let info: Vec<u8> = (0xf0..0xfa).collect();
+        assert_eq!(
+            hkdf::<Sha256, 32>(&salt, &[0x0b; 22], &info, 42),
+            (
+                "077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5".into(),
+                "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf\
+                 34007208d5b887185865"
+                    .into()
+            )
+        );
+
+        // Test case 3: empty salt and info
+        assert_eq!(
+            hkdf::<Sha256, 32>(b"", &[0x0b; 22], b"", 42),
+            (
+                "19ef24a32c717b167f33a91d6f648bdf96596776afdb6377ac434c1c293ccb04".into(),
+                "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d\
+                 9d201395faa4b61a96c8"
+                    .into()
+            )
+        );
+    }
+
+    #[test]
+    fn other_hashes() {
+        // Test case 1 inputs, cross-checked against pyca/cryptography
+        let salt: Vec<u8> = (0..13).collect();
+        let info: Vec<u8> = (0xf0..0xfa).collect();
+        assert_eq!(
+            hkdf::<Sha3<32>, 32>(&salt, &[0x0b; 22], &info, 42).1,
+            "0c5160501d65021deaf2c14f5abce04c5bd2635abceeba61c2edb6e8ed726749\
+             00557728f2c9f2c4c179"
+        );
+        assert_eq!(
+            hkdf::<Sha384, 48>(&salt, &[0x0b; 22], &info, 42).1,
+            "9b5097a86038b805309076a44b3a9f38063e25b516dcbf369f394cfab43685f7\
+             48b6457763e4f0204fc5"
+        );
+    }
+}
@@ -0,0 +1,184 @@
+//! HMAC (RFC 2104, FIPS 198-1).
+//!
+//! Crate-internal and generic over the crate's fixed-output hashes: the
+//! building block of PBKDF2 for legacy password hashes and of HKDF.
+
+use super::keccak::Sha3;
+use super::sha256::Sha256;
+use super::sha384::Sha384;
+use super::sha512::Sha512;
+use crate::r#unsafe::memory::zeroize_words;
+
+/// Largest block size of a supported hash (SHA3-224's rate).
+const MAX_BLOCK_SIZE: usize = 144;
+
+/// A hash function HMAC can be instantiated with.
+pub(crate) trait HmacHash<const N: usize>: Clone {
+    /// Input block size in bytes; the rate, for SHA-3.
+    const BLOCK_SIZE: usize;
+
+    fn new() -> Self;
+    fn update(&mut self, data: &[u8]);
+    fn finalize(self) -> [u8; N];
+}
+
+macro_rules! hmac_hash {
+    ($hash:ty, $output:literal, $block:expr) => {
+        impl HmacHash<$output> for $hash {
+            const BLOCK_SIZE: usize = $block;
+
+            fn new() -> Self {
+                <$hash>::new()
+            }
+
+            fn update(&mut self, data: &[u8]) {
+                <$hash>::update(self, data);
+            }
+
+            fn finalize(self) -> [u8; $output] {
+                <$hash>::finalize(self)
+            }
+        }
+    };
+}
+
+hmac_hash!(Sha256, 32, 64);
+hmac_hash!(Sha384, 48, 128);
+hmac_hash!(Sha512, 64, 128);
+
+impl<const N: usize> HmacHash<N> for Sha3<N> {
+    const BLOCK_SIZE: usize = Sha3::<N>::BLOCK_SIZE;
+
+    fn new() -> Self {
+        Sha3::new()
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        Sha3::update(self, data);
+    }
+
+    fn finalize(self) -> [u8; N] {
+        Sha3::finalize(self)
+    }
+}
+
+/// Incremental HMAC over `H`, with an `N`-byte tag.
+///
+/// Cloning after [`new`](Self::new) reuses the keyed inner and outer
+/// states, which PBKDF2 and HKDF rely on to avoid rehashing the key per
+/// block.
+#[derive(Clone)]
+pub(crate) struct Hmac<H, const N: usize> {
+    inner: H,
+    outer: H,
+}
+
+/// HMAC-SHA-256.
+pub(crate) type HmacSha256 = Hmac<Sha256, 32>;
+
+impl<H: HmacHash<N>, const N: usize> Hmac<H, N> {
+    pub(crate) fn new(key: &[u8]) -> Self {
+        let block_size = H::BLOCK_SIZE;
+        let mut block = [0u8; MAX_BLOCK_SIZE];
+        if key.len() > block_size {
+            let mut hash = H::new();
+            hash.update(key);
+            let mut digest = hash.finalize();
+            block[..N].copy_from_slice(&digest);
+            // SAFETY: `digest` is a local array about to go out of scope
+            unsafe { zeroize_words(&mut digest) };
+        } else {
+            block[..key.len()].copy_from_slice(key);
+        }
+
+        let mut pad = [0u8; MAX_BLOCK_SIZE];
+        for (p, k) in pad.iter_mut().zip(block.iter()) {
+            *p = k ^ 0x36;
+        }
+        let mut inner = H::new();
+        inner.update(&pad[..block_size]);
+        for (p, k) in pad.iter_mut().zip(block.iter()) {
+            *p = k ^ 0x5c;
+        }
+        let mut outer = H::new();
+        outer.update(&pad[..block_size]);
+
+        // SAFETY: both are local arrays about to go out of scope
+        unsafe {
+            zeroize_words(&mut pad);
+            zeroize_words(&mut block);
+        }
+        Self { inner, outer }
+    }
+
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    pub(crate) fn finalize(self) -> [u8; N] {
+        let Self { inner, mut outer } = self;
+        let mut inner_hash = inner.finalize();
+        outer.update(&inner_hash);
+        // SAFETY: `inner_hash` is a local array about to go out of scope
+        unsafe { zeroize_words(&mut inner_hash) };
+        outer.finalize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    fn mac<H: HmacHash<N>, const N: usize>(key: &[u8], data: &[u8]) -> String {
+        let mut hmac = Hmac::<H, N>::new(key);
+        hmac.update(data);
+        hex(&hmac.finalize())
+    }
+
+    #[test]
+    fn rfc4231_vectors() {
+        // Test cases 1, 2 and 6 (key longer than the block size)
+        assert_eq!(
+            mac::<Sha256, 32>(&[0x0b; 20], b"Hi There"),
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7"
+        );
+        assert_eq!(
+            mac::<Sha256, 32>(b"Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert_eq!(
+            mac::<Sha256, 32>(
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            ),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+        assert_eq!(
+            mac::<Sha384, 48>(b"Jefe", b"what do ya want for nothing?"),
+            "af45d2e376484031617f78d2b58a6b1b9c7ef464f5a01b47e42ec3736322445e\
+             8e2240ca5e69e2c78b3239ecfab21649"
+        );
+        assert_eq!(
+            mac::<Sha512, 64>(
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            ),
+            "80b24263c7c1a3ebb71493c1dd7be8b49b46d1f41b4aeec1121b013783f8f352\
+             6b56d037e05f2598bd0fd2215d6a1e5295e64f73f63f0aec8b915a985d786598"
+        );
+    }
+
+    #[test]
+    fn sha3_vectors() {
+        // NIST HMAC-SHA3 examples, key longer than the rate
+        let key: Vec<u8> = (0..=0xa7).collect();
+        assert_eq!(
+            mac::<Sha3<32>, 32>(&key, b"Sample message for keylen>blocklen"),
+            "9bcf2c238e235c3ce88404e813bd2f3a97185ac6f238c63d6229a00b07974258"
+        );
+    }
+}
@@ -0,0 +1,286 @@
+//! KangarooTwelve (RFC 9861).
+//!
+//! Crate-internal core of the public `hash::KangarooTwelve`. The input
+//! `S = M || C || length_encode(|C|)` is cut into 8 KiB chunks: the first
+//! is absorbed by the final node, every later one is hashed as a leaf
+//! whose 32-byte chaining value the final node absorbs in order. Leaves
+//! are independent, so with the `parallel` feature
+//! [`K12::update_parallel`] hashes runs of whole leaves on the rayon
+//! thread pool.
+
+use super::keccak::{self, Sponge};
+
+const CHUNK_LEN: usize = 8192;
+const CV_LEN: usize = 32;
+
+/// Final-node suffix when the input fits in one chunk.
+const SINGLE_NODE: u8 = 0x07;
+/// Final-node suffix in tree mode.
+const FINAL_NODE: u8 = 0x06;
+/// Leaf suffix.
+const LEAF: u8 = 0x0b;
+/// Separates the first chunk from the chaining values in tree mode.
+const TREE_MARKER: [u8; 8] = [0x03, 0, 0, 0, 0, 0, 0, 0];
+
+/// `length_encode(value)`: big-endian without leading zero bytes, then
+/// the byte count. Returns the buffer and the encoded length.
+fn length_encode(value: u64) -> ([u8; 9], usize) {
+    let skip = value.leading_zeros() as usize / 8;
+    let len = 8 - skip;
+    let mut out = [0u8; 9];
+    out[..len].copy_from_slice(&value.to_be_bytes()[skip..]);
+    out[len] = len as u8;
+    (out, len + 1)
+}
+
+#[cfg(feature = "parallel")]
+fn leaf_cv(leaf: &[u8]) -> [u8; CV_LEN] {
+    let mut sponge = keccak::turboshake128(LEAF);
+    sponge.absorb(leaf);
+    let mut cv = [0u8; CV_LEN];
+    sponge.squeeze(&mut cv);
+    cv
+}
+
+/// Incremental KangarooTwelve state.
+#[derive(Clone)]
+pub(crate) struct K12 {
+    final_node: Sponge,
+    leaf: Sponge,
+    customization: Vec<u8>,
+    /// Bytes of `S` absorbed so far.
+    absorbed: u64,
+    /// Leaves whose chaining value the final node has absorbed.
+    leaves: u64,
+}
+
+impl K12 {
+    pub(crate) fn new(customization: &[u8]) -> Self {
+        Self {
+            final_node: keccak::turboshake128(FINAL_NODE),
+            leaf: keccak::turboshake128(LEAF),
+            customization: customization.to_vec(),
+            absorbed: 0,
+            leaves: 0,
+        }
+    }
+
+    pub(crate) fn customization(&self) -> &[u8] {
+        &self.customization
+    }
+
+    /// Offset of the next byte within its leaf, if it belongs to one.
+    fn leaf_offset(&self) -> Option<usize> {
+        let past_first = self.absorbed.checked_sub(CHUNK_LEN as u64)?;
+        Some((past_first % CHUNK_LEN as u64) as usize)
+    }
+
+    fn absorb_cv(&mut self, cv: &[u8; CV_LEN]) {
+        if self.leaves == 0 {
+            self.final_node.absorb(&TREE_MARKER);
+        }
+        self.final_node.absorb(cv);
+        self.leaves += 1;
+    }
+
+    fn finish_leaf(&mut self) {
+        let mut leaf = core::mem::replace(&mut self.leaf, keccak::turboshake128(LEAF));
+        let mut cv = [0u8; CV_LEN];
+        leaf.squeeze(&mut cv);
+        self.absorb_cv(&cv);
+    }
+
+    pub(crate) fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            let take = match self.leaf_offset() {
+                None => {
+                    let take = (CHUNK_LEN - self.absorbed as usize).min(data.len());
+                    self.final_node.absorb(&data[..take]);
+                    take
+                }
+                Some(offset) => {
+                    let take = (CHUNK_LEN - offset).min(data.len());
+                    self.leaf.absorb(&data[..take]);
+                    if offset + take == CHUNK_LEN {
+                        self.finish_leaf();
+                    }
+                    take
+                }
+            };
+            self.absorbed += take as u64;
+            data = &data[take..];
+        }
+    }
+
+    /// Like [`update`](Self::update), hashing whole leaves on the rayon
+    /// thread pool.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn update_parallel(&mut self, data: &[u8]) {
+        use rayon::prelude::*;
+
+        // Serially up to a leaf boundary
+        let head = match self.leaf_offset() {
+            None => CHUNK_LEN - self.absorbed as usize,
+            Some(0) => 0,
+            Some(offset) => CHUNK_LEN - offset,
+        };
+        let (head, rest) = data.split_at(head.min(data.len()));
+        self.update(head);
+
+        let whole = rest.len() / CHUNK_LEN * CHUNK_LEN;
+        let (leaves, tail) = rest.split_at(whole);
+        let cvs: Vec<[u8; CV_LEN]> = leaves.par_chunks(CHUNK_LEN).map(leaf_cv).collect();
+        for cv in &cvs {
+            self.absorb_cv(cv);
+        }
+        self.absorbed += whole as u64;
+        self.update(tail);
+    }
+
+    /// Fill `out` with the extendable output.
+    pub(crate) fn finalize_xof(mut self, out: &mut [u8]) {
+        let customization = core::mem::take(&mut self.customization);
+        self.update(&customization);
+        let (encoded, len) = length_encode(customization.len() as u64);
+        self.update(&encoded[..len]);
+
+        if self.absorbed <= CHUNK_LEN as u64 {
+            self.final_node.set_suffix(SINGLE_NODE);
+        } else {
+            if self.leaf_offset() != Some(0) {
+                self.finish_leaf();
+            }
+            let (encoded, len) = length_encode(self.leaves);
+            self.final_node.absorb(&encoded[..len]);
+            self.final_node.absorb(&[0xff, 0xff]);
+        }
+        self.final_node.squeeze(out);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    fn pattern(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    fn k12(message: &[u8], customization: &[u8]) -> String {
+        let mut k12 = K12::new(customization);
+        k12.update(message);
+        let mut out = [0u8; 32];
+        k12.finalize_xof(&mut out);
+        hex(&out)
+    }
+
+    #[test]
+    fn rfc9861_vectors() {
+        assert_eq!(
+            k12(b"", b""),
+            "1ac2d450fc3b4205d19da7bfca1b37513c0803577ac7167f06fe2ce1f0ef39e5"
+        );
+        // Last 32 of 10032 output bytes
+        let mut out = vec![0u8; 10032];
+        K12::new(b"").finalize_xof(&mut out);
+        assert_eq!(
+            hex(&out[10000..]),
+            "e8dc563642f7228c84684c898405d3a834799158c079b12880277a1d28e2ff6d"
+        );
+        assert_eq!(
+            k12(&pattern(17 * 17 * 17), b""),
+            "cb552e2ec77d9910701d578b457ddf772c12e322e4ee7fe417f92c758f0d59d0"
+        );
+    }
+
+    #[test]
+    fn chunk_boundaries() {
+        // Cross-checked against an independent implementation
+        let cases: &[(usize, usize, &str)] = &[
+            (
+                8191,
+                0,
+                "1b577636f723643e990cc7d6a659837436fd6a103626600eb8301cd1dbe553d6",
+            ),
+            (
+                8192,
+                0,
+                "48f256f6772f9edfb6a8b661ec92dc93b95ebd05a08a17b39ae3490870c926c3",
+            ),
+            (
+                8193,
+                0,
+                "bb66fe72eaea5179418d5295ee1344854d8ad7f3fa17efcb467ec152341284cf",
+            ),
+            (
+                16384,
+                0,
+                "82778f7f7234c83352e76837b721fbdbb5270b88010d84fa5ab0b61ec8ce0956",
+            ),
+            (
+                16385,
+                0,
+                "5f8d2b943922b451842b4e82740d02369e2d5f9f33c5123509a53b955fe177b2",
+            ),
+            (
+                100000,
+                0,
+                "9bc07b25f06d738be68375643c1b2755b54c402c9fd3827e4783cddbb320e5e5",
+            ),
+            (
+                0,
+                1,
+                "fab658db63e94a246188bf7af69a133045f46ee984c56e3c3328caaf1aa1a583",
+            ),
+            (
+                1,
+                41,
+                "8234d8630d549449dca134f63793c219c6d60a3ea53f7881c8042c226ea17e1e",
+            ),
+            (
+                8190,
+                5,
+                "2e419a433f3cbfcad545bdd5243ca5e57dcde710f08fefbfd8ea2ec8076f4d35",
+            ),
+        ];
+        for &(len, customization, expected) in cases {
+            assert_eq!(
+                k12(&pattern(len), &pattern(customization)),
+                expected,
+                "length {len}, customization {customization}"
+            );
+        }
+    }
+
+    #[test]
+    fn split_updates_match() {
+        let data = pattern(100000);
+        let expected = k12(&data, b"");
+        for split in [1, 8191, 8192, 8193, 16384, 50000] {
+            let mut k12 = K12::new(b"");
+            k12.update(&data[..split]);
+            k12.update(&data[split..]);
+            let mut out = [0u8; 32];
+            k12.finalize_xof(&mut out);
+            assert_eq!(hex(&out), expected, "split {split}");
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_matches_serial() {
+        let data = pattern(100000);
+        for split in [0, 100, 8192, 9000] {
+            let mut k12 = K12::new(b"ctx");
+            k12.update(&data[..split]);
+            k12.update_parallel(&data[split..]);
+            let mut out = [0u8; 32];
+            k12.finalize_xof(&mut out);
+            assert_eq!(hex(&out), self::k12(&data, b"ctx"), "split {split}");
+        }
+    }
+}
@@ -0,0 +1,434 @@
+//! Keccak-f[1600] sponge: SHA3-256, SHA3-512, SHAKE128 and SHAKE256
+//! (FIPS 202), cSHAKE128/256 (SP 800-185), and the 12-round
+//! TurboSHAKE128 behind KangarooTwelve (RFC 9861).
+//!
+//! Crate-internal; used by the ML-KEM and ML-DSA implementations. The
+//! permutation has no data-dependent branches or table lookups.
+//!
+//! Sponge state is zeroized on drop since it absorbs secret seeds.
+
+use crate::r#unsafe::memory::zeroize_words;
+
+const ROUNDS: usize = 24;
+
+const ROUND_CONSTANTS: [u64; ROUNDS] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+const RHO: [u32; 24] = [
+    1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
+];
+
+const PI: [usize; 24] = [
+    10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
+];
+
+/// Domain separation suffix for SHA3-*.
+const SHA3_PAD: u8 = 0x06;
+/// Domain separation suffix for SHAKE*.
+const SHAKE_PAD: u8 = 0x1f;
+/// Domain separation suffix for cSHAKE*.
+const CSHAKE_PAD: u8 = 0x04;
+
+/// Rate of SHAKE128 in bytes.
+pub(crate) const SHAKE128_RATE: usize = 168;
+/// Rate of SHAKE256 in bytes.
+pub(crate) const SHAKE256_RATE: usize = 136;
+
+/// Keccak-p[1600, rounds]: the last `rounds` rounds of Keccak-f[1600].
+fn keccak_p1600(state: &mut [u64; 25], rounds: usize) {
+    for &round_constant in &ROUND_CONSTANTS[ROUNDS - rounds..] {
+        // Theta
+        let mut c = [0u64; 5];
+        for (x, column) in c.iter_mut().enumerate() {
+            *column = state[x] ^ state[x + 5] ^ state[x + 10] ^ state[x + 15] ^ state[x + 20];
+        }
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                state[x + 5 * y] ^= d;
+            }
+        }
+
+        // Rho and pi
+        let mut current = state[1];
+        for (&offset, &target) in RHO.iter().zip(PI.iter()) {
+            let next = state[target];
+            state[target] = current.rotate_left(offset);
+            current = next;
+        }
+
+        // Chi
+        for y in 0..5 {
+            let row = [
+                state[5 * y],
+                state[5 * y + 1],
+                state[5 * y + 2],
+                state[5 * y + 3],
+                state[5 * y + 4],
+            ];
+            for x in 0..5 {
+                state[5 * y + x] = row[x] ^ (!row[(x + 1) % 5] & row[(x + 2) % 5]);
+            }
+        }
+
+        // Iota
+        state[0] ^= round_constant;
+    }
+}
+
+/// Keccak sponge with a fixed rate and domain separation suffix.
+#[derive(Clone)]
+pub(crate) struct Sponge {
+    state: [u64; 25],
+    rate: usize,
+    rounds: usize,
+    position: usize,
+    pad: u8,
+    squeezing: bool,
+}
+
+impl Sponge {
+    const fn new(rate: usize, pad: u8) -> Self {
+        Self::with_rounds(rate, pad, ROUNDS)
+    }
+
+    const fn with_rounds(rate: usize, pad: u8, rounds: usize) -> Self {
+        Self {
+            state: [0u64; 25],
+            rate,
+            rounds,
+            position: 0,
+            pad,
+            squeezing: false,
+        }
+    }
+
+    #[inline]
+    fn xor_byte(&mut self, index: usize, byte: u8) {
+        self.state[index / 8] ^= (byte as u64) << (8 * (index % 8));
+    }
+
+    #[inline]
+    fn byte(&self, index: usize) -> u8 {
+        (self.state[index / 8] >> (8 * (index % 8))) as u8
+    }
+
+    /// Absorb more input. Must not be called after squeezing has started.
+    pub(crate) fn absorb(&mut self, data: &[u8]) {
+        debug_assert!(!self.squeezing);
+        for &byte in data {
+            self.xor_byte(self.position, byte);
+            self.position += 1;
+            if self.position == self.rate {
+                keccak_p1600(&mut self.state, self.rounds);
+                self.position = 0;
+            }
+        }
+    }
+
+    fn finish_absorbing(&mut self) {
+        self.xor_byte(self.position, self.pad);
+        self.xor_byte(self.rate - 1, 0x80);
+        keccak_p1600(&mut self.state, self.rounds);
+        self.position = 0;
+        self.squeezing = true;
+    }
+
+    /// Absorb `left_encode(value)` (SP 800-185, section 2.3.1).
+    pub(crate) fn absorb_left_encode(&mut self, value: u64) {
+        let bytes = value.to_be_bytes();
+        let skip = (value.leading_zeros() as usize / 8).min(7);
+        self.absorb(&[(8 - skip) as u8]);
+        self.absorb(&bytes[skip..]);
+    }
+
+    /// Absorb `right_encode(value)` (SP 800-185, section 2.3.1).
+    pub(crate) fn absorb_right_encode(&mut self, value: u64) {
+        let bytes = value.to_be_bytes();
+        let skip = (value.leading_zeros() as usize / 8).min(7);
+        self.absorb(&bytes[skip..]);
+        self.absorb(&[(8 - skip) as u8]);
+    }
+
+    /// Absorb `encode_string(data)` (SP 800-185, section 2.3.2).
+    pub(crate) fn absorb_encoded_string(&mut self, data: &[u8]) {
+        self.absorb_left_encode((data.len() as u64).wrapping_mul(8));
+        self.absorb(data);
+    }
+
+    /// Absorb zeros up to the next block boundary, completing a `bytepad`
+    /// whose `left_encode(rate)` prefix was absorbed from a fresh state.
+    pub(crate) fn pad_to_block(&mut self) {
+        while self.position != 0 {
+            self.absorb(&[0]);
+        }
+    }
+
+    /// Replace the domain separation suffix. Absorption does not depend on
+    /// it, so this is valid until squeezing starts.
+    #[cfg(feature = "k12")]
+    pub(crate) fn set_suffix(&mut self, pad: u8) {
+        debug_assert!(!self.squeezing);
+        self.pad = pad;
+    }
+
+    /// Rate in bytes.
+    pub(crate) const fn rate(&self) -> usize {
+        self.rate
+    }
+
+    /// Whether squeezing has started, after which absorbing is invalid.
+    pub(crate) fn is_squeezing(&self) -> bool {
+        self.squeezing
+    }
+
+    /// Squeeze output, finishing absorption on the first call.
+    pub(crate) fn squeeze(&mut self, out: &mut [u8]) {
+        if !self.squeezing {
+            self.finish_absorbing();
+        }
+        for byte in out.iter_mut() {
+            if self.position == self.rate {
+                keccak_p1600(&mut self.state, self.rounds);
+                self.position = 0;
+            }
+            *byte = self.byte(self.position);
+            self.position += 1;
+        }
+    }
+}
+
+impl Drop for Sponge {
+    fn drop(&mut self) {
+        // SAFETY: `state` is an owned, exclusively borrowed array
+        unsafe { zeroize_words(&mut self.state) };
+    }
+}
+
+/// SHAKE128 extendable-output function.
+pub(crate) const fn shake128() -> Sponge {
+    Sponge::new(SHAKE128_RATE, SHAKE_PAD)
+}
+
+/// SHAKE256 extendable-output function.
+pub(crate) const fn shake256() -> Sponge {
+    Sponge::new(SHAKE256_RATE, SHAKE_PAD)
+}
+
+/// cSHAKE with function name `name` and customization string
+/// `customization`; plain SHAKE when both are empty.
+fn cshake(rate: usize, name: &[u8], customization: &[u8]) -> Sponge {
+    if name.is_empty() && customization.is_empty() {
+        return Sponge::new(rate, SHAKE_PAD);
+    }
+    let mut sponge = Sponge::new(rate, CSHAKE_PAD);
+    sponge.absorb_left_encode(rate as u64);
+    sponge.absorb_encoded_string(name);
+    sponge.absorb_encoded_string(customization);
+    sponge.pad_to_block();
+    sponge
+}
+
+/// cSHAKE128 (SP 800-185).
+pub(crate) fn cshake128(name: &[u8], customization: &[u8]) -> Sponge {
+    cshake(SHAKE128_RATE, name, customization)
+}
+
+/// cSHAKE256 (SP 800-185).
+pub(crate) fn cshake256(name: &[u8], customization: &[u8]) -> Sponge {
+    cshake(SHAKE256_RATE, name, customization)
+}
+
+/// TurboSHAKE128 with domain separation byte `domain` (0x01..=0x7f).
+#[cfg(feature = "k12")]
+pub(crate) const fn turboshake128(domain: u8) -> Sponge {
+    Sponge::with_rounds(SHAKE128_RATE, domain, 12)
+}
+
+/// SHAKE256 of the concatenation of `parts`, filling `out`.
+#[cfg_attr(not(any(feature = "mlkem", feature = "mldsa")), allow(dead_code))]
+pub(crate) fn shake256_into(parts: &[&[u8]], out: &mut [u8]) {
+    let mut sponge = shake256();
+    for part in parts {
+        sponge.absorb(part);
+    }
+    sponge.squeeze(out);
+}
+
+/// SHA3 with an `output_size`-byte digest (28, 32, 48 or 64).
+pub(crate) const fn sha3(output_size: usize) -> Sponge {
+    Sponge::new(200 - 2 * output_size, SHA3_PAD)
+}
+
+/// SHA3 with an `N`-byte digest as an incremental fixed-output hasher,
+/// for constructions generic over the hash such as HMAC.
+#[derive(Clone)]
+pub(crate) struct Sha3<const N: usize> {
+    sponge: Sponge,
+}
+
+impl<const N: usize> Sha3<N> {
+    /// Block size (the rate) in bytes.
+    pub(crate) const BLOCK_SIZE: usize = 200 - 2 * N;
+
+    pub(crate) const fn new() -> Self {
+        Self { sponge: sha3(N) }
+    }
+
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        self.sponge.absorb(data);
+    }
+
+    pub(crate) fn finalize(mut self) -> [u8; N] {
+        let mut out = [0u8; N];
+        self.sponge.squeeze(&mut out);
+        out
+    }
+}
+
+/// SHA3-256 of the concatenation of `parts`.
+pub(crate) fn sha3_256(parts: &[&[u8]]) -> [u8; 32] {
+    let mut sponge = sha3(32);
+    for part in parts {
+        sponge.absorb(part);
+    }
+    let mut out = [0u8; 32];
+    sponge.squeeze(&mut out);
+    out
+}
+
+/// SHA3-512 of the concatenation of `parts`.
+pub(crate) fn sha3_512(parts: &[&[u8]]) -> [u8; 64] {
+    let mut sponge = sha3(64);
+    for part in parts {
+        sponge.absorb(part);
+    }
+    let mut out = [0u8; 64];
+    sponge.squeeze(&mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn sha3_vectors() {
+        assert_eq!(
+            hex(&sha3_256(&[b""])),
+            "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a"
+        );
+        assert_eq!(
+            hex(&sha3_256(&[b"abc"])),
+            "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532"
+        );
+        assert_eq!(
+            hex(&sha3_512(&[b"abc"])),
+            "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e\
+             10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0"
+        );
+    }
+
+    #[test]
+    fn shake_vectors() {
+        let mut out = [0u8; 32];
+        shake128().squeeze(&mut out);
+        assert_eq!(
+            hex(&out),
+            "7f9c2ba4e88f827d616045507605853ed73b8093f6efbc88eb1a6eacfa66ef26"
+        );
+        shake256_into(&[b""], &mut out);
+        assert_eq!(
+            hex(&out),
+            "46b9dd2b0ba88d13233b3feb743eeb243fcd52ea62b81b82b50c27646ed5762f"
+        );
+    }
+
+    #[test]
+    fn incremental_matches_one_shot() {
+        let data: Vec<u8> = (0..500u32).map(|i| i as u8).collect();
+        for split in [0, 1, 135, 136, 137, 300, 500] {
+            assert_eq!(
+                sha3_256(&[&data[..split], &data[split..]]),
+                sha3_256(&[&data])
+            );
+        }
+
+        let mut whole = [0u8; 400];
+        let mut sponge = shake128();
+        sponge.absorb(&data);
+        sponge.squeeze(&mut whole);
+
+        let mut pieces = [0u8; 400];
+        let mut sponge = shake128();
+        sponge.absorb(&data);
+        let (first, rest) = pieces.split_at_mut(170);
+        sponge.squeeze(first);
+        sponge.squeeze(rest);
+        assert_eq!(whole, pieces);
+    }
+
+    #[test]
+    fn sp800_185_encodings() {
+        let mut sponge = shake128();
+        sponge.absorb_left_encode(0);
+        sponge.absorb_left_encode(168);
+        sponge.absorb_right_encode(256);
+        sponge.absorb_encoded_string(b"KMAC");
+        assert_eq!(sponge.position, 2 + 2 + 3 + 2 + 4);
+        let mut expected = shake128();
+        expected.absorb(&[1, 0, 1, 168, 1, 0, 2, 1, 32, b'K', b'M', b'A', b'C']);
+        assert_eq!(sponge.state, expected.state);
+    }
+
+    #[test]
+    fn cshake_vectors() {
+        // SP 800-185 examples, cSHAKE samples 1 and 3
+        let mut out = [0u8; 32];
+        let mut sponge = cshake128(b"", b"Email Signature");
+        sponge.absorb(&[0, 1, 2, 3]);
+        sponge.squeeze(&mut out);
+        assert_eq!(
+            hex(&out),
+            "c1c36925b6409a04f1b504fcbca9d82b4017277cb5ed2b2065fc1d3814d5aaf5"
+        );
+
+        let mut out = [0u8; 64];
+        let mut sponge = cshake256(b"", b"Email Signature");
+        sponge.absorb(&[0, 1, 2, 3]);
+        sponge.squeeze(&mut out);
+        assert_eq!(
+            hex(&out),
+            "d008828e2b80ac9d2218ffee1d070c48b8e4c87bff32c9699d5b6896eee0edd1\
+             64020e2be0560858d9c00c037e34a96937c561a74c412bb4c746469527281c8c"
+        );
+    }
+}
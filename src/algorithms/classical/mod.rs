@@ -0,0 +1,29 @@
+#[cfg(feature = "aegis256")]
+pub(crate) mod aegis256;
+pub(crate) mod aes256;
+pub(crate) mod aes_kw;
+#[cfg(feature = "aes-gcm-siv")]
+pub(crate) mod aes256_gcm_siv;
+#[cfg(feature = "argon2")]
+pub(crate) mod argon2;
+#[cfg(feature = "argon2")]
+pub(crate) mod blake2b;
+#[cfg(feature = "blake3")]
+pub(crate) mod blake3;
+pub(crate) mod curve25519;
+pub(crate) mod drbg;
+pub(crate) mod ed25519;
+pub(crate) mod hkdf;
+pub(crate) mod hmac;
+#[cfg(feature = "k12")]
+pub(crate) mod k12;
+pub(crate) mod keccak;
+pub(crate) mod kmac;
+pub(crate) mod pbkdf2;
+#[cfg(feature = "scrypt")]
+pub(crate) mod scrypt;
+pub(crate) mod shamir;
+pub(crate) mod sha256;
+pub(crate) mod sha384;
+pub(crate) mod sha512;
+pub(crate) mod x25519;
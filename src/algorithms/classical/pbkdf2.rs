@@ -0,0 +1,81 @@
+//! PBKDF2-HMAC-SHA-256 (RFC 8018, section 5.2).
+//!
+//! Kept for verifying legacy password hashes and as the outer layer of
+//! scrypt. PBKDF2 is not memory-hard; new password hashes should use a
+//! memory-hard function.
+
+use super::hmac::HmacSha256;
+use crate::r#unsafe::memory::zeroize_words;
+
+/// HMAC-SHA-256 output size in bytes.
+const OUTPUT_SIZE: usize = 32;
+
+/// Fill `output` with PBKDF2-HMAC-SHA-256 of `password` and `salt`.
+///
+/// The caller validates `iterations >= 1` and that `output` is at most
+/// `(2^32 - 1) * 32` bytes.
+pub(crate) fn pbkdf2_hmac_sha256(password: &[u8], salt: &[u8], iterations: u32, output: &mut [u8]) {
+    debug_assert!(iterations >= 1);
+    let keyed = HmacSha256::new(password);
+    for (index, chunk) in output.chunks_mut(OUTPUT_SIZE).enumerate() {
+        let mut hmac = keyed.clone();
+        hmac.update(salt);
+        hmac.update(&(index as u32 + 1).to_be_bytes());
+        let mut u = hmac.finalize();
+        let mut t = u;
+        for _ in 1..iterations {
+            let mut hmac = keyed.clone();
+            hmac.update(&u);
+            u = hmac.finalize();
+            for (t, u) in t.iter_mut().zip(u.iter()) {
+                *t ^= u;
+            }
+        }
+        chunk.copy_from_slice(&t[..chunk.len()]);
+        // SAFETY: both are local arrays about to go out of scope
+        unsafe {
+            zeroize_words(&mut u);
+            zeroize_words(&mut t);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    fn derive(password: &[u8], salt: &[u8], iterations: u32, len: usize) -> String {
+        let mut output = vec![0u8; len];
+        pbkdf2_hmac_sha256(password, salt, iterations, &mut output);
+        hex(&output)
+    }
+
+    #[test]
+    fn rfc7914_vectors() {
+        // RFC 7914, section 11
+        assert_eq!(
+            derive(b"passwd", b"salt", 1, 64),
+            "55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc\
+             49ca9cccf179b645991664b39d77ef317c71b845b1e30bd509112041d3a19783"
+        );
+        assert_eq!(
+            derive(b"Password", b"NaCl", 80000, 64),
+            "4ddcd8f60b98be21830cee5ef22701f9641a4418d04c0414aeff08876b34ab56\
+             a1d425a1225833549adb841b51c9b3176a272bdebba1d078478f62b397f33c8d"
+        );
+    }
+
+    #[test]
+    fn partial_final_block() {
+        let long = derive(b"password", b"salt", 2, 40);
+        assert_eq!(
+            &long[..64],
+            "ae4d0c95af6b46d32d0adff928f06dd02a303f8ef3c251dfd6e2d85a95474c43"
+        );
+        assert_eq!(derive(b"password", b"salt", 2, 20), long[..40]);
+    }
+}
@@ -0,0 +1,199 @@
+//! scrypt (RFC 7914).
+//!
+//! Memory-hard password-based key derivation, used by existing password
+//! stores and encrypted key formats.
+//!
+//! # Side Channels
+//!
+//! ROMix indexes its table with data derived from the password, so memory
+//! access patterns depend on the secret. This is inherent to scrypt.
+
+use super::pbkdf2::pbkdf2_hmac_sha256;
+use crate::errors::{MisuseError, Result};
+use crate::r#unsafe::memory::zeroize_words;
+
+/// Largest working memory accepted, in bytes, for both the ROMix table
+/// (`128 * r * N`) and the blocks (`128 * r * p`).
+pub(crate) const MAX_MEMORY: u64 = 1 << 30;
+
+/// Validated scrypt cost parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Params {
+    log_n: u8,
+    r: u32,
+    p: u32,
+}
+
+impl Params {
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidParameterSet`: If a parameter is zero, the
+    ///   RFC 7914 bounds are violated, or either memory cost exceeds
+    ///   [`MAX_MEMORY`]
+    pub(crate) fn new(log_n: u8, r: u32, p: u32) -> Result<Self> {
+        let block_size = 128 * u64::from(r);
+        let valid = log_n >= 1
+            && r >= 1
+            && p >= 1
+            && u64::from(log_n) < 16 * u64::from(r)
+            && u64::from(r) * u64::from(p) < 1 << 30
+            && log_n < 64
+            && block_size <= MAX_MEMORY >> log_n
+            && block_size * u64::from(p) <= MAX_MEMORY;
+        if !valid {
+            return Err(MisuseError::InvalidParameterSet.into());
+        }
+        Ok(Self { log_n, r, p })
+    }
+
+    pub(crate) fn log_n(&self) -> u8 {
+        self.log_n
+    }
+
+    pub(crate) fn r(&self) -> u32 {
+        self.r
+    }
+
+    pub(crate) fn p(&self) -> u32 {
+        self.p
+    }
+}
+
+/// Fill `output` with scrypt of `password` and `salt`.
+pub(crate) fn scrypt(password: &[u8], salt: &[u8], params: &Params, output: &mut [u8]) {
+    let block_words = 32 * params.r as usize;
+    let mut blocks = vec![0u8; 4 * block_words * params.p as usize];
+    pbkdf2_hmac_sha256(password, salt, 1, &mut blocks);
+
+    let mut x = vec![0u32; block_words];
+    let mut scratch = vec![0u32; block_words];
+    let mut table = vec![0u32; block_words << params.log_n];
+    for block in blocks.chunks_exact_mut(4 * block_words) {
+        for (word, bytes) in x.iter_mut().zip(block.chunks_exact(4)) {
+            *word = u32::from_le_bytes(bytes.try_into().expect("4 bytes"));
+        }
+        ro_mix(&mut x, &mut scratch, &mut table, params.log_n);
+        for (bytes, word) in block.chunks_exact_mut(4).zip(x.iter()) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+    }
+    pbkdf2_hmac_sha256(password, &blocks, 1, output);
+
+    // SAFETY: all buffers are owned locals about to be dropped
+    unsafe {
+        zeroize_words(&mut blocks);
+        zeroize_words(&mut x);
+        zeroize_words(&mut scratch);
+        zeroize_words(&mut table);
+    }
+}
+
+fn ro_mix(x: &mut [u32], scratch: &mut [u32], table: &mut [u32], log_n: u8) {
+    let block_words = x.len();
+    let n = 1u64 << log_n;
+    for entry in table.chunks_exact_mut(block_words) {
+        entry.copy_from_slice(x);
+        block_mix(x, scratch);
+    }
+    for _ in 0..n {
+        let last = &x[block_words - 16..];
+        let j = (u64::from(last[0]) | u64::from(last[1]) << 32) & (n - 1);
+        let entry = &table[j as usize * block_words..][..block_words];
+        for (word, v) in x.iter_mut().zip(entry.iter()) {
+            *word ^= v;
+        }
+        block_mix(x, scratch);
+    }
+}
+
+/// BlockMix with Salsa20/8; `scratch` receives intermediate output.
+fn block_mix(b: &mut [u32], scratch: &mut [u32]) {
+    let half = b.len() / 2;
+    let mut x: [u32; 16] = b[b.len() - 16..].try_into().expect("16 words");
+    for (i, chunk) in b.chunks_exact(16).enumerate() {
+        for (word, input) in x.iter_mut().zip(chunk.iter()) {
+            *word ^= input;
+        }
+        salsa20_8(&mut x);
+        // Even blocks go to the first half, odd blocks to the second
+        let offset = (i / 2) * 16 + (i % 2) * half;
+        scratch[offset..offset + 16].copy_from_slice(&x);
+    }
+    b.copy_from_slice(scratch);
+}
+
+fn salsa20_8(block: &mut [u32; 16]) {
+    let mut x = *block;
+    for _ in 0..4 {
+        for [a, b, c, d] in [[0, 4, 8, 12], [5, 9, 13, 1], [10, 14, 2, 6], [15, 3, 7, 11]] {
+            quarter_round(&mut x, a, b, c, d);
+        }
+        for [a, b, c, d] in [[0, 1, 2, 3], [5, 6, 7, 4], [10, 11, 8, 9], [15, 12, 13, 14]] {
+            quarter_round(&mut x, a, b, c, d);
+        }
+    }
+    for (word, mixed) in block.iter_mut().zip(x.iter()) {
+        *word = word.wrapping_add(*mixed);
+    }
+}
+
+#[inline]
+fn quarter_round(x: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    x[b] ^= x[a].wrapping_add(x[d]).rotate_left(7);
+    x[c] ^= x[b].wrapping_add(x[a]).rotate_left(9);
+    x[d] ^= x[c].wrapping_add(x[b]).rotate_left(13);
+    x[a] ^= x[d].wrapping_add(x[c]).rotate_left(18);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    fn derive(password: &[u8], salt: &[u8], log_n: u8, r: u32, p: u32) -> String {
+        let mut output = [0u8; 64];
+        scrypt(
+            password,
+            salt,
+            &Params::new(log_n, r, p).unwrap(),
+            &mut output,
+        );
+        hex(&output)
+    }
+
+    #[test]
+    fn rfc7914_vectors() {
+        assert_eq!(
+            derive(b"", b"", 4, 1, 1),
+            "77d6576238657b203b19ca42c18a0497f16b4844e3074ae8dfdffa3fede21442\
+             fcd0069ded0948f8326a753a0fc81f17e8d3e0fb2e0d3628cf35e20c38d18906"
+        );
+        assert_eq!(
+            derive(b"password", b"NaCl", 10, 8, 16),
+            "fdbabe1c9d3472007856e7190d01e9fe7c6ad7cbc8237830e77376634b373162\
+             2eaf30d92e22a3886ff109279d9830dac727afb94a83ee6d8360cbdfa2cc0640"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_parameters() {
+        for (log_n, r, p) in [
+            (0, 8, 1),
+            (10, 0, 1),
+            (10, 8, 0),
+            (16, 1, 1),
+            (30, 8, 1),
+            (4, 1, 1 << 24),
+        ] {
+            assert_eq!(
+                Params::new(log_n, r, p).unwrap_err().misuse(),
+                Some(MisuseError::InvalidParameterSet),
+                "ln={log_n} r={r} p={p}"
+            );
+        }
+        assert!(Params::new(20, 8, 1).is_ok());
+    }
+}
@@ -0,0 +1,78 @@
+//! SHA-384 (FIPS 180-4).
+//!
+//! SHA-512 with its own initial state, truncated to 48 bytes.
+
+use super::sha512::Sha512;
+
+const IV: [u64; 8] = [
+    0xcbbb9d5dc1059ed8,
+    0x629a292a367cd507,
+    0x9159015a3070dd17,
+    0x152fecd8f70e5939,
+    0x67332667ffc00b31,
+    0x8eb44a8768581511,
+    0xdb0c2e0d64f98fa7,
+    0x47b5481dbefa4fa4,
+];
+
+/// Incremental SHA-384 state.
+#[derive(Clone)]
+pub(crate) struct Sha384 {
+    inner: Sha512,
+}
+
+impl Sha384 {
+    pub(crate) const fn new() -> Self {
+        Self {
+            inner: Sha512::with_iv(IV),
+        }
+    }
+
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    pub(crate) fn finalize(self) -> [u8; 48] {
+        let full = self.inner.finalize();
+        let mut out = [0u8; 48];
+        out.copy_from_slice(&full[..48]);
+        out
+    }
+
+    pub(crate) fn digest(data: &[u8]) -> [u8; 48] {
+        let mut hasher = Self::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn fips_180_vectors() {
+        assert_eq!(
+            hex(&Sha384::digest(b"")),
+            "38b060a751ac96384cd9327eb1b1e36a21fdb71114be07434c0cc7bf63f6e1da\
+             274edebfe76f65fbd51ad2f14898b95b"
+        );
+        assert_eq!(
+            hex(&Sha384::digest(b"abc")),
+            "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed\
+             8086072ba1e7cc2358baeca134c825a7"
+        );
+        assert_eq!(
+            hex(&Sha384::digest(
+                b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmn\
+                  hijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu"
+            )),
+            "09330c33f71147e83d192fc782cd1b4753111b173b3b05d22fa08086e3b0f712\
+             fcc7c71a557e2db966c3e9fa91746039"
+        );
+    }
+}
@@ -0,0 +1,126 @@
+//! Shamir secret sharing over GF(2^8).
+//!
+//! Every byte of the secret is the constant term of its own polynomial of
+//! degree `threshold - 1` with random higher coefficients. Share `x`, for
+//! `x` in `1..=255`, holds the polynomials' values at `x`: any `threshold`
+//! shares recover the secret by Lagrange interpolation at 0, and fewer
+//! reveal nothing about it.
+//!
+//! The caller draws the coefficients, so this module needs no randomness.
+//!
+//! # Side Channels
+//!
+//! - Field arithmetic is table-free and branch-free, modulo the AES
+//!   polynomial `x^8 + x^4 + x^3 + x + 1`
+//! - Share indices are public and may affect timing
+
+/// Product in GF(2^8).
+fn mul(mut a: u8, b: u8) -> u8 {
+    let mut product = 0;
+    for bit in 0..8 {
+        // 0xff if the multiplier has this bit set
+        product ^= a & 0u8.wrapping_sub((b >> bit) & 1);
+        a = (a << 1) ^ (0x1b & 0u8.wrapping_sub(a >> 7));
+    }
+    product
+}
+
+/// Inverse in GF(2^8), as `a^254`; 0 maps to 0.
+fn invert(a: u8) -> u8 {
+    let a2 = mul(a, a);
+    let a3 = mul(a2, a);
+    let a6 = mul(a3, a3);
+    let a12 = mul(a6, a6);
+    let a15 = mul(a12, a3);
+    let a30 = mul(a15, a15);
+    let a60 = mul(a30, a30);
+    let a120 = mul(a60, a60);
+    let a240 = mul(a120, a120);
+    let a252 = mul(a240, a12);
+    mul(a252, a2)
+}
+
+/// Write share `x` of `secret` to `out`, which must be as long as
+/// `secret`.
+///
+/// `coefficients` holds `threshold - 1` uniformly random rows of
+/// `secret.len()` bytes, the same for every share of one secret; row `i`
+/// is the coefficient of `x^(i + 1)`.
+pub(crate) fn share(secret: &[u8], coefficients: &[u8], x: u8, out: &mut [u8]) {
+    debug_assert!(x != 0 && out.len() == secret.len());
+    debug_assert!(coefficients.len().is_multiple_of(secret.len().max(1)));
+    let rows: Vec<&[u8]> = coefficients.chunks(secret.len().max(1)).collect();
+    for (i, (out, &constant)) in out.iter_mut().zip(secret).enumerate() {
+        // Horner's rule from the highest coefficient
+        let y = rows.iter().rev().fold(0, |y, row| mul(y, x) ^ row[i]);
+        *out = mul(y, x) ^ constant;
+    }
+}
+
+/// Recover the secret into `out` from `(x, share)` pairs with distinct
+/// nonzero `x`.
+///
+/// At least `threshold` shares give the secret; fewer give an unrelated
+/// value, which callers detect by authenticating the result.
+pub(crate) fn combine(shares: &[(u8, &[u8])], out: &mut [u8]) {
+    out.fill(0);
+    for (i, &(x, share)) in shares.iter().enumerate() {
+        // Lagrange basis polynomial of `x` at 0
+        let basis = shares
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| j != i)
+            .fold(1, |basis, (_, &(other, _))| {
+                mul(basis, mul(other, invert(other ^ x)))
+            });
+        for (out, &y) in out.iter_mut().zip(share) {
+            *out ^= mul(basis, y);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn field_arithmetic() {
+        // FIPS 197, section 4.2
+        assert_eq!(mul(0x57, 0x83), 0xc1);
+        assert_eq!(mul(0x57, 0x13), 0xfe);
+        for a in 1..=255 {
+            assert_eq!(mul(a, invert(a)), 1);
+        }
+        assert_eq!(invert(0), 0);
+    }
+
+    #[test]
+    fn any_threshold_of_shares_recovers_the_secret() {
+        let secret: Vec<u8> = (0..32).collect();
+        let coefficients: Vec<u8> = (0..64).map(|i| (i * 37 + 11) as u8).collect();
+        let shares: Vec<Vec<u8>> = (1..=5)
+            .map(|x| {
+                let mut out = vec![0u8; secret.len()];
+                share(&secret, &coefficients, x, &mut out);
+                out
+            })
+            .collect();
+
+        let mut recovered = vec![0u8; secret.len()];
+        for a in 0..5 {
+            for b in a + 1..5 {
+                for c in b + 1..5 {
+                    let picked = [a, b, c].map(|i| (i as u8 + 1, &shares[i][..]));
+                    combine(&picked, &mut recovered);
+                    assert_eq!(recovered, secret);
+                }
+                // Two of a 3-of-5 sharing give something else
+                combine(
+                    &[(a as u8 + 1, &shares[a]), (b as u8 + 1, &shares[b])],
+                    &mut recovered,
+                );
+                assert_ne!(recovered, secret);
+            }
+        }
+    }
+}
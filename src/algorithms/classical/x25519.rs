@@ -0,0 +1,220 @@
+//! X25519 Diffie-Hellman (RFC 7748).
+//!
+//! Constant-time Montgomery ladder over Curve25519. Used as the classical
+//! half of hybrid key exchange and exposed through `citadel::kex`.
+
+use super::curve25519::FieldElement;
+use crate::consts;
+use crate::errors::{CryptoError, Result};
+use crate::internal::entropy;
+use crate::internal::traits::KeyAgreement;
+use crate::memory::SensitiveBytes;
+use crate::r#unsafe::memory::zeroize_words;
+
+/// Public key (u-coordinate) size in bytes.
+pub(crate) const PUBLIC_KEY_SIZE: usize = consts::X25519::PUBLIC_KEY_SIZE;
+/// Secret scalar size in bytes.
+pub(crate) const SECRET_KEY_SIZE: usize = consts::X25519::SECRET_KEY_SIZE;
+/// Shared secret size in bytes.
+pub(crate) const SHARED_SECRET_SIZE: usize = consts::X25519::SHARED_SECRET_SIZE;
+
+/// The base point, u = 9.
+const BASE_POINT: [u8; 32] = {
+    let mut u = [0u8; 32];
+    u[0] = 9;
+    u
+};
+
+/// (A - 2) / 4 for Curve25519.
+const A24: u32 = 121_665;
+
+/// The X25519 function: scalar multiplication of `u` by the clamped `k`.
+pub(crate) fn scalar_mult(k: &[u8; 32], u: &[u8; 32]) -> [u8; 32] {
+    let mut scalar = SensitiveBytes::new(*k);
+    let scalar = scalar.as_bytes_mut();
+    scalar[0] &= 248;
+    scalar[31] &= 127;
+    scalar[31] |= 64;
+
+    let x1 = FieldElement::from_bytes(u);
+    let mut x2 = FieldElement::ONE;
+    let mut z2 = FieldElement::ZERO;
+    let mut x3 = x1;
+    let mut z3 = FieldElement::ONE;
+    let mut swap = 0u8;
+
+    for t in (0..255).rev() {
+        let bit = (scalar[t / 8] >> (t % 8)) & 1;
+        swap ^= bit;
+        FieldElement::conditional_swap(&mut x2, &mut x3, swap);
+        FieldElement::conditional_swap(&mut z2, &mut z3, swap);
+        swap = bit;
+
+        let a = x2 + z2;
+        let aa = a.square();
+        let b = x2 - z2;
+        let bb = b.square();
+        let e = aa - bb;
+        let c = x3 + z3;
+        let d = x3 - z3;
+        let da = d * a;
+        let cb = c * b;
+        x3 = (da + cb).square();
+        z3 = x1 * (da - cb).square();
+        x2 = aa * bb;
+        z2 = e * (aa + e.mul_small(A24));
+    }
+    FieldElement::conditional_swap(&mut x2, &mut x3, swap);
+    FieldElement::conditional_swap(&mut z2, &mut z3, swap);
+
+    let out = (x2 * z2.invert()).to_bytes();
+    for element in [&mut x2, &mut z2, &mut x3, &mut z3] {
+        // SAFETY: each limb array is an owned, exclusively borrowed local
+        unsafe { zeroize_words(&mut element.0) };
+    }
+    out
+}
+
+/// X25519 over raw byte arrays.
+pub(crate) struct X25519;
+
+impl X25519 {
+    /// Public key for `secret_key`.
+    pub(crate) fn public_key(secret_key: &[u8; SECRET_KEY_SIZE]) -> [u8; PUBLIC_KEY_SIZE] {
+        scalar_mult(secret_key, &BASE_POINT)
+    }
+
+    /// Shared secret between `secret_key` and `peer_public_key`.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::KeyEncapsulationFailed`: If the result is all zeros,
+    ///   i.e. the peer key is a low-order point
+    pub(crate) fn diffie_hellman(
+        secret_key: &[u8; SECRET_KEY_SIZE],
+        peer_public_key: &[u8; PUBLIC_KEY_SIZE],
+    ) -> Result<SensitiveBytes<SHARED_SECRET_SIZE>> {
+        let shared = SensitiveBytes::new(scalar_mult(secret_key, peer_public_key));
+        let nonzero = shared.as_bytes().iter().fold(0u8, |acc, &byte| acc | byte);
+        if nonzero == 0 {
+            return Err(CryptoError::KeyEncapsulationFailed.into());
+        }
+        Ok(shared)
+    }
+}
+
+impl KeyAgreement<PUBLIC_KEY_SIZE, SECRET_KEY_SIZE, SHARED_SECRET_SIZE> for X25519 {
+    fn generate_keypair(&self) -> Result<([u8; PUBLIC_KEY_SIZE], [u8; SECRET_KEY_SIZE])> {
+        let mut secret_key = SensitiveBytes::<SECRET_KEY_SIZE>::zeroed();
+        entropy::fill(secret_key.as_bytes_mut())?;
+        Ok((
+            Self::public_key(secret_key.as_bytes()),
+            secret_key.into_inner(),
+        ))
+    }
+
+    fn public_key(&self, secret_key: &[u8; SECRET_KEY_SIZE]) -> Result<[u8; PUBLIC_KEY_SIZE]> {
+        Ok(Self::public_key(secret_key))
+    }
+
+    fn agree(
+        &self,
+        secret_key: &[u8; SECRET_KEY_SIZE],
+        peer_public_key: &[u8; PUBLIC_KEY_SIZE],
+    ) -> Result<[u8; SHARED_SECRET_SIZE]> {
+        Ok(Self::diffie_hellman(secret_key, peer_public_key)?.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unhex(hex: &str) -> [u8; 32] {
+        let bytes: Vec<u8> = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect();
+        bytes.try_into().unwrap()
+    }
+
+    #[test]
+    fn rfc7748_scalar_mult_vectors() {
+        let cases = [
+            (
+                "a546e36bf0527c9d3b16154b82465edd62144c0ac1fc5a18506a2244ba449ac4",
+                "e6db6867583030db3594c1a424b15f7c726624ec26b3353b10a903a6d0ab1c4c",
+                "c3da55379de9c6908e94ea4df28d084f32eccf03491c71f754b4075577a28552",
+            ),
+            (
+                "4b66e9d4d1b4673c5ad22691957d6af5c11b6421e0ea01d42ca4169e7918ba0d",
+                "e5210f12786811d3f4b7959d0538ae2c31dbe7106fc03c3efc4cd549c715a493",
+                "95cbde9476e8907d7aade45cb4b873f88b595a68799fa152e6f8f7647aac7957",
+            ),
+        ];
+        for (k, u, expected) in cases {
+            assert_eq!(scalar_mult(&unhex(k), &unhex(u)), unhex(expected));
+        }
+    }
+
+    #[test]
+    fn rfc7748_iterated() {
+        let mut k = BASE_POINT;
+        let mut u = BASE_POINT;
+        for i in 1..=1000 {
+            let result = scalar_mult(&k, &u);
+            u = k;
+            k = result;
+            if i == 1 {
+                assert_eq!(
+                    k,
+                    unhex("422c8e7a6227d7bca1350b3e2bb7279f7897b87bb6854b783c60e80311ae3079")
+                );
+            }
+        }
+        assert_eq!(
+            k,
+            unhex("684cf59ba83309552800ef566f2f4d3c1c3887c49360e3875f2eb94d99532c51")
+        );
+    }
+
+    #[test]
+    fn rfc7748_diffie_hellman() {
+        let alice = unhex("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a");
+        let bob = unhex("5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb");
+        let alice_public = X25519::public_key(&alice);
+        let bob_public = X25519::public_key(&bob);
+        assert_eq!(
+            alice_public,
+            unhex("8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a")
+        );
+        assert_eq!(
+            bob_public,
+            unhex("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f")
+        );
+        let expected = unhex("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742");
+        let kex = X25519;
+        assert_eq!(kex.agree(&alice, &bob_public).unwrap(), expected);
+        assert_eq!(kex.agree(&bob, &alice_public).unwrap(), expected);
+    }
+
+    #[test]
+    fn rejects_low_order_points() {
+        let secret = [0x42u8; 32];
+        assert!(X25519::diffie_hellman(&secret, &[0u8; 32]).is_err());
+        let mut one = [0u8; 32];
+        one[0] = 1;
+        assert!(X25519::diffie_hellman(&secret, &one).is_err());
+    }
+
+    #[test]
+    fn dh_kem_round_trip() {
+        use crate::internal::traits::{DhKem, KeyEncapsulation};
+
+        let kem = DhKem(X25519);
+        let (pk, sk) = kem.generate_keypair().unwrap();
+        let (ct, ss) = kem.encapsulate(&pk).unwrap();
+        assert_eq!(kem.decapsulate(&sk, &ct).unwrap(), ss);
+        assert!(kem.decapsulate(&sk, &[0u8; 32]).is_err());
+    }
+}
@@ -0,0 +1,2 @@
+pub(crate) mod classical;
+pub(crate) mod pq;
@@ -0,0 +1,978 @@
+//! ML-DSA-87 (FIPS 204, security category 5).
+//!
+//! Module-lattice signatures with k = 8, l = 7, η = 2, τ = 60, γ1 = 2^19,
+//! γ2 = (q - 1) / 32 and ω = 75. Implements the internal algorithms
+//! (`KeyGen_internal`, `Sign_internal`, `Verify_internal`) with
+//! caller-supplied randomness, the pure and pre-hash (HashML-DSA) message
+//! encodings with a context string, and the internal `SignatureScheme`
+//! trait over raw byte arrays. The typed public API lives in `citadel::sig`.
+//!
+//! # Side Channels
+//!
+//! - Arithmetic uses Montgomery reduction; no division by `q` and no
+//!   secret-indexed table lookups
+//! - The number of signing iterations is independent of the secret key
+//!   (FIPS 204 §3.4); rejected candidates are discarded, so the early exit
+//!   in the norm checks only reveals data about thrown-away values
+//! - Matrix and challenge sampling branch on rejection over public data
+
+use crate::algorithms::classical::keccak::{self, SHAKE128_RATE, SHAKE256_RATE};
+use crate::consts;
+#[cfg(feature = "signing")]
+use crate::errors::MisuseError;
+use crate::errors::{CryptoError, Result};
+#[cfg(feature = "signing")]
+use crate::internal::entropy;
+#[cfg(feature = "signing")]
+use crate::internal::traits::SignatureScheme;
+use crate::internal::traits::SignatureVerifier;
+use crate::memory::{SensitiveBytes, constant_time_eq};
+use crate::r#unsafe::memory::zeroize_words;
+
+/// Public (verification) key size in bytes.
+pub(crate) const PUBLIC_KEY_SIZE: usize = consts::MlDsa87::PUBLIC_KEY_SIZE;
+/// Secret (signing) key size in bytes.
+pub(crate) const SECRET_KEY_SIZE: usize = consts::MlDsa87::SECRET_KEY_SIZE;
+/// Signature size in bytes.
+pub(crate) const SIGNATURE_SIZE: usize = consts::MlDsa87::SIGNATURE_SIZE;
+/// Key generation seed size in bytes (ξ).
+pub(crate) const SEED_SIZE: usize = consts::MlDsa87::SEED_SIZE;
+/// Longest context string accepted by the pure ML-DSA encoding.
+pub(crate) const MAX_CONTEXT_SIZE: usize = 255;
+
+const N: usize = 256;
+const Q: i32 = 8380417;
+/// q^-1 mod 2^32
+const QINV: i32 = 58728449;
+/// mont^2 / 256, scales the inverse NTT output
+const INVNTT_SCALE: i32 = 41978;
+/// Dropped bits of t
+const D: u32 = 13;
+const K: usize = 8;
+const L: usize = 7;
+const ETA: i32 = 2;
+const TAU: usize = 60;
+const BETA: i32 = TAU as i32 * ETA;
+const GAMMA1: i32 = 1 << 19;
+const GAMMA2: i32 = (Q - 1) / 32;
+const OMEGA: usize = 75;
+
+const SEED_BYTES: usize = 32;
+const TR_BYTES: usize = 64;
+const CTILDE_BYTES: usize = 64;
+
+const T1_BITS: u32 = 10;
+const T0_BITS: u32 = D;
+const ETA_BITS: u32 = 3;
+const Z_BITS: u32 = 20;
+const W1_BITS: u32 = 4;
+
+const fn packed(bits: u32) -> usize {
+    N * bits as usize / 8
+}
+
+const T1_BYTES: usize = packed(T1_BITS);
+const T0_BYTES: usize = packed(T0_BITS);
+const ETA_BYTES: usize = packed(ETA_BITS);
+const Z_BYTES: usize = packed(Z_BITS);
+const W1_BYTES: usize = packed(W1_BITS);
+
+const _: () = {
+    assert!(PUBLIC_KEY_SIZE == SEED_BYTES + K * T1_BYTES);
+    assert!(SECRET_KEY_SIZE == 2 * SEED_BYTES + TR_BYTES + (K + L) * ETA_BYTES + K * T0_BYTES);
+    assert!(SIGNATURE_SIZE == CTILDE_BYTES + L * Z_BYTES + OMEGA + K);
+};
+
+/// Powers of 1753 in bit-reversed order, in the Montgomery domain.
+const ZETAS: [i32; N] = [
+    0, 25847, -2608894, -518909, 237124, -777960, -876248, 466468, 1826347, 2353451, -359251,
+    -2091905, 3119733, -2884855, 3111497, 2680103, 2725464, 1024112, -1079900, 3585928, -549488,
+    -1119584, 2619752, -2108549, -2118186, -3859737, -1399561, -3277672, 1757237, -19422, 4010497,
+    280005, 2706023, 95776, 3077325, 3530437, -1661693, -3592148, -2537516, 3915439, -3861115,
+    -3043716, 3574422, -2867647, 3539968, -300467, 2348700, -539299, -1699267, -1643818, 3505694,
+    -3821735, 3507263, -2140649, -1600420, 3699596, 811944, 531354, 954230, 3881043, 3900724,
+    -2556880, 2071892, -2797779, -3930395, -1528703, -3677745, -3041255, -1452451, 3475950,
+    2176455, -1585221, -1257611, 1939314, -4083598, -1000202, -3190144, -3157330, -3632928, 126922,
+    3412210, -983419, 2147896, 2715295, -2967645, -3693493, -411027, -2477047, -671102, -1228525,
+    -22981, -1308169, -381987, 1349076, 1852771, -1430430, -3343383, 264944, 508951, 3097992,
+    44288, -1100098, 904516, 3958618, -3724342, -8578, 1653064, -3249728, 2389356, -210977, 759969,
+    -1316856, 189548, -3553272, 3159746, -1851402, -2409325, -177440, 1315589, 1341330, 1285669,
+    -1584928, -812732, -1439742, -3019102, -3881060, -3628969, 3839961, 2091667, 3407706, 2316500,
+    3817976, -3342478, 2244091, -2446433, -3562462, 266997, 2434439, -1235728, 3513181, -3520352,
+    -3759364, -1197226, -3193378, 900702, 1859098, 909542, 819034, 495491, -1613174, -43260,
+    -522500, -655327, -3122442, 2031748, 3207046, -3556995, -525098, -768622, -3595838, 342297,
+    286988, -2437823, 4108315, 3437287, -3342277, 1735879, 203044, 2842341, 2691481, -2590150,
+    1265009, 4055324, 1247620, 2486353, 1595974, -3767016, 1250494, 2635921, -3548272, -2994039,
+    1869119, 1903435, -1050970, -1333058, 1237275, -3318210, -1430225, -451100, 1312455, 3306115,
+    -1962642, -1279661, 1917081, -2546312, -1374803, 1500165, 777191, 2235880, 3406031, -542412,
+    -2831860, -1671176, -1846953, -2584293, -3724270, 594136, -3776993, -2013608, 2432395, 2454455,
+    -164721, 1957272, 3369112, 185531, -1207385, -3183426, 162844, 1616392, 3014001, 810149,
+    1652634, -3694233, -1799107, -3038916, 3523897, 3866901, 269760, 2213111, -975884, 1717735,
+    472078, -426683, 1723600, -1803090, 1910376, -1667432, -1104333, -260646, -3833893, -2939036,
+    -2235985, -420899, -2286327, 183443, -976891, 1612842, -3545687, -554416, 3919660, -48306,
+    -1362209, 3937738, 1400424, -846154, 1976782,
+];
+
+#[inline]
+fn montgomery_reduce(a: i64) -> i32 {
+    let t = (a as i32).wrapping_mul(QINV);
+    ((a - t as i64 * Q as i64) >> 32) as i32
+}
+
+/// Representative of `a` mod q in `(-6283009, 6283007]`.
+#[inline]
+fn reduce32(a: i32) -> i32 {
+    let t = (a + (1 << 22)) >> 23;
+    a - t * Q
+}
+
+/// Add q if `a` is negative, without branching.
+#[inline]
+fn caddq(a: i32) -> i32 {
+    a + ((a >> 31) & Q)
+}
+
+/// Power2Round: `(a1, a0)` with `a = a1 * 2^d + a0`, for `a` in `[0, q)`.
+#[cfg(feature = "signing")]
+#[inline]
+fn power2round(a: i32) -> (i32, i32) {
+    let a1 = (a + (1 << (D - 1)) - 1) >> D;
+    (a1, a - (a1 << D))
+}
+
+/// Decompose: `(a1, a0)` with `a = a1 * 2γ2 + a0`, for `a` in `[0, q)`.
+#[inline]
+fn decompose(a: i32) -> (i32, i32) {
+    let mut a1 = (a + 127) >> 7;
+    a1 = (a1 * 1025 + (1 << 21)) >> 22;
+    a1 &= 15;
+    let mut a0 = a - a1 * 2 * GAMMA2;
+    a0 -= (((Q - 1) / 2 - a0) >> 31) & Q;
+    (a1, a0)
+}
+
+/// MakeHint, given the low bits `a0` of `w - cs2 + ct0` and `a1 = w1`.
+#[cfg(feature = "signing")]
+#[inline]
+fn make_hint(a0: i32, a1: i32) -> bool {
+    !(-GAMMA2..=GAMMA2).contains(&a0) || (a0 == -GAMMA2 && a1 != 0)
+}
+
+/// UseHint for `a` in `[0, q)`.
+#[inline]
+fn use_hint(a: i32, hint: bool) -> i32 {
+    let (a1, a0) = decompose(a);
+    match (hint, a0 > 0) {
+        (false, _) => a1,
+        (true, true) => (a1 + 1) & 15,
+        (true, false) => (a1 - 1) & 15,
+    }
+}
+
+/// A polynomial in `Z_q[X]/(X^256 + 1)`, zeroized on drop.
+#[derive(Clone)]
+struct Poly([i32; N]);
+
+impl Poly {
+    const fn zero() -> Self {
+        Self([0; N])
+    }
+
+    fn ntt(&mut self) {
+        let r = &mut self.0;
+        let mut k = 0;
+        let mut len = 128;
+        while len > 0 {
+            for start in (0..N).step_by(2 * len) {
+                k += 1;
+                let zeta = ZETAS[k] as i64;
+                for j in start..start + len {
+                    let t = montgomery_reduce(zeta * r[j + len] as i64);
+                    r[j + len] = r[j] - t;
+                    r[j] += t;
+                }
+            }
+            len >>= 1;
+        }
+    }
+
+    /// Inverse NTT; also multiplies by the Montgomery factor.
+    fn inv_ntt(&mut self) {
+        let r = &mut self.0;
+        let mut k = N;
+        let mut len = 1;
+        while len < N {
+            for start in (0..N).step_by(2 * len) {
+                k -= 1;
+                let zeta = -ZETAS[k] as i64;
+                for j in start..start + len {
+                    let t = r[j];
+                    r[j] = t + r[j + len];
+                    r[j + len] = montgomery_reduce(zeta * (t - r[j + len]) as i64);
+                }
+            }
+            len <<= 1;
+        }
+        for coeff in r.iter_mut() {
+            *coeff = montgomery_reduce(INVNTT_SCALE as i64 * *coeff as i64);
+        }
+    }
+
+    /// Accumulate `a ∘ b` (NTT-domain product, Montgomery-scaled) into self.
+    fn add_pointwise(&mut self, a: &Poly, b: &Poly) {
+        for ((acc, x), y) in self.0.iter_mut().zip(a.0.iter()).zip(b.0.iter()) {
+            *acc += montgomery_reduce(*x as i64 * *y as i64);
+        }
+    }
+
+    fn pointwise(a: &Poly, b: &Poly) -> Self {
+        let mut product = Self::zero();
+        product.add_pointwise(a, b);
+        product
+    }
+
+    #[cfg(feature = "signing")]
+    fn add(&mut self, other: &Poly) {
+        for (a, b) in self.0.iter_mut().zip(other.0.iter()) {
+            *a += b;
+        }
+    }
+
+    fn sub(&mut self, other: &Poly) {
+        for (a, b) in self.0.iter_mut().zip(other.0.iter()) {
+            *a -= b;
+        }
+    }
+
+    fn reduce(&mut self) {
+        for coeff in self.0.iter_mut() {
+            *coeff = reduce32(*coeff);
+        }
+    }
+
+    fn caddq(&mut self) {
+        for coeff in self.0.iter_mut() {
+            *coeff = caddq(*coeff);
+        }
+    }
+
+    fn shift_left(&mut self) {
+        for coeff in self.0.iter_mut() {
+            *coeff <<= D;
+        }
+    }
+
+    /// Whether any coefficient has absolute value at least `bound`.
+    /// Coefficients must be reduced.
+    fn exceeds(&self, bound: i32) -> bool {
+        self.0.iter().any(|&coeff| {
+            let abs = coeff - ((coeff >> 31) & (2 * coeff));
+            abs >= bound
+        })
+    }
+
+    /// Pack `map(coeff)` as `bits`-bit little-endian fields.
+    fn pack(&self, bits: u32, map: impl Fn(i32) -> u32, out: &mut [u8]) {
+        debug_assert_eq!(out.len(), packed(bits));
+        let mut accumulator = 0u64;
+        let mut filled = 0;
+        let mut bytes = out.iter_mut();
+        for &coeff in self.0.iter() {
+            accumulator |= (map(coeff) as u64) << filled;
+            filled += bits;
+            while filled >= 8 {
+                if let Some(byte) = bytes.next() {
+                    *byte = accumulator as u8;
+                }
+                accumulator >>= 8;
+                filled -= 8;
+            }
+        }
+    }
+
+    /// Inverse of [`pack`](Self::pack).
+    fn unpack(bytes: &[u8], bits: u32, map: impl Fn(u32) -> i32) -> Self {
+        debug_assert_eq!(bytes.len(), packed(bits));
+        let mut poly = Self::zero();
+        let mut accumulator = 0u64;
+        let mut filled = 0;
+        let mut input = bytes.iter();
+        for coeff in poly.0.iter_mut() {
+            while filled < bits {
+                accumulator |= (*input.next().unwrap_or(&0) as u64) << filled;
+                filled += 8;
+            }
+            *coeff = map((accumulator & ((1 << bits) - 1)) as u32);
+            accumulator >>= bits;
+            filled -= bits;
+        }
+        poly
+    }
+
+    /// RejNTTPoly(ρ || s || r), with `nonce = r * 256 + s`.
+    fn uniform(rho: &[u8; SEED_BYTES], nonce: u16) -> Self {
+        let mut xof = keccak::shake128();
+        xof.absorb(rho);
+        xof.absorb(&nonce.to_le_bytes());
+
+        let mut poly = Self::zero();
+        let mut filled = 0;
+        let mut block = [0u8; SHAKE128_RATE];
+        while filled < N {
+            xof.squeeze(&mut block);
+            for chunk in block.chunks_exact(3) {
+                let candidate =
+                    chunk[0] as i32 | (chunk[1] as i32) << 8 | ((chunk[2] & 0x7f) as i32) << 16;
+                if candidate < Q && filled < N {
+                    poly.0[filled] = candidate;
+                    filled += 1;
+                }
+            }
+        }
+        poly
+    }
+
+    /// RejBoundedPoly(ρ' || nonce) for η = 2.
+    #[cfg(feature = "signing")]
+    fn uniform_eta(seed: &[u8; 64], nonce: u16) -> Self {
+        let mut xof = keccak::shake256();
+        xof.absorb(seed);
+        xof.absorb(&nonce.to_le_bytes());
+
+        let mut poly = Self::zero();
+        let mut filled = 0;
+        let mut block = SensitiveBytes::<SHAKE256_RATE>::zeroed();
+        while filled < N {
+            xof.squeeze(block.as_bytes_mut());
+            for &byte in block.as_bytes().iter() {
+                for half in [(byte & 0x0f) as i32, (byte >> 4) as i32] {
+                    if half < 15 && filled < N {
+                        // half mod 5, without division
+                        poly.0[filled] = ETA - (half - ((205 * half) >> 10) * 5);
+                        filled += 1;
+                    }
+                }
+            }
+        }
+        poly
+    }
+
+    /// ExpandMask component: BitUnpack(H(ρ'' || nonce), γ1 - 1, γ1).
+    #[cfg(feature = "signing")]
+    fn uniform_gamma1(seed: &[u8; 64], nonce: u16) -> Self {
+        let mut buf = SensitiveBytes::<Z_BYTES>::zeroed();
+        keccak::shake256_into(&[seed, &nonce.to_le_bytes()], buf.as_bytes_mut());
+        Self::unpack(buf.as_bytes(), Z_BITS, |v| GAMMA1 - v as i32)
+    }
+
+    /// SampleInBall(c̃): τ coefficients of ±1, the rest zero.
+    fn challenge(c_tilde: &[u8; CTILDE_BYTES]) -> Self {
+        let mut xof = keccak::shake256();
+        xof.absorb(c_tilde);
+        let mut block = [0u8; SHAKE256_RATE];
+        xof.squeeze(&mut block);
+
+        let mut signs = u64::from_le_bytes(block[..8].try_into().expect("8 bytes"));
+        let mut position = 8;
+        let mut poly = Self::zero();
+        for i in N - TAU..N {
+            let j = loop {
+                if position == SHAKE256_RATE {
+                    xof.squeeze(&mut block);
+                    position = 0;
+                }
+                let j = block[position] as usize;
+                position += 1;
+                if j <= i {
+                    break j;
+                }
+            };
+            poly.0[i] = poly.0[j];
+            poly.0[j] = 1 - 2 * (signs & 1) as i32;
+            signs >>= 1;
+        }
+        poly
+    }
+}
+
+impl Drop for Poly {
+    fn drop(&mut self) {
+        // SAFETY: `self.0` is an owned, exclusively borrowed array
+        unsafe { zeroize_words(&mut self.0) };
+    }
+}
+
+type Matrix = [[Poly; L]; K];
+
+/// ExpandA(ρ), in the NTT domain.
+fn expand_a(rho: &[u8; SEED_BYTES]) -> Matrix {
+    core::array::from_fn(|i| core::array::from_fn(|j| Poly::uniform(rho, ((i << 8) | j) as u16)))
+}
+
+/// `Â ∘ v` for `v` in the NTT domain, unreduced.
+fn matrix_mul(a: &Matrix, v: &[Poly; L]) -> [Poly; K] {
+    core::array::from_fn(|i| {
+        let mut acc = Poly::zero();
+        for (x, y) in a[i].iter().zip(v.iter()) {
+            acc.add_pointwise(x, y);
+        }
+        acc
+    })
+}
+
+fn ntt_all<const M: usize>(v: &[Poly; M]) -> [Poly; M] {
+    core::array::from_fn(|i| {
+        let mut poly = v[i].clone();
+        poly.ntt();
+        poly
+    })
+}
+
+/// What a signature covers: the message itself (ML-DSA) or its digest
+/// under the hash identified by `oid` (HashML-DSA, FIPS 204 §5.4).
+#[derive(Clone, Copy)]
+pub(crate) enum Message<'a> {
+    Pure(&'a [u8]),
+    PreHash {
+        /// DER encoding of the hash function's object identifier.
+        oid: &'a [u8],
+        digest: &'a [u8],
+    },
+}
+
+/// `μ = H(tr || M', 64)`, where `M'` is `0 || |ctx| || ctx || M` for pure
+/// ML-DSA and `1 || |ctx| || ctx || OID || PH(M)` for HashML-DSA.
+fn message_representative(tr: &[u8], context: &[u8], message: Message<'_>) -> SensitiveBytes<64> {
+    debug_assert!(context.len() <= MAX_CONTEXT_SIZE);
+    let (domain, oid, message) = match message {
+        Message::Pure(message) => (0, &[][..], message),
+        Message::PreHash { oid, digest } => (1, oid, digest),
+    };
+    let mut mu = SensitiveBytes::<64>::zeroed();
+    keccak::shake256_into(
+        &[tr, &[domain, context.len() as u8], context, oid, message],
+        mu.as_bytes_mut(),
+    );
+    mu
+}
+
+/// `c̃ = H(μ || w1Encode(w1), λ/4)`.
+fn commitment_hash(mu: &[u8; 64], w1: &[Poly; K]) -> [u8; CTILDE_BYTES] {
+    let mut encoded = [0u8; K * W1_BYTES];
+    for (poly, chunk) in w1.iter().zip(encoded.chunks_exact_mut(W1_BYTES)) {
+        poly.pack(W1_BITS, |c| c as u32, chunk);
+    }
+    let mut c_tilde = [0u8; CTILDE_BYTES];
+    keccak::shake256_into(&[mu, &encoded], &mut c_tilde);
+    c_tilde
+}
+
+/// HintBitUnpack; `None` if the encoding is malformed.
+fn unpack_hints(bytes: &[u8]) -> Option<[[bool; N]; K]> {
+    let mut hints = [[false; N]; K];
+    let mut index = 0;
+    for (i, row) in hints.iter_mut().enumerate() {
+        let end = bytes[OMEGA + i] as usize;
+        if end < index || end > OMEGA {
+            return None;
+        }
+        let first = index;
+        while index < end {
+            if index > first && bytes[index - 1] >= bytes[index] {
+                return None;
+            }
+            row[bytes[index] as usize] = true;
+            index += 1;
+        }
+    }
+    bytes[index..OMEGA].iter().all(|&b| b == 0).then_some(hints)
+}
+
+/// ML-DSA-87 over raw byte arrays.
+pub(crate) struct MlDsa87;
+
+impl MlDsa87 {
+    /// ML-DSA.KeyGen_internal(ξ).
+    #[cfg(feature = "signing")]
+    pub(crate) fn keypair_from_seed(
+        seed: &[u8; SEED_SIZE],
+    ) -> ([u8; PUBLIC_KEY_SIZE], SensitiveBytes<SECRET_KEY_SIZE>) {
+        let mut seeds = SensitiveBytes::<{ 2 * SEED_BYTES + 64 }>::zeroed();
+        keccak::shake256_into(&[seed, &[K as u8, L as u8]], seeds.as_bytes_mut());
+        let (rho, rest) = seeds.as_bytes().split_at(SEED_BYTES);
+        let (rho_prime, key) = rest.split_at(64);
+        let rho: [u8; SEED_BYTES] = rho.try_into().expect("split at SEED_BYTES");
+        let rho_prime: &[u8; 64] = rho_prime.try_into().expect("split at 64");
+
+        let s1: [Poly; L] = core::array::from_fn(|i| Poly::uniform_eta(rho_prime, i as u16));
+        let s2: [Poly; K] = core::array::from_fn(|i| Poly::uniform_eta(rho_prime, (L + i) as u16));
+
+        let mut t = matrix_mul(&expand_a(&rho), &ntt_all(&s1));
+        let mut t1 = core::array::from_fn::<Poly, K, _>(|_| Poly::zero());
+        let mut t0 = core::array::from_fn::<Poly, K, _>(|_| Poly::zero());
+        for i in 0..K {
+            t[i].reduce();
+            t[i].inv_ntt();
+            t[i].add(&s2[i]);
+            t[i].caddq();
+            for j in 0..N {
+                (t1[i].0[j], t0[i].0[j]) = power2round(t[i].0[j]);
+            }
+        }
+
+        let mut pk = [0u8; PUBLIC_KEY_SIZE];
+        let (pk_rho, pk_t1) = pk.split_at_mut(SEED_BYTES);
+        pk_rho.copy_from_slice(&rho);
+        for (poly, chunk) in t1.iter().zip(pk_t1.chunks_exact_mut(T1_BYTES)) {
+            poly.pack(T1_BITS, |c| c as u32, chunk);
+        }
+
+        let mut sk = SensitiveBytes::<SECRET_KEY_SIZE>::zeroed();
+        let bytes = sk.as_bytes_mut();
+        let (sk_rho, rest) = bytes.split_at_mut(SEED_BYTES);
+        let (sk_key, rest) = rest.split_at_mut(SEED_BYTES);
+        let (sk_tr, rest) = rest.split_at_mut(TR_BYTES);
+        let (sk_s, sk_t0) = rest.split_at_mut((K + L) * ETA_BYTES);
+        sk_rho.copy_from_slice(&rho);
+        sk_key.copy_from_slice(key);
+        keccak::shake256_into(&[&pk], sk_tr);
+        for (poly, chunk) in s1
+            .iter()
+            .chain(s2.iter())
+            .zip(sk_s.chunks_exact_mut(ETA_BYTES))
+        {
+            poly.pack(ETA_BITS, |c| (ETA - c) as u32, chunk);
+        }
+        for (poly, chunk) in t0.iter().zip(sk_t0.chunks_exact_mut(T0_BYTES)) {
+            poly.pack(T0_BITS, |c| ((1 << (D - 1)) - c) as u32, chunk);
+        }
+        (pk, sk)
+    }
+
+    /// Pure ML-DSA signing with caller-supplied randomness `rnd`.
+    ///
+    /// An all-zero `rnd` gives the deterministic variant. Otherwise `rnd`
+    /// MUST be fresh and uniformly random; use the `SignatureScheme` impl.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::ContextTooLong`: If `context` exceeds 255 bytes
+    /// - `MisuseError::InvalidEncoding`: If the secret key fails
+    ///   [`is_valid_secret_key`](Self::is_valid_secret_key)
+    #[cfg(feature = "signing")]
+    pub(crate) fn sign_with(
+        secret_key: &[u8; SECRET_KEY_SIZE],
+        message: &[u8],
+        context: &[u8],
+        rnd: &[u8; 32],
+    ) -> Result<[u8; SIGNATURE_SIZE]> {
+        Self::sign_message(secret_key, Message::Pure(message), context, rnd)
+    }
+
+    /// [`sign_with`](Self::sign_with) for either message encoding.
+    #[cfg(feature = "signing")]
+    pub(crate) fn sign_message(
+        secret_key: &[u8; SECRET_KEY_SIZE],
+        message: Message<'_>,
+        context: &[u8],
+        rnd: &[u8; 32],
+    ) -> Result<[u8; SIGNATURE_SIZE]> {
+        if context.len() > MAX_CONTEXT_SIZE {
+            return Err(MisuseError::ContextTooLong.into());
+        }
+        if !Self::is_valid_secret_key(secret_key) {
+            return Err(MisuseError::InvalidEncoding.into());
+        }
+
+        let (rho, rest) = secret_key.split_at(SEED_BYTES);
+        let (key, rest) = rest.split_at(SEED_BYTES);
+        let (tr, rest) = rest.split_at(TR_BYTES);
+        let (s_bytes, t0_bytes) = rest.split_at((K + L) * ETA_BYTES);
+        let rho: &[u8; SEED_BYTES] = rho.try_into().expect("split at SEED_BYTES");
+
+        let unpack_eta = |i: usize| {
+            let mut poly = Poly::unpack(&s_bytes[i * ETA_BYTES..][..ETA_BYTES], ETA_BITS, |v| {
+                ETA - v as i32
+            });
+            poly.ntt();
+            poly
+        };
+        let s1_hat: [Poly; L] = core::array::from_fn(unpack_eta);
+        let s2_hat: [Poly; K] = core::array::from_fn(|i| unpack_eta(L + i));
+        let t0_hat: [Poly; K] = core::array::from_fn(|i| {
+            let mut poly = Poly::unpack(&t0_bytes[i * T0_BYTES..][..T0_BYTES], T0_BITS, |v| {
+                (1 << (D - 1)) - v as i32
+            });
+            poly.ntt();
+            poly
+        });
+        let a = expand_a(rho);
+
+        let mu = message_representative(tr, context, message);
+        let mut rho_prime = SensitiveBytes::<64>::zeroed();
+        keccak::shake256_into(&[key, rnd, mu.as_bytes()], rho_prime.as_bytes_mut());
+
+        let mut kappa = 0u16;
+        loop {
+            let y: [Poly; L] = core::array::from_fn(|r| {
+                Poly::uniform_gamma1(rho_prime.as_bytes(), kappa.wrapping_add(r as u16))
+            });
+            kappa = kappa.wrapping_add(L as u16);
+
+            let mut w = matrix_mul(&a, &ntt_all(&y));
+            let mut w1 = core::array::from_fn::<Poly, K, _>(|_| Poly::zero());
+            let mut w0 = core::array::from_fn::<Poly, K, _>(|_| Poly::zero());
+            for i in 0..K {
+                w[i].reduce();
+                w[i].inv_ntt();
+                w[i].caddq();
+                for j in 0..N {
+                    (w1[i].0[j], w0[i].0[j]) = decompose(w[i].0[j]);
+                }
+            }
+
+            let c_tilde = commitment_hash(mu.as_bytes(), &w1);
+            let mut c = Poly::challenge(&c_tilde);
+            c.ntt();
+
+            let z: [Poly; L] = core::array::from_fn(|i| {
+                let mut poly = Poly::pointwise(&c, &s1_hat[i]);
+                poly.inv_ntt();
+                poly.add(&y[i]);
+                poly.reduce();
+                poly
+            });
+            if z.iter().any(|poly| poly.exceeds(GAMMA1 - BETA)) {
+                continue;
+            }
+
+            let mut rejected = false;
+            for (w0, s2) in w0.iter_mut().zip(s2_hat.iter()) {
+                let mut cs2 = Poly::pointwise(&c, s2);
+                cs2.inv_ntt();
+                w0.sub(&cs2);
+                w0.reduce();
+                rejected |= w0.exceeds(GAMMA2 - BETA);
+            }
+            if rejected {
+                continue;
+            }
+
+            for (w0, t0) in w0.iter_mut().zip(t0_hat.iter()) {
+                let mut ct0 = Poly::pointwise(&c, t0);
+                ct0.inv_ntt();
+                ct0.reduce();
+                rejected |= ct0.exceeds(GAMMA2);
+                w0.add(&ct0);
+            }
+            if rejected {
+                continue;
+            }
+
+            let mut hints = [0u8; OMEGA + K];
+            let mut count = 0;
+            for i in 0..K {
+                for j in 0..N {
+                    if make_hint(w0[i].0[j], w1[i].0[j]) {
+                        if count < OMEGA {
+                            hints[count] = j as u8;
+                        }
+                        count += 1;
+                    }
+                }
+                hints[OMEGA + i] = count.min(OMEGA + 1) as u8;
+            }
+            if count > OMEGA {
+                continue;
+            }
+
+            let mut signature = [0u8; SIGNATURE_SIZE];
+            let (sig_c, rest) = signature.split_at_mut(CTILDE_BYTES);
+            let (sig_z, sig_h) = rest.split_at_mut(L * Z_BYTES);
+            sig_c.copy_from_slice(&c_tilde);
+            for (poly, chunk) in z.iter().zip(sig_z.chunks_exact_mut(Z_BYTES)) {
+                poly.pack(Z_BITS, |c| (GAMMA1 - c) as u32, chunk);
+            }
+            sig_h.copy_from_slice(&hints);
+            return Ok(signature);
+        }
+    }
+
+    /// Pure ML-DSA verification. Returns `false` for any invalid signature,
+    /// including malformed encodings and over-long contexts.
+    pub(crate) fn verify_with(
+        public_key: &[u8; PUBLIC_KEY_SIZE],
+        message: &[u8],
+        context: &[u8],
+        signature: &[u8; SIGNATURE_SIZE],
+    ) -> bool {
+        Self::verify_message(public_key, Message::Pure(message), context, signature)
+    }
+
+    /// [`verify_with`](Self::verify_with) for either message encoding.
+    pub(crate) fn verify_message(
+        public_key: &[u8; PUBLIC_KEY_SIZE],
+        message: Message<'_>,
+        context: &[u8],
+        signature: &[u8; SIGNATURE_SIZE],
+    ) -> bool {
+        if context.len() > MAX_CONTEXT_SIZE {
+            return false;
+        }
+
+        let (c_tilde, rest) = signature.split_at(CTILDE_BYTES);
+        let (z_bytes, h_bytes) = rest.split_at(L * Z_BYTES);
+        let c_tilde: &[u8; CTILDE_BYTES] = c_tilde.try_into().expect("split at CTILDE_BYTES");
+        let Some(hints) = unpack_hints(h_bytes) else {
+            return false;
+        };
+        let z: [Poly; L] = core::array::from_fn(|i| {
+            Poly::unpack(&z_bytes[i * Z_BYTES..][..Z_BYTES], Z_BITS, |v| {
+                GAMMA1 - v as i32
+            })
+        });
+        if z.iter().any(|poly| poly.exceeds(GAMMA1 - BETA)) {
+            return false;
+        }
+
+        let (rho, t1_bytes) = public_key.split_at(SEED_BYTES);
+        let rho: &[u8; SEED_BYTES] = rho.try_into().expect("split at SEED_BYTES");
+        let mut tr = [0u8; TR_BYTES];
+        keccak::shake256_into(&[public_key], &mut tr);
+        let mu = message_representative(&tr, context, message);
+
+        let mut c = Poly::challenge(c_tilde);
+        c.ntt();
+
+        let mut w = matrix_mul(&expand_a(rho), &ntt_all(&z));
+        for (i, w) in w.iter_mut().enumerate() {
+            let mut t1 = Poly::unpack(&t1_bytes[i * T1_BYTES..][..T1_BYTES], T1_BITS, |v| v as i32);
+            t1.shift_left();
+            t1.ntt();
+            w.sub(&Poly::pointwise(&c, &t1));
+            w.reduce();
+            w.inv_ntt();
+            w.caddq();
+            for (coeff, &hint) in w.0.iter_mut().zip(hints[i].iter()) {
+                *coeff = use_hint(*coeff, hint);
+            }
+        }
+
+        constant_time_eq(&commitment_hash(mu.as_bytes(), &w), c_tilde)
+    }
+
+    /// Whether the `s1` and `s2` encodings in `secret_key` are in range.
+    ///
+    /// FIPS 204 does not require this check; it rejects keys that would
+    /// make signing misbehave rather than proving the key is well formed.
+    #[cfg(feature = "signing")]
+    pub(crate) fn is_valid_secret_key(secret_key: &[u8]) -> bool {
+        if secret_key.len() != SECRET_KEY_SIZE {
+            return false;
+        }
+        let start = 2 * SEED_BYTES + TR_BYTES;
+        let s_bytes = &secret_key[start..start + (K + L) * ETA_BYTES];
+        let mut valid = true;
+        for chunk in s_bytes.chunks_exact(ETA_BYTES) {
+            let poly = Poly::unpack(chunk, ETA_BITS, |v| v as i32);
+            valid &= poly.0.iter().all(|&v| v <= 2 * ETA);
+        }
+        valid
+    }
+}
+
+impl SignatureVerifier<PUBLIC_KEY_SIZE, SIGNATURE_SIZE> for MlDsa87 {
+    fn verify(
+        &self,
+        public_key: &[u8; PUBLIC_KEY_SIZE],
+        message: &[u8],
+        signature: &[u8; SIGNATURE_SIZE],
+    ) -> Result<()> {
+        if !Self::verify_with(public_key, message, &[], signature) {
+            return Err(CryptoError::VerificationFailed.into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "signing")]
+impl SignatureScheme<PUBLIC_KEY_SIZE, SECRET_KEY_SIZE, SIGNATURE_SIZE> for MlDsa87 {
+    fn generate_keypair(&self) -> Result<([u8; PUBLIC_KEY_SIZE], [u8; SECRET_KEY_SIZE])> {
+        let mut seed = SensitiveBytes::<SEED_SIZE>::zeroed();
+        entropy::fill(seed.as_bytes_mut())?;
+        let (pk, sk) = Self::keypair_from_seed(seed.as_bytes());
+        Ok((pk, sk.into_inner()))
+    }
+
+    fn sign(
+        &self,
+        secret_key: &[u8; SECRET_KEY_SIZE],
+        message: &[u8],
+    ) -> Result<[u8; SIGNATURE_SIZE]> {
+        let mut rnd = SensitiveBytes::<32>::zeroed();
+        entropy::fill(rnd.as_bytes_mut())?;
+        Self::sign_with(secret_key, message, &[], rnd.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VECTORS: &str = include_str!("../../../tests/vectors/ml_dsa_87.txt");
+
+    fn field(record: &str, name: &str) -> Vec<u8> {
+        let hex = record
+            .lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix(" = "))
+            .unwrap_or_else(|| panic!("missing field {name}"));
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn records() -> impl Iterator<Item = &'static str> {
+        VECTORS
+            .split("\n\n")
+            .filter(|record| record.contains("count = "))
+    }
+
+    #[test]
+    fn reductions_match_naive() {
+        for a in [-(1i64 << 40), -12345, 0, 1, 12345, 1 << 40] {
+            let reduced = montgomery_reduce(a) as i64;
+            assert_eq!(
+                (reduced << 32) - a,
+                ((reduced << 32) - a) / Q as i64 * Q as i64
+            );
+        }
+        for a in [i32::MIN / 2, -Q, -1, 0, 1, Q, i32::MAX / 2] {
+            assert_eq!((reduce32(a) - a).rem_euclid(Q), 0);
+        }
+        for a in [0, 1, GAMMA2, GAMMA2 + 1, Q / 2, Q - GAMMA2, Q - 1] {
+            let (a1, a0) = decompose(a);
+            assert_eq!((a1 * 2 * GAMMA2 + a0).rem_euclid(Q), a);
+            assert!(a0.abs() <= GAMMA2);
+            #[cfg(feature = "signing")]
+            {
+                let (a1, a0) = power2round(a);
+                assert_eq!((a1 << D) + a0, a);
+            }
+        }
+    }
+
+    #[test]
+    fn ntt_round_trip() {
+        let mut poly = Poly::zero();
+        for (i, coeff) in poly.0.iter_mut().enumerate() {
+            *coeff = (i as i32 * 7919) % Q;
+        }
+        let original = poly.clone();
+        poly.ntt();
+        poly.reduce();
+        poly.inv_ntt();
+        // inv_ntt leaves a Montgomery factor of 2^32
+        for (a, b) in poly.0.iter().zip(original.0.iter()) {
+            let lhs = (*a as i64).rem_euclid(Q as i64);
+            let rhs = ((*b as i64) << 32).rem_euclid(Q as i64);
+            assert_eq!(lhs, rhs);
+        }
+    }
+
+    #[cfg(feature = "signing")]
+    #[test]
+    fn fips204_keygen_vectors() {
+        for record in records() {
+            let seed: [u8; SEED_SIZE] = field(record, "seed").try_into().unwrap();
+            let (pk, sk) = MlDsa87::keypair_from_seed(&seed);
+            assert_eq!(pk.as_slice(), field(record, "pk"));
+            assert_eq!(sk.as_bytes().as_slice(), field(record, "sk"));
+        }
+    }
+
+    #[test]
+    fn fips204_signature_vectors() {
+        for record in records() {
+            let pk: [u8; PUBLIC_KEY_SIZE] = field(record, "pk").try_into().unwrap();
+            let signature: [u8; SIGNATURE_SIZE] = field(record, "sig").try_into().unwrap();
+            let (message, context) = (field(record, "msg"), field(record, "ctx"));
+
+            #[cfg(feature = "signing")]
+            {
+                let sk: [u8; SECRET_KEY_SIZE] = field(record, "sk").try_into().unwrap();
+                let rnd: [u8; 32] = field(record, "rnd").try_into().unwrap();
+                let signed = MlDsa87::sign_with(&sk, &message, &context, &rnd).unwrap();
+                assert!(signed == signature);
+            }
+            assert!(MlDsa87::verify_with(&pk, &message, &context, &signature));
+            assert!(!MlDsa87::verify_with(&pk, &message, b"other", &signature));
+        }
+    }
+
+    #[cfg(feature = "signing")]
+    #[test]
+    fn hash_ml_dsa_vector() {
+        // OpenSSL 3.5, deterministic signing with `message-encoding:0` over
+        // `M' = 1 || |ctx| || ctx || OID(SHA3-512) || SHA3-512("abc")`
+        let (pk, sk) = MlDsa87::keypair_from_seed(&core::array::from_fn(|i| i as u8));
+        let oid = [
+            0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x0a,
+        ];
+        let digest = keccak::sha3_512(&[b"abc"]);
+        let message = Message::PreHash {
+            oid: &oid,
+            digest: &digest,
+        };
+        let signature =
+            MlDsa87::sign_message(sk.as_bytes(), message, b"citadel", &[0; 32]).unwrap();
+        let fingerprint = crate::algorithms::classical::sha256::Sha256::digest(&signature);
+        assert_eq!(
+            fingerprint
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect::<String>(),
+            "6e72c9a00bb3928c84ef6d9075463fddd96bdd234beaaff6a91cabaea7b3e856"
+        );
+        assert!(MlDsa87::verify_message(
+            &pk, message, b"citadel", &signature
+        ));
+        // Neither the pure encoding of the digest nor another context verifies
+        assert!(!MlDsa87::verify_with(&pk, &digest, b"citadel", &signature));
+        assert!(!MlDsa87::verify_message(&pk, message, b"", &signature));
+    }
+
+    #[cfg(feature = "signing")]
+    #[test]
+    fn rejects_tampering() {
+        let (pk, sk) = MlDsa87::keypair_from_seed(&[7u8; 32]);
+        let signature = MlDsa87.sign(sk.as_bytes(), b"message").unwrap();
+        assert!(MlDsa87.verify(&pk, b"message", &signature).is_ok());
+        assert!(MlDsa87.verify(&pk, b"massage", &signature).is_err());
+
+        let mut tampered = signature;
+        tampered[CTILDE_BYTES + 5] ^= 1;
+        assert!(MlDsa87.verify(&pk, b"message", &tampered).is_err());
+
+        // Hint indices must be strictly increasing
+        let mut tampered = signature;
+        tampered[SIGNATURE_SIZE - K - OMEGA] = 0xff;
+        tampered[SIGNATURE_SIZE - K] = OMEGA as u8 + 1;
+        assert!(unpack_hints(&tampered[SIGNATURE_SIZE - K - OMEGA..]).is_none());
+        assert!(MlDsa87.verify(&pk, b"message", &tampered).is_err());
+
+        let context = [0u8; MAX_CONTEXT_SIZE + 1];
+        assert!(MlDsa87::sign_with(sk.as_bytes(), b"m", &context, &[0; 32]).is_err());
+    }
+
+    #[cfg(feature = "signing")]
+    #[test]
+    fn secret_key_check_rejects_out_of_range_coefficients() {
+        let (_, sk) = MlDsa87::keypair_from_seed(&[7u8; 32]);
+        assert!(MlDsa87::is_valid_secret_key(sk.as_bytes()));
+        let mut sk = sk.into_inner();
+        // First s1 coefficient becomes 7 > 2η
+        sk[2 * SEED_BYTES + TR_BYTES] |= 0x07;
+        assert!(!MlDsa87::is_valid_secret_key(&sk));
+    }
+}
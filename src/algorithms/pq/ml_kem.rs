@@ -0,0 +1,605 @@
+//! ML-KEM (FIPS 203) core, generic over the parameter set.
+//!
+//! Implements K-PKE and the internal ML-KEM algorithms
+//! (`KeyGen_internal`, `Encaps_internal`, `Decaps_internal`). Randomness is
+//! supplied by the caller so the same code path serves both production use
+//! and known-answer tests.
+//!
+//! # Side Channels
+//!
+//! - Arithmetic uses Montgomery and Barrett reduction; no division by `q`
+//!   and no secret-indexed table lookups
+//! - Decapsulation always re-encrypts and selects between the real and the
+//!   implicit-rejection key without branching on the comparison result
+//! - Matrix sampling branches on rejection, but only over public data
+//!
+//! # Bounds
+//!
+//! Coefficient bounds follow the reference implementation, so no `i16`
+//! arithmetic overflows for any (including malformed) key or ciphertext.
+
+use crate::algorithms::classical::keccak::{self, SHAKE128_RATE};
+use crate::memory::{SensitiveBytes, constant_time_eq, constant_time_select};
+use crate::r#unsafe::memory::zeroize_words;
+
+const N: usize = 256;
+const Q: i16 = 3329;
+/// q^-1 mod 2^16
+const QINV: i16 = -3327;
+/// 2^32 mod q, converts to the Montgomery domain
+const MONT_SQUARED: i16 = 1353;
+/// mont^2 / 128, scales the inverse NTT output
+const INVNTT_SCALE: i16 = 1441;
+/// ceil(2^36 / q): `(x * COMPRESS_MUL) >> 36 == x / q` for `x < 2^23`
+const COMPRESS_MUL: u64 = 20642679;
+
+/// Powers of 17 in bit-reversed order, in the Montgomery domain.
+const ZETAS: [i16; 128] = [
+    -1044, -758, -359, -1517, 1493, 1422, 287, 202, -171, 622, 1577, 182, 962, -1202, -1474, 1468,
+    573, -1325, 264, 383, -829, 1458, -1602, -130, -681, 1017, 732, 608, -1542, 411, -205, -1571,
+    1223, 652, -552, 1015, -1293, 1491, -282, -1544, 516, -8, -320, -666, -1618, -1162, 126, 1469,
+    -853, -90, -271, 830, 107, -1421, -247, -951, -398, 961, -1508, -725, 448, -1065, 677, -1275,
+    -1103, 430, 555, 843, -1251, 871, 1550, 105, 422, 587, 177, -235, -291, -460, 1574, 1653, -246,
+    778, 1159, -147, -777, 1483, -602, 1119, -1590, 644, -872, 349, 418, 329, -156, -75, 817, 1097,
+    603, 610, 1322, -1285, -1465, 384, -1215, -136, 1218, -1335, -874, 220, -1187, -1659, -1185,
+    -1530, -1278, 794, -1510, -854, -870, 478, -108, -308, 996, 991, 958, -1460, 1522, 1628,
+];
+
+/// Size of an encoded polynomial with 12-bit coefficients.
+const POLY_BYTES: usize = 384;
+/// Size of seeds, messages and shared keys.
+const SYMBYTES: usize = 32;
+/// Largest supported CBD parameter (ML-KEM-512 uses 3).
+const MAX_ETA: usize = 3;
+
+#[inline]
+fn montgomery_reduce(a: i32) -> i16 {
+    let t = (a as i16).wrapping_mul(QINV);
+    ((a - (t as i32) * (Q as i32)) >> 16) as i16
+}
+
+/// Centered representative of `a` mod q.
+#[inline]
+fn barrett_reduce(a: i16) -> i16 {
+    const V: i32 = ((1 << 26) + (Q as i32) / 2) / (Q as i32);
+    let t = ((V * a as i32 + (1 << 25)) >> 26) as i16;
+    a.wrapping_sub(t.wrapping_mul(Q))
+}
+
+#[inline]
+fn fqmul(a: i16, b: i16) -> i16 {
+    montgomery_reduce(a as i32 * b as i32)
+}
+
+/// Map a centered representative into `[0, q)` without branching.
+#[inline]
+fn to_unsigned(a: i16) -> u16 {
+    (a + ((a >> 15) & Q)) as u16
+}
+
+#[inline]
+fn compress(x: u16, d: u32) -> u16 {
+    let numerator = ((x as u64) << d) + (Q as u64 / 2);
+    (((numerator * COMPRESS_MUL) >> 36) & ((1 << d) - 1)) as u16
+}
+
+#[inline]
+fn decompress(y: u16, d: u32) -> i16 {
+    ((y as u32 * Q as u32 + (1 << (d - 1))) >> d) as i16
+}
+
+/// A polynomial in `Z_q[X]/(X^256 + 1)`, zeroized on drop.
+#[derive(Clone)]
+struct Poly([i16; N]);
+
+impl Poly {
+    const fn zero() -> Self {
+        Self([0; N])
+    }
+
+    fn ntt(&mut self) {
+        let r = &mut self.0;
+        let mut k = 1;
+        let mut len = 128;
+        while len >= 2 {
+            for start in (0..N).step_by(2 * len) {
+                let zeta = ZETAS[k];
+                k += 1;
+                for j in start..start + len {
+                    let t = fqmul(zeta, r[j + len]);
+                    r[j + len] = r[j] - t;
+                    r[j] += t;
+                }
+            }
+            len >>= 1;
+        }
+        self.reduce();
+    }
+
+    /// Inverse NTT; also multiplies by the Montgomery factor.
+    fn inv_ntt(&mut self) {
+        let r = &mut self.0;
+        let mut k = 127;
+        let mut len = 2;
+        while len <= 128 {
+            for start in (0..N).step_by(2 * len) {
+                let zeta = ZETAS[k];
+                k -= 1;
+                for j in start..start + len {
+                    let t = r[j];
+                    r[j] = barrett_reduce(t + r[j + len]);
+                    r[j + len] -= t;
+                    r[j + len] = fqmul(zeta, r[j + len]);
+                }
+            }
+            len <<= 1;
+        }
+        for coeff in r.iter_mut() {
+            *coeff = fqmul(*coeff, INVNTT_SCALE);
+        }
+    }
+
+    /// Accumulate `a ∘ b` (NTT-domain product, Montgomery-scaled) into self.
+    fn add_basemul(&mut self, a: &Poly, b: &Poly) {
+        for i in 0..N / 4 {
+            let zeta = ZETAS[64 + i];
+            for (offset, zeta) in [(4 * i, zeta), (4 * i + 2, -zeta)] {
+                let (a0, a1) = (a.0[offset], a.0[offset + 1]);
+                let (b0, b1) = (b.0[offset], b.0[offset + 1]);
+                self.0[offset] += fqmul(fqmul(a1, b1), zeta) + fqmul(a0, b0);
+                self.0[offset + 1] += fqmul(a0, b1) + fqmul(a1, b0);
+            }
+        }
+    }
+
+    fn convert_to_mont(&mut self) {
+        for coeff in self.0.iter_mut() {
+            *coeff = montgomery_reduce(*coeff as i32 * MONT_SQUARED as i32);
+        }
+    }
+
+    fn add(&mut self, other: &Poly) {
+        for (a, b) in self.0.iter_mut().zip(other.0.iter()) {
+            *a += b;
+        }
+    }
+
+    fn sub_from(&mut self, minuend: &Poly) {
+        for (a, b) in self.0.iter_mut().zip(minuend.0.iter()) {
+            *a = b - *a;
+        }
+    }
+
+    fn reduce(&mut self) {
+        for coeff in self.0.iter_mut() {
+            *coeff = barrett_reduce(*coeff);
+        }
+    }
+
+    /// ByteEncode_12 of the canonical coefficients.
+    fn to_bytes(&self, out: &mut [u8]) {
+        for (pair, bytes) in self.0.chunks_exact(2).zip(out.chunks_exact_mut(3)) {
+            let t0 = to_unsigned(barrett_reduce(pair[0]));
+            let t1 = to_unsigned(barrett_reduce(pair[1]));
+            bytes[0] = t0 as u8;
+            bytes[1] = ((t0 >> 8) | (t1 << 4)) as u8;
+            bytes[2] = (t1 >> 4) as u8;
+        }
+    }
+
+    /// ByteDecode_12. Coefficients may be up to 4095; see `is_canonical`.
+    fn from_bytes(bytes: &[u8]) -> Self {
+        let mut poly = Self::zero();
+        for (pair, b) in poly.0.chunks_exact_mut(2).zip(bytes.chunks_exact(3)) {
+            pair[0] = ((b[0] as u16 | ((b[1] as u16) << 8)) & 0xfff) as i16;
+            pair[1] = (((b[1] as u16) >> 4) | ((b[2] as u16) << 4)) as i16;
+        }
+        poly
+    }
+
+    fn is_canonical(&self) -> bool {
+        self.0.iter().all(|&coeff| coeff < Q)
+    }
+
+    fn compress_into(&self, d: u32, out: &mut [u8]) {
+        let mut accumulator = 0u32;
+        let mut bits = 0u32;
+        let mut bytes = out.iter_mut();
+        for &coeff in self.0.iter() {
+            accumulator |= (compress(to_unsigned(barrett_reduce(coeff)), d) as u32) << bits;
+            bits += d;
+            while bits >= 8 {
+                if let Some(byte) = bytes.next() {
+                    *byte = accumulator as u8;
+                }
+                accumulator >>= 8;
+                bits -= 8;
+            }
+        }
+    }
+
+    fn decompress_from(d: u32, bytes: &[u8]) -> Self {
+        let mut poly = Self::zero();
+        let mut accumulator = 0u32;
+        let mut bits = 0u32;
+        let mut input = bytes.iter();
+        for coeff in poly.0.iter_mut() {
+            while bits < d {
+                accumulator |= (*input.next().unwrap_or(&0) as u32) << bits;
+                bits += 8;
+            }
+            *coeff = decompress((accumulator & ((1 << d) - 1)) as u16, d);
+            accumulator >>= d;
+            bits -= d;
+        }
+        poly
+    }
+
+    /// Decompress_1(ByteDecode_1(msg))
+    fn from_message(msg: &[u8; SYMBYTES]) -> Self {
+        let mut poly = Self::zero();
+        for (i, coeff) in poly.0.iter_mut().enumerate() {
+            let bit = ((msg[i / 8] >> (i % 8)) & 1) as i16;
+            *coeff = (-bit) & ((Q + 1) / 2);
+        }
+        poly
+    }
+
+    /// ByteEncode_1(Compress_1(self))
+    fn to_message(&self) -> SensitiveBytes<SYMBYTES> {
+        let mut msg = SensitiveBytes::<SYMBYTES>::zeroed();
+        self.compress_into(1, msg.as_bytes_mut());
+        msg
+    }
+
+    /// SamplePolyCBD_eta(PRF_eta(seed, nonce))
+    fn sample_cbd(eta: usize, seed: &[u8; SYMBYTES], nonce: u8) -> Self {
+        let mut buf = SensitiveBytes::<{ 64 * MAX_ETA }>::zeroed();
+        let buf = &mut buf.as_bytes_mut()[..64 * eta];
+        keccak::shake256_into(&[seed, &[nonce]], buf);
+
+        let bit = |i: usize| ((buf[i / 8] >> (i % 8)) & 1) as i16;
+        let mut poly = Self::zero();
+        for (i, coeff) in poly.0.iter_mut().enumerate() {
+            let base = 2 * i * eta;
+            let mut x = 0;
+            let mut y = 0;
+            for j in 0..eta {
+                x += bit(base + j);
+                y += bit(base + eta + j);
+            }
+            *coeff = x - y;
+        }
+        poly
+    }
+
+    /// SampleNTT(XOF(rho || i || j))
+    fn sample_ntt(rho: &[u8; SYMBYTES], i: u8, j: u8) -> Self {
+        let mut xof = keccak::shake128();
+        xof.absorb(rho);
+        xof.absorb(&[i, j]);
+
+        let mut poly = Self::zero();
+        let mut filled = 0;
+        let mut block = [0u8; SHAKE128_RATE];
+        while filled < N {
+            xof.squeeze(&mut block);
+            for chunk in block.chunks_exact(3) {
+                let d1 = chunk[0] as u16 | ((chunk[1] as u16 & 0x0f) << 8);
+                let d2 = (chunk[1] as u16 >> 4) | ((chunk[2] as u16) << 4);
+                for candidate in [d1, d2] {
+                    if candidate < Q as u16 && filled < N {
+                        poly.0[filled] = candidate as i16;
+                        filled += 1;
+                    }
+                }
+            }
+        }
+        poly
+    }
+}
+
+impl Drop for Poly {
+    fn drop(&mut self) {
+        // SAFETY: `self.0` is an owned, exclusively borrowed array
+        unsafe { zeroize_words(&mut self.0) };
+    }
+}
+
+fn new_vec<const K: usize>() -> [Poly; K] {
+    core::array::from_fn(|_| Poly::zero())
+}
+
+/// `Σ a[i] ∘ b[i]` in the NTT domain, reduced.
+fn inner_product<const K: usize>(a: &[Poly; K], b: &[Poly; K]) -> Poly {
+    let mut acc = Poly::zero();
+    for (x, y) in a.iter().zip(b.iter()) {
+        acc.add_basemul(x, y);
+    }
+    acc.reduce();
+    acc
+}
+
+/// ML-KEM with module rank `K`, noise parameters `ETA1`/`ETA2` and
+/// compression parameters `DU`/`DV`.
+pub(crate) struct MlKem<
+    const K: usize,
+    const ETA1: usize,
+    const ETA2: usize,
+    const DU: u32,
+    const DV: u32,
+>;
+
+impl<const K: usize, const ETA1: usize, const ETA2: usize, const DU: u32, const DV: u32>
+    MlKem<K, ETA1, ETA2, DU, DV>
+{
+    /// Encapsulation (public) key size.
+    pub(crate) const ENCAPSULATION_KEY_SIZE: usize = POLY_BYTES * K + SYMBYTES;
+    /// Decapsulation (secret) key size.
+    pub(crate) const DECAPSULATION_KEY_SIZE: usize = 2 * POLY_BYTES * K + 3 * SYMBYTES;
+    /// Ciphertext size.
+    pub(crate) const CIPHERTEXT_SIZE: usize = 32 * (DU as usize * K + DV as usize);
+    /// Shared secret size.
+    pub(crate) const SHARED_SECRET_SIZE: usize = SYMBYTES;
+
+    const PKE_SECRET_KEY_SIZE: usize = POLY_BYTES * K;
+    const U_BYTES: usize = 32 * DU as usize;
+
+    /// ML-KEM.KeyGen_internal(d, z)
+    ///
+    /// `ek` and `dk` must be exactly `ENCAPSULATION_KEY_SIZE` and
+    /// `DECAPSULATION_KEY_SIZE` bytes.
+    pub(crate) fn keygen(d: &[u8; SYMBYTES], z: &[u8; SYMBYTES], ek: &mut [u8], dk: &mut [u8]) {
+        debug_assert_eq!(ek.len(), Self::ENCAPSULATION_KEY_SIZE);
+        debug_assert_eq!(dk.len(), Self::DECAPSULATION_KEY_SIZE);
+
+        let seeds = SensitiveBytes::new(keccak::sha3_512(&[d, &[K as u8]]));
+        let (rho, sigma) = split_seeds(seeds.as_bytes());
+
+        let mut s = new_vec::<K>();
+        let mut e = new_vec::<K>();
+        for (i, poly) in s.iter_mut().enumerate() {
+            *poly = Poly::sample_cbd(ETA1, &sigma, i as u8);
+            poly.ntt();
+        }
+        for (i, poly) in e.iter_mut().enumerate() {
+            *poly = Poly::sample_cbd(ETA1, &sigma, (K + i) as u8);
+            poly.ntt();
+        }
+
+        let (t_bytes, rho_bytes) = ek.split_at_mut(Self::PKE_SECRET_KEY_SIZE);
+        for (i, chunk) in t_bytes.chunks_exact_mut(POLY_BYTES).enumerate() {
+            let row: [Poly; K] = core::array::from_fn(|j| Poly::sample_ntt(&rho, j as u8, i as u8));
+            let mut t = inner_product(&row, &s);
+            t.convert_to_mont();
+            t.add(&e[i]);
+            t.reduce();
+            t.to_bytes(chunk);
+        }
+        rho_bytes.copy_from_slice(&rho);
+
+        let (dk_pke, rest) = dk.split_at_mut(Self::PKE_SECRET_KEY_SIZE);
+        for (poly, chunk) in s.iter().zip(dk_pke.chunks_exact_mut(POLY_BYTES)) {
+            poly.to_bytes(chunk);
+        }
+        let (dk_ek, rest) = rest.split_at_mut(Self::ENCAPSULATION_KEY_SIZE);
+        dk_ek.copy_from_slice(ek);
+        let (h, dk_z) = rest.split_at_mut(SYMBYTES);
+        h.copy_from_slice(&keccak::sha3_256(&[ek]));
+        dk_z.copy_from_slice(z);
+    }
+
+    /// ML-KEM.Encaps_internal(ek, m). Writes the ciphertext and returns the
+    /// shared secret.
+    ///
+    /// `ek` must have passed [`check_encapsulation_key`](Self::check_encapsulation_key).
+    pub(crate) fn encaps(ek: &[u8], m: &[u8; SYMBYTES], ct: &mut [u8]) -> SensitiveBytes<SYMBYTES> {
+        let h = keccak::sha3_256(&[ek]);
+        let kr = SensitiveBytes::new(keccak::sha3_512(&[m, &h]));
+        let (key, r) = split_seeds(kr.as_bytes());
+        Self::encrypt(ek, m, &r, ct);
+        SensitiveBytes::new(key)
+    }
+
+    /// ML-KEM.Decaps_internal(dk, c), additionally returning whether the
+    /// ciphertext was valid.
+    ///
+    /// Never fails: an invalid ciphertext yields the implicit-rejection key
+    /// `J(z || c)`. `dk` must have passed
+    /// [`check_decapsulation_key`](Self::check_decapsulation_key).
+    ///
+    /// The flag is secret-dependent: only aggregate it, never branch on it
+    /// in a way that is observable per call.
+    pub(crate) fn decaps_with_status(dk: &[u8], ct: &[u8]) -> (SensitiveBytes<SYMBYTES>, bool) {
+        debug_assert_eq!(dk.len(), Self::DECAPSULATION_KEY_SIZE);
+        debug_assert_eq!(ct.len(), Self::CIPHERTEXT_SIZE);
+
+        let (dk_pke, rest) = dk.split_at(Self::PKE_SECRET_KEY_SIZE);
+        let (ek, rest) = rest.split_at(Self::ENCAPSULATION_KEY_SIZE);
+        let (h, z) = rest.split_at(SYMBYTES);
+
+        let m = Self::decrypt(dk_pke, ct);
+        let kr = SensitiveBytes::new(keccak::sha3_512(&[m.as_bytes(), h]));
+        let (key, r) = split_seeds(kr.as_bytes());
+        let key = SensitiveBytes::new(key);
+        let r = SensitiveBytes::new(r);
+
+        let mut rejection = SensitiveBytes::<SYMBYTES>::zeroed();
+        keccak::shake256_into(&[z, ct], rejection.as_bytes_mut());
+
+        let mut reencrypted = [0u8; 32 * (11 * 4 + 5)];
+        let reencrypted = &mut reencrypted[..Self::CIPHERTEXT_SIZE];
+        Self::encrypt(ek, m.as_bytes(), r.as_bytes(), reencrypted);
+        let valid = constant_time_eq(ct, reencrypted);
+
+        let mut shared = SensitiveBytes::<SYMBYTES>::zeroed();
+        constant_time_select(
+            valid,
+            key.as_bytes(),
+            rejection.as_bytes(),
+            shared.as_bytes_mut(),
+        );
+        (shared, valid)
+    }
+
+    /// Modulus check (FIPS 203 §7.2): every encoded coefficient is below q.
+    pub(crate) fn check_encapsulation_key(ek: &[u8]) -> bool {
+        ek.len() == Self::ENCAPSULATION_KEY_SIZE
+            && ek[..Self::PKE_SECRET_KEY_SIZE]
+                .chunks_exact(POLY_BYTES)
+                .all(|chunk| Poly::from_bytes(chunk).is_canonical())
+    }
+
+    /// Hash check (FIPS 203 §7.3): the embedded `H(ek)` matches `ek`.
+    pub(crate) fn check_decapsulation_key(dk: &[u8]) -> bool {
+        if dk.len() != Self::DECAPSULATION_KEY_SIZE {
+            return false;
+        }
+        let ek_start = Self::PKE_SECRET_KEY_SIZE;
+        let h_start = ek_start + Self::ENCAPSULATION_KEY_SIZE;
+        let h = keccak::sha3_256(&[&dk[ek_start..h_start]]);
+        constant_time_eq(&h, &dk[h_start..h_start + SYMBYTES])
+    }
+
+    /// K-PKE.Encrypt(ek, m, r)
+    fn encrypt(ek: &[u8], m: &[u8; SYMBYTES], r: &[u8; SYMBYTES], ct: &mut [u8]) {
+        let (t_bytes, rho_bytes) = ek.split_at(Self::PKE_SECRET_KEY_SIZE);
+        let rho: [u8; SYMBYTES] = rho_bytes.try_into().unwrap_or([0u8; SYMBYTES]);
+        let t: [Poly; K] = core::array::from_fn(|i| Poly::from_bytes(&t_bytes[i * POLY_BYTES..]));
+
+        let mut y = new_vec::<K>();
+        for (i, poly) in y.iter_mut().enumerate() {
+            *poly = Poly::sample_cbd(ETA1, r, i as u8);
+            poly.ntt();
+        }
+
+        let (u_bytes, v_bytes) = ct.split_at_mut(Self::U_BYTES * K);
+        for (i, chunk) in u_bytes.chunks_exact_mut(Self::U_BYTES).enumerate() {
+            // Column i of A, i.e. row i of A^T
+            let column: [Poly; K] =
+                core::array::from_fn(|j| Poly::sample_ntt(&rho, i as u8, j as u8));
+            let mut u = inner_product(&column, &y);
+            u.inv_ntt();
+            u.add(&Poly::sample_cbd(ETA2, r, (K + i) as u8));
+            u.reduce();
+            u.compress_into(DU, chunk);
+        }
+
+        let mut v = inner_product(&t, &y);
+        v.inv_ntt();
+        v.add(&Poly::sample_cbd(ETA2, r, (2 * K) as u8));
+        v.add(&Poly::from_message(m));
+        v.reduce();
+        v.compress_into(DV, v_bytes);
+    }
+
+    /// K-PKE.Decrypt(dk_pke, c)
+    fn decrypt(dk_pke: &[u8], ct: &[u8]) -> SensitiveBytes<SYMBYTES> {
+        let (u_bytes, v_bytes) = ct.split_at(Self::U_BYTES * K);
+        let u: [Poly; K] = core::array::from_fn(|i| {
+            let mut poly = Poly::decompress_from(DU, &u_bytes[i * Self::U_BYTES..]);
+            poly.ntt();
+            poly
+        });
+        let s: [Poly; K] = core::array::from_fn(|i| Poly::from_bytes(&dk_pke[i * POLY_BYTES..]));
+
+        let mut w = inner_product(&s, &u);
+        w.inv_ntt();
+        w.sub_from(&Poly::decompress_from(DV, v_bytes));
+        w.reduce();
+        w.to_message()
+    }
+}
+
+/// Split a 64-byte `G` output into its two 32-byte halves.
+fn split_seeds(seeds: &[u8; 2 * SYMBYTES]) -> ([u8; SYMBYTES], [u8; SYMBYTES]) {
+    let mut first = [0u8; SYMBYTES];
+    let mut second = [0u8; SYMBYTES];
+    first.copy_from_slice(&seeds[..SYMBYTES]);
+    second.copy_from_slice(&seeds[SYMBYTES..]);
+    (first, second)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type MlKem768 = MlKem<3, 2, 2, 10, 4>;
+
+    #[test]
+    fn reductions_match_naive() {
+        for a in [-32768i32, -3329, -1, 0, 1, 1664, 3328, 3329, 32767] {
+            let reduced = barrett_reduce(a as i16) as i32;
+            assert_eq!((reduced - a).rem_euclid(Q as i32), 0);
+            assert!(reduced.abs() <= (Q as i32) / 2);
+        }
+        // montgomery_reduce(a) * 2^16 == a (mod q)
+        for a in [-(1 << 20), -12345, 0, 1, 12345, 1 << 20] {
+            let reduced = montgomery_reduce(a) as i64;
+            assert_eq!((reduced * 65536 - a as i64).rem_euclid(Q as i64), 0);
+        }
+    }
+
+    #[test]
+    fn compress_matches_definition() {
+        for d in [1, 4, 5, 10, 11] {
+            for x in 0..Q as u16 {
+                let expected = ((((x as u64) << d) * 2 + Q as u64) / (2 * Q as u64)) % (1 << d);
+                assert_eq!(compress(x, d) as u64, expected, "x={x} d={d}");
+            }
+        }
+    }
+
+    #[test]
+    fn ntt_round_trip() {
+        let mut poly = Poly::zero();
+        for (i, coeff) in poly.0.iter_mut().enumerate() {
+            *coeff = (i as i16 * 13) % Q;
+        }
+        let original = poly.clone();
+        poly.ntt();
+        poly.inv_ntt();
+        // inv_ntt leaves a Montgomery factor of 2^16
+        for (a, b) in poly.0.iter().zip(original.0.iter()) {
+            let lhs = (*a as i64).rem_euclid(Q as i64);
+            let rhs = (*b as i64 * 65536).rem_euclid(Q as i64);
+            assert_eq!(lhs, rhs);
+        }
+    }
+
+    #[test]
+    fn round_trip_and_implicit_rejection() {
+        let mut ek = vec![0u8; MlKem768::ENCAPSULATION_KEY_SIZE];
+        let mut dk = vec![0u8; MlKem768::DECAPSULATION_KEY_SIZE];
+        MlKem768::keygen(&[1u8; 32], &[2u8; 32], &mut ek, &mut dk);
+        assert!(MlKem768::check_encapsulation_key(&ek));
+        assert!(MlKem768::check_decapsulation_key(&dk));
+
+        let mut ct = vec![0u8; MlKem768::CIPHERTEXT_SIZE];
+        let shared = MlKem768::encaps(&ek, &[3u8; 32], &mut ct);
+        let (decapsulated, valid) = MlKem768::decaps_with_status(&dk, &ct);
+        assert!(valid);
+        assert_eq!(shared.as_bytes(), decapsulated.as_bytes());
+
+        ct[0] ^= 1;
+        let (rejected, valid) = MlKem768::decaps_with_status(&dk, &ct);
+        assert!(!valid);
+        assert_ne!(shared.as_bytes(), rejected.as_bytes());
+    }
+
+    #[test]
+    fn key_checks_reject_malformed_keys() {
+        let mut ek = vec![0u8; MlKem768::ENCAPSULATION_KEY_SIZE];
+        let mut dk = vec![0u8; MlKem768::DECAPSULATION_KEY_SIZE];
+        MlKem768::keygen(&[1u8; 32], &[2u8; 32], &mut ek, &mut dk);
+
+        // Coefficient 0 becomes 0xfff >= q
+        ek[0] = 0xff;
+        ek[1] |= 0x0f;
+        assert!(!MlKem768::check_encapsulation_key(&ek));
+
+        let last_h = MlKem768::DECAPSULATION_KEY_SIZE - SYMBYTES - 1;
+        dk[last_h] ^= 1;
+        assert!(!MlKem768::check_decapsulation_key(&dk));
+    }
+}
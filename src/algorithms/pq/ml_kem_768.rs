@@ -0,0 +1,177 @@
+//! ML-KEM-768 parameter set (FIPS 203, security category 3).
+//!
+//! Binds the generic core in `ml_kem` to k = 3, η1 = η2 = 2, du = 10,
+//! dv = 4 and implements the internal `KeyEncapsulation` trait over raw
+//! byte arrays. The typed public API lives in `citadel::kem`.
+
+use super::ml_kem::MlKem;
+use crate::consts;
+use crate::errors::{MisuseError, Result};
+use crate::internal::entropy;
+use crate::internal::traits::KeyEncapsulation;
+use crate::memory::SensitiveBytes;
+
+type Params = MlKem<3, 2, 2, 10, 4>;
+
+/// Encapsulation key size in bytes.
+pub(crate) const PUBLIC_KEY_SIZE: usize = consts::MlKem768::PUBLIC_KEY_SIZE;
+/// Decapsulation key size in bytes.
+pub(crate) const SECRET_KEY_SIZE: usize = consts::MlKem768::SECRET_KEY_SIZE;
+/// Ciphertext size in bytes.
+pub(crate) const CIPHERTEXT_SIZE: usize = consts::MlKem768::CIPHERTEXT_SIZE;
+/// Shared secret size in bytes.
+pub(crate) const SHARED_SECRET_SIZE: usize = consts::MlKem768::SHARED_SECRET_SIZE;
+/// Key generation seed size in bytes (`d || z`).
+pub(crate) const SEED_SIZE: usize = consts::MlKem768::SEED_SIZE;
+
+const _: () = {
+    assert!(Params::ENCAPSULATION_KEY_SIZE == PUBLIC_KEY_SIZE);
+    assert!(Params::DECAPSULATION_KEY_SIZE == SECRET_KEY_SIZE);
+    assert!(Params::CIPHERTEXT_SIZE == CIPHERTEXT_SIZE);
+    assert!(Params::SHARED_SECRET_SIZE == SHARED_SECRET_SIZE);
+};
+
+/// ML-KEM-768 over raw byte arrays.
+pub(crate) struct MlKem768;
+
+impl MlKem768 {
+    /// Deterministic key generation from `seed = d || z`.
+    pub(crate) fn keypair_from_seed(
+        seed: &[u8; SEED_SIZE],
+    ) -> ([u8; PUBLIC_KEY_SIZE], SensitiveBytes<SECRET_KEY_SIZE>) {
+        let mut d = SensitiveBytes::<32>::zeroed();
+        let mut z = SensitiveBytes::<32>::zeroed();
+        d.as_bytes_mut().copy_from_slice(&seed[..32]);
+        z.as_bytes_mut().copy_from_slice(&seed[32..]);
+
+        let mut ek = [0u8; PUBLIC_KEY_SIZE];
+        let mut dk = SensitiveBytes::<SECRET_KEY_SIZE>::zeroed();
+        Params::keygen(d.as_bytes(), z.as_bytes(), &mut ek, dk.as_bytes_mut());
+        (ek, dk)
+    }
+
+    /// Deterministic encapsulation with caller-supplied randomness `m`.
+    ///
+    /// Only for known-answer tests and derandomized constructions; `m` MUST
+    /// be fresh and uniformly random otherwise.
+    pub(crate) fn encapsulate_with(
+        public_key: &[u8; PUBLIC_KEY_SIZE],
+        m: &[u8; 32],
+    ) -> Result<([u8; CIPHERTEXT_SIZE], SensitiveBytes<SHARED_SECRET_SIZE>)> {
+        if !Self::is_valid_public_key(public_key) {
+            return Err(MisuseError::InvalidEncoding.into());
+        }
+        let mut ct = [0u8; CIPHERTEXT_SIZE];
+        let shared = Params::encaps(public_key, m, &mut ct);
+        Ok((ct, shared))
+    }
+
+    /// Decapsulate, also reporting whether the implicit-rejection path was
+    /// taken. See [`MlKem::decaps_with_status`] for how to treat the flag.
+    pub(crate) fn decapsulate_with_status(
+        secret_key: &[u8; SECRET_KEY_SIZE],
+        ciphertext: &[u8; CIPHERTEXT_SIZE],
+    ) -> (SensitiveBytes<SHARED_SECRET_SIZE>, bool) {
+        Params::decaps_with_status(secret_key, ciphertext)
+    }
+
+    /// FIPS 203 encapsulation key check.
+    pub(crate) fn is_valid_public_key(public_key: &[u8]) -> bool {
+        Params::check_encapsulation_key(public_key)
+    }
+
+    /// FIPS 203 decapsulation key check.
+    pub(crate) fn is_valid_secret_key(secret_key: &[u8]) -> bool {
+        Params::check_decapsulation_key(secret_key)
+    }
+}
+
+impl KeyEncapsulation<PUBLIC_KEY_SIZE, SECRET_KEY_SIZE, CIPHERTEXT_SIZE, SHARED_SECRET_SIZE>
+    for MlKem768
+{
+    fn generate_keypair(&self) -> Result<([u8; PUBLIC_KEY_SIZE], [u8; SECRET_KEY_SIZE])> {
+        let mut seed = SensitiveBytes::<SEED_SIZE>::zeroed();
+        entropy::fill(seed.as_bytes_mut())?;
+        let (ek, dk) = Self::keypair_from_seed(seed.as_bytes());
+        Ok((ek, dk.into_inner()))
+    }
+
+    fn encapsulate(
+        &self,
+        public_key: &[u8; PUBLIC_KEY_SIZE],
+    ) -> Result<([u8; CIPHERTEXT_SIZE], [u8; SHARED_SECRET_SIZE])> {
+        let mut m = SensitiveBytes::<32>::zeroed();
+        entropy::fill(m.as_bytes_mut())?;
+        let (ct, shared) = Self::encapsulate_with(public_key, m.as_bytes())?;
+        Ok((ct, shared.into_inner()))
+    }
+
+    fn decapsulate(
+        &self,
+        secret_key: &[u8; SECRET_KEY_SIZE],
+        ciphertext: &[u8; CIPHERTEXT_SIZE],
+    ) -> Result<[u8; SHARED_SECRET_SIZE]> {
+        if !Self::is_valid_secret_key(secret_key) {
+            return Err(MisuseError::InvalidEncoding.into());
+        }
+        Ok(Self::decapsulate_with_status(secret_key, ciphertext)
+            .0
+            .into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VECTORS: &str = include_str!("../../../tests/vectors/ml_kem_768.txt");
+
+    fn field<'a>(record: &'a str, name: &str) -> &'a str {
+        record
+            .lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix(" = "))
+            .unwrap()
+    }
+
+    fn unhex<const N: usize>(hex: &str) -> [u8; N] {
+        let bytes: Vec<u8> = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect();
+        bytes.try_into().unwrap()
+    }
+
+    #[test]
+    fn encapsulation_known_answers() {
+        let records: Vec<&str> = VECTORS
+            .split("\n\n")
+            .filter(|r| r.contains("count"))
+            .collect();
+        assert_eq!(records.len(), 3);
+        for record in records {
+            let ek = unhex::<PUBLIC_KEY_SIZE>(field(record, "ek"));
+            let m = unhex::<32>(field(record, "m"));
+            let (ct, shared) = MlKem768::encapsulate_with(&ek, &m).unwrap();
+            assert_eq!(ct, unhex::<CIPHERTEXT_SIZE>(field(record, "ct")));
+            assert_eq!(shared.as_bytes(), &unhex::<32>(field(record, "ss")));
+        }
+    }
+
+    #[test]
+    fn trait_round_trip() {
+        let kem = MlKem768;
+        let (pk, sk) = kem.generate_keypair().unwrap();
+        let (ct, ss) = kem.encapsulate(&pk).unwrap();
+        assert_eq!(kem.decapsulate(&sk, &ct).unwrap(), ss);
+    }
+
+    #[test]
+    fn rejects_invalid_keys() {
+        let kem = MlKem768;
+        assert!(kem.encapsulate(&[0xff; PUBLIC_KEY_SIZE]).is_err());
+        assert!(
+            kem.decapsulate(&[0u8; SECRET_KEY_SIZE], &[0u8; CIPHERTEXT_SIZE])
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,6 @@
+#[cfg(feature = "mldsa")]
+pub(crate) mod ml_dsa_87;
+#[cfg(feature = "mlkem")]
+pub(crate) mod ml_kem;
+#[cfg(feature = "mlkem")]
+pub(crate) mod ml_kem_768;
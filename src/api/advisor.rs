@@ -0,0 +1,406 @@
+//! Suite recommendations from usage profiles.
+//!
+//! # Purpose
+//!
+//! Picking between hybrid and pure post-quantum constructions, or between
+//! AEADs, is a trade between defence in depth and latency. [`recommend`]
+//! makes that trade from a [`UsageProfile`]: message size, operation rates
+//! and device class. It returns a [`SecuritySuite`] together with the
+//! latencies the choice is expected to cost.
+//!
+//! # Policy
+//!
+//! Only suites this crate exposes are considered, and every one of them is
+//! post-quantum: the advisor never falls back to classical-only key
+//! exchange or signatures. It starts from the strongest suite (hybrid KEM,
+//! hybrid signatures) and drops the classical halves, signatures first,
+//! only while the profile's targets cannot be met on one core. If even the
+//! cheapest suite misses them, that suite is returned with
+//! [`SecuritySuite::meets_targets`] set to `false`; add cores or lower the
+//! rates rather than the security level.
+//!
+//! # Latency Data
+//!
+//! [`recommend`] uses reference timings embedded in the crate, scaled by
+//! [`DeviceClass`]. [`recommend_measured`] uses a [`bench::Profile`] taken
+//! on the target machine for the operations it covers, and scales the
+//! reference timings of the others by the machine's measured speed. Both
+//! are estimates for capacity planning, not guarantees.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use citadel::advisor::{self, DeviceClass, UsageProfile};
+//!
+//! let mut profile = UsageProfile::new(DeviceClass::Mobile);
+//! profile.handshakes_per_sec = 5.0;
+//! profile.verifications_per_sec = 20.0;
+//! let suite = advisor::recommend(&profile);
+//! assert!(suite.meets_targets);
+//! ```
+
+use core::time::Duration;
+
+use crate::bench::{self, Benchmark};
+use crate::level::SecurityLevel;
+
+/// Reference timings in microseconds, measured single-threaded on an
+/// x86_64 server in a release build.
+mod reference {
+    pub(super) const ML_KEM_768_KEYGEN: f64 = 50.0;
+    pub(super) const ML_KEM_768_ENCAPSULATE: f64 = 60.0;
+    pub(super) const ML_KEM_768_DECAPSULATE: f64 = 80.0;
+    pub(super) const X25519: f64 = 70.0;
+    pub(super) const ED25519_SIGN: f64 = 230.0;
+    pub(super) const ED25519_VERIFY: f64 = 240.0;
+    pub(super) const ML_DSA_87_SIGN: f64 = 900.0;
+    pub(super) const ML_DSA_87_VERIFY: f64 = 330.0;
+    pub(super) const AES_256_GCM_SIV_PER_BYTE: f64 = 0.3;
+    pub(super) const AES_256_GCM_SIV_PER_MESSAGE: f64 = 30.0;
+    #[cfg(feature = "aegis256")]
+    pub(super) const AEGIS_256_PER_BYTE: f64 = 0.001;
+    #[cfg(feature = "aegis256")]
+    pub(super) const AEGIS_256_PER_MESSAGE: f64 = 1.0;
+}
+
+/// The kind of machine the suite will run on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DeviceClass {
+    /// Data-centre CPU.
+    #[default]
+    Server,
+    /// Laptop or desktop CPU.
+    Desktop,
+    /// Phone or tablet application processor.
+    Mobile,
+    /// Microcontroller or low-power SoC without crypto extensions.
+    Embedded,
+}
+
+impl DeviceClass {
+    /// How many times slower than the reference server this class is.
+    fn slowdown(self) -> f64 {
+        match self {
+            DeviceClass::Server => 1.0,
+            DeviceClass::Desktop => 1.5,
+            DeviceClass::Mobile => 4.0,
+            DeviceClass::Embedded => 50.0,
+        }
+    }
+}
+
+/// Workload to plan for. Rates are per second, on one core.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[non_exhaustive]
+pub struct UsageProfile {
+    /// Typical plaintext size in bytes.
+    pub message_size: usize,
+    /// Messages sealed or opened.
+    pub messages_per_sec: f64,
+    /// Key exchanges, each a key generation, encapsulation and
+    /// decapsulation.
+    pub handshakes_per_sec: f64,
+    /// Signatures created.
+    pub signatures_per_sec: f64,
+    /// Signatures verified.
+    pub verifications_per_sec: f64,
+    /// Where the workload runs.
+    pub device: DeviceClass,
+}
+
+impl UsageProfile {
+    /// An idle profile on `device`; set the rates that apply.
+    pub fn new(device: DeviceClass) -> Self {
+        Self {
+            device,
+            ..Self::default()
+        }
+    }
+}
+
+/// Key encapsulation choices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum KemChoice {
+    /// ML-KEM-768 combined with X25519 ([`crate::hybrid::HybridKem`]).
+    X25519MlKem768,
+    /// ML-KEM-768 alone ([`crate::kem::MlKem768`]).
+    MlKem768,
+}
+
+/// Signature choices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SignatureChoice {
+    /// ML-DSA-87 combined with Ed25519 ([`crate::hybrid::HybridSigner`]).
+    Ed25519MlDsa87,
+    /// ML-DSA-87 alone ([`crate::sign::MlDsa87`]).
+    MlDsa87,
+}
+
+/// AEAD choices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum AeadChoice {
+    /// [`crate::aead::Aes256GcmSiv`].
+    Aes256GcmSiv,
+    /// `citadel::aead::Aegis256`; only recommended when the
+    /// `aegis256` feature is enabled.
+    Aegis256,
+}
+
+impl KemChoice {
+    /// NIST security level of the post-quantum component.
+    pub const fn security_level(self) -> SecurityLevel {
+        match self {
+            KemChoice::X25519MlKem768 | KemChoice::MlKem768 => SecurityLevel::L3,
+        }
+    }
+}
+
+impl SignatureChoice {
+    /// NIST security level of the post-quantum component.
+    pub const fn security_level(self) -> SecurityLevel {
+        match self {
+            SignatureChoice::Ed25519MlDsa87 | SignatureChoice::MlDsa87 => SecurityLevel::L5,
+        }
+    }
+}
+
+/// Expected cost of each operation under a suite.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct Latencies {
+    /// One key exchange: key generation, encapsulation and decapsulation.
+    pub handshake: Duration,
+    /// One signature.
+    pub sign: Duration,
+    /// One verification.
+    pub verify: Duration,
+    /// Sealing one message of [`UsageProfile::message_size`] bytes.
+    pub seal: Duration,
+}
+
+/// A recommended suite and what it is expected to cost.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct SecuritySuite {
+    /// Key encapsulation.
+    pub kem: KemChoice,
+    /// Signatures.
+    pub signature: SignatureChoice,
+    /// Bulk encryption.
+    pub aead: AeadChoice,
+    /// Expected per-operation latencies.
+    pub latencies: Latencies,
+    /// Fraction of one core the profile keeps busy under this suite.
+    pub utilization: f64,
+    /// Whether `utilization` is at most 1.0.
+    pub meets_targets: bool,
+}
+
+/// Recommend a suite for `profile` from the embedded reference timings.
+pub fn recommend(profile: &UsageProfile) -> SecuritySuite {
+    let slowdown = profile.device.slowdown();
+    choose(profile, |micros, _| micros * slowdown)
+}
+
+/// Recommend a suite for `profile` from timings measured on the target
+/// machine. `profile.device` is ignored.
+pub fn recommend_measured(profile: &UsageProfile, measured: &bench::Profile) -> SecuritySuite {
+    let slowdown = measured_slowdown(measured);
+    choose(profile, |micros, benchmark| {
+        match benchmark.map(|b| measured.ops_per_sec(b)) {
+            Some(ops) if ops > 0.0 => 1e6 / ops,
+            _ => micros * slowdown,
+        }
+    })
+}
+
+/// Geometric mean of measured over reference time for the benchmarks with
+/// reference timings; 1.0 if none were run.
+fn measured_slowdown(measured: &bench::Profile) -> f64 {
+    let ratios: Vec<f64> = [
+        (Benchmark::MlKem768Keygen, reference::ML_KEM_768_KEYGEN),
+        (
+            Benchmark::MlKem768Encapsulate,
+            reference::ML_KEM_768_ENCAPSULATE,
+        ),
+        (
+            Benchmark::MlKem768Decapsulate,
+            reference::ML_KEM_768_DECAPSULATE,
+        ),
+        (Benchmark::X25519, reference::X25519),
+        (Benchmark::Ed25519Sign, reference::ED25519_SIGN),
+        (Benchmark::Ed25519Verify, reference::ED25519_VERIFY),
+    ]
+    .iter()
+    .filter_map(|&(benchmark, micros)| {
+        let ops = measured.ops_per_sec(benchmark);
+        (ops > 0.0).then(|| (1e6 / ops / micros).ln())
+    })
+    .collect();
+    if ratios.is_empty() {
+        return 1.0;
+    }
+    (ratios.iter().sum::<f64>() / ratios.len() as f64).exp()
+}
+
+/// Walk the suites from strongest to cheapest and return the first that
+/// meets the targets, or the cheapest. `cost` maps a reference timing and
+/// the benchmark measuring it, if any, to microseconds on the target.
+fn choose(profile: &UsageProfile, cost: impl Fn(f64, Option<Benchmark>) -> f64) -> SecuritySuite {
+    use reference as r;
+
+    let kem = cost(r::ML_KEM_768_KEYGEN, Some(Benchmark::MlKem768Keygen))
+        + cost(
+            r::ML_KEM_768_ENCAPSULATE,
+            Some(Benchmark::MlKem768Encapsulate),
+        )
+        + cost(
+            r::ML_KEM_768_DECAPSULATE,
+            Some(Benchmark::MlKem768Decapsulate),
+        );
+    // Key generation, encapsulation and decapsulation each add one
+    // scalar multiplication
+    let x25519 = 3.0 * cost(r::X25519, Some(Benchmark::X25519));
+    let ml_dsa = (
+        cost(r::ML_DSA_87_SIGN, None),
+        cost(r::ML_DSA_87_VERIFY, None),
+    );
+    let ed25519 = (
+        cost(r::ED25519_SIGN, Some(Benchmark::Ed25519Sign)),
+        cost(r::ED25519_VERIFY, Some(Benchmark::Ed25519Verify)),
+    );
+    let (aead, seal) = aead(profile, &cost);
+
+    let candidates = [
+        (KemChoice::X25519MlKem768, SignatureChoice::Ed25519MlDsa87),
+        (KemChoice::X25519MlKem768, SignatureChoice::MlDsa87),
+        (KemChoice::MlKem768, SignatureChoice::MlDsa87),
+    ];
+    let mut suite = None;
+    for (kem_choice, signature) in candidates {
+        let handshake = match kem_choice {
+            KemChoice::X25519MlKem768 => kem + x25519,
+            KemChoice::MlKem768 => kem,
+        };
+        let (sign, verify) = match signature {
+            SignatureChoice::Ed25519MlDsa87 => (ml_dsa.0 + ed25519.0, ml_dsa.1 + ed25519.1),
+            SignatureChoice::MlDsa87 => ml_dsa,
+        };
+        let busy_micros = profile.handshakes_per_sec * handshake
+            + profile.signatures_per_sec * sign
+            + profile.verifications_per_sec * verify
+            + profile.messages_per_sec * seal;
+        let utilization = busy_micros / 1e6;
+        let candidate = SecuritySuite {
+            kem: kem_choice,
+            signature,
+            aead,
+            latencies: Latencies {
+                handshake: micros(handshake),
+                sign: micros(sign),
+                verify: micros(verify),
+                seal: micros(seal),
+            },
+            utilization,
+            meets_targets: utilization <= 1.0,
+        };
+        if candidate.meets_targets {
+            return candidate;
+        }
+        suite = Some(candidate);
+    }
+    suite.expect("candidate list is not empty")
+}
+
+/// The faster available AEAD for the profile's message size, and its
+/// per-message cost in microseconds.
+fn aead(
+    profile: &UsageProfile,
+    cost: &impl Fn(f64, Option<Benchmark>) -> f64,
+) -> (AeadChoice, f64) {
+    use reference as r;
+
+    let bytes = profile.message_size as f64;
+    let gcm_siv = cost(
+        r::AES_256_GCM_SIV_PER_MESSAGE + bytes * r::AES_256_GCM_SIV_PER_BYTE,
+        None,
+    );
+    #[cfg(feature = "aegis256")]
+    {
+        let aegis = cost(
+            r::AEGIS_256_PER_MESSAGE + bytes * r::AEGIS_256_PER_BYTE,
+            None,
+        );
+        // Without AES instructions both are table-free software AES, and
+        // the misuse resistance of GCM-SIV wins
+        if profile.device != DeviceClass::Embedded && aegis < gcm_siv {
+            return (AeadChoice::Aegis256, aegis);
+        }
+    }
+    (AeadChoice::Aes256GcmSiv, gcm_siv)
+}
+
+fn micros(micros: f64) -> Duration {
+    Duration::from_secs_f64(micros / 1e6)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn light_load_gets_strongest_suite() {
+        let mut profile = UsageProfile::new(DeviceClass::Mobile);
+        profile.handshakes_per_sec = 1.0;
+        profile.verifications_per_sec = 10.0;
+        let suite = recommend(&profile);
+        assert_eq!(suite.kem, KemChoice::X25519MlKem768);
+        assert_eq!(suite.signature, SignatureChoice::Ed25519MlDsa87);
+        assert!(suite.meets_targets && suite.utilization < 1.0);
+        assert!(suite.latencies.sign > suite.latencies.verify);
+    }
+
+    #[test]
+    fn heavy_load_drops_classical_halves_but_never_pq() {
+        let mut profile = UsageProfile::new(DeviceClass::Server);
+        profile.signatures_per_sec = 1000.0;
+        let suite = recommend(&profile);
+        assert_eq!(suite.kem, KemChoice::X25519MlKem768);
+        assert_eq!(suite.signature, SignatureChoice::MlDsa87);
+        assert!(suite.meets_targets);
+
+        profile.device = DeviceClass::Embedded;
+        let suite = recommend(&profile);
+        assert_eq!(suite.kem, KemChoice::MlKem768);
+        assert_eq!(suite.signature, SignatureChoice::MlDsa87);
+        assert!(!suite.meets_targets && suite.utilization > 1.0);
+    }
+
+    #[test]
+    fn slower_devices_have_higher_latencies() {
+        let mut profile = UsageProfile::new(DeviceClass::Server);
+        profile.message_size = 4096;
+        let server = recommend(&profile).latencies;
+        profile.device = DeviceClass::Embedded;
+        let embedded = recommend(&profile).latencies;
+        assert!(embedded.handshake > server.handshake);
+        assert!(embedded.seal > server.seal);
+        assert_eq!(recommend(&profile).aead, AeadChoice::Aes256GcmSiv);
+    }
+
+    #[cfg(feature = "mlkem")]
+    #[test]
+    fn measured_profile_is_used() {
+        let measured = bench::profile_with_budget(Duration::from_millis(1));
+        let mut profile = UsageProfile::new(DeviceClass::Embedded);
+        profile.handshakes_per_sec = 1.0;
+        let suite = recommend_measured(&profile, &measured);
+        let expected = 1.0 / measured.ops_per_sec(Benchmark::MlKem768Decapsulate);
+        assert!(suite.latencies.handshake.as_secs_f64() > expected);
+        assert!(suite.latencies.sign > Duration::ZERO);
+    }
+}
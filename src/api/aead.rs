@@ -0,0 +1,816 @@
+//! Authenticated symmetric encryption.
+//!
+//! # Purpose
+//!
+//! AEAD ciphers for data encrypted under a shared key, typically one
+//! derived from a [`kem`](crate::kem) shared secret.
+//!
+//! # Algorithms
+//!
+//! - [`Aes256GcmSiv`]: AES-256-GCM-SIV, RFC 8452 (nonce-misuse resistant,
+//!   feature `aes-gcm-siv`)
+//! - `Aegis256`: AEGIS-256 with a 256-bit tag, for high-throughput links
+//!   on CPUs with AES instructions (feature `aegis256`)
+//!
+//! # Security
+//!
+//! - Keys are zeroized on drop
+//! - Decryption verifies the tag before returning any plaintext
+//! - With AES-256-GCM-SIV, an accidentally repeated nonce only reveals
+//!   whether the same message was encrypted twice. Still use a fresh
+//!   [`Nonce`] per message: misuse resistance is a safety net, not a
+//!   license to use a constant nonce
+//! - AEGIS-256 is not misuse resistant: a repeated nonce leaks plaintext.
+//!   Its 256-bit nonces can be drawn at random without a collision risk
+//! - Every typed key counts the messages and bytes it has encrypted and
+//!   refuses to encrypt past its algorithm's [usage limit](KeyUsage), so a
+//!   long-lived service is forced to rekey. Query the remaining budget
+//!   with `remaining()` and rotate before it runs out
+//! - For one key per message, [`SealOnceKey`] is consumed by encryption,
+//!   so reusing it does not compile. It cannot be rebuilt from bytes; the
+//!   recipient decrypts with an [`OpenOnceKey`]
+//!
+//! # Example
+//!
+//! ```ignore
+//! use citadel::nonce::Nonce;
+//! use citadel::aead::Aes256GcmSiv;
+//!
+//! let key = Aes256GcmSiv::generate_key()?;
+//! let nonce = Nonce::random()?;
+//! let nonce_bytes = *nonce.as_bytes();
+//! let ciphertext = Aes256GcmSiv::encrypt(&key, nonce, b"secret", b"header")?;
+//! let plaintext = Aes256GcmSiv::decrypt(&key, &nonce_bytes, &ciphertext, b"header")?;
+//! ```
+
+#![cfg_attr(
+    not(any(feature = "aes-gcm-siv", feature = "aegis256")),
+    allow(unused_imports)
+)]
+
+use core::fmt;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use crate::algorithm::{AlgorithmId, AlgorithmKind};
+#[cfg(feature = "aegis256")]
+use crate::algorithms::classical::aegis256 as aegis_imp;
+#[cfg(feature = "aes-gcm-siv")]
+use crate::algorithms::classical::aes256_gcm_siv as imp;
+use crate::context::{self, EntropySource};
+use crate::errors::{MisuseError, Result};
+use crate::internal::aead as dispatch;
+use crate::internal::entropy::ContextEntropy;
+use crate::memory::{SecureBuffer, SensitiveBytes};
+use crate::nonce::Nonce;
+
+/// Messages and plaintext bytes encrypted under a key, or allowed to be.
+///
+/// Only the typed keys of this module (`Aes256GcmSivKey`, `Aegis256Key`)
+/// count their use. Functions that take raw key bytes do not: the
+/// [`dynamic`](crate::dynamic) ciphers, [`envelope`](crate::envelope),
+/// [`stream`](crate::stream), [`dek`](crate::dek), encrypted key
+/// containers and [`SealOnceKey`]. Most of them encrypt once per key or
+/// derive a fresh key per stream; a caller reusing one raw key across many
+/// calls must track its usage against these limits itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyUsage {
+    /// Number of encryptions.
+    pub messages: u64,
+    /// Total plaintext bytes.
+    pub bytes: u64,
+}
+
+/// Encryptions performed under a key, checked against its limit.
+#[cfg(any(feature = "aes-gcm-siv", feature = "aegis256"))]
+struct UsageCounter {
+    limit: KeyUsage,
+    messages: AtomicU64,
+    bytes: AtomicU64,
+}
+
+#[cfg(any(feature = "aes-gcm-siv", feature = "aegis256"))]
+impl UsageCounter {
+    const fn new(limit: KeyUsage) -> Self {
+        Self {
+            limit,
+            messages: AtomicU64::new(0),
+            bytes: AtomicU64::new(0),
+        }
+    }
+
+    /// Count one encryption of `len` bytes, unless it would exceed the
+    /// limit. Counted whether or not the encryption then succeeds.
+    fn record(&self, len: usize) -> Result<()> {
+        self.messages
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |messages| {
+                (messages < self.limit.messages).then_some(messages + 1)
+            })
+            .map_err(|_| MisuseError::InvalidState)?;
+        let counted = self
+            .bytes
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bytes| {
+                bytes
+                    .checked_add(len as u64)
+                    .filter(|&total| total <= self.limit.bytes)
+            });
+        if counted.is_err() {
+            self.messages.fetch_sub(1, Ordering::Relaxed);
+            return Err(MisuseError::InvalidState.into());
+        }
+        Ok(())
+    }
+
+    /// The two counters are read independently, so under concurrent use the
+    /// result may be off by in-flight calls.
+    fn usage(&self) -> KeyUsage {
+        KeyUsage {
+            messages: self.messages.load(Ordering::Relaxed),
+            bytes: self.bytes.load(Ordering::Relaxed),
+        }
+    }
+
+    fn remaining(&self) -> KeyUsage {
+        let used = self.usage();
+        KeyUsage {
+            messages: self.limit.messages.saturating_sub(used.messages),
+            bytes: self.limit.bytes.saturating_sub(used.bytes),
+        }
+    }
+}
+
+/// AES-256-GCM-SIV (RFC 8452).
+///
+/// Namespace for key generation, encryption and decryption. Ciphertexts
+/// are the encrypted message followed by a 16-byte tag.
+#[cfg(feature = "aes-gcm-siv")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Aes256GcmSiv;
+
+#[cfg(feature = "aes-gcm-siv")]
+impl Aes256GcmSiv {
+    /// Key size in bytes.
+    pub const KEY_SIZE: usize = imp::KEY_SIZE;
+    /// Nonce size in bytes.
+    pub const NONCE_SIZE: usize = imp::NONCE_SIZE;
+    /// Tag size in bytes.
+    pub const TAG_SIZE: usize = imp::TAG_SIZE;
+    /// Most a key may encrypt: 2^32 messages and 2^50 bytes.
+    ///
+    /// RFC 8452 section 9 permits more: each nonce derives its own
+    /// encryption and authentication keys, so the birthday bound applies
+    /// per message rather than per key. The limit is set by nonce
+    /// collisions instead: 2^32 random 96-bit nonces, as drawn by
+    /// [`Nonce::random`], collide with probability below 2^-33, and a
+    /// collision only reveals whether two messages were equal.
+    pub const USAGE_LIMIT: KeyUsage = KeyUsage {
+        messages: 1 << 32,
+        bytes: 1 << 50,
+    };
+
+    /// Generate a new key from system randomness.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+    /// - `CryptoError::RandomnessFailure`: If the system RNG fails a health test
+    pub fn generate_key() -> Result<Aes256GcmSivKey> {
+        Self::generate_key_with_rng(&ContextEntropy)
+    }
+
+    /// Generate a new key from `rng`.
+    ///
+    /// # Security
+    ///
+    /// `rng` MUST be a CSPRNG of at least 256-bit security, e.g. a
+    /// hardware TRNG or an approved DRBG. Deterministic sources are for
+    /// known-answer tests only.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If `rng` fails
+    pub fn generate_key_with_rng(rng: &dyn EntropySource) -> Result<Aes256GcmSivKey> {
+        let mut key = SensitiveBytes::zeroed();
+        rng.fill(key.as_bytes_mut())?;
+        Ok(Aes256GcmSivKey::new(key))
+    }
+
+    /// Encrypt `plaintext`, authenticating `associated_data` with it.
+    ///
+    /// Transmit the nonce bytes alongside the ciphertext.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidPlaintextLength`: If `plaintext` exceeds 2^36
+    ///   bytes
+    /// - `MisuseError::AssociatedDataTooLong`: If `associated_data` exceeds
+    ///   2^36 bytes
+    /// - `MisuseError::InvalidState`: If `key` has reached its usage limit;
+    ///   rekey
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    pub fn encrypt(
+        key: &Aes256GcmSivKey,
+        nonce: Nonce<{ imp::NONCE_SIZE }>,
+        plaintext: &[u8],
+        associated_data: &[u8],
+    ) -> Result<Vec<u8>> {
+        context::checkpoint()?;
+        key.1.record(plaintext.len())?;
+        let mut ciphertext = vec![0u8; plaintext.len() + imp::TAG_SIZE];
+        imp::Aes256GcmSiv::encrypt_with(
+            key.0.as_bytes(),
+            nonce.as_bytes(),
+            plaintext,
+            associated_data,
+            &mut ciphertext,
+        )?;
+        Ok(ciphertext)
+    }
+
+    /// Verify and decrypt `ciphertext`.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::DecryptionFailed`: If the ciphertext, nonce, key or
+    ///   associated data do not match
+    /// - `MisuseError::InvalidCiphertextLength`: If `ciphertext` is shorter
+    ///   than [`Self::TAG_SIZE`]
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    pub fn decrypt(
+        key: &Aes256GcmSivKey,
+        nonce: &[u8; imp::NONCE_SIZE],
+        ciphertext: &[u8],
+        associated_data: &[u8],
+    ) -> Result<Vec<u8>> {
+        context::checkpoint()?;
+        let mut plaintext = vec![0u8; ciphertext.len().saturating_sub(imp::TAG_SIZE)];
+        imp::Aes256GcmSiv::decrypt_with(
+            key.0.as_bytes(),
+            nonce,
+            ciphertext,
+            associated_data,
+            &mut plaintext,
+        )?;
+        Ok(plaintext)
+    }
+}
+
+/// AES-256-GCM-SIV key, zeroized on drop.
+#[cfg(feature = "aes-gcm-siv")]
+pub struct Aes256GcmSivKey(SensitiveBytes<{ imp::KEY_SIZE }>, UsageCounter);
+
+#[cfg(feature = "aes-gcm-siv")]
+crate::memory::never_serialize!([] Aes256GcmSivKey);
+
+#[cfg(feature = "aes-gcm-siv")]
+impl Aes256GcmSivKey {
+    fn new(key: SensitiveBytes<{ imp::KEY_SIZE }>) -> Self {
+        Self(key, UsageCounter::new(Aes256GcmSiv::USAGE_LIMIT))
+    }
+
+    /// Wrap raw key bytes.
+    ///
+    /// Usage is counted from zero: a key restored from storage starts
+    /// with a fresh budget, so persist [`remaining`](Self::remaining)
+    /// alongside it and restore it with
+    /// [`with_usage_limit`](Self::with_usage_limit).
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidKeyLength`: If `bytes` is not
+    ///   [`Aes256GcmSiv::KEY_SIZE`] bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != imp::KEY_SIZE {
+            return Err(MisuseError::InvalidKeyLength.into());
+        }
+        let mut key = SensitiveBytes::zeroed();
+        key.as_bytes_mut().copy_from_slice(bytes);
+        Ok(Self::new(key))
+    }
+
+    /// Lower this key's usage limit below [`Aes256GcmSiv::USAGE_LIMIT`], e.g.
+    /// to the budget a previous process had left. Usage so far counts
+    /// against the new limit; a higher limit is clamped to the default.
+    pub fn with_usage_limit(self, limit: KeyUsage) -> Self {
+        let used = self.1.usage();
+        let counter = UsageCounter::new(KeyUsage {
+            messages: limit.messages.min(Aes256GcmSiv::USAGE_LIMIT.messages),
+            bytes: limit.bytes.min(Aes256GcmSiv::USAGE_LIMIT.bytes),
+        });
+        counter.messages.store(used.messages, Ordering::Relaxed);
+        counter.bytes.store(used.bytes, Ordering::Relaxed);
+        Self(self.0, counter)
+    }
+
+    /// Messages and bytes encrypted under this key so far.
+    pub fn usage(&self) -> KeyUsage {
+        self.1.usage()
+    }
+
+    /// Messages and bytes this key may still encrypt.
+    pub fn remaining(&self) -> KeyUsage {
+        self.1.remaining()
+    }
+
+    /// Raw key bytes.
+    ///
+    /// # Security
+    ///
+    /// Do not log, serialize unencrypted, or store the returned bytes.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8; imp::KEY_SIZE] {
+        self.0.as_bytes()
+    }
+}
+
+#[cfg(feature = "aes-gcm-siv")]
+impl fmt::Debug for Aes256GcmSivKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Aes256GcmSivKey { <redacted> }")
+    }
+}
+
+/// AEGIS-256 (draft-irtf-cfrg-aegis-aead) with a 256-bit tag.
+///
+/// Namespace for key generation, encryption and decryption. Ciphertexts
+/// are the encrypted message followed by a 32-byte tag. Uses AES-NI when
+/// the CPU supports it and a much slower portable implementation
+/// otherwise.
+#[cfg(feature = "aegis256")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Aegis256;
+
+#[cfg(feature = "aegis256")]
+impl Aegis256 {
+    /// Key size in bytes.
+    pub const KEY_SIZE: usize = aegis_imp::KEY_SIZE;
+    /// Nonce size in bytes.
+    pub const NONCE_SIZE: usize = aegis_imp::NONCE_SIZE;
+    /// Tag size in bytes.
+    pub const TAG_SIZE: usize = aegis_imp::TAG_SIZE;
+    /// Most a key may encrypt: 2^48 messages, at which random 256-bit
+    /// nonces still collide with negligible probability, and 2^50 bytes.
+    pub const USAGE_LIMIT: KeyUsage = KeyUsage {
+        messages: 1 << 48,
+        bytes: 1 << 50,
+    };
+
+    /// Generate a new key from system randomness.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+    /// - `CryptoError::RandomnessFailure`: If the system RNG fails a health test
+    pub fn generate_key() -> Result<Aegis256Key> {
+        Self::generate_key_with_rng(&ContextEntropy)
+    }
+
+    /// Generate a new key from `rng`.
+    ///
+    /// # Security
+    ///
+    /// `rng` MUST be a CSPRNG of at least 256-bit security, e.g. a
+    /// hardware TRNG or an approved DRBG. Deterministic sources are for
+    /// known-answer tests only.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If `rng` fails
+    pub fn generate_key_with_rng(rng: &dyn EntropySource) -> Result<Aegis256Key> {
+        let mut key = SensitiveBytes::zeroed();
+        rng.fill(key.as_bytes_mut())?;
+        Ok(Aegis256Key::new(key))
+    }
+
+    /// Encrypt `plaintext`, authenticating `associated_data` with it.
+    ///
+    /// Transmit the nonce bytes alongside the ciphertext.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidPlaintextLength`: If `plaintext` exceeds
+    ///   `2^61 - 1` bytes
+    /// - `MisuseError::AssociatedDataTooLong`: If `associated_data` exceeds
+    ///   `2^61 - 1` bytes
+    /// - `MisuseError::InvalidState`: If `key` has reached its usage limit;
+    ///   rekey
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    pub fn encrypt(
+        key: &Aegis256Key,
+        nonce: Nonce<{ aegis_imp::NONCE_SIZE }>,
+        plaintext: &[u8],
+        associated_data: &[u8],
+    ) -> Result<Vec<u8>> {
+        context::checkpoint()?;
+        key.1.record(plaintext.len())?;
+        let mut ciphertext = vec![0u8; plaintext.len() + aegis_imp::TAG_SIZE];
+        aegis_imp::Aegis256::encrypt_with(
+            key.0.as_bytes(),
+            nonce.as_bytes(),
+            plaintext,
+            associated_data,
+            &mut ciphertext,
+        )?;
+        Ok(ciphertext)
+    }
+
+    /// Verify and decrypt `ciphertext`.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::DecryptionFailed`: If the ciphertext, nonce, key or
+    ///   associated data do not match
+    /// - `MisuseError::InvalidCiphertextLength`: If `ciphertext` is shorter
+    ///   than [`Self::TAG_SIZE`]
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    pub fn decrypt(
+        key: &Aegis256Key,
+        nonce: &[u8; aegis_imp::NONCE_SIZE],
+        ciphertext: &[u8],
+        associated_data: &[u8],
+    ) -> Result<Vec<u8>> {
+        context::checkpoint()?;
+        let mut plaintext = vec![0u8; ciphertext.len().saturating_sub(aegis_imp::TAG_SIZE)];
+        aegis_imp::Aegis256::decrypt_with(
+            key.0.as_bytes(),
+            nonce,
+            ciphertext,
+            associated_data,
+            &mut plaintext,
+        )?;
+        Ok(plaintext)
+    }
+}
+
+/// AEGIS-256 key, zeroized on drop.
+#[cfg(feature = "aegis256")]
+pub struct Aegis256Key(SensitiveBytes<{ aegis_imp::KEY_SIZE }>, UsageCounter);
+
+#[cfg(feature = "aegis256")]
+crate::memory::never_serialize!([] Aegis256Key);
+
+#[cfg(feature = "aegis256")]
+impl Aegis256Key {
+    fn new(key: SensitiveBytes<{ aegis_imp::KEY_SIZE }>) -> Self {
+        Self(key, UsageCounter::new(Aegis256::USAGE_LIMIT))
+    }
+
+    /// Wrap raw key bytes.
+    ///
+    /// Usage is counted from zero: a key restored from storage starts
+    /// with a fresh budget, so persist [`remaining`](Self::remaining)
+    /// alongside it and restore it with
+    /// [`with_usage_limit`](Self::with_usage_limit).
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidKeyLength`: If `bytes` is not
+    ///   [`Aegis256::KEY_SIZE`] bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != aegis_imp::KEY_SIZE {
+            return Err(MisuseError::InvalidKeyLength.into());
+        }
+        let mut key = SensitiveBytes::zeroed();
+        key.as_bytes_mut().copy_from_slice(bytes);
+        Ok(Self::new(key))
+    }
+
+    /// Lower this key's usage limit below [`Aegis256::USAGE_LIMIT`], e.g.
+    /// to the budget a previous process had left. Usage so far counts
+    /// against the new limit; a higher limit is clamped to the default.
+    pub fn with_usage_limit(self, limit: KeyUsage) -> Self {
+        let used = self.1.usage();
+        let counter = UsageCounter::new(KeyUsage {
+            messages: limit.messages.min(Aegis256::USAGE_LIMIT.messages),
+            bytes: limit.bytes.min(Aegis256::USAGE_LIMIT.bytes),
+        });
+        counter.messages.store(used.messages, Ordering::Relaxed);
+        counter.bytes.store(used.bytes, Ordering::Relaxed);
+        Self(self.0, counter)
+    }
+
+    /// Messages and bytes encrypted under this key so far.
+    pub fn usage(&self) -> KeyUsage {
+        self.1.usage()
+    }
+
+    /// Messages and bytes this key may still encrypt.
+    pub fn remaining(&self) -> KeyUsage {
+        self.1.remaining()
+    }
+
+    /// Raw key bytes.
+    ///
+    /// # Security
+    ///
+    /// Do not log, serialize unencrypted, or store the returned bytes.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8; aegis_imp::KEY_SIZE] {
+        self.0.as_bytes()
+    }
+}
+
+#[cfg(feature = "aegis256")]
+impl fmt::Debug for Aegis256Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Aegis256Key { <redacted> }")
+    }
+}
+
+/// A key that encrypts exactly one message.
+///
+/// [`seal`](Self::seal) takes the key by value, so a second use is a
+/// compile error rather than a nonce reuse. Since the key never sees a
+/// second message, the nonce is fixed at zero and not transmitted.
+///
+/// The key can only be generated, never built from bytes or read back:
+/// [`generate`](Self::generate) hands out the raw bytes once, for the
+/// recipient's [`OpenOnceKey`]. Send them encrypted, e.g. under a key
+/// derived from a [`kem`](crate::kem) shared secret.
+///
+/// # Example
+///
+/// ```ignore
+/// use citadel::aead::{OpenOnceKey, SealOnceKey};
+/// use citadel::algorithm::AlgorithmId;
+///
+/// let (key, key_bytes) = SealOnceKey::generate(AlgorithmId::Aes256GcmSiv)?;
+/// let ciphertext = key.seal(b"message", b"header")?;
+/// // `key` has been moved: sealing again does not compile
+///
+/// let receiver = OpenOnceKey::from_bytes(AlgorithmId::Aes256GcmSiv, key_bytes.as_slice())?;
+/// let plaintext = receiver.open(&ciphertext, b"header")?;
+/// ```
+pub struct SealOnceKey {
+    algorithm: AlgorithmId,
+    key: SecureBuffer,
+}
+
+crate::memory::never_serialize!([] SealOnceKey);
+
+impl SealOnceKey {
+    /// Generate a new key for `algorithm` from system randomness.
+    ///
+    /// Returns the key and its raw bytes, for the recipient's
+    /// [`OpenOnceKey`].
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidAlgorithmIdentifier`: If `algorithm` is not
+    ///   an AEAD
+    /// - `MisuseError::FeatureNotEnabled`: If the algorithm's feature is off
+    /// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+    ///
+    /// # Security
+    ///
+    /// Do not log, serialize unencrypted, or store the returned bytes.
+    pub fn generate(algorithm: AlgorithmId) -> Result<(Self, SecureBuffer)> {
+        let mut key = SecureBuffer::zeroed(once_key_size(algorithm)?);
+        ContextEntropy.fill(key.as_mut_slice())?;
+        let exported = SecureBuffer::new(key.as_slice().to_vec());
+        Ok((Self { algorithm, key }, exported))
+    }
+
+    /// The AEAD this key is for.
+    pub fn algorithm(&self) -> AlgorithmId {
+        self.algorithm
+    }
+
+    /// Encrypt `plaintext`, authenticating `associated_data` with it, and
+    /// consume the key. The output is `ciphertext || tag`.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidPlaintextLength` /
+    ///   `MisuseError::AssociatedDataTooLong`: If an input exceeds the
+    ///   algorithm's limit
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    pub fn seal(self, plaintext: &[u8], associated_data: &[u8]) -> Result<Vec<u8>> {
+        context::checkpoint()?;
+        dispatch::seal(
+            self.algorithm,
+            self.key.as_slice(),
+            &zero_nonce(self.algorithm),
+            associated_data,
+            plaintext,
+        )
+    }
+}
+
+impl fmt::Debug for SealOnceKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SealOnceKey")
+            .field("algorithm", &self.algorithm)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The recipient's side of a [`SealOnceKey`]: decrypts the one message
+/// sealed under it.
+///
+/// Built from the bytes [`SealOnceKey::generate`] returned. It can only
+/// open, so rebuilding it from the same bytes never encrypts a second
+/// message under the fixed nonce.
+pub struct OpenOnceKey {
+    algorithm: AlgorithmId,
+    key: SecureBuffer,
+}
+
+crate::memory::never_serialize!([] OpenOnceKey);
+
+impl OpenOnceKey {
+    /// Wrap raw key bytes for `algorithm`.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidAlgorithmIdentifier`: If `algorithm` is not
+    ///   an AEAD
+    /// - `MisuseError::FeatureNotEnabled`: If the algorithm's feature is off
+    /// - `MisuseError::InvalidKeyLength`: If `bytes` has the wrong length
+    pub fn from_bytes(algorithm: AlgorithmId, bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != once_key_size(algorithm)? {
+            return Err(MisuseError::InvalidKeyLength.into());
+        }
+        Ok(Self {
+            algorithm,
+            key: SecureBuffer::new(bytes.to_vec()),
+        })
+    }
+
+    /// The AEAD this key is for.
+    pub fn algorithm(&self) -> AlgorithmId {
+        self.algorithm
+    }
+
+    /// Verify and decrypt the one message sealed under this key, and
+    /// consume the key.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::DecryptionFailed`: If the ciphertext, key or
+    ///   associated data do not match
+    /// - `MisuseError::InvalidCiphertextLength`: If `ciphertext` is shorter
+    ///   than a tag
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    pub fn open(self, ciphertext: &[u8], associated_data: &[u8]) -> Result<Vec<u8>> {
+        context::checkpoint()?;
+        dispatch::open(
+            self.algorithm,
+            self.key.as_slice(),
+            &zero_nonce(self.algorithm),
+            associated_data,
+            ciphertext,
+        )
+    }
+}
+
+impl fmt::Debug for OpenOnceKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OpenOnceKey")
+            .field("algorithm", &self.algorithm)
+            .finish_non_exhaustive()
+    }
+}
+
+fn once_key_size(algorithm: AlgorithmId) -> Result<usize> {
+    if algorithm.kind() != AlgorithmKind::Aead {
+        return Err(MisuseError::InvalidAlgorithmIdentifier.into());
+    }
+    if !algorithm.is_enabled() {
+        return Err(MisuseError::FeatureNotEnabled.into());
+    }
+    Ok(algorithm.sizes().secret_key.unwrap_or(0))
+}
+
+fn zero_nonce(algorithm: AlgorithmId) -> Vec<u8> {
+    vec![0u8; algorithm.sizes().nonce.unwrap_or(0)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::{CryptoError, Error};
+
+    #[cfg(feature = "aes-gcm-siv")]
+    #[test]
+    fn round_trip() {
+        let key = Aes256GcmSiv::generate_key().unwrap();
+        let nonce = Nonce::random().unwrap();
+        let nonce_bytes = *nonce.as_bytes();
+        let ciphertext = Aes256GcmSiv::encrypt(&key, nonce, b"secret", b"header").unwrap();
+        assert_eq!(ciphertext.len(), 6 + Aes256GcmSiv::TAG_SIZE);
+        assert_eq!(
+            Aes256GcmSiv::decrypt(&key, &nonce_bytes, &ciphertext, b"header").unwrap(),
+            b"secret"
+        );
+        assert_eq!(
+            Aes256GcmSiv::decrypt(&key, &nonce_bytes, &ciphertext, b"other").unwrap_err(),
+            Error::Crypto(CryptoError::DecryptionFailed)
+        );
+        assert_eq!(
+            Aes256GcmSiv::decrypt(&key, &nonce_bytes, &ciphertext[..15], b"").unwrap_err(),
+            Error::Misuse(MisuseError::InvalidCiphertextLength)
+        );
+    }
+
+    #[cfg(feature = "aes-gcm-siv")]
+    #[test]
+    fn key_parsing() {
+        assert_eq!(
+            Aes256GcmSivKey::from_bytes(&[0u8; 31]).unwrap_err(),
+            Error::Misuse(MisuseError::InvalidKeyLength)
+        );
+        let key = Aes256GcmSivKey::from_bytes(&[0u8; 32]).unwrap();
+        assert_eq!(format!("{key:?}"), "Aes256GcmSivKey { <redacted> }");
+    }
+
+    #[cfg(feature = "aes-gcm-siv")]
+    #[test]
+    fn usage_limit() {
+        let key = Aes256GcmSiv::generate_key().unwrap();
+        assert_eq!(key.remaining(), Aes256GcmSiv::USAGE_LIMIT);
+        let key = key.with_usage_limit(KeyUsage {
+            messages: 2,
+            bytes: u64::MAX,
+        });
+        assert_eq!(key.remaining().bytes, Aes256GcmSiv::USAGE_LIMIT.bytes);
+
+        let key = key.with_usage_limit(KeyUsage {
+            messages: 2,
+            bytes: 10,
+        });
+        let encrypt = |len: usize| {
+            Aes256GcmSiv::encrypt(&key, Nonce::random().unwrap(), &vec![0u8; len], b"")
+        };
+        encrypt(6).unwrap();
+        // A refused encryption does not count
+        assert_eq!(
+            encrypt(5).unwrap_err(),
+            Error::Misuse(MisuseError::InvalidState)
+        );
+        assert_eq!(
+            key.usage(),
+            KeyUsage {
+                messages: 1,
+                bytes: 6
+            }
+        );
+        encrypt(4).unwrap();
+        assert_eq!(
+            key.remaining(),
+            KeyUsage {
+                messages: 0,
+                bytes: 0
+            }
+        );
+        assert_eq!(
+            encrypt(0).unwrap_err(),
+            Error::Misuse(MisuseError::InvalidState)
+        );
+    }
+
+    #[cfg(feature = "aes-gcm-siv")]
+    #[test]
+    fn seal_once_key() {
+        let (key, key_bytes) = SealOnceKey::generate(AlgorithmId::Aes256GcmSiv).unwrap();
+        let receiver = OpenOnceKey::from_bytes(key.algorithm(), key_bytes.as_slice()).unwrap();
+        let ciphertext = key.seal(b"message", b"header").unwrap();
+        assert_eq!(receiver.open(&ciphertext, b"header").unwrap(), b"message");
+
+        assert_eq!(
+            OpenOnceKey::from_bytes(AlgorithmId::Aes256GcmSiv, &[0u8; 16]).unwrap_err(),
+            Error::Misuse(MisuseError::InvalidKeyLength)
+        );
+        assert_eq!(
+            SealOnceKey::generate(AlgorithmId::Ed25519).err(),
+            Some(Error::Misuse(MisuseError::InvalidAlgorithmIdentifier))
+        );
+    }
+
+    #[cfg(feature = "aegis256")]
+    #[test]
+    fn aegis256_round_trip() {
+        let key = Aegis256::generate_key().unwrap();
+        let nonce = Nonce::random().unwrap();
+        let nonce_bytes = *nonce.as_bytes();
+        let ciphertext = Aegis256::encrypt(&key, nonce, b"packet", b"header").unwrap();
+        assert_eq!(ciphertext.len(), 6 + Aegis256::TAG_SIZE);
+        assert_eq!(
+            Aegis256::decrypt(&key, &nonce_bytes, &ciphertext, b"header").unwrap(),
+            b"packet"
+        );
+        assert_eq!(
+            Aegis256::decrypt(&key, &nonce_bytes, &ciphertext, b"").unwrap_err(),
+            Error::Crypto(CryptoError::DecryptionFailed)
+        );
+        assert_eq!(
+            format!("{:?}", Aegis256Key::from_bytes(&[0u8; 32]).unwrap()),
+            "Aegis256Key { <redacted> }"
+        );
+    }
+}
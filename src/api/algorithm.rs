@@ -0,0 +1,503 @@
+//! Algorithm identifiers.
+//!
+//! # Purpose
+//!
+//! Wire formats and key stores need to record which algorithm produced a
+//! key, ciphertext or signature, and to check it before using the bytes.
+//! [`AlgorithmId`] gives every algorithm in the crate a stable 16-bit code
+//! and the sizes of its keys, ciphertexts and signatures, so a parser can
+//! reject a truncated or mislabelled blob before touching it.
+//!
+//! # Codes
+//!
+//! | Code     | Algorithm             | Kind      |
+//! |----------|-----------------------|-----------|
+//! | `0x0101` | ML-KEM-768            | KEM       |
+//! | `0x0102` | X25519 (DHKEM)        | KEM       |
+//! | `0x0103` | X25519 + ML-KEM-768   | KEM       |
+//! | `0x0201` | ML-DSA-87             | Signature |
+//! | `0x0202` | Ed25519               | Signature |
+//! | `0x0203` | Ed25519 + ML-DSA-87   | Signature |
+//! | `0x0301` | AES-256-GCM-SIV       | AEAD      |
+//! | `0x0302` | AEGIS-256             | AEAD      |
+//!
+//! The high byte is the [`AlgorithmKind`]. Codes are serialized big-endian,
+//! never change meaning and are never reused. Every algorithm has a code
+//! whether or not its feature is enabled, so data from a build with more
+//! features still parses.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use citadel::algorithm::AlgorithmId;
+//!
+//! let mut blob = AlgorithmId::MlDsa87.to_bytes().to_vec();
+//! blob.extend_from_slice(&signature);
+//!
+//! let (algorithm, signature) = AlgorithmId::split_prefix(&blob)?;
+//! algorithm.check_signature(signature)?;
+//! ```
+
+use core::fmt;
+
+use crate::advisor::{AeadChoice, KemChoice, SignatureChoice};
+use crate::consts;
+use crate::errors::{MisuseError, Result};
+
+/// Algorithm family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum AlgorithmKind {
+    /// Key encapsulation.
+    Kem,
+    /// Digital signature.
+    Signature,
+    /// Authenticated encryption.
+    Aead,
+}
+
+/// Stable identifier of an algorithm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum AlgorithmId {
+    /// ML-KEM-768.
+    MlKem768,
+    /// X25519 as a KEM.
+    X25519,
+    /// ML-KEM-768 combined with X25519 ([`crate::hybrid::HybridKem`]).
+    X25519MlKem768,
+    /// ML-DSA-87.
+    MlDsa87,
+    /// Ed25519.
+    Ed25519,
+    /// ML-DSA-87 combined with Ed25519 ([`crate::hybrid::HybridSigner`]).
+    Ed25519MlDsa87,
+    /// AES-256-GCM-SIV.
+    Aes256GcmSiv,
+    /// AEGIS-256.
+    Aegis256,
+}
+
+/// Encoded sizes of an algorithm's keys and outputs, in bytes.
+///
+/// Fields that do not apply to the algorithm's kind are `None`. For hybrids
+/// every size is the sum of both components. This struct is
+/// `#[non_exhaustive]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct AlgorithmSizes {
+    /// Public (encapsulation or verifying) key.
+    pub public_key: Option<usize>,
+    /// Secret key: decapsulation key, signing key or AEAD key.
+    pub secret_key: Option<usize>,
+    /// KEM ciphertext.
+    pub ciphertext: Option<usize>,
+    /// Signature.
+    pub signature: Option<usize>,
+    /// AEAD nonce.
+    pub nonce: Option<usize>,
+    /// AEAD tag.
+    pub tag: Option<usize>,
+}
+
+impl AlgorithmSizes {
+    const fn kem(public_key: usize, secret_key: usize, ciphertext: usize) -> Self {
+        Self {
+            public_key: Some(public_key),
+            secret_key: Some(secret_key),
+            ciphertext: Some(ciphertext),
+            signature: None,
+            nonce: None,
+            tag: None,
+        }
+    }
+
+    const fn signature(public_key: usize, secret_key: usize, signature: usize) -> Self {
+        Self {
+            public_key: Some(public_key),
+            secret_key: Some(secret_key),
+            ciphertext: None,
+            signature: Some(signature),
+            nonce: None,
+            tag: None,
+        }
+    }
+
+    const fn aead(key: usize, nonce: usize, tag: usize) -> Self {
+        Self {
+            public_key: None,
+            secret_key: Some(key),
+            ciphertext: None,
+            signature: None,
+            nonce: Some(nonce),
+            tag: Some(tag),
+        }
+    }
+}
+
+impl AlgorithmId {
+    /// Every algorithm, in code order.
+    pub const ALL: &[AlgorithmId] = &[
+        AlgorithmId::MlKem768,
+        AlgorithmId::X25519,
+        AlgorithmId::X25519MlKem768,
+        AlgorithmId::MlDsa87,
+        AlgorithmId::Ed25519,
+        AlgorithmId::Ed25519MlDsa87,
+        AlgorithmId::Aes256GcmSiv,
+        AlgorithmId::Aegis256,
+    ];
+    /// Size of an encoded identifier.
+    pub const SIZE: usize = 2;
+
+    /// Stable numeric code.
+    pub const fn code(self) -> u16 {
+        match self {
+            AlgorithmId::MlKem768 => 0x0101,
+            AlgorithmId::X25519 => 0x0102,
+            AlgorithmId::X25519MlKem768 => 0x0103,
+            AlgorithmId::MlDsa87 => 0x0201,
+            AlgorithmId::Ed25519 => 0x0202,
+            AlgorithmId::Ed25519MlDsa87 => 0x0203,
+            AlgorithmId::Aes256GcmSiv => 0x0301,
+            AlgorithmId::Aegis256 => 0x0302,
+        }
+    }
+
+    /// The algorithm with code `code`.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidAlgorithmIdentifier`: If no algorithm has
+    ///   this code
+    pub fn from_code(code: u16) -> Result<Self> {
+        match code {
+            0x0101 => Ok(AlgorithmId::MlKem768),
+            0x0102 => Ok(AlgorithmId::X25519),
+            0x0103 => Ok(AlgorithmId::X25519MlKem768),
+            0x0201 => Ok(AlgorithmId::MlDsa87),
+            0x0202 => Ok(AlgorithmId::Ed25519),
+            0x0203 => Ok(AlgorithmId::Ed25519MlDsa87),
+            0x0301 => Ok(AlgorithmId::Aes256GcmSiv),
+            0x0302 => Ok(AlgorithmId::Aegis256),
+            _ => Err(MisuseError::InvalidAlgorithmIdentifier.into()),
+        }
+    }
+
+    /// Big-endian encoding of [`code`](Self::code).
+    pub const fn to_bytes(self) -> [u8; Self::SIZE] {
+        self.code().to_be_bytes()
+    }
+
+    /// Decode an identifier of exactly [`SIZE`](Self::SIZE) bytes.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidAlgorithmIdentifier`: If `bytes` has the
+    ///   wrong length or an unknown code
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let bytes: [u8; Self::SIZE] = bytes
+            .try_into()
+            .map_err(|_| MisuseError::InvalidAlgorithmIdentifier)?;
+        Self::from_code(u16::from_be_bytes(bytes))
+    }
+
+    /// Split the identifier off the front of `bytes`, returning it and the
+    /// rest.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidAlgorithmIdentifier`: If `bytes` is shorter
+    ///   than [`SIZE`](Self::SIZE) or starts with an unknown code
+    pub fn split_prefix(bytes: &[u8]) -> Result<(Self, &[u8])> {
+        let Some((prefix, rest)) = bytes.split_first_chunk::<{ Self::SIZE }>() else {
+            return Err(MisuseError::InvalidAlgorithmIdentifier.into());
+        };
+        Ok((Self::from_code(u16::from_be_bytes(*prefix))?, rest))
+    }
+
+    /// Canonical name, as accepted by [`from_name`](Self::from_name).
+    /// [`crate::dynamic`] looks up the non-hybrid algorithms by the same
+    /// names.
+    pub const fn name(self) -> &'static str {
+        match self {
+            AlgorithmId::MlKem768 => "ML-KEM-768",
+            AlgorithmId::X25519 => "X25519",
+            AlgorithmId::X25519MlKem768 => "X25519-ML-KEM-768",
+            AlgorithmId::MlDsa87 => "ML-DSA-87",
+            AlgorithmId::Ed25519 => "Ed25519",
+            AlgorithmId::Ed25519MlDsa87 => "Ed25519-ML-DSA-87",
+            AlgorithmId::Aes256GcmSiv => "AES-256-GCM-SIV",
+            AlgorithmId::Aegis256 => "AEGIS-256",
+        }
+    }
+
+    /// The algorithm named `name`, ignoring ASCII case.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::UnsupportedAlgorithm`: If no algorithm has this
+    ///   name
+    pub fn from_name(name: &str) -> Result<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|id| id.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| MisuseError::UnsupportedAlgorithm.into())
+    }
+
+    /// Family of the algorithm.
+    pub const fn kind(self) -> AlgorithmKind {
+        match self {
+            AlgorithmId::MlKem768 | AlgorithmId::X25519 | AlgorithmId::X25519MlKem768 => {
+                AlgorithmKind::Kem
+            }
+            AlgorithmId::MlDsa87 | AlgorithmId::Ed25519 | AlgorithmId::Ed25519MlDsa87 => {
+                AlgorithmKind::Signature
+            }
+            AlgorithmId::Aes256GcmSiv | AlgorithmId::Aegis256 => AlgorithmKind::Aead,
+        }
+    }
+
+    /// Encoded sizes of the algorithm's keys and outputs.
+    pub const fn sizes(self) -> AlgorithmSizes {
+        use consts::{Aegis256, Aes256GcmSiv, Ed25519, MlDsa87, MlKem768, X25519};
+
+        match self {
+            AlgorithmId::MlKem768 => AlgorithmSizes::kem(
+                MlKem768::PUBLIC_KEY_SIZE,
+                MlKem768::SECRET_KEY_SIZE,
+                MlKem768::CIPHERTEXT_SIZE,
+            ),
+            AlgorithmId::X25519 => AlgorithmSizes::kem(
+                X25519::PUBLIC_KEY_SIZE,
+                X25519::SECRET_KEY_SIZE,
+                X25519::PUBLIC_KEY_SIZE,
+            ),
+            AlgorithmId::X25519MlKem768 => AlgorithmSizes::kem(
+                MlKem768::PUBLIC_KEY_SIZE + X25519::PUBLIC_KEY_SIZE,
+                MlKem768::SECRET_KEY_SIZE + X25519::SECRET_KEY_SIZE,
+                MlKem768::CIPHERTEXT_SIZE + X25519::PUBLIC_KEY_SIZE,
+            ),
+            AlgorithmId::MlDsa87 => AlgorithmSizes::signature(
+                MlDsa87::PUBLIC_KEY_SIZE,
+                MlDsa87::SECRET_KEY_SIZE,
+                MlDsa87::SIGNATURE_SIZE,
+            ),
+            AlgorithmId::Ed25519 => AlgorithmSizes::signature(
+                Ed25519::PUBLIC_KEY_SIZE,
+                Ed25519::SECRET_KEY_SIZE,
+                Ed25519::SIGNATURE_SIZE,
+            ),
+            AlgorithmId::Ed25519MlDsa87 => AlgorithmSizes::signature(
+                MlDsa87::PUBLIC_KEY_SIZE + Ed25519::PUBLIC_KEY_SIZE,
+                MlDsa87::SECRET_KEY_SIZE + Ed25519::SECRET_KEY_SIZE,
+                MlDsa87::SIGNATURE_SIZE + Ed25519::SIGNATURE_SIZE,
+            ),
+            AlgorithmId::Aes256GcmSiv => AlgorithmSizes::aead(
+                Aes256GcmSiv::KEY_SIZE,
+                Aes256GcmSiv::NONCE_SIZE,
+                Aes256GcmSiv::TAG_SIZE,
+            ),
+            AlgorithmId::Aegis256 => {
+                AlgorithmSizes::aead(Aegis256::KEY_SIZE, Aegis256::NONCE_SIZE, Aegis256::TAG_SIZE)
+            }
+        }
+    }
+
+    /// Whether this build implements the algorithm: its feature is enabled.
+    pub const fn is_enabled(self) -> bool {
+        match self {
+            AlgorithmId::MlKem768 | AlgorithmId::X25519MlKem768 => cfg!(feature = "mlkem"),
+            AlgorithmId::MlDsa87 | AlgorithmId::Ed25519MlDsa87 => cfg!(feature = "mldsa"),
+            AlgorithmId::X25519 | AlgorithmId::Ed25519 => true,
+            AlgorithmId::Aes256GcmSiv => cfg!(feature = "aes-gcm-siv"),
+            AlgorithmId::Aegis256 => cfg!(feature = "aegis256"),
+        }
+    }
+
+    /// Check that `public_key` has this algorithm's public key size.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidPublicKeyLength`: If the length differs, or
+    ///   the algorithm has no public key
+    pub fn check_public_key(self, public_key: &[u8]) -> Result<()> {
+        check(
+            self.sizes().public_key,
+            public_key,
+            MisuseError::InvalidPublicKeyLength,
+        )
+    }
+
+    /// Check that `secret_key` has this algorithm's secret key size.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidSecretKeyLength`: If the length differs
+    pub fn check_secret_key(self, secret_key: &[u8]) -> Result<()> {
+        check(
+            self.sizes().secret_key,
+            secret_key,
+            MisuseError::InvalidSecretKeyLength,
+        )
+    }
+
+    /// Check that `ciphertext` has this algorithm's KEM ciphertext size.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidCiphertextLength`: If the length differs, or
+    ///   the algorithm is not a KEM
+    pub fn check_ciphertext(self, ciphertext: &[u8]) -> Result<()> {
+        check(
+            self.sizes().ciphertext,
+            ciphertext,
+            MisuseError::InvalidCiphertextLength,
+        )
+    }
+
+    /// Check that `signature` has this algorithm's signature size.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidSignatureLength`: If the length differs, or
+    ///   the algorithm is not a signature scheme
+    pub fn check_signature(self, signature: &[u8]) -> Result<()> {
+        check(
+            self.sizes().signature,
+            signature,
+            MisuseError::InvalidSignatureLength,
+        )
+    }
+}
+
+fn check(expected: Option<usize>, bytes: &[u8], error: MisuseError) -> Result<()> {
+    if expected == Some(bytes.len()) {
+        Ok(())
+    } else {
+        Err(error.into())
+    }
+}
+
+impl fmt::Display for AlgorithmId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl From<KemChoice> for AlgorithmId {
+    fn from(choice: KemChoice) -> Self {
+        match choice {
+            KemChoice::X25519MlKem768 => AlgorithmId::X25519MlKem768,
+            KemChoice::MlKem768 => AlgorithmId::MlKem768,
+        }
+    }
+}
+
+impl From<SignatureChoice> for AlgorithmId {
+    fn from(choice: SignatureChoice) -> Self {
+        match choice {
+            SignatureChoice::Ed25519MlDsa87 => AlgorithmId::Ed25519MlDsa87,
+            SignatureChoice::MlDsa87 => AlgorithmId::MlDsa87,
+        }
+    }
+}
+
+impl From<AeadChoice> for AlgorithmId {
+    fn from(choice: AeadChoice) -> Self {
+        match choice {
+            AeadChoice::Aes256GcmSiv => AlgorithmId::Aes256GcmSiv,
+            AeadChoice::Aegis256 => AlgorithmId::Aegis256,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_and_names_round_trip() {
+        for &id in AlgorithmId::ALL {
+            assert_eq!(AlgorithmId::from_code(id.code()).unwrap(), id);
+            assert_eq!(AlgorithmId::from_bytes(&id.to_bytes()).unwrap(), id);
+            assert_eq!(AlgorithmId::from_name(id.name()).unwrap(), id);
+            assert_eq!((id.code() >> 8) as u8, id.kind() as u8 + 1);
+        }
+        assert_eq!(
+            AlgorithmId::from_name("ed25519").unwrap(),
+            AlgorithmId::Ed25519
+        );
+        assert_eq!(AlgorithmId::Aegis256.to_string(), "AEGIS-256");
+    }
+
+    #[test]
+    fn rejects_unknown_and_truncated_identifiers() {
+        for bytes in [
+            &[0x01, 0x04][..],
+            &[0x00, 0x00],
+            &[0x01],
+            &[0x01, 0x01, 0x00],
+        ] {
+            assert_eq!(
+                AlgorithmId::from_bytes(bytes).unwrap_err().misuse(),
+                Some(MisuseError::InvalidAlgorithmIdentifier)
+            );
+        }
+        assert_eq!(
+            AlgorithmId::split_prefix(&[0x02]).unwrap_err().misuse(),
+            Some(MisuseError::InvalidAlgorithmIdentifier)
+        );
+        assert_eq!(
+            AlgorithmId::from_name("ML-KEM-1024").unwrap_err().misuse(),
+            Some(MisuseError::UnsupportedAlgorithm)
+        );
+
+        let blob = [&AlgorithmId::Ed25519.to_bytes()[..], &[0u8; 64]].concat();
+        let (id, signature) = AlgorithmId::split_prefix(&blob).unwrap();
+        assert_eq!(id, AlgorithmId::Ed25519);
+        id.check_signature(signature).unwrap();
+        assert_eq!(
+            id.check_signature(&signature[1..]).unwrap_err().misuse(),
+            Some(MisuseError::InvalidSignatureLength)
+        );
+        assert_eq!(
+            id.check_ciphertext(signature).unwrap_err().misuse(),
+            Some(MisuseError::InvalidCiphertextLength)
+        );
+    }
+
+    #[test]
+    fn sizes_match_the_typed_apis() {
+        let sizes = AlgorithmId::Ed25519MlDsa87.sizes();
+        assert_eq!(sizes.signature, Some(4627 + 64));
+        assert_eq!(sizes.ciphertext, None);
+        #[cfg(feature = "mlkem")]
+        {
+            let sizes = AlgorithmId::MlKem768.sizes();
+            assert_eq!(
+                sizes.public_key,
+                Some(crate::kem::MlKem768::PUBLIC_KEY_SIZE)
+            );
+            assert_eq!(
+                sizes.ciphertext,
+                Some(crate::kem::MlKem768::CIPHERTEXT_SIZE)
+            );
+        }
+        #[cfg(feature = "aes-gcm-siv")]
+        assert_eq!(
+            AlgorithmId::Aes256GcmSiv.sizes().nonce,
+            Some(crate::aead::Aes256GcmSiv::NONCE_SIZE)
+        );
+        for &id in AlgorithmId::ALL {
+            let sizes = id.sizes();
+            assert_eq!(sizes.ciphertext.is_some(), id.kind() == AlgorithmKind::Kem);
+            assert_eq!(
+                sizes.signature.is_some(),
+                id.kind() == AlgorithmKind::Signature
+            );
+            assert_eq!(sizes.tag.is_some(), id.kind() == AlgorithmKind::Aead);
+        }
+    }
+}
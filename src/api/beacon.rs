@@ -0,0 +1,334 @@
+//! Public randomness beacon verification.
+//!
+//! # Purpose
+//!
+//! Key-generation ceremonies sometimes want a publicly auditable
+//! contribution to their randomness: anyone can later check that the keys
+//! depended on beacon outputs that did not exist before the ceremony.
+//! This module verifies signed, hash-chained beacon records and mixes
+//! their outputs into Citadel's randomness.
+//!
+//! # Records
+//!
+//! A [`BeaconRecord`] is an index, the digest of the previous record, a
+//! 64-byte output and a signature over
+//!
+//! ```text
+//! "citadel-beacon-v1" 0x00 index (u64, big-endian) previous output
+//! ```
+//!
+//! `previous` is [`BeaconRecord::digest`] of the preceding record, so a
+//! [`BeaconChain`] that has accepted record `i` only accepts the one true
+//! record `i + 1`. Signatures use any [`EnvelopeVerifier`], normally an
+//! ML-DSA-87 key.
+//!
+//! # Security
+//!
+//! - Beacon outputs are public. [`BeaconEntropy`] uses them as the key of a
+//!   KMAC256 extractor over fresh OS randomness, never as a substitute for
+//!   it: a malicious beacon cannot make the output predictable, and an
+//!   honest one makes it auditable
+//! - A chain started with [`BeaconChain::new`] trusts its first record's
+//!   position; pin a known record with [`BeaconChain::from_checkpoint`]
+//!
+//! # Example
+//!
+//! ```ignore
+//! let mut chain = BeaconChain::from_checkpoint(beacon_key, index, digest);
+//! let pulse = chain.verify(&record)?;
+//!
+//! let entropy = Arc::new(BeaconEntropy::new(&[pulse]));
+//! let keys = OpContext::new()
+//!     .with_entropy(entropy)
+//!     .run(MlDsa87::generate_keypair)?;
+//! ```
+
+use core::fmt;
+
+use crate::algorithms::classical::keccak::sha3_512;
+use crate::algorithms::classical::kmac::Kmac;
+use crate::context::{EntropySource, OsEntropy};
+#[cfg(feature = "signing")]
+use crate::dsse::EnvelopeSigner;
+use crate::dsse::EnvelopeVerifier;
+use crate::errors::{CryptoError, Result};
+use crate::memory::SensitiveBytes;
+
+/// Domain separation prefix of signed beacon records.
+pub const RECORD_DOMAIN: &str = "citadel-beacon-v1";
+
+/// Customization string of the [`BeaconEntropy`] extractor.
+const EXTRACT_CUSTOMIZATION: &[u8] = b"citadel-beacon-extract";
+
+/// One signed beacon output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BeaconRecord {
+    /// Position in the chain.
+    pub index: u64,
+    /// [`digest`](Self::digest) of the record at `index - 1`.
+    pub previous: [u8; 64],
+    /// The published randomness.
+    pub output: [u8; 64],
+    /// Signature over [`signed_message`](Self::signed_message).
+    pub signature: Vec<u8>,
+}
+
+impl BeaconRecord {
+    /// Sign a new record, as a beacon operator.
+    ///
+    /// # Errors
+    ///
+    /// - Any error from the underlying signature scheme
+    #[cfg(feature = "signing")]
+    pub fn sign(
+        index: u64,
+        previous: [u8; 64],
+        output: [u8; 64],
+        signer: &impl EnvelopeSigner,
+    ) -> Result<Self> {
+        let mut record = Self {
+            index,
+            previous,
+            output,
+            signature: Vec::new(),
+        };
+        record.signature = signer.sign_message(&record.signed_message())?;
+        Ok(record)
+    }
+
+    /// The bytes the signature covers.
+    pub fn signed_message(&self) -> Vec<u8> {
+        [
+            RECORD_DOMAIN.as_bytes(),
+            &[0],
+            &self.index.to_be_bytes(),
+            &self.previous,
+            &self.output,
+        ]
+        .concat()
+    }
+
+    /// SHA3-512 of the signed message; the next record's `previous`.
+    ///
+    /// The signature is excluded, since ML-DSA signatures are randomized.
+    pub fn digest(&self) -> [u8; 64] {
+        sha3_512(&[&self.signed_message()])
+    }
+}
+
+/// A beacon output whose signature and chain position were verified.
+///
+/// Only [`BeaconChain::verify`] creates these.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerifiedBeacon {
+    index: u64,
+    output: [u8; 64],
+}
+
+impl VerifiedBeacon {
+    /// Position in the chain.
+    #[inline]
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+
+    /// The published randomness.
+    #[inline]
+    pub fn output(&self) -> &[u8; 64] {
+        &self.output
+    }
+}
+
+/// Verifies consecutive records of one beacon.
+pub struct BeaconChain<V> {
+    verifier: V,
+    head: Option<(u64, [u8; 64])>,
+}
+
+impl<V: EnvelopeVerifier> BeaconChain<V> {
+    /// A chain that accepts any correctly signed first record.
+    pub fn new(verifier: V) -> Self {
+        Self {
+            verifier,
+            head: None,
+        }
+    }
+
+    /// A chain continuing from a trusted record, given its index and
+    /// [`digest`](BeaconRecord::digest).
+    pub fn from_checkpoint(verifier: V, index: u64, digest: [u8; 64]) -> Self {
+        Self {
+            verifier,
+            head: Some((index, digest)),
+        }
+    }
+
+    /// Index of the last accepted record.
+    pub fn head_index(&self) -> Option<u64> {
+        self.head.map(|(index, _)| index)
+    }
+
+    /// Verify `record` and advance the chain to it.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::VerificationFailed`: If the signature is invalid, or
+    ///   the record does not directly follow the last accepted one
+    /// - `MisuseError::InvalidSignatureLength`: If the signature has the
+    ///   wrong size for the verifier's scheme
+    pub fn verify(&mut self, record: &BeaconRecord) -> Result<VerifiedBeacon> {
+        if let Some((index, digest)) = self.head
+            && (index.checked_add(1) != Some(record.index) || digest != record.previous)
+        {
+            return Err(CryptoError::VerificationFailed.into());
+        }
+        self.verifier
+            .verify_message(&record.signed_message(), &record.signature)?;
+        self.head = Some((record.index, record.digest()));
+        Ok(VerifiedBeacon {
+            index: record.index,
+            output: record.output,
+        })
+    }
+}
+
+impl<V> fmt::Debug for BeaconChain<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BeaconChain")
+            .field("head_index", &self.head.map(|(index, _)| index))
+            .finish_non_exhaustive()
+    }
+}
+
+/// An [`EntropySource`] mixing verified beacon outputs into OS randomness.
+///
+/// Each fill draws 64 fresh bytes from the OS and extracts the output
+/// with KMAC256 keyed by a digest of the beacon outputs. Install it with
+/// [`OpContext::with_entropy`](crate::context::OpContext::with_entropy).
+#[derive(Clone)]
+pub struct BeaconEntropy {
+    transcript: [u8; 64],
+}
+
+impl BeaconEntropy {
+    /// Mix in `beacons`, in order.
+    pub fn new(beacons: &[VerifiedBeacon]) -> Self {
+        let mut parts: Vec<&[u8]> = vec![RECORD_DOMAIN.as_bytes()];
+        let indices: Vec<[u8; 8]> = beacons.iter().map(|b| b.index.to_be_bytes()).collect();
+        for (beacon, index) in beacons.iter().zip(&indices) {
+            parts.push(index);
+            parts.push(&beacon.output);
+        }
+        Self {
+            transcript: sha3_512(&parts),
+        }
+    }
+
+    /// Digest of the mixed-in outputs, for the ceremony's audit record.
+    pub fn transcript(&self) -> &[u8; 64] {
+        &self.transcript
+    }
+}
+
+impl EntropySource for BeaconEntropy {
+    fn fill(&self, buf: &mut [u8]) -> Result<()> {
+        let mut seed = SensitiveBytes::<64>::zeroed();
+        // Straight from the OS: the context source may be this one
+        OsEntropy.fill(seed.as_bytes_mut())?;
+        let mut kmac = Kmac::kmac256(&self.transcript, EXTRACT_CUSTOMIZATION);
+        kmac.update(seed.as_bytes());
+        kmac.finalize_into(buf);
+        Ok(())
+    }
+}
+
+impl fmt::Debug for BeaconEntropy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BeaconEntropy").finish_non_exhaustive()
+    }
+}
+
+#[cfg(all(test, feature = "signing"))]
+mod tests {
+    use super::*;
+    use crate::sign::{Ed25519, Ed25519VerifyingKey};
+
+    fn chain_of(count: u64) -> (Ed25519VerifyingKey, Vec<BeaconRecord>) {
+        let (verifying_key, signing_key) = Ed25519::generate_keypair().unwrap();
+        let mut records: Vec<BeaconRecord> = Vec::new();
+        let mut previous = [0u8; 64];
+        for index in 0..count {
+            let record =
+                BeaconRecord::sign(index, previous, [index as u8; 64], &signing_key).unwrap();
+            previous = record.digest();
+            records.push(record);
+        }
+        (verifying_key, records)
+    }
+
+    #[test]
+    fn follows_chain() {
+        let (verifying_key, records) = chain_of(3);
+        let mut chain = BeaconChain::new(verifying_key.clone());
+        for record in &records {
+            let beacon = chain.verify(record).unwrap();
+            assert_eq!(beacon.output(), &record.output);
+        }
+        assert_eq!(chain.head_index(), Some(2));
+
+        let mut pinned = BeaconChain::from_checkpoint(verifying_key, 0, records[0].digest());
+        assert!(pinned.verify(&records[1]).is_ok());
+    }
+
+    #[test]
+    fn rejects_breaks_in_the_chain() {
+        let (verifying_key, records) = chain_of(3);
+        let expect_failure = |chain: &mut BeaconChain<Ed25519VerifyingKey>, record| {
+            let error = chain.verify(record).unwrap_err();
+            assert_eq!(error.crypto(), Some(CryptoError::VerificationFailed));
+        };
+
+        // Skipped and replayed records
+        let mut chain = BeaconChain::new(verifying_key.clone());
+        chain.verify(&records[0]).unwrap();
+        expect_failure(&mut chain, &records[2]);
+        expect_failure(&mut chain, &records[0]);
+
+        // Tampered output, index and signature
+        let mut tampered = records[1].clone();
+        tampered.output[0] ^= 1;
+        expect_failure(&mut chain, &tampered);
+        let mut tampered = records[1].clone();
+        tampered.signature[0] ^= 1;
+        expect_failure(&mut chain, &tampered);
+        assert_eq!(chain.head_index(), Some(0));
+        chain.verify(&records[1]).unwrap();
+
+        // Correctly signed by someone else
+        let (_, other) = chain_of(1);
+        expect_failure(&mut BeaconChain::new(verifying_key), &other[0]);
+    }
+
+    #[cfg(feature = "mldsa")]
+    #[test]
+    fn ml_dsa_beacon_feeds_keygen() {
+        use crate::context::OpContext;
+        use crate::sign::MlDsa87;
+        use std::sync::Arc;
+
+        let (verifying_key, signing_key) = MlDsa87::generate_keypair().unwrap();
+        let record = BeaconRecord::sign(7, [0u8; 64], [0x5a; 64], &signing_key).unwrap();
+        let beacon = BeaconChain::new(verifying_key).verify(&record).unwrap();
+
+        let entropy = BeaconEntropy::new(&[beacon]);
+        assert_ne!(entropy.transcript(), BeaconEntropy::new(&[]).transcript());
+        let mut a = [0u8; 100];
+        let mut b = [0u8; 100];
+        entropy.fill(&mut a).unwrap();
+        entropy.fill(&mut b).unwrap();
+        assert_ne!(a, b);
+
+        let context = OpContext::new().with_entropy(Arc::new(entropy));
+        assert!(context.run(Ed25519::generate_keypair).is_ok());
+    }
+}
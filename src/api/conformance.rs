@@ -0,0 +1,500 @@
+//! Conformance checks for storage and transport stacks.
+//!
+//! # Purpose
+//!
+//! Citadel's formats detect modification, but only of the bytes that
+//! reach the opener. A stack that re-frames, caches, pads or "repairs"
+//! stored objects can undo that without any error showing up. [`run`]
+//! seals a sample of each format, modifies it in every way the format
+//! must detect, passes the intact and modified copies through the
+//! caller's stack, and opens whatever comes back (feature
+//! `conformance`). Integrators run it in their own test suites against a
+//! real store or channel.
+//!
+//! # Checks
+//!
+//! For [envelopes](crate::envelope), [data-key envelopes](crate::dek) and
+//! [streams](crate::stream):
+//!
+//! - [`Mutation::Intact`]: the unmodified sample comes back and opens to
+//!   its plaintext
+//! - [`Mutation::Tamper`]: one bit flipped in each header field and each
+//!   section after it
+//! - [`Mutation::Truncate`]: cut before each section, and one byte short
+//! - [`Mutation::SwapAlgorithm`]: the algorithm ID replaced by every other
+//!   registered one
+//! - [`Mutation::Replay`]: sections of another sample under the same key
+//!   spliced in; for streams, chunks duplicated, reordered or dropped
+//!
+//! A modified sample passes when the stack rejects it, returns the
+//! original bytes, or returns bytes that fail to open. It fails only when
+//! modified bytes open.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use citadel::conformance;
+//!
+//! let report = conformance::run(|bytes| {
+//!     bucket.put("conformance-probe", bytes)?;
+//!     bucket.get("conformance-probe")
+//! })?;
+//! for check in report.failures() {
+//!     eprintln!("{check}");
+//! }
+//! assert!(report.passed());
+//! ```
+
+use core::fmt;
+
+use crate::algorithm::AlgorithmId;
+use crate::dek::{self, DataKeyEnvelope, Kek, Recipient};
+use crate::encoding::ParseBudget;
+use crate::envelope::{self, Envelope};
+use crate::errors::{MisuseError, Result};
+use crate::stream::{self, StreamingDecryptor, StreamingEncryptor};
+
+/// AEAD of every sample.
+const ALGORITHM: AlgorithmId = AlgorithmId::Aes256GcmSiv;
+
+/// Key of envelopes and streams, and KEK of the first data-key recipient.
+const KEY: [u8; 32] = [0x5a; 32];
+
+/// KEK of the second data-key recipient, the one samples open with.
+const OTHER_KEY: [u8; 32] = [0xa5; 32];
+
+const KEY_IDS: [&[u8]; 2] = [b"conformance-a", b"conformance-b"];
+
+const ASSOCIATED_DATA: &[u8] = b"citadel conformance";
+
+/// Full chunks in the stream sample, before a shorter last one.
+const STREAM_CHUNKS: usize = 3;
+
+/// Wire format a check exercises.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Format {
+    /// An envelope from [`envelope::seal`].
+    Envelope,
+    /// A data-key envelope from [`dek::seal`].
+    DataKeyEnvelope,
+    /// A [`stream`] header followed by its chunks.
+    Stream,
+}
+
+/// How a check modifies its sample.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Mutation {
+    /// Not at all: the stack must preserve the sample.
+    Intact,
+    /// One bit flipped.
+    Tamper,
+    /// Bytes cut off the end.
+    Truncate,
+    /// Algorithm ID replaced.
+    SwapAlgorithm,
+    /// Sections of another sample spliced in, or chunks rearranged.
+    Replay,
+}
+
+/// Outcome of one check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    format: Format,
+    mutation: Mutation,
+    target: String,
+    passed: bool,
+}
+
+impl Check {
+    /// The format checked.
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    /// How the sample was modified.
+    pub fn mutation(&self) -> Mutation {
+        self.mutation
+    }
+
+    /// What was modified, such as `"suite"` or `"chunk 1 replayed"`.
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Whether the stack preserved the guarantee.
+    pub fn passed(&self) -> bool {
+        self.passed
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let outcome = if self.passed { "passed" } else { "FAILED" };
+        write!(
+            f,
+            "{:?} {:?} {}: {outcome}",
+            self.format, self.mutation, self.target
+        )
+    }
+}
+
+/// Outcomes of every check of a [`run`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    checks: Vec<Check>,
+}
+
+impl Report {
+    /// Every check, in the order run.
+    pub fn checks(&self) -> &[Check] {
+        &self.checks
+    }
+
+    /// The checks that failed.
+    pub fn failures(&self) -> impl Iterator<Item = &Check> {
+        self.checks.iter().filter(|check| !check.passed)
+    }
+
+    /// Whether every check passed.
+    pub fn passed(&self) -> bool {
+        self.failures().next().is_none()
+    }
+}
+
+/// Pass samples of every format, intact and modified, through
+/// `transport` and check what comes back.
+///
+/// `transport` stores or sends its input and returns what a reader would
+/// get, or an error if the stack refuses it. Its error type is not
+/// inspected.
+///
+/// # Errors
+///
+/// - `CryptoError::RandomnessUnavailable`: If the system RNG fails while
+///   sealing the samples
+pub fn run<E>(
+    mut transport: impl FnMut(&[u8]) -> core::result::Result<Vec<u8>, E>,
+) -> Result<Report> {
+    let mut checks = Vec::new();
+    for (sample, probes) in [envelope_sample()?, dek_sample()?, stream_sample()?] {
+        for probe in probes {
+            let passed = match transport(&probe.bytes) {
+                Err(_) => probe.mutation != Mutation::Intact,
+                Ok(returned) if probe.mutation == Mutation::Intact => {
+                    (sample.open)(&returned).is_ok_and(|plaintext| plaintext == sample.plaintext)
+                }
+                Ok(returned) => returned == sample.bytes || (sample.open)(&returned).is_err(),
+            };
+            checks.push(Check {
+                format: sample.format,
+                mutation: probe.mutation,
+                target: probe.target,
+                passed,
+            });
+        }
+    }
+    Ok(Report { checks })
+}
+
+/// A sealed sample and how to open it.
+struct Sample {
+    format: Format,
+    bytes: Vec<u8>,
+    plaintext: Vec<u8>,
+    open: fn(&[u8]) -> Result<Vec<u8>>,
+}
+
+/// One input to the stack.
+struct Probe {
+    mutation: Mutation,
+    target: String,
+    bytes: Vec<u8>,
+}
+
+/// Probes of `bytes`: intact, then tampered in and truncated before each
+/// of `sections`, named with their start offsets in order, then with the
+/// algorithm ID at `algorithm` swapped.
+fn probes(bytes: &[u8], sections: &[(&str, usize)], algorithm: usize) -> Vec<Probe> {
+    let mut probes = vec![Probe {
+        mutation: Mutation::Intact,
+        target: "sample".into(),
+        bytes: bytes.to_vec(),
+    }];
+    for &(name, start) in sections {
+        let mut tampered = bytes.to_vec();
+        tampered[start] ^= 1;
+        probes.push(Probe {
+            mutation: Mutation::Tamper,
+            target: name.into(),
+            bytes: tampered,
+        });
+    }
+    for &(name, start) in sections {
+        probes.push(Probe {
+            mutation: Mutation::Truncate,
+            target: format!("before {name}"),
+            bytes: bytes[..start].to_vec(),
+        });
+    }
+    probes.push(Probe {
+        mutation: Mutation::Truncate,
+        target: "last byte".into(),
+        bytes: bytes[..bytes.len() - 1].to_vec(),
+    });
+    for other in AlgorithmId::ALL.iter().filter(|&&other| other != ALGORITHM) {
+        let mut swapped = bytes.to_vec();
+        swapped[algorithm..algorithm + AlgorithmId::SIZE].copy_from_slice(&other.to_bytes());
+        probes.push(Probe {
+            mutation: Mutation::SwapAlgorithm,
+            target: other.name().into(),
+            bytes: swapped,
+        });
+    }
+    probes
+}
+
+fn replay(target: &str, bytes: Vec<u8>) -> Probe {
+    Probe {
+        mutation: Mutation::Replay,
+        target: target.into(),
+        bytes,
+    }
+}
+
+fn envelope_sample() -> Result<(Sample, Vec<Probe>)> {
+    let plaintext = b"conformance envelope".to_vec();
+    let bytes = envelope::seal(ALGORITHM, &KEY, &plaintext, ASSOCIATED_DATA)?;
+    let other = envelope::seal(ALGORITHM, &KEY, &plaintext, b"citadel conformance 2")?;
+
+    let body = envelope::FIXED_HEADER_SIZE + ASSOCIATED_DATA.len();
+    let other_body = other.len() - (bytes.len() - body);
+    let sizes = ALGORITHM.sizes();
+    let (nonce, tag) = (sizes.nonce.unwrap_or(0), sizes.tag.unwrap_or(0));
+    let sections = [
+        ("magic", 0),
+        ("version", envelope::MAGIC.len()),
+        ("suite", envelope::MAGIC.len() + 1),
+        ("aad_len", envelope::MAGIC.len() + 1 + AlgorithmId::SIZE),
+        ("aad", envelope::FIXED_HEADER_SIZE),
+        ("nonce", body),
+        ("ciphertext", body + nonce),
+        ("tag", bytes.len() - tag),
+    ];
+    let mut probes = probes(&bytes, &sections, envelope::MAGIC.len() + 1);
+    probes.push(replay(
+        "body of another envelope",
+        [&bytes[..body], &other[other_body..]].concat(),
+    ));
+    probes.push(replay(
+        "header of another envelope",
+        [&other[..other_body], &bytes[body..]].concat(),
+    ));
+
+    let sample = Sample {
+        format: Format::Envelope,
+        bytes,
+        plaintext,
+        open: |bytes| Envelope::parse(bytes, &ParseBudget::DEFAULT)?.open(&KEY),
+    };
+    Ok((sample, probes))
+}
+
+fn dek_sample() -> Result<(Sample, Vec<Probe>)> {
+    let recipients = [
+        Recipient::aes_kw(KEY_IDS[0], &KEY)?,
+        Recipient::aes_kw(KEY_IDS[1], &OTHER_KEY)?,
+    ];
+    let plaintext = b"conformance data-key envelope".to_vec();
+    let bytes = dek::seal(ALGORITHM, &recipients, &plaintext, ASSOCIATED_DATA)?;
+    let other = dek::seal(ALGORITHM, &recipients, &plaintext, ASSOCIATED_DATA)?;
+
+    let sizes = ALGORITHM.sizes();
+    let wrapped_size = sizes.secret_key.unwrap_or(0) + 8;
+    let first = dek::FIXED_HEADER_SIZE;
+    let second = first + 2 + KEY_IDS[0].len() + wrapped_size;
+    let mac = second + 2 + KEY_IDS[1].len() + wrapped_size;
+    let body = mac + dek::HEADER_MAC_SIZE;
+    let sections = [
+        ("magic", 0),
+        ("version", dek::MAGIC.len()),
+        ("algorithm", dek::MAGIC.len() + 1),
+        ("count", dek::MAGIC.len() + 1 + AlgorithmId::SIZE),
+        ("first recipient", first),
+        ("first wrapped key", second - wrapped_size),
+        ("second recipient", second),
+        ("second wrapped key", mac - wrapped_size),
+        ("header_mac", mac),
+        ("nonce", body),
+        ("ciphertext", body + sizes.nonce.unwrap_or(0)),
+        ("tag", bytes.len() - sizes.tag.unwrap_or(0)),
+    ];
+    let mut probes = probes(&bytes, &sections, dek::MAGIC.len() + 1);
+    probes.push(replay(
+        "payload of another envelope",
+        [&bytes[..body], &other[body..]].concat(),
+    ));
+    probes.push(replay(
+        "first recipient of another envelope",
+        [&bytes[..first], &other[first..second], &bytes[second..]].concat(),
+    ));
+    probes.push(replay(
+        "header of another envelope",
+        [&other[..body], &bytes[body..]].concat(),
+    ));
+
+    let sample = Sample {
+        format: Format::DataKeyEnvelope,
+        bytes,
+        plaintext,
+        open: |bytes| {
+            let kek = Kek::aes_kw(KEY_IDS[1], &OTHER_KEY)?;
+            DataKeyEnvelope::parse(bytes, &ParseBudget::DEFAULT)?.open(&kek, ASSOCIATED_DATA)
+        },
+    };
+    Ok((sample, probes))
+}
+
+/// A stream of `plaintext` under a fresh header.
+fn seal_stream(plaintext: &[u8]) -> Result<Vec<u8>> {
+    let mut encryptor = StreamingEncryptor::with_chunk_size(
+        ALGORITHM,
+        &KEY,
+        ASSOCIATED_DATA,
+        stream::MIN_CHUNK_SIZE,
+    )?;
+    let mut bytes = encryptor.header().to_vec();
+    let mut chunks = plaintext.chunks(stream::MIN_CHUNK_SIZE);
+    for chunk in chunks.by_ref().take(STREAM_CHUNKS) {
+        bytes.extend(encryptor.encrypt_chunk(chunk)?);
+    }
+    bytes.extend(encryptor.finish(chunks.next().unwrap_or(&[]))?);
+    Ok(bytes)
+}
+
+/// Read a stream as the I/O adapters do: full chunks, then a shorter or
+/// full last one.
+fn open_stream(bytes: &[u8]) -> Result<Vec<u8>> {
+    let Some((header, mut chunks)) = bytes.split_first_chunk::<{ stream::HEADER_SIZE }>() else {
+        return Err(MisuseError::InvalidCiphertextLength.into());
+    };
+    let mut decryptor = StreamingDecryptor::new(ALGORITHM, &KEY, header, ASSOCIATED_DATA)?;
+    let chunk_len = decryptor.chunk_size() + ALGORITHM.sizes().tag.unwrap_or(0);
+    let mut plaintext = Vec::new();
+    while chunks.len() > chunk_len {
+        let (chunk, rest) = chunks.split_at(chunk_len);
+        plaintext.extend(decryptor.decrypt_chunk(chunk)?);
+        chunks = rest;
+    }
+    plaintext.extend(decryptor.finish(chunks)?);
+    Ok(plaintext)
+}
+
+fn stream_sample() -> Result<(Sample, Vec<Probe>)> {
+    let plaintext: Vec<u8> = (0..STREAM_CHUNKS * stream::MIN_CHUNK_SIZE + 100)
+        .map(|i| i as u8)
+        .collect();
+    let bytes = seal_stream(&plaintext)?;
+    let other = seal_stream(&plaintext)?;
+
+    let chunk_len = stream::MIN_CHUNK_SIZE + ALGORITHM.sizes().tag.unwrap_or(0);
+    let chunk = |i: usize| stream::HEADER_SIZE + i * chunk_len;
+    let last = chunk(STREAM_CHUNKS);
+    let sections = [
+        ("algorithm", 0),
+        ("chunk_size", AlgorithmId::SIZE),
+        ("salt", AlgorithmId::SIZE + 4),
+        ("chunk 0", chunk(0)),
+        ("chunk 1", chunk(1)),
+        ("chunk 2", chunk(2)),
+        ("last chunk", last),
+    ];
+    let mut probes = probes(&bytes, &sections, 0);
+    let (header, chunks) = bytes.split_at(stream::HEADER_SIZE);
+    let [c0, c1, c2] = [0, 1, 2].map(|i| &chunks[i * chunk_len..(i + 1) * chunk_len]);
+    let tail = &bytes[last..];
+    probes.push(replay(
+        "chunk 0 replayed",
+        [header, c0, c0, c1, c2, tail].concat(),
+    ));
+    probes.push(replay(
+        "chunks 0 and 1 swapped",
+        [header, c1, c0, c2, tail].concat(),
+    ));
+    probes.push(replay("chunk 1 dropped", [header, c0, c2, tail].concat()));
+    probes.push(replay(
+        "last chunk of another stream",
+        [&bytes[..last], &other[last..]].concat(),
+    ));
+    probes.push(replay(
+        "header of another stream",
+        [&other[..stream::HEADER_SIZE], chunks].concat(),
+    ));
+
+    let sample = Sample {
+        format: Format::Stream,
+        bytes,
+        plaintext,
+        open: open_stream,
+    };
+    Ok((sample, probes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_detect_every_mutation() {
+        let report = run(|bytes| Ok::<_, ()>(bytes.to_vec())).unwrap();
+        assert!(
+            report.passed(),
+            "{:?}",
+            report.failures().collect::<Vec<_>>()
+        );
+        for format in [Format::Envelope, Format::DataKeyEnvelope, Format::Stream] {
+            for mutation in [
+                Mutation::Intact,
+                Mutation::Tamper,
+                Mutation::Truncate,
+                Mutation::SwapAlgorithm,
+                Mutation::Replay,
+            ] {
+                assert!(
+                    report
+                        .checks()
+                        .iter()
+                        .any(|c| c.format() == format && c.mutation() == mutation)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn reports_stacks_that_break_guarantees() {
+        // Dropping everything loses intact samples
+        let report = run(|_| Err(())).unwrap();
+        let failures: Vec<_> = report.failures().collect();
+        assert_eq!(failures.len(), 3);
+        assert!(failures.iter().all(|c| c.mutation() == Mutation::Intact));
+
+        // A gateway that re-seals envelopes instead of storing them passes
+        // on whatever it received
+        let report = run(|bytes| {
+            if bytes.starts_with(&envelope::MAGIC) {
+                envelope::seal(ALGORITHM, &KEY, b"conformance envelope", ASSOCIATED_DATA)
+            } else {
+                Ok(bytes.to_vec())
+            }
+        })
+        .unwrap();
+        let failures: Vec<_> = report.failures().collect();
+        assert!(!failures.is_empty());
+        assert!(
+            failures
+                .iter()
+                .all(|c| c.format() == Format::Envelope && c.mutation() != Mutation::Intact)
+        );
+    }
+}
@@ -0,0 +1,1556 @@
+//! Envelope encryption with data keys.
+//!
+//! # Purpose
+//!
+//! Encrypting every record directly under a long-lived key makes rotating
+//! or sharing that key expensive. Envelope encryption instead draws a
+//! fresh data-encryption key (DEK) per payload, encrypts the payload under
+//! it, and stores the DEK wrapped under one or more key-encryption keys
+//! (KEKs). Rotating a KEK only rewraps 40 bytes per record, and adding a
+//! reader adds one wrapped copy.
+//!
+//! [`seal`] does all of it in one call and produces a single encoding;
+//! [`DataKeyEnvelope::parse`] reads it back and opens it with any one of
+//! the KEKs. [`rewrap`] replaces the recipients of an envelope without
+//! touching its payload.
+//!
+//! # Key Wrapping
+//!
+//! Each [`Recipient`] is one of:
+//!
+//! - AES-256 key wrap (RFC 3394) under a 32-byte symmetric KEK, typically
+//!   held in a KMS or HSM
+//! - A KEM public key ([`AlgorithmKind::Kem`]): a fresh encapsulation is
+//!   made per envelope, HKDF-SHA256 derives a KEK from the shared secret,
+//!   and the DEK is AES-key-wrapped under it
+//! - A [threshold](Recipient::threshold) of custodians: the DEK is split
+//!   into Shamir shares over GF(2^8), one wrapped to each custodian as
+//!   above, and any `threshold` of them together recover it with
+//!   [`DataKeyEnvelope::unwrap_threshold`]
+//!
+//! Recipients carry a caller-chosen key ID, so a reader holding several
+//! KEKs can find the wrapped copy meant for it.
+//!
+//! # Recovery
+//!
+//! A [`RecoveryPolicy`] adds an organization's recovery key to every
+//! envelope it seals, as one more recipient whose stanza is flagged in the
+//! header. The flag is covered by the header MAC, so it cannot be set or
+//! cleared without the DEK, and [`rewrap`] carries the flagged stanza over
+//! unchanged: rotating the other recipients does not drop escrow.
+//!
+//! # Deterministic Sealing
+//!
+//! A [`DeterministicPolicy`] seals content-addressed or deduplicated
+//! storage: the DEK and nonce are derived from the recipients' KEKs, a
+//! per-tenant secret and a keyed hash of the plaintext and associated
+//! data, so identical inputs produce byte-identical envelopes. They open
+//! like any other envelope. This is convergent encryption, and it gives
+//! up guarantees that [`seal`] provides:
+//!
+//! - Equal plaintexts are visible as equal envelopes to anyone who can
+//!   see the stored bytes, including the storage provider
+//! - Anyone able to derive the DEK, that is holding a KEK and the tenant
+//!   secret, can confirm a guessed plaintext by sealing it and comparing.
+//!   Low-entropy or templated content (forms, salaries, short files with
+//!   one varying field) must not be sealed deterministically
+//! - The tenant secret, mixed in by default, confines both leaks to one
+//!   tenant: envelopes of different tenants cannot be compared, and a
+//!   tenant cannot probe another's data. Deduplicating across tenants
+//!   needs [`DeterministicPolicy::without_tenant_secret`] and accepts both
+//!   leaks across all of them
+//! - Only AES key-wrap recipients are allowed, as KEM encapsulation and
+//!   threshold sharing are randomized
+//! - With the `nonce-reuse-debug` feature, sealing the same input twice
+//!   on one thread is reported as nonce reuse, which it is, by design
+//!
+//! # Format
+//!
+//! ```text
+//! magic "CTDK" (4) || version (1) || algorithm (2) || count (1)
+//!     || recipient * count || header_mac (32) || nonce || ciphertext || tag
+//!
+//! recipient = wrap (1) || key_id_len (1) || key_id
+//!     || [kem (2) || kem_ciphertext] || wrapped_dek
+//! threshold recipient = wrap (1) || key_id_len (1) || key_id
+//!     || threshold (1) || shares (1) || custodian * shares
+//! ```
+//!
+//! - `algorithm` is the big-endian [`AlgorithmId`] code of the payload
+//!   AEAD, which fixes the DEK size
+//! - `wrap` is 1 for AES key wrap and 2 for a KEM; only KEM recipients
+//!   carry the KEM's [`AlgorithmId`] and ciphertext, and 3 for a threshold
+//!   recipient. Its high bit flags the recovery recipient, of which there
+//!   is at most one
+//! - `custodian` is an AES key wrap or KEM recipient wrapping share `i`,
+//!   counting from 1, instead of the DEK
+//! - `wrapped_dek` is the DEK plus 8 bytes
+//! - `header_mac` is HMAC-SHA256 over everything before it, under a key
+//!   derived from the DEK
+//!
+//! The payload AEAD authenticates the magic, version and algorithm
+//! followed by the caller's associated data, but not the recipients: those
+//! are bound by the header MAC instead. Without the DEK, swapping, removing
+//! or altering a recipient still makes the envelope fail to open, while
+//! [`rewrap`], which has the DEK, can replace them and recompute the MAC
+//! without re-encrypting the payload. Rotating a KEK over terabytes of
+//! stored objects thus rewrites only their headers.
+//!
+//! # Security
+//!
+//! - Every envelope has its own DEK and nonce, except that deterministic
+//!   envelopes of identical inputs share theirs
+//! - Key IDs and the recipient list are sent in the clear
+//! - Any recipient can decrypt and re-seal the payload to another list;
+//!   an envelope does not prove which KEK holder created it
+//! - KEM recipients are only as strong as the KEM; use ML-KEM-768 for
+//!   post-quantum confidentiality
+//!
+//! # Example
+//!
+//! ```ignore
+//! use citadel::algorithm::AlgorithmId;
+//! use citadel::dek::{self, DataKeyEnvelope, Kek, Recipient};
+//! use citadel::encoding::ParseBudget;
+//!
+//! let recipients = [
+//!     Recipient::aes_kw(b"kms-2024", &kms_key)?,
+//!     Recipient::kem(b"backup", AlgorithmId::MlKem768, &backup_public_key)?,
+//! ];
+//! let sealed = dek::seal(AlgorithmId::Aes256GcmSiv, &recipients, b"secret", b"users.ssn:42")?;
+//!
+//! let envelope = DataKeyEnvelope::parse(&sealed, &ParseBudget::DEFAULT)?;
+//! let plaintext = envelope.open(&Kek::aes_kw(b"kms-2024", &kms_key)?, b"users.ssn:42")?;
+//! ```
+
+use core::fmt;
+
+use crate::algorithm::{AlgorithmId, AlgorithmKind};
+use crate::algorithms::classical::aes_kw;
+use crate::algorithms::classical::hkdf;
+use crate::algorithms::classical::hmac::HmacSha256;
+use crate::algorithms::classical::sha256::Sha256;
+use crate::algorithms::classical::shamir;
+use crate::dynamic;
+use crate::encoding::{ParseBudget, Reader};
+use crate::errors::{CryptoError, MisuseError, Result};
+use crate::internal::aead as internal_aead;
+use crate::internal::entropy;
+use crate::memory::{SecureBuffer, SensitiveBytes, constant_time_eq};
+
+/// First bytes of every data-key envelope.
+pub const MAGIC: [u8; 4] = *b"CTDK";
+
+/// Format version written by [`seal`].
+pub const VERSION: u8 = 2;
+
+/// Size of the fixed header fields before the recipients.
+pub const FIXED_HEADER_SIZE: usize = MAGIC.len() + 1 + AlgorithmId::SIZE + 1;
+
+/// Size of the header MAC in bytes.
+pub const HEADER_MAC_SIZE: usize = 32;
+
+/// Size of an AES key-wrap KEK in bytes.
+pub const KEK_SIZE: usize = 32;
+
+/// Most recipients one envelope can carry.
+pub const MAX_RECIPIENTS: usize = u8::MAX as usize;
+
+/// Longest key ID in bytes.
+pub const MAX_KEY_ID_LEN: usize = u8::MAX as usize;
+
+const WRAP_AES_KW: u8 = 1;
+const WRAP_KEM: u8 = 2;
+const WRAP_THRESHOLD: u8 = 3;
+
+/// Set in the wrap type of the recovery recipient.
+const RECOVERY_FLAG: u8 = 0x80;
+
+/// Domain separator of the KEK derived from a KEM shared secret.
+const KEM_KEK_LABEL: &[u8] = b"citadel dek kem-kek v1";
+
+/// Domain separator of the deterministic plaintext hash key.
+const DETERMINISTIC_HASH_LABEL: &[u8] = b"citadel dek deterministic-hash v1";
+
+/// Domain separator of the deterministic DEK and nonce.
+const DETERMINISTIC_KEY_LABEL: &[u8] = b"citadel dek deterministic-key v1";
+
+/// Shortest tenant secret of a [`DeterministicPolicy`] in bytes.
+pub const MIN_TENANT_SECRET_SIZE: usize = 32;
+
+/// Domain separator of the header MAC key derived from the DEK.
+const HEADER_MAC_LABEL: &[u8] = b"citadel dek header-mac v2";
+
+/// Size of the fields the payload AEAD authenticates: the magic, version
+/// and algorithm.
+const PAYLOAD_HEADER_SIZE: usize = MAGIC.len() + 1 + AlgorithmId::SIZE;
+
+/// How a recipient's copy of the DEK is wrapped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum KeyWrap {
+    /// AES-256 key wrap under a symmetric KEK.
+    Aes256Kw,
+    /// Encapsulation to a KEM public key.
+    Kem(AlgorithmId),
+    /// Shamir sharing of the DEK, each share wrapped to one custodian.
+    Threshold {
+        /// Number of shares that recover the DEK.
+        threshold: u8,
+        /// Number of shares, one per custodian.
+        shares: u8,
+    },
+}
+
+impl KeyWrap {
+    /// Check `key_id` and `key` for this wrap, where `key` is the KEK, the
+    /// KEM public key (`public`) or the KEM secret key.
+    fn check(self, key_id: &[u8], key: &[u8], public: bool) -> Result<()> {
+        if key_id.is_empty() || key_id.len() > MAX_KEY_ID_LEN {
+            return Err(MisuseError::InvalidParameterSet.into());
+        }
+        match self {
+            KeyWrap::Aes256Kw if key.len() == KEK_SIZE => Ok(()),
+            KeyWrap::Aes256Kw => Err(MisuseError::InvalidKeyLength.into()),
+            KeyWrap::Kem(algorithm) if algorithm.kind() != AlgorithmKind::Kem => {
+                Err(MisuseError::InvalidAlgorithmIdentifier.into())
+            }
+            KeyWrap::Kem(algorithm) if public => algorithm.check_public_key(key),
+            KeyWrap::Kem(algorithm) => algorithm.check_secret_key(key),
+            KeyWrap::Threshold { threshold, shares } if 0 < threshold && threshold <= shares => {
+                Ok(())
+            }
+            KeyWrap::Threshold { .. } => Err(MisuseError::InvalidParameterSet.into()),
+        }
+    }
+}
+
+/// A KEK the DEK is wrapped to when sealing.
+#[derive(Clone, Copy)]
+pub struct Recipient<'a> {
+    key_id: &'a [u8],
+    wrap: KeyWrap,
+    key: &'a [u8],
+    /// The share holders of a threshold recipient.
+    custodians: &'a [Recipient<'a>],
+}
+
+impl<'a> Recipient<'a> {
+    /// Wrap under the 32-byte symmetric `kek` known as `key_id`.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidKeyLength`: If `kek` is not [`KEK_SIZE`]
+    ///   bytes
+    /// - `MisuseError::InvalidParameterSet`: If `key_id` is empty or longer
+    ///   than [`MAX_KEY_ID_LEN`]
+    pub fn aes_kw(key_id: &'a [u8], kek: &'a [u8]) -> Result<Self> {
+        Self::new(key_id, KeyWrap::Aes256Kw, kek)
+    }
+
+    /// Wrap by encapsulating to the `algorithm` public key known as
+    /// `key_id`.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidAlgorithmIdentifier`: If `algorithm` is not a
+    ///   KEM
+    /// - `MisuseError::InvalidPublicKeyLength`: If `public_key` has the
+    ///   wrong length
+    /// - `MisuseError::InvalidParameterSet`: If `key_id` is empty or longer
+    ///   than [`MAX_KEY_ID_LEN`]
+    pub fn kem(key_id: &'a [u8], algorithm: AlgorithmId, public_key: &'a [u8]) -> Result<Self> {
+        Self::new(key_id, KeyWrap::Kem(algorithm), public_key)
+    }
+
+    /// Split the DEK so that any `threshold` of `custodians` recover it,
+    /// under the key ID `key_id`.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidParameterSet`: If `threshold` is 0 or more
+    ///   than the number of custodians, there are more than
+    ///   [`MAX_RECIPIENTS`] custodians, a custodian is itself a threshold
+    ///   recipient, or `key_id` is empty or longer than [`MAX_KEY_ID_LEN`]
+    pub fn threshold(
+        key_id: &'a [u8],
+        threshold: u8,
+        custodians: &'a [Recipient<'a>],
+    ) -> Result<Self> {
+        let shares =
+            u8::try_from(custodians.len()).map_err(|_| MisuseError::InvalidParameterSet)?;
+        if custodians.iter().any(|c| !c.custodians.is_empty()) {
+            return Err(MisuseError::InvalidParameterSet.into());
+        }
+        let wrap = KeyWrap::Threshold { threshold, shares };
+        wrap.check(key_id, &[], true)?;
+        Ok(Self {
+            key_id,
+            wrap,
+            key: &[],
+            custodians,
+        })
+    }
+
+    fn new(key_id: &'a [u8], wrap: KeyWrap, key: &'a [u8]) -> Result<Self> {
+        wrap.check(key_id, key, true)?;
+        Ok(Self {
+            key_id,
+            wrap,
+            key,
+            custodians: &[],
+        })
+    }
+
+    /// The key ID.
+    pub fn key_id(&self) -> &'a [u8] {
+        self.key_id
+    }
+
+    /// How the DEK is wrapped.
+    pub fn wrap(&self) -> KeyWrap {
+        self.wrap
+    }
+}
+
+/// A KEK that unwraps the DEK when opening.
+#[derive(Clone, Copy)]
+pub struct Kek<'a> {
+    key_id: &'a [u8],
+    wrap: KeyWrap,
+    key: &'a [u8],
+}
+
+impl<'a> Kek<'a> {
+    /// The 32-byte symmetric `kek` known as `key_id`.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidKeyLength`: If `kek` is not [`KEK_SIZE`]
+    ///   bytes
+    /// - `MisuseError::InvalidParameterSet`: If `key_id` is empty or longer
+    ///   than [`MAX_KEY_ID_LEN`]
+    pub fn aes_kw(key_id: &'a [u8], kek: &'a [u8]) -> Result<Self> {
+        Self::new(key_id, KeyWrap::Aes256Kw, kek)
+    }
+
+    /// The `algorithm` secret key known as `key_id`.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidAlgorithmIdentifier`: If `algorithm` is not a
+    ///   KEM
+    /// - `MisuseError::InvalidSecretKeyLength`: If `secret_key` has the
+    ///   wrong length
+    /// - `MisuseError::InvalidParameterSet`: If `key_id` is empty or longer
+    ///   than [`MAX_KEY_ID_LEN`]
+    pub fn kem(key_id: &'a [u8], algorithm: AlgorithmId, secret_key: &'a [u8]) -> Result<Self> {
+        Self::new(key_id, KeyWrap::Kem(algorithm), secret_key)
+    }
+
+    fn new(key_id: &'a [u8], wrap: KeyWrap, key: &'a [u8]) -> Result<Self> {
+        wrap.check(key_id, key, false)?;
+        Ok(Self { key_id, wrap, key })
+    }
+
+    /// The key ID.
+    pub fn key_id(&self) -> &'a [u8] {
+        self.key_id
+    }
+
+    /// How the DEK is wrapped.
+    pub fn wrap(&self) -> KeyWrap {
+        self.wrap
+    }
+}
+
+impl fmt::Debug for Recipient<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Recipient")
+            .field("key_id", &self.key_id)
+            .field("wrap", &self.wrap)
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Debug for Kek<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Kek")
+            .field("key_id", &self.key_id)
+            .field("wrap", &self.wrap)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Encrypt `plaintext` under a fresh DEK for `algorithm`, wrap the DEK to
+/// every recipient, and encode the result.
+///
+/// `associated_data` is authenticated but not stored; pass the same value
+/// to [`DataKeyEnvelope::open`].
+///
+/// # Errors
+///
+/// - `MisuseError::InvalidAlgorithmIdentifier`: If `algorithm` is not an
+///   AEAD
+/// - `MisuseError::FeatureNotEnabled`: If the AEAD's or a KEM's feature is
+///   off
+/// - `MisuseError::InvalidParameterSet`: If `recipients` is empty or has
+///   more than [`MAX_RECIPIENTS`] entries
+/// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+/// - Any error of a recipient's KEM encapsulation
+pub fn seal(
+    algorithm: AlgorithmId,
+    recipients: &[Recipient<'_>],
+    plaintext: &[u8],
+    associated_data: &[u8],
+) -> Result<Vec<u8>> {
+    seal_with(algorithm, recipients, None, plaintext, associated_data)
+}
+
+fn seal_with(
+    algorithm: AlgorithmId,
+    recipients: &[Recipient<'_>],
+    recovery: Option<Recovery<'_>>,
+    plaintext: &[u8],
+    associated_data: &[u8],
+) -> Result<Vec<u8>> {
+    let aead = aead(algorithm)?;
+    check_count(recipients, recovery)?;
+    let data_key = aead.generate_key()?;
+    let mut envelope = header(algorithm, recipients, recovery, &data_key)?;
+    let body = aead.encrypt(
+        data_key.as_slice(),
+        plaintext,
+        &payload_ad(&envelope, associated_data),
+    )?;
+    envelope.extend_from_slice(&body);
+    Ok(envelope)
+}
+
+/// Replace the recipients of `envelope` with `recipients`, keeping its
+/// payload byte for byte.
+///
+/// The DEK is unwrapped with `kek` into secure memory, the header MAC is
+/// checked, and the DEK is wrapped to `recipients` under a new header and
+/// MAC. The payload is neither decrypted nor verified, as that needs its
+/// associated data; open the result to check it. A
+/// [recovery](RecoveryPolicy) recipient is kept as it is.
+///
+/// # Errors
+///
+/// - `CryptoError::DecryptionFailed`: If `kek` does not unwrap the DEK or
+///   the header was modified
+/// - `MisuseError::InvalidParameterSet`: If `recipients` is empty or has
+///   more than [`MAX_RECIPIENTS`] entries, counting the recovery recipient
+/// - `MisuseError::FeatureNotEnabled`: If a KEM's feature is off
+/// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+/// - Any error of a recipient's KEM encapsulation
+pub fn rewrap(
+    envelope: &DataKeyEnvelope<'_>,
+    kek: &Kek<'_>,
+    recipients: &[Recipient<'_>],
+) -> Result<Vec<u8>> {
+    let recovery = envelope.recovery().copied().map(Recovery::Keep);
+    rewrap_with(envelope, kek, recipients, recovery)
+}
+
+fn rewrap_with(
+    envelope: &DataKeyEnvelope<'_>,
+    kek: &Kek<'_>,
+    recipients: &[Recipient<'_>],
+    recovery: Option<Recovery<'_>>,
+) -> Result<Vec<u8>> {
+    check_count(recipients, recovery)?;
+    let data_key = envelope.unwrap_key(kek)?;
+    envelope.verify_header(data_key.as_slice())?;
+    let mut rewrapped = header(envelope.algorithm, recipients, recovery, &data_key)?;
+    rewrapped.extend_from_slice(envelope.body);
+    Ok(rewrapped)
+}
+
+/// An organization recovery key that every envelope sealed under the
+/// policy is also wrapped to.
+///
+/// Use it in place of [`seal`] wherever escrow is required, rather than
+/// adding the recovery key to each recipient list by hand: the recovery
+/// stanza is flagged in the authenticated header, so auditors can check
+/// for it with [`DataKeyEnvelope::recovery`].
+#[derive(Debug, Clone, Copy)]
+pub struct RecoveryPolicy<'a> {
+    recipient: Recipient<'a>,
+}
+
+impl<'a> RecoveryPolicy<'a> {
+    /// Escrow to `recipient`, typically a KEM public key whose secret key
+    /// is held offline.
+    pub fn new(recipient: Recipient<'a>) -> Self {
+        Self { recipient }
+    }
+
+    /// The recovery recipient.
+    pub fn recipient(&self) -> &Recipient<'a> {
+        &self.recipient
+    }
+
+    /// [`seal`] to `recipients` and the recovery recipient.
+    ///
+    /// # Errors
+    ///
+    /// As for [`seal`], counting the recovery recipient towards
+    /// [`MAX_RECIPIENTS`].
+    pub fn seal(
+        &self,
+        algorithm: AlgorithmId,
+        recipients: &[Recipient<'_>],
+        plaintext: &[u8],
+        associated_data: &[u8],
+    ) -> Result<Vec<u8>> {
+        let recovery = Some(Recovery::Wrap(self.recipient));
+        seal_with(algorithm, recipients, recovery, plaintext, associated_data)
+    }
+
+    /// [`rewrap`] to `recipients`, replacing any recovery recipient with
+    /// this policy's, for instance to rotate the recovery key.
+    ///
+    /// # Errors
+    ///
+    /// As for [`rewrap`].
+    pub fn rewrap(
+        &self,
+        envelope: &DataKeyEnvelope<'_>,
+        kek: &Kek<'_>,
+        recipients: &[Recipient<'_>],
+    ) -> Result<Vec<u8>> {
+        rewrap_with(
+            envelope,
+            kek,
+            recipients,
+            Some(Recovery::Wrap(self.recipient)),
+        )
+    }
+}
+
+/// Deterministic sealing for deduplicated storage: identical inputs seal
+/// to identical envelopes.
+///
+/// Read the caveats under
+/// [Deterministic Sealing](self#deterministic-sealing) before using it.
+#[derive(Clone, Copy)]
+pub struct DeterministicPolicy<'a> {
+    tenant_secret: &'a [u8],
+}
+
+impl<'a> DeterministicPolicy<'a> {
+    /// Converge within the tenant holding `tenant_secret`, a uniformly
+    /// random secret kept apart from the KEKs.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidKeyLength`: If `tenant_secret` is shorter
+    ///   than [`MIN_TENANT_SECRET_SIZE`]
+    pub fn new(tenant_secret: &'a [u8]) -> Result<Self> {
+        if tenant_secret.len() < MIN_TENANT_SECRET_SIZE {
+            return Err(MisuseError::InvalidKeyLength.into());
+        }
+        Ok(Self { tenant_secret })
+    }
+
+    /// Converge across everyone sharing the KEKs, without a tenant secret.
+    ///
+    /// Any KEK holder can then compare and confirm the plaintexts of all
+    /// tenants' envelopes.
+    pub fn without_tenant_secret() -> Self {
+        Self { tenant_secret: &[] }
+    }
+
+    /// [`seal`] with a DEK and nonce derived from the KEKs of
+    /// `recipients`, the tenant secret, `algorithm`, `plaintext` and
+    /// `associated_data`.
+    ///
+    /// # Errors
+    ///
+    /// As for [`seal`], and
+    ///
+    /// - `MisuseError::InvalidParameterSet`: If a recipient is not an AES
+    ///   key-wrap recipient
+    pub fn seal(
+        &self,
+        algorithm: AlgorithmId,
+        recipients: &[Recipient<'_>],
+        plaintext: &[u8],
+        associated_data: &[u8],
+    ) -> Result<Vec<u8>> {
+        let aead = aead(algorithm)?;
+        check_count(recipients, None)?;
+        if recipients.iter().any(|r| r.wrap != KeyWrap::Aes256Kw) {
+            return Err(MisuseError::InvalidParameterSet.into());
+        }
+
+        // Keyed hash of the input under the KEKs and tenant secret
+        let mut keks = SecureBuffer::zeroed(KEK_SIZE * recipients.len());
+        for (out, recipient) in keks.as_mut_slice().chunks_mut(KEK_SIZE).zip(recipients) {
+            out.copy_from_slice(recipient.key);
+        }
+        let prk = SensitiveBytes::new(hkdf::extract::<Sha256, 32>(
+            self.tenant_secret,
+            keks.as_slice(),
+        ));
+        let mut hash_key = SensitiveBytes::<32>::zeroed();
+        hkdf::expand::<Sha256, 32>(
+            prk.as_bytes(),
+            DETERMINISTIC_HASH_LABEL,
+            hash_key.as_bytes_mut(),
+        );
+        let mut hmac = HmacSha256::new(hash_key.as_bytes());
+        hmac.update(&algorithm.to_bytes());
+        hmac.update(&(associated_data.len() as u64).to_be_bytes());
+        hmac.update(associated_data);
+        hmac.update(plaintext);
+        let digest = SensitiveBytes::new(hmac.finalize());
+
+        let mut okm = SecureBuffer::zeroed(aead.key_size() + aead.nonce_size());
+        hkdf::expand::<Sha256, 32>(
+            digest.as_bytes(),
+            DETERMINISTIC_KEY_LABEL,
+            okm.as_mut_slice(),
+        );
+        let (key, nonce) = okm.as_slice().split_at(aead.key_size());
+        let mut data_key = SecureBuffer::zeroed(aead.key_size());
+        data_key.as_mut_slice().copy_from_slice(key);
+
+        let mut envelope = header(algorithm, recipients, None, &data_key)?;
+        let ad = payload_ad(&envelope, associated_data);
+        let ciphertext = internal_aead::seal(algorithm, key, nonce, &ad, plaintext)?;
+        envelope.extend_from_slice(nonce);
+        envelope.extend_from_slice(&ciphertext);
+        Ok(envelope)
+    }
+}
+
+impl fmt::Debug for DeterministicPolicy<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeterministicPolicy")
+            .finish_non_exhaustive()
+    }
+}
+
+/// The recovery stanza of a header being written.
+#[derive(Clone, Copy)]
+enum Recovery<'a> {
+    /// Wrap the DEK to a policy's recipient.
+    Wrap(Recipient<'a>),
+    /// Copy the stanza of the envelope being rewrapped.
+    Keep(WrappedKey<'a>),
+}
+
+fn check_count(recipients: &[Recipient<'_>], recovery: Option<Recovery<'_>>) -> Result<()> {
+    let count = recipients.len() + usize::from(recovery.is_some());
+    if recipients.is_empty() || count > MAX_RECIPIENTS {
+        return Err(MisuseError::InvalidParameterSet.into());
+    }
+    Ok(())
+}
+
+/// Encode the header wrapping `data_key` to `recipients` and `recovery`,
+/// followed by its MAC.
+fn header(
+    algorithm: AlgorithmId,
+    recipients: &[Recipient<'_>],
+    recovery: Option<Recovery<'_>>,
+    data_key: &SecureBuffer,
+) -> Result<Vec<u8>> {
+    let mut header = Vec::new();
+    header.extend_from_slice(&MAGIC);
+    header.push(VERSION);
+    header.extend_from_slice(&algorithm.to_bytes());
+    header.push((recipients.len() + usize::from(recovery.is_some())) as u8);
+    for recipient in recipients {
+        wrap_to(recipient, data_key, &mut header)?;
+    }
+    match recovery {
+        Some(Recovery::Wrap(recipient)) => {
+            let start = header.len();
+            wrap_to(&recipient, data_key, &mut header)?;
+            header[start] |= RECOVERY_FLAG;
+        }
+        Some(Recovery::Keep(stanza)) => stanza.encode_to(&mut header),
+        None => {}
+    }
+    let mac = header_mac(data_key.as_slice(), &header);
+    header.extend_from_slice(&mac);
+    Ok(header)
+}
+
+/// HMAC-SHA256 of `header` under a key derived from `data_key`.
+fn header_mac(data_key: &[u8], header: &[u8]) -> [u8; HEADER_MAC_SIZE] {
+    let prk = SensitiveBytes::new(hkdf::extract::<Sha256, 32>(&[], data_key));
+    let mut mac_key = SensitiveBytes::<32>::zeroed();
+    hkdf::expand::<Sha256, 32>(prk.as_bytes(), HEADER_MAC_LABEL, mac_key.as_bytes_mut());
+    let mut hmac = HmacSha256::new(mac_key.as_bytes());
+    hmac.update(header);
+    hmac.finalize()
+}
+
+/// The payload AEAD's associated data.
+fn payload_ad(header: &[u8], associated_data: &[u8]) -> Vec<u8> {
+    [&header[..PAYLOAD_HEADER_SIZE], associated_data].concat()
+}
+
+fn wrap_to(recipient: &Recipient<'_>, data_key: &SecureBuffer, out: &mut Vec<u8>) -> Result<()> {
+    let mut wrapped = vec![0u8; data_key.len() + aes_kw::OVERHEAD];
+    match recipient.wrap {
+        KeyWrap::Threshold { threshold, shares } => {
+            out.push(WRAP_THRESHOLD);
+            push_key_id(out, recipient.key_id);
+            out.extend_from_slice(&[threshold, shares]);
+            let mut coefficients =
+                SecureBuffer::zeroed(data_key.len() * (usize::from(threshold) - 1));
+            entropy::fill(coefficients.as_mut_slice())?;
+            let mut share = SecureBuffer::zeroed(data_key.len());
+            for (x, custodian) in (1..=shares).zip(recipient.custodians) {
+                shamir::share(
+                    data_key.as_slice(),
+                    coefficients.as_slice(),
+                    x,
+                    share.as_mut_slice(),
+                );
+                wrap_to(custodian, &share, out)?;
+            }
+            return Ok(());
+        }
+        KeyWrap::Aes256Kw => {
+            out.push(WRAP_AES_KW);
+            push_key_id(out, recipient.key_id);
+            let kek = recipient.key.try_into().expect("length checked");
+            aes_kw::wrap(kek, data_key.as_slice(), &mut wrapped)?;
+        }
+        KeyWrap::Kem(algorithm) => {
+            let (kem_ciphertext, shared_secret) = kem(algorithm)?.encapsulate(recipient.key)?;
+            let kek = kem_kek(
+                algorithm,
+                recipient.key_id,
+                &kem_ciphertext,
+                shared_secret.as_bytes(),
+            );
+            out.push(WRAP_KEM);
+            push_key_id(out, recipient.key_id);
+            out.extend_from_slice(&algorithm.to_bytes());
+            out.extend_from_slice(&kem_ciphertext);
+            aes_kw::wrap(kek.as_bytes(), data_key.as_slice(), &mut wrapped)?;
+        }
+    }
+    out.extend_from_slice(&wrapped);
+    Ok(())
+}
+
+fn push_key_id(out: &mut Vec<u8>, key_id: &[u8]) {
+    out.push(key_id.len() as u8);
+    out.extend_from_slice(key_id);
+}
+
+/// KEK for a KEM recipient, bound to the algorithm, key ID and ciphertext.
+fn kem_kek(
+    algorithm: AlgorithmId,
+    key_id: &[u8],
+    kem_ciphertext: &[u8],
+    shared_secret: &[u8],
+) -> SensitiveBytes<KEK_SIZE> {
+    let prk = SensitiveBytes::new(hkdf::extract::<Sha256, 32>(&[], shared_secret));
+    let info = [
+        KEM_KEK_LABEL,
+        &algorithm.to_bytes(),
+        &[key_id.len() as u8],
+        key_id,
+        kem_ciphertext,
+    ]
+    .concat();
+    let mut kek = SensitiveBytes::zeroed();
+    hkdf::expand::<Sha256, 32>(prk.as_bytes(), &info, kek.as_bytes_mut());
+    kek
+}
+
+/// One recipient's wrapped copy of the DEK.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WrappedKey<'a> {
+    key_id: &'a [u8],
+    wrap: KeyWrap,
+    recovery: bool,
+    kem_ciphertext: &'a [u8],
+    /// The wrapped DEK, or a threshold recipient's encoded custodians.
+    wrapped_key: &'a [u8],
+    /// Size of a wrapped DEK or share.
+    wrapped_size: usize,
+}
+
+impl<'a> WrappedKey<'a> {
+    /// The recipient's key ID.
+    pub fn key_id(&self) -> &'a [u8] {
+        self.key_id
+    }
+
+    /// How the DEK is wrapped.
+    pub fn wrap(&self) -> KeyWrap {
+        self.wrap
+    }
+
+    /// Whether this is the recovery recipient of a [`RecoveryPolicy`].
+    pub fn is_recovery(&self) -> bool {
+        self.recovery
+    }
+
+    /// The share holders of a threshold recipient, in share order; empty
+    /// for other recipients.
+    pub fn custodians(&self) -> Vec<WrappedKey<'a>> {
+        let KeyWrap::Threshold { shares, .. } = self.wrap else {
+            return Vec::new();
+        };
+        let mut reader = Reader::new(self.wrapped_key);
+        (0..shares)
+            .map(|_| {
+                wrapped_key(&mut reader, self.wrapped_size, true).expect("checked when parsed")
+            })
+            .collect()
+    }
+
+    fn encode_to(&self, out: &mut Vec<u8>) {
+        let flag = if self.recovery { RECOVERY_FLAG } else { 0 };
+        match self.wrap {
+            KeyWrap::Aes256Kw => {
+                out.push(WRAP_AES_KW | flag);
+                push_key_id(out, self.key_id);
+            }
+            KeyWrap::Kem(algorithm) => {
+                out.push(WRAP_KEM | flag);
+                push_key_id(out, self.key_id);
+                out.extend_from_slice(&algorithm.to_bytes());
+            }
+            KeyWrap::Threshold { threshold, shares } => {
+                out.push(WRAP_THRESHOLD | flag);
+                push_key_id(out, self.key_id);
+                out.extend_from_slice(&[threshold, shares]);
+            }
+        }
+        out.extend_from_slice(self.kem_ciphertext);
+        out.extend_from_slice(self.wrapped_key);
+    }
+
+    fn matches(&self, kek: &Kek<'_>) -> bool {
+        self.key_id == kek.key_id && self.wrap == kek.wrap
+    }
+
+    fn unwrap_with(&self, kek: &Kek<'_>) -> Result<SecureBuffer> {
+        let mut data_key = SecureBuffer::zeroed(self.wrapped_key.len() - aes_kw::OVERHEAD);
+        match self.wrap {
+            KeyWrap::Aes256Kw => {
+                let kek = kek.key.try_into().expect("length checked");
+                aes_kw::unwrap(kek, self.wrapped_key, data_key.as_mut_slice())?;
+            }
+            KeyWrap::Kem(algorithm) => {
+                let shared_secret = kem(algorithm)?.decapsulate(kek.key, self.kem_ciphertext)?;
+                let kek = kem_kek(
+                    algorithm,
+                    self.key_id,
+                    self.kem_ciphertext,
+                    shared_secret.as_bytes(),
+                );
+                aes_kw::unwrap(kek.as_bytes(), self.wrapped_key, data_key.as_mut_slice())?;
+            }
+            // No single KEK matches a threshold recipient
+            KeyWrap::Threshold { .. } => return Err(CryptoError::DecryptionFailed.into()),
+        }
+        Ok(data_key)
+    }
+}
+
+/// A parsed data-key envelope, borrowing from its encoding.
+#[derive(Clone, PartialEq, Eq)]
+pub struct DataKeyEnvelope<'a> {
+    algorithm: AlgorithmId,
+    /// Everything before the header MAC.
+    header: &'a [u8],
+    header_mac: &'a [u8],
+    /// `nonce || ciphertext || tag`.
+    body: &'a [u8],
+    recipients: Vec<WrappedKey<'a>>,
+}
+
+impl<'a> DataKeyEnvelope<'a> {
+    /// Parse an envelope produced by [`seal`].
+    ///
+    /// Algorithms whose feature is disabled still parse; opening them
+    /// fails.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::ParseBudgetExceeded`: If `bytes`, the recipient
+    ///   count or the ciphertext exceeds `budget`
+    /// - `MisuseError::InvalidEncoding`: If the magic, the version, a wrap
+    ///   type or a key ID is invalid, more than one recipient is flagged for
+    ///   recovery, or a field, including the header MAC,
+    ///   is truncated
+    /// - `MisuseError::InvalidAlgorithmIdentifier`: If the payload
+    ///   algorithm is not an AEAD or a recipient's is not a KEM
+    /// - `MisuseError::InvalidCiphertextLength`: If the envelope is too
+    ///   short for the AEAD's nonce and tag
+    pub fn parse(bytes: &'a [u8], budget: &ParseBudget) -> Result<Self> {
+        budget.check_input(bytes.len())?;
+        let mut reader = Reader::new(bytes);
+        if reader.take(MAGIC.len())? != MAGIC || reader.byte()? != VERSION {
+            return Err(MisuseError::InvalidEncoding.into());
+        }
+        let algorithm = AlgorithmId::from_bytes(reader.take(AlgorithmId::SIZE)?)?;
+        if algorithm.kind() != AlgorithmKind::Aead {
+            return Err(MisuseError::InvalidAlgorithmIdentifier.into());
+        }
+        let sizes = algorithm.sizes();
+        let key_size = sizes.secret_key.unwrap_or(0);
+
+        let count = reader.byte()? as usize;
+        if count > budget.max_recipients {
+            return Err(MisuseError::ParseBudgetExceeded.into());
+        }
+        if count == 0 {
+            return Err(MisuseError::InvalidEncoding.into());
+        }
+        let mut recipients = Vec::with_capacity(count);
+        for _ in 0..count {
+            recipients.push(wrapped_key(
+                &mut reader,
+                key_size + aes_kw::OVERHEAD,
+                false,
+            )?);
+        }
+        if recipients.iter().filter(|r| r.recovery).count() > 1 {
+            return Err(MisuseError::InvalidEncoding.into());
+        }
+
+        let header = &bytes[..bytes.len() - reader.rest().len()];
+        let header_mac = reader.take(HEADER_MAC_SIZE)?;
+        let body = reader.rest();
+        let (nonce_size, tag_size) = (sizes.nonce.unwrap_or(0), sizes.tag.unwrap_or(0));
+        let Some(ciphertext_len) = body.len().checked_sub(nonce_size + tag_size) else {
+            return Err(MisuseError::InvalidCiphertextLength.into());
+        };
+        budget.check_output(ciphertext_len)?;
+
+        Ok(Self {
+            algorithm,
+            header,
+            header_mac,
+            body,
+            recipients,
+        })
+    }
+
+    /// The payload AEAD.
+    pub fn algorithm(&self) -> AlgorithmId {
+        self.algorithm
+    }
+
+    /// The wrapped copies of the DEK, in the order they were sealed.
+    pub fn recipients(&self) -> &[WrappedKey<'a>] {
+        &self.recipients
+    }
+
+    /// The recovery recipient, if the envelope was sealed under a
+    /// [`RecoveryPolicy`].
+    pub fn recovery(&self) -> Option<&WrappedKey<'a>> {
+        self.recipients.iter().find(|r| r.recovery)
+    }
+
+    /// The encoded envelope.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [self.header, self.header_mac, self.body].concat()
+    }
+
+    /// Unwrap the DEK with `kek`.
+    ///
+    /// The DEK alone does not prove the envelope is intact; use it with
+    /// [`open_with_data_key`](Self::open_with_data_key), for instance to
+    /// open several envelopes sharing a cached DEK, or [`rewrap`].
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::DecryptionFailed`: If no recipient has `kek`'s key
+    ///   ID and wrap, or none unwraps under it
+    /// - `MisuseError::FeatureNotEnabled`: If the KEM's feature is off
+    pub fn unwrap_key(&self, kek: &Kek<'_>) -> Result<SecureBuffer> {
+        for recipient in self.recipients.iter().filter(|r| r.matches(kek)) {
+            match recipient.unwrap_with(kek) {
+                Err(error) if error.crypto() == Some(CryptoError::DecryptionFailed) => continue,
+                result => return result,
+            }
+        }
+        Err(CryptoError::DecryptionFailed.into())
+    }
+
+    /// Unwrap the DEK from the threshold recipient `key_id` with the
+    /// custodians' `keks`.
+    ///
+    /// Each KEK unwraps the share of the custodian with its key ID and
+    /// wrap; once the recipient's threshold of shares is unwrapped they are
+    /// combined. As with [`unwrap_key`](Self::unwrap_key), the DEK alone
+    /// does not prove the envelope is intact.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::DecryptionFailed`: If there is no threshold
+    ///   recipient `key_id`, or fewer than its threshold of shares unwrap
+    ///   under `keks`
+    /// - `MisuseError::FeatureNotEnabled`: If a KEM's feature is off
+    pub fn unwrap_threshold(&self, key_id: &[u8], keks: &[Kek<'_>]) -> Result<SecureBuffer> {
+        let Some((recipient, threshold)) = self.recipients.iter().find_map(|r| match r.wrap {
+            KeyWrap::Threshold { threshold, .. } if r.key_id == key_id => {
+                Some((r, usize::from(threshold)))
+            }
+            _ => None,
+        }) else {
+            return Err(CryptoError::DecryptionFailed.into());
+        };
+        let mut shares = Vec::with_capacity(threshold);
+        for (x, custodian) in (1..=u8::MAX).zip(recipient.custodians()) {
+            if shares.len() == threshold {
+                break;
+            }
+            for kek in keks.iter().filter(|kek| custodian.matches(kek)) {
+                match custodian.unwrap_with(kek) {
+                    Ok(share) => {
+                        shares.push((x, share));
+                        break;
+                    }
+                    Err(error) if error.crypto() == Some(CryptoError::DecryptionFailed) => {}
+                    Err(error) => return Err(error),
+                }
+            }
+        }
+        if shares.len() < threshold {
+            return Err(CryptoError::DecryptionFailed.into());
+        }
+        let shares: Vec<_> = shares
+            .iter()
+            .map(|(x, share)| (*x, share.as_slice()))
+            .collect();
+        let mut data_key = SecureBuffer::zeroed(recipient.wrapped_size - aes_kw::OVERHEAD);
+        shamir::combine(&shares, data_key.as_mut_slice());
+        Ok(data_key)
+    }
+
+    /// Unwrap the DEK from the threshold recipient `key_id` with the
+    /// custodians' `keks`, then verify and decrypt the payload.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::DecryptionFailed`: If the KEKs do not unwrap the DEK,
+    ///   as for [`unwrap_threshold`](Self::unwrap_threshold),
+    ///   `associated_data` differs from sealing, or any part of the
+    ///   envelope was modified
+    /// - `MisuseError::FeatureNotEnabled`: If the AEAD's or a KEM's feature
+    ///   is off
+    pub fn open_threshold(
+        &self,
+        key_id: &[u8],
+        keks: &[Kek<'_>],
+        associated_data: &[u8],
+    ) -> Result<Vec<u8>> {
+        let data_key = self.unwrap_threshold(key_id, keks)?;
+        self.open_with_data_key(data_key.as_slice(), associated_data)
+    }
+
+    /// Unwrap the DEK with `kek`, then verify and decrypt the payload.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::DecryptionFailed`: If `kek` does not unwrap the DEK,
+    ///   `associated_data` differs from sealing, or any part of the
+    ///   envelope was modified
+    /// - `MisuseError::FeatureNotEnabled`: If the AEAD's or KEM's feature
+    ///   is off
+    pub fn open(&self, kek: &Kek<'_>, associated_data: &[u8]) -> Result<Vec<u8>> {
+        let data_key = self.unwrap_key(kek)?;
+        self.open_with_data_key(data_key.as_slice(), associated_data)
+    }
+
+    /// Verify and decrypt the payload with an already unwrapped DEK.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::DecryptionFailed`: If the DEK or `associated_data`
+    ///   is wrong, or any part of the envelope was modified
+    /// - `MisuseError::InvalidKeyLength`: If `data_key` has the wrong length
+    /// - `MisuseError::FeatureNotEnabled`: If the AEAD's feature is off
+    pub fn open_with_data_key(&self, data_key: &[u8], associated_data: &[u8]) -> Result<Vec<u8>> {
+        let aead = aead(self.algorithm)?;
+        if data_key.len() != self.algorithm.sizes().secret_key.unwrap_or(0) {
+            return Err(MisuseError::InvalidKeyLength.into());
+        }
+        self.verify_header(data_key)?;
+        aead.decrypt(
+            data_key,
+            self.body,
+            &payload_ad(self.header, associated_data),
+        )
+    }
+
+    /// Check the header MAC under `data_key`.
+    fn verify_header(&self, data_key: &[u8]) -> Result<()> {
+        if !constant_time_eq(&header_mac(data_key, self.header), self.header_mac) {
+            return Err(CryptoError::DecryptionFailed.into());
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for DataKeyEnvelope<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DataKeyEnvelope")
+            .field("algorithm", &self.algorithm)
+            .field("recipients", &self.recipients.len())
+            .field("body_len", &self.body.len())
+            .finish()
+    }
+}
+
+/// Read one recipient's wrapped key, or with `custodian` one share
+/// holder of a threshold recipient.
+fn wrapped_key<'a>(
+    reader: &mut Reader<'a>,
+    wrapped_size: usize,
+    custodian: bool,
+) -> Result<WrappedKey<'a>> {
+    let wrap_type = reader.byte()?;
+    let recovery = wrap_type & RECOVERY_FLAG != 0;
+    // Custodians are never flagged nor nested, which also bounds recursion
+    if custodian && (recovery || wrap_type == WRAP_THRESHOLD) {
+        return Err(MisuseError::InvalidEncoding.into());
+    }
+    let key_id_len = reader.byte()? as usize;
+    if key_id_len == 0 {
+        return Err(MisuseError::InvalidEncoding.into());
+    }
+    let key_id = reader.take(key_id_len)?;
+    let (wrap, kem_ciphertext) = match wrap_type & !RECOVERY_FLAG {
+        WRAP_AES_KW => (KeyWrap::Aes256Kw, &[][..]),
+        WRAP_KEM => {
+            let algorithm = AlgorithmId::from_bytes(reader.take(AlgorithmId::SIZE)?)?;
+            let Some(ciphertext_size) = algorithm
+                .sizes()
+                .ciphertext
+                .filter(|_| algorithm.kind() == AlgorithmKind::Kem)
+            else {
+                return Err(MisuseError::InvalidAlgorithmIdentifier.into());
+            };
+            (KeyWrap::Kem(algorithm), reader.take(ciphertext_size)?)
+        }
+        WRAP_THRESHOLD => {
+            let (threshold, shares) = (reader.byte()?, reader.byte()?);
+            if threshold == 0 || threshold > shares {
+                return Err(MisuseError::InvalidEncoding.into());
+            }
+            let custodians = reader.rest();
+            for _ in 0..shares {
+                wrapped_key(reader, wrapped_size, true)?;
+            }
+            return Ok(WrappedKey {
+                key_id,
+                wrap: KeyWrap::Threshold { threshold, shares },
+                recovery,
+                kem_ciphertext: &[],
+                wrapped_key: &custodians[..custodians.len() - reader.rest().len()],
+                wrapped_size,
+            });
+        }
+        _ => return Err(MisuseError::InvalidEncoding.into()),
+    };
+    Ok(WrappedKey {
+        key_id,
+        wrap,
+        recovery,
+        kem_ciphertext,
+        wrapped_key: reader.take(wrapped_size)?,
+        wrapped_size,
+    })
+}
+
+fn aead(algorithm: AlgorithmId) -> Result<&'static dyn dynamic::DynAead> {
+    if algorithm.kind() != AlgorithmKind::Aead {
+        return Err(MisuseError::InvalidAlgorithmIdentifier.into());
+    }
+    dynamic::aead(algorithm.name())
+}
+
+fn kem(algorithm: AlgorithmId) -> Result<&'static dyn dynamic::DynKem> {
+    dynamic::kem(algorithm.name())
+}
+
+#[cfg(all(test, feature = "aes-gcm-siv"))]
+mod tests {
+    use super::*;
+
+    const KMS_KEY: [u8; KEK_SIZE] = [0x11; KEK_SIZE];
+
+    #[test]
+    fn seals_to_several_recipients() {
+        let (public_key, secret_key) = dynamic::kem("X25519").unwrap().generate_keypair().unwrap();
+        let recipients = [
+            Recipient::aes_kw(b"kms", &KMS_KEY).unwrap(),
+            Recipient::kem(b"backup", AlgorithmId::X25519, &public_key).unwrap(),
+        ];
+        let sealed = seal(AlgorithmId::Aes256GcmSiv, &recipients, b"payload", b"row 7").unwrap();
+
+        let envelope = DataKeyEnvelope::parse(&sealed, &ParseBudget::DEFAULT).unwrap();
+        assert_eq!(envelope.algorithm(), AlgorithmId::Aes256GcmSiv);
+        assert_eq!(envelope.recipients().len(), 2);
+        assert_eq!(envelope.recipients()[1].key_id(), b"backup");
+        assert_eq!(envelope.to_bytes(), sealed);
+
+        let kms = Kek::aes_kw(b"kms", &KMS_KEY).unwrap();
+        let backup = Kek::kem(b"backup", AlgorithmId::X25519, secret_key.as_slice()).unwrap();
+        assert_eq!(envelope.open(&kms, b"row 7").unwrap(), b"payload");
+        assert_eq!(envelope.open(&backup, b"row 7").unwrap(), b"payload");
+        assert_eq!(
+            envelope.unwrap_key(&kms).unwrap().as_slice(),
+            envelope.unwrap_key(&backup).unwrap().as_slice()
+        );
+
+        assert_eq!(
+            envelope.open(&kms, b"row 8").unwrap_err().crypto(),
+            Some(CryptoError::DecryptionFailed)
+        );
+        let wrong = Kek::aes_kw(b"kms", &[0x22; KEK_SIZE]).unwrap();
+        assert_eq!(
+            envelope.open(&wrong, b"row 7").unwrap_err().crypto(),
+            Some(CryptoError::DecryptionFailed)
+        );
+        let unknown = Kek::aes_kw(b"other", &KMS_KEY).unwrap();
+        assert_eq!(
+            envelope.open(&unknown, b"row 7").unwrap_err().crypto(),
+            Some(CryptoError::DecryptionFailed)
+        );
+    }
+
+    #[test]
+    fn header_is_authenticated() {
+        let recipients = [Recipient::aes_kw(b"kms", &KMS_KEY).unwrap()];
+        let mut sealed = seal(AlgorithmId::Aes256GcmSiv, &recipients, b"payload", b"").unwrap();
+        // Flip a bit of the key ID and rename the matching KEK
+        sealed[FIXED_HEADER_SIZE + 2] ^= 0x20;
+        let envelope = DataKeyEnvelope::parse(&sealed, &ParseBudget::DEFAULT).unwrap();
+        let kek = Kek::aes_kw(b"Kms", &KMS_KEY).unwrap();
+        assert_eq!(
+            envelope.open(&kek, b"").unwrap_err().crypto(),
+            Some(CryptoError::DecryptionFailed)
+        );
+    }
+
+    #[test]
+    fn rewraps_without_touching_the_payload() {
+        let kms = Kek::aes_kw(b"kms", &KMS_KEY).unwrap();
+        let recipients = [Recipient::aes_kw(b"kms", &KMS_KEY).unwrap()];
+        let sealed = seal(AlgorithmId::Aes256GcmSiv, &recipients, b"payload", b"row 7").unwrap();
+        let envelope = DataKeyEnvelope::parse(&sealed, &ParseBudget::DEFAULT).unwrap();
+
+        let rotated_key = [0x22; KEK_SIZE];
+        let rotated = [Recipient::aes_kw(b"kms-2", &rotated_key).unwrap()];
+        let rewrapped = rewrap(&envelope, &kms, &rotated).unwrap();
+        let payload = sealed.len() - envelope.body.len();
+        assert_eq!(
+            rewrapped[rewrapped.len() - envelope.body.len()..],
+            sealed[payload..]
+        );
+
+        let rewrapped = DataKeyEnvelope::parse(&rewrapped, &ParseBudget::DEFAULT).unwrap();
+        let kek = Kek::aes_kw(b"kms-2", &rotated_key).unwrap();
+        assert_eq!(rewrapped.open(&kek, b"row 7").unwrap(), b"payload");
+        assert_eq!(
+            rewrapped.open(&kms, b"row 7").unwrap_err().crypto(),
+            Some(CryptoError::DecryptionFailed)
+        );
+        assert_eq!(
+            rewrap(&rewrapped, &kms, &recipients).unwrap_err().crypto(),
+            Some(CryptoError::DecryptionFailed)
+        );
+
+        // A header modified without the DEK is not rewrapped: rename the
+        // second recipient, which leaves the first one's copy intact
+        let two = [recipients[0], rotated[0]];
+        let mut tampered = seal(AlgorithmId::Aes256GcmSiv, &two, b"payload", b"").unwrap();
+        let second = DataKeyEnvelope::parse(&tampered, &ParseBudget::DEFAULT)
+            .unwrap()
+            .header
+            .len()
+            - KEK_SIZE
+            - aes_kw::OVERHEAD
+            - 1;
+        tampered[second] ^= 1;
+        let tampered = DataKeyEnvelope::parse(&tampered, &ParseBudget::DEFAULT).unwrap();
+        assert_eq!(tampered.unwrap_key(&kms).unwrap().len(), KEK_SIZE);
+        assert_eq!(
+            rewrap(&tampered, &kms, &rotated).unwrap_err().crypto(),
+            Some(CryptoError::DecryptionFailed)
+        );
+    }
+
+    #[test]
+    fn recovery_policy_survives_rewrapping() {
+        let (public_key, secret_key) = dynamic::kem("X25519").unwrap().generate_keypair().unwrap();
+        let policy = RecoveryPolicy::new(
+            Recipient::kem(b"recovery", AlgorithmId::X25519, &public_key).unwrap(),
+        );
+        let recovery = Kek::kem(b"recovery", AlgorithmId::X25519, secret_key.as_slice()).unwrap();
+        let kms = Kek::aes_kw(b"kms", &KMS_KEY).unwrap();
+        let recipients = [Recipient::aes_kw(b"kms", &KMS_KEY).unwrap()];
+        let sealed = policy
+            .seal(AlgorithmId::Aes256GcmSiv, &recipients, b"payload", b"")
+            .unwrap();
+
+        let envelope = DataKeyEnvelope::parse(&sealed, &ParseBudget::DEFAULT).unwrap();
+        assert_eq!(envelope.recipients().len(), 2);
+        assert!(!envelope.recipients()[0].is_recovery());
+        assert_eq!(envelope.recovery().unwrap().key_id(), b"recovery");
+        assert_eq!(envelope.open(&recovery, b"").unwrap(), b"payload");
+
+        // Rotating the other recipients keeps the recovery stanza
+        let rotated_key = [0x22; KEK_SIZE];
+        let rotated = [Recipient::aes_kw(b"kms-2", &rotated_key).unwrap()];
+        let rewrapped = rewrap(&envelope, &kms, &rotated).unwrap();
+        let rewrapped = DataKeyEnvelope::parse(&rewrapped, &ParseBudget::DEFAULT).unwrap();
+        assert_eq!(rewrapped.recovery(), envelope.recovery());
+        assert_eq!(rewrapped.open(&recovery, b"").unwrap(), b"payload");
+        rewrap(&rewrapped, &recovery, &rotated).unwrap();
+
+        // Clearing the flag without the DEK breaks the header MAC
+        let mut cleared = sealed.clone();
+        let stanza = envelope.recovery().unwrap();
+        let flag = envelope.header.len()
+            - stanza.wrapped_key.len()
+            - stanza.kem_ciphertext.len()
+            - AlgorithmId::SIZE
+            - stanza.key_id.len()
+            - 2;
+        assert_eq!(cleared[flag], WRAP_KEM | RECOVERY_FLAG);
+        cleared[flag] = WRAP_KEM;
+        let cleared = DataKeyEnvelope::parse(&cleared, &ParseBudget::DEFAULT).unwrap();
+        assert!(cleared.recovery().is_none());
+        assert_eq!(
+            cleared.open(&kms, b"").unwrap_err().crypto(),
+            Some(CryptoError::DecryptionFailed)
+        );
+
+        // Two flagged stanzas do not parse
+        let mut twice = sealed.clone();
+        twice[FIXED_HEADER_SIZE] |= RECOVERY_FLAG;
+        assert_eq!(
+            DataKeyEnvelope::parse(&twice, &ParseBudget::DEFAULT)
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::InvalidEncoding)
+        );
+        let full = [recipients[0]; MAX_RECIPIENTS];
+        assert_eq!(
+            policy
+                .seal(AlgorithmId::Aes256GcmSiv, &full, b"", b"")
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::InvalidParameterSet)
+        );
+    }
+
+    #[test]
+    fn threshold_of_custodians_recovers_the_data_key() {
+        let keys = [[0x31; KEK_SIZE], [0x32; KEK_SIZE], [0x33; KEK_SIZE]];
+        let custodians = [
+            Recipient::aes_kw(b"alice", &keys[0]).unwrap(),
+            Recipient::aes_kw(b"bob", &keys[1]).unwrap(),
+            Recipient::aes_kw(b"carol", &keys[2]).unwrap(),
+        ];
+        let keks = [
+            Kek::aes_kw(b"alice", &keys[0]).unwrap(),
+            Kek::aes_kw(b"bob", &keys[1]).unwrap(),
+            Kek::aes_kw(b"carol", &keys[2]).unwrap(),
+        ];
+        let recipients = [
+            Recipient::aes_kw(b"kms", &KMS_KEY).unwrap(),
+            Recipient::threshold(b"quorum", 2, &custodians).unwrap(),
+        ];
+        let sealed = seal(AlgorithmId::Aes256GcmSiv, &recipients, b"payload", b"ad").unwrap();
+        let envelope = DataKeyEnvelope::parse(&sealed, &ParseBudget::DEFAULT).unwrap();
+        let quorum = &envelope.recipients()[1];
+        assert_eq!(
+            quorum.wrap(),
+            KeyWrap::Threshold {
+                threshold: 2,
+                shares: 3
+            }
+        );
+        let held: Vec<_> = quorum.custodians().iter().map(|c| c.key_id()).collect();
+        assert_eq!(held, [&b"alice"[..], b"bob", b"carol"]);
+
+        let kms = Kek::aes_kw(b"kms", &KMS_KEY).unwrap();
+        let data_key = envelope.unwrap_key(&kms).unwrap();
+        for pair in [[0, 1], [0, 2], [1, 2]] {
+            let pair = pair.map(|i| keks[i]);
+            assert_eq!(
+                envelope
+                    .unwrap_threshold(b"quorum", &pair)
+                    .unwrap()
+                    .as_slice(),
+                data_key.as_slice()
+            );
+            assert_eq!(
+                envelope.open_threshold(b"quorum", &pair, b"ad").unwrap(),
+                b"payload"
+            );
+        }
+        for kek in keks {
+            assert_eq!(
+                envelope
+                    .open_threshold(b"quorum", &[kek], b"ad")
+                    .unwrap_err()
+                    .crypto(),
+                Some(CryptoError::DecryptionFailed)
+            );
+        }
+        assert_eq!(
+            envelope
+                .open_threshold(b"kms", &keks, b"ad")
+                .unwrap_err()
+                .crypto(),
+            Some(CryptoError::DecryptionFailed)
+        );
+
+        // Nested or flagged custodians do not parse
+        let alice = sealed.windows(5).position(|w| w == b"alice").unwrap() - 2;
+        assert_eq!(sealed[alice], WRAP_AES_KW);
+        for wrap_type in [WRAP_THRESHOLD, WRAP_AES_KW | RECOVERY_FLAG] {
+            let mut nested = sealed.clone();
+            nested[alice] = wrap_type;
+            assert_eq!(
+                DataKeyEnvelope::parse(&nested, &ParseBudget::DEFAULT)
+                    .unwrap_err()
+                    .misuse(),
+                Some(MisuseError::InvalidEncoding)
+            );
+        }
+
+        // Out-of-range thresholds and nesting are refused
+        for threshold in [0, 4] {
+            assert_eq!(
+                Recipient::threshold(b"quorum", threshold, &custodians)
+                    .unwrap_err()
+                    .misuse(),
+                Some(MisuseError::InvalidParameterSet)
+            );
+        }
+        let nested = [recipients[1]];
+        assert_eq!(
+            Recipient::threshold(b"nested", 1, &nested)
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::InvalidParameterSet)
+        );
+    }
+
+    #[test]
+    fn deterministic_sealing_converges_per_tenant() {
+        let tenant = [0x41; MIN_TENANT_SECRET_SIZE];
+        let policy = DeterministicPolicy::new(&tenant).unwrap();
+        let recipients = [Recipient::aes_kw(b"kms", &KMS_KEY).unwrap()];
+        let kms = Kek::aes_kw(b"kms", &KMS_KEY).unwrap();
+        let seal = |policy: &DeterministicPolicy<'_>, plaintext: &[u8], ad: &[u8]| {
+            // Repeating a key and nonce is the point here
+            #[cfg(feature = "nonce-reuse-debug")]
+            crate::nonce::reuse::clear();
+            policy
+                .seal(AlgorithmId::Aes256GcmSiv, &recipients, plaintext, ad)
+                .unwrap()
+        };
+
+        let sealed = seal(&policy, b"block", b"ad");
+        assert_eq!(seal(&policy, b"block", b"ad"), sealed);
+        let envelope = DataKeyEnvelope::parse(&sealed, &ParseBudget::DEFAULT).unwrap();
+        assert_eq!(envelope.open(&kms, b"ad").unwrap(), b"block");
+
+        let other_tenant = [0x42; MIN_TENANT_SECRET_SIZE];
+        let other_tenant = DeterministicPolicy::new(&other_tenant).unwrap();
+        let global = DeterministicPolicy::without_tenant_secret();
+        for differs in [
+            seal(&policy, b"blocK", b"ad"),
+            seal(&policy, b"block", b"ae"),
+            seal(&other_tenant, b"block", b"ad"),
+            seal(&global, b"block", b"ad"),
+        ] {
+            assert_ne!(differs, sealed);
+        }
+        assert_eq!(
+            seal(&global, b"block", b"ad"),
+            seal(&global, b"block", b"ad")
+        );
+
+        assert_eq!(
+            DeterministicPolicy::new(&tenant[1..]).unwrap_err().misuse(),
+            Some(MisuseError::InvalidKeyLength)
+        );
+        let (public_key, _) = dynamic::kem("X25519").unwrap().generate_keypair().unwrap();
+        let randomized = [
+            recipients[0],
+            Recipient::kem(b"backup", AlgorithmId::X25519, &public_key).unwrap(),
+        ];
+        assert_eq!(
+            policy
+                .seal(AlgorithmId::Aes256GcmSiv, &randomized, b"", b"")
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::InvalidParameterSet)
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        let misuse = |bytes: &[u8], budget: &ParseBudget| {
+            DataKeyEnvelope::parse(bytes, budget).unwrap_err().misuse()
+        };
+        let recipients = [
+            Recipient::aes_kw(b"a", &KMS_KEY).unwrap(),
+            Recipient::aes_kw(b"b", &KMS_KEY).unwrap(),
+        ];
+        let sealed = seal(AlgorithmId::Aes256GcmSiv, &recipients, b"", b"").unwrap();
+
+        assert_eq!(
+            misuse(&sealed[..sealed.len() - 30], &ParseBudget::DEFAULT),
+            Some(MisuseError::InvalidEncoding)
+        );
+        assert_eq!(
+            misuse(&sealed, &ParseBudget::DEFAULT.with_max_recipients(1)),
+            Some(MisuseError::ParseBudgetExceeded)
+        );
+        let mut bad_wrap = sealed.clone();
+        bad_wrap[FIXED_HEADER_SIZE] = 9;
+        assert_eq!(
+            misuse(&bad_wrap, &ParseBudget::DEFAULT),
+            Some(MisuseError::InvalidEncoding)
+        );
+        let mut bad_version = sealed.clone();
+        bad_version[MAGIC.len()] = VERSION + 1;
+        assert_eq!(
+            misuse(&bad_version, &ParseBudget::DEFAULT),
+            Some(MisuseError::InvalidEncoding)
+        );
+
+        assert_eq!(
+            seal(AlgorithmId::Aes256GcmSiv, &[], b"", b"")
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::InvalidParameterSet)
+        );
+        assert_eq!(
+            Recipient::kem(b"id", AlgorithmId::Ed25519, &[0u8; 32])
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::InvalidAlgorithmIdentifier)
+        );
+        assert_eq!(
+            Recipient::aes_kw(b"", &KMS_KEY).unwrap_err().misuse(),
+            Some(MisuseError::InvalidParameterSet)
+        );
+    }
+}
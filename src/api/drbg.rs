@@ -0,0 +1,360 @@
+//! Deterministic random bit generators (NIST SP 800-90A).
+//!
+//! # Purpose
+//!
+//! FIPS-oriented deployments must draw keys and nonces from an approved
+//! DRBG rather than directly from the OS. [`HmacDrbg`] (HMAC-SHA-512) and
+//! [`CtrDrbg`] (AES-256, no derivation function) are seeded from an
+//! [`EntropySource`], the OS by default, and are themselves
+//! [`EntropySource`]s: install one with
+//! [`OpContext::with_entropy`](crate::context::OpContext::with_entropy) and
+//! every operation in the context draws from it.
+//!
+//! # Reseeding
+//!
+//! - Each generator reseeds from its source after
+//!   [`DEFAULT_RESEED_INTERVAL`] generate requests, or the interval set
+//!   with `with_reseed_interval`
+//! - With prediction resistance enabled, it reseeds before every request,
+//!   so a compromised state does not reveal later outputs
+//! - [`HmacDrbg::reseed`] and [`CtrDrbg::reseed`] reseed on demand
+//!
+//! Requests longer than [`MAX_REQUEST_SIZE`] are split into several
+//! requests, each counting towards the interval.
+//!
+//! # Fork Safety
+//!
+//! A forked child inherits its parent's DRBG state and would repeat the
+//! parent's output. Each generator records the process id at every
+//! (re)seed and reseeds before serving a request from a different process,
+//! so parent and child diverge from the child's first request. As with
+//! any lock, fork only from a single-threaded parent or one where no other
+//! thread is inside a generate call.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let drbg = HmacDrbg::new(b"payments-service")?.with_prediction_resistance(true);
+//! let keys = OpContext::new()
+//!     .with_entropy(Arc::new(drbg))
+//!     .run(MlDsa87::generate_keypair)?;
+//! ```
+
+use core::fmt;
+use std::process;
+use std::sync::{Arc, Mutex};
+
+use crate::algorithms::classical::drbg::{self as imp, Mechanism};
+use crate::context::{EntropySource, OsEntropy};
+use crate::errors::{CryptoError, MisuseError, Result};
+use crate::r#unsafe::memory::zeroize_slice;
+
+/// Largest output of one generate request in bytes (2^19 bits).
+pub const MAX_REQUEST_SIZE: usize = imp::MAX_REQUEST_SIZE;
+
+/// Generate requests between reseeds unless configured otherwise.
+pub const DEFAULT_RESEED_INTERVAL: u64 = 1 << 20;
+
+/// Largest reseed interval SP 800-90A allows (2^48 requests).
+pub const MAX_RESEED_INTERVAL: u64 = 1 << 48;
+
+/// Working state, requests served since the last (re)seed, and the
+/// process that (re)seeded it.
+struct State<M> {
+    mechanism: M,
+    requests: u64,
+    pid: u32,
+}
+
+/// Scheduling shared by both mechanisms.
+struct Drbg<M> {
+    source: Arc<dyn EntropySource>,
+    state: Mutex<State<M>>,
+    reseed_interval: u64,
+    prediction_resistance: bool,
+}
+
+impl<M: Mechanism> Drbg<M> {
+    fn new(source: Arc<dyn EntropySource>, personalization: &[u8]) -> Result<Self> {
+        check_input::<M>(personalization)?;
+        let mut entropy = vec![0u8; M::INSTANTIATE_ENTROPY];
+        let filled = source.fill(&mut entropy);
+        let mechanism = filled.map(|()| M::instantiate(&entropy, personalization));
+        // SAFETY: `entropy` is an owned, initialized buffer about to be dropped
+        unsafe { zeroize_slice(&mut entropy) };
+        Ok(Self {
+            source,
+            state: Mutex::new(State {
+                mechanism: mechanism?,
+                requests: 0,
+                pid: process::id(),
+            }),
+            reseed_interval: DEFAULT_RESEED_INTERVAL,
+            prediction_resistance: false,
+        })
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, State<M>>> {
+        // A panic mid-request may have left the state half-updated
+        self.state
+            .lock()
+            .map_err(|_| CryptoError::InternalFailure.into())
+    }
+
+    fn reseed_locked(&self, state: &mut State<M>, additional: &[u8]) -> Result<()> {
+        let mut entropy = vec![0u8; M::RESEED_ENTROPY];
+        let filled = self.source.fill(&mut entropy);
+        if filled.is_ok() {
+            state.mechanism.reseed(&entropy, additional);
+            state.requests = 0;
+            state.pid = process::id();
+        }
+        // SAFETY: `entropy` is an owned, initialized buffer about to be dropped
+        unsafe { zeroize_slice(&mut entropy) };
+        filled
+    }
+
+    fn reseed(&self, additional: &[u8]) -> Result<()> {
+        check_input::<M>(additional)?;
+        let mut state = self.lock()?;
+        self.reseed_locked(&mut state, additional)
+    }
+
+    fn generate(&self, out: &mut [u8], additional: &[u8]) -> Result<()> {
+        check_input::<M>(additional)?;
+        let mut state = self.lock()?;
+        for chunk in out.chunks_mut(MAX_REQUEST_SIZE) {
+            // Additional input is consumed by the reseed when one happens
+            let mut additional = additional;
+            if self.prediction_resistance
+                || state.requests >= self.reseed_interval
+                || state.pid != process::id()
+            {
+                self.reseed_locked(&mut state, additional)?;
+                additional = &[];
+            }
+            state.mechanism.generate(chunk, additional);
+            state.requests += 1;
+        }
+        Ok(())
+    }
+}
+
+fn check_input<M: Mechanism>(input: &[u8]) -> Result<()> {
+    match M::MAX_INPUT {
+        Some(max) if input.len() > max => Err(MisuseError::InvalidParameterSet.into()),
+        _ => Ok(()),
+    }
+}
+
+macro_rules! drbg {
+    ($(#[$doc:meta])* $name:ident, $mechanism:ty $(, $input_error:literal)?) => {
+        $(#[$doc])*
+        pub struct $name(Drbg<$mechanism>);
+
+        impl $name {
+            /// Instantiate from OS randomness, with an optional
+            /// `personalization` string distinguishing this instance.
+            ///
+            /// # Errors
+            ///
+            $(#[doc = concat!("- `MisuseError::InvalidParameterSet`: If `personalization` is ", $input_error)])?
+            /// - `CryptoError::RandomnessUnavailable`: If the OS source fails
+            /// - `CryptoError::RandomnessFailure`: If the OS source fails a health test
+            pub fn new(personalization: &[u8]) -> Result<Self> {
+                Self::with_source(Arc::new(OsEntropy), personalization)
+            }
+
+            /// Instantiate and reseed from `source` instead of the OS.
+            ///
+            /// `source` must provide full-entropy bytes; a DRBG cannot add
+            /// entropy its source lacks.
+            ///
+            /// # Errors
+            ///
+            $(#[doc = concat!("- `MisuseError::InvalidParameterSet`: If `personalization` is ", $input_error)])?
+            /// - `CryptoError::RandomnessUnavailable`: If `source` fails
+            pub fn with_source(
+                source: Arc<dyn EntropySource>,
+                personalization: &[u8],
+            ) -> Result<Self> {
+                Drbg::new(source, personalization).map(Self)
+            }
+
+            /// Reseed after `requests` generate requests, clamped to
+            /// `1..=`[`MAX_RESEED_INTERVAL`].
+            #[inline]
+            pub fn with_reseed_interval(mut self, requests: u64) -> Self {
+                self.0.reseed_interval = requests.clamp(1, MAX_RESEED_INTERVAL);
+                self
+            }
+
+            /// Reseed before every generate request when `enabled`.
+            #[inline]
+            pub fn with_prediction_resistance(mut self, enabled: bool) -> Self {
+                self.0.prediction_resistance = enabled;
+                self
+            }
+
+            /// Fill `out`, mixing in optional `additional` input.
+            ///
+            /// # Errors
+            ///
+            $(#[doc = concat!("- `MisuseError::InvalidParameterSet`: If `additional` is ", $input_error)])?
+            /// - `CryptoError::RandomnessUnavailable`: If a due reseed fails
+            /// - `CryptoError::InternalFailure`: If an earlier request
+            ///   panicked and left the state unusable
+            pub fn generate(&self, out: &mut [u8], additional: &[u8]) -> Result<()> {
+                self.0.generate(out, additional)
+            }
+
+            /// Reseed from the source now, mixing in optional `additional`
+            /// input.
+            ///
+            /// # Errors
+            ///
+            $(#[doc = concat!("- `MisuseError::InvalidParameterSet`: If `additional` is ", $input_error)])?
+            /// - `CryptoError::RandomnessUnavailable`: If the source fails
+            /// - `CryptoError::InternalFailure`: If an earlier request
+            ///   panicked and left the state unusable
+            pub fn reseed(&self, additional: &[u8]) -> Result<()> {
+                self.0.reseed(additional)
+            }
+        }
+
+        impl EntropySource for $name {
+            fn fill(&self, buf: &mut [u8]) -> Result<()> {
+                self.generate(buf, &[])
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_struct(stringify!($name))
+                    .field("reseed_interval", &self.0.reseed_interval)
+                    .field("prediction_resistance", &self.0.prediction_resistance)
+                    .finish_non_exhaustive()
+            }
+        }
+    };
+}
+
+drbg!(
+    /// HMAC_DRBG with HMAC-SHA-512, 256-bit security strength.
+    HmacDrbg,
+    imp::HmacDrbg
+);
+
+drbg!(
+    /// CTR_DRBG with AES-256 and no derivation function, 256-bit security
+    /// strength. Personalization strings and additional input are at most
+    /// 48 bytes.
+    CtrDrbg,
+    imp::CtrDrbg,
+    "longer than 48 bytes"
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::OpContext;
+    use crate::kex::X25519;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Counts the bytes drawn from the OS.
+    #[derive(Default)]
+    struct Counting(AtomicUsize);
+
+    impl EntropySource for Counting {
+        fn fill(&self, buf: &mut [u8]) -> Result<()> {
+            self.0.fetch_add(buf.len(), Ordering::Relaxed);
+            OsEntropy.fill(buf)
+        }
+    }
+
+    struct Failing;
+
+    impl EntropySource for Failing {
+        fn fill(&self, _: &mut [u8]) -> Result<()> {
+            Err(CryptoError::RandomnessUnavailable.into())
+        }
+    }
+
+    #[test]
+    fn reseeds_on_schedule() {
+        let source = Arc::new(Counting::default());
+        let drbg = HmacDrbg::with_source(source.clone(), b"test")
+            .unwrap()
+            .with_reseed_interval(2);
+        let mut out = [0u8; 32];
+        for _ in 0..5 {
+            drbg.generate(&mut out, b"").unwrap();
+        }
+        // Instantiation, then reseeds before requests 3 and 5
+        assert_eq!(source.0.load(Ordering::Relaxed), 48 + 2 * 32);
+
+        let source = Arc::new(Counting::default());
+        let drbg = CtrDrbg::with_source(source.clone(), b"")
+            .unwrap()
+            .with_prediction_resistance(true);
+        drbg.generate(&mut out, b"").unwrap();
+        drbg.generate(&mut out, b"").unwrap();
+        drbg.reseed(b"").unwrap();
+        assert_eq!(source.0.load(Ordering::Relaxed), 4 * 48);
+    }
+
+    #[test]
+    fn splits_long_requests() {
+        let source = Arc::new(Counting::default());
+        let drbg = CtrDrbg::with_source(source.clone(), b"")
+            .unwrap()
+            .with_reseed_interval(1);
+        let mut out = vec![0u8; 2 * MAX_REQUEST_SIZE + 1];
+        drbg.generate(&mut out, b"").unwrap();
+        assert_eq!(source.0.load(Ordering::Relaxed), 3 * 48);
+        assert_ne!(out[..64], out[MAX_REQUEST_SIZE..MAX_REQUEST_SIZE + 64]);
+    }
+
+    #[test]
+    fn rejects_bad_inputs_and_failed_sources() {
+        let error = CtrDrbg::new(&[0u8; 49]).unwrap_err();
+        assert_eq!(error.misuse(), Some(MisuseError::InvalidParameterSet));
+        let drbg = CtrDrbg::new(&[0u8; 48]).unwrap();
+        let error = drbg.generate(&mut [0u8; 16], &[0u8; 49]).unwrap_err();
+        assert_eq!(error.misuse(), Some(MisuseError::InvalidParameterSet));
+        assert!(HmacDrbg::new(&[0u8; 1000]).is_ok());
+
+        let error = HmacDrbg::with_source(Arc::new(Failing), b"").unwrap_err();
+        assert_eq!(error.crypto(), Some(CryptoError::RandomnessUnavailable));
+    }
+
+    #[test]
+    fn reseeds_in_forked_child() {
+        let source = Arc::new(Counting::default());
+        let drbg = HmacDrbg::with_source(source.clone(), b"").unwrap();
+        let mut parent = [0u8; 32];
+        drbg.generate(&mut parent, b"").unwrap();
+        assert_eq!(source.0.load(Ordering::Relaxed), 48);
+
+        // What a child sees: state seeded by another process
+        drbg.0.lock().unwrap().pid = process::id().wrapping_add(1);
+        let mut child = [0u8; 32];
+        drbg.generate(&mut child, b"").unwrap();
+        assert_eq!(source.0.load(Ordering::Relaxed), 48 + 32);
+        drbg.generate(&mut child, b"").unwrap();
+        assert_eq!(source.0.load(Ordering::Relaxed), 48 + 32);
+    }
+
+    #[test]
+    fn serves_as_context_entropy() {
+        let drbg = HmacDrbg::new(b"keygen").unwrap();
+        let mut a = [0u8; 32];
+        let mut b = [0u8; 32];
+        drbg.fill(&mut a).unwrap();
+        drbg.fill(&mut b).unwrap();
+        assert_ne!(a, b);
+
+        let context = OpContext::new().with_entropy(Arc::new(drbg));
+        assert!(context.run(X25519::generate_keypair).is_ok());
+    }
+}
@@ -0,0 +1,471 @@
+//! DSSE signing envelopes.
+//!
+//! # Purpose
+//!
+//! The Dead Simple Signing Envelope is the wrapper in-toto attestations and
+//! sigstore bundles use. This module builds and verifies DSSE envelopes with
+//! Citadel's signature schemes, so attestations can carry ML-DSA signatures
+//! alongside or instead of classical ones.
+//!
+//! # Format
+//!
+//! Signatures cover the pre-authentication encoding ([`pae`]) of the payload
+//! type and payload, never the JSON itself:
+//!
+//! ```text
+//! "DSSEv1" SP len(type) SP type SP len(payload) SP payload
+//! ```
+//!
+//! The payload type is a media type. It is covered by every signature, so
+//! [`Envelope::verify_content`] hands receivers a [`ContentType`] they can
+//! dispatch on without sniffing the payload and without trusting an
+//! unsigned field.
+//!
+//! Envelopes serialize to JSON with Base64 `payload` and `sig` fields.
+//! [`Envelope::to_json`] emits RFC 8785 canonical JSON; [`Envelope::from_json`]
+//! accepts any valid I-JSON and ignores unknown fields.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let mut envelope = Envelope::new("application/vnd.in-toto+json", statement);
+//! envelope.sign("release-2026", &ml_dsa_signing_key)?;
+//! let json = envelope.to_json();
+//!
+//! let envelope = Envelope::from_json(&json, &ParseBudget::DEFAULT)?;
+//! match envelope.verify_content("release-2026", &ml_dsa_verifying_key)? {
+//!     (ContentType::InToto, statement) => process_statement(statement),
+//!     (ContentType::Json, document) => process_json(document),
+//!     (other, _) => return Err(unsupported(other.media_type())),
+//! }
+//! ```
+
+use crate::encoding::jcs::{self, Value};
+use crate::encoding::{ParseBudget, base64};
+use crate::errors::{CryptoError, MisuseError, Result};
+#[cfg(feature = "signing")]
+use crate::sign::Ed25519SigningKey;
+#[cfg(all(feature = "mldsa", feature = "signing"))]
+use crate::sign::MlDsa87SigningKey;
+use crate::sign::{Ed25519, Ed25519Signature, Ed25519VerifyingKey};
+#[cfg(feature = "mldsa")]
+use crate::sign::{MlDsa87, MlDsa87Signature, MlDsa87VerifyingKey};
+
+/// Version prefix of the pre-authentication encoding.
+pub const PAE_PREFIX: &str = "DSSEv1";
+
+/// Pre-authentication encoding of `payload_type` and `payload`.
+pub fn pae(payload_type: &str, payload: &[u8]) -> Vec<u8> {
+    let header = format!(
+        "{PAE_PREFIX} {} {payload_type} {} ",
+        payload_type.len(),
+        payload.len()
+    );
+    [header.as_bytes(), payload].concat()
+}
+
+/// A key that can sign DSSE envelopes.
+#[cfg(feature = "signing")]
+pub trait EnvelopeSigner {
+    /// Sign the pre-authentication encoding `message`.
+    ///
+    /// # Errors
+    ///
+    /// - Any error from the underlying signature scheme
+    fn sign_message(&self, message: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// A key that can verify DSSE envelope signatures.
+pub trait EnvelopeVerifier {
+    /// Verify `signature` over the pre-authentication encoding `message`.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::VerificationFailed`: If the signature is invalid
+    /// - `MisuseError::InvalidSignatureLength`: If `signature` has the
+    ///   wrong size for this scheme
+    fn verify_message(&self, message: &[u8], signature: &[u8]) -> Result<()>;
+}
+
+#[cfg(all(feature = "mldsa", feature = "signing"))]
+impl EnvelopeSigner for MlDsa87SigningKey {
+    fn sign_message(&self, message: &[u8]) -> Result<Vec<u8>> {
+        Ok(MlDsa87::sign(self, message)?.as_bytes().to_vec())
+    }
+}
+
+#[cfg(feature = "mldsa")]
+impl EnvelopeVerifier for MlDsa87VerifyingKey {
+    fn verify_message(&self, message: &[u8], signature: &[u8]) -> Result<()> {
+        MlDsa87::verify(self, message, &MlDsa87Signature::from_bytes(signature)?)
+    }
+}
+
+#[cfg(feature = "signing")]
+impl EnvelopeSigner for Ed25519SigningKey {
+    fn sign_message(&self, message: &[u8]) -> Result<Vec<u8>> {
+        Ok(Ed25519::sign(self, message)?.as_bytes().to_vec())
+    }
+}
+
+impl EnvelopeVerifier for Ed25519VerifyingKey {
+    fn verify_message(&self, message: &[u8], signature: &[u8]) -> Result<()> {
+        Ed25519::verify(self, message, &Ed25519Signature::from_bytes(signature)?)
+    }
+}
+
+/// Well-known payload media types.
+///
+/// Matching ignores ASCII case and media type parameters (`; charset=...`);
+/// anything else is [`ContentType::Other`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ContentType<'a> {
+    /// `application/json`.
+    Json,
+    /// `application/protobuf`, or the legacy `application/x-protobuf`.
+    Protobuf,
+    /// `application/cbor`.
+    Cbor,
+    /// `application/octet-stream`: raw bytes.
+    OctetStream,
+    /// `application/vnd.in-toto+json`: an in-toto statement.
+    InToto,
+    /// Any other payload type, verbatim.
+    Other(&'a str),
+}
+
+impl<'a> ContentType<'a> {
+    /// Classify the media type `payload_type`.
+    pub fn parse(payload_type: &'a str) -> Self {
+        let essence = payload_type.split(';').next().unwrap_or_default().trim();
+        [
+            ("application/json", ContentType::Json),
+            ("application/protobuf", ContentType::Protobuf),
+            ("application/x-protobuf", ContentType::Protobuf),
+            ("application/cbor", ContentType::Cbor),
+            ("application/octet-stream", ContentType::OctetStream),
+            ("application/vnd.in-toto+json", ContentType::InToto),
+        ]
+        .into_iter()
+        .find(|(media_type, _)| essence.eq_ignore_ascii_case(media_type))
+        .map_or(ContentType::Other(payload_type), |(_, known)| known)
+    }
+
+    /// The media type to write into an envelope.
+    pub fn media_type(&self) -> &'a str {
+        match self {
+            ContentType::Json => "application/json",
+            ContentType::Protobuf => "application/protobuf",
+            ContentType::Cbor => "application/cbor",
+            ContentType::OctetStream => "application/octet-stream",
+            ContentType::InToto => "application/vnd.in-toto+json",
+            ContentType::Other(media_type) => media_type,
+        }
+    }
+}
+
+/// One signature in an [`Envelope`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvelopeSignature {
+    /// Unauthenticated hint naming the signing key; may be empty.
+    pub key_id: String,
+    /// Raw signature bytes.
+    pub sig: Vec<u8>,
+}
+
+/// A DSSE envelope: a typed payload and its signatures.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Envelope {
+    payload_type: String,
+    payload: Vec<u8>,
+    signatures: Vec<EnvelopeSignature>,
+}
+
+impl Envelope {
+    /// An unsigned envelope.
+    pub fn new(payload_type: impl Into<String>, payload: impl Into<Vec<u8>>) -> Self {
+        Self {
+            payload_type: payload_type.into(),
+            payload: payload.into(),
+            signatures: Vec::new(),
+        }
+    }
+
+    /// An unsigned envelope whose payload type is `content_type`.
+    pub fn with_content_type(content_type: ContentType<'_>, payload: impl Into<Vec<u8>>) -> Self {
+        Self::new(content_type.media_type(), payload)
+    }
+
+    /// Media type of the payload, e.g. `application/vnd.in-toto+json`.
+    #[inline]
+    pub fn payload_type(&self) -> &str {
+        &self.payload_type
+    }
+
+    /// The payload type, classified; unverified.
+    ///
+    /// Use [`verify_content`](Self::verify_content) before dispatching on
+    /// it.
+    #[inline]
+    pub fn content_type(&self) -> ContentType<'_> {
+        ContentType::parse(&self.payload_type)
+    }
+
+    /// The payload, unverified.
+    ///
+    /// Use [`verify`](Self::verify) to obtain a payload that can be trusted.
+    #[inline]
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    /// Signatures attached so far.
+    #[inline]
+    pub fn signatures(&self) -> &[EnvelopeSignature] {
+        &self.signatures
+    }
+
+    /// Sign the envelope with `signer` and attach the signature under
+    /// `key_id`.
+    ///
+    /// # Errors
+    ///
+    /// - Any error from `signer`
+    #[cfg(feature = "signing")]
+    pub fn sign(&mut self, key_id: &str, signer: &impl EnvelopeSigner) -> Result<()> {
+        let sig = signer.sign_message(&pae(&self.payload_type, &self.payload))?;
+        self.signatures.push(EnvelopeSignature {
+            key_id: key_id.to_owned(),
+            sig,
+        });
+        Ok(())
+    }
+
+    /// Check that a signature by `verifier` is attached and return the
+    /// payload.
+    ///
+    /// Signatures whose key id is non-empty and differs from `key_id` are
+    /// skipped without verification. The key id is not authenticated; it
+    /// only saves work.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::VerificationFailed`: If no attached signature
+    ///   verifies with `verifier`
+    pub fn verify(&self, key_id: &str, verifier: &impl EnvelopeVerifier) -> Result<&[u8]> {
+        let message = pae(&self.payload_type, &self.payload);
+        let verified = self
+            .signatures
+            .iter()
+            .filter(|signature| signature.key_id.is_empty() || signature.key_id == key_id)
+            .any(|signature| verifier.verify_message(&message, &signature.sig).is_ok());
+        if !verified {
+            return Err(CryptoError::VerificationFailed.into());
+        }
+        Ok(&self.payload)
+    }
+
+    /// Like [`verify`](Self::verify), but also return the authenticated
+    /// content type to dispatch on.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::VerificationFailed`: If no attached signature
+    ///   verifies with `verifier`
+    pub fn verify_content(
+        &self,
+        key_id: &str,
+        verifier: &impl EnvelopeVerifier,
+    ) -> Result<(ContentType<'_>, &[u8])> {
+        let payload = self.verify(key_id, verifier)?;
+        Ok((self.content_type(), payload))
+    }
+
+    /// Serialize as canonical JSON.
+    pub fn to_json(&self) -> String {
+        let signatures = self
+            .signatures
+            .iter()
+            .map(|signature| {
+                Value::object(vec![
+                    ("keyid".into(), Value::String(signature.key_id.clone())),
+                    ("sig".into(), Value::String(base64::encode(&signature.sig))),
+                ])
+            })
+            .collect();
+        Value::object(vec![
+            (
+                "payload".into(),
+                Value::String(base64::encode(&self.payload)),
+            ),
+            (
+                "payloadType".into(),
+                Value::String(self.payload_type.clone()),
+            ),
+            ("signatures".into(), Value::Array(signatures)),
+        ])
+        .to_canonical()
+    }
+
+    /// Parse a JSON envelope.
+    ///
+    /// `keyid` is optional; unknown fields are ignored. Signatures are not
+    /// verified.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::ParseBudgetExceeded`: If `json` exceeds `budget`
+    /// - `MisuseError::InvalidEncoding`: If `json` is not a DSSE envelope
+    pub fn from_json(json: &str, budget: &ParseBudget) -> Result<Self> {
+        let value = jcs::parse(json, budget)?;
+        let Some(Value::Array(entries)) = value.get("signatures") else {
+            return Err(MisuseError::InvalidEncoding.into());
+        };
+        let signatures = entries
+            .iter()
+            .map(|entry| {
+                let key_id = match entry.get("keyid") {
+                    None => String::new(),
+                    Some(Value::String(key_id)) => key_id.clone(),
+                    Some(_) => return Err(MisuseError::InvalidEncoding.into()),
+                };
+                Ok(EnvelopeSignature {
+                    key_id,
+                    sig: base64::decode(string_field(entry, "sig")?, budget)?,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            payload_type: string_field(&value, "payloadType")?.to_owned(),
+            payload: base64::decode(string_field(&value, "payload")?, budget)?,
+            signatures,
+        })
+    }
+}
+
+fn string_field<'a>(value: &'a Value, name: &str) -> Result<&'a str> {
+    match value.get(name) {
+        Some(Value::String(string)) => Ok(string),
+        _ => Err(MisuseError::InvalidEncoding.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::Error;
+
+    const PAYLOAD_TYPE: &str = "application/vnd.in-toto+json";
+
+    #[test]
+    fn pae_matches_specification() {
+        assert_eq!(
+            pae("http://example.com/HelloWorld", b"hello world"),
+            b"DSSEv1 29 http://example.com/HelloWorld 11 hello world"
+        );
+        assert_eq!(pae("", b""), b"DSSEv1 0  0 ");
+    }
+
+    #[cfg(all(feature = "mldsa", feature = "signing"))]
+    #[test]
+    fn hybrid_envelope_round_trip() {
+        let (pq_verifying, pq_signing) = MlDsa87::generate_keypair().unwrap();
+        let (ed_verifying, ed_signing) = Ed25519::generate_keypair().unwrap();
+
+        let mut envelope = Envelope::new(PAYLOAD_TYPE, &b"{\"_type\":\"statement\"}"[..]);
+        envelope.sign("pq", &pq_signing).unwrap();
+        envelope.sign("ed", &ed_signing).unwrap();
+
+        let parsed = Envelope::from_json(&envelope.to_json(), &ParseBudget::DEFAULT).unwrap();
+        assert_eq!(parsed, envelope);
+        assert_eq!(
+            parsed.verify("pq", &pq_verifying).unwrap(),
+            b"{\"_type\":\"statement\"}"
+        );
+        parsed.verify("ed", &ed_verifying).unwrap();
+        // Key ids are hints: a mismatched id is never tried
+        assert_eq!(
+            parsed.verify("ed", &pq_verifying).unwrap_err(),
+            Error::Crypto(CryptoError::VerificationFailed)
+        );
+    }
+
+    #[cfg(feature = "signing")]
+    #[test]
+    fn signature_binds_payload_type() {
+        let (verifying_key, signing_key) = Ed25519::generate_keypair().unwrap();
+        let mut envelope = Envelope::new(PAYLOAD_TYPE, &b"payload"[..]);
+        envelope.sign("", &signing_key).unwrap();
+        envelope.verify("any", &verifying_key).unwrap();
+
+        envelope.payload_type = "text/plain".into();
+        assert!(envelope.verify("any", &verifying_key).is_err());
+    }
+
+    #[test]
+    fn content_type_is_authenticated() {
+        assert_eq!(
+            ContentType::parse("Application/JSON; charset=utf-8"),
+            ContentType::Json
+        );
+        assert_eq!(
+            ContentType::parse("application/x-protobuf"),
+            ContentType::Protobuf
+        );
+        assert_eq!(
+            ContentType::parse("text/plain"),
+            ContentType::Other("text/plain")
+        );
+        assert_eq!(ContentType::InToto.media_type(), PAYLOAD_TYPE);
+
+        #[cfg(feature = "signing")]
+        {
+            let (verifying_key, signing_key) = Ed25519::generate_keypair().unwrap();
+            let mut envelope = Envelope::with_content_type(ContentType::Protobuf, &b"\x08\x01"[..]);
+            envelope.sign("", &signing_key).unwrap();
+            let (content_type, payload) = envelope.verify_content("", &verifying_key).unwrap();
+            assert_eq!(
+                (content_type, payload),
+                (ContentType::Protobuf, &b"\x08\x01"[..])
+            );
+
+            // Relabelling the payload breaks the signature
+            envelope.payload_type = ContentType::Json.media_type().into();
+            assert_eq!(envelope.content_type(), ContentType::Json);
+            assert!(envelope.verify_content("", &verifying_key).is_err());
+        }
+    }
+
+    #[test]
+    fn parses_foreign_envelopes() {
+        let json = r#"{
+            "payloadType": "text/plain",
+            "payload": "aGVsbG8",
+            "signatures": [{"sig": "AAEC"}],
+            "extension": {"ignored": true}
+        }"#;
+        let envelope = Envelope::from_json(json, &ParseBudget::DEFAULT).unwrap();
+        assert_eq!(envelope.payload(), b"hello");
+        assert_eq!(
+            envelope.signatures(),
+            [EnvelopeSignature {
+                key_id: String::new(),
+                sig: vec![0, 1, 2],
+            }]
+        );
+
+        for json in [
+            r#"{"payloadType":"t","payload":"","signatures":{}}"#,
+            r#"{"payloadType":1,"payload":"","signatures":[]}"#,
+            r#"{"payloadType":"t","payload":"!","signatures":[]}"#,
+            r#"{"payloadType":"t","payload":"","signatures":[{"keyid":"k"}]}"#,
+        ] {
+            assert_eq!(
+                Envelope::from_json(json, &ParseBudget::DEFAULT).unwrap_err(),
+                Error::Misuse(MisuseError::InvalidEncoding),
+                "{json}"
+            );
+        }
+    }
+}
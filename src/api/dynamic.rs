@@ -0,0 +1,639 @@
+//! Runtime algorithm selection.
+//!
+//! # Purpose
+//!
+//! The typed APIs fix every algorithm, and every key and ciphertext size,
+//! at compile time. When the algorithm comes from a configuration file or
+//! is negotiated with a peer, [`kem`], [`signer`] and [`aead`] look it up
+//! by name and return an object-safe [`DynKem`], [`DynSigner`] or
+//! [`DynAead`] over byte strings, which checks every length at run time.
+//!
+//! # Algorithms
+//!
+//! - [`DynKem`]: `ML-KEM-768` (feature `mlkem`), `X25519` (DHKEM)
+//! - [`DynSigner`]: `Ed25519`, `ML-DSA-87` (feature `mldsa`)
+//! - [`DynAead`]: `AES-256-GCM-SIV` (feature `aes-gcm-siv`), `AEGIS-256`
+//!   (feature `aegis256`)
+//!
+//! Names match case-insensitively. Key generation and signing need the
+//! `signing` feature, like their typed counterparts.
+//!
+//! # Security
+//!
+//! - Lengths are checked on every call and reported with the same
+//!   `MisuseError` variants as the typed APIs
+//! - Secret keys are returned in a [`SecureBuffer`] and shared secrets as
+//!   [`SharedSecret`], both zeroized on drop
+//! - [`DynAead`] draws a fresh random nonce for every encryption and
+//!   prepends it to the ciphertext; callers never handle nonces
+//! - Looking an algorithm up by name is not a negotiation protocol:
+//!   restrict the names a peer may pick to an allow-list, or an attacker
+//!   can steer both sides to the weakest one enabled
+//!
+//! # Example
+//!
+//! ```ignore
+//! use citadel::dynamic;
+//!
+//! let kem = dynamic::kem(&config.kem)?;
+//! let (ciphertext, shared_secret) = kem.encapsulate(&peer_public_key)?;
+//! ```
+
+use core::fmt;
+
+#[cfg(feature = "aegis256")]
+use crate::algorithms::classical::aegis256;
+#[cfg(feature = "aes-gcm-siv")]
+use crate::algorithms::classical::aes256_gcm_siv;
+use crate::algorithms::classical::ed25519 as ed;
+use crate::algorithms::classical::x25519::{self, X25519};
+#[cfg(feature = "mldsa")]
+use crate::algorithms::pq::ml_dsa_87 as dsa;
+#[cfg(feature = "mlkem")]
+use crate::algorithms::pq::ml_kem_768;
+use crate::context;
+#[cfg(any(feature = "aes-gcm-siv", feature = "aegis256"))]
+use crate::context::EntropySource;
+use crate::errors::{MisuseError, Result};
+#[cfg(any(feature = "aes-gcm-siv", feature = "aegis256"))]
+use crate::internal::entropy::ContextEntropy;
+#[cfg(any(feature = "aes-gcm-siv", feature = "aegis256"))]
+use crate::internal::traits::AeadCipher;
+#[cfg(feature = "signing")]
+use crate::internal::traits::SignatureScheme;
+#[cfg(not(feature = "signing"))]
+use crate::internal::traits::SignatureVerifier;
+use crate::internal::traits::{DhKem, KeyEncapsulation};
+use crate::kem::SharedSecret;
+use crate::memory::{SecureBuffer, SensitiveBytes};
+#[cfg(any(feature = "aes-gcm-siv", feature = "aegis256"))]
+use crate::nonce::Nonce;
+use crate::sign::Ed25519;
+#[cfg(feature = "mldsa")]
+use crate::sign::MlDsa87;
+
+/// A key encapsulation mechanism chosen at run time.
+pub trait DynKem: Send + Sync {
+    /// Algorithm name, as accepted by [`kem`].
+    fn name(&self) -> &'static str;
+    /// Public key size in bytes.
+    fn public_key_size(&self) -> usize;
+    /// Secret key size in bytes.
+    fn secret_key_size(&self) -> usize;
+    /// Ciphertext size in bytes.
+    fn ciphertext_size(&self) -> usize;
+
+    /// Generate a keypair.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+    /// - `CryptoError::RandomnessFailure`: If the system RNG fails a health test
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    fn generate_keypair(&self) -> Result<(Vec<u8>, SecureBuffer)>;
+
+    /// Encapsulate a fresh shared secret to `public_key`.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidPublicKeyLength`: If `public_key` has the
+    ///   wrong length
+    /// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+    /// - Any error of the algorithm's own encapsulation
+    fn encapsulate(&self, public_key: &[u8]) -> Result<(Vec<u8>, SharedSecret)>;
+
+    /// Recover the shared secret from `ciphertext`.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidSecretKeyLength`: If `secret_key` has the
+    ///   wrong length
+    /// - `MisuseError::InvalidCiphertextLength`: If `ciphertext` has the
+    ///   wrong length
+    /// - Any error of the algorithm's own decapsulation
+    fn decapsulate(&self, secret_key: &[u8], ciphertext: &[u8]) -> Result<SharedSecret>;
+}
+
+/// A signature scheme chosen at run time.
+pub trait DynSigner: Send + Sync {
+    /// Algorithm name, as accepted by [`signer`].
+    fn name(&self) -> &'static str;
+    /// Verifying key size in bytes.
+    fn public_key_size(&self) -> usize;
+    /// Signing key size in bytes.
+    fn secret_key_size(&self) -> usize;
+    /// Signature size in bytes.
+    fn signature_size(&self) -> usize;
+
+    /// Generate a keypair.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+    /// - `CryptoError::RandomnessFailure`: If the system RNG fails a health test
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    #[cfg(feature = "signing")]
+    fn generate_keypair(&self) -> Result<(Vec<u8>, SecureBuffer)>;
+
+    /// Sign `message`.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidSecretKeyLength`: If `secret_key` has the
+    ///   wrong length
+    /// - Any error of the algorithm's own signing
+    #[cfg(feature = "signing")]
+    fn sign(&self, secret_key: &[u8], message: &[u8]) -> Result<Vec<u8>>;
+
+    /// Verify `signature` over `message`.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::VerificationFailed`: If the signature is invalid
+    /// - `MisuseError::InvalidPublicKeyLength`: If `public_key` has the
+    ///   wrong length
+    /// - `MisuseError::InvalidSignatureLength`: If `signature` has the
+    ///   wrong length
+    fn verify(&self, public_key: &[u8], message: &[u8], signature: &[u8]) -> Result<()>;
+}
+
+/// An AEAD chosen at run time.
+///
+/// Ciphertexts are `nonce || ciphertext || tag`, with a fresh random nonce
+/// per encryption.
+pub trait DynAead: Send + Sync {
+    /// Algorithm name, as accepted by [`aead`].
+    fn name(&self) -> &'static str;
+    /// Key size in bytes.
+    fn key_size(&self) -> usize;
+    /// Nonce size in bytes.
+    fn nonce_size(&self) -> usize;
+    /// Tag size in bytes.
+    fn tag_size(&self) -> usize;
+
+    /// Generate a key.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+    /// - `CryptoError::RandomnessFailure`: If the system RNG fails a health test
+    fn generate_key(&self) -> Result<SecureBuffer>;
+
+    /// Encrypt `plaintext` under a fresh random nonce, authenticating
+    /// `associated_data` with it.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidKeyLength`: If `key` has the wrong length
+    /// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+    /// - Any error of the algorithm's own encryption
+    fn encrypt(&self, key: &[u8], plaintext: &[u8], associated_data: &[u8]) -> Result<Vec<u8>>;
+
+    /// Verify and decrypt the output of [`encrypt`](Self::encrypt).
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::DecryptionFailed`: If the ciphertext, key or
+    ///   associated data do not match
+    /// - `MisuseError::InvalidKeyLength`: If `key` has the wrong length
+    /// - `MisuseError::InvalidCiphertextLength`: If `ciphertext` is shorter
+    ///   than a nonce and a tag
+    fn decrypt(&self, key: &[u8], ciphertext: &[u8], associated_data: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// The KEM named `name`.
+///
+/// # Errors
+///
+/// - `MisuseError::FeatureNotEnabled`: If the algorithm's feature is off
+/// - `MisuseError::UnsupportedAlgorithm`: If `name` is not a KEM
+pub fn kem(name: &str) -> Result<&'static dyn DynKem> {
+    let found = KEMS.iter().copied().find(|kem| matches(kem.name(), name));
+    lookup(found, name, &["ML-KEM-768"])
+}
+
+/// The signature scheme named `name`.
+///
+/// # Errors
+///
+/// - `MisuseError::FeatureNotEnabled`: If the algorithm's feature is off
+/// - `MisuseError::UnsupportedAlgorithm`: If `name` is not a signature
+///   scheme
+pub fn signer(name: &str) -> Result<&'static dyn DynSigner> {
+    let found = SIGNERS
+        .iter()
+        .copied()
+        .find(|signer| matches(signer.name(), name));
+    lookup(found, name, &["ML-DSA-87"])
+}
+
+/// The AEAD named `name`.
+///
+/// # Errors
+///
+/// - `MisuseError::FeatureNotEnabled`: If the algorithm's feature is off
+/// - `MisuseError::UnsupportedAlgorithm`: If `name` is not an AEAD
+pub fn aead(name: &str) -> Result<&'static dyn DynAead> {
+    let found = AEADS
+        .iter()
+        .copied()
+        .find(|aead| matches(aead.name(), name));
+    lookup(found, name, &["AES-256-GCM-SIV", "AEGIS-256"])
+}
+
+/// Every enabled KEM.
+pub fn kems() -> &'static [&'static dyn DynKem] {
+    KEMS
+}
+
+/// Every enabled signature scheme.
+pub fn signers() -> &'static [&'static dyn DynSigner] {
+    SIGNERS
+}
+
+/// Every enabled AEAD.
+pub fn aeads() -> &'static [&'static dyn DynAead] {
+    AEADS
+}
+
+fn matches(name: &str, requested: &str) -> bool {
+    name.eq_ignore_ascii_case(requested)
+}
+
+/// `found`, or why not: a feature-gated name is `FeatureNotEnabled`.
+fn lookup<T: ?Sized>(found: Option<&'static T>, name: &str, gated: &[&str]) -> Result<&'static T> {
+    match found {
+        Some(found) => Ok(found),
+        None if gated.iter().any(|gated| matches(gated, name)) => {
+            Err(MisuseError::FeatureNotEnabled.into())
+        }
+        None => Err(MisuseError::UnsupportedAlgorithm.into()),
+    }
+}
+
+#[cfg(feature = "mlkem")]
+type MlKem768Kem = KemAdapter<
+    ml_kem_768::MlKem768,
+    { ml_kem_768::PUBLIC_KEY_SIZE },
+    { ml_kem_768::SECRET_KEY_SIZE },
+    { ml_kem_768::CIPHERTEXT_SIZE },
+>;
+type X25519Kem = KemAdapter<
+    DhKem<X25519>,
+    { x25519::PUBLIC_KEY_SIZE },
+    { x25519::SECRET_KEY_SIZE },
+    { x25519::PUBLIC_KEY_SIZE },
+>;
+type Ed25519Signer = SignerAdapter<
+    Ed25519,
+    { ed::PUBLIC_KEY_SIZE },
+    { ed::SECRET_KEY_SIZE },
+    { ed::SIGNATURE_SIZE },
+>;
+#[cfg(feature = "mldsa")]
+type MlDsa87Signer = SignerAdapter<
+    MlDsa87,
+    { dsa::PUBLIC_KEY_SIZE },
+    { dsa::SECRET_KEY_SIZE },
+    { dsa::SIGNATURE_SIZE },
+>;
+#[cfg(feature = "aes-gcm-siv")]
+type Aes256GcmSivAead = AeadAdapter<
+    aes256_gcm_siv::Aes256GcmSiv,
+    { aes256_gcm_siv::KEY_SIZE },
+    { aes256_gcm_siv::NONCE_SIZE },
+    { aes256_gcm_siv::TAG_SIZE },
+>;
+#[cfg(feature = "aegis256")]
+type Aegis256Aead = AeadAdapter<
+    aegis256::Aegis256,
+    { aegis256::KEY_SIZE },
+    { aegis256::NONCE_SIZE },
+    { aegis256::TAG_SIZE },
+>;
+
+static KEMS: &[&dyn DynKem] = &[
+    #[cfg(feature = "mlkem")]
+    &MlKem768Kem::new("ML-KEM-768", ml_kem_768::MlKem768),
+    &X25519Kem::new("X25519", DhKem(X25519)),
+];
+
+static SIGNERS: &[&dyn DynSigner] = &[
+    &Ed25519Signer::new("Ed25519", Ed25519),
+    #[cfg(feature = "mldsa")]
+    &MlDsa87Signer::new("ML-DSA-87", MlDsa87),
+];
+
+static AEADS: &[&dyn DynAead] = &[
+    #[cfg(feature = "aes-gcm-siv")]
+    &Aes256GcmSivAead::new("AES-256-GCM-SIV", aes256_gcm_siv::Aes256GcmSiv),
+    #[cfg(feature = "aegis256")]
+    &Aegis256Aead::new("AEGIS-256", aegis256::Aegis256),
+];
+
+/// View `bytes` as an array, or fail with `error` on a length mismatch.
+fn array<const N: usize>(bytes: &[u8], error: MisuseError) -> Result<&[u8; N]> {
+    bytes.try_into().map_err(|_| error.into())
+}
+
+/// A [`KeyEncapsulation`] with 32-byte shared secrets as a [`DynKem`].
+struct KemAdapter<K, const PK: usize, const SK: usize, const CT: usize> {
+    name: &'static str,
+    kem: K,
+}
+
+impl<K, const PK: usize, const SK: usize, const CT: usize> KemAdapter<K, PK, SK, CT> {
+    const fn new(name: &'static str, kem: K) -> Self {
+        Self { name, kem }
+    }
+}
+
+impl<K, const PK: usize, const SK: usize, const CT: usize> DynKem for KemAdapter<K, PK, SK, CT>
+where
+    K: KeyEncapsulation<PK, SK, CT, 32> + Send + Sync,
+{
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn public_key_size(&self) -> usize {
+        PK
+    }
+
+    fn secret_key_size(&self) -> usize {
+        SK
+    }
+
+    fn ciphertext_size(&self) -> usize {
+        CT
+    }
+
+    fn generate_keypair(&self) -> Result<(Vec<u8>, SecureBuffer)> {
+        context::checkpoint()?;
+        let (public_key, secret_key) = self.kem.generate_keypair()?;
+        Ok((public_key.to_vec(), SensitiveBytes::new(secret_key).into()))
+    }
+
+    fn encapsulate(&self, public_key: &[u8]) -> Result<(Vec<u8>, SharedSecret)> {
+        context::checkpoint()?;
+        let public_key = array(public_key, MisuseError::InvalidPublicKeyLength)?;
+        let (ciphertext, shared) = self.kem.encapsulate(public_key)?;
+        Ok((
+            ciphertext.to_vec(),
+            SharedSecret(SensitiveBytes::new(shared)),
+        ))
+    }
+
+    fn decapsulate(&self, secret_key: &[u8], ciphertext: &[u8]) -> Result<SharedSecret> {
+        context::checkpoint()?;
+        let secret_key = array(secret_key, MisuseError::InvalidSecretKeyLength)?;
+        let ciphertext = array(ciphertext, MisuseError::InvalidCiphertextLength)?;
+        let shared = self.kem.decapsulate(secret_key, ciphertext)?;
+        Ok(SharedSecret(SensitiveBytes::new(shared)))
+    }
+}
+
+/// A signature scheme from [`crate::sign`] as a [`DynSigner`].
+struct SignerAdapter<S, const PK: usize, const SK: usize, const SIG: usize> {
+    name: &'static str,
+    scheme: S,
+}
+
+impl<S, const PK: usize, const SK: usize, const SIG: usize> SignerAdapter<S, PK, SK, SIG> {
+    const fn new(name: &'static str, scheme: S) -> Self {
+        Self { name, scheme }
+    }
+}
+
+macro_rules! signer_sizes {
+    () => {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn public_key_size(&self) -> usize {
+            PK
+        }
+
+        fn secret_key_size(&self) -> usize {
+            SK
+        }
+
+        fn signature_size(&self) -> usize {
+            SIG
+        }
+
+        fn verify(&self, public_key: &[u8], message: &[u8], signature: &[u8]) -> Result<()> {
+            let public_key = array(public_key, MisuseError::InvalidPublicKeyLength)?;
+            let signature = array(signature, MisuseError::InvalidSignatureLength)?;
+            self.scheme.verify(public_key, message, signature)
+        }
+    };
+}
+
+#[cfg(feature = "signing")]
+impl<S, const PK: usize, const SK: usize, const SIG: usize> DynSigner
+    for SignerAdapter<S, PK, SK, SIG>
+where
+    S: SignatureScheme<PK, SK, SIG> + Send + Sync,
+{
+    signer_sizes!();
+
+    fn generate_keypair(&self) -> Result<(Vec<u8>, SecureBuffer)> {
+        let (public_key, secret_key) = self.scheme.generate_keypair()?;
+        Ok((public_key.to_vec(), SensitiveBytes::new(secret_key).into()))
+    }
+
+    fn sign(&self, secret_key: &[u8], message: &[u8]) -> Result<Vec<u8>> {
+        let secret_key = array(secret_key, MisuseError::InvalidSecretKeyLength)?;
+        Ok(self.scheme.sign(secret_key, message)?.to_vec())
+    }
+}
+
+#[cfg(not(feature = "signing"))]
+impl<S, const PK: usize, const SK: usize, const SIG: usize> DynSigner
+    for SignerAdapter<S, PK, SK, SIG>
+where
+    S: SignatureVerifier<PK, SIG> + Send + Sync,
+{
+    signer_sizes!();
+}
+
+/// An [`AeadCipher`] as a [`DynAead`].
+#[cfg(any(feature = "aes-gcm-siv", feature = "aegis256"))]
+struct AeadAdapter<A, const KEY: usize, const NONCE: usize, const TAG: usize> {
+    name: &'static str,
+    aead: A,
+}
+
+#[cfg(any(feature = "aes-gcm-siv", feature = "aegis256"))]
+impl<A, const KEY: usize, const NONCE: usize, const TAG: usize> AeadAdapter<A, KEY, NONCE, TAG> {
+    const fn new(name: &'static str, aead: A) -> Self {
+        Self { name, aead }
+    }
+}
+
+#[cfg(any(feature = "aes-gcm-siv", feature = "aegis256"))]
+impl<A, const KEY: usize, const NONCE: usize, const TAG: usize> DynAead
+    for AeadAdapter<A, KEY, NONCE, TAG>
+where
+    A: AeadCipher<KEY, NONCE, TAG> + Send + Sync,
+{
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn key_size(&self) -> usize {
+        KEY
+    }
+
+    fn nonce_size(&self) -> usize {
+        NONCE
+    }
+
+    fn tag_size(&self) -> usize {
+        TAG
+    }
+
+    fn generate_key(&self) -> Result<SecureBuffer> {
+        let mut key = SecureBuffer::zeroed(KEY);
+        ContextEntropy.fill(key.as_mut_slice())?;
+        Ok(key)
+    }
+
+    fn encrypt(&self, key: &[u8], plaintext: &[u8], associated_data: &[u8]) -> Result<Vec<u8>> {
+        context::checkpoint()?;
+        let key = array(key, MisuseError::InvalidKeyLength)?;
+        let nonce = Nonce::<NONCE>::random()?;
+        let mut output = vec![0u8; NONCE + plaintext.len() + TAG];
+        output[..NONCE].copy_from_slice(nonce.as_bytes());
+        self.aead
+            .encrypt(key, nonce, plaintext, associated_data, &mut output[NONCE..])?;
+        Ok(output)
+    }
+
+    fn decrypt(&self, key: &[u8], ciphertext: &[u8], associated_data: &[u8]) -> Result<Vec<u8>> {
+        context::checkpoint()?;
+        let key = array(key, MisuseError::InvalidKeyLength)?;
+        if ciphertext.len() < NONCE + TAG {
+            return Err(MisuseError::InvalidCiphertextLength.into());
+        }
+        let (nonce, body) = ciphertext.split_at(NONCE);
+        let nonce = array(nonce, MisuseError::InvalidNonceLength)?;
+        let mut plaintext = vec![0u8; body.len() - TAG];
+        self.aead
+            .decrypt(key, nonce, body, associated_data, &mut plaintext)?;
+        Ok(plaintext)
+    }
+}
+
+impl fmt::Debug for dyn DynKem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DynKem({})", self.name())
+    }
+}
+
+impl fmt::Debug for dyn DynSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DynSigner({})", self.name())
+    }
+}
+
+impl fmt::Debug for dyn DynAead {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DynAead({})", self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::CryptoError;
+
+    #[test]
+    fn kems_round_trip_and_check_lengths() {
+        for kem in kems() {
+            let (public_key, secret_key) = kem.generate_keypair().unwrap();
+            assert_eq!(public_key.len(), kem.public_key_size());
+            assert_eq!(secret_key.len(), kem.secret_key_size());
+            let (ciphertext, sender) = kem.encapsulate(&public_key).unwrap();
+            assert_eq!(ciphertext.len(), kem.ciphertext_size());
+            let recipient = kem.decapsulate(secret_key.as_slice(), &ciphertext).unwrap();
+            assert_eq!(sender.as_bytes(), recipient.as_bytes());
+
+            let error = kem.encapsulate(&public_key[1..]).unwrap_err();
+            assert_eq!(error.misuse(), Some(MisuseError::InvalidPublicKeyLength));
+            let error = kem
+                .decapsulate(secret_key.as_slice(), &ciphertext[1..])
+                .unwrap_err();
+            assert_eq!(error.misuse(), Some(MisuseError::InvalidCiphertextLength));
+        }
+    }
+
+    #[cfg(feature = "signing")]
+    #[test]
+    fn signers_round_trip_and_check_lengths() {
+        for signer in signers() {
+            let (public_key, secret_key) = signer.generate_keypair().unwrap();
+            let signature = signer.sign(secret_key.as_slice(), b"manifest").unwrap();
+            assert_eq!(signature.len(), signer.signature_size());
+            signer.verify(&public_key, b"manifest", &signature).unwrap();
+            let error = signer
+                .verify(&public_key, b"tampered", &signature)
+                .unwrap_err();
+            assert_eq!(error.crypto(), Some(CryptoError::VerificationFailed));
+
+            let error = signer
+                .verify(&public_key, b"manifest", &signature[1..])
+                .unwrap_err();
+            assert_eq!(error.misuse(), Some(MisuseError::InvalidSignatureLength));
+            let error = signer.sign(&[0u8; 1], b"manifest").unwrap_err();
+            assert_eq!(error.misuse(), Some(MisuseError::InvalidSecretKeyLength));
+        }
+    }
+
+    #[test]
+    fn aeads_round_trip_and_check_lengths() {
+        for aead in aeads() {
+            let key = aead.generate_key().unwrap();
+            let ciphertext = aead.encrypt(key.as_slice(), b"message", b"header").unwrap();
+            assert_eq!(
+                ciphertext.len(),
+                aead.nonce_size() + b"message".len() + aead.tag_size()
+            );
+            let plaintext = aead
+                .decrypt(key.as_slice(), &ciphertext, b"header")
+                .unwrap();
+            assert_eq!(plaintext, b"message");
+            let error = aead
+                .decrypt(key.as_slice(), &ciphertext, b"other")
+                .unwrap_err();
+            assert_eq!(error.crypto(), Some(CryptoError::DecryptionFailed));
+
+            let error = aead.encrypt(&[0u8; 16], b"", b"").unwrap_err();
+            assert_eq!(error.misuse(), Some(MisuseError::InvalidKeyLength));
+            let error = aead
+                .decrypt(key.as_slice(), &ciphertext[..aead.nonce_size()], b"")
+                .unwrap_err();
+            assert_eq!(error.misuse(), Some(MisuseError::InvalidCiphertextLength));
+        }
+    }
+
+    #[test]
+    fn looks_up_by_name() {
+        assert_eq!(kem("x25519").unwrap().name(), "X25519");
+        assert_eq!(signer("ED25519").unwrap().name(), "Ed25519");
+        let error = kem("ML-KEM-512").unwrap_err();
+        assert_eq!(error.misuse(), Some(MisuseError::UnsupportedAlgorithm));
+        let error = aead("ChaCha20-Poly1305").unwrap_err();
+        assert_eq!(error.misuse(), Some(MisuseError::UnsupportedAlgorithm));
+        #[cfg(not(feature = "aegis256"))]
+        assert_eq!(
+            aead("AEGIS-256").unwrap_err().misuse(),
+            Some(MisuseError::FeatureNotEnabled)
+        );
+        assert_eq!(format!("{:?}", kem("X25519").unwrap()), "DynKem(X25519)");
+    }
+}
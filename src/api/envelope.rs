@@ -0,0 +1,977 @@
+//! Versioned ciphertext envelopes.
+//!
+//! # Purpose
+//!
+//! Two applications exchanging AEAD ciphertexts have to agree on framing:
+//! where the nonce goes, how the algorithm is recorded, how associated
+//! data travels. [`seal`] produces a self-describing envelope and
+//! [`Envelope::parse`] reads one back, so they do not have to invent their
+//! own. Messages sealed to a public key with
+//! [`Suite::seal`](crate::suite::Suite::seal) use the same envelope.
+//!
+//! Content that is public but must not be altered, such as release
+//! artifacts, is framed the same way by [`sign`]: the envelope carries it
+//! in the clear with a signature, and [`SignedEnvelope::parse`] reads it
+//! back with the same header fields and budget checks.
+//!
+//! # Detached Signatures
+//!
+//! Some workflows keep content and signature apart, others want one file.
+//! [`SignedEnvelope::detach`] turns a signed envelope into a detached
+//! signature to keep next to its content, [`DetachedSignature::attach`]
+//! turns it back, and [`sign_detached`] signs without copying the content.
+//! Both forms carry the same algorithm, associated data and signature
+//! bytes, so converting never needs the signing key:
+//!
+//! ```text
+//! magic "CTDS" (4) || version (1) || suite (2) || aad_len (4) || aad
+//!     || signature
+//! ```
+//!
+//! The signature covers the attached form's header, so it verifies the
+//! same way in both.
+//!
+//! # Format
+//!
+//! ```text
+//! magic "CTDE" (4) || version (1) || suite (2) || aad_len (4) || aad
+//!     || body
+//!
+//! body = nonce || ciphertext || tag             AEAD under a shared key
+//!      | kem_ciphertext || ciphertext || tag    suite, to a public key
+//!      | content || signature                   signature, integrity only
+//! ```
+//!
+//! - `version` is [`VERSION`]
+//! - `suite` is big-endian and names what sealed the body: the
+//!   [`AlgorithmId`] code of an AEAD for [`seal`] or of a signature scheme
+//!   for [`sign`], or the ID of a [`Suite`](crate::suite::Suite). Suite IDs are below `0x0100` and
+//!   algorithm codes above, so the two never collide
+//! - `aad_len` is the big-endian length of `aad`
+//! - `nonce` and `tag` have the algorithm's sizes; `ciphertext` is the rest.
+//!   Suites derive their nonce and send none
+//!
+//! Everything before the body is the AEAD's associated data, so the
+//! version, suite and `aad` are authenticated along with the ciphertext.
+//! A signature covers everything before the body followed by the SHA-512
+//! digest of `content`.
+//!
+//! # Public-Key Encryption with Associated Data
+//!
+//! [`seal_with_aad`] seals to a suite public key and authenticates the
+//! caller's associated data, such as a user ID or object path, along with
+//! the envelope header. That data is not carried: [`open_with_aad`] needs
+//! the same bytes, and fails on any other. Use [`seal`] with a shared key
+//! when the associated data should travel in the envelope instead.
+//!
+//! # Password-Based Encryption
+//!
+//! [`encrypt_with_password`] derives the key from a passphrase with
+//! Argon2id (feature `argon2`) and stores the KDF parameters and salt as
+//! the envelope's associated data:
+//!
+//! ```text
+//! kdf (1) || memory_kib (4) || passes (4) || lanes (4) || salt (16)
+//! ```
+//!
+//! `kdf` is [`KDF_ARGON2ID`] and the integers are big-endian. Being
+//! associated data, the parameters are authenticated; a modified one
+//! derives another key and the envelope fails to open.
+//! [`decrypt_with_password`] reads them back, so the cost can be raised
+//! for new envelopes without breaking old ones.
+//!
+//! # Security
+//!
+//! - Every envelope is sealed under a fresh random nonce
+//! - Parsing is strict: the magic, version, suite and every length are
+//!   checked, and the [`ParseBudget`] is enforced before any allocation
+//! - `aad` is sent in the clear. Associated data that must stay private
+//!   belongs in the plaintext
+//! - Signed envelopes protect integrity and origin only: their content is
+//!   readable by anyone
+//!
+//! # Example
+//!
+//! ```ignore
+//! use citadel::algorithm::AlgorithmId;
+//! use citadel::encoding::ParseBudget;
+//! use citadel::envelope::{self, Envelope};
+//!
+//! let sealed = envelope::seal(AlgorithmId::Aes256GcmSiv, &key, b"message", b"record 7")?;
+//!
+//! let envelope = Envelope::parse(&sealed, &ParseBudget::DEFAULT)?;
+//! assert_eq!(envelope.associated_data(), b"record 7");
+//! let plaintext = envelope.open(&key)?;
+//! ```
+
+use core::fmt;
+
+use crate::algorithm::{AlgorithmId, AlgorithmKind};
+use crate::algorithms::classical::sha512::Sha512;
+use crate::dynamic;
+use crate::encoding::ParseBudget;
+use crate::errors::{MisuseError, Result};
+#[cfg(feature = "argon2")]
+use crate::internal::entropy;
+#[cfg(feature = "argon2")]
+use crate::kdf::password::{self, Argon2idParams};
+#[cfg(feature = "argon2")]
+use crate::memory::SecureBuffer;
+#[cfg(all(feature = "mlkem", feature = "aes-gcm-siv"))]
+use crate::suite::{Suite, SuitePublicKey, SuiteSecretKey};
+
+/// First bytes of every envelope.
+pub const MAGIC: [u8; 4] = *b"CTDE";
+
+/// First bytes of every detached signature.
+pub const DETACHED_MAGIC: [u8; 4] = *b"CTDS";
+
+/// Format version written by [`seal`].
+pub const VERSION: u8 = 1;
+
+/// Size of the fixed header fields before the associated data.
+pub const FIXED_HEADER_SIZE: usize = MAGIC.len() + 1 + AlgorithmId::SIZE + 4;
+
+/// Password KDF identifier of Argon2id.
+pub const KDF_ARGON2ID: u8 = 1;
+
+/// Salt size of password-based envelopes, in bytes.
+pub const PASSWORD_SALT_SIZE: usize = 16;
+
+/// Size of the password KDF header carried as associated data.
+pub const PASSWORD_HEADER_SIZE: usize = 1 + 3 * 4 + PASSWORD_SALT_SIZE;
+
+/// Encrypt `plaintext` under `key` with `algorithm` and frame it as an
+/// envelope carrying `associated_data`.
+///
+/// # Errors
+///
+/// - `MisuseError::InvalidAlgorithmIdentifier`: If `algorithm` is not an
+///   AEAD
+/// - `MisuseError::FeatureNotEnabled`: If the algorithm's feature is off
+/// - `MisuseError::InvalidKeyLength`: If `key` has the wrong length
+/// - `MisuseError::AssociatedDataTooLong`: If `associated_data` is 4 GiB or
+///   longer
+/// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+pub fn seal(
+    algorithm: AlgorithmId,
+    key: &[u8],
+    plaintext: &[u8],
+    associated_data: &[u8],
+) -> Result<Vec<u8>> {
+    let aead = aead(algorithm)?;
+    let body_len = aead.nonce_size() + plaintext.len() + aead.tag_size();
+    let mut envelope = header(algorithm.to_bytes(), associated_data, body_len)?;
+    let body = aead.encrypt(key, plaintext, &envelope)?;
+    envelope.extend_from_slice(&body);
+    Ok(envelope)
+}
+
+/// Seal `plaintext` to `recipient`, authenticating `associated_data`
+/// along with the envelope header.
+///
+/// `associated_data` is bound to the ciphertext but not carried in it;
+/// open with [`open_with_aad`] and the same bytes. A thin wrapper over
+/// [`Suite::seal`].
+///
+/// # Errors
+///
+/// Those of [`Suite::seal`].
+#[cfg(all(feature = "mlkem", feature = "aes-gcm-siv"))]
+pub fn seal_with_aad(
+    recipient: &SuitePublicKey,
+    plaintext: &[u8],
+    associated_data: &[u8],
+) -> Result<Vec<u8>> {
+    Suite::seal(recipient, plaintext, associated_data)
+}
+
+/// Verify and decrypt the output of [`seal_with_aad`] with `secret_key`
+/// and the associated data it was sealed with.
+///
+/// # Errors
+///
+/// - `CryptoError::DecryptionFailed`: If `associated_data` differs from
+///   sealing, or the key or envelope do not match
+/// - Any other error of [`Suite::open`]
+#[cfg(all(feature = "mlkem", feature = "aes-gcm-siv"))]
+pub fn open_with_aad(
+    secret_key: &SuiteSecretKey,
+    sealed: &[u8],
+    associated_data: &[u8],
+) -> Result<Vec<u8>> {
+    Suite::open(secret_key, sealed, associated_data)
+}
+
+/// Sign `content` with `algorithm` under `secret_key` and frame it, in the
+/// clear, as an envelope carrying `associated_data`.
+///
+/// # Errors
+///
+/// - `MisuseError::InvalidAlgorithmIdentifier`: If `algorithm` is not a
+///   signature scheme
+/// - `MisuseError::FeatureNotEnabled`: If the algorithm's feature is off
+/// - `MisuseError::UnsupportedAlgorithm`: If `algorithm` is a hybrid,
+///   which has no run-time signer
+/// - `MisuseError::AssociatedDataTooLong`: If `associated_data` is 4 GiB or
+///   longer
+/// - Any error of the algorithm's signing
+#[cfg(feature = "signing")]
+pub fn sign(
+    algorithm: AlgorithmId,
+    secret_key: &[u8],
+    content: &[u8],
+    associated_data: &[u8],
+) -> Result<Vec<u8>> {
+    let mut envelope = sign_header(algorithm, secret_key, content, associated_data)?;
+    let signature = envelope.split_off(envelope.len() - signature_size(algorithm));
+    envelope.extend_from_slice(content);
+    envelope.extend_from_slice(&signature);
+    Ok(envelope)
+}
+
+/// Sign `content` like [`sign`], but return a detached signature that
+/// leaves the content out.
+///
+/// # Errors
+///
+/// As for [`sign`].
+#[cfg(feature = "signing")]
+pub fn sign_detached(
+    algorithm: AlgorithmId,
+    secret_key: &[u8],
+    content: &[u8],
+    associated_data: &[u8],
+) -> Result<Vec<u8>> {
+    let mut detached = sign_header(algorithm, secret_key, content, associated_data)?;
+    detached[..MAGIC.len()].copy_from_slice(&DETACHED_MAGIC);
+    Ok(detached)
+}
+
+/// The header of a signed envelope of `content`, followed by its
+/// signature.
+#[cfg(feature = "signing")]
+fn sign_header(
+    algorithm: AlgorithmId,
+    secret_key: &[u8],
+    content: &[u8],
+    associated_data: &[u8],
+) -> Result<Vec<u8>> {
+    let signer = signer(algorithm)?;
+    let body_len = content.len() + signer.signature_size();
+    let mut header = header(algorithm.to_bytes(), associated_data, body_len)?;
+    let signature = signer.sign(secret_key, &signed_message(&header, content))?;
+    header.extend_from_slice(&signature);
+    Ok(header)
+}
+
+/// Start an envelope sealed by `suite`, an AEAD's [`AlgorithmId`] code or a
+/// suite ID, carrying `associated_data`, with room for a `body_len`-byte
+/// body.
+///
+/// # Errors
+///
+/// - `MisuseError::AssociatedDataTooLong`: If `associated_data` is 4 GiB or
+///   longer
+pub(crate) fn header(suite: [u8; 2], associated_data: &[u8], body_len: usize) -> Result<Vec<u8>> {
+    let aad_len =
+        u32::try_from(associated_data.len()).map_err(|_| MisuseError::AssociatedDataTooLong)?;
+    let mut envelope = Vec::with_capacity(FIXED_HEADER_SIZE + associated_data.len() + body_len);
+    envelope.extend_from_slice(&MAGIC);
+    envelope.push(VERSION);
+    envelope.extend_from_slice(&suite);
+    envelope.extend_from_slice(&aad_len.to_be_bytes());
+    envelope.extend_from_slice(associated_data);
+    Ok(envelope)
+}
+
+/// Split an envelope into its suite field, its header (everything before
+/// the body) and its body.
+///
+/// # Errors
+///
+/// - `MisuseError::InvalidEncoding`: If the magic or version is wrong or
+///   the header is truncated
+pub(crate) fn split(bytes: &[u8]) -> Result<([u8; 2], &[u8], &[u8])> {
+    split_with(MAGIC, bytes)
+}
+
+/// [`split`] for a header starting with `magic`.
+fn split_with(magic: [u8; 4], bytes: &[u8]) -> Result<([u8; 2], &[u8], &[u8])> {
+    let Some((fixed, rest)) = bytes.split_first_chunk::<FIXED_HEADER_SIZE>() else {
+        return Err(MisuseError::InvalidEncoding.into());
+    };
+    let [m0, m1, m2, m3, version, s0, s1, l0, l1, l2, l3] = *fixed;
+    if [m0, m1, m2, m3] != magic || version != VERSION {
+        return Err(MisuseError::InvalidEncoding.into());
+    }
+    let aad_len = u32::from_be_bytes([l0, l1, l2, l3]) as usize;
+    if rest.len() < aad_len {
+        return Err(MisuseError::InvalidEncoding.into());
+    }
+    let (header, body) = bytes.split_at(FIXED_HEADER_SIZE + aad_len);
+    Ok(([s0, s1], header, body))
+}
+
+/// A parsed envelope, borrowing from its encoding.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Envelope<'a> {
+    algorithm: AlgorithmId,
+    /// Everything before the nonce: the AEAD associated data.
+    header: &'a [u8],
+    /// `nonce || ciphertext || tag`.
+    body: &'a [u8],
+    nonce_size: usize,
+    tag_size: usize,
+}
+
+impl<'a> Envelope<'a> {
+    /// Parse an envelope produced by [`seal`].
+    ///
+    /// Algorithms whose feature is disabled still parse; opening them
+    /// fails.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::ParseBudgetExceeded`: If `bytes` or the ciphertext
+    ///   exceeds `budget`
+    /// - `MisuseError::InvalidEncoding`: If the magic is wrong, the version
+    ///   is not [`VERSION`] or the header is truncated
+    /// - `MisuseError::InvalidAlgorithmIdentifier`: If the algorithm is
+    ///   unknown or not an AEAD, including envelopes sealed to a suite
+    /// - `MisuseError::InvalidCiphertextLength`: If the envelope is too
+    ///   short for the algorithm's nonce and tag
+    pub fn parse(bytes: &'a [u8], budget: &ParseBudget) -> Result<Self> {
+        budget.check_input(bytes.len())?;
+        let (suite, header, body) = split(bytes)?;
+        let algorithm =
+            AlgorithmId::from_bytes(&suite).map_err(|_| MisuseError::InvalidAlgorithmIdentifier)?;
+        if algorithm.kind() != AlgorithmKind::Aead {
+            return Err(MisuseError::InvalidAlgorithmIdentifier.into());
+        }
+
+        let sizes = algorithm.sizes();
+        let (nonce_size, tag_size) = (sizes.nonce.unwrap_or(0), sizes.tag.unwrap_or(0));
+        let Some(ciphertext_len) = body.len().checked_sub(nonce_size + tag_size) else {
+            return Err(MisuseError::InvalidCiphertextLength.into());
+        };
+        budget.check_output(ciphertext_len)?;
+
+        Ok(Self {
+            algorithm,
+            header,
+            body,
+            nonce_size,
+            tag_size,
+        })
+    }
+
+    /// The AEAD the envelope was sealed with.
+    pub fn algorithm(&self) -> AlgorithmId {
+        self.algorithm
+    }
+
+    /// The associated data carried in the envelope.
+    pub fn associated_data(&self) -> &'a [u8] {
+        &self.header[FIXED_HEADER_SIZE..]
+    }
+
+    /// The nonce.
+    pub fn nonce(&self) -> &'a [u8] {
+        &self.body[..self.nonce_size]
+    }
+
+    /// The ciphertext, without nonce and tag.
+    pub fn ciphertext(&self) -> &'a [u8] {
+        &self.body[self.nonce_size..self.body.len() - self.tag_size]
+    }
+
+    /// The authentication tag.
+    pub fn tag(&self) -> &'a [u8] {
+        &self.body[self.body.len() - self.tag_size..]
+    }
+
+    /// The encoded envelope.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [self.header, self.body].concat()
+    }
+
+    /// Verify the envelope and decrypt it under `key`.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::DecryptionFailed`: If the key is wrong or any part
+    ///   of the envelope was modified
+    /// - `MisuseError::FeatureNotEnabled`: If the algorithm's feature is off
+    /// - `MisuseError::InvalidKeyLength`: If `key` has the wrong length
+    pub fn open(&self, key: &[u8]) -> Result<Vec<u8>> {
+        aead(self.algorithm)?.decrypt(key, self.body, self.header)
+    }
+}
+
+impl fmt::Debug for Envelope<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Envelope")
+            .field("algorithm", &self.algorithm)
+            .field("associated_data_len", &self.associated_data().len())
+            .field("ciphertext_len", &self.ciphertext().len())
+            .finish()
+    }
+}
+
+/// A parsed integrity-only envelope, borrowing from its encoding.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct SignedEnvelope<'a> {
+    algorithm: AlgorithmId,
+    /// Everything before the content.
+    header: &'a [u8],
+    content: &'a [u8],
+    signature: &'a [u8],
+}
+
+impl<'a> SignedEnvelope<'a> {
+    /// Parse an envelope produced by [`sign`].
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::ParseBudgetExceeded`: If `bytes` or the content
+    ///   exceeds `budget`
+    /// - `MisuseError::InvalidEncoding`: If the magic is wrong, the version
+    ///   is not [`VERSION`] or the header is truncated
+    /// - `MisuseError::InvalidAlgorithmIdentifier`: If the algorithm is
+    ///   unknown or not a signature scheme
+    /// - `MisuseError::InvalidSignatureLength`: If the envelope is too
+    ///   short for the algorithm's signature
+    pub fn parse(bytes: &'a [u8], budget: &ParseBudget) -> Result<Self> {
+        budget.check_input(bytes.len())?;
+        let (suite, header, body) = split(bytes)?;
+        let algorithm =
+            AlgorithmId::from_bytes(&suite).map_err(|_| MisuseError::InvalidAlgorithmIdentifier)?;
+        if algorithm.kind() != AlgorithmKind::Signature {
+            return Err(MisuseError::InvalidAlgorithmIdentifier.into());
+        }
+        let Some(content_len) = body.len().checked_sub(signature_size(algorithm)) else {
+            return Err(MisuseError::InvalidSignatureLength.into());
+        };
+        budget.check_output(content_len)?;
+        let (content, signature) = body.split_at(content_len);
+        Ok(Self {
+            algorithm,
+            header,
+            content,
+            signature,
+        })
+    }
+
+    /// The signature scheme.
+    pub fn algorithm(&self) -> AlgorithmId {
+        self.algorithm
+    }
+
+    /// The associated data carried in the envelope.
+    pub fn associated_data(&self) -> &'a [u8] {
+        &self.header[FIXED_HEADER_SIZE..]
+    }
+
+    /// The content, unverified.
+    pub fn content(&self) -> &'a [u8] {
+        self.content
+    }
+
+    /// The signature.
+    pub fn signature(&self) -> &'a [u8] {
+        self.signature
+    }
+
+    /// The encoded envelope.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [self.header, self.content, self.signature].concat()
+    }
+
+    /// The same signature detached from the content, as from
+    /// [`sign_detached`].
+    pub fn detach(&self) -> Vec<u8> {
+        [&DETACHED_MAGIC, &self.header[MAGIC.len()..], self.signature].concat()
+    }
+
+    /// Verify the signature under `public_key` and return the content.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::VerificationFailed`: If the key is wrong or any part
+    ///   of the envelope was modified
+    /// - `MisuseError::FeatureNotEnabled`: If the algorithm's feature is off
+    /// - `MisuseError::UnsupportedAlgorithm`: If the algorithm is a hybrid
+    /// - `MisuseError::InvalidPublicKeyLength`: If `public_key` has the
+    ///   wrong length
+    pub fn verify(&self, public_key: &[u8]) -> Result<&'a [u8]> {
+        let message = signed_message(self.header, self.content);
+        signer(self.algorithm)?.verify(public_key, &message, self.signature)?;
+        Ok(self.content)
+    }
+}
+
+impl fmt::Debug for SignedEnvelope<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SignedEnvelope")
+            .field("algorithm", &self.algorithm)
+            .field("associated_data_len", &self.associated_data().len())
+            .field("content_len", &self.content.len())
+            .finish()
+    }
+}
+
+/// A parsed detached signature, borrowing from its encoding.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct DetachedSignature<'a> {
+    algorithm: AlgorithmId,
+    /// Everything before the signature.
+    header: &'a [u8],
+    signature: &'a [u8],
+}
+
+impl<'a> DetachedSignature<'a> {
+    /// Parse a detached signature produced by [`sign_detached`] or
+    /// [`SignedEnvelope::detach`].
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::ParseBudgetExceeded`: If `bytes` exceeds `budget`
+    /// - `MisuseError::InvalidEncoding`: If the magic is wrong, the version
+    ///   is not [`VERSION`] or the header is truncated
+    /// - `MisuseError::InvalidAlgorithmIdentifier`: If the algorithm is
+    ///   unknown or not a signature scheme
+    /// - `MisuseError::InvalidSignatureLength`: If the signature has the
+    ///   wrong length for the algorithm
+    pub fn parse(bytes: &'a [u8], budget: &ParseBudget) -> Result<Self> {
+        budget.check_input(bytes.len())?;
+        let (suite, header, signature) = split_with(DETACHED_MAGIC, bytes)?;
+        let algorithm =
+            AlgorithmId::from_bytes(&suite).map_err(|_| MisuseError::InvalidAlgorithmIdentifier)?;
+        if algorithm.kind() != AlgorithmKind::Signature {
+            return Err(MisuseError::InvalidAlgorithmIdentifier.into());
+        }
+        if signature.len() != signature_size(algorithm) {
+            return Err(MisuseError::InvalidSignatureLength.into());
+        }
+        Ok(Self {
+            algorithm,
+            header,
+            signature,
+        })
+    }
+
+    /// The signature scheme.
+    pub fn algorithm(&self) -> AlgorithmId {
+        self.algorithm
+    }
+
+    /// The associated data the signature covers.
+    pub fn associated_data(&self) -> &'a [u8] {
+        &self.header[FIXED_HEADER_SIZE..]
+    }
+
+    /// The signature.
+    pub fn signature(&self) -> &'a [u8] {
+        self.signature
+    }
+
+    /// The encoded detached signature.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [self.header, self.signature].concat()
+    }
+
+    /// The signed envelope of `content` under this signature, as from
+    /// [`sign`]. The signature is not checked; verify the result.
+    pub fn attach(&self, content: &[u8]) -> Vec<u8> {
+        [&self.attached_header(), content, self.signature].concat()
+    }
+
+    /// Verify the signature over `content` under `public_key`.
+    ///
+    /// # Errors
+    ///
+    /// As for [`SignedEnvelope::verify`].
+    pub fn verify(&self, public_key: &[u8], content: &[u8]) -> Result<()> {
+        let message = signed_message(&self.attached_header(), content);
+        signer(self.algorithm)?.verify(public_key, &message, self.signature)
+    }
+
+    /// The header of the attached form, which the signature covers.
+    fn attached_header(&self) -> Vec<u8> {
+        [&MAGIC, &self.header[DETACHED_MAGIC.len()..]].concat()
+    }
+}
+
+impl fmt::Debug for DetachedSignature<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DetachedSignature")
+            .field("algorithm", &self.algorithm)
+            .field("associated_data_len", &self.associated_data().len())
+            .finish()
+    }
+}
+
+/// What the signature of a signed envelope covers: its header, then the
+/// SHA-512 digest of its content.
+fn signed_message(header: &[u8], content: &[u8]) -> Vec<u8> {
+    [header, &Sha512::digest(content)].concat()
+}
+
+/// Encrypt `plaintext` with `algorithm` under a key derived from
+/// `password` by Argon2id with `params` and a fresh random salt.
+///
+/// The result is an envelope whose associated data is the KDF header; open
+/// it with [`decrypt_with_password`].
+///
+/// # Errors
+///
+/// - `MisuseError::InvalidAlgorithmIdentifier`: If `algorithm` is not an
+///   AEAD
+/// - `MisuseError::FeatureNotEnabled`: If the algorithm's feature is off
+/// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+/// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+///   current [`OpContext`](crate::context::OpContext) has expired
+#[cfg(feature = "argon2")]
+pub fn encrypt_with_password(
+    algorithm: AlgorithmId,
+    password: &[u8],
+    plaintext: &[u8],
+    params: &Argon2idParams,
+) -> Result<Vec<u8>> {
+    let aead = aead(algorithm)?;
+    let mut salt = [0u8; PASSWORD_SALT_SIZE];
+    entropy::fill(&mut salt)?;
+
+    let mut header = Vec::with_capacity(PASSWORD_HEADER_SIZE);
+    header.push(KDF_ARGON2ID);
+    header.extend_from_slice(&params.memory_kib().to_be_bytes());
+    header.extend_from_slice(&params.passes().to_be_bytes());
+    header.extend_from_slice(&params.lanes().to_be_bytes());
+    header.extend_from_slice(&salt);
+
+    let mut key = SecureBuffer::zeroed(aead.key_size());
+    password::argon2id(password, &salt, params, key.as_mut_slice())?;
+    seal(algorithm, key.as_slice(), plaintext, &header)
+}
+
+/// Decrypt the output of [`encrypt_with_password`].
+///
+/// The Argon2id cost comes from the envelope; parameters above
+/// [`Argon2idParams::MAX_MEMORY`] or [`Argon2idParams::MAX_PASSES`] are
+/// rejected before any work is done.
+///
+/// # Errors
+///
+/// - `CryptoError::DecryptionFailed`: If the password is wrong or the
+///   envelope was modified
+/// - `MisuseError::InvalidEncoding`: If the envelope has no password KDF
+///   header
+/// - `MisuseError::InvalidParameterSet`: If the stored Argon2id parameters
+///   are out of range
+/// - Any error of [`Envelope::parse`]
+#[cfg(feature = "argon2")]
+pub fn decrypt_with_password(
+    password: &[u8],
+    sealed: &[u8],
+    budget: &ParseBudget,
+) -> Result<Vec<u8>> {
+    let envelope = Envelope::parse(sealed, budget)?;
+    let Some((&[kdf], rest)) = envelope.associated_data().split_first_chunk::<1>() else {
+        return Err(MisuseError::InvalidEncoding.into());
+    };
+    if kdf != KDF_ARGON2ID || rest.len() != PASSWORD_HEADER_SIZE - 1 {
+        return Err(MisuseError::InvalidEncoding.into());
+    }
+    let (costs, salt) = rest.split_at(3 * 4);
+    let [memory_kib, passes, lanes] = [0, 1, 2]
+        .map(|i| u32::from_be_bytes(costs[4 * i..4 * i + 4].try_into().expect("4-byte field")));
+    let params = Argon2idParams::new(memory_kib, passes, lanes)?;
+
+    let aead = aead(envelope.algorithm)?;
+    let mut key = SecureBuffer::zeroed(aead.key_size());
+    password::argon2id(password, salt, &params, key.as_mut_slice())?;
+    envelope.open(key.as_slice())
+}
+
+fn aead(algorithm: AlgorithmId) -> Result<&'static dyn dynamic::DynAead> {
+    if algorithm.kind() != AlgorithmKind::Aead {
+        return Err(MisuseError::InvalidAlgorithmIdentifier.into());
+    }
+    dynamic::aead(algorithm.name())
+}
+
+/// Signature size of the signature scheme `algorithm`.
+fn signature_size(algorithm: AlgorithmId) -> usize {
+    algorithm.sizes().signature.unwrap_or(0)
+}
+
+fn signer(algorithm: AlgorithmId) -> Result<&'static dyn dynamic::DynSigner> {
+    if algorithm.kind() != AlgorithmKind::Signature {
+        return Err(MisuseError::InvalidAlgorithmIdentifier.into());
+    }
+    dynamic::signer(algorithm.name())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(any(feature = "aes-gcm-siv", feature = "signing"))]
+    use crate::errors::CryptoError;
+
+    #[cfg(feature = "aes-gcm-siv")]
+    fn misuse(bytes: &[u8]) -> Option<MisuseError> {
+        Envelope::parse(bytes, &ParseBudget::DEFAULT)
+            .unwrap_err()
+            .misuse()
+    }
+
+    #[cfg(feature = "aes-gcm-siv")]
+    #[test]
+    fn seals_and_opens() {
+        let key = [7u8; 32];
+        let sealed = seal(AlgorithmId::Aes256GcmSiv, &key, b"message", b"record 7").unwrap();
+        let envelope = Envelope::parse(&sealed, &ParseBudget::DEFAULT).unwrap();
+        assert_eq!(envelope.algorithm(), AlgorithmId::Aes256GcmSiv);
+        assert_eq!(envelope.associated_data(), b"record 7");
+        assert_eq!(envelope.nonce().len(), 12);
+        assert_eq!(envelope.ciphertext().len(), 7);
+        assert_eq!(envelope.tag().len(), 16);
+        assert_eq!(envelope.to_bytes(), sealed);
+        assert_eq!(envelope.open(&key).unwrap(), b"message");
+
+        assert_eq!(
+            envelope.open(&[8u8; 32]).unwrap_err().crypto(),
+            Some(CryptoError::DecryptionFailed)
+        );
+        let mut tampered = sealed.clone();
+        tampered[FIXED_HEADER_SIZE] ^= 1;
+        let envelope = Envelope::parse(&tampered, &ParseBudget::DEFAULT).unwrap();
+        assert_eq!(
+            envelope.open(&key).unwrap_err().crypto(),
+            Some(CryptoError::DecryptionFailed)
+        );
+    }
+
+    #[cfg(feature = "aes-gcm-siv")]
+    #[test]
+    fn parsing_is_strict() {
+        let sealed = seal(AlgorithmId::Aes256GcmSiv, &[7u8; 32], b"", b"").unwrap();
+
+        assert_eq!(
+            misuse(&sealed[..FIXED_HEADER_SIZE - 1]),
+            Some(MisuseError::InvalidEncoding)
+        );
+        assert_eq!(
+            misuse(&sealed[..sealed.len() - 1]),
+            Some(MisuseError::InvalidCiphertextLength)
+        );
+
+        let mut bad = sealed.clone();
+        bad[0] = b'X';
+        assert_eq!(misuse(&bad), Some(MisuseError::InvalidEncoding));
+        let mut bad = sealed.clone();
+        bad[4] = VERSION + 1;
+        assert_eq!(misuse(&bad), Some(MisuseError::InvalidEncoding));
+        let mut bad = sealed.clone();
+        bad[5..7].copy_from_slice(&AlgorithmId::Ed25519.to_bytes());
+        assert_eq!(misuse(&bad), Some(MisuseError::InvalidAlgorithmIdentifier));
+        bad[5..7].copy_from_slice(&[0x00, 0x01]);
+        assert_eq!(misuse(&bad), Some(MisuseError::InvalidAlgorithmIdentifier));
+        let mut bad = sealed.clone();
+        bad[7..11].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert_eq!(misuse(&bad), Some(MisuseError::InvalidEncoding));
+
+        let budget = ParseBudget::DEFAULT.with_max_input_len(sealed.len() - 1);
+        assert_eq!(
+            Envelope::parse(&sealed, &budget).unwrap_err().misuse(),
+            Some(MisuseError::ParseBudgetExceeded)
+        );
+    }
+
+    #[cfg(all(feature = "argon2", feature = "aes-gcm-siv"))]
+    #[test]
+    fn password_round_trip() {
+        let params = Argon2idParams::new(64, 1, 1).unwrap();
+        let sealed =
+            encrypt_with_password(AlgorithmId::Aes256GcmSiv, b"hunter2", b"secret", &params)
+                .unwrap();
+        let envelope = Envelope::parse(&sealed, &ParseBudget::DEFAULT).unwrap();
+        assert_eq!(envelope.associated_data().len(), PASSWORD_HEADER_SIZE);
+        assert_eq!(envelope.associated_data()[0], KDF_ARGON2ID);
+
+        let budget = ParseBudget::DEFAULT;
+        assert_eq!(
+            decrypt_with_password(b"hunter2", &sealed, &budget).unwrap(),
+            b"secret"
+        );
+        assert_eq!(
+            decrypt_with_password(b"hunter3", &sealed, &budget)
+                .unwrap_err()
+                .crypto(),
+            Some(CryptoError::DecryptionFailed)
+        );
+
+        // Raising the stored pass count derives another key
+        let mut tampered = sealed.clone();
+        tampered[FIXED_HEADER_SIZE + 8] += 1;
+        assert_eq!(
+            decrypt_with_password(b"hunter2", &tampered, &budget)
+                .unwrap_err()
+                .crypto(),
+            Some(CryptoError::DecryptionFailed)
+        );
+
+        let plain = seal(AlgorithmId::Aes256GcmSiv, &[7u8; 32], b"", b"aad").unwrap();
+        assert_eq!(
+            decrypt_with_password(b"hunter2", &plain, &budget)
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::InvalidEncoding)
+        );
+    }
+
+    #[cfg(all(feature = "mlkem", feature = "aes-gcm-siv"))]
+    #[test]
+    fn binds_associated_data_to_public_key_envelopes() {
+        let (public_key, secret_key) = Suite::PqStandard.generate_keypair().unwrap();
+        let sealed = seal_with_aad(&public_key, b"message", b"user 7/photo.jpg").unwrap();
+        assert_eq!(Suite::from_header(&sealed).unwrap(), Suite::PqStandard);
+        assert_eq!(
+            open_with_aad(&secret_key, &sealed, b"user 7/photo.jpg").unwrap(),
+            b"message"
+        );
+        for associated_data in [&b"user 8/photo.jpg"[..], b""] {
+            assert_eq!(
+                open_with_aad(&secret_key, &sealed, associated_data)
+                    .unwrap_err()
+                    .crypto(),
+                Some(CryptoError::DecryptionFailed)
+            );
+        }
+    }
+
+    #[cfg(all(feature = "signing", feature = "aes-gcm-siv"))]
+    #[test]
+    fn signs_content_in_the_clear() {
+        let ed25519 = dynamic::signer("Ed25519").unwrap();
+        let (public_key, secret_key) = ed25519.generate_keypair().unwrap();
+        let signed = sign(
+            AlgorithmId::Ed25519,
+            secret_key.as_slice(),
+            b"release.tar",
+            b"v1.2",
+        )
+        .unwrap();
+        let envelope = SignedEnvelope::parse(&signed, &ParseBudget::DEFAULT).unwrap();
+        assert_eq!(envelope.algorithm(), AlgorithmId::Ed25519);
+        assert_eq!(envelope.associated_data(), b"v1.2");
+        assert_eq!(envelope.content(), b"release.tar");
+        assert_eq!(envelope.signature().len(), ed25519.signature_size());
+        assert_eq!(envelope.to_bytes(), signed);
+        assert_eq!(envelope.verify(&public_key).unwrap(), b"release.tar");
+
+        // Every header, content and signature byte is covered
+        for i in 0..signed.len() {
+            let mut tampered = signed.clone();
+            tampered[i] ^= 1;
+            if let Ok(envelope) = SignedEnvelope::parse(&tampered, &ParseBudget::DEFAULT) {
+                assert_eq!(
+                    envelope.verify(&public_key).unwrap_err().crypto(),
+                    Some(CryptoError::VerificationFailed)
+                );
+            }
+        }
+
+        // Signed and encrypted envelopes do not parse as each other
+        assert_eq!(
+            misuse(&signed),
+            Some(MisuseError::InvalidAlgorithmIdentifier)
+        );
+        let sealed = seal(AlgorithmId::Aes256GcmSiv, &[7u8; 32], b"", b"").unwrap();
+        assert_eq!(
+            SignedEnvelope::parse(&sealed, &ParseBudget::DEFAULT)
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::InvalidAlgorithmIdentifier)
+        );
+        assert_eq!(
+            SignedEnvelope::parse(&signed[..FIXED_HEADER_SIZE + 4 + 63], &ParseBudget::DEFAULT)
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::InvalidSignatureLength)
+        );
+        assert_eq!(
+            sign(AlgorithmId::Aes256GcmSiv, &[7u8; 32], b"", b"")
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::InvalidAlgorithmIdentifier)
+        );
+    }
+
+    #[cfg(feature = "signing")]
+    #[test]
+    fn converts_between_detached_and_attached_signatures() {
+        let (public_key, secret_key) = dynamic::signer("Ed25519")
+            .unwrap()
+            .generate_keypair()
+            .unwrap();
+        let key = secret_key.as_slice();
+        let attached = sign(AlgorithmId::Ed25519, key, b"contract", b"context").unwrap();
+        let detached = sign_detached(AlgorithmId::Ed25519, key, b"contract", b"context").unwrap();
+
+        let envelope = SignedEnvelope::parse(&attached, &ParseBudget::DEFAULT).unwrap();
+        assert_eq!(envelope.detach(), detached);
+        let signature = DetachedSignature::parse(&detached, &ParseBudget::DEFAULT).unwrap();
+        assert_eq!(signature.algorithm(), AlgorithmId::Ed25519);
+        assert_eq!(signature.associated_data(), b"context");
+        assert_eq!(signature.signature(), envelope.signature());
+        assert_eq!(signature.to_bytes(), detached);
+        assert_eq!(signature.attach(b"contract"), attached);
+        signature.verify(&public_key, b"contract").unwrap();
+        assert_eq!(
+            signature
+                .verify(&public_key, b"contracT")
+                .unwrap_err()
+                .crypto(),
+            Some(CryptoError::VerificationFailed)
+        );
+
+        // Each form is rejected by the other's parser
+        assert_eq!(
+            SignedEnvelope::parse(&detached, &ParseBudget::DEFAULT)
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::InvalidEncoding)
+        );
+        assert_eq!(
+            DetachedSignature::parse(&attached, &ParseBudget::DEFAULT)
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::InvalidEncoding)
+        );
+        assert_eq!(
+            DetachedSignature::parse(&detached[..detached.len() - 1], &ParseBudget::DEFAULT)
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::InvalidSignatureLength)
+        );
+    }
+
+    #[test]
+    fn rejects_non_aead_algorithms() {
+        assert_eq!(
+            seal(AlgorithmId::MlKem768, &[0u8; 32], b"", b"")
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::InvalidAlgorithmIdentifier)
+        );
+        #[cfg(not(feature = "aegis256"))]
+        assert_eq!(
+            seal(AlgorithmId::Aegis256, &[0u8; 32], b"", b"")
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::FeatureNotEnabled)
+        );
+    }
+}
@@ -0,0 +1,197 @@
+//! Best-effort destruction of files and storage regions.
+//!
+//! # Purpose
+//!
+//! Removing plaintext originals after they have been encrypted, and
+//! clearing regions of files or devices that held secrets.
+//!
+//! Requires the `std` feature.
+//!
+//! # Limits
+//!
+//! Overwriting only destroys data if the write lands on the same physical
+//! storage as the original. Often it does not:
+//!
+//! - SSDs, SD cards and other flash storage remap writes (wear levelling)
+//!   and keep stale copies in spare blocks the OS cannot address
+//! - Copy-on-write and log-structured filesystems (btrfs, ZFS, APFS, F2FS)
+//!   write new data to new blocks, and journals may hold old contents
+//! - Snapshots, backups, swap and hibernation files keep their own copies
+//!
+//! On such systems these functions only remove the file's name and make
+//! recovery harder; they do not guarantee erasure. The dependable way to
+//! destroy data is to never store it in plaintext: encrypt it from the
+//! start and destroy the key. For whole devices, use the drive's own
+//! sanitize or secure-erase command.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use citadel::file::{self, WipePolicy};
+//!
+//! file::secure_delete("report.pdf", WipePolicy::Random)?;
+//! ```
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Seek, SeekFrom, Write};
+use std::ops::Range;
+use std::path::Path;
+
+use crate::internal::entropy;
+
+/// Bytes written per call.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// What to overwrite data with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WipePolicy {
+    /// One pass of zeros (NIST SP 800-88 "Clear" for magnetic media).
+    #[default]
+    Zeros,
+    /// One pass of random bytes, so the overwrite does not reveal which
+    /// regions were wiped on storage that compresses or deduplicates.
+    Random,
+}
+
+/// Overwrite `range` of `target` according to `policy` and flush it.
+///
+/// The range may extend past the current end, which grows `target`.
+///
+/// # Errors
+///
+/// - Any I/O error from seeking or writing
+/// - [`io::ErrorKind::Other`]: If random bytes are unavailable
+pub fn wipe_region<W: Write + Seek + ?Sized>(
+    target: &mut W,
+    range: Range<u64>,
+    policy: WipePolicy,
+) -> io::Result<()> {
+    target.seek(SeekFrom::Start(range.start))?;
+    let mut chunk = vec![0u8; CHUNK_SIZE];
+    let mut remaining = range.end.saturating_sub(range.start);
+    while remaining > 0 {
+        let len = remaining.min(CHUNK_SIZE as u64) as usize;
+        if policy == WipePolicy::Random {
+            entropy::fill(&mut chunk[..len]).map_err(io::Error::other)?;
+        }
+        target.write_all(&chunk[..len])?;
+        remaining -= len as u64;
+    }
+    target.flush()
+}
+
+/// Overwrite the regular file at `path` according to `policy`, sync it to
+/// disk, and delete it.
+///
+/// See the [module documentation](self) for why this cannot guarantee
+/// erasure on flash storage or copy-on-write filesystems.
+///
+/// # Errors
+///
+/// - [`io::ErrorKind::InvalidInput`]: If `path` is not a regular file.
+///   Symbolic links are rejected rather than followed
+/// - Any I/O error from opening, writing, syncing or removing the file;
+///   the file is only removed once the overwrite has been synced
+pub fn secure_delete(path: impl AsRef<Path>, policy: WipePolicy) -> io::Result<()> {
+    let path = path.as_ref();
+    // Checked first for a clear error; opening a directory or FIFO for
+    // writing would fail or block
+    if !fs::symlink_metadata(path)?.is_file() {
+        return Err(not_a_regular_file());
+    }
+
+    // The path may have been swapped since, so open it without following
+    // links and check what was actually opened
+    let mut file = open_no_follow(path)?;
+    let metadata = file.metadata()?;
+    if !metadata.is_file() {
+        return Err(not_a_regular_file());
+    }
+    wipe_region(&mut file, 0..metadata.len(), policy)?;
+    file.sync_all()?;
+    drop(file);
+    fs::remove_file(path)
+}
+
+/// Open `path` for writing, failing if it is a symbolic link.
+fn open_no_follow(path: &Path) -> io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.write(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.custom_flags(libc::O_NOFOLLOW);
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::OpenOptionsExt;
+        // FILE_FLAG_OPEN_REPARSE_POINT: open the link itself, which the
+        // metadata check then rejects
+        options.custom_flags(0x0020_0000);
+    }
+    options.open(path)
+}
+
+fn not_a_regular_file() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, "not a regular file")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn wipes_only_the_region() {
+        let mut target = Cursor::new(vec![0xaa; 200_000]);
+        wipe_region(&mut target, 10..150_000, WipePolicy::Zeros).unwrap();
+        let data = target.into_inner();
+        assert!(data[..10].iter().all(|&b| b == 0xaa));
+        assert!(data[10..150_000].iter().all(|&b| b == 0));
+        assert!(data[150_000..].iter().all(|&b| b == 0xaa));
+
+        let mut target = Cursor::new(vec![0u8; 1000]);
+        wipe_region(&mut target, 0..1000, WipePolicy::Random).unwrap();
+        assert!(target.get_ref().iter().any(|&b| b != 0));
+    }
+
+    #[test]
+    fn deletes_regular_files_only() {
+        let dir = std::env::temp_dir().join(format!("citadel-file-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("secret.txt");
+        fs::write(&path, b"plaintext original").unwrap();
+
+        secure_delete(&path, WipePolicy::Random).unwrap();
+        assert!(!path.exists());
+
+        let error = secure_delete(&dir, WipePolicy::Zeros).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(
+            secure_delete(&path, WipePolicy::Zeros).unwrap_err().kind(),
+            io::ErrorKind::NotFound
+        );
+        fs::remove_dir(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn never_follows_symbolic_links() {
+        let dir = std::env::temp_dir().join(format!("citadel-link-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let target = dir.join("target.txt");
+        let link = dir.join("link.txt");
+        fs::write(&target, b"must survive").unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        assert_eq!(
+            secure_delete(&link, WipePolicy::Zeros).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+        // A link swapped in after the first check is not opened either
+        assert!(open_no_follow(&link).is_err());
+        assert_eq!(fs::read(&target).unwrap(), b"must survive");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,804 @@
+//! Cryptographic hash functions.
+//!
+//! # Purpose
+//!
+//! Concrete implementations of [`HashFunction`] and [`HashContext`] for
+//! protocols and formats that need a specific digest.
+//!
+//! # Algorithms
+//!
+//! - [`Blake3`]: BLAKE3, for fast file integrity checks; not FIPS
+//!   approved. Hashes large inputs on all cores with the `parallel`
+//!   feature. Feature `blake3`
+//! - [`KangarooTwelve`]: KangarooTwelve, RFC 9861. A 12-round Keccak
+//!   tree hash for prehashing very large inputs; parallel like BLAKE3.
+//!   Feature `k12`
+//! - [`Sha256`], [`Sha384`], [`Sha512`]: SHA-2, FIPS 180-4, for interop
+//!   with existing protocols (HKDF-SHA256, TLS, JWS)
+//! - [`Sha3_256`], [`Sha3_384`], [`Sha3_512`]: SHA-3, FIPS 202
+//! - [`Shake128`], [`Shake256`]: SHAKE extendable-output functions, FIPS
+//!   202; see [`Xof`]
+//! - [`CShake128`], [`CShake256`]: SHAKE with a customization string, SP
+//!   800-185. For keyed use see [`mac`](crate::mac)
+//!
+//! # Security
+//!
+//! - Hashes are unkeyed: do not use them as MACs
+//! - SHAKE128 and SHAKE256 give at most 128- and 256-bit security however
+//!   much output is squeezed; squeeze at least 32 and 64 bytes
+//!   respectively for full collision resistance
+//! - Hasher state is zeroized on drop, since it may hold secret input
+//!
+//! # Example
+//!
+//! ```ignore
+//! use citadel::hash::Sha3_384;
+//!
+//! let digest = Sha3_384::digest(b"message");
+//!
+//! let mut hasher = Sha3_384::new();
+//! hasher.update(b"mess");
+//! hasher.update(b"age");
+//! assert_eq!(hasher.finalize(), digest);
+//! ```
+
+use core::fmt;
+
+#[cfg(feature = "blake3")]
+use crate::algorithms::classical::blake3;
+#[cfg(feature = "k12")]
+use crate::algorithms::classical::k12::K12;
+use crate::algorithms::classical::keccak::{self, Sponge};
+use crate::algorithms::classical::{sha256, sha384, sha512};
+use crate::errors::{MisuseError, Result};
+use crate::internal::traits::{HashContext, HashFunction};
+
+pub use crate::internal::traits::Xof;
+
+/// BLAKE3 with a 32-byte digest and extendable output.
+#[cfg(feature = "blake3")]
+#[derive(Clone)]
+pub struct Blake3 {
+    inner: blake3::Hasher,
+}
+
+#[cfg(feature = "blake3")]
+impl Blake3 {
+    /// Digest size in bytes.
+    pub const OUTPUT_SIZE: usize = 32;
+
+    /// A hasher with no input absorbed.
+    pub fn new() -> Self {
+        Self {
+            inner: blake3::Hasher::new(),
+        }
+    }
+
+    /// Absorb more input.
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    /// Absorb more input, hashing it on the rayon thread pool.
+    ///
+    /// Gives the same digest as [`update`](Self::update); only worthwhile
+    /// for inputs of hundreds of kilobytes or more.
+    #[cfg(feature = "parallel")]
+    pub fn update_parallel(&mut self, data: &[u8]) {
+        self.inner.update_parallel(data);
+    }
+
+    /// Finish hashing and return the digest.
+    pub fn finalize(self) -> [u8; 32] {
+        self.inner.finalize()
+    }
+
+    /// Finish hashing and fill `output` with extendable output, of which
+    /// the digest is a prefix.
+    pub fn finalize_xof(self, output: &mut [u8]) {
+        self.inner.finalize_xof(output);
+    }
+
+    /// Hash `data` in one call.
+    pub fn digest(data: &[u8]) -> [u8; 32] {
+        let mut hasher = Self::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+}
+
+#[cfg(feature = "blake3")]
+impl Default for Blake3 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "blake3")]
+impl fmt::Debug for Blake3 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Blake3 { <redacted> }")
+    }
+}
+
+#[cfg(feature = "blake3")]
+impl HashFunction<32> for Blake3 {
+    fn hash(&self, input: &[u8]) -> Result<[u8; 32]> {
+        Ok(Self::digest(input))
+    }
+
+    fn new_context(&self) -> Box<dyn HashContext<32>> {
+        Box::new(Self::new())
+    }
+}
+
+#[cfg(feature = "blake3")]
+impl HashContext<32> for Blake3 {
+    fn update(&mut self, data: &[u8]) {
+        Blake3::update(self, data);
+    }
+
+    fn finalize(self: Box<Self>) -> [u8; 32] {
+        Blake3::finalize(*self)
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+/// KangarooTwelve with an optional customization string.
+///
+/// Streaming and one-shot; [`finalize`](Self::finalize) gives a 32-byte
+/// digest, [`finalize_xof`](Self::finalize_xof) any length.
+#[cfg(feature = "k12")]
+#[derive(Clone)]
+pub struct KangarooTwelve {
+    inner: K12,
+}
+
+#[cfg(feature = "k12")]
+impl KangarooTwelve {
+    /// Digest size in bytes of [`finalize`](Self::finalize).
+    pub const OUTPUT_SIZE: usize = 32;
+
+    /// A hasher with an empty customization string.
+    pub fn new() -> Self {
+        Self::with_customization(b"")
+    }
+
+    /// A hasher whose output is domain-separated by `customization`.
+    pub fn with_customization(customization: &[u8]) -> Self {
+        Self {
+            inner: K12::new(customization),
+        }
+    }
+
+    /// Absorb more input.
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    /// Absorb more input, hashing whole 8 KiB leaves on the rayon thread
+    /// pool.
+    ///
+    /// Gives the same digest as [`update`](Self::update).
+    #[cfg(feature = "parallel")]
+    pub fn update_parallel(&mut self, data: &[u8]) {
+        self.inner.update_parallel(data);
+    }
+
+    /// Finish hashing and return a 32-byte digest.
+    pub fn finalize(self) -> [u8; 32] {
+        let mut digest = [0u8; 32];
+        self.inner.finalize_xof(&mut digest);
+        digest
+    }
+
+    /// Finish hashing and fill `output`.
+    pub fn finalize_xof(self, output: &mut [u8]) {
+        self.inner.finalize_xof(output);
+    }
+
+    /// Hash `data` in one call.
+    pub fn digest(data: &[u8]) -> [u8; 32] {
+        let mut hasher = Self::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+}
+
+#[cfg(feature = "k12")]
+impl Default for KangarooTwelve {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "k12")]
+impl fmt::Debug for KangarooTwelve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("KangarooTwelve { <redacted> }")
+    }
+}
+
+#[cfg(feature = "k12")]
+impl HashFunction<32> for KangarooTwelve {
+    fn hash(&self, input: &[u8]) -> Result<[u8; 32]> {
+        let mut hasher = Self::with_customization(self.inner.customization());
+        hasher.update(input);
+        Ok(hasher.finalize())
+    }
+
+    fn new_context(&self) -> Box<dyn HashContext<32>> {
+        Box::new(Self::with_customization(self.inner.customization()))
+    }
+}
+
+#[cfg(feature = "k12")]
+impl HashContext<32> for KangarooTwelve {
+    fn update(&mut self, data: &[u8]) {
+        KangarooTwelve::update(self, data);
+    }
+
+    fn finalize(self: Box<Self>) -> [u8; 32] {
+        KangarooTwelve::finalize(*self)
+    }
+
+    /// Reset to the initial state, keeping the customization string.
+    fn reset(&mut self) {
+        *self = Self::with_customization(self.inner.customization());
+    }
+}
+
+macro_rules! sha2_hasher {
+    ($name:ident, $inner:ty, $size:literal, $doc:literal) => {
+        #[doc = $doc]
+        #[derive(Clone)]
+        pub struct $name {
+            inner: $inner,
+        }
+
+        impl $name {
+            /// Digest size in bytes.
+            pub const OUTPUT_SIZE: usize = $size;
+
+            /// A hasher with no input absorbed.
+            pub fn new() -> Self {
+                Self {
+                    inner: <$inner>::new(),
+                }
+            }
+
+            /// Absorb more input.
+            pub fn update(&mut self, data: &[u8]) {
+                self.inner.update(data);
+            }
+
+            /// Finish hashing and return the digest.
+            pub fn finalize(self) -> [u8; $size] {
+                self.inner.finalize()
+            }
+
+            /// Hash `data` in one call.
+            pub fn digest(data: &[u8]) -> [u8; $size] {
+                <$inner>::digest(data)
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(concat!(stringify!($name), " { <redacted> }"))
+            }
+        }
+
+        impl HashFunction<$size> for $name {
+            fn hash(&self, input: &[u8]) -> Result<[u8; $size]> {
+                Ok(Self::digest(input))
+            }
+
+            fn new_context(&self) -> Box<dyn HashContext<$size>> {
+                Box::new(Self::new())
+            }
+        }
+
+        impl HashContext<$size> for $name {
+            fn update(&mut self, data: &[u8]) {
+                $name::update(self, data);
+            }
+
+            fn finalize(self: Box<Self>) -> [u8; $size] {
+                $name::finalize(*self)
+            }
+
+            fn reset(&mut self) {
+                *self = Self::new();
+            }
+        }
+    };
+}
+
+sha2_hasher!(Sha256, sha256::Sha256, 32, "SHA-256 (FIPS 180-4).");
+sha2_hasher!(Sha384, sha384::Sha384, 48, "SHA-384 (FIPS 180-4).");
+sha2_hasher!(Sha512, sha512::Sha512, 64, "SHA-512 (FIPS 180-4).");
+
+/// SHA-3 with an `N`-byte digest.
+///
+/// Use the [`Sha3_256`], [`Sha3_384`] and [`Sha3_512`] aliases; other
+/// values of `N` fail to compile.
+#[derive(Clone)]
+pub struct Sha3<const N: usize> {
+    sponge: Sponge,
+}
+
+/// SHA3-256 (FIPS 202).
+pub type Sha3_256 = Sha3<32>;
+/// SHA3-384 (FIPS 202).
+pub type Sha3_384 = Sha3<48>;
+/// SHA3-512 (FIPS 202).
+pub type Sha3_512 = Sha3<64>;
+
+impl<const N: usize> Sha3<N> {
+    const VALID_OUTPUT_SIZE: () = assert!(N == 32 || N == 48 || N == 64);
+
+    /// Digest size in bytes.
+    pub const OUTPUT_SIZE: usize = N;
+
+    /// A hasher with no input absorbed.
+    pub fn new() -> Self {
+        let () = Self::VALID_OUTPUT_SIZE;
+        Self {
+            sponge: keccak::sha3(N),
+        }
+    }
+
+    /// Absorb more input.
+    pub fn update(&mut self, data: &[u8]) {
+        self.sponge.absorb(data);
+    }
+
+    /// Finish hashing and return the digest.
+    pub fn finalize(mut self) -> [u8; N] {
+        let mut digest = [0u8; N];
+        self.sponge.squeeze(&mut digest);
+        digest
+    }
+
+    /// Hash `data` in one call.
+    pub fn digest(data: &[u8]) -> [u8; N] {
+        let mut hasher = Self::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+}
+
+impl<const N: usize> Default for Sha3<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> fmt::Debug for Sha3<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Sha3<{N}> {{ <redacted> }}")
+    }
+}
+
+impl<const N: usize> HashFunction<N> for Sha3<N> {
+    fn hash(&self, input: &[u8]) -> Result<[u8; N]> {
+        Ok(Self::digest(input))
+    }
+
+    fn new_context(&self) -> Box<dyn HashContext<N>> {
+        Box::new(Self::new())
+    }
+}
+
+impl<const N: usize> HashContext<N> for Sha3<N> {
+    fn update(&mut self, data: &[u8]) {
+        Sha3::update(self, data);
+    }
+
+    fn finalize(self: Box<Self>) -> [u8; N] {
+        Sha3::finalize(*self)
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+macro_rules! shake_xof {
+    ($name:ident, $constructor:path, $doc:literal) => {
+        #[doc = $doc]
+        #[derive(Clone)]
+        pub struct $name {
+            sponge: Sponge,
+        }
+
+        impl $name {
+            /// An XOF with no input absorbed.
+            pub fn new() -> Self {
+                Self {
+                    sponge: $constructor(),
+                }
+            }
+
+            /// Absorb more input.
+            ///
+            /// # Errors
+            ///
+            /// - `MisuseError::InvalidState`: If output has already been
+            ///   squeezed
+            pub fn absorb(&mut self, data: &[u8]) -> Result<()> {
+                if self.sponge.is_squeezing() {
+                    return Err(MisuseError::InvalidState.into());
+                }
+                self.sponge.absorb(data);
+                Ok(())
+            }
+
+            /// Fill `output` with the next bytes of the output stream.
+            pub fn squeeze(&mut self, output: &mut [u8]) {
+                self.sponge.squeeze(output);
+            }
+
+            /// Absorb `data` and fill `output` in one call.
+            pub fn digest_into(data: &[u8], output: &mut [u8]) {
+                let mut xof = Self::new();
+                xof.sponge.absorb(data);
+                xof.sponge.squeeze(output);
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(concat!(stringify!($name), " { <redacted> }"))
+            }
+        }
+
+        impl Xof for $name {
+            fn absorb(&mut self, data: &[u8]) -> Result<()> {
+                $name::absorb(self, data)
+            }
+
+            fn squeeze(&mut self, output: &mut [u8]) {
+                $name::squeeze(self, output);
+            }
+
+            fn reset(&mut self) {
+                *self = Self::new();
+            }
+        }
+    };
+}
+
+shake_xof!(Shake128, keccak::shake128, "SHAKE128 (FIPS 202).");
+shake_xof!(Shake256, keccak::shake256, "SHAKE256 (FIPS 202).");
+
+macro_rules! cshake_xof {
+    ($name:ident, $constructor:path, $doc:literal) => {
+        #[doc = $doc]
+        ///
+        /// Different customization strings give independent functions; an
+        /// empty one gives plain SHAKE.
+        #[derive(Clone)]
+        pub struct $name {
+            sponge: Sponge,
+            initial: Sponge,
+        }
+
+        impl $name {
+            /// An XOF customized by `customization`, with no input
+            /// absorbed.
+            pub fn new(customization: &[u8]) -> Self {
+                let initial = $constructor(b"", customization);
+                Self {
+                    sponge: initial.clone(),
+                    initial,
+                }
+            }
+
+            /// Absorb more input.
+            ///
+            /// # Errors
+            ///
+            /// - `MisuseError::InvalidState`: If output has already been
+            ///   squeezed
+            pub fn absorb(&mut self, data: &[u8]) -> Result<()> {
+                if self.sponge.is_squeezing() {
+                    return Err(MisuseError::InvalidState.into());
+                }
+                self.sponge.absorb(data);
+                Ok(())
+            }
+
+            /// Fill `output` with the next bytes of the output stream.
+            pub fn squeeze(&mut self, output: &mut [u8]) {
+                self.sponge.squeeze(output);
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(concat!(stringify!($name), " { <redacted> }"))
+            }
+        }
+
+        impl Xof for $name {
+            fn absorb(&mut self, data: &[u8]) -> Result<()> {
+                $name::absorb(self, data)
+            }
+
+            fn squeeze(&mut self, output: &mut [u8]) {
+                $name::squeeze(self, output);
+            }
+
+            /// Reset to the customized initial state.
+            fn reset(&mut self) {
+                self.sponge = self.initial.clone();
+            }
+        }
+    };
+}
+
+cshake_xof!(CShake128, keccak::cshake128, "cSHAKE128 (SP 800-185).");
+cshake_xof!(CShake256, keccak::cshake256, "cSHAKE256 (SP 800-185).");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn fips202_vectors() {
+        // NIST examples: "abc" and 200 bytes of 0xa3
+        let long = [0xa3u8; 200];
+        assert_eq!(
+            hex(&Sha3_256::digest(&long)),
+            "79f38adec5c20307a98ef76e8324afbfd46cfd81b22e3973c65fa1bd9de31787"
+        );
+        assert_eq!(
+            hex(&Sha3_384::digest(b"")),
+            "0c63a75b845e4f7d01107d852e4c2485c51a50aaaa94fc61995e71bbee983a2a\
+             c3713831264adb47fb6bd1e058d5f004"
+        );
+        assert_eq!(
+            hex(&Sha3_384::digest(b"abc")),
+            "ec01498288516fc926459f58e2c6ad8df9b473cb0fc08c2596da7cf0e49be4b2\
+             98d88cea927ac7f539f1edf228376d25"
+        );
+        assert_eq!(
+            hex(&Sha3_384::digest(&long)),
+            "1881de2ca7e41ef95dc4732b8f5f002b189cc1e42b74168ed1732649ce1dbcdd\
+             76197a31fd55ee989f2d7050dd473e8f"
+        );
+        assert_eq!(
+            hex(&Sha3_512::digest(&long)),
+            "e76dfad22084a8b1467fcf2ffa58361bec7628edf5f3fdc0e4805dc48caeeca8\
+             1b7c13c30adf52a3659584739a2df46be589c51ca1a4a8416df6545a1ce8ba00"
+        );
+    }
+
+    #[test]
+    fn fips180_vectors() {
+        // NIST examples: "abc" and one million repetitions of "a"
+        let million = vec![b'a'; 1_000_000];
+        assert_eq!(
+            hex(&Sha256::digest(b"abc")),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            hex(&Sha256::digest(&million)),
+            "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0"
+        );
+        assert_eq!(
+            hex(&Sha384::digest(&million)),
+            "9d0e1809716474cb086e834e310a4a1ced149e9c00f248527972cec5704c2a5b\
+             07b8b3dc38ecc4ebae97ddd87f3d8985"
+        );
+        assert_eq!(
+            hex(&Sha512::digest(&million)),
+            "e718483d0ce769644e2e42c7bc15b4638e1f98b13b2044285632a803afa973eb\
+             de0ff244877ea60a4cb0432ce577c31beb009c5c2c49aa2e4eadb217ad8cc09b"
+        );
+    }
+
+    #[test]
+    fn sha2_context_matches_one_shot() {
+        let data: Vec<u8> = (0..1000u32).map(|i| i as u8).collect();
+        let mut context = Sha384::new().new_context();
+        for chunk in data.chunks(7) {
+            context.update(chunk);
+        }
+        assert_eq!(context.finalize(), Sha384::digest(&data));
+
+        let mut context = Sha256::new().new_context();
+        context.update(b"discarded");
+        context.reset();
+        context.update(b"abc");
+        assert_eq!(context.finalize(), Sha256::digest(b"abc"));
+
+        assert_eq!(Sha512::new().hash(b"abc").unwrap(), Sha512::digest(b"abc"));
+        assert_eq!(format!("{:?}", Sha256::new()), "Sha256 { <redacted> }");
+    }
+
+    #[test]
+    fn context_matches_one_shot() {
+        let data: Vec<u8> = (0..1000u32).map(|i| i as u8).collect();
+        let mut context = Sha3_384::new().new_context();
+        for chunk in data.chunks(7) {
+            context.update(chunk);
+        }
+        assert_eq!(context.finalize(), Sha3_384::digest(&data));
+
+        let mut context = Sha3_512::new().new_context();
+        context.update(b"discarded");
+        context.reset();
+        context.update(b"abc");
+        assert_eq!(context.finalize(), Sha3_512::digest(b"abc"));
+
+        assert_eq!(
+            Sha3_256::new().hash(b"abc").unwrap(),
+            Sha3_256::digest(b"abc")
+        );
+    }
+
+    #[test]
+    fn shake_vectors() {
+        let long = [0xa3u8; 200];
+        let mut out = [0u8; 32];
+        Shake128::digest_into(b"", &mut out);
+        assert_eq!(
+            hex(&out),
+            "7f9c2ba4e88f827d616045507605853ed73b8093f6efbc88eb1a6eacfa66ef26"
+        );
+        Shake128::digest_into(&long, &mut out);
+        assert_eq!(
+            hex(&out),
+            "131ab8d2b594946b9c81333f9bb6e0ce75c3b93104fa3469d3917457385da037"
+        );
+        Shake256::digest_into(&long, &mut out);
+        assert_eq!(
+            hex(&out),
+            "cd8a920ed141aa0407a22d59288652e9d9f1a7ee0c1e7c1ca699424da84a904d"
+        );
+        let mut out = [0u8; 64];
+        Shake256::digest_into(b"", &mut out);
+        assert_eq!(
+            hex(&out),
+            "46b9dd2b0ba88d13233b3feb743eeb243fcd52ea62b81b82b50c27646ed5762f\
+             d75dc4ddd8c0f200cb05019d67b592f6fc821c49479ab48640292eacb3b7c4be"
+        );
+    }
+
+    #[test]
+    fn squeeze_is_a_consistent_stream() {
+        // Bytes 268..300 of SHAKE256("abc") span a permutation boundary
+        let mut xof = Shake256::new();
+        xof.absorb(b"ab").unwrap();
+        xof.absorb(b"c").unwrap();
+        let mut skipped = [0u8; 268];
+        xof.squeeze(&mut skipped[..1]);
+        xof.squeeze(&mut skipped[1..]);
+        let mut tail = [0u8; 32];
+        xof.squeeze(&mut tail);
+        assert_eq!(
+            hex(&tail),
+            "2ddf384af3334560ea1d363966caa7d8ddcbec7da52b42215c11d5f8ee57f341"
+        );
+
+        let error = xof.absorb(b"late").unwrap_err();
+        assert_eq!(error.misuse(), Some(MisuseError::InvalidState));
+
+        let mut context: Box<dyn Xof> = Box::new(xof);
+        context.reset();
+        context.absorb(b"").unwrap();
+        let mut out = [0u8; 32];
+        context.squeeze(&mut out);
+        let mut expected = [0u8; 32];
+        Shake256::digest_into(b"", &mut expected);
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn cshake_customization() {
+        // SP 800-185 cSHAKE sample 1, then reset
+        let mut xof = CShake128::new(b"Email Signature");
+        xof.absorb(&[0, 1, 2, 3]).unwrap();
+        let mut out = [0u8; 32];
+        xof.squeeze(&mut out);
+        assert_eq!(
+            hex(&out),
+            "c1c36925b6409a04f1b504fcbca9d82b4017277cb5ed2b2065fc1d3814d5aaf5"
+        );
+        Xof::reset(&mut xof);
+        xof.absorb(&[0, 1, 2, 3]).unwrap();
+        let mut again = [0u8; 32];
+        xof.squeeze(&mut again);
+        assert_eq!(again, out);
+
+        let mut plain = CShake256::new(b"");
+        plain.absorb(b"abc").unwrap();
+        plain.squeeze(&mut out);
+        let mut expected = [0u8; 32];
+        Shake256::digest_into(b"abc", &mut expected);
+        assert_eq!(out, expected);
+    }
+
+    #[cfg(feature = "blake3")]
+    #[test]
+    fn blake3_hash_function() {
+        assert_eq!(
+            hex(&Blake3::digest(b"")),
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+        );
+        let data: Vec<u8> = (0..100_000u32).map(|i| i as u8).collect();
+        let mut context = Blake3::new().new_context();
+        context.update(b"discarded");
+        context.reset();
+        for chunk in data.chunks(4099) {
+            context.update(chunk);
+        }
+        assert_eq!(context.finalize(), Blake3::digest(&data));
+
+        let mut xof = [0u8; 100];
+        let mut hasher = Blake3::new();
+        hasher.update(&data);
+        hasher.finalize_xof(&mut xof);
+        assert_eq!(xof[..32], Blake3::digest(&data));
+    }
+
+    #[cfg(all(feature = "blake3", feature = "parallel"))]
+    #[test]
+    fn blake3_parallel_matches_serial() {
+        let data: Vec<u8> = (0..1_000_000u32).map(|i| (i % 251) as u8).collect();
+        let mut hasher = Blake3::new();
+        hasher.update_parallel(&data);
+        assert_eq!(hasher.finalize(), Blake3::digest(&data));
+    }
+
+    #[cfg(feature = "k12")]
+    #[test]
+    fn kangaroo_twelve() {
+        // RFC 9861: empty message and customization
+        assert_eq!(
+            hex(&KangarooTwelve::digest(b"")),
+            "1ac2d450fc3b4205d19da7bfca1b37513c0803577ac7167f06fe2ce1f0ef39e5"
+        );
+
+        let data: Vec<u8> = (0..50_000u32).map(|i| i as u8).collect();
+        let hasher = KangarooTwelve::with_customization(b"signing");
+        let mut context = hasher.new_context();
+        context.update(b"discarded");
+        context.reset();
+        for chunk in data.chunks(3000) {
+            context.update(chunk);
+        }
+        let digest = context.finalize();
+        assert_eq!(hasher.hash(&data).unwrap(), digest);
+        assert_ne!(KangarooTwelve::digest(&data), digest);
+
+        #[cfg(feature = "parallel")]
+        {
+            let mut parallel = KangarooTwelve::with_customization(b"signing");
+            parallel.update_parallel(&data);
+            assert_eq!(parallel.finalize(), digest);
+        }
+    }
+}
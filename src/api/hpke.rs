@@ -0,0 +1,1252 @@
+//! Hybrid Public Key Encryption (RFC 9180).
+//!
+//! # Purpose
+//!
+//! HPKE turns a KEM into public-key encryption: the sender encapsulates to
+//! the recipient's public key, both sides run the same key schedule over
+//! the shared secret, and messages are sealed with an AEAD under keys and
+//! nonces derived from it. A [`CipherSuite`] fixes the KEM, KDF and AEAD;
+//! [`CipherSuite::seal`] and [`CipherSuite::open`] encrypt one message,
+//! and [`CipherSuite::setup_sender`] / [`CipherSuite::setup_receiver`]
+//! return contexts for a stream of messages and for exporting secrets.
+//!
+//! # Modes
+//!
+//! All four modes of RFC 9180 are supported, selected by the optional
+//! arguments of the setup functions:
+//!
+//! - Base: no pre-shared key, no sender authentication
+//! - PSK: both sides hold a [`Psk`]
+//! - Auth: the sender proves possession of its own KEM secret key. Only
+//!   [`Kem::DhKemX25519HkdfSha256`] supports it
+//! - AuthPSK: both of the above
+//!
+//! # Algorithms
+//!
+//! | Kind | Algorithm                        | ID       | Source               |
+//! |------|----------------------------------|----------|----------------------|
+//! | KEM  | DHKEM(X25519, HKDF-SHA256)       | `0x0020` | RFC 9180             |
+//! | KEM  | ML-KEM-768                       | `0x0041` | draft-ietf-hpke-pq   |
+//! | KEM  | X-Wing (X25519 + ML-KEM-768)     | `0x647a` | draft-connolly-cfrg-xwing-kem |
+//! | KDF  | HKDF-SHA256, -SHA384, -SHA512    | `0x0001`-`0x0003` | RFC 9180    |
+//! | AEAD | AES-256-GCM-SIV                  | `0xff01` | Citadel              |
+//! | AEAD | AEGIS-256                        | `0xff02` | Citadel              |
+//! | AEAD | Export-only                      | `0xffff` | RFC 9180             |
+//!
+//! HPKE has no registered identifiers for the AEADs this crate implements,
+//! so the two AEAD IDs above are Citadel's own: suites using them only
+//! interoperate with other Citadel peers. The KEMs, KDFs and key schedule
+//! follow their specifications. ML-KEM-768 and X-Wing need the `mlkem`
+//! feature and each AEAD its own feature; otherwise setup returns
+//! `MisuseError::FeatureNotEnabled`.
+//!
+//! X-Wing secret keys are the expanded `ML-KEM secret key || X25519 secret
+//! key` (2432 bytes) rather than the draft's 32-byte seed; ciphertexts and
+//! shared secrets are those of the draft.
+//!
+//! # Security
+//!
+//! - Base and PSK modes do not authenticate the sender: anyone with the
+//!   recipient's public key can seal. Use Auth mode or sign the message
+//!   when the recipient must know who sent it
+//! - Every encapsulation is fresh, so keys and nonces never repeat across
+//!   setups; within a context the nonce is derived from a sequence number
+//!   that is never reused
+//! - `info` binds the derived keys to the application's context; use a
+//!   distinct value per purpose
+//!
+//! # Example
+//!
+//! ```ignore
+//! use citadel::hpke::{Aead, CipherSuite, Kdf, Kem};
+//!
+//! let suite = CipherSuite::new(Kem::XWing, Kdf::HkdfSha256, Aead::Aes256GcmSiv);
+//! let (public_key, secret_key) = suite.kem().generate_keypair()?;
+//!
+//! let (enc, ciphertext) = suite.seal(&public_key, b"app v1", b"header", b"message")?;
+//! let plaintext = suite.open(&enc, secret_key.as_slice(), b"app v1", b"header", &ciphertext)?;
+//! ```
+
+use core::fmt;
+
+use crate::algorithm::AlgorithmId;
+use crate::algorithms::classical::hkdf;
+use crate::algorithms::classical::sha256::Sha256;
+use crate::algorithms::classical::sha384::Sha384;
+use crate::algorithms::classical::sha512::Sha512;
+use crate::algorithms::classical::x25519::X25519;
+#[cfg(feature = "mlkem")]
+use crate::algorithms::pq::ml_kem_768;
+use crate::consts;
+use crate::context;
+use crate::errors::{MisuseError, Result};
+#[cfg(feature = "mlkem")]
+use crate::hybrid::{CombinerInput, KemCombiner, XWingCombiner};
+use crate::internal::aead;
+use crate::internal::traits::KeyAgreement;
+#[cfg(feature = "mlkem")]
+use crate::internal::traits::KeyEncapsulation;
+use crate::memory::{SecureBuffer, SensitiveBytes};
+
+/// Prefix of every labeled KDF input (RFC 9180, section 4).
+const VERSION_LABEL: &[u8] = b"HPKE-v1";
+
+/// Size of every KEM shared secret.
+const SHARED_SECRET_SIZE: usize = 32;
+
+const MODE_BASE: u8 = 0x00;
+const MODE_PSK: u8 = 0x01;
+const MODE_AUTH: u8 = 0x02;
+const MODE_AUTH_PSK: u8 = 0x03;
+
+/// Key encapsulation mechanism.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Kem {
+    /// DHKEM(X25519, HKDF-SHA256), RFC 9180.
+    DhKemX25519HkdfSha256,
+    /// ML-KEM-768 used directly as the KEM.
+    MlKem768,
+    /// X-Wing: ML-KEM-768 and X25519 combined with SHA3-256.
+    XWing,
+}
+
+impl Kem {
+    /// HPKE KEM identifier.
+    pub const fn id(self) -> u16 {
+        match self {
+            Kem::DhKemX25519HkdfSha256 => 0x0020,
+            Kem::MlKem768 => 0x0041,
+            Kem::XWing => 0x647a,
+        }
+    }
+
+    /// Public key size in bytes (`Npk`).
+    pub const fn public_key_size(self) -> usize {
+        match self {
+            Kem::DhKemX25519HkdfSha256 => consts::X25519::PUBLIC_KEY_SIZE,
+            Kem::MlKem768 => consts::MlKem768::PUBLIC_KEY_SIZE,
+            Kem::XWing => consts::MlKem768::PUBLIC_KEY_SIZE + consts::X25519::PUBLIC_KEY_SIZE,
+        }
+    }
+
+    /// Secret key size in bytes (`Nsk`).
+    pub const fn secret_key_size(self) -> usize {
+        match self {
+            Kem::DhKemX25519HkdfSha256 => consts::X25519::SECRET_KEY_SIZE,
+            Kem::MlKem768 => consts::MlKem768::SECRET_KEY_SIZE,
+            Kem::XWing => consts::MlKem768::SECRET_KEY_SIZE + consts::X25519::SECRET_KEY_SIZE,
+        }
+    }
+
+    /// Encapsulated key size in bytes (`Nenc`).
+    pub const fn enc_size(self) -> usize {
+        match self {
+            Kem::DhKemX25519HkdfSha256 => consts::X25519::PUBLIC_KEY_SIZE,
+            Kem::MlKem768 => consts::MlKem768::CIPHERTEXT_SIZE,
+            Kem::XWing => consts::MlKem768::CIPHERTEXT_SIZE + consts::X25519::PUBLIC_KEY_SIZE,
+        }
+    }
+
+    /// Whether the KEM supports the Auth and AuthPSK modes.
+    pub const fn supports_auth(self) -> bool {
+        matches!(self, Kem::DhKemX25519HkdfSha256)
+    }
+
+    /// Generate a recipient (or, in Auth mode, sender) keypair.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::FeatureNotEnabled`: If the KEM's feature is off
+    /// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+    /// - `CryptoError::RandomnessFailure`: If the system RNG fails a health test
+    pub fn generate_keypair(self) -> Result<(Vec<u8>, SecureBuffer)> {
+        context::checkpoint()?;
+        match self {
+            Kem::DhKemX25519HkdfSha256 => {
+                let (public_key, secret_key) = X25519.generate_keypair()?;
+                Ok((public_key.to_vec(), SensitiveBytes::new(secret_key).into()))
+            }
+            #[cfg(feature = "mlkem")]
+            Kem::MlKem768 => {
+                let (public_key, secret_key) = ml_kem_768::MlKem768.generate_keypair()?;
+                Ok((public_key.to_vec(), SensitiveBytes::new(secret_key).into()))
+            }
+            #[cfg(feature = "mlkem")]
+            Kem::XWing => {
+                let (pq_public, pq_secret) = ml_kem_768::MlKem768.generate_keypair()?;
+                let pq_secret = SensitiveBytes::new(pq_secret);
+                let (classical_public, classical_secret) = X25519.generate_keypair()?;
+                let classical_secret = SensitiveBytes::new(classical_secret);
+                let mut secret_key = SecureBuffer::zeroed(self.secret_key_size());
+                let (pq, classical) = secret_key
+                    .as_mut_slice()
+                    .split_at_mut(consts::MlKem768::SECRET_KEY_SIZE);
+                pq.copy_from_slice(pq_secret.as_bytes());
+                classical.copy_from_slice(classical_secret.as_bytes());
+                Ok(([&pq_public[..], &classical_public[..]].concat(), secret_key))
+            }
+            #[cfg(not(feature = "mlkem"))]
+            Kem::MlKem768 | Kem::XWing => Err(MisuseError::FeatureNotEnabled.into()),
+        }
+    }
+
+    /// `Encap` or, with a sender secret key, `AuthEncap`.
+    fn encap(
+        self,
+        public_key: &[u8],
+        sender_secret_key: Option<&[u8]>,
+    ) -> Result<(Vec<u8>, SensitiveBytes<SHARED_SECRET_SIZE>)> {
+        let public_key = check(
+            public_key,
+            self.public_key_size(),
+            MisuseError::InvalidPublicKeyLength,
+        )?;
+        match self {
+            Kem::DhKemX25519HkdfSha256 => {
+                let (_, ephemeral) = X25519.generate_keypair()?;
+                let ephemeral = SensitiveBytes::new(ephemeral);
+                let sender = sender_secret_key.map(x25519_secret_key).transpose()?;
+                let (enc, shared) =
+                    dhkem_encap(ephemeral.as_bytes(), x25519_key(public_key)?, sender)?;
+                Ok((enc.to_vec(), shared))
+            }
+            #[cfg(feature = "mlkem")]
+            Kem::MlKem768 => {
+                let (ciphertext, shared) = ml_kem_768::MlKem768
+                    .encapsulate(public_key.try_into().expect("length checked"))?;
+                Ok((ciphertext.to_vec(), SensitiveBytes::new(shared)))
+            }
+            #[cfg(feature = "mlkem")]
+            Kem::XWing => {
+                let (pq_public, classical_public) =
+                    public_key.split_at(consts::MlKem768::PUBLIC_KEY_SIZE);
+                let classical_public = x25519_key(classical_public)?;
+                let (pq_ciphertext, pq_shared) = ml_kem_768::MlKem768
+                    .encapsulate(pq_public.try_into().expect("length checked"))?;
+                let pq_shared = SensitiveBytes::new(pq_shared);
+                let (classical_ciphertext, ephemeral) = X25519.generate_keypair()?;
+                let ephemeral = SensitiveBytes::new(ephemeral);
+                let classical_shared =
+                    X25519::diffie_hellman(ephemeral.as_bytes(), classical_public)?;
+                let shared = xwing_combine(
+                    &pq_shared,
+                    &classical_shared,
+                    &pq_ciphertext,
+                    &classical_ciphertext,
+                    pq_public,
+                    classical_public,
+                );
+                Ok((
+                    [&pq_ciphertext[..], &classical_ciphertext[..]].concat(),
+                    shared,
+                ))
+            }
+            #[cfg(not(feature = "mlkem"))]
+            Kem::MlKem768 | Kem::XWing => Err(MisuseError::FeatureNotEnabled.into()),
+        }
+    }
+
+    /// `Decap` or, with a sender public key, `AuthDecap`.
+    fn decap(
+        self,
+        enc: &[u8],
+        secret_key: &[u8],
+        sender_public_key: Option<&[u8]>,
+    ) -> Result<SensitiveBytes<SHARED_SECRET_SIZE>> {
+        let enc = check(enc, self.enc_size(), MisuseError::InvalidCiphertextLength)?;
+        let secret_key = check(
+            secret_key,
+            self.secret_key_size(),
+            MisuseError::InvalidSecretKeyLength,
+        )?;
+        match self {
+            Kem::DhKemX25519HkdfSha256 => {
+                let sender = sender_public_key
+                    .map(|key| {
+                        check(
+                            key,
+                            self.public_key_size(),
+                            MisuseError::InvalidPublicKeyLength,
+                        )
+                    })
+                    .transpose()?
+                    .map(x25519_key)
+                    .transpose()?;
+                dhkem_decap(x25519_key(enc)?, x25519_secret_key(secret_key)?, sender)
+            }
+            #[cfg(feature = "mlkem")]
+            Kem::MlKem768 => {
+                let shared = ml_kem_768::MlKem768.decapsulate(
+                    secret_key.try_into().expect("length checked"),
+                    enc.try_into().expect("length checked"),
+                )?;
+                Ok(SensitiveBytes::new(shared))
+            }
+            #[cfg(feature = "mlkem")]
+            Kem::XWing => {
+                let (pq_ciphertext, classical_ciphertext) =
+                    enc.split_at(consts::MlKem768::CIPHERTEXT_SIZE);
+                let (pq_secret, classical_secret) =
+                    secret_key.split_at(consts::MlKem768::SECRET_KEY_SIZE);
+                let classical_secret = x25519_secret_key(classical_secret)?;
+                let classical_ciphertext = x25519_key(classical_ciphertext)?;
+                let pq_shared = SensitiveBytes::new(ml_kem_768::MlKem768.decapsulate(
+                    pq_secret.try_into().expect("length checked"),
+                    pq_ciphertext.try_into().expect("length checked"),
+                )?);
+                let classical_shared =
+                    X25519::diffie_hellman(classical_secret, classical_ciphertext)?;
+                // An ML-KEM decapsulation key embeds the encapsulation key.
+                let pq_public = &pq_secret[ML_KEM_PUBLIC_KEY_OFFSET
+                    ..ML_KEM_PUBLIC_KEY_OFFSET + consts::MlKem768::PUBLIC_KEY_SIZE];
+                let classical_public = X25519::public_key(classical_secret);
+                Ok(xwing_combine(
+                    &pq_shared,
+                    &classical_shared,
+                    pq_ciphertext,
+                    classical_ciphertext,
+                    pq_public,
+                    &classical_public,
+                ))
+            }
+            #[cfg(not(feature = "mlkem"))]
+            Kem::MlKem768 | Kem::XWing => Err(MisuseError::FeatureNotEnabled.into()),
+        }
+    }
+}
+
+/// Offset of the encapsulation key inside an ML-KEM-768 decapsulation key
+/// (FIPS 203: `dk = dk_pke || ek || H(ek) || z`).
+#[cfg(feature = "mlkem")]
+const ML_KEM_PUBLIC_KEY_OFFSET: usize = 1152;
+
+/// Key derivation function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Kdf {
+    /// HKDF-SHA256.
+    HkdfSha256,
+    /// HKDF-SHA384.
+    HkdfSha384,
+    /// HKDF-SHA512.
+    HkdfSha512,
+}
+
+impl Kdf {
+    /// HPKE KDF identifier.
+    pub const fn id(self) -> u16 {
+        match self {
+            Kdf::HkdfSha256 => 0x0001,
+            Kdf::HkdfSha384 => 0x0002,
+            Kdf::HkdfSha512 => 0x0003,
+        }
+    }
+
+    /// Extract output size in bytes (`Nh`).
+    pub const fn output_size(self) -> usize {
+        match self {
+            Kdf::HkdfSha256 => 32,
+            Kdf::HkdfSha384 => 48,
+            Kdf::HkdfSha512 => 64,
+        }
+    }
+
+    fn extract(self, salt: &[u8], ikm: &[u8]) -> SecureBuffer {
+        match self {
+            Kdf::HkdfSha256 => SensitiveBytes::new(hkdf::extract::<Sha256, 32>(salt, ikm)).into(),
+            Kdf::HkdfSha384 => SensitiveBytes::new(hkdf::extract::<Sha384, 48>(salt, ikm)).into(),
+            Kdf::HkdfSha512 => SensitiveBytes::new(hkdf::extract::<Sha512, 64>(salt, ikm)).into(),
+        }
+    }
+
+    fn expand(self, prk: &[u8], info: &[u8], okm: &mut [u8]) {
+        match self {
+            Kdf::HkdfSha256 => hkdf::expand::<Sha256, 32>(prk, info, okm),
+            Kdf::HkdfSha384 => hkdf::expand::<Sha384, 48>(prk, info, okm),
+            Kdf::HkdfSha512 => hkdf::expand::<Sha512, 64>(prk, info, okm),
+        }
+    }
+
+    /// `LabeledExtract(salt, label, ikm)`.
+    fn labeled_extract(
+        self,
+        suite_id: &[u8],
+        salt: &[u8],
+        label: &[u8],
+        ikm: &[u8],
+    ) -> SecureBuffer {
+        let labeled_ikm = SecureBuffer::new([VERSION_LABEL, suite_id, label, ikm].concat());
+        self.extract(salt, labeled_ikm.as_slice())
+    }
+
+    /// `LabeledExpand(prk, label, info, okm.len())`.
+    fn labeled_expand(
+        self,
+        suite_id: &[u8],
+        prk: &[u8],
+        label: &[u8],
+        info: &[u8],
+        okm: &mut [u8],
+    ) {
+        let length = (okm.len() as u16).to_be_bytes();
+        let labeled_info = [&length[..], VERSION_LABEL, suite_id, label, info].concat();
+        self.expand(prk, &labeled_info, okm);
+    }
+}
+
+/// Authenticated encryption algorithm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Aead {
+    /// AES-256-GCM-SIV (Citadel identifier).
+    Aes256GcmSiv,
+    /// AEGIS-256 (Citadel identifier).
+    Aegis256,
+    /// No encryption: contexts only export secrets.
+    ExportOnly,
+}
+
+impl Aead {
+    /// HPKE AEAD identifier.
+    pub const fn id(self) -> u16 {
+        match self {
+            Aead::Aes256GcmSiv => 0xff01,
+            Aead::Aegis256 => 0xff02,
+            Aead::ExportOnly => 0xffff,
+        }
+    }
+
+    /// Key size in bytes (`Nk`).
+    pub const fn key_size(self) -> usize {
+        match self {
+            Aead::Aes256GcmSiv => consts::Aes256GcmSiv::KEY_SIZE,
+            Aead::Aegis256 => consts::Aegis256::KEY_SIZE,
+            Aead::ExportOnly => 0,
+        }
+    }
+
+    /// Nonce size in bytes (`Nn`).
+    pub const fn nonce_size(self) -> usize {
+        match self {
+            Aead::Aes256GcmSiv => consts::Aes256GcmSiv::NONCE_SIZE,
+            Aead::Aegis256 => consts::Aegis256::NONCE_SIZE,
+            Aead::ExportOnly => 0,
+        }
+    }
+
+    /// Tag size in bytes (`Nt`).
+    pub const fn tag_size(self) -> usize {
+        match self {
+            Aead::Aes256GcmSiv => consts::Aes256GcmSiv::TAG_SIZE,
+            Aead::Aegis256 => consts::Aegis256::TAG_SIZE,
+            Aead::ExportOnly => 0,
+        }
+    }
+
+    fn check_enabled(self) -> Result<()> {
+        let enabled = match self {
+            Aead::Aes256GcmSiv => cfg!(feature = "aes-gcm-siv"),
+            Aead::Aegis256 => cfg!(feature = "aegis256"),
+            Aead::ExportOnly => true,
+        };
+        if enabled {
+            Ok(())
+        } else {
+            Err(MisuseError::FeatureNotEnabled.into())
+        }
+    }
+
+    fn seal(self, key: &[u8], nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+        match self.algorithm() {
+            Some(algorithm) => aead::seal(algorithm, key, nonce, aad, plaintext),
+            None => Err(MisuseError::InvalidState.into()),
+        }
+    }
+
+    fn open(self, key: &[u8], nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+        match self.algorithm() {
+            Some(algorithm) => aead::open(algorithm, key, nonce, aad, ciphertext),
+            None => Err(MisuseError::InvalidState.into()),
+        }
+    }
+
+    /// The crate's AEAD, or `None` for export-only.
+    fn algorithm(self) -> Option<AlgorithmId> {
+        match self {
+            Aead::Aes256GcmSiv => Some(AlgorithmId::Aes256GcmSiv),
+            Aead::Aegis256 => Some(AlgorithmId::Aegis256),
+            Aead::ExportOnly => None,
+        }
+    }
+}
+
+/// A pre-shared key and its identifier, for the PSK and AuthPSK modes.
+#[derive(Clone, Copy)]
+pub struct Psk<'a> {
+    key: &'a [u8],
+    id: &'a [u8],
+}
+
+impl<'a> Psk<'a> {
+    /// Shortest accepted key, in bytes.
+    pub const MIN_KEY_SIZE: usize = 32;
+
+    /// A pre-shared `key` known to both sides under `id`.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidKeyLength`: If `key` is shorter than
+    ///   [`MIN_KEY_SIZE`](Self::MIN_KEY_SIZE)
+    /// - `MisuseError::InvalidParameterSet`: If `id` is empty
+    pub fn new(key: &'a [u8], id: &'a [u8]) -> Result<Self> {
+        if key.len() < Self::MIN_KEY_SIZE {
+            return Err(MisuseError::InvalidKeyLength.into());
+        }
+        if id.is_empty() {
+            return Err(MisuseError::InvalidParameterSet.into());
+        }
+        Ok(Self { key, id })
+    }
+}
+
+impl fmt::Debug for Psk<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Psk { .. }")
+    }
+}
+
+/// A KEM, KDF and AEAD combination.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CipherSuite {
+    kem: Kem,
+    kdf: Kdf,
+    aead: Aead,
+}
+
+impl CipherSuite {
+    /// The suite of `kem`, `kdf` and `aead`.
+    pub const fn new(kem: Kem, kdf: Kdf, aead: Aead) -> Self {
+        Self { kem, kdf, aead }
+    }
+
+    /// The KEM.
+    pub const fn kem(self) -> Kem {
+        self.kem
+    }
+
+    /// The KDF.
+    pub const fn kdf(self) -> Kdf {
+        self.kdf
+    }
+
+    /// The AEAD.
+    pub const fn aead(self) -> Aead {
+        self.aead
+    }
+
+    /// `"HPKE" || kem_id || kdf_id || aead_id`, the domain separator of
+    /// every derivation under this suite.
+    pub const fn suite_id(self) -> [u8; 10] {
+        suite_id(self.kem.id(), self.kdf.id(), self.aead.id())
+    }
+
+    /// Encapsulate to `recipient_public_key` and derive a sending context.
+    ///
+    /// Passing `psk` selects PSK mode and `sender_secret_key` Auth mode;
+    /// both select AuthPSK. Returns the encapsulated key `enc`, which the
+    /// recipient needs, and the context.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::FeatureNotEnabled`: If the KEM's or AEAD's feature
+    ///   is off
+    /// - `MisuseError::UnsupportedAlgorithm`: If `sender_secret_key` is
+    ///   given and the KEM does not support Auth mode
+    /// - `MisuseError::InvalidPublicKeyLength` /
+    ///   `MisuseError::InvalidSecretKeyLength`: If a key has the wrong
+    ///   length
+    /// - `CryptoError::KeyEncapsulationFailed`: If a DH result is zero
+    /// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+    pub fn setup_sender(
+        self,
+        recipient_public_key: &[u8],
+        info: &[u8],
+        psk: Option<Psk<'_>>,
+        sender_secret_key: Option<&[u8]>,
+    ) -> Result<(Vec<u8>, SenderContext)> {
+        context::checkpoint()?;
+        self.check(sender_secret_key.is_some())?;
+        let (enc, shared_secret) = self.kem.encap(recipient_public_key, sender_secret_key)?;
+        let mode = mode(psk.is_some(), sender_secret_key.is_some());
+        let schedule = self.key_schedule(mode, &shared_secret, info, psk);
+        Ok((enc, SenderContext(schedule)))
+    }
+
+    /// Decapsulate `enc` with `recipient_secret_key` and derive the
+    /// matching receiving context.
+    ///
+    /// `psk` and `sender_public_key` must match what the sender used.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::FeatureNotEnabled`: If the KEM's or AEAD's feature
+    ///   is off
+    /// - `MisuseError::UnsupportedAlgorithm`: If `sender_public_key` is
+    ///   given and the KEM does not support Auth mode
+    /// - `MisuseError::InvalidCiphertextLength`: If `enc` has the wrong
+    ///   length
+    /// - `MisuseError::InvalidPublicKeyLength` /
+    ///   `MisuseError::InvalidSecretKeyLength`: If a key has the wrong
+    ///   length
+    /// - `CryptoError::KeyEncapsulationFailed`: If a DH result is zero
+    pub fn setup_receiver(
+        self,
+        enc: &[u8],
+        recipient_secret_key: &[u8],
+        info: &[u8],
+        psk: Option<Psk<'_>>,
+        sender_public_key: Option<&[u8]>,
+    ) -> Result<ReceiverContext> {
+        context::checkpoint()?;
+        self.check(sender_public_key.is_some())?;
+        let shared_secret = self
+            .kem
+            .decap(enc, recipient_secret_key, sender_public_key)?;
+        let mode = mode(psk.is_some(), sender_public_key.is_some());
+        let schedule = self.key_schedule(mode, &shared_secret, info, psk);
+        Ok(ReceiverContext(schedule))
+    }
+
+    /// Single-shot Base-mode encryption of `plaintext` to
+    /// `recipient_public_key`.
+    ///
+    /// Returns `(enc, ciphertext)`. For the other modes, call
+    /// [`setup_sender`](Self::setup_sender) and seal once.
+    ///
+    /// # Errors
+    ///
+    /// - As [`setup_sender`](Self::setup_sender)
+    /// - `MisuseError::InvalidState`: If the AEAD is [`Aead::ExportOnly`]
+    pub fn seal(
+        self,
+        recipient_public_key: &[u8],
+        info: &[u8],
+        aad: &[u8],
+        plaintext: &[u8],
+    ) -> Result<(Vec<u8>, Vec<u8>)> {
+        let (enc, mut context) = self.setup_sender(recipient_public_key, info, None, None)?;
+        let ciphertext = context.seal(aad, plaintext)?;
+        Ok((enc, ciphertext))
+    }
+
+    /// Single-shot Base-mode decryption of the output of
+    /// [`seal`](Self::seal).
+    ///
+    /// # Errors
+    ///
+    /// - As [`setup_receiver`](Self::setup_receiver)
+    /// - `CryptoError::DecryptionFailed`: If the ciphertext, `enc`, key,
+    ///   `info` or `aad` do not match
+    pub fn open(
+        self,
+        enc: &[u8],
+        recipient_secret_key: &[u8],
+        info: &[u8],
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>> {
+        let mut context = self.setup_receiver(enc, recipient_secret_key, info, None, None)?;
+        context.open(aad, ciphertext)
+    }
+
+    fn check(self, auth: bool) -> Result<()> {
+        self.aead.check_enabled()?;
+        if auth && !self.kem.supports_auth() {
+            return Err(MisuseError::UnsupportedAlgorithm.into());
+        }
+        Ok(())
+    }
+
+    fn key_schedule(
+        self,
+        mode: u8,
+        shared_secret: &SensitiveBytes<SHARED_SECRET_SIZE>,
+        info: &[u8],
+        psk: Option<Psk<'_>>,
+    ) -> KeySchedule {
+        let (psk, psk_id) = psk.map_or((&[][..], &[][..]), |psk| (psk.key, psk.id));
+        KeySchedule::derive(
+            self.kdf,
+            self.aead,
+            self.suite_id(),
+            mode,
+            shared_secret.as_bytes(),
+            info,
+            psk,
+            psk_id,
+        )
+    }
+}
+
+const fn suite_id(kem: u16, kdf: u16, aead: u16) -> [u8; 10] {
+    let [k0, k1] = kem.to_be_bytes();
+    let [f0, f1] = kdf.to_be_bytes();
+    let [a0, a1] = aead.to_be_bytes();
+    [b'H', b'P', b'K', b'E', k0, k1, f0, f1, a0, a1]
+}
+
+const fn mode(psk: bool, auth: bool) -> u8 {
+    match (psk, auth) {
+        (false, false) => MODE_BASE,
+        (true, false) => MODE_PSK,
+        (false, true) => MODE_AUTH,
+        (true, true) => MODE_AUTH_PSK,
+    }
+}
+
+/// Keys, base nonce and exporter secret of one setup.
+struct KeySchedule {
+    kdf: Kdf,
+    aead: Aead,
+    suite_id: [u8; 10],
+    key: SecureBuffer,
+    base_nonce: SecureBuffer,
+    exporter_secret: SecureBuffer,
+    sequence: u64,
+}
+
+impl KeySchedule {
+    /// `KeySchedule<ROLE>()` of RFC 9180, section 5.1.
+    #[allow(clippy::too_many_arguments)]
+    fn derive(
+        kdf: Kdf,
+        aead: Aead,
+        suite_id: [u8; 10],
+        mode: u8,
+        shared_secret: &[u8],
+        info: &[u8],
+        psk: &[u8],
+        psk_id: &[u8],
+    ) -> Self {
+        let psk_id_hash = kdf.labeled_extract(&suite_id, &[], b"psk_id_hash", psk_id);
+        let info_hash = kdf.labeled_extract(&suite_id, &[], b"info_hash", info);
+        let context = [&[mode][..], psk_id_hash.as_slice(), info_hash.as_slice()].concat();
+        let secret = kdf.labeled_extract(&suite_id, shared_secret, b"secret", psk);
+
+        let mut key = SecureBuffer::zeroed(aead.key_size());
+        let mut base_nonce = SecureBuffer::zeroed(aead.nonce_size());
+        if aead != Aead::ExportOnly {
+            kdf.labeled_expand(
+                &suite_id,
+                secret.as_slice(),
+                b"key",
+                &context,
+                key.as_mut_slice(),
+            );
+            kdf.labeled_expand(
+                &suite_id,
+                secret.as_slice(),
+                b"base_nonce",
+                &context,
+                base_nonce.as_mut_slice(),
+            );
+        }
+        let mut exporter_secret = SecureBuffer::zeroed(kdf.output_size());
+        kdf.labeled_expand(
+            &suite_id,
+            secret.as_slice(),
+            b"exp",
+            &context,
+            exporter_secret.as_mut_slice(),
+        );
+
+        Self {
+            kdf,
+            aead,
+            suite_id,
+            key,
+            base_nonce,
+            exporter_secret,
+            sequence: 0,
+        }
+    }
+
+    /// `base_nonce XOR I2OSP(seq, Nn)`.
+    fn nonce(&self) -> Result<SecureBuffer> {
+        if self.aead == Aead::ExportOnly {
+            return Err(MisuseError::InvalidState.into());
+        }
+        if self.sequence == u64::MAX {
+            return Err(MisuseError::NonceExhausted.into());
+        }
+        let mut nonce = SecureBuffer::new(self.base_nonce.as_slice().to_vec());
+        let sequence = self.sequence.to_be_bytes();
+        let nonce_bytes = nonce.as_mut_slice();
+        let offset = nonce_bytes.len() - sequence.len();
+        for (byte, counter) in nonce_bytes[offset..].iter_mut().zip(sequence) {
+            *byte ^= counter;
+        }
+        Ok(nonce)
+    }
+
+    fn seal(&mut self, aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+        context::checkpoint()?;
+        let nonce = self.nonce()?;
+        let ciphertext = self
+            .aead
+            .seal(self.key.as_slice(), nonce.as_slice(), aad, plaintext)?;
+        self.sequence += 1;
+        Ok(ciphertext)
+    }
+
+    fn open(&mut self, aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+        context::checkpoint()?;
+        let nonce = self.nonce()?;
+        let plaintext = self
+            .aead
+            .open(self.key.as_slice(), nonce.as_slice(), aad, ciphertext)?;
+        self.sequence += 1;
+        Ok(plaintext)
+    }
+
+    fn export(&self, exporter_context: &[u8], length: usize) -> Result<SecureBuffer> {
+        if length == 0 || length > 255 * self.kdf.output_size() {
+            return Err(MisuseError::BufferTooSmall.into());
+        }
+        let mut secret = SecureBuffer::zeroed(length);
+        self.kdf.labeled_expand(
+            &self.suite_id,
+            self.exporter_secret.as_slice(),
+            b"sec",
+            exporter_context,
+            secret.as_mut_slice(),
+        );
+        Ok(secret)
+    }
+}
+
+/// The sender's side of an HPKE setup: seals messages in order.
+pub struct SenderContext(KeySchedule);
+
+crate::memory::never_serialize!([] SenderContext);
+
+impl SenderContext {
+    /// Encrypt the next message.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidState`: If the AEAD is [`Aead::ExportOnly`]
+    /// - `MisuseError::NonceExhausted`: If the context has sealed 2^64 - 1
+    ///   messages
+    pub fn seal(&mut self, aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+        self.0.seal(aad, plaintext)
+    }
+
+    /// Derive a `length`-byte secret bound to this context and
+    /// `exporter_context`.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::BufferTooSmall`: If `length` is 0 or more than 255
+    ///   times the KDF's output size
+    pub fn export(&self, exporter_context: &[u8], length: usize) -> Result<SecureBuffer> {
+        self.0.export(exporter_context, length)
+    }
+}
+
+impl fmt::Debug for SenderContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SenderContext { .. }")
+    }
+}
+
+/// The recipient's side of an HPKE setup: opens messages in the order they
+/// were sealed.
+pub struct ReceiverContext(KeySchedule);
+
+crate::memory::never_serialize!([] ReceiverContext);
+
+impl ReceiverContext {
+    /// Decrypt the next message.
+    ///
+    /// A failed open does not advance the sequence, so the next message
+    /// can still be opened.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::DecryptionFailed`: If the ciphertext or `aad` do
+    ///   not match, or messages arrive out of order
+    /// - `MisuseError::InvalidCiphertextLength`: If `ciphertext` is shorter
+    ///   than a tag
+    /// - `MisuseError::InvalidState`: If the AEAD is [`Aead::ExportOnly`]
+    pub fn open(&mut self, aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+        self.0.open(aad, ciphertext)
+    }
+
+    /// Derive the secret the sender's
+    /// [`export`](SenderContext::export) returns for the same arguments.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::BufferTooSmall`: If `length` is 0 or more than 255
+    ///   times the KDF's output size
+    pub fn export(&self, exporter_context: &[u8], length: usize) -> Result<SecureBuffer> {
+        self.0.export(exporter_context, length)
+    }
+}
+
+impl fmt::Debug for ReceiverContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ReceiverContext { .. }")
+    }
+}
+
+/// `kem_id` suite ID of DHKEM(X25519, HKDF-SHA256).
+const DHKEM_SUITE_ID: [u8; 5] = [b'K', b'E', b'M', 0x00, 0x20];
+
+/// DHKEM `Encap` / `AuthEncap` with a given ephemeral secret key.
+fn dhkem_encap(
+    ephemeral_secret_key: &[u8; 32],
+    recipient_public_key: &[u8; 32],
+    sender_secret_key: Option<&[u8; 32]>,
+) -> Result<([u8; 32], SensitiveBytes<SHARED_SECRET_SIZE>)> {
+    let enc = X25519::public_key(ephemeral_secret_key);
+    let dh = X25519::diffie_hellman(ephemeral_secret_key, recipient_public_key)?;
+    let mut kem_context = [&enc[..], &recipient_public_key[..]].concat();
+    let dh = match sender_secret_key {
+        Some(sender_secret_key) => {
+            let dh_static = X25519::diffie_hellman(sender_secret_key, recipient_public_key)?;
+            kem_context.extend_from_slice(&X25519::public_key(sender_secret_key));
+            SecureBuffer::new([&dh.as_bytes()[..], &dh_static.as_bytes()[..]].concat())
+        }
+        None => dh.into(),
+    };
+    Ok((enc, extract_and_expand(&dh, &kem_context)))
+}
+
+/// DHKEM `Decap` / `AuthDecap`.
+fn dhkem_decap(
+    enc: &[u8; 32],
+    recipient_secret_key: &[u8; 32],
+    sender_public_key: Option<&[u8; 32]>,
+) -> Result<SensitiveBytes<SHARED_SECRET_SIZE>> {
+    let dh = X25519::diffie_hellman(recipient_secret_key, enc)?;
+    let recipient_public_key = X25519::public_key(recipient_secret_key);
+    let mut kem_context = [&enc[..], &recipient_public_key[..]].concat();
+    let dh = match sender_public_key {
+        Some(sender_public_key) => {
+            let dh_static = X25519::diffie_hellman(recipient_secret_key, sender_public_key)?;
+            kem_context.extend_from_slice(sender_public_key);
+            SecureBuffer::new([&dh.as_bytes()[..], &dh_static.as_bytes()[..]].concat())
+        }
+        None => dh.into(),
+    };
+    Ok(extract_and_expand(&dh, &kem_context))
+}
+
+/// DHKEM `ExtractAndExpand(dh, kem_context)`.
+fn extract_and_expand(dh: &SecureBuffer, kem_context: &[u8]) -> SensitiveBytes<SHARED_SECRET_SIZE> {
+    let kdf = Kdf::HkdfSha256;
+    let eae_prk = kdf.labeled_extract(&DHKEM_SUITE_ID, &[], b"eae_prk", dh.as_slice());
+    let mut shared_secret = SensitiveBytes::zeroed();
+    kdf.labeled_expand(
+        &DHKEM_SUITE_ID,
+        eae_prk.as_slice(),
+        b"shared_secret",
+        kem_context,
+        shared_secret.as_bytes_mut(),
+    );
+    shared_secret
+}
+
+#[cfg(feature = "mlkem")]
+fn xwing_combine(
+    pq_shared: &SensitiveBytes<32>,
+    classical_shared: &SensitiveBytes<32>,
+    pq_ciphertext: &[u8],
+    classical_ciphertext: &[u8],
+    pq_public_key: &[u8],
+    classical_public_key: &[u8],
+) -> SensitiveBytes<SHARED_SECRET_SIZE> {
+    SensitiveBytes::new(XWingCombiner.combine(&CombinerInput {
+        pq_shared_secret: pq_shared.as_bytes(),
+        classical_shared_secret: classical_shared.as_bytes(),
+        pq_ciphertext,
+        classical_ciphertext,
+        pq_public_key,
+        classical_public_key,
+    }))
+}
+
+fn check(bytes: &[u8], size: usize, error: MisuseError) -> Result<&[u8]> {
+    if bytes.len() == size {
+        Ok(bytes)
+    } else {
+        Err(error.into())
+    }
+}
+
+fn x25519_key(bytes: &[u8]) -> Result<&[u8; 32]> {
+    bytes
+        .try_into()
+        .map_err(|_| MisuseError::InvalidPublicKeyLength.into())
+}
+
+fn x25519_secret_key(bytes: &[u8]) -> Result<&[u8; 32]> {
+    bytes
+        .try_into()
+        .map_err(|_| MisuseError::InvalidSecretKeyLength.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "aes-gcm-siv")]
+    use crate::errors::CryptoError;
+
+    fn unhex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    /// DHKEM `DeriveKeyPair(ikm)` for X25519.
+    fn derive_key_pair(ikm: &[u8]) -> [u8; 32] {
+        let kdf = Kdf::HkdfSha256;
+        let dkp_prk = kdf.labeled_extract(&DHKEM_SUITE_ID, &[], b"dkp_prk", ikm);
+        let mut secret_key = [0u8; 32];
+        kdf.labeled_expand(
+            &DHKEM_SUITE_ID,
+            dkp_prk.as_slice(),
+            b"sk",
+            &[],
+            &mut secret_key,
+        );
+        secret_key
+    }
+
+    // RFC 9180, appendix A.1.1: DHKEM(X25519, HKDF-SHA256), HKDF-SHA256,
+    // AES-128-GCM, Base mode. The key schedule is run with the vector's
+    // AEAD ID and sizes; sealing is not, since AES-128-GCM is not
+    // implemented.
+    #[test]
+    fn rfc9180_base_mode_vector() {
+        let ephemeral = derive_key_pair(&unhex(
+            "7268600d403fce431561aef583ee1613527cff655c1343f29812e66706df3234",
+        ));
+        assert_eq!(
+            ephemeral.to_vec(),
+            unhex("52c4a758a802cd8b936eceea314432798d5baf2d7e9235dc084ab1b9cfa2f736")
+        );
+        let recipient = derive_key_pair(&unhex(
+            "6db9df30aa07dd42ee5e8181afdb977e538f5e1fec8a06223f33f7013e525037",
+        ));
+        assert_eq!(
+            recipient.to_vec(),
+            unhex("4612c550263fc8ad58375df3f557aac531d26850903e55a9f23f21d8534e8ac8")
+        );
+
+        let recipient_public = X25519::public_key(&recipient);
+        let (enc, shared) = dhkem_encap(&ephemeral, &recipient_public, None).unwrap();
+        assert_eq!(
+            enc.to_vec(),
+            unhex("37fda3567bdbd628e88668c3c8d7e97d1d1253b6d4ea6d44c150f741f1bf4431")
+        );
+        assert_eq!(
+            shared.as_bytes().to_vec(),
+            unhex("fe0e18c9f024ce43799ae393c7e8fe8fce9d218875e8227b0187c04e7d2ea1fc")
+        );
+        assert_eq!(
+            dhkem_decap(&enc, &recipient, None).unwrap().as_bytes(),
+            shared.as_bytes()
+        );
+
+        let info = unhex("4f6465206f6e2061204772656369616e2055726e");
+        let suite_id = suite_id(0x0020, 0x0001, 0x0001);
+        let kdf = Kdf::HkdfSha256;
+        {
+            let psk_id_hash = kdf.labeled_extract(&suite_id, &[], b"psk_id_hash", &[]);
+            let info_hash = kdf.labeled_extract(&suite_id, &[], b"info_hash", &info);
+            let context = [
+                &[MODE_BASE][..],
+                psk_id_hash.as_slice(),
+                info_hash.as_slice(),
+            ]
+            .concat();
+            let secret = kdf.labeled_extract(&suite_id, shared.as_bytes(), b"secret", &[]);
+            let mut key = [0u8; 16];
+            kdf.labeled_expand(&suite_id, secret.as_slice(), b"key", &context, &mut key);
+            assert_eq!(key.to_vec(), unhex("4531685d41d65f03dc48f6b8302c05b0"));
+        }
+
+        // The exporter secret depends only on the KDF and suite ID, so the
+        // full schedule can run with the vector's suite ID.
+        let schedule = KeySchedule::derive(
+            kdf,
+            Aead::ExportOnly,
+            suite_id,
+            MODE_BASE,
+            shared.as_bytes(),
+            &info,
+            &[],
+            &[],
+        );
+        assert_eq!(
+            schedule.exporter_secret.as_slice(),
+            &unhex("45ff1c2e220db587171952c0592d5f5ebe103f1561a2614e38f2ffd47e99e3f8")[..]
+        );
+        assert_eq!(
+            schedule.export(&[], 32).unwrap().as_slice(),
+            &unhex("3853fe2b4035195a573ffc53856e77058e15d9ea064de3e59f4961d0095250ee")[..]
+        );
+        assert_eq!(
+            schedule.export(b"TestContext", 32).unwrap().as_slice(),
+            &unhex("e9e43065102c3836401bed8c3c3c75ae46be1639869391d62c61f1ec7af54931")[..]
+        );
+    }
+
+    #[cfg(feature = "aes-gcm-siv")]
+    #[test]
+    fn all_modes_round_trip() {
+        let suite = CipherSuite::new(
+            Kem::DhKemX25519HkdfSha256,
+            Kdf::HkdfSha256,
+            Aead::Aes256GcmSiv,
+        );
+        let (recipient_public, recipient_secret) = suite.kem().generate_keypair().unwrap();
+        let (sender_public, sender_secret) = suite.kem().generate_keypair().unwrap();
+        let psk = Psk::new(&[9u8; 32], b"psk id").unwrap();
+
+        for (psk, auth) in [
+            (None, false),
+            (Some(psk), false),
+            (None, true),
+            (Some(psk), true),
+        ] {
+            let (enc, mut sender) = suite
+                .setup_sender(
+                    &recipient_public,
+                    b"info",
+                    psk,
+                    auth.then(|| sender_secret.as_slice()),
+                )
+                .unwrap();
+            let mut receiver = suite
+                .setup_receiver(
+                    &enc,
+                    recipient_secret.as_slice(),
+                    b"info",
+                    psk,
+                    auth.then_some(&sender_public[..]),
+                )
+                .unwrap();
+            for message in [&b"first"[..], b"second", b""] {
+                let ciphertext = sender.seal(b"aad", message).unwrap();
+                assert_eq!(receiver.open(b"aad", &ciphertext).unwrap(), message);
+            }
+            assert_eq!(
+                sender.export(b"ctx", 48).unwrap().as_slice(),
+                receiver.export(b"ctx", 48).unwrap().as_slice()
+            );
+        }
+
+        // A receiver without the PSK, or with another sender key, derives
+        // different keys.
+        let (enc, mut sender) = suite
+            .setup_sender(&recipient_public, b"info", Some(psk), None)
+            .unwrap();
+        let ciphertext = sender.seal(b"", b"message").unwrap();
+        let mut receiver = suite
+            .setup_receiver(&enc, recipient_secret.as_slice(), b"info", None, None)
+            .unwrap();
+        assert_eq!(
+            receiver.open(b"", &ciphertext).unwrap_err().crypto(),
+            Some(CryptoError::DecryptionFailed)
+        );
+    }
+
+    #[cfg(all(feature = "mlkem", feature = "aes-gcm-siv"))]
+    #[test]
+    fn post_quantum_kems_round_trip() {
+        for kem in [Kem::MlKem768, Kem::XWing] {
+            let suite = CipherSuite::new(kem, Kdf::HkdfSha384, Aead::Aes256GcmSiv);
+            let (public_key, secret_key) = kem.generate_keypair().unwrap();
+            assert_eq!(public_key.len(), kem.public_key_size());
+            assert_eq!(secret_key.len(), kem.secret_key_size());
+            let (enc, ciphertext) = suite
+                .seal(&public_key, b"info", b"aad", b"message")
+                .unwrap();
+            assert_eq!(enc.len(), kem.enc_size());
+            let plaintext = suite
+                .open(&enc, secret_key.as_slice(), b"info", b"aad", &ciphertext)
+                .unwrap();
+            assert_eq!(plaintext, b"message");
+            assert_eq!(
+                suite
+                    .open(&enc, secret_key.as_slice(), b"other", b"aad", &ciphertext)
+                    .unwrap_err()
+                    .crypto(),
+                Some(CryptoError::DecryptionFailed)
+            );
+
+            let (_, sender_secret) = kem.generate_keypair().unwrap();
+            assert_eq!(
+                suite
+                    .setup_sender(&public_key, b"", None, Some(sender_secret.as_slice()))
+                    .unwrap_err()
+                    .misuse(),
+                Some(MisuseError::UnsupportedAlgorithm)
+            );
+        }
+    }
+
+    #[test]
+    fn export_only_and_misuse() {
+        let suite = CipherSuite::new(
+            Kem::DhKemX25519HkdfSha256,
+            Kdf::HkdfSha512,
+            Aead::ExportOnly,
+        );
+        let (public_key, secret_key) = suite.kem().generate_keypair().unwrap();
+        let (enc, mut sender) = suite.setup_sender(&public_key, b"", None, None).unwrap();
+        let receiver = suite
+            .setup_receiver(&enc, secret_key.as_slice(), b"", None, None)
+            .unwrap();
+        assert_eq!(
+            sender.export(b"key", 64).unwrap().as_slice(),
+            receiver.export(b"key", 64).unwrap().as_slice()
+        );
+        assert_eq!(
+            sender.seal(b"", b"message").unwrap_err().misuse(),
+            Some(MisuseError::InvalidState)
+        );
+        assert_eq!(
+            sender
+                .export(b"", 255 * 64 + 1)
+                .err()
+                .and_then(|e| e.misuse()),
+            Some(MisuseError::BufferTooSmall)
+        );
+        assert_eq!(
+            suite
+                .setup_sender(&public_key[1..], b"", None, None)
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::InvalidPublicKeyLength)
+        );
+        assert_eq!(
+            suite
+                .setup_receiver(&enc[1..], secret_key.as_slice(), b"", None, None)
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::InvalidCiphertextLength)
+        );
+        assert_eq!(
+            Psk::new(&[0u8; 31], b"id").unwrap_err().misuse(),
+            Some(MisuseError::InvalidKeyLength)
+        );
+        assert_eq!(format!("{:?}", sender), "SenderContext { .. }");
+    }
+}
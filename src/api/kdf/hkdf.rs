@@ -0,0 +1,156 @@
+//! HKDF: extract-then-expand key derivation (RFC 5869).
+//!
+//! # Purpose
+//!
+//! Turning shared secrets, such as KEM or Diffie-Hellman outputs, into
+//! independent keys for each purpose.
+//!
+//! # Algorithms
+//!
+//! - [`HkdfSha256`]: HKDF with SHA-256
+//! - [`HkdfSha384`]: HKDF with SHA-384
+//! - [`HkdfSha3_256`]: HKDF over HMAC-SHA3-256
+//!
+//! Each implements [`Kdf`](crate::internal::traits::Kdf) for code generic
+//! over the hash.
+//!
+//! # Security
+//!
+//! - Use a distinct `info` label per derived key; keys with different
+//!   labels are independent
+//! - HKDF is not a password hash: its input must already have high
+//!   entropy. Use [`password`](super::password) for passwords
+//! - Output is limited to 255 hash blocks per `expand` call
+//!
+//! # Example
+//!
+//! ```ignore
+//! use citadel::kdf::hkdf::HkdfSha256;
+//!
+//! let prk = HkdfSha256::extract(&salt, shared_secret.as_bytes());
+//! let mut key = [0u8; 32];
+//! HkdfSha256::expand(&prk, b"myapp v1 encryption key", &mut key)?;
+//! ```
+
+use core::fmt;
+
+use crate::algorithms::classical::hkdf;
+use crate::algorithms::classical::keccak::Sha3;
+use crate::algorithms::classical::sha256::Sha256;
+use crate::algorithms::classical::sha384::Sha384;
+use crate::errors::{MisuseError, Result};
+use crate::internal::traits::Kdf;
+use crate::memory::SensitiveBytes;
+
+macro_rules! hkdf {
+    ($name:ident, $hash:ty, $size:literal, $doc:literal) => {
+        #[doc = $doc]
+        #[derive(Clone, Copy, Default)]
+        pub struct $name;
+
+        impl $name {
+            /// Pseudorandom key size in bytes.
+            pub const PRK_SIZE: usize = $size;
+            /// Longest output of one [`expand`](Self::expand) call, in bytes.
+            pub const MAX_OUTPUT_SIZE: usize = 255 * $size;
+
+            /// Extract a pseudorandom key from `ikm`, salted with `salt`
+            /// (which may be empty).
+            pub fn extract(salt: &[u8], ikm: &[u8]) -> SensitiveBytes<$size> {
+                SensitiveBytes::new(hkdf::extract::<$hash, $size>(salt, ikm))
+            }
+
+            /// Fill `output` with key material for the purpose named by
+            /// `info`.
+            ///
+            /// # Errors
+            ///
+            /// - `MisuseError::BufferTooSmall`: If `output` is empty or
+            ///   longer than [`MAX_OUTPUT_SIZE`](Self::MAX_OUTPUT_SIZE)
+            pub fn expand(
+                prk: &SensitiveBytes<$size>,
+                info: &[u8],
+                output: &mut [u8],
+            ) -> Result<()> {
+                if output.is_empty() || output.len() > Self::MAX_OUTPUT_SIZE {
+                    return Err(MisuseError::BufferTooSmall.into());
+                }
+                hkdf::expand::<$hash, $size>(prk.as_bytes(), info, output);
+                Ok(())
+            }
+
+            /// Extract and expand in one call.
+            ///
+            /// # Errors
+            ///
+            /// - `MisuseError::BufferTooSmall`: If `output` is empty or
+            ///   longer than [`MAX_OUTPUT_SIZE`](Self::MAX_OUTPUT_SIZE)
+            pub fn derive(salt: &[u8], ikm: &[u8], info: &[u8], output: &mut [u8]) -> Result<()> {
+                let prk = Self::extract(salt, ikm);
+                Self::expand(&prk, info, output)
+            }
+        }
+
+        impl Kdf<$size> for $name {
+            fn extract(&self, salt: &[u8], ikm: &[u8]) -> SensitiveBytes<$size> {
+                Self::extract(salt, ikm)
+            }
+
+            fn expand(
+                &self,
+                prk: &SensitiveBytes<$size>,
+                info: &[u8],
+                output: &mut [u8],
+            ) -> Result<()> {
+                Self::expand(prk, info, output)
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(stringify!($name))
+            }
+        }
+    };
+}
+
+hkdf!(HkdfSha256, Sha256, 32, "HKDF-SHA-256 (RFC 5869).");
+hkdf!(HkdfSha384, Sha384, 48, "HKDF-SHA-384 (RFC 5869).");
+hkdf!(HkdfSha3_256, Sha3<32>, 32, "HKDF over HMAC-SHA3-256.");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derive_matches_extract_then_expand() {
+        let prk = HkdfSha3_256::extract(b"salt", b"input key material");
+        let mut expanded = [0u8; 100];
+        HkdfSha3_256::expand(&prk, b"label", &mut expanded).unwrap();
+
+        // Through the trait, as generic code would
+        let mut derived = [0u8; 100];
+        let kdf: &dyn Kdf<32> = &HkdfSha3_256;
+        kdf.derive(b"salt", b"input key material", b"label", &mut derived)
+            .unwrap();
+        assert_eq!(expanded, derived);
+
+        let mut other = [0u8; 100];
+        HkdfSha3_256::expand(&prk, b"other label", &mut other).unwrap();
+        assert_ne!(expanded, other);
+    }
+
+    #[test]
+    fn rejects_bad_output_lengths() {
+        let prk = HkdfSha256::extract(b"", b"ikm");
+        let mut longest = vec![0u8; HkdfSha256::MAX_OUTPUT_SIZE];
+        assert!(HkdfSha256::expand(&prk, b"", &mut longest).is_ok());
+
+        for len in [0, HkdfSha256::MAX_OUTPUT_SIZE + 1] {
+            let error = HkdfSha256::expand(&prk, b"", &mut vec![0u8; len]).unwrap_err();
+            assert_eq!(error.misuse(), Some(MisuseError::BufferTooSmall));
+        }
+        let error = HkdfSha384::derive(b"", b"ikm", b"", &mut []).unwrap_err();
+        assert_eq!(error.misuse(), Some(MisuseError::BufferTooSmall));
+    }
+}
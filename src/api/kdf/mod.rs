@@ -0,0 +1,11 @@
+//! Key derivation.
+//!
+//! # Purpose
+//!
+//! Turning low-entropy or non-uniform secrets into keys.
+//!
+//! - [`hkdf`]: HKDF, for deriving keys from high-entropy shared secrets
+//! - [`password`]: password hashing and password-based key derivation
+
+pub mod hkdf;
+pub mod password;
@@ -0,0 +1,772 @@
+//! Password hashing and password-based key derivation.
+//!
+//! # Purpose
+//!
+//! Storing and verifying password hashes, and deriving keys from
+//! passwords, for example to encrypt a keystore file under a passphrase.
+//!
+//! # Algorithms
+//!
+//! - [`argon2id`]: RFC 9106, PHC id `argon2id`. The default: [`hash`]
+//!   produces Argon2id hashes. Feature `argon2`
+//! - [`scrypt`]: RFC 7914, PHC id `scrypt`. [`scrypt_key`] derives keys for
+//!   scrypt-based encrypted-key formats. Feature `scrypt`
+//! - [`pbkdf2_sha256`]: PBKDF2-HMAC-SHA-256, RFC 8018, PHC id
+//!   `pbkdf2-sha256`
+//!
+//! scrypt and PBKDF2 are legacy choices here: hashes in these formats can
+//! be verified, and [`verify`] reports them as [`Verified::NeedsRehash`] so
+//! callers can replace them with Argon2id hashes once the password is
+//! known good. Argon2id hashes weaker than [`Argon2idParams::INTERACTIVE`]
+//! are reported the same way.
+//!
+//! # Choosing Parameters
+//!
+//! [`Argon2idParams`] has presets for interactive logins, moderate use and
+//! sensitive data such as keystores. [`Argon2idParams::calibrate`] picks a
+//! pass count that takes a target time on the current host.
+//!
+//! # PHC Strings
+//!
+//! [`hash`] writes, and [`verify`] accepts, PHC string format hashes with
+//! B64 (unpadded standard Base64) salt and hash fields:
+//!
+//! ```text
+//! $argon2id$v=19$m=65536,t=2,p=1$<salt>$<hash>
+//! $scrypt$ln=17,r=8,p=1$<salt>$<hash>
+//! $pbkdf2-sha256$i=600000[,l=32]$<salt>$<hash>
+//! ```
+//!
+//! passlib's `$pbkdf2-sha256$<rounds>$<salt>$<hash>` variant, which uses
+//! `.` instead of `+`, is accepted as well.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use citadel::encoding::ParseBudget;
+//! use citadel::kdf::password::{self, Argon2idParams, Verified};
+//!
+//! let stored = password::hash(attempt, &Argon2idParams::INTERACTIVE)?;
+//!
+//! match password::verify(attempt, &stored, &ParseBudget::DEFAULT)? {
+//!     Verified::Current => {}
+//!     Verified::NeedsRehash => { /* store password::hash(attempt, ..) */ }
+//! }
+//!
+//! // A keystore key
+//! let key = password::argon2id_key(passphrase, &salt, &Argon2idParams::SENSITIVE)?;
+//! ```
+
+#[cfg(feature = "argon2")]
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "argon2")]
+use crate::algorithms::classical::argon2 as argon2_imp;
+use crate::algorithms::classical::pbkdf2 as pbkdf2_imp;
+#[cfg(feature = "scrypt")]
+use crate::algorithms::classical::scrypt as scrypt_imp;
+use crate::context;
+use crate::encoding::{ParseBudget, base64};
+use crate::errors::{CryptoError, MisuseError, Result};
+#[cfg(feature = "argon2")]
+use crate::internal::entropy;
+#[cfg(any(feature = "argon2", feature = "scrypt"))]
+use crate::memory::SensitiveBytes;
+use crate::memory::{SecureBuffer, constant_time_eq};
+
+/// Most PBKDF2 iterations accepted.
+///
+/// Bounds the work a stored hash can demand.
+pub const MAX_PBKDF2_ITERATIONS: u32 = 10_000_000;
+
+/// Shortest and longest hash field accepted in a PHC string, in bytes.
+const HASH_LENGTH_RANGE: core::ops::RangeInclusive<usize> = 16..=128;
+
+/// Largest derived key, in bytes.
+const MAX_OUTPUT_SIZE: usize = 1 << 20;
+
+/// Shortest Argon2id output, in bytes (RFC 9106, section 3.1).
+#[cfg(feature = "argon2")]
+const MIN_ARGON2_OUTPUT_SIZE: usize = 4;
+
+/// Salt and hash sizes [`hash`] uses, in bytes.
+#[cfg(feature = "argon2")]
+const SALT_SIZE: usize = 16;
+#[cfg(feature = "argon2")]
+const HASH_SIZE: usize = 32;
+
+/// Argon2id cost parameters.
+#[cfg(feature = "argon2")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Argon2idParams(argon2_imp::Params);
+
+#[cfg(feature = "argon2")]
+impl Argon2idParams {
+    /// 64 MiB, 2 passes, 1 lane: interactive logins. Also the weakest
+    /// parameters [`verify`] reports as [`Verified::Current`].
+    pub const INTERACTIVE: Self = Self(argon2_imp::Params::INTERACTIVE);
+    /// 256 MiB, 3 passes, 1 lane.
+    pub const MODERATE: Self = Self(argon2_imp::Params::MODERATE);
+    /// 1 GiB, 4 passes, 1 lane: keystores and other secrets that are
+    /// derived rarely and attacked offline.
+    pub const SENSITIVE: Self = Self(argon2_imp::Params::SENSITIVE);
+
+    /// Largest working memory accepted, in bytes.
+    pub const MAX_MEMORY: u64 = argon2_imp::MAX_MEMORY;
+    /// Most passes accepted.
+    pub const MAX_PASSES: u32 = argon2_imp::MAX_PASSES;
+
+    /// Cost parameters with `memory_kib` KiB of memory, `passes` passes
+    /// over it and `lanes` lanes.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidParameterSet`: If `passes` or `lanes` is
+    ///   zero, `passes` exceeds [`Self::MAX_PASSES`], `lanes` is `2^24` or
+    ///   more, or memory is below 8 KiB per lane or above
+    ///   [`Self::MAX_MEMORY`]
+    pub fn new(memory_kib: u32, passes: u32, lanes: u32) -> Result<Self> {
+        Ok(Self(argon2_imp::Params::new(memory_kib, passes, lanes)?))
+    }
+
+    /// The pass count that makes one hash with `memory_kib` KiB and
+    /// `lanes` lanes take about `target` on this host.
+    ///
+    /// Runs one single-pass hash to measure, so it takes at least as long
+    /// as that. The result is clamped to `1..=MAX_PASSES`.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidParameterSet`: If `memory_kib` and `lanes` are
+    ///   not valid, as for [`Self::new`]
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    pub fn calibrate(target: Duration, memory_kib: u32, lanes: u32) -> Result<Self> {
+        context::checkpoint()?;
+        let single = argon2_imp::Params::new(memory_kib, 1, lanes)?;
+        let mut output = [0u8; HASH_SIZE];
+        let start = Instant::now();
+        argon2_imp::argon2id(
+            b"calibrate",
+            &[0u8; SALT_SIZE],
+            b"",
+            b"",
+            &single,
+            &mut output,
+        );
+        let elapsed = start.elapsed().max(Duration::from_micros(1));
+        let passes = target.as_nanos().div_ceil(elapsed.as_nanos());
+        let passes = passes.clamp(1, u128::from(Self::MAX_PASSES)) as u32;
+        Self::new(memory_kib, passes, lanes)
+    }
+
+    /// Memory in KiB.
+    #[inline]
+    pub fn memory_kib(&self) -> u32 {
+        self.0.memory_kib()
+    }
+
+    /// Passes over memory.
+    #[inline]
+    pub fn passes(&self) -> u32 {
+        self.0.passes()
+    }
+
+    /// Lanes (parallelism).
+    #[inline]
+    pub fn lanes(&self) -> u32 {
+        self.0.lanes()
+    }
+
+    /// Whether these parameters are at least as costly as `other` in both
+    /// memory and passes.
+    fn at_least(&self, other: &Self) -> bool {
+        self.memory_kib() >= other.memory_kib() && self.passes() >= other.passes()
+    }
+}
+
+/// Fill `output` with Argon2id of `password` and `salt`.
+///
+/// # Errors
+///
+/// - `MisuseError::BufferTooSmall`: If `output` is shorter than 4 bytes or
+///   longer than 1 MiB
+/// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+///   current [`OpContext`](crate::context::OpContext) has expired
+#[cfg(feature = "argon2")]
+pub fn argon2id(
+    password: &[u8],
+    salt: &[u8],
+    params: &Argon2idParams,
+    output: &mut [u8],
+) -> Result<()> {
+    context::checkpoint()?;
+    check_output(output)?;
+    if output.len() < MIN_ARGON2_OUTPUT_SIZE {
+        return Err(MisuseError::BufferTooSmall.into());
+    }
+    argon2_imp::argon2id(password, salt, b"", b"", &params.0, output);
+    Ok(())
+}
+
+/// Derive a 256-bit key from `password` and `salt` with Argon2id.
+///
+/// # Errors
+///
+/// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+///   current [`OpContext`](crate::context::OpContext) has expired
+#[cfg(feature = "argon2")]
+pub fn argon2id_key(
+    password: &[u8],
+    salt: &[u8],
+    params: &Argon2idParams,
+) -> Result<SensitiveBytes<32>> {
+    let mut key = SensitiveBytes::<32>::zeroed();
+    argon2id(password, salt, params, key.as_bytes_mut())?;
+    Ok(key)
+}
+
+/// Hash `password` with Argon2id and a fresh random salt, as a PHC string.
+///
+/// # Errors
+///
+/// - `CryptoError::RandomnessUnavailable`: If no salt can be generated
+/// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+///   current [`OpContext`](crate::context::OpContext) has expired
+#[cfg(feature = "argon2")]
+pub fn hash(password: &[u8], params: &Argon2idParams) -> Result<String> {
+    let mut salt = [0u8; SALT_SIZE];
+    entropy::fill(&mut salt)?;
+    let mut hash = [0u8; HASH_SIZE];
+    argon2id(password, &salt, params, &mut hash)?;
+    Ok(format!(
+        "$argon2id$v=19$m={},t={},p={}${}${}",
+        params.memory_kib(),
+        params.passes(),
+        params.lanes(),
+        encode_b64(&salt),
+        encode_b64(&hash),
+    ))
+}
+
+/// scrypt cost parameters.
+#[cfg(feature = "scrypt")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScryptParams(scrypt_imp::Params);
+
+#[cfg(feature = "scrypt")]
+impl ScryptParams {
+    /// Largest working memory accepted, in bytes.
+    pub const MAX_MEMORY: u64 = scrypt_imp::MAX_MEMORY;
+
+    /// Cost parameters with `N = 2^log_n`, block size `r` and
+    /// parallelism `p`.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidParameterSet`: If a parameter is zero, the
+    ///   RFC 7914 bounds are violated, or the memory cost exceeds
+    ///   [`Self::MAX_MEMORY`]
+    pub fn new(log_n: u8, r: u32, p: u32) -> Result<Self> {
+        Ok(Self(scrypt_imp::Params::new(log_n, r, p)?))
+    }
+
+    /// `log2(N)`.
+    #[inline]
+    pub fn log_n(&self) -> u8 {
+        self.0.log_n()
+    }
+
+    /// Block size.
+    #[inline]
+    pub fn r(&self) -> u32 {
+        self.0.r()
+    }
+
+    /// Parallelism.
+    #[inline]
+    pub fn p(&self) -> u32 {
+        self.0.p()
+    }
+}
+
+/// Fill `output` with scrypt of `password` and `salt`.
+///
+/// # Errors
+///
+/// - `MisuseError::BufferTooSmall`: If `output` is empty or longer than
+///   1 MiB
+/// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+///   current [`OpContext`](crate::context::OpContext) has expired
+#[cfg(feature = "scrypt")]
+pub fn scrypt(
+    password: &[u8],
+    salt: &[u8],
+    params: &ScryptParams,
+    output: &mut [u8],
+) -> Result<()> {
+    context::checkpoint()?;
+    check_output(output)?;
+    scrypt_imp::scrypt(password, salt, &params.0, output);
+    Ok(())
+}
+
+/// Derive a 256-bit key from `password` and `salt` with scrypt, as
+/// scrypt-based encrypted-key formats do.
+///
+/// # Errors
+///
+/// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+///   current [`OpContext`](crate::context::OpContext) has expired
+#[cfg(feature = "scrypt")]
+pub fn scrypt_key(
+    password: &[u8],
+    salt: &[u8],
+    params: &ScryptParams,
+) -> Result<SensitiveBytes<32>> {
+    let mut key = SensitiveBytes::<32>::zeroed();
+    scrypt(password, salt, params, key.as_bytes_mut())?;
+    Ok(key)
+}
+
+/// Fill `output` with PBKDF2-HMAC-SHA-256 of `password` and `salt`.
+///
+/// # Errors
+///
+/// - `MisuseError::InvalidParameterSet`: If `iterations` is zero
+/// - `MisuseError::BufferTooSmall`: If `output` is empty or longer than
+///   1 MiB
+/// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+///   current [`OpContext`](crate::context::OpContext) has expired
+pub fn pbkdf2_sha256(
+    password: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    output: &mut [u8],
+) -> Result<()> {
+    context::checkpoint()?;
+    if iterations == 0 {
+        return Err(MisuseError::InvalidParameterSet.into());
+    }
+    check_output(output)?;
+    pbkdf2_imp::pbkdf2_hmac_sha256(password, salt, iterations, output);
+    Ok(())
+}
+
+fn check_output(output: &[u8]) -> Result<()> {
+    if output.is_empty() || output.len() > MAX_OUTPUT_SIZE {
+        return Err(MisuseError::BufferTooSmall.into());
+    }
+    Ok(())
+}
+
+/// Encode a B64 field: standard Base64 without padding.
+#[cfg(feature = "argon2")]
+fn encode_b64(data: &[u8]) -> String {
+    base64::encode(data).trim_end_matches('=').to_owned()
+}
+
+/// Outcome of a successful [`verify`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Verified {
+    /// The hash uses the current algorithm and parameters.
+    Current,
+    /// The password is correct, but the hash uses a legacy algorithm or
+    /// weak parameters and should be replaced.
+    NeedsRehash,
+}
+
+/// Check `password` against the PHC string `phc`.
+///
+/// # Errors
+///
+/// - `CryptoError::VerificationFailed`: If the password does not match
+/// - `MisuseError::UnsupportedAlgorithm`: If the algorithm id, or the
+///   Argon2 version, is not supported
+/// - `MisuseError::FeatureNotEnabled`: If `phc` is an Argon2id or scrypt
+///   hash and the `argon2` or `scrypt` feature is disabled
+/// - `MisuseError::InvalidParameterSet`: If the cost parameters are out of
+///   range, including PBKDF2 iterations above [`MAX_PBKDF2_ITERATIONS`]
+/// - `MisuseError::InvalidEncoding`: If `phc` is not a well-formed PHC
+///   string
+/// - `MisuseError::ParseBudgetExceeded`: If `phc` exceeds `budget`
+/// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+///   current [`OpContext`](crate::context::OpContext) has expired
+pub fn verify(password: &[u8], phc: &str, budget: &ParseBudget) -> Result<Verified> {
+    context::checkpoint()?;
+    budget.check_input(phc.len())?;
+    let hash = PhcHash::parse(phc, budget)?;
+    let mut derived = SecureBuffer::zeroed(hash.hash.len());
+    let verified = match hash.params {
+        #[cfg(feature = "argon2")]
+        PhcParams::Argon2id(params) => {
+            argon2_imp::argon2id(
+                password,
+                &hash.salt,
+                b"",
+                b"",
+                &params,
+                derived.as_mut_slice(),
+            );
+            if Argon2idParams(params).at_least(&Argon2idParams::INTERACTIVE) {
+                Verified::Current
+            } else {
+                Verified::NeedsRehash
+            }
+        }
+        #[cfg(feature = "scrypt")]
+        PhcParams::Scrypt(params) => {
+            scrypt_imp::scrypt(password, &hash.salt, &params, derived.as_mut_slice());
+            Verified::NeedsRehash
+        }
+        PhcParams::Pbkdf2Sha256 { iterations } => {
+            pbkdf2_imp::pbkdf2_hmac_sha256(
+                password,
+                &hash.salt,
+                iterations,
+                derived.as_mut_slice(),
+            );
+            Verified::NeedsRehash
+        }
+    };
+    if !constant_time_eq(derived.as_slice(), &hash.hash) {
+        return Err(CryptoError::VerificationFailed.into());
+    }
+    Ok(verified)
+}
+
+enum PhcParams {
+    #[cfg(feature = "argon2")]
+    Argon2id(argon2_imp::Params),
+    #[cfg(feature = "scrypt")]
+    Scrypt(scrypt_imp::Params),
+    Pbkdf2Sha256 {
+        iterations: u32,
+    },
+}
+
+/// A parsed PHC string.
+struct PhcHash {
+    params: PhcParams,
+    salt: Vec<u8>,
+    hash: Vec<u8>,
+}
+
+impl PhcHash {
+    fn parse(phc: &str, budget: &ParseBudget) -> Result<Self> {
+        let fields: Vec<&str> = phc.split('$').collect();
+        let (id, version, params, salt, hash) = match fields[..] {
+            ["", id, version, params, salt, hash] if version.starts_with("v=") => {
+                (id, Some(version), params, salt, hash)
+            }
+            ["", id, params, salt, hash] => (id, None, params, salt, hash),
+            _ => return Err(MisuseError::InvalidEncoding.into()),
+        };
+        if version.is_some() && id != "argon2id" {
+            return Err(MisuseError::InvalidEncoding.into());
+        }
+
+        let (params, passlib) = match id {
+            #[cfg(feature = "argon2")]
+            "argon2id" => {
+                // A missing version means Argon2 1.0
+                if version != Some("v=19") {
+                    return Err(MisuseError::UnsupportedAlgorithm.into());
+                }
+                let [memory_kib, passes, lanes] = parse_params(params, ["m", "t", "p"])?;
+                (
+                    PhcParams::Argon2id(argon2_imp::Params::new(memory_kib, passes, lanes)?),
+                    false,
+                )
+            }
+            #[cfg(feature = "scrypt")]
+            "scrypt" => {
+                let [log_n, r, p] = parse_params(params, ["ln", "r", "p"])?;
+                let log_n = u8::try_from(log_n).map_err(|_| MisuseError::InvalidParameterSet)?;
+                (
+                    PhcParams::Scrypt(scrypt_imp::Params::new(log_n, r, p)?),
+                    false,
+                )
+            }
+            "pbkdf2-sha256" => {
+                // passlib writes the bare iteration count
+                let (iterations, passlib) = match params.parse::<u32>() {
+                    Ok(iterations) if is_decimal(params) => (iterations, true),
+                    _ => (parse_pbkdf2_params(params, hash)?, false),
+                };
+                if iterations == 0 || iterations > MAX_PBKDF2_ITERATIONS {
+                    return Err(MisuseError::InvalidParameterSet.into());
+                }
+                (PhcParams::Pbkdf2Sha256 { iterations }, passlib)
+            }
+            #[cfg(not(feature = "argon2"))]
+            "argon2id" => return Err(MisuseError::FeatureNotEnabled.into()),
+            #[cfg(not(feature = "scrypt"))]
+            "scrypt" => return Err(MisuseError::FeatureNotEnabled.into()),
+            _ => return Err(MisuseError::UnsupportedAlgorithm.into()),
+        };
+
+        let hash = decode_b64(hash, passlib, budget)?;
+        if !HASH_LENGTH_RANGE.contains(&hash.len()) {
+            return Err(MisuseError::InvalidEncoding.into());
+        }
+        Ok(Self {
+            params,
+            salt: decode_b64(salt, passlib, budget)?,
+            hash,
+        })
+    }
+}
+
+/// Parse `i=<iterations>[,l=<length>]`, checking `l` against the hash.
+fn parse_pbkdf2_params(params: &str, hash: &str) -> Result<u32> {
+    match params.split_once(",l=") {
+        Some((iterations, length)) => {
+            let [iterations] = parse_params(iterations, ["i"])?;
+            let length = parse_decimal(length)?;
+            // B64 without padding: 4 characters per 3 bytes
+            if (length as usize).checked_mul(4).map(|n| n.div_ceil(3)) != Some(hash.len()) {
+                return Err(MisuseError::InvalidEncoding.into());
+            }
+            Ok(iterations)
+        }
+        None => Ok(parse_params(params, ["i"])?[0]),
+    }
+}
+
+/// Parse `name=value` pairs in exactly the order of `names`.
+fn parse_params<const N: usize>(params: &str, names: [&str; N]) -> Result<[u32; N]> {
+    let mut values = [0u32; N];
+    let mut pairs = params.split(',');
+    for (value, name) in values.iter_mut().zip(names) {
+        let pair = pairs.next().ok_or(MisuseError::InvalidEncoding)?;
+        let (key, text) = pair.split_once('=').ok_or(MisuseError::InvalidEncoding)?;
+        if key != name {
+            return Err(MisuseError::InvalidEncoding.into());
+        }
+        *value = parse_decimal(text)?;
+    }
+    if pairs.next().is_some() {
+        return Err(MisuseError::InvalidEncoding.into());
+    }
+    Ok(values)
+}
+
+/// Parse a PHC decimal: no sign, no leading zeros.
+fn parse_decimal(text: &str) -> Result<u32> {
+    if !is_decimal(text) {
+        return Err(MisuseError::InvalidEncoding.into());
+    }
+    text.parse()
+        .map_err(|_| MisuseError::InvalidParameterSet.into())
+}
+
+fn is_decimal(text: &str) -> bool {
+    !text.is_empty()
+        && text.bytes().all(|c| c.is_ascii_digit())
+        && (text == "0" || !text.starts_with('0'))
+}
+
+/// Decode a B64 field; `passlib` selects passlib's `.`-for-`+` alphabet.
+fn decode_b64(field: &str, passlib: bool, budget: &ParseBudget) -> Result<Vec<u8>> {
+    let plus = if passlib { b'.' } else { b'+' };
+    if !field
+        .bytes()
+        .all(|c| c.is_ascii_alphanumeric() || c == plus || c == b'/')
+    {
+        return Err(MisuseError::InvalidEncoding.into());
+    }
+    if passlib {
+        base64::decode(&field.replace('.', "+"), budget)
+    } else {
+        base64::decode(field, budget)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::Error;
+
+    fn verify_default(password: &[u8], phc: &str) -> Result<Verified> {
+        verify(password, phc, &ParseBudget::DEFAULT)
+    }
+
+    #[cfg(feature = "argon2")]
+    #[test]
+    fn verifies_argon2id() {
+        // Reference implementation test vector
+        let phc = "$argon2id$v=19$m=65536,t=2,p=1$c29tZXNhbHQ$\
+                   CTFhFdXPJO1aFaMaO6Mm5c8y7cJHAph8ArZWb2GRPPc";
+        assert_eq!(verify_default(b"password", phc), Ok(Verified::Current));
+        assert_eq!(
+            verify_default(b"Password", phc),
+            Err(Error::Crypto(CryptoError::VerificationFailed))
+        );
+    }
+
+    #[cfg(feature = "argon2")]
+    #[test]
+    fn hash_round_trips() {
+        let params = Argon2idParams::new(64, 1, 2).unwrap();
+        let phc = hash(b"hunter2", &params).unwrap();
+        assert!(phc.starts_with("$argon2id$v=19$m=64,t=1,p=2$"));
+        assert_ne!(phc, hash(b"hunter2", &params).unwrap(), "fresh salt");
+        // Below the interactive preset
+        assert_eq!(verify_default(b"hunter2", &phc), Ok(Verified::NeedsRehash));
+        assert_eq!(
+            verify_default(b"hunter3", &phc),
+            Err(Error::Crypto(CryptoError::VerificationFailed))
+        );
+
+        let key = argon2id_key(b"passphrase", b"keystore salt", &params).unwrap();
+        let mut output = [0u8; 32];
+        argon2id(b"passphrase", b"keystore salt", &params, &mut output).unwrap();
+        assert_eq!(key.as_bytes(), &output);
+        assert_eq!(
+            argon2id(b"", b"", &params, &mut [0u8; 3]).unwrap_err(),
+            Error::Misuse(MisuseError::BufferTooSmall)
+        );
+    }
+
+    #[cfg(feature = "argon2")]
+    #[test]
+    fn calibrates_passes() {
+        let params = Argon2idParams::calibrate(Duration::ZERO, 64, 1).unwrap();
+        assert_eq!((params.memory_kib(), params.passes()), (64, 1));
+        let params = Argon2idParams::calibrate(Duration::from_secs(3600), 64, 1).unwrap();
+        assert_eq!(params.passes(), Argon2idParams::MAX_PASSES);
+        assert_eq!(
+            Argon2idParams::calibrate(Duration::ZERO, 7, 1).unwrap_err(),
+            Error::Misuse(MisuseError::InvalidParameterSet)
+        );
+    }
+
+    #[cfg(feature = "scrypt")]
+    #[test]
+    fn verifies_scrypt() {
+        // hashlib.scrypt(b"hunter2", salt=b"saltsaltsaltsalt", n=1024, r=8, p=1, dklen=32)
+        let phc = "$scrypt$ln=10,r=8,p=1$c2FsdHNhbHRzYWx0c2FsdA$\
+                   v/uBvjpkrv4+RPlRbT7o/v0/ucpdIQIN3+rMqzxpxj4";
+        assert_eq!(verify_default(b"hunter2", phc), Ok(Verified::NeedsRehash));
+        assert_eq!(
+            verify_default(b"hunter3", phc),
+            Err(Error::Crypto(CryptoError::VerificationFailed))
+        );
+    }
+
+    #[test]
+    fn verifies_pbkdf2_variants() {
+        // hashlib.pbkdf2_hmac("sha256", b"correct horse", b"saltsaltsaltsalt", 1000)
+        let phc = "$pbkdf2-sha256$i=1000,l=32$c2FsdHNhbHRzYWx0c2FsdA$\
+                   BBs+1+PaslLtBPULUr8/lQicvVuHiEPMz0i8MjLCbzM";
+        assert_eq!(
+            verify_default(b"correct horse", phc),
+            Ok(Verified::NeedsRehash)
+        );
+        let without_length = phc.replace(",l=32", "");
+        assert_eq!(
+            verify_default(b"correct horse", &without_length),
+            Ok(Verified::NeedsRehash)
+        );
+        let passlib = phc.replace("i=1000,l=32", "1000").replace('+', ".");
+        assert_eq!(
+            verify_default(b"correct horse", &passlib),
+            Ok(Verified::NeedsRehash)
+        );
+        assert_eq!(
+            verify_default(b"correct horse ", &passlib),
+            Err(Error::Crypto(CryptoError::VerificationFailed))
+        );
+    }
+
+    #[cfg(all(feature = "argon2", feature = "scrypt"))]
+    #[test]
+    fn rejects_malformed_strings() {
+        let salt_and_hash = "$c2FsdHNhbHRzYWx0c2FsdA$BBs+1+PaslLtBPULUr8/lQicvVuHiEPMz0i8MjLCbzM";
+        for (params, error) in [
+            ("$pbkdf2-sha256$i=0", MisuseError::InvalidParameterSet),
+            (
+                "$pbkdf2-sha256$i=99999999",
+                MisuseError::InvalidParameterSet,
+            ),
+            ("$pbkdf2-sha256$i=01000", MisuseError::InvalidEncoding),
+            ("$pbkdf2-sha256$i=1000,l=16", MisuseError::InvalidEncoding),
+            ("$pbkdf2-sha256$c=1000", MisuseError::InvalidEncoding),
+            ("$scrypt$ln=10,r=8", MisuseError::InvalidEncoding),
+            ("$scrypt$ln=40,r=8,p=1", MisuseError::InvalidParameterSet),
+            ("$scrypt$r=8,ln=10,p=1", MisuseError::InvalidEncoding),
+            ("$md5$i=1000", MisuseError::UnsupportedAlgorithm),
+            ("$argon2id$m=64,t=1,p=1", MisuseError::UnsupportedAlgorithm),
+            (
+                "$argon2id$v=16$m=64,t=1,p=1",
+                MisuseError::UnsupportedAlgorithm,
+            ),
+            ("$argon2id$v=19$t=1,m=64,p=1", MisuseError::InvalidEncoding),
+            (
+                "$argon2id$v=19$m=64,t=0,p=1",
+                MisuseError::InvalidParameterSet,
+            ),
+            ("$scrypt$v=19$ln=10,r=8,p=1", MisuseError::InvalidEncoding),
+            ("pbkdf2-sha256$i=1000", MisuseError::InvalidEncoding),
+        ] {
+            assert_eq!(
+                verify_default(b"", &format!("{params}{salt_and_hash}")).unwrap_err(),
+                Error::Misuse(error),
+                "{params}"
+            );
+        }
+        for phc in [
+            "$pbkdf2-sha256$i=1000$c2FsdA==$BBs+1+PaslLtBPULUr8/lQicvVuHiEPMz0i8MjLCbzM",
+            "$pbkdf2-sha256$i=1000$c2FsdA$BBs+1+PaslLt",
+            "$pbkdf2-sha256$i=1000$c2FsdA",
+        ] {
+            assert_eq!(
+                verify_default(b"", phc).unwrap_err(),
+                Error::Misuse(MisuseError::InvalidEncoding),
+                "{phc}"
+            );
+        }
+    }
+
+    #[cfg(feature = "scrypt")]
+    #[test]
+    fn raw_derivation_checks_arguments() {
+        let mut output = [0u8; 32];
+        assert_eq!(
+            pbkdf2_sha256(b"password", b"salt", 0, &mut output).unwrap_err(),
+            Error::Misuse(MisuseError::InvalidParameterSet)
+        );
+        assert_eq!(
+            scrypt(b"", b"", &ScryptParams::new(4, 1, 1).unwrap(), &mut []).unwrap_err(),
+            Error::Misuse(MisuseError::BufferTooSmall)
+        );
+        // RFC 7914, section 12: the first 32 bytes of the second vector
+        let params = ScryptParams::new(10, 8, 16).unwrap();
+        let key = scrypt_key(b"password", b"NaCl", &params).unwrap();
+        assert_eq!(key.as_bytes()[..4], [0xfd, 0xba, 0xbe, 0x1c]);
+
+        pbkdf2_sha256(b"password", b"salt", 1, &mut output).unwrap();
+        assert_eq!(
+            output[..4],
+            [0x12, 0x0f, 0xb6, 0xcf],
+            "RFC 7914 PBKDF2 prefix"
+        );
+    }
+
+    #[cfg(not(all(feature = "argon2", feature = "scrypt")))]
+    #[test]
+    fn disabled_schemes_are_reported() {
+        let salt_and_hash = "$c2FsdHNhbHRzYWx0c2FsdA$BBs+1+PaslLtBPULUr8/lQicvVuHiEPMz0i8MjLCbzM";
+        for (params, enabled) in [
+            ("$argon2id$v=19$m=64,t=1,p=1", cfg!(feature = "argon2")),
+            ("$scrypt$ln=10,r=8,p=1", cfg!(feature = "scrypt")),
+        ] {
+            let result = verify_default(b"", &format!("{params}{salt_and_hash}"));
+            if !enabled {
+                assert_eq!(result, Err(Error::Misuse(MisuseError::FeatureNotEnabled)));
+            }
+        }
+    }
+}
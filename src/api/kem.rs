@@ -0,0 +1,594 @@
+//! Key encapsulation mechanisms.
+//!
+//! # Purpose
+//!
+//! A KEM lets a sender derive a fresh shared secret for a recipient's public
+//! key and transmit it as a ciphertext only the recipient can open. Use the
+//! shared secret as input to a key derivation function, not directly as a
+//! key.
+//!
+//! # Algorithms
+//!
+//! - [`MlKem768`]: ML-KEM-768 (FIPS 203), NIST security category 3
+//!   (feature `mlkem`)
+//!
+//! # Security
+//!
+//! - Public keys are validated on construction (FIPS 203 modulus check)
+//! - Secret keys are validated on construction (FIPS 203 hash check) and
+//!   zeroized on drop
+//! - Decapsulation uses implicit rejection: a tampered ciphertext yields a
+//!   pseudorandom shared secret instead of an error, so failures cannot be
+//!   used as a decryption oracle
+//! - [`RejectionMonitor`] optionally counts how often implicit rejection
+//!   happens, in aggregate only, to detect ciphertext-manipulation attacks
+//!
+//! # Example
+//!
+//! ```ignore
+//! use citadel::kem::MlKem768;
+//!
+//! let (public_key, secret_key) = MlKem768::generate_keypair()?;
+//! let (ciphertext, sender_secret) = MlKem768::encapsulate(&public_key)?;
+//! let recipient_secret = MlKem768::decapsulate(&secret_key, &ciphertext)?;
+//! assert_eq!(sender_secret.as_bytes(), recipient_secret.as_bytes());
+//! ```
+
+use core::fmt;
+#[cfg(feature = "mlkem")]
+use core::sync::atomic::{AtomicU64, Ordering};
+
+#[cfg(feature = "mlkem")]
+use crate::algorithms::pq::ml_kem_768 as imp;
+use crate::consts;
+#[cfg(feature = "mlkem")]
+use crate::context::{self, EntropySource};
+#[cfg(feature = "mlkem")]
+use crate::errors::{MisuseError, Result};
+#[cfg(feature = "mlkem")]
+use crate::internal::entropy::ContextEntropy;
+use crate::memory::SensitiveBytes;
+
+/// ML-KEM-768 (FIPS 203).
+///
+/// Namespace for key generation, encapsulation and decapsulation. Keys and
+/// ciphertexts are dedicated types so they cannot be mixed up with other
+/// byte strings.
+#[cfg(feature = "mlkem")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MlKem768;
+
+#[cfg(feature = "mlkem")]
+impl MlKem768 {
+    /// Public (encapsulation) key size in bytes.
+    pub const PUBLIC_KEY_SIZE: usize = imp::PUBLIC_KEY_SIZE;
+    /// Secret (decapsulation) key size in bytes.
+    pub const SECRET_KEY_SIZE: usize = imp::SECRET_KEY_SIZE;
+    /// Ciphertext size in bytes.
+    pub const CIPHERTEXT_SIZE: usize = imp::CIPHERTEXT_SIZE;
+    /// Shared secret size in bytes.
+    pub const SHARED_SECRET_SIZE: usize = imp::SHARED_SECRET_SIZE;
+    /// Key generation seed size in bytes.
+    pub const SEED_SIZE: usize = imp::SEED_SIZE;
+
+    /// Generate a new keypair from system randomness.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+    /// - `CryptoError::RandomnessFailure`: If the system RNG fails a health test
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    pub fn generate_keypair() -> Result<(MlKem768PublicKey, MlKem768SecretKey)> {
+        Self::generate_keypair_with_rng(&ContextEntropy)
+    }
+
+    /// Generate a new keypair from `rng`.
+    ///
+    /// # Security
+    ///
+    /// `rng` MUST be a CSPRNG of at least 256-bit security, e.g. a
+    /// hardware TRNG or an approved DRBG. Deterministic sources are for
+    /// known-answer tests only.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If `rng` fails
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    pub fn generate_keypair_with_rng(
+        rng: &dyn EntropySource,
+    ) -> Result<(MlKem768PublicKey, MlKem768SecretKey)> {
+        context::checkpoint()?;
+        let mut seed = SensitiveBytes::<{ imp::SEED_SIZE }>::zeroed();
+        rng.fill(seed.as_bytes_mut())?;
+        Ok(Self::keypair_from_seed(seed.as_bytes()))
+    }
+
+    /// Generate a fresh 64-byte key generation seed from system randomness.
+    ///
+    /// Store the seed and rebuild the keypair with
+    /// [`keypair_from_seed`](Self::keypair_from_seed) on load.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+    /// - `CryptoError::RandomnessFailure`: If the system RNG fails a health test
+    pub fn generate_seed() -> Result<SensitiveBytes<{ imp::SEED_SIZE }>> {
+        let mut seed = SensitiveBytes::zeroed();
+        ContextEntropy.fill(seed.as_bytes_mut())?;
+        Ok(seed)
+    }
+
+    /// Derive a keypair deterministically from a 64-byte seed (`d || z` in
+    /// FIPS 203).
+    ///
+    /// Storing the seed instead of the expanded secret key is a compact and
+    /// standard-compliant alternative.
+    ///
+    /// # Security
+    ///
+    /// The seed is as sensitive as the secret key and MUST come from a
+    /// cryptographically secure source.
+    pub fn keypair_from_seed(seed: &[u8; 64]) -> (MlKem768PublicKey, MlKem768SecretKey) {
+        let (ek, dk) = imp::MlKem768::keypair_from_seed(seed);
+        (MlKem768PublicKey(ek), MlKem768SecretKey(dk))
+    }
+
+    /// Encapsulate a fresh shared secret to `public_key`.
+    ///
+    /// # Returns
+    ///
+    /// A tuple of (ciphertext, shared_secret).
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+    /// - `CryptoError::RandomnessFailure`: If the system RNG fails a health test
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    pub fn encapsulate(
+        public_key: &MlKem768PublicKey,
+    ) -> Result<(MlKem768Ciphertext, SharedSecret)> {
+        Self::encapsulate_with_rng(public_key, &ContextEntropy)
+    }
+
+    /// Encapsulate a fresh shared secret to `public_key`, drawing the
+    /// message from `rng`.
+    ///
+    /// # Security
+    ///
+    /// `rng` MUST be a CSPRNG of at least 256-bit security, e.g. a
+    /// hardware TRNG or an approved DRBG. Deterministic sources are for
+    /// known-answer tests only.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If `rng` fails
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    pub fn encapsulate_with_rng(
+        public_key: &MlKem768PublicKey,
+        rng: &dyn EntropySource,
+    ) -> Result<(MlKem768Ciphertext, SharedSecret)> {
+        context::checkpoint()?;
+        let mut m = SensitiveBytes::<32>::zeroed();
+        rng.fill(m.as_bytes_mut())?;
+        let (ct, shared) = imp::MlKem768::encapsulate_with(&public_key.0, m.as_bytes())?;
+        Ok((MlKem768Ciphertext(ct), SharedSecret(shared)))
+    }
+
+    /// Encapsulate with the fixed randomness `m`, for known-answer tests.
+    ///
+    /// Compiled only with the `test-vectors` feature.
+    ///
+    /// # Security
+    ///
+    /// Anyone who knows or can predict `m` can compute the shared secret.
+    /// Never call this outside test harnesses.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    #[cfg(feature = "test-vectors")]
+    pub fn encapsulate_derand(
+        public_key: &MlKem768PublicKey,
+        m: &[u8; 32],
+    ) -> Result<(MlKem768Ciphertext, SharedSecret)> {
+        context::checkpoint()?;
+        let (ct, shared) = imp::MlKem768::encapsulate_with(&public_key.0, m)?;
+        Ok((MlKem768Ciphertext(ct), SharedSecret(shared)))
+    }
+
+    /// Recover the shared secret from `ciphertext`.
+    ///
+    /// # Security
+    ///
+    /// Never reports a tampered ciphertext: it yields an unrelated
+    /// pseudorandom secret instead (implicit rejection), which makes any
+    /// subsequent authenticated decryption fail. Runs in constant time with
+    /// respect to the secret key and ciphertext validity.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    ///
+    /// Never fails because of the ciphertext.
+    pub fn decapsulate(
+        secret_key: &MlKem768SecretKey,
+        ciphertext: &MlKem768Ciphertext,
+    ) -> Result<SharedSecret> {
+        context::checkpoint()?;
+        let (shared, _) =
+            imp::MlKem768::decapsulate_with_status(secret_key.0.as_bytes(), &ciphertext.0);
+        Ok(SharedSecret(shared))
+    }
+
+    /// [`decapsulate`](Self::decapsulate), also recording the outcome in
+    /// `monitor`.
+    ///
+    /// Both counters of the monitor are updated unconditionally, so the
+    /// call is as constant-time as `decapsulate`.
+    ///
+    /// # Errors
+    ///
+    /// See [`decapsulate`](Self::decapsulate). Nothing is recorded when the
+    /// call is aborted by its context.
+    pub fn decapsulate_monitored(
+        secret_key: &MlKem768SecretKey,
+        ciphertext: &MlKem768Ciphertext,
+        monitor: &RejectionMonitor,
+    ) -> Result<SharedSecret> {
+        context::checkpoint()?;
+        let (shared, valid) =
+            imp::MlKem768::decapsulate_with_status(secret_key.0.as_bytes(), &ciphertext.0);
+        monitor.record(valid);
+        Ok(SharedSecret(shared))
+    }
+}
+
+/// Aggregate counter of decapsulations that took the implicit-rejection
+/// path.
+///
+/// Honest peers essentially never produce rejected ciphertexts (the
+/// ML-KEM decryption failure rate is below 2^-160), so a non-zero rejection
+/// rate indicates corrupted transport or an active attack against the
+/// endpoint. Share one monitor per key or endpoint, e.g. in a `static`.
+///
+/// # Security
+///
+/// Whether a single decapsulation was rejected is secret. The monitor only
+/// exposes totals; do not snapshot it around individual calls, and never
+/// report its values back to the peer.
+///
+/// # Example
+///
+/// ```ignore
+/// static MONITOR: RejectionMonitor = RejectionMonitor::new();
+///
+/// let secret = MlKem768::decapsulate_monitored(&sk, &ct, &MONITOR)?;
+/// if MONITOR.snapshot().rejection_rate() > 0.01 {
+///     alert_operator();
+/// }
+/// ```
+#[cfg(feature = "mlkem")]
+#[derive(Debug, Default)]
+pub struct RejectionMonitor {
+    decapsulations: AtomicU64,
+    rejections: AtomicU64,
+}
+
+#[cfg(feature = "mlkem")]
+impl RejectionMonitor {
+    /// Create a monitor with both counters at zero.
+    pub const fn new() -> Self {
+        Self {
+            decapsulations: AtomicU64::new(0),
+            rejections: AtomicU64::new(0),
+        }
+    }
+
+    #[inline]
+    fn record(&self, valid: bool) {
+        self.decapsulations.fetch_add(1, Ordering::Relaxed);
+        self.rejections
+            .fetch_add(u64::from(!valid), Ordering::Relaxed);
+    }
+
+    /// Current totals.
+    ///
+    /// The two counters are read independently, so under concurrent use the
+    /// snapshot may be off by in-flight calls.
+    pub fn snapshot(&self) -> RejectionStats {
+        RejectionStats {
+            decapsulations: self.decapsulations.load(Ordering::Relaxed),
+            rejections: self.rejections.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Reset both counters, e.g. at the start of a reporting interval.
+    pub fn reset(&self) {
+        self.decapsulations.store(0, Ordering::Relaxed);
+        self.rejections.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Totals reported by [`RejectionMonitor::snapshot`].
+#[cfg(feature = "mlkem")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RejectionStats {
+    /// Decapsulations recorded.
+    pub decapsulations: u64,
+    /// Decapsulations that took the implicit-rejection path.
+    pub rejections: u64,
+}
+
+#[cfg(feature = "mlkem")]
+impl RejectionStats {
+    /// Fraction of decapsulations that were rejected, or 0.0 if none were
+    /// recorded.
+    pub fn rejection_rate(&self) -> f64 {
+        if self.decapsulations == 0 {
+            return 0.0;
+        }
+        self.rejections.min(self.decapsulations) as f64 / self.decapsulations as f64
+    }
+}
+
+/// ML-KEM-768 public (encapsulation) key.
+#[cfg(feature = "mlkem")]
+#[derive(Clone, PartialEq, Eq)]
+pub struct MlKem768PublicKey([u8; imp::PUBLIC_KEY_SIZE]);
+
+#[cfg(feature = "mlkem")]
+impl MlKem768PublicKey {
+    /// Parse and validate an encoded public key.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidPublicKeyLength`: If `bytes` is not
+    ///   [`MlKem768::PUBLIC_KEY_SIZE`] bytes
+    /// - `MisuseError::InvalidEncoding`: If a coefficient is not reduced
+    ///   modulo q
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let key: [u8; imp::PUBLIC_KEY_SIZE] = bytes
+            .try_into()
+            .map_err(|_| MisuseError::InvalidPublicKeyLength)?;
+        if !imp::MlKem768::is_valid_public_key(&key) {
+            return Err(MisuseError::InvalidEncoding.into());
+        }
+        Ok(Self(key))
+    }
+
+    /// Encoded public key.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8; imp::PUBLIC_KEY_SIZE] {
+        &self.0
+    }
+}
+
+#[cfg(feature = "mlkem")]
+impl fmt::Debug for MlKem768PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("MlKem768PublicKey { .. }")
+    }
+}
+
+/// ML-KEM-768 secret (decapsulation) key, zeroized on drop.
+#[cfg(feature = "mlkem")]
+pub struct MlKem768SecretKey(SensitiveBytes<{ imp::SECRET_KEY_SIZE }>);
+
+#[cfg(feature = "mlkem")]
+crate::memory::never_serialize!([] MlKem768SecretKey);
+
+#[cfg(feature = "mlkem")]
+impl MlKem768SecretKey {
+    /// Parse and validate an encoded (expanded) secret key.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidSecretKeyLength`: If `bytes` is not
+    ///   [`MlKem768::SECRET_KEY_SIZE`] bytes
+    /// - `MisuseError::InvalidEncoding`: If the embedded public key hash
+    ///   does not match
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != imp::SECRET_KEY_SIZE {
+            return Err(MisuseError::InvalidSecretKeyLength.into());
+        }
+        if !imp::MlKem768::is_valid_secret_key(bytes) {
+            return Err(MisuseError::InvalidEncoding.into());
+        }
+        let mut key = SensitiveBytes::zeroed();
+        key.as_bytes_mut().copy_from_slice(bytes);
+        Ok(Self(key))
+    }
+
+    /// Encoded secret key.
+    ///
+    /// # Security
+    ///
+    /// Do not log, serialize unencrypted, or store the returned bytes.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8; imp::SECRET_KEY_SIZE] {
+        self.0.as_bytes()
+    }
+
+    /// The public key embedded in this secret key.
+    pub fn public_key(&self) -> MlKem768PublicKey {
+        let offset = imp::SECRET_KEY_SIZE - imp::PUBLIC_KEY_SIZE - 64;
+        let mut key = [0u8; imp::PUBLIC_KEY_SIZE];
+        key.copy_from_slice(&self.0.as_bytes()[offset..offset + imp::PUBLIC_KEY_SIZE]);
+        MlKem768PublicKey(key)
+    }
+}
+
+#[cfg(feature = "mlkem")]
+impl fmt::Debug for MlKem768SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("MlKem768SecretKey { <redacted> }")
+    }
+}
+
+/// ML-KEM-768 ciphertext.
+#[cfg(feature = "mlkem")]
+#[derive(Clone, PartialEq, Eq)]
+pub struct MlKem768Ciphertext([u8; imp::CIPHERTEXT_SIZE]);
+
+#[cfg(feature = "mlkem")]
+impl MlKem768Ciphertext {
+    /// Wrap an encoded ciphertext.
+    ///
+    /// Any byte string of the right length is accepted; tampering is
+    /// handled by implicit rejection during decapsulation.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidCiphertextLength`: If `bytes` is not
+    ///   [`MlKem768::CIPHERTEXT_SIZE`] bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let ct = bytes
+            .try_into()
+            .map_err(|_| MisuseError::InvalidCiphertextLength)?;
+        Ok(Self(ct))
+    }
+
+    /// Encoded ciphertext.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8; imp::CIPHERTEXT_SIZE] {
+        &self.0
+    }
+}
+
+#[cfg(feature = "mlkem")]
+impl fmt::Debug for MlKem768Ciphertext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("MlKem768Ciphertext { .. }")
+    }
+}
+
+/// A 32-byte KEM or key agreement shared secret, zeroized on drop.
+pub struct SharedSecret(pub(crate) SensitiveBytes<32>);
+
+crate::memory::never_serialize!([] SharedSecret);
+
+// Every KEM and key agreement in the crate yields 32-byte shared secrets.
+crate::static_assert_size!(consts::MlKem768::SHARED_SECRET_SIZE, 32);
+crate::static_assert_size!(consts::X25519::SHARED_SECRET_SIZE, 32);
+
+impl SharedSecret {
+    /// The shared secret bytes.
+    ///
+    /// # Security
+    ///
+    /// Feed these into a KDF; do not log or store them.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        self.0.as_bytes()
+    }
+}
+
+impl fmt::Debug for SharedSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SharedSecret { <redacted> }")
+    }
+}
+
+#[cfg(all(test, feature = "mlkem"))]
+mod tests {
+    use super::*;
+    use crate::errors::Error;
+
+    #[test]
+    fn round_trip() {
+        let (pk, sk) = MlKem768::generate_keypair().unwrap();
+        let (ct, sender) = MlKem768::encapsulate(&pk).unwrap();
+        let recipient = MlKem768::decapsulate(&sk, &ct).unwrap();
+        assert_eq!(sender.as_bytes(), recipient.as_bytes());
+        assert!(sk.public_key() == pk);
+    }
+
+    #[test]
+    fn seed_rebuilds_keypair() {
+        let seed = MlKem768::generate_seed().unwrap();
+        let (pk, sk) = MlKem768::keypair_from_seed(seed.as_bytes());
+        let (again_pk, again_sk) = MlKem768::keypair_from_seed(seed.as_bytes());
+        assert!(pk == again_pk);
+        assert_eq!(sk.as_bytes(), again_sk.as_bytes());
+    }
+
+    #[test]
+    fn injected_rng_is_deterministic() {
+        use crate::internal::entropy::CounterEntropy;
+
+        let (pk, sk) = MlKem768::generate_keypair_with_rng(&CounterEntropy::new(0)).unwrap();
+        let (expected_pk, _) = MlKem768::keypair_from_seed(&core::array::from_fn(|i| i as u8));
+        assert_eq!(pk, expected_pk);
+
+        let (ct, ss) = MlKem768::encapsulate_with_rng(&pk, &CounterEntropy::new(1)).unwrap();
+        let (again, _) = MlKem768::encapsulate_with_rng(&pk, &CounterEntropy::new(1)).unwrap();
+        assert_eq!(ct.as_bytes(), again.as_bytes());
+        assert_eq!(
+            MlKem768::decapsulate(&sk, &ct).unwrap().as_bytes(),
+            ss.as_bytes()
+        );
+    }
+
+    #[test]
+    fn parsing_checks_lengths() {
+        assert_eq!(
+            MlKem768PublicKey::from_bytes(&[0u8; 10]).unwrap_err(),
+            Error::Misuse(MisuseError::InvalidPublicKeyLength)
+        );
+        assert_eq!(
+            MlKem768SecretKey::from_bytes(&[0u8; 10]).unwrap_err(),
+            Error::Misuse(MisuseError::InvalidSecretKeyLength)
+        );
+        assert_eq!(
+            MlKem768Ciphertext::from_bytes(&[0u8; 10]).unwrap_err(),
+            Error::Misuse(MisuseError::InvalidCiphertextLength)
+        );
+    }
+
+    #[test]
+    fn monitor_counts_rejections() {
+        let monitor = RejectionMonitor::new();
+        let (pk, sk) = MlKem768::keypair_from_seed(&[1u8; 64]);
+        let (ct, _) = MlKem768::encapsulate(&pk).unwrap();
+        MlKem768::decapsulate_monitored(&sk, &ct, &monitor).unwrap();
+
+        let mut tampered = ct.as_bytes().to_owned();
+        tampered[0] ^= 1;
+        let tampered = MlKem768Ciphertext::from_bytes(&tampered).unwrap();
+        MlKem768::decapsulate_monitored(&sk, &tampered, &monitor).unwrap();
+
+        let stats = monitor.snapshot();
+        assert_eq!(stats.decapsulations, 2);
+        assert_eq!(stats.rejections, 1);
+        assert_eq!(stats.rejection_rate(), 0.5);
+
+        monitor.reset();
+        assert_eq!(monitor.snapshot(), RejectionStats::default());
+        assert_eq!(monitor.snapshot().rejection_rate(), 0.0);
+    }
+
+    #[test]
+    fn honours_operation_context() {
+        use crate::context::{CancellationToken, OpContext};
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let ctx = OpContext::new().with_cancellation(token);
+        assert_eq!(
+            ctx.run(MlKem768::generate_keypair).unwrap_err(),
+            Error::Misuse(MisuseError::Cancelled)
+        );
+    }
+
+    #[test]
+    fn debug_output_is_redacted() {
+        let (_, sk) = MlKem768::keypair_from_seed(&[7u8; 64]);
+        assert_eq!(format!("{:?}", sk), "MlKem768SecretKey { <redacted> }");
+    }
+}
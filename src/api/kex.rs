@@ -0,0 +1,217 @@
+//! Classical Diffie-Hellman key agreement.
+//!
+//! # Purpose
+//!
+//! Key agreement for interoperability with existing classical deployments
+//! and as the classical half of hybrid key exchange. Prefer the KEMs in
+//! [`kem`](crate::kem) for new designs.
+//!
+//! # Algorithms
+//!
+//! - [`X25519`]: Curve25519 Diffie-Hellman, RFC 7748
+//!
+//! # Security
+//!
+//! - Secret keys are zeroized on drop
+//! - Low-order peer public keys are rejected (all-zero shared secret)
+//! - The shared secret is not uniformly random; feed it to a KDF together
+//!   with both public keys
+//!
+//! # Example
+//!
+//! ```ignore
+//! use citadel::kex::X25519;
+//!
+//! let (alice_public, alice_secret) = X25519::generate_keypair()?;
+//! let (bob_public, bob_secret) = X25519::generate_keypair()?;
+//! let alice_shared = X25519::diffie_hellman(&alice_secret, &bob_public)?;
+//! let bob_shared = X25519::diffie_hellman(&bob_secret, &alice_public)?;
+//! assert_eq!(alice_shared.as_bytes(), bob_shared.as_bytes());
+//! ```
+
+use core::fmt;
+
+use crate::algorithms::classical::x25519 as imp;
+use crate::context::{self, EntropySource};
+use crate::errors::{MisuseError, Result};
+use crate::internal::entropy::ContextEntropy;
+use crate::kem::SharedSecret;
+use crate::memory::SensitiveBytes;
+
+/// X25519 (RFC 7748).
+///
+/// Namespace for key generation and Diffie-Hellman.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct X25519;
+
+impl X25519 {
+    /// Public key size in bytes.
+    pub const PUBLIC_KEY_SIZE: usize = imp::PUBLIC_KEY_SIZE;
+    /// Secret key size in bytes.
+    pub const SECRET_KEY_SIZE: usize = imp::SECRET_KEY_SIZE;
+
+    /// Generate a new keypair from system randomness.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+    /// - `CryptoError::RandomnessFailure`: If the system RNG fails a health test
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    pub fn generate_keypair() -> Result<(X25519PublicKey, X25519SecretKey)> {
+        Self::generate_keypair_with_rng(&ContextEntropy)
+    }
+
+    /// Generate a new keypair from `rng`.
+    ///
+    /// # Security
+    ///
+    /// `rng` MUST be a CSPRNG of at least 256-bit security, e.g. a
+    /// hardware TRNG or an approved DRBG. Deterministic sources are for
+    /// known-answer tests only.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If `rng` fails
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    pub fn generate_keypair_with_rng(
+        rng: &dyn EntropySource,
+    ) -> Result<(X25519PublicKey, X25519SecretKey)> {
+        context::checkpoint()?;
+        let mut secret = SensitiveBytes::<{ imp::SECRET_KEY_SIZE }>::zeroed();
+        rng.fill(secret.as_bytes_mut())?;
+        let secret = X25519SecretKey(secret);
+        Ok((secret.public_key(), secret))
+    }
+
+    /// Compute the shared secret between `secret_key` and `peer_public_key`.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::KeyEncapsulationFailed`: If the peer public key is a
+    ///   low-order point
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    pub fn diffie_hellman(
+        secret_key: &X25519SecretKey,
+        peer_public_key: &X25519PublicKey,
+    ) -> Result<SharedSecret> {
+        context::checkpoint()?;
+        let shared = imp::X25519::diffie_hellman(secret_key.0.as_bytes(), &peer_public_key.0)?;
+        Ok(SharedSecret(shared))
+    }
+}
+
+/// X25519 public key (u-coordinate).
+#[derive(Clone, PartialEq, Eq)]
+pub struct X25519PublicKey([u8; imp::PUBLIC_KEY_SIZE]);
+
+impl X25519PublicKey {
+    /// Wrap an encoded public key.
+    ///
+    /// Any 32-byte string is a valid encoding; low-order points are
+    /// rejected by [`X25519::diffie_hellman`].
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidPublicKeyLength`: If `bytes` is not
+    ///   [`X25519::PUBLIC_KEY_SIZE`] bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let key = bytes
+            .try_into()
+            .map_err(|_| MisuseError::InvalidPublicKeyLength)?;
+        Ok(Self(key))
+    }
+
+    /// Encoded public key.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8; imp::PUBLIC_KEY_SIZE] {
+        &self.0
+    }
+}
+
+impl fmt::Debug for X25519PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("X25519PublicKey { .. }")
+    }
+}
+
+/// X25519 secret key, zeroized on drop.
+pub struct X25519SecretKey(SensitiveBytes<{ imp::SECRET_KEY_SIZE }>);
+
+crate::memory::never_serialize!([] X25519SecretKey);
+
+impl X25519SecretKey {
+    /// Wrap an encoded secret key.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidSecretKeyLength`: If `bytes` is not
+    ///   [`X25519::SECRET_KEY_SIZE`] bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != imp::SECRET_KEY_SIZE {
+            return Err(MisuseError::InvalidSecretKeyLength.into());
+        }
+        let mut key = SensitiveBytes::zeroed();
+        key.as_bytes_mut().copy_from_slice(bytes);
+        Ok(Self(key))
+    }
+
+    /// Encoded secret key.
+    ///
+    /// # Security
+    ///
+    /// Do not log, serialize unencrypted, or store the returned bytes.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8; imp::SECRET_KEY_SIZE] {
+        self.0.as_bytes()
+    }
+
+    /// The public key for this secret key.
+    pub fn public_key(&self) -> X25519PublicKey {
+        X25519PublicKey(imp::X25519::public_key(self.0.as_bytes()))
+    }
+}
+
+impl fmt::Debug for X25519SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("X25519SecretKey { <redacted> }")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::{CryptoError, Error};
+
+    #[test]
+    fn agreement_is_symmetric() {
+        let (alice_public, alice_secret) = X25519::generate_keypair().unwrap();
+        let (bob_public, bob_secret) = X25519::generate_keypair().unwrap();
+        let alice = X25519::diffie_hellman(&alice_secret, &bob_public).unwrap();
+        let bob = X25519::diffie_hellman(&bob_secret, &alice_public).unwrap();
+        assert_eq!(alice.as_bytes(), bob.as_bytes());
+    }
+
+    #[test]
+    fn rejects_low_order_peer() {
+        let (_, secret) = X25519::generate_keypair().unwrap();
+        let zero = X25519PublicKey::from_bytes(&[0u8; 32]).unwrap();
+        assert_eq!(
+            X25519::diffie_hellman(&secret, &zero).unwrap_err(),
+            Error::Crypto(CryptoError::KeyEncapsulationFailed)
+        );
+    }
+
+    #[test]
+    fn injected_rng_is_deterministic() {
+        use crate::internal::entropy::CounterEntropy;
+
+        let (public, secret) = X25519::generate_keypair_with_rng(&CounterEntropy::new(0)).unwrap();
+        let (again, _) = X25519::generate_keypair_with_rng(&CounterEntropy::new(0)).unwrap();
+        assert_eq!(public.as_bytes(), again.as_bytes());
+        let expected: Vec<u8> = (0..32).collect();
+        assert_eq!(secret.as_bytes().as_slice(), expected);
+    }
+}
@@ -0,0 +1,737 @@
+//! Keys, ciphertexts and signatures bound to their algorithm.
+//!
+//! # Purpose
+//!
+//! Encoded keys of different algorithms often have the same shape: an
+//! X25519 and an Ed25519 public key are both 32 bytes, and a byte slice
+//! says nothing about which scheme produced it. [`PublicKey`],
+//! [`SecretKey`], [`Ciphertext`] and [`Signature`] carry an algorithm
+//! marker as a type parameter, so handing a key of one algorithm to code
+//! expecting another is a compile error rather than a failure at run time,
+//! or worse, none at all.
+//!
+//! The markers are the algorithm namespaces themselves ([`MlKem768`],
+//! [`X25519`], [`Ed25519`], [`MlDsa87`]) and the traits they implement
+//! here are sealed. Each type converts to and from the algorithm's own key
+//! types, which the operations take.
+//!
+//! [`SecretKey`] and [`SharedSecret`] also convert to and from
+//! [`SecureBuffer`] and [`SensitiveBytes`] by value. The source is moved or
+//! zeroized, so no copy of the secret outlives the conversion, unlike
+//! copying out through `as_bytes`.
+//!
+//! # Pairwise Consistency
+//!
+//! FIPS 140-3 requires a pairwise consistency test on every generated
+//! keypair: an encapsulate/decapsulate or sign/verify round trip showing
+//! the secret key matches the public key. [`KeyPair::generate`] runs it
+//! before returning; [`KeyPair::verify_consistency`] runs it on demand, for
+//! keys imported from elsewhere.
+//!
+//! # Serialization
+//!
+//! [`PublicKey`] reads and writes DER `SubjectPublicKeyInfo` and
+//! [`SecretKey`] DER PKCS#8, under the registered OIDs, so keys move to and
+//! from OpenSSL and other PKI tooling. Both also read and write the PEM
+//! armor of those encodings. To store a secret key, encrypt it under a
+//! passphrase or a key-encryption key instead; see [`encrypted`].
+//!
+//! # Example
+//!
+//! ```ignore
+//! use citadel::keys::PublicKey;
+//! use citadel::kem::MlKem768;
+//!
+//! fn store_recipient(key: PublicKey<MlKem768>) { /* ... */ }
+//!
+//! let key = PublicKey::<MlKem768>::from_bytes(&encoded)?;
+//! store_recipient(key);
+//! // Passing a `PublicKey<X25519>` here does not compile
+//! ```
+//!
+//! [`MlKem768`]: crate::kem::MlKem768
+//! [`MlDsa87`]: crate::sign::MlDsa87
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use crate::algorithm::{AlgorithmId, AlgorithmKind};
+use crate::dynamic;
+use crate::errors::{CryptoError, Error, MisuseError, Result};
+use crate::kem::SharedSecret;
+use crate::kex::{X25519, X25519PublicKey, X25519SecretKey};
+use crate::memory::{SecureBuffer, SensitiveBytes, constant_time_eq};
+use crate::sign::{Ed25519, Ed25519Signature, Ed25519VerifyingKey};
+
+mod der;
+pub mod encrypted;
+mod pem;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// An algorithm marker for the types in this module. Sealed.
+pub trait Algorithm: sealed::Sealed {
+    /// The algorithm's identifier, which fixes the encoded sizes.
+    const ID: AlgorithmId;
+}
+
+/// A key encapsulation algorithm, which has [`Ciphertext`]s. Sealed.
+pub trait KemAlgorithm: Algorithm {}
+
+/// A signature algorithm, which has [`Signature`]s. Sealed.
+pub trait SignatureAlgorithm: Algorithm {}
+
+impl sealed::Sealed for X25519 {}
+
+impl Algorithm for X25519 {
+    const ID: AlgorithmId = AlgorithmId::X25519;
+}
+
+impl sealed::Sealed for Ed25519 {}
+
+impl Algorithm for Ed25519 {
+    const ID: AlgorithmId = AlgorithmId::Ed25519;
+}
+
+impl SignatureAlgorithm for Ed25519 {}
+
+#[cfg(feature = "mlkem")]
+impl sealed::Sealed for crate::kem::MlKem768 {}
+
+#[cfg(feature = "mlkem")]
+impl Algorithm for crate::kem::MlKem768 {
+    const ID: AlgorithmId = AlgorithmId::MlKem768;
+}
+
+#[cfg(feature = "mlkem")]
+impl KemAlgorithm for crate::kem::MlKem768 {}
+
+#[cfg(feature = "mldsa")]
+impl sealed::Sealed for crate::sign::MlDsa87 {}
+
+#[cfg(feature = "mldsa")]
+impl Algorithm for crate::sign::MlDsa87 {
+    const ID: AlgorithmId = AlgorithmId::MlDsa87;
+}
+
+#[cfg(feature = "mldsa")]
+impl SignatureAlgorithm for crate::sign::MlDsa87 {}
+
+/// Encoded public key of algorithm `A`.
+pub struct PublicKey<A: Algorithm> {
+    bytes: Box<[u8]>,
+    algorithm: PhantomData<A>,
+}
+
+impl<A: Algorithm> PublicKey<A> {
+    /// Wrap an encoded public key.
+    ///
+    /// Only the length is checked; the algorithm validates the encoding
+    /// when the key is converted to its own key type.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidPublicKeyLength`: If `bytes` is not `A`'s
+    ///   public key size
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        A::ID.check_public_key(bytes)?;
+        Ok(Self {
+            bytes: bytes.into(),
+            algorithm: PhantomData,
+        })
+    }
+
+    /// The algorithm this key belongs to.
+    #[inline]
+    pub fn algorithm(&self) -> AlgorithmId {
+        A::ID
+    }
+
+    /// Encoded public key.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl<A: Algorithm> Clone for PublicKey<A> {
+    fn clone(&self) -> Self {
+        Self {
+            bytes: self.bytes.clone(),
+            algorithm: PhantomData,
+        }
+    }
+}
+
+impl<A: Algorithm> PartialEq for PublicKey<A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.bytes == other.bytes
+    }
+}
+
+impl<A: Algorithm> Eq for PublicKey<A> {}
+
+impl<A: Algorithm> fmt::Debug for PublicKey<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PublicKey")
+            .field("algorithm", &A::ID)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Encoded secret key of algorithm `A`, zeroized on drop.
+pub struct SecretKey<A: Algorithm> {
+    bytes: SecureBuffer,
+    algorithm: PhantomData<A>,
+}
+
+crate::memory::never_serialize!([A: Algorithm] SecretKey<A>);
+
+impl<A: Algorithm> SecretKey<A> {
+    /// Wrap an encoded secret key.
+    ///
+    /// Only the length is checked; the algorithm validates the encoding
+    /// when the key is converted to its own key type.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidSecretKeyLength`: If `bytes` is not `A`'s
+    ///   secret key size
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        A::ID.check_secret_key(bytes)?;
+        Ok(Self {
+            bytes: SecureBuffer::new(bytes.to_vec()),
+            algorithm: PhantomData,
+        })
+    }
+
+    /// The algorithm this key belongs to.
+    #[inline]
+    pub fn algorithm(&self) -> AlgorithmId {
+        A::ID
+    }
+
+    /// Encoded secret key.
+    ///
+    /// # Security
+    ///
+    /// Do not log, serialize unencrypted, or store the returned bytes.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        self.bytes.as_slice()
+    }
+}
+
+impl<A: Algorithm> fmt::Debug for SecretKey<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecretKey")
+            .field("algorithm", &A::ID)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Encoded KEM ciphertext of algorithm `A`.
+pub struct Ciphertext<A: KemAlgorithm> {
+    bytes: Box<[u8]>,
+    algorithm: PhantomData<A>,
+}
+
+impl<A: KemAlgorithm> Ciphertext<A> {
+    /// Wrap an encoded ciphertext.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidCiphertextLength`: If `bytes` is not `A`'s
+    ///   ciphertext size
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        A::ID.check_ciphertext(bytes)?;
+        Ok(Self {
+            bytes: bytes.into(),
+            algorithm: PhantomData,
+        })
+    }
+
+    /// The algorithm this ciphertext belongs to.
+    #[inline]
+    pub fn algorithm(&self) -> AlgorithmId {
+        A::ID
+    }
+
+    /// Encoded ciphertext.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl<A: KemAlgorithm> Clone for Ciphertext<A> {
+    fn clone(&self) -> Self {
+        Self {
+            bytes: self.bytes.clone(),
+            algorithm: PhantomData,
+        }
+    }
+}
+
+impl<A: KemAlgorithm> PartialEq for Ciphertext<A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.bytes == other.bytes
+    }
+}
+
+impl<A: KemAlgorithm> Eq for Ciphertext<A> {}
+
+impl<A: KemAlgorithm> fmt::Debug for Ciphertext<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ciphertext")
+            .field("algorithm", &A::ID)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Encoded signature of algorithm `A`.
+pub struct Signature<A: SignatureAlgorithm> {
+    bytes: Box<[u8]>,
+    algorithm: PhantomData<A>,
+}
+
+impl<A: SignatureAlgorithm> Signature<A> {
+    /// Wrap an encoded signature.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidSignatureLength`: If `bytes` is not `A`'s
+    ///   signature size
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        A::ID.check_signature(bytes)?;
+        Ok(Self {
+            bytes: bytes.into(),
+            algorithm: PhantomData,
+        })
+    }
+
+    /// The algorithm this signature belongs to.
+    #[inline]
+    pub fn algorithm(&self) -> AlgorithmId {
+        A::ID
+    }
+
+    /// Encoded signature.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl<A: SignatureAlgorithm> Clone for Signature<A> {
+    fn clone(&self) -> Self {
+        Self {
+            bytes: self.bytes.clone(),
+            algorithm: PhantomData,
+        }
+    }
+}
+
+impl<A: SignatureAlgorithm> PartialEq for Signature<A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.bytes == other.bytes
+    }
+}
+
+impl<A: SignatureAlgorithm> Eq for Signature<A> {}
+
+impl<A: SignatureAlgorithm> fmt::Debug for Signature<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Signature")
+            .field("algorithm", &A::ID)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Message signed by the pairwise consistency test.
+#[cfg(feature = "signing")]
+const CONSISTENCY_MESSAGE: &[u8] = b"citadel pairwise consistency test";
+
+/// A public key and the secret key it belongs to.
+pub struct KeyPair<A: Algorithm> {
+    public_key: PublicKey<A>,
+    secret_key: SecretKey<A>,
+}
+
+impl<A: Algorithm> KeyPair<A> {
+    /// Generate a keypair from system randomness and check it with
+    /// [`verify_consistency`](Self::verify_consistency).
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+    /// - `CryptoError::RandomnessFailure`: If the system RNG fails a health
+    ///   test
+    /// - `MisuseError::FeatureNotEnabled`: If `A` is a signature scheme and
+    ///   the `signing` feature is off
+    /// - Any error of [`verify_consistency`](Self::verify_consistency)
+    pub fn generate() -> Result<Self> {
+        let (public_key, secret_key) = match A::ID.kind() {
+            AlgorithmKind::Kem => dynamic::kem(A::ID.name())?.generate_keypair()?,
+            #[cfg(feature = "signing")]
+            AlgorithmKind::Signature => dynamic::signer(A::ID.name())?.generate_keypair()?,
+            _ => return Err(MisuseError::FeatureNotEnabled.into()),
+        };
+        let pair = Self {
+            public_key: PublicKey {
+                bytes: public_key.into(),
+                algorithm: PhantomData,
+            },
+            secret_key: SecretKey {
+                bytes: secret_key,
+                algorithm: PhantomData,
+            },
+        };
+        pair.verify_consistency()?;
+        Ok(pair)
+    }
+
+    /// Pair `public_key` with `secret_key` without checking that they
+    /// match. Call [`verify_consistency`](Self::verify_consistency) on
+    /// imported keys before use.
+    pub fn new(public_key: PublicKey<A>, secret_key: SecretKey<A>) -> Self {
+        Self {
+            public_key,
+            secret_key,
+        }
+    }
+
+    /// Check that the secret key belongs to the public key.
+    ///
+    /// KEM keypairs encapsulate to the public key and decapsulate with the
+    /// secret key; signature keypairs sign a fixed message and verify it.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::KeyEncapsulationFailed`: If a KEM keypair's shared
+    ///   secrets differ
+    /// - `CryptoError::VerificationFailed`: If a signature keypair's
+    ///   signature does not verify
+    /// - `MisuseError::FeatureNotEnabled`: If `A` is a signature scheme and
+    ///   the `signing` feature is off
+    /// - Any error of the algorithm rejecting either key's encoding
+    pub fn verify_consistency(&self) -> Result<()> {
+        let public_key = self.public_key.as_bytes();
+        let secret_key = self.secret_key.as_bytes();
+        match A::ID.kind() {
+            AlgorithmKind::Kem => {
+                let kem = dynamic::kem(A::ID.name())?;
+                let (ciphertext, sent) = kem.encapsulate(public_key)?;
+                let received = kem.decapsulate(secret_key, &ciphertext)?;
+                if !constant_time_eq(sent.as_bytes(), received.as_bytes()) {
+                    return Err(CryptoError::KeyEncapsulationFailed.into());
+                }
+                Ok(())
+            }
+            #[cfg(feature = "signing")]
+            AlgorithmKind::Signature => {
+                let signer = dynamic::signer(A::ID.name())?;
+                let signature = signer.sign(secret_key, CONSISTENCY_MESSAGE)?;
+                signer.verify(public_key, CONSISTENCY_MESSAGE, &signature)
+            }
+            _ => Err(MisuseError::FeatureNotEnabled.into()),
+        }
+    }
+
+    /// The public key.
+    #[inline]
+    pub fn public_key(&self) -> &PublicKey<A> {
+        &self.public_key
+    }
+
+    /// The secret key.
+    #[inline]
+    pub fn secret_key(&self) -> &SecretKey<A> {
+        &self.secret_key
+    }
+
+    /// Split into the public and secret key.
+    pub fn into_parts(self) -> (PublicKey<A>, SecretKey<A>) {
+        (self.public_key, self.secret_key)
+    }
+}
+
+impl<A: Algorithm> fmt::Debug for KeyPair<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyPair")
+            .field("algorithm", &A::ID)
+            .finish_non_exhaustive()
+    }
+}
+
+crate::memory::never_serialize!([A: Algorithm] KeyPair<A>);
+
+/// Conversions between a generic type and the algorithm's own type.
+///
+/// Into the generic type cannot fail: the algorithm's type already has the
+/// right size. Out of it goes through the algorithm's `from_bytes`, which
+/// may validate more than the length.
+macro_rules! convert {
+    ($(#[$cfg:meta])* $generic:ident<$algorithm:ty>, $concrete:ty, $wrap:expr) => {
+        $(#[$cfg])*
+        impl From<$concrete> for $generic<$algorithm> {
+            fn from(value: $concrete) -> Self {
+                Self {
+                    bytes: $wrap(value.as_bytes()),
+                    algorithm: PhantomData,
+                }
+            }
+        }
+
+        $(#[$cfg])*
+        impl TryFrom<&$generic<$algorithm>> for $concrete {
+            type Error = Error;
+
+            fn try_from(value: &$generic<$algorithm>) -> Result<Self> {
+                <$concrete>::from_bytes(value.as_bytes())
+            }
+        }
+    };
+}
+
+fn boxed(bytes: &[u8]) -> Box<[u8]> {
+    bytes.into()
+}
+
+fn secure(bytes: &[u8]) -> SecureBuffer {
+    SecureBuffer::new(bytes.to_vec())
+}
+
+convert!(PublicKey<X25519>, X25519PublicKey, boxed);
+convert!(SecretKey<X25519>, X25519SecretKey, secure);
+convert!(PublicKey<Ed25519>, Ed25519VerifyingKey, boxed);
+convert!(
+    #[cfg(feature = "signing")]
+    SecretKey<Ed25519>,
+    crate::sign::Ed25519SigningKey,
+    secure
+);
+convert!(Signature<Ed25519>, Ed25519Signature, boxed);
+convert!(
+    #[cfg(feature = "mlkem")]
+    PublicKey<crate::kem::MlKem768>,
+    crate::kem::MlKem768PublicKey,
+    boxed
+);
+convert!(
+    #[cfg(feature = "mlkem")]
+    SecretKey<crate::kem::MlKem768>,
+    crate::kem::MlKem768SecretKey,
+    secure
+);
+convert!(
+    #[cfg(feature = "mlkem")]
+    Ciphertext<crate::kem::MlKem768>,
+    crate::kem::MlKem768Ciphertext,
+    boxed
+);
+convert!(
+    #[cfg(feature = "mldsa")]
+    PublicKey<crate::sign::MlDsa87>,
+    crate::sign::MlDsa87VerifyingKey,
+    boxed
+);
+convert!(
+    #[cfg(all(feature = "mldsa", feature = "signing"))]
+    SecretKey<crate::sign::MlDsa87>,
+    crate::sign::MlDsa87SigningKey,
+    secure
+);
+convert!(
+    #[cfg(feature = "mldsa")]
+    Signature<crate::sign::MlDsa87>,
+    crate::sign::MlDsa87Signature,
+    boxed
+);
+
+impl<A: Algorithm> From<SecretKey<A>> for SecureBuffer {
+    fn from(value: SecretKey<A>) -> Self {
+        value.bytes
+    }
+}
+
+/// Moves the buffer into the key; it is zeroized on drop if the length is
+/// wrong.
+impl<A: Algorithm> TryFrom<SecureBuffer> for SecretKey<A> {
+    type Error = Error;
+
+    fn try_from(value: SecureBuffer) -> Result<Self> {
+        A::ID.check_secret_key(value.as_slice())?;
+        Ok(Self {
+            bytes: value,
+            algorithm: PhantomData,
+        })
+    }
+}
+
+impl<A: Algorithm, const N: usize> TryFrom<SensitiveBytes<N>> for SecretKey<A> {
+    type Error = Error;
+
+    fn try_from(value: SensitiveBytes<N>) -> Result<Self> {
+        Self::try_from(SecureBuffer::from(value))
+    }
+}
+
+impl<A: Algorithm, const N: usize> TryFrom<SecretKey<A>> for SensitiveBytes<N> {
+    type Error = Error;
+
+    fn try_from(value: SecretKey<A>) -> Result<Self> {
+        Self::try_from(SecureBuffer::from(value))
+    }
+}
+
+impl From<SharedSecret> for SensitiveBytes<32> {
+    fn from(value: SharedSecret) -> Self {
+        value.0
+    }
+}
+
+impl From<SensitiveBytes<32>> for SharedSecret {
+    fn from(value: SensitiveBytes<32>) -> Self {
+        Self(value)
+    }
+}
+
+impl From<SharedSecret> for SecureBuffer {
+    fn from(value: SharedSecret) -> Self {
+        Self::from(value.0)
+    }
+}
+
+impl TryFrom<SecureBuffer> for SharedSecret {
+    type Error = Error;
+
+    fn try_from(value: SecureBuffer) -> Result<Self> {
+        SensitiveBytes::try_from(value).map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secrets_move_between_containers() {
+        let key = SecretKey::<X25519>::from_bytes(&[7; 32]).unwrap();
+        let buffer = SecureBuffer::from(key);
+        assert_eq!(buffer.as_slice(), [7; 32]);
+        let key = SecretKey::<X25519>::try_from(buffer).unwrap();
+        let bytes = SensitiveBytes::<32>::try_from(key).unwrap();
+        let key = SecretKey::<X25519>::try_from(bytes).unwrap();
+        assert_eq!(key.as_bytes(), [7; 32]);
+        assert_eq!(
+            SecretKey::<X25519>::try_from(SecureBuffer::zeroed(31))
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::InvalidSecretKeyLength)
+        );
+        assert_eq!(
+            SensitiveBytes::<16>::try_from(key).unwrap_err().misuse(),
+            Some(MisuseError::InvalidKeyLength)
+        );
+
+        let secret = SharedSecret::from(SensitiveBytes::new([3; 32]));
+        let buffer = SecureBuffer::from(secret);
+        let secret = SharedSecret::try_from(buffer).unwrap();
+        assert_eq!(SensitiveBytes::from(secret).as_bytes(), &[3; 32]);
+        assert!(SharedSecret::try_from(SecureBuffer::zeroed(16)).is_err());
+    }
+
+    #[test]
+    fn lengths_follow_the_marker() {
+        assert!(PublicKey::<X25519>::from_bytes(&[9; 32]).is_ok());
+        assert_eq!(
+            PublicKey::<X25519>::from_bytes(&[9; 33])
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::InvalidPublicKeyLength)
+        );
+        assert_eq!(
+            Signature::<Ed25519>::from_bytes(&[0; 32])
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::InvalidSignatureLength)
+        );
+        assert_eq!(
+            PublicKey::<Ed25519>::from_bytes(&[0; 32])
+                .unwrap()
+                .algorithm(),
+            AlgorithmId::Ed25519
+        );
+    }
+
+    #[cfg(feature = "signing")]
+    #[test]
+    fn converts_to_and_from_algorithm_types() {
+        let (verifying_key, signing_key) = Ed25519::generate_keypair().unwrap();
+        let signature = Ed25519::sign(&signing_key, b"message").unwrap();
+
+        let public = PublicKey::<Ed25519>::from(verifying_key.clone());
+        let secret = SecretKey::<Ed25519>::from(signing_key);
+        let signature = Signature::<Ed25519>::from(signature);
+        assert_eq!(public.as_bytes(), verifying_key.as_bytes());
+        assert!(!format!("{secret:?}").contains(&format!("{:?}", secret.as_bytes())));
+
+        let signing_key = crate::sign::Ed25519SigningKey::try_from(&secret).unwrap();
+        let signature = Ed25519Signature::try_from(&signature).unwrap();
+        assert_eq!(signing_key.verifying_key(), verifying_key);
+        Ed25519::verify(
+            &Ed25519VerifyingKey::try_from(&public).unwrap(),
+            b"message",
+            &signature,
+        )
+        .unwrap();
+
+        // Right length, but not a valid Ed25519 point
+        let public = PublicKey::<Ed25519>::from_bytes(&[0xff; 32]).unwrap();
+        assert_eq!(
+            Ed25519VerifyingKey::try_from(&public).unwrap_err().misuse(),
+            Some(MisuseError::InvalidEncoding)
+        );
+    }
+
+    #[test]
+    fn pairwise_consistency() {
+        let pair = KeyPair::<X25519>::generate().unwrap();
+        pair.verify_consistency().unwrap();
+        let (_, other) = KeyPair::<X25519>::generate().unwrap().into_parts();
+        let mismatched = KeyPair::new(pair.public_key().clone(), other);
+        assert_eq!(
+            mismatched.verify_consistency().unwrap_err().crypto(),
+            Some(CryptoError::KeyEncapsulationFailed)
+        );
+    }
+
+    #[cfg(all(feature = "mlkem", feature = "mldsa", feature = "signing"))]
+    #[test]
+    fn pairwise_consistency_post_quantum() {
+        use crate::kem::MlKem768;
+        use crate::sign::MlDsa87;
+
+        KeyPair::<MlKem768>::generate().unwrap();
+        let (_, other) = KeyPair::<MlKem768>::generate().unwrap().into_parts();
+        let (public_key, _) = KeyPair::<MlKem768>::generate().unwrap().into_parts();
+        assert!(
+            KeyPair::new(public_key, other)
+                .verify_consistency()
+                .is_err()
+        );
+
+        let (public_key, _) = KeyPair::<MlDsa87>::generate().unwrap().into_parts();
+        let (_, other) = KeyPair::<MlDsa87>::generate().unwrap().into_parts();
+        assert_eq!(
+            KeyPair::new(public_key, other)
+                .verify_consistency()
+                .unwrap_err()
+                .crypto(),
+            Some(CryptoError::VerificationFailed)
+        );
+    }
+}
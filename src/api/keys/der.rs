@@ -0,0 +1,346 @@
+//! DER key serialization: SubjectPublicKeyInfo (RFC 5280) for public keys
+//! and PKCS#8 (RFC 5958) for secret keys.
+//!
+//! Algorithm identifiers are the registered OIDs with parameters absent:
+//! `id-X25519` and `id-Ed25519` from RFC 8410, and the NIST arcs
+//! `id-alg-ml-kem-768` (2.16.840.1.101.3.4.4.2) and `id-ml-dsa-87`
+//! (2.16.840.1.101.3.4.3.19), as OpenSSL's providers use them.
+//!
+//! Secret keys are written as an `OCTET STRING` holding the raw key: the
+//! RFC 8410 `CurvePrivateKey`, and the `expandedKey` choice of the IETF
+//! LAMPS ML-KEM and ML-DSA private key formats. On import the `seed` and
+//! `both` choices are accepted too; the key is expanded from the seed, and
+//! in `both` must match the expanded key given alongside it.
+
+use super::{Algorithm, PublicKey, SecretKey};
+use crate::algorithm::AlgorithmId;
+use crate::encoding::Reader;
+use crate::errors::{MisuseError, Result};
+use crate::memory::{SecureBuffer, constant_time_eq};
+
+const INTEGER: u8 = 0x02;
+const BIT_STRING: u8 = 0x03;
+const OCTET_STRING: u8 = 0x04;
+const OBJECT_IDENTIFIER: u8 = 0x06;
+const SEQUENCE: u8 = 0x30;
+/// `seed [0] OCTET STRING` of an ML-KEM or ML-DSA private key.
+const SEED: u8 = 0x80;
+/// `attributes [0]` of a PKCS#8 `OneAsymmetricKey`.
+const ATTRIBUTES: u8 = 0xa0;
+/// `publicKey [1] BIT STRING` of a version 2 `OneAsymmetricKey`.
+const PUBLIC_KEY: u8 = 0x81;
+
+/// Content octets of the algorithm's OID.
+fn oid(algorithm: AlgorithmId) -> &'static [u8] {
+    match algorithm {
+        AlgorithmId::X25519 => &[0x2b, 0x65, 0x6e],
+        AlgorithmId::Ed25519 => &[0x2b, 0x65, 0x70],
+        AlgorithmId::MlKem768 => &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x04, 0x02],
+        AlgorithmId::MlDsa87 => &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x03, 0x13],
+        other => unreachable!("no key marker for {other}"),
+    }
+}
+
+impl<A: Algorithm> PublicKey<A> {
+    /// Parse a DER `SubjectPublicKeyInfo`.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidEncoding`: If `der` is not a DER
+    ///   `SubjectPublicKeyInfo`, or has trailing data
+    /// - `MisuseError::InvalidAlgorithmIdentifier`: If the key is not an
+    ///   `A` key
+    /// - `MisuseError::InvalidPublicKeyLength`: If the key has the wrong
+    ///   length
+    pub fn from_spki_der(der: &[u8]) -> Result<Self> {
+        let mut outer = Reader::new(der);
+        let mut info = Reader::new(read(&mut outer, SEQUENCE)?);
+        outer.finish()?;
+        read_algorithm(&mut info, A::ID)?;
+        let bits = read(&mut info, BIT_STRING)?;
+        info.finish()?;
+        let Some((0, key)) = bits.split_first() else {
+            return Err(MisuseError::InvalidEncoding.into());
+        };
+        Self::from_bytes(key)
+    }
+
+    /// Encode as a DER `SubjectPublicKeyInfo`.
+    pub fn to_spki_der(&self) -> Vec<u8> {
+        let key = self.as_bytes();
+        let oid = oid(A::ID);
+        let length = algorithm_len(oid) + tlv_len(1 + key.len());
+        let mut out = Vec::with_capacity(tlv_len(length));
+        write_header(SEQUENCE, length, &mut out);
+        write_algorithm(oid, &mut out);
+        write_header(BIT_STRING, 1 + key.len(), &mut out);
+        out.push(0);
+        out.extend_from_slice(key);
+        out
+    }
+}
+
+impl<A: Algorithm> SecretKey<A> {
+    /// Parse a DER PKCS#8 `PrivateKeyInfo` or `OneAsymmetricKey`.
+    ///
+    /// Attributes and an embedded public key are ignored.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidEncoding`: If `der` is not DER PKCS#8, has
+    ///   trailing data, or a seed does not match the expanded key stored
+    ///   with it
+    /// - `MisuseError::InvalidAlgorithmIdentifier`: If the key is not an
+    ///   `A` key
+    /// - `MisuseError::InvalidSecretKeyLength`: If the key or seed has the
+    ///   wrong length
+    /// - `MisuseError::FeatureNotEnabled`: If an ML-DSA-87 key is given as
+    ///   a seed and the `signing` feature is off
+    pub fn from_pkcs8_der(der: &[u8]) -> Result<Self> {
+        let mut outer = Reader::new(der);
+        let mut info = Reader::new(read(&mut outer, SEQUENCE)?);
+        outer.finish()?;
+        if !matches!(read(&mut info, INTEGER)?, [0] | [1]) {
+            return Err(MisuseError::InvalidEncoding.into());
+        }
+        read_algorithm(&mut info, A::ID)?;
+        let mut private_key = Reader::new(read(&mut info, OCTET_STRING)?);
+        if info.peek() == Some(ATTRIBUTES) {
+            read(&mut info, ATTRIBUTES)?;
+        }
+        if info.peek() == Some(PUBLIC_KEY) {
+            read(&mut info, PUBLIC_KEY)?;
+        }
+        info.finish()?;
+
+        let key = match private_key.peek() {
+            Some(OCTET_STRING) => Self::from_bytes(read(&mut private_key, OCTET_STRING)?)?,
+            Some(SEED) => Self::from_seed(read(&mut private_key, SEED)?)?,
+            Some(SEQUENCE) => {
+                let mut both = Reader::new(read(&mut private_key, SEQUENCE)?);
+                let key = Self::from_seed(read(&mut both, OCTET_STRING)?)?;
+                let expanded = read(&mut both, OCTET_STRING)?;
+                both.finish()?;
+                if !constant_time_eq(key.as_bytes(), expanded) {
+                    return Err(MisuseError::InvalidEncoding.into());
+                }
+                key
+            }
+            _ => return Err(MisuseError::InvalidEncoding.into()),
+        };
+        private_key.finish()?;
+        Ok(key)
+    }
+
+    /// Encode as a DER PKCS#8 `PrivateKeyInfo`.
+    ///
+    /// # Security
+    ///
+    /// The output contains the secret key unencrypted. Do not log or store
+    /// it.
+    pub fn to_pkcs8_der(&self) -> SecureBuffer {
+        let key = self.as_bytes();
+        let oid = oid(A::ID);
+        let private_key = tlv_len(tlv_len(key.len()));
+        let length = tlv_len(1) + algorithm_len(oid) + private_key;
+        // Sized up front so the key is never copied by a reallocation
+        let mut out = Vec::with_capacity(tlv_len(length));
+        write_header(SEQUENCE, length, &mut out);
+        out.extend_from_slice(&[INTEGER, 1, 0]);
+        write_algorithm(oid, &mut out);
+        write_header(OCTET_STRING, tlv_len(key.len()), &mut out);
+        write_header(OCTET_STRING, key.len(), &mut out);
+        out.extend_from_slice(key);
+        SecureBuffer::new(out)
+    }
+
+    /// Expand the `seed` choice of a post-quantum private key.
+    #[cfg_attr(not(feature = "mlkem"), allow(unused_variables))]
+    fn from_seed(seed: &[u8]) -> Result<Self> {
+        match A::ID {
+            #[cfg(feature = "mlkem")]
+            AlgorithmId::MlKem768 => {
+                let seed = seed
+                    .try_into()
+                    .map_err(|_| MisuseError::InvalidSecretKeyLength)?;
+                let (_, secret_key) = crate::kem::MlKem768::keypair_from_seed(seed);
+                Self::from_bytes(secret_key.as_bytes())
+            }
+            #[cfg(all(feature = "mldsa", feature = "signing"))]
+            AlgorithmId::MlDsa87 => {
+                let seed = seed
+                    .try_into()
+                    .map_err(|_| MisuseError::InvalidSecretKeyLength)?;
+                let (_, secret_key) = crate::sign::MlDsa87::keypair_from_seed(seed);
+                Self::from_bytes(secret_key.as_bytes())
+            }
+            #[cfg(all(feature = "mldsa", not(feature = "signing")))]
+            AlgorithmId::MlDsa87 => Err(MisuseError::FeatureNotEnabled.into()),
+            _ => Err(MisuseError::InvalidEncoding.into()),
+        }
+    }
+}
+
+/// Read `AlgorithmIdentifier`, which must be `expected` with parameters
+/// absent.
+fn read_algorithm(reader: &mut Reader<'_>, expected: AlgorithmId) -> Result<()> {
+    let mut identifier = Reader::new(read(reader, SEQUENCE)?);
+    let oid = read(&mut identifier, OBJECT_IDENTIFIER)?;
+    identifier.finish()?;
+    if oid != self::oid(expected) {
+        return Err(MisuseError::InvalidAlgorithmIdentifier.into());
+    }
+    Ok(())
+}
+
+fn algorithm_len(oid: &[u8]) -> usize {
+    tlv_len(tlv_len(oid.len()))
+}
+
+fn write_algorithm(oid: &[u8], out: &mut Vec<u8>) {
+    write_header(SEQUENCE, tlv_len(oid.len()), out);
+    write_header(OBJECT_IDENTIFIER, oid.len(), out);
+    out.extend_from_slice(oid);
+}
+
+/// Encoded size of a tag, length and `length` content octets.
+fn tlv_len(length: usize) -> usize {
+    match length {
+        0..0x80 => 2 + length,
+        0x80..0x100 => 3 + length,
+        _ => 4 + length,
+    }
+}
+
+fn write_header(tag: u8, length: usize, out: &mut Vec<u8>) {
+    debug_assert!(length <= 0xffff);
+    out.push(tag);
+    match length {
+        0..0x80 => out.push(length as u8),
+        0x80..0x100 => out.extend_from_slice(&[0x81, length as u8]),
+        _ => out.extend_from_slice(&[0x82, (length >> 8) as u8, length as u8]),
+    }
+}
+
+/// Read one `tag` element and return its contents.
+///
+/// Tags are single bytes and lengths below 64 KiB, which covers every key
+/// format here.
+fn read<'a>(reader: &mut Reader<'a>, tag: u8) -> Result<&'a [u8]> {
+    if reader.byte()? != tag {
+        return Err(MisuseError::InvalidEncoding.into());
+    }
+    let length = match reader.byte()? {
+        length @ 0..=0x7f => usize::from(length),
+        // Minimal encodings only, as DER requires
+        0x81 => match reader.byte()? {
+            length @ 0x80.. => usize::from(length),
+            _ => return Err(MisuseError::InvalidEncoding.into()),
+        },
+        0x82 => match *reader.take(2)? {
+            [high, low] if high != 0 => usize::from(high) << 8 | usize::from(low),
+            _ => return Err(MisuseError::InvalidEncoding.into()),
+        },
+        _ => return Err(MisuseError::InvalidEncoding.into()),
+    };
+    reader.take(length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kex::X25519;
+    use crate::sign::Ed25519;
+
+    fn unhex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn rfc8410_examples() {
+        // Sections 10.1 and 10.3
+        let spki = unhex(
+            "302a300506032b657003210019bf44096984cdfe8541bac167dc3b96c85086aa30b6b6cb0c5c38ad703166e1",
+        );
+        let pkcs8 = unhex(
+            "302e020100300506032b657004220420d4ee72dbf913584ad5b6d8f1f769f8ad3afe7c28cbf1d4fbe097a88f44755842",
+        );
+        let public_key = PublicKey::<Ed25519>::from_spki_der(&spki).unwrap();
+        assert_eq!(public_key.as_bytes(), &spki[12..]);
+        assert_eq!(public_key.to_spki_der(), spki);
+        let secret_key = SecretKey::<Ed25519>::from_pkcs8_der(&pkcs8).unwrap();
+        assert_eq!(secret_key.as_bytes(), &pkcs8[16..]);
+        assert_eq!(secret_key.to_pkcs8_der().as_slice(), pkcs8);
+
+        assert_eq!(
+            PublicKey::<X25519>::from_spki_der(&spki)
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::InvalidAlgorithmIdentifier)
+        );
+        for malformed in [&spki[..spki.len() - 1], &[&spki[..], &[0]].concat()] {
+            assert_eq!(
+                PublicKey::<Ed25519>::from_spki_der(malformed)
+                    .unwrap_err()
+                    .misuse(),
+                Some(MisuseError::InvalidEncoding)
+            );
+        }
+    }
+
+    #[cfg(all(feature = "mlkem", feature = "mldsa", feature = "signing"))]
+    #[test]
+    fn openssl_post_quantum_keys() {
+        use crate::algorithms::classical::sha256::Sha256;
+        use crate::kem::MlKem768;
+        use crate::sign::MlDsa87;
+
+        // `openssl genpkey` with `hexseed` 00..1f / 00..3f and seed-only
+        // output, and SHA-256 of `openssl pkey -pubout -outform DER`
+        let dsa_seed = unhex(
+            "3034020100300b06096086480165030403130422802000010203\
+             0405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+        );
+        let secret_key = SecretKey::<MlDsa87>::from_pkcs8_der(&dsa_seed).unwrap();
+        let seed: [u8; 32] = core::array::from_fn(|i| i as u8);
+        let (verifying_key, signing_key) = MlDsa87::keypair_from_seed(&seed);
+        assert_eq!(secret_key.as_bytes(), signing_key.as_bytes());
+        assert_eq!(
+            Sha256::digest(&PublicKey::from(verifying_key).to_spki_der()),
+            unhex("07e57c4f14dbad1267f621ec3777b4e2e6c4fbc4c22fbb87510ff8e0b3c6a642")[..]
+        );
+
+        let kem_seed = unhex(
+            "3054020100300b06096086480165030404020442804000010203\
+             0405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f\
+             202122232425262728292a2b2c2d2e2f303132333435363738393a3b3c3d3e3f",
+        );
+        let secret_key = SecretKey::<MlKem768>::from_pkcs8_der(&kem_seed).unwrap();
+        let seed: [u8; 64] = core::array::from_fn(|i| i as u8);
+        let (public_key, expanded) = MlKem768::keypair_from_seed(&seed);
+        assert_eq!(secret_key.as_bytes(), expanded.as_bytes());
+        let public_key = PublicKey::from(public_key);
+        assert_eq!(
+            Sha256::digest(&public_key.to_spki_der()),
+            unhex("c23e23dd3d485a9256cda09358a4a286e00b373db10761eadf99f710649ca31c")[..]
+        );
+        assert_eq!(
+            PublicKey::<MlKem768>::from_spki_der(&public_key.to_spki_der()).unwrap(),
+            public_key
+        );
+
+        // Expanded form round trip
+        let der = secret_key.to_pkcs8_der();
+        let decoded = SecretKey::<MlKem768>::from_pkcs8_der(der.as_slice()).unwrap();
+        assert_eq!(decoded.as_bytes(), secret_key.as_bytes());
+        assert_eq!(
+            SecretKey::<MlDsa87>::from_pkcs8_der(der.as_slice())
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::InvalidAlgorithmIdentifier)
+        );
+    }
+}
@@ -0,0 +1,331 @@
+//! Encrypted secret-key containers.
+//!
+//! Secret keys at rest should never be stored as raw bytes. These methods
+//! seal a [`SecretKey`] under a passphrase, stretched with Argon2id, or
+//! under a 32-byte key-encryption key (KEK) from a KMS or HSM, into one
+//! self-describing encoding.
+//!
+//! # Format
+//!
+//! ```text
+//! magic "CTSK" (4) || version (1) || key algorithm (2) || aead (2)
+//!     || kdf (1) || kdf params || nonce || ciphertext || tag
+//!
+//! kdf params = memory_kib (4) || passes (4) || lanes (4) || salt (16)    kdf 1, Argon2id
+//!            | empty                                                    kdf 2, KEK
+//! ```
+//!
+//! - `key algorithm` and `aead` are big-endian [`AlgorithmId`] codes
+//! - Argon2id parameters are big-endian and its output is the AEAD key; a
+//!   KEK is the AEAD key directly
+//!
+//! Everything before the nonce is the AEAD's associated data, so the tag
+//! is the container's checksum: a wrong passphrase or KEK, or any change
+//! to the algorithm, the KDF parameters or the ciphertext, fails to
+//! decrypt.
+//!
+//! # Security
+//!
+//! - The Argon2id parameters are read from the container, so a hostile
+//!   container could demand any cost; decryption takes the highest cost
+//!   the caller accepts
+//! - The key algorithm is in the clear, but nothing else about the key
+
+use core::marker::PhantomData;
+
+use super::{Algorithm, SecretKey};
+use crate::algorithm::{AlgorithmId, AlgorithmKind};
+use crate::dynamic;
+use crate::encoding::Reader;
+use crate::errors::{MisuseError, Result};
+#[cfg(feature = "argon2")]
+use crate::kdf::password::{self, Argon2idParams};
+use crate::memory::SecureBuffer;
+
+/// First bytes of every encrypted secret key.
+pub const MAGIC: [u8; 4] = *b"CTSK";
+
+/// Format version written by the `encrypt_with_*` methods.
+pub const VERSION: u8 = 1;
+
+/// Size of the AEAD key, and so of a KEK, in bytes.
+pub const KEK_SIZE: usize = 32;
+
+/// Size of the Argon2id salt in bytes.
+#[cfg(feature = "argon2")]
+const SALT_SIZE: usize = 16;
+
+const KDF_ARGON2ID: u8 = 1;
+const KDF_KEK: u8 = 2;
+
+impl<A: Algorithm> SecretKey<A> {
+    /// Encrypt under `passphrase`, stretched with Argon2id under `params`
+    /// and a fresh salt, with the AEAD `aead`.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidAlgorithmIdentifier`: If `aead` is not an
+    ///   AEAD with 32-byte keys
+    /// - `MisuseError::FeatureNotEnabled`: If the AEAD's feature is off
+    /// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    #[cfg(feature = "argon2")]
+    pub fn encrypt_with_passphrase(
+        &self,
+        passphrase: &[u8],
+        params: &Argon2idParams,
+        aead: AlgorithmId,
+    ) -> Result<Vec<u8>> {
+        let cipher = cipher(aead)?;
+        let mut salt = [0u8; SALT_SIZE];
+        crate::internal::entropy::fill(&mut salt)?;
+        let key = password::argon2id_key(passphrase, &salt, params)?;
+
+        let mut header = header::<A>(aead, KDF_ARGON2ID);
+        for value in [params.memory_kib(), params.passes(), params.lanes()] {
+            header.extend_from_slice(&value.to_be_bytes());
+        }
+        header.extend_from_slice(&salt);
+        let body = cipher.encrypt(key.as_bytes(), self.as_bytes(), &header)?;
+        header.extend_from_slice(&body);
+        Ok(header)
+    }
+
+    /// Encrypt under the key-encryption key `kek` with the AEAD `aead`.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidKeyLength`: If `kek` is not [`KEK_SIZE`]
+    ///   bytes
+    /// - `MisuseError::InvalidAlgorithmIdentifier`: If `aead` is not an
+    ///   AEAD with 32-byte keys
+    /// - `MisuseError::FeatureNotEnabled`: If the AEAD's feature is off
+    /// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+    pub fn encrypt_with_kek(&self, kek: &[u8], aead: AlgorithmId) -> Result<Vec<u8>> {
+        let cipher = cipher(aead)?;
+        if kek.len() != KEK_SIZE {
+            return Err(MisuseError::InvalidKeyLength.into());
+        }
+        let mut header = header::<A>(aead, KDF_KEK);
+        let body = cipher.encrypt(kek, self.as_bytes(), &header)?;
+        header.extend_from_slice(&body);
+        Ok(header)
+    }
+
+    /// Decrypt a container from
+    /// [`encrypt_with_passphrase`](Self::encrypt_with_passphrase).
+    ///
+    /// Containers demanding more memory, passes or lanes than `max_cost`
+    /// are rejected before any work is done.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::DecryptionFailed`: If `passphrase` is wrong or the
+    ///   container was modified
+    /// - `MisuseError::ParseBudgetExceeded`: If the container's Argon2id
+    ///   cost exceeds `max_cost`
+    /// - `MisuseError::InvalidAlgorithmIdentifier`: If the key is not an
+    ///   `A` key, or the container is not passphrase-encrypted
+    /// - `MisuseError::InvalidEncoding`: If the magic, the version or a
+    ///   field is invalid
+    /// - `MisuseError::InvalidCiphertextLength`: If the container is too
+    ///   short for the AEAD's nonce and tag
+    /// - `MisuseError::InvalidParameterSet`: If the Argon2id parameters are
+    ///   invalid
+    /// - `MisuseError::FeatureNotEnabled`: If the AEAD's feature is off
+    #[cfg(feature = "argon2")]
+    pub fn decrypt_with_passphrase(
+        container: &[u8],
+        passphrase: &[u8],
+        max_cost: &Argon2idParams,
+    ) -> Result<Self> {
+        let mut reader = Reader::new(container);
+        let aead = read_header::<A>(&mut reader, KDF_ARGON2ID)?;
+        let mut value = || -> Result<u32> {
+            let bytes = reader.take(4)?.try_into().expect("4 bytes");
+            Ok(u32::from_be_bytes(bytes))
+        };
+        let (memory_kib, passes, lanes) = (value()?, value()?, value()?);
+        if memory_kib > max_cost.memory_kib()
+            || passes > max_cost.passes()
+            || lanes > max_cost.lanes()
+        {
+            return Err(MisuseError::ParseBudgetExceeded.into());
+        }
+        let params = Argon2idParams::new(memory_kib, passes, lanes)?;
+        let salt = reader.take(SALT_SIZE)?;
+        let key = password::argon2id_key(passphrase, salt, &params)?;
+        Self::open(container, reader, aead, key.as_bytes())
+    }
+
+    /// Decrypt a container from [`encrypt_with_kek`](Self::encrypt_with_kek).
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::DecryptionFailed`: If `kek` is wrong or the
+    ///   container was modified
+    /// - `MisuseError::InvalidKeyLength`: If `kek` is not [`KEK_SIZE`]
+    ///   bytes
+    /// - `MisuseError::InvalidAlgorithmIdentifier`: If the key is not an
+    ///   `A` key, or the container is not KEK-encrypted
+    /// - `MisuseError::InvalidEncoding`: If the magic, the version or a
+    ///   field is invalid
+    /// - `MisuseError::InvalidCiphertextLength`: If the container is too
+    ///   short for the AEAD's nonce and tag
+    /// - `MisuseError::FeatureNotEnabled`: If the AEAD's feature is off
+    pub fn decrypt_with_kek(container: &[u8], kek: &[u8]) -> Result<Self> {
+        if kek.len() != KEK_SIZE {
+            return Err(MisuseError::InvalidKeyLength.into());
+        }
+        let mut reader = Reader::new(container);
+        let aead = read_header::<A>(&mut reader, KDF_KEK)?;
+        Self::open(container, reader, aead, kek)
+    }
+
+    /// Decrypt the body after the header `reader` has consumed.
+    fn open(container: &[u8], reader: Reader<'_>, aead: AlgorithmId, key: &[u8]) -> Result<Self> {
+        let (header, body) = container.split_at(container.len() - reader.rest().len());
+        let secret_key = SecureBuffer::new(cipher(aead)?.decrypt(key, body, header)?);
+        A::ID.check_secret_key(secret_key.as_slice())?;
+        Ok(Self {
+            bytes: secret_key,
+            algorithm: PhantomData,
+        })
+    }
+}
+
+/// The AEAD `aead`, which must take [`KEK_SIZE`]-byte keys.
+fn cipher(aead: AlgorithmId) -> Result<&'static dyn dynamic::DynAead> {
+    if aead.kind() != AlgorithmKind::Aead || aead.sizes().secret_key != Some(KEK_SIZE) {
+        return Err(MisuseError::InvalidAlgorithmIdentifier.into());
+    }
+    dynamic::aead(aead.name())
+}
+
+fn header<A: Algorithm>(aead: AlgorithmId, kdf: u8) -> Vec<u8> {
+    let mut header = Vec::new();
+    header.extend_from_slice(&MAGIC);
+    header.push(VERSION);
+    header.extend_from_slice(&A::ID.to_bytes());
+    header.extend_from_slice(&aead.to_bytes());
+    header.push(kdf);
+    header
+}
+
+/// Read the fixed header fields and return the AEAD.
+fn read_header<A: Algorithm>(reader: &mut Reader<'_>, kdf: u8) -> Result<AlgorithmId> {
+    if reader.take(MAGIC.len())? != MAGIC || reader.byte()? != VERSION {
+        return Err(MisuseError::InvalidEncoding.into());
+    }
+    if AlgorithmId::from_bytes(reader.take(AlgorithmId::SIZE)?)? != A::ID {
+        return Err(MisuseError::InvalidAlgorithmIdentifier.into());
+    }
+    let aead = AlgorithmId::from_bytes(reader.take(AlgorithmId::SIZE)?)?;
+    match reader.take(1)? {
+        [found] if *found == kdf => Ok(aead),
+        [KDF_ARGON2ID | KDF_KEK] => Err(MisuseError::InvalidAlgorithmIdentifier.into()),
+        _ => Err(MisuseError::InvalidEncoding.into()),
+    }
+}
+
+#[cfg(all(test, feature = "argon2", feature = "aes-gcm-siv"))]
+mod tests {
+    use super::*;
+    use crate::errors::CryptoError;
+    use crate::kex::X25519;
+    use crate::sign::Ed25519;
+
+    fn params() -> Argon2idParams {
+        Argon2idParams::new(64, 1, 1).unwrap()
+    }
+
+    #[test]
+    fn passphrase_round_trip() {
+        let secret_key = SecretKey::<X25519>::from_bytes(&[7; 32]).unwrap();
+        let container = secret_key
+            .encrypt_with_passphrase(b"hunter2", &params(), AlgorithmId::Aes256GcmSiv)
+            .unwrap();
+        assert_eq!(container[..4], MAGIC);
+        assert!(!container.windows(32).any(|window| window == [7; 32]));
+
+        let decrypted =
+            SecretKey::<X25519>::decrypt_with_passphrase(&container, b"hunter2", &params())
+                .unwrap();
+        assert_eq!(decrypted.as_bytes(), secret_key.as_bytes());
+        assert_eq!(
+            SecretKey::<X25519>::decrypt_with_passphrase(&container, b"hunter3", &params())
+                .unwrap_err()
+                .crypto(),
+            Some(CryptoError::DecryptionFailed)
+        );
+
+        // Lowering the stored cost is caught by the tag
+        let mut weakened = container.clone();
+        weakened[13] -= 32;
+        assert_eq!(
+            SecretKey::<X25519>::decrypt_with_passphrase(&weakened, b"hunter2", &params())
+                .unwrap_err()
+                .crypto(),
+            Some(CryptoError::DecryptionFailed)
+        );
+        let cheaper = Argon2idParams::new(32, 1, 1).unwrap();
+        assert_eq!(
+            SecretKey::<X25519>::decrypt_with_passphrase(&container, b"hunter2", &cheaper)
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::ParseBudgetExceeded)
+        );
+        assert_eq!(
+            SecretKey::<Ed25519>::decrypt_with_passphrase(&container, b"hunter2", &params())
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::InvalidAlgorithmIdentifier)
+        );
+    }
+
+    #[test]
+    fn kek_round_trip() {
+        let kek = [0x42; KEK_SIZE];
+        let secret_key = SecretKey::<Ed25519>::from_bytes(&[9; 32]).unwrap();
+        let container = secret_key
+            .encrypt_with_kek(&kek, AlgorithmId::Aes256GcmSiv)
+            .unwrap();
+        let decrypted = SecretKey::<Ed25519>::decrypt_with_kek(&container, &kek).unwrap();
+        assert_eq!(decrypted.as_bytes(), secret_key.as_bytes());
+
+        assert_eq!(
+            SecretKey::<Ed25519>::decrypt_with_kek(&container, &[0x43; KEK_SIZE])
+                .unwrap_err()
+                .crypto(),
+            Some(CryptoError::DecryptionFailed)
+        );
+        assert_eq!(
+            SecretKey::<Ed25519>::decrypt_with_passphrase(&container, b"kek", &params())
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::InvalidAlgorithmIdentifier)
+        );
+        assert_eq!(
+            secret_key
+                .encrypt_with_kek(&kek, AlgorithmId::Ed25519)
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::InvalidAlgorithmIdentifier)
+        );
+        assert_eq!(
+            SecretKey::<Ed25519>::decrypt_with_kek(&container[..8], &kek)
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::InvalidEncoding)
+        );
+        let mut future_version = container.clone();
+        future_version[MAGIC.len()] = VERSION + 1;
+        assert_eq!(
+            SecretKey::<Ed25519>::decrypt_with_kek(&future_version, &kek)
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::InvalidEncoding)
+        );
+    }
+}
@@ -0,0 +1,188 @@
+//! Security levels.
+//!
+//! # Purpose
+//!
+//! Matching parameter sets by hand (a KEM of one strength, a signature of
+//! another, a hash too short for both) is easy to get wrong. A
+//! [`SecurityLevel`] names a NIST post-quantum security category, and
+//! [`SecurityLevel::parameters`] returns the cheapest consistent
+//! combination of KEM, signature and hash whose every component reaches
+//! it.
+//!
+//! # Levels
+//!
+//! | Level | Strength | KEM                 | Signature           | Hash     |
+//! |-------|----------|---------------------|---------------------|----------|
+//! | L1    | AES-128  | X25519 + ML-KEM-768 | Ed25519 + ML-DSA-87 | SHA3-256 |
+//! | L3    | AES-192  | X25519 + ML-KEM-768 | Ed25519 + ML-DSA-87 | SHA3-384 |
+//! | L5    | AES-256  | not available       | Ed25519 + ML-DSA-87 | SHA3-512 |
+//!
+//! Components are never weaker than the level, but may be stronger when
+//! the crate has nothing closer: ML-DSA-87 serves every level. Level 5
+//! needs ML-KEM-1024, which this crate does not implement, so it is
+//! rejected rather than silently downgraded. The level of a hybrid is the
+//! level of its post-quantum component; the classical half adds defence in
+//! depth only.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use citadel::level::SecurityLevel;
+//!
+//! let parameters = SecurityLevel::L3.parameters()?;
+//! assert!(parameters.level() >= SecurityLevel::L3);
+//! ```
+
+use crate::advisor::{KemChoice, SignatureChoice};
+use crate::errors::{MisuseError, Result};
+
+/// NIST post-quantum security category.
+///
+/// Levels are ordered: `L1 < L3 < L5`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum SecurityLevel {
+    /// Category 1: at least as hard to break as AES-128 key search.
+    L1,
+    /// Category 3: at least as hard to break as AES-192 key search.
+    L3,
+    /// Category 5: at least as hard to break as AES-256 key search.
+    L5,
+}
+
+impl SecurityLevel {
+    /// Every level, weakest first.
+    pub const ALL: &[SecurityLevel] = &[SecurityLevel::L1, SecurityLevel::L3, SecurityLevel::L5];
+
+    /// The NIST category number: 1, 3 or 5.
+    pub const fn category(self) -> u8 {
+        match self {
+            SecurityLevel::L1 => 1,
+            SecurityLevel::L3 => 3,
+            SecurityLevel::L5 => 5,
+        }
+    }
+
+    /// The cheapest parameter set whose every component reaches this
+    /// level.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidParameterSet`: If the crate has no KEM,
+    ///   signature or hash at this level
+    pub fn parameters(self) -> Result<ParameterSet> {
+        let kem = [KemChoice::X25519MlKem768]
+            .into_iter()
+            .find(|kem| kem.security_level() >= self);
+        let signature = [SignatureChoice::Ed25519MlDsa87]
+            .into_iter()
+            .find(|signature| signature.security_level() >= self);
+        let hash = HashChoice::ALL
+            .iter()
+            .copied()
+            .find(|hash| hash.security_level() >= self);
+        match (kem, signature, hash) {
+            (Some(kem), Some(signature), Some(hash)) => Ok(ParameterSet {
+                kem,
+                signature,
+                hash,
+            }),
+            _ => Err(MisuseError::InvalidParameterSet.into()),
+        }
+    }
+}
+
+/// Hash choices, for digests, prehashing and key derivation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum HashChoice {
+    /// [`crate::hash::Sha3_256`].
+    Sha3_256,
+    /// [`crate::hash::Sha3_384`].
+    Sha3_384,
+    /// [`crate::hash::Sha3_512`].
+    Sha3_512,
+}
+
+impl HashChoice {
+    /// Every choice, shortest digest first.
+    pub const ALL: &[HashChoice] = &[
+        HashChoice::Sha3_256,
+        HashChoice::Sha3_384,
+        HashChoice::Sha3_512,
+    ];
+
+    /// Digest size in bytes.
+    pub const fn output_size(self) -> usize {
+        match self {
+            HashChoice::Sha3_256 => 32,
+            HashChoice::Sha3_384 => 48,
+            HashChoice::Sha3_512 => 64,
+        }
+    }
+
+    /// Highest level whose collision-resistance bound the digest meets.
+    pub const fn security_level(self) -> SecurityLevel {
+        match self {
+            HashChoice::Sha3_256 => SecurityLevel::L1,
+            HashChoice::Sha3_384 => SecurityLevel::L3,
+            HashChoice::Sha3_512 => SecurityLevel::L5,
+        }
+    }
+}
+
+/// A consistent combination of parameter sets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct ParameterSet {
+    /// Key encapsulation.
+    pub kem: KemChoice,
+    /// Signatures.
+    pub signature: SignatureChoice,
+    /// Hashing.
+    pub hash: HashChoice,
+}
+
+impl ParameterSet {
+    /// The level of the weakest component.
+    pub fn level(&self) -> SecurityLevel {
+        self.kem
+            .security_level()
+            .min(self.signature.security_level())
+            .min(self.hash.security_level())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parameters_reach_the_requested_level() {
+        let l1 = SecurityLevel::L1.parameters().unwrap();
+        assert_eq!(l1.hash, HashChoice::Sha3_256);
+        assert_eq!(l1.level(), SecurityLevel::L1);
+
+        let l3 = SecurityLevel::L3.parameters().unwrap();
+        assert_eq!(l3.kem, KemChoice::X25519MlKem768);
+        assert_eq!(l3.signature, SignatureChoice::Ed25519MlDsa87);
+        assert_eq!(l3.hash, HashChoice::Sha3_384);
+        assert_eq!(l3.level(), SecurityLevel::L3);
+
+        // No ML-KEM-1024: rejected, not downgraded
+        let error = SecurityLevel::L5.parameters().unwrap_err();
+        assert_eq!(error.misuse(), Some(MisuseError::InvalidParameterSet));
+    }
+
+    #[test]
+    fn levels_are_ordered() {
+        assert!(SecurityLevel::ALL.windows(2).all(|pair| pair[0] < pair[1]));
+        let categories: Vec<u8> = SecurityLevel::ALL.iter().map(|l| l.category()).collect();
+        assert_eq!(categories, [1, 3, 5]);
+        assert!(
+            HashChoice::ALL
+                .windows(2)
+                .all(|pair| pair[0].output_size() < pair[1].output_size())
+        );
+    }
+}
@@ -0,0 +1,333 @@
+//! Message authentication codes.
+//!
+//! # Purpose
+//!
+//! Keyed integrity tags for data that is authenticated but not encrypted,
+//! and keyed derivations where a MAC is the specified primitive.
+//!
+//! # Algorithms
+//!
+//! - [`Kmac128`], [`Kmac256`]: KMAC, SP 800-185. Keccak-based, so it
+//!   shares its permutation with ML-KEM and ML-DSA
+//! - [`HmacSha256`], [`HmacSha384`], [`HmacSha512`], [`HmacSha3_256`]:
+//!   HMAC, RFC 2104, for protocols and formats that specify it
+//!
+//! All of them implement [`Mac`](crate::internal::traits::Mac) for code
+//! generic over the algorithm.
+//!
+//! # Security
+//!
+//! - Keys shorter than the security level are rejected
+//! - HMAC keys shorter than 32 bytes are rejected too, even though
+//!   RFC 2104 allows them
+//! - The tag length is bound into KMAC's output: a truncated tag is not a
+//!   valid shorter tag, so fix the length per protocol
+//! - Use a distinct customization string per purpose to separate domains
+//!   under the same key
+//! - Verify with `verify` (for example [`Kmac256::verify`]) rather than
+//!   comparing tags by hand; it runs in constant time
+//!
+//! # Example
+//!
+//! ```ignore
+//! use citadel::mac::Kmac256;
+//!
+//! let tag = Kmac256::mac(&key, b"audit-log", record)?;
+//!
+//! let mut kmac = Kmac256::new(&key, b"audit-log")?;
+//! kmac.update(record);
+//! kmac.verify(&tag)?;
+//! ```
+
+use core::fmt;
+
+use crate::algorithms::classical::hmac::Hmac;
+use crate::algorithms::classical::keccak::Sha3;
+use crate::algorithms::classical::kmac::Kmac;
+use crate::algorithms::classical::sha256::Sha256;
+use crate::algorithms::classical::sha384::Sha384;
+use crate::algorithms::classical::sha512::Sha512;
+use crate::errors::{MisuseError, Result};
+use crate::internal::traits::Mac;
+
+/// Shortest tag [`Kmac128::finalize_into`] and [`Kmac256::finalize_into`]
+/// accept, in bytes (SP 800-185, section 8.4.2).
+pub const MIN_TAG_SIZE: usize = 4;
+
+macro_rules! kmac {
+    ($name:ident, $constructor:path, $key:literal, $tag:literal, $doc:literal) => {
+        #[doc = $doc]
+        pub struct $name {
+            inner: Kmac,
+        }
+
+        impl $name {
+            /// Shortest accepted key, in bytes.
+            pub const MIN_KEY_SIZE: usize = $key;
+            /// Default tag size in bytes.
+            pub const TAG_SIZE: usize = $tag;
+
+            /// A MAC keyed with `key` and domain-separated by
+            /// `customization` (which may be empty).
+            ///
+            /// # Errors
+            ///
+            /// - `MisuseError::InvalidKeyLength`: If `key` is shorter than
+            ///   [`MIN_KEY_SIZE`](Self::MIN_KEY_SIZE)
+            pub fn new(key: &[u8], customization: &[u8]) -> Result<Self> {
+                if key.len() < Self::MIN_KEY_SIZE {
+                    return Err(MisuseError::InvalidKeyLength.into());
+                }
+                Ok(Self {
+                    inner: $constructor(key, customization),
+                })
+            }
+
+            /// Absorb more input.
+            pub fn update(&mut self, data: &[u8]) {
+                self.inner.update(data);
+            }
+
+            /// Finish and return a [`TAG_SIZE`](Self::TAG_SIZE)-byte tag.
+            pub fn finalize(self) -> [u8; $tag] {
+                let mut tag = [0u8; $tag];
+                self.inner.finalize_into(&mut tag);
+                tag
+            }
+
+            /// Finish and fill `tag`, whose length selects the tag size.
+            ///
+            /// # Errors
+            ///
+            /// - `MisuseError::BufferTooSmall`: If `tag` is shorter than
+            ///   [`MIN_TAG_SIZE`]
+            pub fn finalize_into(self, tag: &mut [u8]) -> Result<()> {
+                if tag.len() < MIN_TAG_SIZE {
+                    return Err(MisuseError::BufferTooSmall.into());
+                }
+                self.inner.finalize_into(tag);
+                Ok(())
+            }
+
+            /// Finish and compare against `tag` in constant time.
+            ///
+            /// # Errors
+            ///
+            /// - `CryptoError::VerificationFailed`: If the tags differ
+            pub fn verify(self, tag: &[u8; $tag]) -> Result<()> {
+                Mac::verify(self, tag)
+            }
+
+            /// Compute the default-size tag of `data` in one call.
+            ///
+            /// # Errors
+            ///
+            /// - `MisuseError::InvalidKeyLength`: If `key` is shorter than
+            ///   [`MIN_KEY_SIZE`](Self::MIN_KEY_SIZE)
+            pub fn mac(key: &[u8], customization: &[u8], data: &[u8]) -> Result<[u8; $tag]> {
+                let mut kmac = Self::new(key, customization)?;
+                kmac.update(data);
+                Ok(kmac.finalize())
+            }
+        }
+
+        impl Mac<$tag> for $name {
+            fn update(&mut self, data: &[u8]) {
+                self.inner.update(data);
+            }
+
+            fn finalize(self) -> [u8; $tag] {
+                $name::finalize(self)
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(concat!(stringify!($name), " { <redacted> }"))
+            }
+        }
+    };
+}
+
+kmac!(Kmac128, Kmac::kmac128, 16, 32, "KMAC128 (SP 800-185).");
+kmac!(Kmac256, Kmac::kmac256, 32, 64, "KMAC256 (SP 800-185).");
+
+macro_rules! hmac {
+    ($name:ident, $hash:ty, $tag:literal, $doc:literal) => {
+        #[doc = $doc]
+        pub struct $name {
+            inner: Hmac<$hash, $tag>,
+        }
+
+        impl $name {
+            /// Shortest accepted key, in bytes.
+            pub const MIN_KEY_SIZE: usize = 32;
+            /// Tag size in bytes.
+            pub const TAG_SIZE: usize = $tag;
+
+            /// A MAC keyed with `key`.
+            ///
+            /// # Errors
+            ///
+            /// - `MisuseError::InvalidKeyLength`: If `key` is shorter than
+            ///   [`MIN_KEY_SIZE`](Self::MIN_KEY_SIZE)
+            pub fn new(key: &[u8]) -> Result<Self> {
+                if key.len() < Self::MIN_KEY_SIZE {
+                    return Err(MisuseError::InvalidKeyLength.into());
+                }
+                Ok(Self {
+                    inner: Hmac::new(key),
+                })
+            }
+
+            /// Absorb more input.
+            pub fn update(&mut self, data: &[u8]) {
+                self.inner.update(data);
+            }
+
+            /// Finish and return the tag.
+            pub fn finalize(self) -> [u8; $tag] {
+                self.inner.finalize()
+            }
+
+            /// Finish and compare against `tag` in constant time.
+            ///
+            /// # Errors
+            ///
+            /// - `CryptoError::VerificationFailed`: If the tags differ
+            pub fn verify(self, tag: &[u8; $tag]) -> Result<()> {
+                Mac::verify(self, tag)
+            }
+
+            /// Compute the tag of `data` in one call.
+            ///
+            /// # Errors
+            ///
+            /// - `MisuseError::InvalidKeyLength`: If `key` is shorter than
+            ///   [`MIN_KEY_SIZE`](Self::MIN_KEY_SIZE)
+            pub fn mac(key: &[u8], data: &[u8]) -> Result<[u8; $tag]> {
+                let mut hmac = Self::new(key)?;
+                hmac.update(data);
+                Ok(hmac.finalize())
+            }
+        }
+
+        impl Mac<$tag> for $name {
+            fn update(&mut self, data: &[u8]) {
+                self.inner.update(data);
+            }
+
+            fn finalize(self) -> [u8; $tag] {
+                self.inner.finalize()
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(concat!(stringify!($name), " { <redacted> }"))
+            }
+        }
+    };
+}
+
+hmac!(HmacSha256, Sha256, 32, "HMAC-SHA-256 (RFC 2104, RFC 4231).");
+hmac!(HmacSha384, Sha384, 48, "HMAC-SHA-384 (RFC 2104, RFC 4231).");
+hmac!(HmacSha512, Sha512, 64, "HMAC-SHA-512 (RFC 2104, RFC 4231).");
+hmac!(HmacSha3_256, Sha3<32>, 32, "HMAC-SHA3-256 (FIPS 198-1).");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::CryptoError;
+
+    const KEY: [u8; 32] = [0x42; 32];
+
+    #[test]
+    fn streaming_matches_one_shot() {
+        let data: Vec<u8> = (0..1000u32).map(|i| i as u8).collect();
+        let mut kmac = Kmac256::new(&KEY, b"test").unwrap();
+        for chunk in data.chunks(13) {
+            kmac.update(chunk);
+        }
+        let tag = Kmac256::mac(&KEY, b"test", &data).unwrap();
+        assert_eq!(kmac.finalize(), tag);
+
+        let mut kmac = Kmac256::new(&KEY, b"test").unwrap();
+        kmac.update(&data);
+        assert!(kmac.verify(&tag).is_ok());
+    }
+
+    #[test]
+    fn rejects_forgeries_and_weak_parameters() {
+        let tag = Kmac128::mac(&KEY, b"", b"message").unwrap();
+        let mut forged = tag;
+        forged[31] ^= 1;
+        let mut kmac = Kmac128::new(&KEY, b"").unwrap();
+        kmac.update(b"message");
+        let error = kmac.verify(&forged).unwrap_err();
+        assert_eq!(error.crypto(), Some(CryptoError::VerificationFailed));
+
+        // Customization and tag length both change the output
+        assert_ne!(Kmac128::mac(&KEY, b"other", b"message").unwrap(), tag);
+        let mut short = [0u8; 16];
+        let mut kmac = Kmac128::new(&KEY, b"").unwrap();
+        kmac.update(b"message");
+        kmac.finalize_into(&mut short).unwrap();
+        assert_ne!(short, tag[..16]);
+
+        let error = Kmac256::new(&KEY[..31], b"").unwrap_err();
+        assert_eq!(error.misuse(), Some(MisuseError::InvalidKeyLength));
+        let error = Kmac128::new(&KEY, b"")
+            .unwrap()
+            .finalize_into(&mut [0u8; 3])
+            .unwrap_err();
+        assert_eq!(error.misuse(), Some(MisuseError::BufferTooSmall));
+        assert_eq!(
+            format!("{:?}", Kmac128::new(&KEY, b"").unwrap()),
+            "Kmac128 { <redacted> }"
+        );
+    }
+
+    #[test]
+    fn hmac_vectors() {
+        // RFC 4231 test case 7 (131-byte key)
+        let key = [0xaa; 131];
+        let data: &[u8] = b"This is a test using a larger than block-size key and a larger \
+            than block-size data. The key needs to be hashed before being used by the HMAC \
+            algorithm.";
+        let hex = |bytes: &[u8]| -> String { bytes.iter().map(|b| format!("{:02x}", b)).collect() };
+        assert_eq!(
+            hex(&HmacSha256::mac(&key, data).unwrap()),
+            "9b09ffa71b942fcb27635fbcd5b0e944bfdc63644f0713938a7f51535c3a35e2"
+        );
+        assert_eq!(
+            hex(&HmacSha512::mac(&key, data).unwrap()),
+            "e37b6a775dc87dbaa4dfa9f96e5e3ffddebd71f8867289865df5a32d20cdc944\
+             b6022cac3c4982b10d5eeb55c3e4de15134676fb6de0446065c97440fa8c6a58"
+        );
+    }
+
+    fn verify_generic<M: Mac<32>>(mut mac: M, data: &[u8], tag: &[u8; 32]) -> Result<()> {
+        mac.update(data);
+        mac.verify(tag)
+    }
+
+    #[test]
+    fn hmac_verifies_through_trait() {
+        let tag = HmacSha3_256::mac(&KEY, b"message").unwrap();
+        assert!(verify_generic(HmacSha3_256::new(&KEY).unwrap(), b"message", &tag).is_ok());
+        let error = verify_generic(HmacSha3_256::new(&KEY).unwrap(), b"massage", &tag).unwrap_err();
+        assert_eq!(error.crypto(), Some(CryptoError::VerificationFailed));
+
+        // KMAC128 shares the interface
+        let tag = Kmac128::mac(&KEY, b"", b"message").unwrap();
+        assert!(verify_generic(Kmac128::new(&KEY, b"").unwrap(), b"message", &tag).is_ok());
+
+        let error = HmacSha384::new(&KEY[..31]).unwrap_err();
+        assert_eq!(error.misuse(), Some(MisuseError::InvalidKeyLength));
+        assert_eq!(
+            format!("{:?}", HmacSha256::new(&KEY).unwrap()),
+            "HmacSha256 { <redacted> }"
+        );
+    }
+}
@@ -0,0 +1,32 @@
+//! Public, algorithm-specific APIs.
+//!
+//! Each submodule exposes concrete types over the implementations in
+//! `algorithms`. Re-exported at the crate root.
+
+pub mod advisor;
+pub mod algorithm;
+pub mod aead;
+pub mod beacon;
+#[cfg(feature = "conformance")]
+pub mod conformance;
+pub mod dek;
+pub mod drbg;
+pub mod dsse;
+pub mod dynamic;
+pub mod envelope;
+#[cfg(feature = "std")]
+pub mod file;
+pub mod harden;
+pub mod hash;
+pub mod hpke;
+pub mod kdf;
+pub mod kem;
+pub mod keys;
+pub mod kex;
+pub mod level;
+pub mod mac;
+pub mod sign;
+pub mod signcrypt;
+pub mod stream;
+#[cfg(all(feature = "mlkem", feature = "aes-gcm-siv"))]
+pub mod suite;
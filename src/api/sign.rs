@@ -0,0 +1,1703 @@
+//! Digital signatures.
+//!
+//! # Purpose
+//!
+//! Signature algorithms, and signing interfaces that do not depend on where
+//! the secret key lives. `AsyncSigner` lets the signing side be fulfilled
+//! by a remote service or HSM: the message is hashed locally and only the
+//! digest crosses the network.
+//!
+//! # Algorithms
+//!
+//! - [`Ed25519`]: Edwards-curve signatures, RFC 8032 (classical)
+//! - [`MlDsa87`]: Module-lattice signatures, FIPS 204 (post-quantum,
+//!   feature `mldsa`)
+//!
+//! Both implement [`SignatureVerifier`] and, with the `signing` feature,
+//! [`SignatureScheme`](crate::internal::traits::SignatureScheme) over raw
+//! byte arrays, so they compose with
+//! [`HybridSigner`](crate::hybrid::HybridSigner).
+//!
+//! Verification takes an owned verifying key or a borrowed
+//! [`PublicKeyRef`], which verifies against a key where it is stored, such
+//! as a memory-mapped trust store, without copying it.
+//!
+//! Key generation, signing, the signing-key types and `AsyncSigner` need
+//! the `signing` feature (on by default).
+//!
+//! # Large Messages
+//!
+//! Messages too large to hold in memory are hashed incrementally with a
+//! [`Prehasher`] and the resulting [`Digest`] is signed with
+//! `sign_prehashed`, which uses each algorithm's standardized pre-hash
+//! variant: Ed25519ph (RFC 8032) over a SHA-512 digest, or HashML-DSA
+//! (FIPS 204). The signature is detached: the verifier hashes its copy of
+//! the message the same way and calls `verify_prehashed`. It does not
+//! verify against the message with plain `verify`.
+//!
+//! # Security
+//!
+//! - Signing keys are zeroized on drop
+//! - Verifying keys are validated on construction
+//! - The remote signer never sees the message, only a fixed-size digest
+//! - Signatures returned by a signer are length-checked before use; always
+//!   verify them against the expected public key before publishing
+//!
+//! # Example
+//!
+//! ```ignore
+//! use citadel::sign::{AsyncSigner, RemoteSigner};
+//!
+//! let signer = RemoteSigner::new(HsmClient::connect(endpoint).await?);
+//! let signature = signer.sign(manifest_bytes).await?;
+//! ```
+
+use core::fmt;
+#[cfg(feature = "signing")]
+use core::future::Future;
+use std::io;
+
+use crate::algorithms::classical::ed25519 as ed;
+use crate::algorithms::classical::keccak::{self, Sponge, sha3_512};
+use crate::algorithms::classical::sha256::Sha256;
+use crate::algorithms::classical::sha512::Sha512;
+#[cfg(feature = "mldsa")]
+use crate::algorithms::pq::ml_dsa_87 as dsa;
+use crate::context;
+#[cfg(feature = "signing")]
+use crate::context::EntropySource;
+#[cfg(feature = "mldsa")]
+use crate::encoding::{ParseBudget, jcs};
+use crate::errors::{CryptoError, MisuseError, Result};
+#[cfg(feature = "signing")]
+use crate::internal::entropy::ContextEntropy;
+#[cfg(feature = "signing")]
+use crate::internal::traits::SignatureScheme;
+use crate::internal::traits::SignatureVerifier;
+#[cfg(feature = "signing")]
+use crate::memory::SensitiveBytes;
+
+/// Ed25519 (RFC 8032).
+///
+/// Namespace for key generation, signing and verification. Signatures are
+/// deterministic. Verification is cofactorless and rejects non-canonical
+/// encodings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ed25519;
+
+impl Ed25519 {
+    /// Verifying key size in bytes.
+    pub const PUBLIC_KEY_SIZE: usize = ed::PUBLIC_KEY_SIZE;
+    /// Signing key (seed) size in bytes.
+    pub const SECRET_KEY_SIZE: usize = ed::SECRET_KEY_SIZE;
+    /// Signature size in bytes.
+    pub const SIGNATURE_SIZE: usize = ed::SIGNATURE_SIZE;
+
+    /// Generate a new keypair from system randomness.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+    /// - `CryptoError::RandomnessFailure`: If the system RNG fails a health test
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    #[cfg(feature = "signing")]
+    pub fn generate_keypair() -> Result<(Ed25519VerifyingKey, Ed25519SigningKey)> {
+        Self::generate_keypair_with_rng(&ContextEntropy)
+    }
+
+    /// Generate a new keypair from `rng`.
+    ///
+    /// # Security
+    ///
+    /// `rng` MUST be a CSPRNG of at least 256-bit security, e.g. a
+    /// hardware TRNG or an approved DRBG. Deterministic sources are for
+    /// known-answer tests only.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If `rng` fails
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    #[cfg(feature = "signing")]
+    pub fn generate_keypair_with_rng(
+        rng: &dyn EntropySource,
+    ) -> Result<(Ed25519VerifyingKey, Ed25519SigningKey)> {
+        context::checkpoint()?;
+        let mut seed = SensitiveBytes::<{ ed::SECRET_KEY_SIZE }>::zeroed();
+        rng.fill(seed.as_bytes_mut())?;
+        let signing_key = Ed25519SigningKey(seed);
+        Ok((signing_key.verifying_key(), signing_key))
+    }
+
+    /// Sign `message`.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    #[cfg(feature = "signing")]
+    pub fn sign(signing_key: &Ed25519SigningKey, message: &[u8]) -> Result<Ed25519Signature> {
+        context::checkpoint()?;
+        Ok(Ed25519Signature(ed::Ed25519::sign_with(
+            signing_key.0.as_bytes(),
+            message,
+        )))
+    }
+
+    /// Verify `signature` over `message`.
+    ///
+    /// `verifying_key` is an owned [`Ed25519VerifyingKey`] or a borrowed
+    /// [`PublicKeyRef`].
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::VerificationFailed`: If the signature is invalid
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    pub fn verify<'k>(
+        verifying_key: impl Into<PublicKeyRef<'k, Ed25519>>,
+        message: &[u8],
+        signature: &Ed25519Signature,
+    ) -> Result<()> {
+        context::checkpoint()?;
+        let verifying_key = verifying_key.into();
+        if !ed::Ed25519::verify_with(verifying_key.as_bytes(), message, &signature.0) {
+            return Err(CryptoError::VerificationFailed.into());
+        }
+        Ok(())
+    }
+
+    /// Ed25519ph (RFC 8032 §5.1): sign a message by its [`Prehash::Sha512`]
+    /// [`Digest`], e.g. from a [`Prehasher`], with an empty context.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidAlgorithmIdentifier`: If `digest` is not a
+    ///   SHA-512 digest
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    #[cfg(feature = "signing")]
+    pub fn sign_prehashed(
+        signing_key: &Ed25519SigningKey,
+        digest: &Digest,
+    ) -> Result<Ed25519Signature> {
+        context::checkpoint()?;
+        Ok(Ed25519Signature(ed::Ed25519::sign_prehashed(
+            signing_key.0.as_bytes(),
+            ed25519ph_digest(digest)?,
+        )))
+    }
+
+    /// Verify an Ed25519ph `signature` made with
+    /// [`sign_prehashed`](Self::sign_prehashed) against the verifier's own
+    /// SHA-512 `digest` of the message.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::VerificationFailed`: If the signature is invalid
+    /// - `MisuseError::InvalidAlgorithmIdentifier`: If `digest` is not a
+    ///   SHA-512 digest
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    pub fn verify_prehashed<'k>(
+        verifying_key: impl Into<PublicKeyRef<'k, Ed25519>>,
+        digest: &Digest,
+        signature: &Ed25519Signature,
+    ) -> Result<()> {
+        context::checkpoint()?;
+        let verifying_key = verifying_key.into();
+        let digest = ed25519ph_digest(digest)?;
+        if !ed::Ed25519::verify_prehashed(verifying_key.as_bytes(), digest, &signature.0) {
+            return Err(CryptoError::VerificationFailed.into());
+        }
+        Ok(())
+    }
+}
+
+/// The digest Ed25519ph signs: RFC 8032 fixes the pre-hash to SHA-512.
+fn ed25519ph_digest(digest: &Digest) -> Result<&[u8; 64]> {
+    if digest.algorithm() != Prehash::Sha512 {
+        return Err(MisuseError::InvalidAlgorithmIdentifier.into());
+    }
+    Ok(&digest.bytes)
+}
+
+/// Ed25519 verifying (public) key.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Ed25519VerifyingKey([u8; ed::PUBLIC_KEY_SIZE]);
+
+impl Ed25519VerifyingKey {
+    /// Parse and validate an encoded verifying key.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidPublicKeyLength`: If `bytes` is not
+    ///   [`Ed25519::PUBLIC_KEY_SIZE`] bytes
+    /// - `MisuseError::InvalidEncoding`: If `bytes` is not a canonical curve
+    ///   point encoding
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let key: [u8; ed::PUBLIC_KEY_SIZE] = bytes
+            .try_into()
+            .map_err(|_| MisuseError::InvalidPublicKeyLength)?;
+        if !ed::Ed25519::is_valid_public_key(&key) {
+            return Err(MisuseError::InvalidEncoding.into());
+        }
+        Ok(Self(key))
+    }
+
+    /// Encoded verifying key.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8; ed::PUBLIC_KEY_SIZE] {
+        &self.0
+    }
+
+    /// Borrowed view of this key.
+    #[inline]
+    pub fn as_key_ref(&self) -> PublicKeyRef<'_, Ed25519> {
+        PublicKeyRef { key: &self.0 }
+    }
+}
+
+impl<'k> From<&'k Ed25519VerifyingKey> for PublicKeyRef<'k, Ed25519> {
+    #[inline]
+    fn from(key: &'k Ed25519VerifyingKey) -> Self {
+        key.as_key_ref()
+    }
+}
+
+impl fmt::Debug for Ed25519VerifyingKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Ed25519VerifyingKey { .. }")
+    }
+}
+
+/// Ed25519 signing key (32-byte seed), zeroized on drop.
+#[cfg(feature = "signing")]
+pub struct Ed25519SigningKey(SensitiveBytes<{ ed::SECRET_KEY_SIZE }>);
+
+#[cfg(feature = "signing")]
+crate::memory::never_serialize!([] Ed25519SigningKey);
+
+#[cfg(feature = "signing")]
+impl Ed25519SigningKey {
+    /// Wrap an RFC 8032 private key (32-byte seed).
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidSecretKeyLength`: If `bytes` is not
+    ///   [`Ed25519::SECRET_KEY_SIZE`] bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != ed::SECRET_KEY_SIZE {
+            return Err(MisuseError::InvalidSecretKeyLength.into());
+        }
+        let mut key = SensitiveBytes::zeroed();
+        key.as_bytes_mut().copy_from_slice(bytes);
+        Ok(Self(key))
+    }
+
+    /// Encoded signing key (the seed).
+    ///
+    /// # Security
+    ///
+    /// Do not log, serialize unencrypted, or store the returned bytes.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8; ed::SECRET_KEY_SIZE] {
+        self.0.as_bytes()
+    }
+
+    /// The verifying key for this signing key.
+    pub fn verifying_key(&self) -> Ed25519VerifyingKey {
+        Ed25519VerifyingKey(ed::Ed25519::public_key(self.0.as_bytes()))
+    }
+}
+
+#[cfg(feature = "signing")]
+impl fmt::Debug for Ed25519SigningKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Ed25519SigningKey { <redacted> }")
+    }
+}
+
+/// Ed25519 signature.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Ed25519Signature([u8; ed::SIGNATURE_SIZE]);
+
+impl Ed25519Signature {
+    /// Wrap an encoded signature.
+    ///
+    /// Structural checks happen during verification.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidSignatureLength`: If `bytes` is not
+    ///   [`Ed25519::SIGNATURE_SIZE`] bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let signature = bytes
+            .try_into()
+            .map_err(|_| MisuseError::InvalidSignatureLength)?;
+        Ok(Self(signature))
+    }
+
+    /// Encoded signature.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8; ed::SIGNATURE_SIZE] {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Ed25519Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Ed25519Signature { .. }")
+    }
+}
+
+impl SignatureVerifier<{ ed::PUBLIC_KEY_SIZE }, { ed::SIGNATURE_SIZE }> for Ed25519 {
+    fn verify(
+        &self,
+        public_key: &[u8; ed::PUBLIC_KEY_SIZE],
+        message: &[u8],
+        signature: &[u8; ed::SIGNATURE_SIZE],
+    ) -> Result<()> {
+        context::checkpoint()?;
+        ed::Ed25519.verify(public_key, message, signature)
+    }
+}
+
+#[cfg(feature = "signing")]
+impl SignatureScheme<{ ed::PUBLIC_KEY_SIZE }, { ed::SECRET_KEY_SIZE }, { ed::SIGNATURE_SIZE }>
+    for Ed25519
+{
+    fn generate_keypair(&self) -> Result<([u8; ed::PUBLIC_KEY_SIZE], [u8; ed::SECRET_KEY_SIZE])> {
+        context::checkpoint()?;
+        ed::Ed25519.generate_keypair()
+    }
+
+    fn sign(
+        &self,
+        secret_key: &[u8; ed::SECRET_KEY_SIZE],
+        message: &[u8],
+    ) -> Result<[u8; ed::SIGNATURE_SIZE]> {
+        context::checkpoint()?;
+        ed::Ed25519.sign(secret_key, message)
+    }
+}
+
+/// ML-DSA-87 (FIPS 204, security category 5).
+///
+/// Namespace for key generation, signing and verification. Signing is
+/// hedged: fresh randomness is mixed with the key and message, so signing
+/// the same message twice gives different signatures. Signatures use the
+/// pure ML-DSA encoding with an empty context string, unless a context is
+/// given to [`sign_with_context`](Self::sign_with_context); the pre-hash
+/// variant HashML-DSA is [`hash_sign`](Self::hash_sign).
+#[cfg(feature = "mldsa")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MlDsa87;
+
+#[cfg(feature = "mldsa")]
+impl MlDsa87 {
+    /// Verifying key size in bytes.
+    pub const PUBLIC_KEY_SIZE: usize = dsa::PUBLIC_KEY_SIZE;
+    /// Signing key size in bytes.
+    pub const SECRET_KEY_SIZE: usize = dsa::SECRET_KEY_SIZE;
+    /// Signature size in bytes.
+    pub const SIGNATURE_SIZE: usize = dsa::SIGNATURE_SIZE;
+    /// Key generation seed size in bytes.
+    pub const SEED_SIZE: usize = dsa::SEED_SIZE;
+
+    /// Generate a new keypair from system randomness.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+    /// - `CryptoError::RandomnessFailure`: If the system RNG fails a health test
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    #[cfg(feature = "signing")]
+    pub fn generate_keypair() -> Result<(MlDsa87VerifyingKey, MlDsa87SigningKey)> {
+        Self::generate_keypair_with_rng(&ContextEntropy)
+    }
+
+    /// Generate a new keypair from `rng`.
+    ///
+    /// # Security
+    ///
+    /// `rng` MUST be a CSPRNG of at least 256-bit security, e.g. a
+    /// hardware TRNG or an approved DRBG. Deterministic sources are for
+    /// known-answer tests only.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If `rng` fails
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    #[cfg(feature = "signing")]
+    pub fn generate_keypair_with_rng(
+        rng: &dyn EntropySource,
+    ) -> Result<(MlDsa87VerifyingKey, MlDsa87SigningKey)> {
+        context::checkpoint()?;
+        let mut seed = SensitiveBytes::<{ dsa::SEED_SIZE }>::zeroed();
+        rng.fill(seed.as_bytes_mut())?;
+        Ok(Self::keypair_from_seed(seed.as_bytes()))
+    }
+
+    /// Generate a fresh 32-byte key generation seed from system randomness.
+    ///
+    /// Store the seed and rebuild the keypair with
+    /// [`keypair_from_seed`](Self::keypair_from_seed) on load.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+    /// - `CryptoError::RandomnessFailure`: If the system RNG fails a health test
+    #[cfg(feature = "signing")]
+    pub fn generate_seed() -> Result<SensitiveBytes<{ dsa::SEED_SIZE }>> {
+        let mut seed = SensitiveBytes::zeroed();
+        ContextEntropy.fill(seed.as_bytes_mut())?;
+        Ok(seed)
+    }
+
+    /// Derive a keypair deterministically from a 32-byte seed (`xi` in
+    /// FIPS 204).
+    ///
+    /// Storing the seed instead of the 4896-byte signing key is a compact
+    /// and standard-compliant alternative.
+    ///
+    /// # Security
+    ///
+    /// The seed is as sensitive as the signing key and MUST come from a
+    /// cryptographically secure source.
+    #[cfg(feature = "signing")]
+    pub fn keypair_from_seed(
+        seed: &[u8; dsa::SEED_SIZE],
+    ) -> (MlDsa87VerifyingKey, MlDsa87SigningKey) {
+        let (public_key, secret_key) = dsa::MlDsa87::keypair_from_seed(seed);
+        (
+            MlDsa87VerifyingKey(public_key),
+            MlDsa87SigningKey(secret_key),
+        )
+    }
+
+    /// Sign `message`.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+    /// - `CryptoError::RandomnessFailure`: If the system RNG fails a health test
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    #[cfg(feature = "signing")]
+    pub fn sign(signing_key: &MlDsa87SigningKey, message: &[u8]) -> Result<MlDsa87Signature> {
+        Self::sign_with_rng(signing_key, message, &ContextEntropy)
+    }
+
+    /// Sign `message`, drawing the hedging randomness from `rng`.
+    ///
+    /// A source that always returns the same bytes gives reproducible
+    /// signatures; that is still secure, but forfeits the fault-attack
+    /// protection of hedged signing.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If `rng` fails
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    #[cfg(feature = "signing")]
+    pub fn sign_with_rng(
+        signing_key: &MlDsa87SigningKey,
+        message: &[u8],
+        rng: &dyn EntropySource,
+    ) -> Result<MlDsa87Signature> {
+        Self::sign_hedged(signing_key, dsa::Message::Pure(message), &[], rng)
+    }
+
+    /// Sign `message` under the context string `context`, which a verifier
+    /// must pass to [`verify_with_context`](Self::verify_with_context).
+    ///
+    /// Protocols use distinct contexts to domain-separate signatures made
+    /// with the same key, so one made for one purpose cannot be replayed
+    /// for another.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::ContextTooLong`: If `context` exceeds 255 bytes
+    /// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+    /// - `CryptoError::RandomnessFailure`: If the system RNG fails a health test
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    #[cfg(feature = "signing")]
+    pub fn sign_with_context(
+        signing_key: &MlDsa87SigningKey,
+        message: &[u8],
+        context: &[u8],
+    ) -> Result<MlDsa87Signature> {
+        Self::sign_hedged(
+            signing_key,
+            dsa::Message::Pure(message),
+            context,
+            &ContextEntropy,
+        )
+    }
+
+    /// HashML-DSA (FIPS 204 §5.4): sign a message by its [`Digest`] under
+    /// the context string `context`. The signature binds the hash function,
+    /// and verifies only with [`hash_verify`](Self::hash_verify).
+    ///
+    /// Prefer [`Prehash::Sha3_512`]: a 256-bit digest caps the collision
+    /// resistance below ML-DSA-87's security category.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::ContextTooLong`: If `context` exceeds 255 bytes
+    /// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+    /// - `CryptoError::RandomnessFailure`: If the system RNG fails a health test
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    #[cfg(feature = "signing")]
+    pub fn hash_sign(
+        signing_key: &MlDsa87SigningKey,
+        digest: &Digest,
+        context: &[u8],
+    ) -> Result<MlDsa87Signature> {
+        Self::sign_hedged(
+            signing_key,
+            hash_ml_dsa_message(digest),
+            context,
+            &ContextEntropy,
+        )
+    }
+
+    #[cfg(feature = "signing")]
+    fn sign_hedged(
+        signing_key: &MlDsa87SigningKey,
+        message: dsa::Message<'_>,
+        context: &[u8],
+        rng: &dyn EntropySource,
+    ) -> Result<MlDsa87Signature> {
+        context::checkpoint()?;
+        let mut rnd = SensitiveBytes::<32>::zeroed();
+        rng.fill(rnd.as_bytes_mut())?;
+        let signature =
+            dsa::MlDsa87::sign_message(signing_key.0.as_bytes(), message, context, rnd.as_bytes())?;
+        Ok(MlDsa87Signature(Box::new(signature)))
+    }
+
+    /// Sign `message` under `context` with the fixed randomness `rnd`, for
+    /// known-answer tests. An all-zero `rnd` gives deterministic ML-DSA.
+    ///
+    /// Compiled only with the `test-vectors` feature.
+    ///
+    /// # Security
+    ///
+    /// Fixed randomness gives up the fault-attack protection of hedged
+    /// signing. Never call this outside test harnesses.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::ContextTooLong`: If `context` exceeds 255 bytes
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    #[cfg(all(feature = "test-vectors", feature = "signing"))]
+    pub fn sign_derand(
+        signing_key: &MlDsa87SigningKey,
+        message: &[u8],
+        context: &[u8],
+        rnd: &[u8; 32],
+    ) -> Result<MlDsa87Signature> {
+        context::checkpoint()?;
+        let signature = dsa::MlDsa87::sign_with(signing_key.0.as_bytes(), message, context, rnd)?;
+        Ok(MlDsa87Signature(Box::new(signature)))
+    }
+
+    /// Verify `signature` over `message`.
+    ///
+    /// `verifying_key` is an owned [`MlDsa87VerifyingKey`] or a borrowed
+    /// [`PublicKeyRef`].
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::VerificationFailed`: If the signature is invalid
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    pub fn verify<'k>(
+        verifying_key: impl Into<PublicKeyRef<'k, MlDsa87>>,
+        message: &[u8],
+        signature: &MlDsa87Signature,
+    ) -> Result<()> {
+        context::checkpoint()?;
+        dsa::MlDsa87.verify(verifying_key.into().as_bytes(), message, &signature.0)
+    }
+
+    /// Verify `signature` over `message` under the context string `context`.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::VerificationFailed`: If the signature is invalid or
+    ///   was made under another context
+    /// - `MisuseError::ContextTooLong`: If `context` exceeds 255 bytes
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    pub fn verify_with_context<'k>(
+        verifying_key: impl Into<PublicKeyRef<'k, MlDsa87>>,
+        message: &[u8],
+        context: &[u8],
+        signature: &MlDsa87Signature,
+    ) -> Result<()> {
+        Self::verify_message(
+            verifying_key.into(),
+            dsa::Message::Pure(message),
+            context,
+            signature,
+        )
+    }
+
+    /// Verify a HashML-DSA `signature` made with
+    /// [`hash_sign`](Self::hash_sign) against the verifier's own `digest`
+    /// of the message.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::VerificationFailed`: If the signature is invalid, or
+    ///   was made under another context or hash function
+    /// - `MisuseError::ContextTooLong`: If `context` exceeds 255 bytes
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    pub fn hash_verify<'k>(
+        verifying_key: impl Into<PublicKeyRef<'k, MlDsa87>>,
+        digest: &Digest,
+        context: &[u8],
+        signature: &MlDsa87Signature,
+    ) -> Result<()> {
+        Self::verify_message(
+            verifying_key.into(),
+            hash_ml_dsa_message(digest),
+            context,
+            signature,
+        )
+    }
+
+    fn verify_message(
+        verifying_key: PublicKeyRef<'_, MlDsa87>,
+        message: dsa::Message<'_>,
+        context: &[u8],
+        signature: &MlDsa87Signature,
+    ) -> Result<()> {
+        context::checkpoint()?;
+        if context.len() > dsa::MAX_CONTEXT_SIZE {
+            return Err(MisuseError::ContextTooLong.into());
+        }
+        if !dsa::MlDsa87::verify_message(verifying_key.as_bytes(), message, context, &signature.0) {
+            return Err(CryptoError::VerificationFailed.into());
+        }
+        Ok(())
+    }
+
+    /// Sign a message by its [`Digest`], e.g. from a [`Prehasher`].
+    ///
+    /// [`hash_sign`](Self::hash_sign) with an empty context: the signature
+    /// is HashML-DSA and verifies with [`hash_verify`](Self::hash_verify)
+    /// as well.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+    /// - `CryptoError::RandomnessFailure`: If the system RNG fails a health test
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    #[cfg(feature = "signing")]
+    pub fn sign_prehashed(
+        signing_key: &MlDsa87SigningKey,
+        digest: &Digest,
+    ) -> Result<MlDsa87Signature> {
+        Self::hash_sign(signing_key, digest, b"")
+    }
+
+    /// Verify a detached `signature` made with
+    /// [`sign_prehashed`](Self::sign_prehashed) against the verifier's own
+    /// `digest` of the message.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::VerificationFailed`: If the signature is invalid, or
+    ///   was made under a context or another hash function
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    pub fn verify_prehashed<'k>(
+        verifying_key: impl Into<PublicKeyRef<'k, MlDsa87>>,
+        digest: &Digest,
+        signature: &MlDsa87Signature,
+    ) -> Result<()> {
+        Self::hash_verify(verifying_key, digest, b"", signature)
+    }
+
+    /// Sign the RFC 8785 canonical form of the JSON document `json`.
+    ///
+    /// The signature is detached and covers the canonical bytes, so any
+    /// re-formatting of `json` that preserves its JSON value still verifies
+    /// with [`verify_json`](Self::verify_json) or any other JCS verifier.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidEncoding`: If `json` is not valid I-JSON
+    /// - `MisuseError::ParseBudgetExceeded`: If `json` exceeds `budget`
+    /// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+    /// - `CryptoError::RandomnessFailure`: If the system RNG fails a health test
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    #[cfg(feature = "signing")]
+    pub fn sign_json(
+        signing_key: &MlDsa87SigningKey,
+        json: &str,
+        budget: &ParseBudget,
+    ) -> Result<MlDsa87Signature> {
+        let canonical = jcs::canonicalize(json, budget)?;
+        Self::sign(signing_key, canonical.as_bytes())
+    }
+
+    /// Verify a detached signature over the canonical form of `json`.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::VerificationFailed`: If the signature is invalid
+    /// - `MisuseError::InvalidEncoding`: If `json` is not valid I-JSON
+    /// - `MisuseError::ParseBudgetExceeded`: If `json` exceeds `budget`
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    pub fn verify_json<'k>(
+        verifying_key: impl Into<PublicKeyRef<'k, MlDsa87>>,
+        json: &str,
+        signature: &MlDsa87Signature,
+        budget: &ParseBudget,
+    ) -> Result<()> {
+        let canonical = jcs::canonicalize(json, budget)?;
+        Self::verify(verifying_key, canonical.as_bytes(), signature)
+    }
+}
+
+#[cfg(feature = "mldsa")]
+impl SignatureVerifier<{ dsa::PUBLIC_KEY_SIZE }, { dsa::SIGNATURE_SIZE }> for MlDsa87 {
+    fn verify(
+        &self,
+        public_key: &[u8; dsa::PUBLIC_KEY_SIZE],
+        message: &[u8],
+        signature: &[u8; dsa::SIGNATURE_SIZE],
+    ) -> Result<()> {
+        context::checkpoint()?;
+        dsa::MlDsa87.verify(public_key, message, signature)
+    }
+}
+
+#[cfg(all(feature = "mldsa", feature = "signing"))]
+impl SignatureScheme<{ dsa::PUBLIC_KEY_SIZE }, { dsa::SECRET_KEY_SIZE }, { dsa::SIGNATURE_SIZE }>
+    for MlDsa87
+{
+    fn generate_keypair(&self) -> Result<([u8; dsa::PUBLIC_KEY_SIZE], [u8; dsa::SECRET_KEY_SIZE])> {
+        context::checkpoint()?;
+        dsa::MlDsa87.generate_keypair()
+    }
+
+    fn sign(
+        &self,
+        secret_key: &[u8; dsa::SECRET_KEY_SIZE],
+        message: &[u8],
+    ) -> Result<[u8; dsa::SIGNATURE_SIZE]> {
+        context::checkpoint()?;
+        dsa::MlDsa87.sign(secret_key, message)
+    }
+}
+
+/// ML-DSA-87 verifying (public) key.
+#[cfg(feature = "mldsa")]
+#[derive(Clone, PartialEq, Eq)]
+pub struct MlDsa87VerifyingKey([u8; dsa::PUBLIC_KEY_SIZE]);
+
+#[cfg(feature = "mldsa")]
+impl MlDsa87VerifyingKey {
+    /// Parse an encoded verifying key.
+    ///
+    /// Every byte string of the right length decodes to a valid key.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidPublicKeyLength`: If `bytes` is not
+    ///   [`MlDsa87::PUBLIC_KEY_SIZE`] bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let key = bytes
+            .try_into()
+            .map_err(|_| MisuseError::InvalidPublicKeyLength)?;
+        Ok(Self(key))
+    }
+
+    /// Encoded verifying key.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8; dsa::PUBLIC_KEY_SIZE] {
+        &self.0
+    }
+
+    /// Borrowed view of this key.
+    #[inline]
+    pub fn as_key_ref(&self) -> PublicKeyRef<'_, MlDsa87> {
+        PublicKeyRef { key: &self.0 }
+    }
+}
+
+#[cfg(feature = "mldsa")]
+impl<'k> From<&'k MlDsa87VerifyingKey> for PublicKeyRef<'k, MlDsa87> {
+    #[inline]
+    fn from(key: &'k MlDsa87VerifyingKey) -> Self {
+        key.as_key_ref()
+    }
+}
+
+#[cfg(feature = "mldsa")]
+impl fmt::Debug for MlDsa87VerifyingKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("MlDsa87VerifyingKey { .. }")
+    }
+}
+
+/// ML-DSA-87 signing key (expanded FIPS 204 encoding), zeroized on drop.
+#[cfg(all(feature = "mldsa", feature = "signing"))]
+pub struct MlDsa87SigningKey(SensitiveBytes<{ dsa::SECRET_KEY_SIZE }>);
+
+#[cfg(all(feature = "mldsa", feature = "signing"))]
+crate::memory::never_serialize!([] MlDsa87SigningKey);
+
+#[cfg(all(feature = "mldsa", feature = "signing"))]
+impl MlDsa87SigningKey {
+    /// Parse and validate an encoded signing key.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidSecretKeyLength`: If `bytes` is not
+    ///   [`MlDsa87::SECRET_KEY_SIZE`] bytes
+    /// - `MisuseError::InvalidEncoding`: If the secret vectors are out of
+    ///   range
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != dsa::SECRET_KEY_SIZE {
+            return Err(MisuseError::InvalidSecretKeyLength.into());
+        }
+        if !dsa::MlDsa87::is_valid_secret_key(bytes) {
+            return Err(MisuseError::InvalidEncoding.into());
+        }
+        let mut key = SensitiveBytes::zeroed();
+        key.as_bytes_mut().copy_from_slice(bytes);
+        Ok(Self(key))
+    }
+
+    /// Encoded signing key.
+    ///
+    /// # Security
+    ///
+    /// Do not log, serialize unencrypted, or store the returned bytes.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8; dsa::SECRET_KEY_SIZE] {
+        self.0.as_bytes()
+    }
+}
+
+#[cfg(all(feature = "mldsa", feature = "signing"))]
+impl fmt::Debug for MlDsa87SigningKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("MlDsa87SigningKey { <redacted> }")
+    }
+}
+
+/// ML-DSA-87 signature.
+///
+/// Boxed: at 4627 bytes it is too large to move around on the stack.
+#[cfg(feature = "mldsa")]
+#[derive(Clone, PartialEq, Eq)]
+pub struct MlDsa87Signature(Box<[u8; dsa::SIGNATURE_SIZE]>);
+
+#[cfg(feature = "mldsa")]
+impl MlDsa87Signature {
+    /// Wrap an encoded signature.
+    ///
+    /// Structural checks happen during verification.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidSignatureLength`: If `bytes` is not
+    ///   [`MlDsa87::SIGNATURE_SIZE`] bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let signature: [u8; dsa::SIGNATURE_SIZE] = bytes
+            .try_into()
+            .map_err(|_| MisuseError::InvalidSignatureLength)?;
+        Ok(Self(Box::new(signature)))
+    }
+
+    /// Encoded signature.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8; dsa::SIGNATURE_SIZE] {
+        &self.0
+    }
+}
+
+#[cfg(feature = "mldsa")]
+impl fmt::Debug for MlDsa87Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("MlDsa87Signature { .. }")
+    }
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A signature algorithm whose verifying keys can be borrowed as a
+/// [`PublicKeyRef`]. Sealed.
+pub trait VerifyingAlgorithm: sealed::Sealed {
+    /// Encoded verifying key, e.g. `[u8; 32]`.
+    type PublicKey: ?Sized;
+}
+
+impl sealed::Sealed for Ed25519 {}
+
+impl VerifyingAlgorithm for Ed25519 {
+    type PublicKey = [u8; ed::PUBLIC_KEY_SIZE];
+}
+
+#[cfg(feature = "mldsa")]
+impl sealed::Sealed for MlDsa87 {}
+
+#[cfg(feature = "mldsa")]
+impl VerifyingAlgorithm for MlDsa87 {
+    type PublicKey = [u8; dsa::PUBLIC_KEY_SIZE];
+}
+
+/// Borrowed view of an encoded verifying key of algorithm `A`.
+///
+/// Verifies without copying the key out of where it is stored, such as a
+/// memory-mapped trust store; the lifetime keeps the store alive for as
+/// long as the view is. Validated like the owned key type on construction.
+///
+/// ```ignore
+/// let store = TrustStore::open(path)?;
+/// let key = PublicKeyRef::<MlDsa87>::from_bytes(store.key(issuer))?;
+/// MlDsa87::verify(key, &payload, &signature)?;
+/// ```
+pub struct PublicKeyRef<'a, A: VerifyingAlgorithm> {
+    key: &'a A::PublicKey,
+}
+
+impl<'a, A: VerifyingAlgorithm> PublicKeyRef<'a, A> {
+    /// Encoded verifying key, borrowed from the underlying storage.
+    #[inline]
+    pub fn as_bytes(&self) -> &'a A::PublicKey {
+        self.key
+    }
+}
+
+impl<'a> PublicKeyRef<'a, Ed25519> {
+    /// Borrow and validate an encoded verifying key.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidPublicKeyLength`: If `bytes` is not
+    ///   [`Ed25519::PUBLIC_KEY_SIZE`] bytes
+    /// - `MisuseError::InvalidEncoding`: If `bytes` is not a canonical curve
+    ///   point encoding
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self> {
+        let key = bytes
+            .try_into()
+            .map_err(|_| MisuseError::InvalidPublicKeyLength)?;
+        if !ed::Ed25519::is_valid_public_key(key) {
+            return Err(MisuseError::InvalidEncoding.into());
+        }
+        Ok(Self { key })
+    }
+
+    /// Copy into an owned key.
+    pub fn to_verifying_key(&self) -> Ed25519VerifyingKey {
+        Ed25519VerifyingKey(*self.key)
+    }
+}
+
+#[cfg(feature = "mldsa")]
+impl<'a> PublicKeyRef<'a, MlDsa87> {
+    /// Borrow an encoded verifying key.
+    ///
+    /// Every byte string of the right length decodes to a valid key.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidPublicKeyLength`: If `bytes` is not
+    ///   [`MlDsa87::PUBLIC_KEY_SIZE`] bytes
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self> {
+        let key = bytes
+            .try_into()
+            .map_err(|_| MisuseError::InvalidPublicKeyLength)?;
+        Ok(Self { key })
+    }
+
+    /// Copy into an owned key.
+    pub fn to_verifying_key(&self) -> MlDsa87VerifyingKey {
+        MlDsa87VerifyingKey(*self.key)
+    }
+}
+
+impl<A: VerifyingAlgorithm> Clone for PublicKeyRef<'_, A> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<A: VerifyingAlgorithm> Copy for PublicKeyRef<'_, A> {}
+
+impl<A: VerifyingAlgorithm> PartialEq for PublicKeyRef<'_, A>
+where
+    A::PublicKey: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl<A: VerifyingAlgorithm> Eq for PublicKeyRef<'_, A> where A::PublicKey: Eq {}
+
+impl<A: VerifyingAlgorithm> fmt::Debug for PublicKeyRef<'_, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PublicKeyRef { .. }")
+    }
+}
+
+/// Hash function used to pre-hash messages before remote signing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Prehash {
+    /// SHA3-512 (FIPS 202). Recommended.
+    Sha3_512,
+    /// SHA-256 (FIPS 180-4), for signers that mandate it.
+    Sha256,
+    /// SHA-512 (FIPS 180-4), the pre-hash Ed25519ph requires.
+    Sha512,
+}
+
+impl Prehash {
+    /// Digest size in bytes.
+    pub const fn output_size(self) -> usize {
+        match self {
+            Prehash::Sha3_512 | Prehash::Sha512 => 64,
+            Prehash::Sha256 => 32,
+        }
+    }
+
+    /// An incremental hasher, for messages that arrive in pieces.
+    pub fn hasher(self) -> Prehasher {
+        let state = match self {
+            Prehash::Sha3_512 => PrehasherState::Sha3_512(keccak::sha3(64)),
+            Prehash::Sha256 => PrehasherState::Sha256(Sha256::new()),
+            Prehash::Sha512 => PrehasherState::Sha512(Sha512::new()),
+        };
+        Prehasher {
+            algorithm: self,
+            state,
+        }
+    }
+
+    /// Hash `message`.
+    pub fn digest(self, message: &[u8]) -> Digest {
+        let mut bytes = [0u8; Digest::MAX_SIZE];
+        match self {
+            Prehash::Sha3_512 => bytes = sha3_512(&[message]),
+            Prehash::Sha256 => bytes[..32].copy_from_slice(&Sha256::digest(message)),
+            Prehash::Sha512 => bytes = Sha512::digest(message),
+        }
+        Digest {
+            algorithm: self,
+            bytes,
+        }
+    }
+}
+
+/// The HashML-DSA message for `digest`: the digest tagged with the DER
+/// encoding of its hash function's object identifier.
+#[cfg(feature = "mldsa")]
+fn hash_ml_dsa_message(digest: &Digest) -> dsa::Message<'_> {
+    const SHA256: &[u8] = &[
+        0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01,
+    ];
+    const SHA512: &[u8] = &[
+        0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x03,
+    ];
+    const SHA3_512: &[u8] = &[
+        0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x0a,
+    ];
+    let oid = match digest.algorithm() {
+        Prehash::Sha256 => SHA256,
+        Prehash::Sha512 => SHA512,
+        Prehash::Sha3_512 => SHA3_512,
+    };
+    dsa::Message::PreHash {
+        oid,
+        digest: digest.as_bytes(),
+    }
+}
+
+/// Hashes a message piece by piece into a [`Digest`].
+///
+/// Also a [`Write`](io::Write), so a file can be hashed with
+/// [`io::copy`] without holding it in memory.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut hasher = Prehash::Sha512.hasher();
+/// io::copy(&mut File::open("release.tar")?, &mut hasher)?;
+/// let signature = Ed25519::sign_prehashed(&signing_key, &hasher.finalize())?;
+/// ```
+pub struct Prehasher {
+    algorithm: Prehash,
+    state: PrehasherState,
+}
+
+enum PrehasherState {
+    Sha3_512(Sponge),
+    Sha256(Sha256),
+    Sha512(Sha512),
+}
+
+impl Prehasher {
+    /// Hash function in use.
+    #[inline]
+    pub const fn algorithm(&self) -> Prehash {
+        self.algorithm
+    }
+
+    /// Absorb the next piece of the message.
+    pub fn update(&mut self, data: &[u8]) {
+        match &mut self.state {
+            PrehasherState::Sha3_512(sponge) => sponge.absorb(data),
+            PrehasherState::Sha256(hasher) => hasher.update(data),
+            PrehasherState::Sha512(hasher) => hasher.update(data),
+        }
+    }
+
+    /// Finish hashing and return the digest.
+    pub fn finalize(self) -> Digest {
+        let mut bytes = [0u8; Digest::MAX_SIZE];
+        match self.state {
+            PrehasherState::Sha3_512(mut sponge) => sponge.squeeze(&mut bytes),
+            PrehasherState::Sha256(hasher) => bytes[..32].copy_from_slice(&hasher.finalize()),
+            PrehasherState::Sha512(hasher) => bytes = hasher.finalize(),
+        }
+        Digest {
+            algorithm: self.algorithm,
+            bytes,
+        }
+    }
+}
+
+impl io::Write for Prehasher {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Prehasher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Prehasher")
+            .field("algorithm", &self.algorithm)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A message digest to be signed, tagged with the hash that produced it.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Digest {
+    algorithm: Prehash,
+    bytes: [u8; Digest::MAX_SIZE],
+}
+
+impl Digest {
+    /// Largest supported digest size in bytes.
+    pub const MAX_SIZE: usize = 64;
+
+    /// Hash function that produced this digest.
+    #[inline]
+    pub const fn algorithm(&self) -> Prehash {
+        self.algorithm
+    }
+
+    /// Digest bytes, [`Prehash::output_size`] long.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.algorithm.output_size()]
+    }
+}
+
+impl fmt::Debug for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Digest")
+            .field("algorithm", &self.algorithm)
+            .field("bytes", &self.as_bytes())
+            .finish()
+    }
+}
+
+/// A signer that may complete asynchronously, e.g. over the network.
+///
+/// Implementations wrap a transport (gRPC, HTTP, PKCS#11, ...) and are
+/// executor-agnostic: the returned future only has to be `Send`.
+///
+/// # Example
+///
+/// ```ignore
+/// impl AsyncSigner for HsmClient {
+///     fn prehash(&self) -> Prehash {
+///         Prehash::Sha3_512
+///     }
+///
+///     fn signature_size(&self) -> usize {
+///         MlDsa87::SIGNATURE_SIZE
+///     }
+///
+///     fn sign_digest(&self, digest: &Digest) -> impl Future<Output = Result<Vec<u8>>> + Send {
+///         async move {
+///             self.call("sign", digest.as_bytes())
+///                 .await
+///                 .map_err(|_| CryptoError::SignerUnavailable.into())
+///         }
+///     }
+/// }
+/// ```
+#[cfg(feature = "signing")]
+pub trait AsyncSigner {
+    /// Hash function the signer expects digests from.
+    fn prehash(&self) -> Prehash;
+
+    /// Exact size in bytes of signatures produced by this signer.
+    fn signature_size(&self) -> usize;
+
+    /// Sign a digest produced with [`prehash`](Self::prehash).
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::SignerUnavailable`: If the remote signer cannot be
+    ///   reached or refuses to sign
+    fn sign_digest(&self, digest: &Digest) -> impl Future<Output = Result<Vec<u8>>> + Send;
+}
+
+/// Adapter that hashes messages locally and delegates signing of the digest
+/// to an [`AsyncSigner`].
+#[cfg(feature = "signing")]
+#[derive(Debug, Clone)]
+pub struct RemoteSigner<S> {
+    signer: S,
+}
+
+#[cfg(feature = "signing")]
+impl<S: AsyncSigner> RemoteSigner<S> {
+    /// Wrap `signer`.
+    pub const fn new(signer: S) -> Self {
+        Self { signer }
+    }
+
+    /// The wrapped signer.
+    #[inline]
+    pub fn inner(&self) -> &S {
+        &self.signer
+    }
+
+    /// Hash `message` and have the wrapped signer sign the digest.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::SignerUnavailable`: If the signer fails
+    /// - `MisuseError::InvalidSignatureLength`: If the signer returns a
+    ///   signature of the wrong size
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   [`OpContext`](crate::context::OpContext) current when the future is
+    ///   first polled has expired
+    pub async fn sign(&self, message: &[u8]) -> Result<Vec<u8>> {
+        context::checkpoint()?;
+        let digest = self.signer.prehash().digest(message);
+        self.sign_prehashed(&digest).await
+    }
+
+    /// Have the wrapped signer sign a digest computed by the caller, e.g.
+    /// with a [`Prehasher`] over a message too large to hold in memory.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidAlgorithmIdentifier`: If `digest` was not
+    ///   produced with the signer's [`prehash`](AsyncSigner::prehash)
+    /// - As [`sign`](Self::sign)
+    pub async fn sign_prehashed(&self, digest: &Digest) -> Result<Vec<u8>> {
+        context::checkpoint()?;
+        if digest.algorithm() != self.signer.prehash() {
+            return Err(MisuseError::InvalidAlgorithmIdentifier.into());
+        }
+        let signature = self.signer.sign_digest(digest).await?;
+        if signature.len() != self.signer.signature_size() {
+            return Err(MisuseError::InvalidSignatureLength.into());
+        }
+        Ok(signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::Error;
+    #[cfg(feature = "signing")]
+    use std::pin::pin;
+    #[cfg(feature = "signing")]
+    use std::task::{Context, Poll, Waker};
+
+    #[cfg(feature = "signing")]
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    /// Echoes the digest back as the "signature".
+    #[cfg(feature = "signing")]
+    struct EchoSigner {
+        size: usize,
+    }
+
+    #[cfg(feature = "signing")]
+    impl AsyncSigner for EchoSigner {
+        fn prehash(&self) -> Prehash {
+            Prehash::Sha3_512
+        }
+
+        fn signature_size(&self) -> usize {
+            self.size
+        }
+
+        fn sign_digest(&self, digest: &Digest) -> impl Future<Output = Result<Vec<u8>>> + Send {
+            let bytes = digest.as_bytes().to_vec();
+            async move { Ok(bytes) }
+        }
+    }
+
+    #[cfg(feature = "signing")]
+    #[test]
+    fn ed25519_round_trip() {
+        let (verifying_key, signing_key) = Ed25519::generate_keypair().unwrap();
+        let signature = Ed25519::sign(&signing_key, b"manifest").unwrap();
+        Ed25519::verify(&verifying_key, b"manifest", &signature).unwrap();
+        assert_eq!(
+            Ed25519::verify(&verifying_key, b"tampered", &signature).unwrap_err(),
+            Error::Crypto(CryptoError::VerificationFailed)
+        );
+        assert!(signing_key.verifying_key() == verifying_key);
+        assert_eq!(
+            format!("{:?}", signing_key),
+            "Ed25519SigningKey { <redacted> }"
+        );
+    }
+
+    #[test]
+    fn ed25519_parsing() {
+        assert_eq!(
+            Ed25519VerifyingKey::from_bytes(&[0u8; 31]).unwrap_err(),
+            Error::Misuse(MisuseError::InvalidPublicKeyLength)
+        );
+        assert_eq!(
+            Ed25519VerifyingKey::from_bytes(&[0xff; 32]).unwrap_err(),
+            Error::Misuse(MisuseError::InvalidEncoding)
+        );
+        assert_eq!(
+            Ed25519Signature::from_bytes(&[0u8; 63]).unwrap_err(),
+            Error::Misuse(MisuseError::InvalidSignatureLength)
+        );
+    }
+
+    #[cfg(all(feature = "mldsa", feature = "signing"))]
+    #[test]
+    fn verifies_against_borrowed_keys() {
+        let (ed_key, ed_signing_key) = Ed25519::generate_keypair().unwrap();
+        let (dsa_key, dsa_signing_key) = MlDsa87::generate_keypair().unwrap();
+        // Both keys back to back, as in a trust store
+        let mut store = ed_key.as_bytes().to_vec();
+        store.extend_from_slice(dsa_key.as_bytes());
+
+        let ed_ref = PublicKeyRef::<Ed25519>::from_bytes(&store[..32]).unwrap();
+        let dsa_ref = PublicKeyRef::<MlDsa87>::from_bytes(&store[32..]).unwrap();
+        assert!(core::ptr::eq(
+            dsa_ref.as_bytes().as_ptr(),
+            store[32..].as_ptr()
+        ));
+        let signature = Ed25519::sign(&ed_signing_key, b"manifest").unwrap();
+        Ed25519::verify(ed_ref, b"manifest", &signature).unwrap();
+        let signature = MlDsa87::sign(&dsa_signing_key, b"manifest").unwrap();
+        MlDsa87::verify(dsa_ref, b"manifest", &signature).unwrap();
+        assert!(MlDsa87::verify(dsa_ref, b"tampered", &signature).is_err());
+
+        assert!(ed_ref == ed_key.as_key_ref());
+        assert!(ed_ref.to_verifying_key() == ed_key);
+        assert!(dsa_ref.to_verifying_key() == dsa_key);
+        assert_eq!(
+            PublicKeyRef::<MlDsa87>::from_bytes(&store[1..]).unwrap_err(),
+            Error::Misuse(MisuseError::InvalidPublicKeyLength)
+        );
+        assert_eq!(
+            PublicKeyRef::<Ed25519>::from_bytes(&[0xff; 32]).unwrap_err(),
+            Error::Misuse(MisuseError::InvalidEncoding)
+        );
+    }
+
+    #[cfg(all(feature = "mldsa", feature = "signing"))]
+    #[test]
+    fn ml_dsa_87_round_trip() {
+        let (verifying_key, signing_key) = MlDsa87::generate_keypair().unwrap();
+        let signature = MlDsa87::sign(&signing_key, b"manifest").unwrap();
+        MlDsa87::verify(&verifying_key, b"manifest", &signature).unwrap();
+        assert_eq!(
+            MlDsa87::verify(&verifying_key, b"tampered", &signature).unwrap_err(),
+            Error::Crypto(CryptoError::VerificationFailed)
+        );
+        // Hedged: a second signature differs but also verifies
+        let again = MlDsa87::sign(&signing_key, b"manifest").unwrap();
+        assert!(again != signature);
+        MlDsa87::verify(&verifying_key, b"manifest", &again).unwrap();
+
+        let parsed = MlDsa87SigningKey::from_bytes(signing_key.as_bytes()).unwrap();
+        MlDsa87::verify(&verifying_key, b"m", &MlDsa87::sign(&parsed, b"m").unwrap()).unwrap();
+    }
+
+    #[cfg(feature = "signing")]
+    #[test]
+    fn injected_rng_is_deterministic() {
+        use crate::internal::entropy::CounterEntropy;
+
+        let (ed_verifying, _) =
+            Ed25519::generate_keypair_with_rng(&CounterEntropy::new(0)).unwrap();
+        let (again, _) = Ed25519::generate_keypair_with_rng(&CounterEntropy::new(0)).unwrap();
+        assert_eq!(ed_verifying, again);
+
+        #[cfg(feature = "mldsa")]
+        {
+            let (verifying_key, signing_key) =
+                MlDsa87::generate_keypair_with_rng(&CounterEntropy::new(0)).unwrap();
+            let a = MlDsa87::sign_with_rng(&signing_key, b"kat", &CounterEntropy::new(7)).unwrap();
+            let b = MlDsa87::sign_with_rng(&signing_key, b"kat", &CounterEntropy::new(7)).unwrap();
+            let c = MlDsa87::sign_with_rng(&signing_key, b"kat", &CounterEntropy::new(8)).unwrap();
+            assert_eq!(a.as_bytes(), b.as_bytes());
+            assert_ne!(a.as_bytes(), c.as_bytes());
+            MlDsa87::verify(&verifying_key, b"kat", &c).unwrap();
+        }
+    }
+
+    #[cfg(all(feature = "mldsa", feature = "signing"))]
+    #[test]
+    fn ml_dsa_87_seed_rebuilds_keypair() {
+        let seed = MlDsa87::generate_seed().unwrap();
+        let (verifying_key, signing_key) = MlDsa87::keypair_from_seed(seed.as_bytes());
+        let (_, rebuilt) = MlDsa87::keypair_from_seed(seed.as_bytes());
+        let signature = MlDsa87::sign(&rebuilt, b"stored seed").unwrap();
+        MlDsa87::verify(&verifying_key, b"stored seed", &signature).unwrap();
+        assert_eq!(signing_key.as_bytes(), rebuilt.as_bytes());
+    }
+
+    #[cfg(all(feature = "mldsa", feature = "signing"))]
+    #[test]
+    fn ml_dsa_87_contexts_and_hash_ml_dsa() {
+        let (verifying_key, signing_key) = MlDsa87::generate_keypair().unwrap();
+        let failed = Error::Crypto(CryptoError::VerificationFailed);
+
+        let signature = MlDsa87::sign_with_context(&signing_key, b"m", b"protocol v1").unwrap();
+        MlDsa87::verify_with_context(&verifying_key, b"m", b"protocol v1", &signature).unwrap();
+        assert_eq!(
+            MlDsa87::verify_with_context(&verifying_key, b"m", b"protocol v2", &signature)
+                .unwrap_err(),
+            failed
+        );
+        assert_eq!(
+            MlDsa87::verify(&verifying_key, b"m", &signature).unwrap_err(),
+            failed
+        );
+        // The empty context is plain ML-DSA
+        let plain = MlDsa87::sign(&signing_key, b"m").unwrap();
+        MlDsa87::verify_with_context(&verifying_key, b"m", b"", &plain).unwrap();
+
+        let long = [0u8; 256];
+        assert_eq!(
+            MlDsa87::sign_with_context(&signing_key, b"m", &long).unwrap_err(),
+            Error::Misuse(MisuseError::ContextTooLong)
+        );
+        assert_eq!(
+            MlDsa87::verify_with_context(&verifying_key, b"m", &long, &plain).unwrap_err(),
+            Error::Misuse(MisuseError::ContextTooLong)
+        );
+
+        let digest = Prehash::Sha3_512.digest(b"m");
+        let signature = MlDsa87::hash_sign(&signing_key, &digest, b"ctx").unwrap();
+        MlDsa87::hash_verify(&verifying_key, &digest, b"ctx", &signature).unwrap();
+        // The hash function and the encoding are both bound
+        assert_eq!(
+            MlDsa87::hash_verify(
+                &verifying_key,
+                &Prehash::Sha256.digest(b"m"),
+                b"ctx",
+                &signature
+            )
+            .unwrap_err(),
+            failed
+        );
+        assert_eq!(
+            MlDsa87::verify_prehashed(&verifying_key, &digest, &signature).unwrap_err(),
+            failed
+        );
+        // sign_prehashed is HashML-DSA with an empty context
+        let signature = MlDsa87::sign_prehashed(&signing_key, &digest).unwrap();
+        MlDsa87::hash_verify(&verifying_key, &digest, b"", &signature).unwrap();
+        MlDsa87::verify_prehashed(&verifying_key, &digest, &signature).unwrap();
+        assert_eq!(
+            MlDsa87::verify(&verifying_key, digest.as_bytes(), &signature).unwrap_err(),
+            failed
+        );
+    }
+
+    #[cfg(all(feature = "mldsa", feature = "signing"))]
+    #[test]
+    fn ml_dsa_87_json_signatures_ignore_formatting() {
+        let (verifying_key, signing_key) = MlDsa87::generate_keypair().unwrap();
+        let budget = ParseBudget::DEFAULT;
+        let signature =
+            MlDsa87::sign_json(&signing_key, r#"{"b": 1.50, "a": [true]}"#, &budget).unwrap();
+
+        MlDsa87::verify_json(
+            &verifying_key,
+            "{\"a\":[true],\n \"b\":15e-1}",
+            &signature,
+            &budget,
+        )
+        .unwrap();
+        MlDsa87::verify(&verifying_key, br#"{"a":[true],"b":1.5}"#, &signature).unwrap();
+        assert_eq!(
+            MlDsa87::verify_json(
+                &verifying_key,
+                r#"{"a":[false],"b":1.5}"#,
+                &signature,
+                &budget
+            )
+            .unwrap_err(),
+            Error::Crypto(CryptoError::VerificationFailed)
+        );
+        assert_eq!(
+            MlDsa87::verify_json(&verifying_key, r#"{"a":1,"a":1}"#, &signature, &budget)
+                .unwrap_err(),
+            Error::Misuse(MisuseError::InvalidEncoding)
+        );
+    }
+
+    #[cfg(feature = "mldsa")]
+    #[test]
+    fn ml_dsa_87_parsing() {
+        assert_eq!(
+            MlDsa87VerifyingKey::from_bytes(&[0u8; 2591]).unwrap_err(),
+            Error::Misuse(MisuseError::InvalidPublicKeyLength)
+        );
+        #[cfg(feature = "signing")]
+        {
+            assert_eq!(
+                MlDsa87SigningKey::from_bytes(&[0u8; MlDsa87::SECRET_KEY_SIZE - 1]).unwrap_err(),
+                Error::Misuse(MisuseError::InvalidSecretKeyLength)
+            );
+            assert_eq!(
+                MlDsa87SigningKey::from_bytes(&[0xff; MlDsa87::SECRET_KEY_SIZE]).unwrap_err(),
+                Error::Misuse(MisuseError::InvalidEncoding)
+            );
+        }
+        assert_eq!(
+            MlDsa87Signature::from_bytes(&[0u8; MlDsa87::SIGNATURE_SIZE - 1]).unwrap_err(),
+            Error::Misuse(MisuseError::InvalidSignatureLength)
+        );
+    }
+
+    #[cfg(feature = "signing")]
+    #[test]
+    fn only_the_digest_is_sent() {
+        let signer = RemoteSigner::new(EchoSigner { size: 64 });
+        let signature = block_on(signer.sign(b"abc")).unwrap();
+        assert_eq!(signature, sha3_512(&[b"abc"]));
+    }
+
+    #[cfg(feature = "signing")]
+    #[test]
+    fn rejects_wrong_signature_size() {
+        let signer = RemoteSigner::new(EchoSigner {
+            size: crate::consts::MlDsa87::SIGNATURE_SIZE,
+        });
+        assert_eq!(
+            block_on(signer.sign(b"abc")).unwrap_err(),
+            Error::Misuse(MisuseError::InvalidSignatureLength)
+        );
+    }
+
+    #[test]
+    fn sha256_digest_has_native_length() {
+        let digest = Prehash::Sha256.digest(b"abc");
+        assert_eq!(digest.as_bytes(), Sha256::digest(b"abc"));
+        assert_eq!(digest.algorithm(), Prehash::Sha256);
+    }
+
+    #[test]
+    fn prehasher_matches_one_shot_digest() {
+        let message: Vec<u8> = (0..1000u32).map(|i| i as u8).collect();
+        for algorithm in [Prehash::Sha3_512, Prehash::Sha256, Prehash::Sha512] {
+            let mut hasher = algorithm.hasher();
+            io::copy(&mut &message[..], &mut hasher).unwrap();
+            assert!(hasher.finalize() == algorithm.digest(&message));
+        }
+    }
+
+    #[cfg(feature = "signing")]
+    #[test]
+    fn detached_prehashed_signatures() {
+        let (verifying_key, signing_key) = Ed25519::generate_keypair().unwrap();
+        let mut hasher = Prehash::Sha512.hasher();
+        hasher.update(b"large ");
+        hasher.update(b"file");
+        let signature = Ed25519::sign_prehashed(&signing_key, &hasher.finalize()).unwrap();
+        let digest = Prehash::Sha512.digest(b"large file");
+        Ed25519::verify_prehashed(&verifying_key, &digest, &signature).unwrap();
+        assert_eq!(
+            Ed25519::verify_prehashed(
+                &verifying_key,
+                &Prehash::Sha512.digest(b"other file"),
+                &signature
+            )
+            .unwrap_err(),
+            Error::Crypto(CryptoError::VerificationFailed)
+        );
+        // Ed25519ph is not Ed25519 over the digest bytes
+        assert_eq!(
+            Ed25519::verify(&verifying_key, digest.as_bytes(), &signature).unwrap_err(),
+            Error::Crypto(CryptoError::VerificationFailed)
+        );
+        let digest = Prehash::Sha3_512.digest(b"large file");
+        assert_eq!(
+            Ed25519::sign_prehashed(&signing_key, &digest).unwrap_err(),
+            Error::Misuse(MisuseError::InvalidAlgorithmIdentifier)
+        );
+
+        // A remote signer is handed the same digest bytes
+        let signer = RemoteSigner::new(EchoSigner { size: 64 });
+        assert_eq!(
+            block_on(signer.sign_prehashed(&digest)).unwrap(),
+            digest.as_bytes()
+        );
+        assert_eq!(
+            block_on(signer.sign_prehashed(&Prehash::Sha256.digest(b"large file"))).unwrap_err(),
+            Error::Misuse(MisuseError::InvalidAlgorithmIdentifier)
+        );
+    }
+}
@@ -0,0 +1,340 @@
+//! Sign-then-encrypt in one call.
+//!
+//! # Purpose
+//!
+//! Signing and encrypting separately is easy to get subtly wrong: a
+//! signature over the plaintext alone can be stripped and replaced by
+//! anyone who decrypts, and a signed message can be re-encrypted to a third
+//! party who then believes it was meant for them. [`sign_and_encrypt`] and
+//! [`decrypt_and_verify`] fix the composition so callers do not have to.
+//!
+//! # Construction
+//!
+//! 1. An [HPKE](crate::hpke) context is set up to the recipient, with the
+//!    signature algorithm and the sender's verifying key in `info`, so the
+//!    encryption keys depend on who claims to have signed
+//! 2. The sender signs a domain-separated message covering the HPKE suite,
+//!    the recipient's public key, the encapsulated key, the associated data
+//!    and the plaintext, so the signature only holds for this recipient and
+//!    this encryption
+//! 3. `signature || plaintext` is sealed with the associated data
+//!
+//! The output is `enc || ciphertext`. The signature travels encrypted, so
+//! an eavesdropper learns neither the sender's identity from it nor can it
+//! test guesses of the plaintext against it.
+//!
+//! Decryption checks the AEAD first and then the signature; either failing
+//! fails the whole call, and no plaintext is returned.
+//!
+//! # Security
+//!
+//! - The recipient must know the sender's verifying key in advance; a key
+//!   carried inside the message would prove nothing
+//! - Base-mode HPKE is used, so confidentiality rests on the recipient's
+//!   KEM and authenticity on the sender's signature
+//! - A recipient can show the plaintext and signature to others, who can
+//!   verify that the sender signed it for that recipient: signcryption is
+//!   not deniable
+//!
+//! # Example
+//!
+//! ```ignore
+//! use citadel::algorithm::AlgorithmId;
+//! use citadel::hpke::{Aead, CipherSuite, Kdf, Kem};
+//! use citadel::signcrypt::{self, Sender};
+//!
+//! let suite = CipherSuite::new(Kem::XWing, Kdf::HkdfSha256, Aead::Aes256GcmSiv);
+//! let sender = Sender::new(AlgorithmId::MlDsa87, &signing_key, &verifying_key)?;
+//!
+//! let sealed = signcrypt::sign_and_encrypt(suite, &sender, &recipient_pk, b"message", b"header")?;
+//! let message = signcrypt::decrypt_and_verify(
+//!     suite,
+//!     AlgorithmId::MlDsa87,
+//!     &verifying_key,
+//!     &recipient_pk,
+//!     &recipient_sk,
+//!     &sealed,
+//!     b"header",
+//! )?;
+//! ```
+
+#[cfg(feature = "signing")]
+use core::fmt;
+
+use crate::algorithm::{AlgorithmId, AlgorithmKind};
+use crate::dynamic;
+use crate::errors::{MisuseError, Result};
+use crate::hpke::CipherSuite;
+
+/// Domain separator of the HPKE `info` and of the signed message.
+const LABEL: &[u8] = b"citadel signcrypt v1";
+
+/// The sender's signing key and the verifying key recipients know it by.
+#[cfg(feature = "signing")]
+#[derive(Clone, Copy)]
+pub struct Sender<'a> {
+    algorithm: AlgorithmId,
+    secret_key: &'a [u8],
+    public_key: &'a [u8],
+}
+
+#[cfg(feature = "signing")]
+impl<'a> Sender<'a> {
+    /// A sender signing with `algorithm`.
+    ///
+    /// `public_key` must be the verifying key of `secret_key`; it is bound
+    /// into the encryption, and recipients verify with it.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidAlgorithmIdentifier`: If `algorithm` is not a
+    ///   signature scheme
+    /// - `MisuseError::InvalidSecretKeyLength` /
+    ///   `MisuseError::InvalidPublicKeyLength`: If a key has the wrong
+    ///   length
+    pub fn new(algorithm: AlgorithmId, secret_key: &'a [u8], public_key: &'a [u8]) -> Result<Self> {
+        check_algorithm(algorithm)?;
+        algorithm.check_secret_key(secret_key)?;
+        algorithm.check_public_key(public_key)?;
+        Ok(Self {
+            algorithm,
+            secret_key,
+            public_key,
+        })
+    }
+
+    /// The signature algorithm.
+    pub fn algorithm(&self) -> AlgorithmId {
+        self.algorithm
+    }
+
+    /// The verifying key.
+    pub fn public_key(&self) -> &'a [u8] {
+        self.public_key
+    }
+}
+
+#[cfg(feature = "signing")]
+impl fmt::Debug for Sender<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender")
+            .field("algorithm", &self.algorithm)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Sign `plaintext` as `sender` and encrypt it to `recipient_public_key`.
+///
+/// `associated_data` is authenticated and signed but not included in the
+/// output; pass the same value to [`decrypt_and_verify`].
+///
+/// # Errors
+///
+/// - `MisuseError::FeatureNotEnabled`: If the signature scheme's or the
+///   suite's feature is off
+/// - `MisuseError::InvalidPublicKeyLength`: If `recipient_public_key` has
+///   the wrong length
+/// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+/// - Any error of signing or of [`CipherSuite::setup_sender`]
+#[cfg(feature = "signing")]
+pub fn sign_and_encrypt(
+    suite: CipherSuite,
+    sender: &Sender<'_>,
+    recipient_public_key: &[u8],
+    plaintext: &[u8],
+    associated_data: &[u8],
+) -> Result<Vec<u8>> {
+    let signer = signer(sender.algorithm)?;
+    let info = info(sender.algorithm, sender.public_key);
+    let (mut sealed, mut context) = suite.setup_sender(recipient_public_key, &info, None, None)?;
+
+    let message = signed_message(
+        suite,
+        sender.algorithm,
+        recipient_public_key,
+        &sealed,
+        associated_data,
+        plaintext,
+    );
+    let signature = signer.sign(sender.secret_key, &message)?;
+
+    let ciphertext = context.seal(associated_data, &[&signature[..], plaintext].concat())?;
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+/// Decrypt the output of [`sign_and_encrypt`] and verify that
+/// `sender_public_key` signed it for this recipient.
+///
+/// `recipient_public_key` must be the public key of
+/// `recipient_secret_key`.
+///
+/// # Errors
+///
+/// - `CryptoError::DecryptionFailed`: If the ciphertext, keys, sender or
+///   `associated_data` do not match
+/// - `CryptoError::VerificationFailed`: If the signature does not verify
+/// - `MisuseError::InvalidCiphertextLength`: If `sealed` is too short
+/// - `MisuseError::InvalidAlgorithmIdentifier`: If `signature_algorithm`
+///   is not a signature scheme
+/// - `MisuseError::FeatureNotEnabled`: If the signature scheme's or the
+///   suite's feature is off
+pub fn decrypt_and_verify(
+    suite: CipherSuite,
+    signature_algorithm: AlgorithmId,
+    sender_public_key: &[u8],
+    recipient_public_key: &[u8],
+    recipient_secret_key: &[u8],
+    sealed: &[u8],
+    associated_data: &[u8],
+) -> Result<Vec<u8>> {
+    check_algorithm(signature_algorithm)?;
+    signature_algorithm.check_public_key(sender_public_key)?;
+    let verifier = signer(signature_algorithm)?;
+    let enc_size = suite.kem().enc_size();
+    if sealed.len() < enc_size {
+        return Err(MisuseError::InvalidCiphertextLength.into());
+    }
+    let (enc, ciphertext) = sealed.split_at(enc_size);
+
+    let info = info(signature_algorithm, sender_public_key);
+    let mut context = suite.setup_receiver(enc, recipient_secret_key, &info, None, None)?;
+    let opened = context.open(associated_data, ciphertext)?;
+    let Some((signature, plaintext)) = opened.split_at_checked(verifier.signature_size()) else {
+        return Err(MisuseError::InvalidCiphertextLength.into());
+    };
+
+    let message = signed_message(
+        suite,
+        signature_algorithm,
+        recipient_public_key,
+        enc,
+        associated_data,
+        plaintext,
+    );
+    verifier.verify(sender_public_key, &message, signature)?;
+    Ok(plaintext.to_vec())
+}
+
+fn check_algorithm(algorithm: AlgorithmId) -> Result<()> {
+    if algorithm.kind() != AlgorithmKind::Signature {
+        return Err(MisuseError::InvalidAlgorithmIdentifier.into());
+    }
+    Ok(())
+}
+
+fn signer(algorithm: AlgorithmId) -> Result<&'static dyn dynamic::DynSigner> {
+    dynamic::signer(algorithm.name())
+}
+
+/// HPKE `info`: binds the encryption keys to the claimed sender.
+fn info(algorithm: AlgorithmId, sender_public_key: &[u8]) -> Vec<u8> {
+    [LABEL, &algorithm.to_bytes(), sender_public_key].concat()
+}
+
+/// The message the sender signs, with every variable-length field length
+/// prefixed.
+fn signed_message(
+    suite: CipherSuite,
+    algorithm: AlgorithmId,
+    recipient_public_key: &[u8],
+    enc: &[u8],
+    associated_data: &[u8],
+    plaintext: &[u8],
+) -> Vec<u8> {
+    let mut message = Vec::with_capacity(
+        LABEL.len() + 64 + recipient_public_key.len() + enc.len() + associated_data.len(),
+    );
+    message.extend_from_slice(LABEL);
+    message.extend_from_slice(&algorithm.to_bytes());
+    message.extend_from_slice(&suite.suite_id());
+    for field in [recipient_public_key, enc, associated_data, plaintext] {
+        message.extend_from_slice(&(field.len() as u64).to_be_bytes());
+        message.extend_from_slice(field);
+    }
+    message
+}
+
+#[cfg(all(test, feature = "signing", feature = "aes-gcm-siv"))]
+mod tests {
+    use super::*;
+    use crate::errors::CryptoError;
+    use crate::hpke::{Aead, Kdf, Kem};
+
+    const SUITE: CipherSuite = CipherSuite::new(
+        Kem::DhKemX25519HkdfSha256,
+        Kdf::HkdfSha256,
+        Aead::Aes256GcmSiv,
+    );
+
+    #[test]
+    fn round_trip_and_binding() {
+        let ed25519 = dynamic::signer("Ed25519").unwrap();
+        let (sender_public, sender_secret) = ed25519.generate_keypair().unwrap();
+        let (other_public, other_secret) = ed25519.generate_keypair().unwrap();
+        let (recipient_public, recipient_secret) = SUITE.kem().generate_keypair().unwrap();
+        let sender = Sender::new(
+            AlgorithmId::Ed25519,
+            sender_secret.as_slice(),
+            &sender_public,
+        )
+        .unwrap();
+
+        let sealed =
+            sign_and_encrypt(SUITE, &sender, &recipient_public, b"message", b"header").unwrap();
+        let open = |sender_public: &[u8], associated_data: &[u8]| {
+            decrypt_and_verify(
+                SUITE,
+                AlgorithmId::Ed25519,
+                sender_public,
+                &recipient_public,
+                recipient_secret.as_slice(),
+                &sealed,
+                associated_data,
+            )
+        };
+        assert_eq!(open(&sender_public, b"header").unwrap(), b"message");
+        assert_eq!(
+            open(&other_public, b"header").unwrap_err().crypto(),
+            Some(CryptoError::DecryptionFailed)
+        );
+        assert_eq!(
+            open(&sender_public, b"other").unwrap_err().crypto(),
+            Some(CryptoError::DecryptionFailed)
+        );
+
+        // Claiming someone else's verifying key fails the signature check
+        let impostor = Sender::new(
+            AlgorithmId::Ed25519,
+            other_secret.as_slice(),
+            &sender_public,
+        )
+        .unwrap();
+        let forged =
+            sign_and_encrypt(SUITE, &impostor, &recipient_public, b"message", b"").unwrap();
+        assert_eq!(
+            decrypt_and_verify(
+                SUITE,
+                AlgorithmId::Ed25519,
+                &sender_public,
+                &recipient_public,
+                recipient_secret.as_slice(),
+                &forged,
+                b"",
+            )
+            .unwrap_err()
+            .crypto(),
+            Some(CryptoError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn rejects_non_signature_algorithms() {
+        assert_eq!(
+            Sender::new(AlgorithmId::X25519, &[0u8; 32], &[0u8; 32])
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::InvalidAlgorithmIdentifier)
+        );
+    }
+}
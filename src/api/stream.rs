@@ -0,0 +1,1551 @@
+//! Streaming authenticated encryption (STREAM).
+//!
+//! # Purpose
+//!
+//! A one-shot AEAD needs the whole message in memory and verifies it only
+//! at the end. [`StreamingEncryptor`] and [`StreamingDecryptor`] split a
+//! message into chunks that are encrypted and verified one at a time, so
+//! files of any size can be processed in constant memory, while the
+//! stream as a whole stays authenticated.
+//!
+//! # Construction
+//!
+//! The STREAM construction of Hoang, Reyhanitabar, Rogaway and Vizár, as
+//! used by Tink and age:
+//!
+//! - The stream [header](StreamingEncryptor::header) holds the algorithm,
+//!   the chunk size and a fresh 32-byte salt. HKDF-SHA256 derives a
+//!   per-stream key from the caller's key and the whole header, so nonces
+//!   never repeat across streams under the same key and a modified header
+//!   fails to decrypt
+//! - Chunk `i` is sealed under the nonce `0... || i (u32, big-endian) ||
+//!   last`, where `last` is 1 for the final chunk and 0 otherwise
+//! - Every chunk is authenticated with the stream's associated data
+//!
+//! A dropped, duplicated or reordered chunk changes some chunk's nonce and
+//! fails to decrypt; a stream cut at a chunk boundary is detected because
+//! its last chunk was sealed with `last = 0`.
+//!
+//! # I/O Adapters
+//!
+//! [`EncryptingWriter`] and [`DecryptingReader`] wrap any
+//! [`Write`](std::io::Write) or [`Read`](std::io::Read) and do the
+//! chunking themselves (feature `std`); with the `async` feature,
+//! `AsyncEncryptingWriter` and `AsyncDecryptingReader` do the same for
+//! tokio's `AsyncWrite` and `AsyncRead`. Their format is the header
+//! followed by chunks of the header's chunk size in plaintext bytes, the
+//! last one possibly shorter:
+//!
+//! ```text
+//! header = algorithm (2) || chunk_size (u32, big-endian) || salt (32)
+//! stream = header || chunk_0 || ... || chunk_n
+//! ```
+//!
+//! # Chunk Size
+//!
+//! Each chunk costs one tag and one AEAD call, so small chunks dominate the
+//! cost of large files, while large chunks make readers buffer more before
+//! they can return anything. The writers use [`DEFAULT_CHUNK_SIZE`] unless
+//! given another size between [`MIN_CHUNK_SIZE`] and [`MAX_CHUNK_SIZE`];
+//! readers take it from the header. [`bench::tune_chunk_size`] picks one
+//! from measured throughput.
+//!
+//! [`bench::tune_chunk_size`]: crate::bench::tune_chunk_size
+//!
+//! # Header Policy
+//!
+//! A [`StreamPolicy`] lists the algorithms a decryptor accepts and bounds
+//! the chunk size and chunk count with a [`ParseBudget`].
+//! [`StreamingDecryptor::begin`] and the readers' `with_policy`
+//! constructors check it against the header before accepting any chunk,
+//! and [`StreamPolicy::check`] does so without the key, so a proxy can turn
+//! a stream away after its first [`HEADER_SIZE`] bytes.
+//!
+//! # Security
+//!
+//! - Decrypted chunks are authentic but the stream is only known to be
+//!   complete once [`StreamingDecryptor::finish`] succeeds. Do not act on
+//!   the plaintext irreversibly before then
+//! - After a chunk fails to decrypt the decryptor refuses further chunks
+//! - A stream holds at most 2^32 chunks
+//!
+//! # Example
+//!
+//! ```ignore
+//! use citadel::algorithm::AlgorithmId;
+//! use citadel::stream::{StreamingDecryptor, StreamingEncryptor};
+//!
+//! let mut encryptor = StreamingEncryptor::new(AlgorithmId::Aes256GcmSiv, &key, b"backup.tar")?;
+//! let header = *encryptor.header();
+//! let first = encryptor.encrypt_chunk(&data[..65536])?;
+//! let last = encryptor.finish(&data[65536..])?;
+//!
+//! let mut decryptor = StreamingDecryptor::new(AlgorithmId::Aes256GcmSiv, &key, &header, b"backup.tar")?;
+//! let mut plaintext = decryptor.decrypt_chunk(&first)?;
+//! plaintext.extend(decryptor.finish(&last)?);
+//! ```
+
+use core::fmt;
+#[cfg(feature = "async")]
+use core::future::poll_fn;
+#[cfg(feature = "async")]
+use core::pin::Pin;
+#[cfg(feature = "async")]
+use core::task::{Context, Poll, ready};
+#[cfg(feature = "std")]
+use std::io::{self, Read, Write};
+
+#[cfg(feature = "async")]
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::algorithm::{AlgorithmId, AlgorithmKind};
+use crate::algorithms::classical::hkdf;
+use crate::algorithms::classical::sha256::Sha256;
+use crate::context;
+use crate::encoding::{ParseBudget, Reader};
+#[cfg(feature = "std")]
+use crate::errors::Error;
+use crate::errors::{MisuseError, Result};
+use crate::internal::{aead, entropy};
+use crate::memory::{SecureBuffer, SensitiveBytes};
+
+/// Size of the stream header (the algorithm, the chunk size and the
+/// key-derivation salt) in bytes.
+pub const HEADER_SIZE: usize = AlgorithmId::SIZE + 4 + SALT_SIZE;
+
+/// A reasonable plaintext chunk size, 64 KiB.
+pub const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Smallest plaintext chunk size, 1 KiB.
+pub const MIN_CHUNK_SIZE: usize = 1024;
+
+/// Largest plaintext chunk size, 16 MiB.
+pub const MAX_CHUNK_SIZE: usize = 16 * 1024 * 1024;
+
+/// Size of the key-derivation salt in bytes.
+const SALT_SIZE: usize = 32;
+
+/// Domain separator of the per-stream key.
+const KEY_LABEL: &[u8] = b"citadel stream v2";
+
+/// Counter and last-chunk flag at the end of every nonce.
+const NONCE_SUFFIX_SIZE: usize = 4 + 1;
+
+/// State shared by both directions.
+struct Stream {
+    algorithm: AlgorithmId,
+    key: SecureBuffer,
+    associated_data: Vec<u8>,
+    nonce_size: usize,
+    /// Largest plaintext a chunk may hold.
+    chunk_size: usize,
+    counter: u32,
+    exhausted: bool,
+}
+
+/// A parsed stream header.
+struct Header<'a> {
+    algorithm: AlgorithmId,
+    chunk_size: usize,
+    salt: &'a [u8],
+}
+
+impl<'a> Header<'a> {
+    fn parse(header: &'a [u8]) -> Result<Self> {
+        let mut reader = Reader::new(header);
+        let algorithm = AlgorithmId::from_bytes(reader.take(AlgorithmId::SIZE)?)
+            .map_err(|_| MisuseError::InvalidEncoding)?;
+        let size = reader.take(4)?;
+        let chunk_size = u32::from_be_bytes(size.try_into().expect("took 4 bytes")) as usize;
+        let salt = reader.take(SALT_SIZE)?;
+        reader.finish()?;
+        if !(MIN_CHUNK_SIZE..=MAX_CHUNK_SIZE).contains(&chunk_size) {
+            return Err(MisuseError::InvalidEncoding.into());
+        }
+        Ok(Self {
+            algorithm,
+            chunk_size,
+            salt,
+        })
+    }
+}
+
+impl Stream {
+    fn new(key: &[u8], header: &[u8], associated_data: &[u8]) -> Result<Self> {
+        context::checkpoint()?;
+        let Header {
+            algorithm,
+            chunk_size,
+            salt,
+        } = Header::parse(header)?;
+        if algorithm.kind() != AlgorithmKind::Aead {
+            return Err(MisuseError::InvalidAlgorithmIdentifier.into());
+        }
+        if !algorithm.is_enabled() {
+            return Err(MisuseError::FeatureNotEnabled.into());
+        }
+        let sizes = algorithm.sizes();
+        let key_size = sizes.secret_key.unwrap_or(0);
+        if key.len() != key_size {
+            return Err(MisuseError::InvalidKeyLength.into());
+        }
+
+        let prk = SensitiveBytes::new(hkdf::extract::<Sha256, 32>(salt, key));
+        let info = [KEY_LABEL, &header[..HEADER_SIZE - SALT_SIZE]].concat();
+        let mut stream_key = SecureBuffer::zeroed(key_size);
+        hkdf::expand::<Sha256, 32>(prk.as_bytes(), &info, stream_key.as_mut_slice());
+
+        Ok(Self {
+            algorithm,
+            key: stream_key,
+            associated_data: associated_data.to_vec(),
+            nonce_size: sizes.nonce.unwrap_or(0),
+            chunk_size,
+            counter: 0,
+            exhausted: false,
+        })
+    }
+
+    /// The nonce of the next chunk.
+    fn nonce(&self, last: bool) -> Result<Vec<u8>> {
+        if self.exhausted {
+            return Err(MisuseError::NonceExhausted.into());
+        }
+        let mut nonce = vec![0u8; self.nonce_size];
+        let suffix = self.nonce_size - NONCE_SUFFIX_SIZE;
+        nonce[suffix..suffix + 4].copy_from_slice(&self.counter.to_be_bytes());
+        nonce[self.nonce_size - 1] = u8::from(last);
+        Ok(nonce)
+    }
+
+    fn advance(&mut self) {
+        match self.counter.checked_add(1) {
+            Some(counter) => self.counter = counter,
+            None => self.exhausted = true,
+        }
+    }
+
+    fn seal(&mut self, plaintext: &[u8], last: bool) -> Result<Vec<u8>> {
+        context::checkpoint()?;
+        if plaintext.len() > self.chunk_size {
+            return Err(MisuseError::InvalidPlaintextLength.into());
+        }
+        let nonce = self.nonce(last)?;
+        let ciphertext = aead::seal(
+            self.algorithm,
+            self.key.as_slice(),
+            &nonce,
+            &self.associated_data,
+            plaintext,
+        )?;
+        self.advance();
+        Ok(ciphertext)
+    }
+
+    fn open(&mut self, ciphertext: &[u8], last: bool) -> Result<Vec<u8>> {
+        context::checkpoint()?;
+        if ciphertext.len() > self.chunk_size + self.algorithm.sizes().tag.unwrap_or(0) {
+            return Err(MisuseError::InvalidCiphertextLength.into());
+        }
+        let nonce = self.nonce(last)?;
+        let plaintext = aead::open(
+            self.algorithm,
+            self.key.as_slice(),
+            &nonce,
+            &self.associated_data,
+            ciphertext,
+        )?;
+        self.advance();
+        Ok(plaintext)
+    }
+}
+
+/// Encrypts a stream chunk by chunk.
+pub struct StreamingEncryptor {
+    stream: Stream,
+    header: [u8; HEADER_SIZE],
+}
+
+crate::memory::never_serialize!([] StreamingEncryptor);
+
+impl StreamingEncryptor {
+    /// Start a stream of chunks of up to [`DEFAULT_CHUNK_SIZE`] bytes,
+    /// encrypted with `algorithm` under `key`, with `associated_data`
+    /// authenticated by every chunk.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidAlgorithmIdentifier`: If `algorithm` is not
+    ///   an AEAD
+    /// - `MisuseError::FeatureNotEnabled`: If the algorithm's feature is off
+    /// - `MisuseError::InvalidKeyLength`: If `key` has the wrong length
+    /// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+    pub fn new(algorithm: AlgorithmId, key: &[u8], associated_data: &[u8]) -> Result<Self> {
+        Self::with_chunk_size(algorithm, key, associated_data, DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Start a stream of chunks of up to `chunk_size` bytes. The size is
+    /// recorded in the header.
+    ///
+    /// # Errors
+    ///
+    /// As for [`new`](Self::new), and:
+    ///
+    /// - `MisuseError::InvalidParameterSet`: If `chunk_size` is not between
+    ///   [`MIN_CHUNK_SIZE`] and [`MAX_CHUNK_SIZE`]
+    pub fn with_chunk_size(
+        algorithm: AlgorithmId,
+        key: &[u8],
+        associated_data: &[u8],
+        chunk_size: usize,
+    ) -> Result<Self> {
+        if !(MIN_CHUNK_SIZE..=MAX_CHUNK_SIZE).contains(&chunk_size) {
+            return Err(MisuseError::InvalidParameterSet.into());
+        }
+        let mut header = [0u8; HEADER_SIZE];
+        let (fields, salt) = header.split_at_mut(HEADER_SIZE - SALT_SIZE);
+        fields[..AlgorithmId::SIZE].copy_from_slice(&algorithm.to_bytes());
+        fields[AlgorithmId::SIZE..].copy_from_slice(&(chunk_size as u32).to_be_bytes());
+        entropy::fill(salt)?;
+        let stream = Stream::new(key, &header, associated_data)?;
+        Ok(Self { stream, header })
+    }
+
+    /// The stream header. The decryptor needs it; store or send it before
+    /// the chunks.
+    pub fn header(&self) -> &[u8; HEADER_SIZE] {
+        &self.header
+    }
+
+    /// The largest plaintext a chunk may hold.
+    pub fn chunk_size(&self) -> usize {
+        self.stream.chunk_size
+    }
+
+    /// The number of chunks encrypted so far.
+    pub fn chunks(&self) -> u32 {
+        self.stream.counter
+    }
+
+    /// Encrypt the next chunk, which is not the last.
+    ///
+    /// The result is `ciphertext || tag`, one tag longer than `plaintext`.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidPlaintextLength`: If `plaintext` is longer
+    ///   than the [chunk size](Self::chunk_size)
+    /// - `MisuseError::NonceExhausted`: If the stream already has 2^32
+    ///   chunks
+    pub fn encrypt_chunk(&mut self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        self.stream.seal(plaintext, false)
+    }
+
+    /// Encrypt the last chunk, which may be empty, and end the stream.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidPlaintextLength`: If `plaintext` is longer
+    ///   than the [chunk size](Self::chunk_size)
+    /// - `MisuseError::NonceExhausted`: If the stream already has 2^32
+    ///   chunks
+    pub fn finish(mut self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        self.stream.seal(plaintext, true)
+    }
+}
+
+impl fmt::Debug for StreamingEncryptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamingEncryptor")
+            .field("algorithm", &self.stream.algorithm)
+            .field("chunks", &self.stream.counter)
+            .finish_non_exhaustive()
+    }
+}
+
+/// What a decryptor accepts from a stream header.
+///
+/// [`check`](Self::check) needs only the header, not the key, so a proxy
+/// can reject a stream after reading its first [`HEADER_SIZE`] bytes;
+/// [`StreamingDecryptor::begin`] applies the same check before any chunk is
+/// accepted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamPolicy<'a> {
+    /// Algorithms the stream may be encrypted with.
+    pub algorithms: &'a [AlgorithmId],
+    /// `max_output_len` bounds the chunk size, and so what a reader
+    /// buffers; `max_chunks` bounds the number of chunks. The other limits
+    /// are unused.
+    pub budget: ParseBudget,
+}
+
+impl<'a> StreamPolicy<'a> {
+    /// Accept streams encrypted with any of `algorithms`, of any length.
+    pub const fn new(algorithms: &'a [AlgorithmId]) -> Self {
+        Self {
+            algorithms,
+            budget: ParseBudget {
+                max_input_len: usize::MAX,
+                max_output_len: usize::MAX,
+                max_recipients: usize::MAX,
+                max_chunks: usize::MAX,
+            },
+        }
+    }
+
+    /// Set the budget.
+    pub const fn with_budget(mut self, budget: ParseBudget) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Check a stream header against the policy.
+    ///
+    /// The header is only authenticated once the first chunk decrypts, but
+    /// a forged header can only get a stream rejected: one that passes with
+    /// a changed algorithm or chunk size derives another key and fails.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidEncoding`: If `header` is not
+    ///   [`HEADER_SIZE`] bytes, or its algorithm is unknown or its chunk
+    ///   size out of bounds
+    /// - `MisuseError::UnsupportedAlgorithm`: If the header's algorithm is
+    ///   not in [`algorithms`](Self::algorithms)
+    /// - `MisuseError::ParseBudgetExceeded`: If the header's chunk size
+    ///   exceeds the budget's `max_output_len`
+    pub fn check(&self, header: &[u8]) -> Result<()> {
+        let header = Header::parse(header)?;
+        if !self.algorithms.contains(&header.algorithm) {
+            return Err(MisuseError::UnsupportedAlgorithm.into());
+        }
+        self.budget.check_output(header.chunk_size)
+    }
+}
+
+/// Decrypts and verifies a stream chunk by chunk.
+pub struct StreamingDecryptor {
+    stream: Stream,
+    /// Most chunks the policy allows.
+    max_chunks: usize,
+    failed: bool,
+}
+
+crate::memory::never_serialize!([] StreamingDecryptor);
+
+impl StreamingDecryptor {
+    /// Start decrypting the stream with `header`, encrypted with
+    /// `algorithm` under `key` and `associated_data`.
+    ///
+    /// # Errors
+    ///
+    /// As for [`begin`](Self::begin) with a policy accepting only
+    /// `algorithm`.
+    pub fn new(
+        algorithm: AlgorithmId,
+        key: &[u8],
+        header: &[u8],
+        associated_data: &[u8],
+    ) -> Result<Self> {
+        Self::begin(
+            key,
+            header,
+            associated_data,
+            &StreamPolicy::new(&[algorithm]),
+        )
+    }
+
+    /// Check `header` against `policy`, then start decrypting the stream
+    /// under `key` and `associated_data`.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidEncoding`, `MisuseError::UnsupportedAlgorithm`
+    ///   or `MisuseError::ParseBudgetExceeded`: If the header breaks the
+    ///   policy, as for [`StreamPolicy::check`]
+    /// - `MisuseError::InvalidAlgorithmIdentifier`: If the header's
+    ///   algorithm is not an AEAD
+    /// - `MisuseError::FeatureNotEnabled`: If the algorithm's feature is off
+    /// - `MisuseError::InvalidKeyLength`: If `key` has the wrong length
+    pub fn begin(
+        key: &[u8],
+        header: &[u8],
+        associated_data: &[u8],
+        policy: &StreamPolicy<'_>,
+    ) -> Result<Self> {
+        policy.check(header)?;
+        Ok(Self {
+            stream: Stream::new(key, header, associated_data)?,
+            max_chunks: policy.budget.max_chunks,
+            failed: false,
+        })
+    }
+
+    /// The algorithm named by the header.
+    pub fn algorithm(&self) -> AlgorithmId {
+        self.stream.algorithm
+    }
+
+    /// The largest plaintext a chunk may hold, as recorded in the header.
+    pub fn chunk_size(&self) -> usize {
+        self.stream.chunk_size
+    }
+
+    /// The number of chunks decrypted so far.
+    pub fn chunks(&self) -> u32 {
+        self.stream.counter
+    }
+
+    /// Verify and decrypt the next chunk, which is not the last.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::DecryptionFailed`: If the chunk was modified, is out
+    ///   of order, is the stream's last chunk, or the key or associated
+    ///   data differ
+    /// - `MisuseError::InvalidCiphertextLength`: If `ciphertext` is shorter
+    ///   than a tag or longer than a full chunk
+    /// - `MisuseError::ParseBudgetExceeded`: If the stream has more chunks
+    ///   than the policy's budget allows
+    /// - `MisuseError::InvalidState`: If an earlier chunk failed
+    pub fn decrypt_chunk(&mut self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        self.open(ciphertext, false)
+    }
+
+    /// Verify and decrypt the last chunk, ending the stream.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::DecryptionFailed`: If the chunk was modified, is out
+    ///   of order, is not the stream's last chunk (the stream was
+    ///   truncated), or the key or associated data differ
+    /// - `MisuseError::InvalidCiphertextLength`: If `ciphertext` is shorter
+    ///   than a tag or longer than a full chunk
+    /// - `MisuseError::ParseBudgetExceeded`: If the stream has more chunks
+    ///   than the policy's budget allows
+    /// - `MisuseError::InvalidState`: If an earlier chunk failed
+    pub fn finish(mut self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        self.open(ciphertext, true)
+    }
+
+    fn open(&mut self, ciphertext: &[u8], last: bool) -> Result<Vec<u8>> {
+        if self.failed {
+            return Err(MisuseError::InvalidState.into());
+        }
+        let result = if self.stream.counter as usize >= self.max_chunks {
+            Err(MisuseError::ParseBudgetExceeded.into())
+        } else {
+            self.stream.open(ciphertext, last)
+        };
+        self.failed = result.is_err();
+        result
+    }
+}
+
+impl fmt::Debug for StreamingDecryptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamingDecryptor")
+            .field("algorithm", &self.stream.algorithm)
+            .field("chunks", &self.stream.counter)
+            .field("failed", &self.failed)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Encrypts everything written to it into a stream on `W`.
+///
+/// Data is buffered until a whole chunk is available. Call
+/// [`finish`](Self::finish) after the last write: it writes the final
+/// chunk, without which the stream reads as truncated. Dropping the writer
+/// without finishing it leaves a stream that fails to decrypt.
+///
+/// A failed `write` consumes nothing: a chunk that could not be written to
+/// `W` is kept and written first on the next call, so retrying after an
+/// error neither duplicates nor loses data.
+#[cfg(feature = "std")]
+pub struct EncryptingWriter<W: Write> {
+    inner: W,
+    encryptor: StreamingEncryptor,
+    buffer: Vec<u8>,
+    /// Ciphertext not yet written to `inner`, from `written` on.
+    output: Vec<u8>,
+    written: usize,
+}
+
+#[cfg(feature = "std")]
+impl<W: Write> EncryptingWriter<W> {
+    /// Start a stream of [`DEFAULT_CHUNK_SIZE`] chunks on `inner` and write
+    /// its header.
+    ///
+    /// # Errors
+    ///
+    /// - [`io::ErrorKind::InvalidInput`]: If `algorithm` or `key` is
+    ///   invalid, as for [`StreamingEncryptor::new`]
+    /// - Any error writing to `inner`
+    pub fn new(
+        inner: W,
+        algorithm: AlgorithmId,
+        key: &[u8],
+        associated_data: &[u8],
+    ) -> io::Result<Self> {
+        Self::with_chunk_size(inner, algorithm, key, associated_data, DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Start a stream of `chunk_size` chunks on `inner` and write its
+    /// header.
+    ///
+    /// # Errors
+    ///
+    /// - [`io::ErrorKind::InvalidInput`]: If `algorithm`, `key` or
+    ///   `chunk_size` is invalid, as for
+    ///   [`StreamingEncryptor::with_chunk_size`]
+    /// - Any error writing to `inner`
+    pub fn with_chunk_size(
+        mut inner: W,
+        algorithm: AlgorithmId,
+        key: &[u8],
+        associated_data: &[u8],
+        chunk_size: usize,
+    ) -> io::Result<Self> {
+        let encryptor =
+            StreamingEncryptor::with_chunk_size(algorithm, key, associated_data, chunk_size)
+                .map_err(io_error)?;
+        inner.write_all(encryptor.header())?;
+        Ok(Self {
+            inner,
+            encryptor,
+            buffer: Vec::with_capacity(chunk_size),
+            output: Vec::new(),
+            written: 0,
+        })
+    }
+
+    /// Write the final chunk, flush, and return the inner writer.
+    ///
+    /// # Errors
+    ///
+    /// - Any error writing to or flushing `inner`
+    pub fn finish(mut self) -> io::Result<W> {
+        self.write_output()?;
+        let Self {
+            mut inner,
+            encryptor,
+            buffer,
+            ..
+        } = self;
+        inner.write_all(&encryptor.finish(&buffer).map_err(io_error)?)?;
+        inner.flush()?;
+        Ok(inner)
+    }
+
+    /// Write out buffered ciphertext.
+    fn write_output(&mut self) -> io::Result<()> {
+        while self.written < self.output.len() {
+            match self.inner.write(&self.output[self.written..]) {
+                Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+                Ok(written) => self.written += written,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                Err(error) => return Err(error),
+            }
+        }
+        self.output.clear();
+        self.written = 0;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: Write> Write for EncryptingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_output()?;
+        // A full chunk is only known not to be the last once more follows.
+        // It is encrypted before `buf` is touched, so an error consumes
+        // nothing
+        let chunk_size = self.encryptor.chunk_size();
+        if self.buffer.len() == chunk_size && !buf.is_empty() {
+            self.output = self
+                .encryptor
+                .encrypt_chunk(&self.buffer)
+                .map_err(io_error)?;
+            self.buffer.clear();
+            self.write_output()?;
+        }
+        let accepted = buf.len().min(chunk_size - self.buffer.len());
+        self.buffer.extend_from_slice(&buf[..accepted]);
+        Ok(accepted)
+    }
+
+    /// Write out encrypted chunks and flush the inner writer. A partial
+    /// chunk stays buffered until it fills or [`finish`](Self::finish) is
+    /// called.
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_output()?;
+        self.inner.flush()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: Write> fmt::Debug for EncryptingWriter<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EncryptingWriter")
+            .field("encryptor", &self.encryptor)
+            .field("buffered", &self.buffer.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Decrypts and verifies a stream read from `R`.
+///
+/// Each chunk is verified before any of its plaintext is returned. The
+/// reader reports end of file only after the final chunk verified, so a
+/// truncated stream ends in an error rather than a short read: corrupt or
+/// truncated ciphertext is reported as [`io::ErrorKind::InvalidData`].
+#[cfg(feature = "std")]
+pub struct DecryptingReader<R: Read> {
+    inner: R,
+    chunks: ChunkReader,
+}
+
+#[cfg(feature = "std")]
+impl<R: Read> DecryptingReader<R> {
+    /// Read the stream header from `inner` and prepare to decrypt a stream
+    /// encrypted with `algorithm`.
+    ///
+    /// # Errors
+    ///
+    /// As for [`with_policy`](Self::with_policy) with a policy accepting
+    /// only `algorithm`.
+    pub fn new(
+        inner: R,
+        algorithm: AlgorithmId,
+        key: &[u8],
+        associated_data: &[u8],
+    ) -> io::Result<Self> {
+        Self::with_policy(
+            inner,
+            key,
+            associated_data,
+            &StreamPolicy::new(&[algorithm]),
+        )
+    }
+
+    /// Read the stream header from `inner`, check it against `policy`, and
+    /// prepare to decrypt. Nothing past the header is read before the
+    /// check.
+    ///
+    /// # Errors
+    ///
+    /// - [`io::ErrorKind::UnexpectedEof`]: If `inner` ends within the header
+    /// - [`io::ErrorKind::InvalidData`]: If the header is malformed or
+    ///   breaks the policy, as for [`StreamPolicy::check`]
+    /// - [`io::ErrorKind::InvalidInput`]: If the algorithm or `key` is
+    ///   invalid, as for [`StreamingDecryptor::begin`]
+    /// - Any error reading from `inner`
+    pub fn with_policy(
+        mut inner: R,
+        key: &[u8],
+        associated_data: &[u8],
+        policy: &StreamPolicy<'_>,
+    ) -> io::Result<Self> {
+        let mut header = [0u8; HEADER_SIZE];
+        inner.read_exact(&mut header)?;
+        let chunks = ChunkReader::new(key, &header, associated_data, policy)?;
+        Ok(Self { inner, chunks })
+    }
+
+    /// The inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: Read> Read for DecryptingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let read = self.chunks.read_plaintext(buf);
+            if read > 0 || buf.is_empty() || self.chunks.is_done() {
+                return Ok(read);
+            }
+            while self.chunks.needs_input() {
+                match self.inner.read(self.chunks.spare()) {
+                    Ok(0) => break,
+                    Ok(read) => self.chunks.filled(read),
+                    Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                    Err(error) => return Err(error),
+                }
+            }
+            self.chunks.decrypt()?;
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: Read> fmt::Debug for DecryptingReader<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DecryptingReader")
+            .field("chunks", &self.chunks)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Chunk framing shared by the sync and async readers: ciphertext is read
+/// into `spare` until one byte past a full chunk is available, which shows
+/// that chunk is not the last, or the input ends.
+#[cfg(feature = "std")]
+struct ChunkReader {
+    /// `None` once the final chunk has been decrypted.
+    decryptor: Option<StreamingDecryptor>,
+    /// Size of a full ciphertext chunk.
+    chunk_size: usize,
+    /// Ciphertext read ahead of decryption, `chunk_size + 1` bytes of which
+    /// the first `filled` are valid.
+    pending: Vec<u8>,
+    filled: usize,
+    plaintext: Vec<u8>,
+    position: usize,
+    /// Set when a chunk failed; later reads fail too rather than end.
+    failed: bool,
+}
+
+#[cfg(feature = "std")]
+impl ChunkReader {
+    fn new(
+        key: &[u8],
+        header: &[u8; HEADER_SIZE],
+        associated_data: &[u8],
+        policy: &StreamPolicy<'_>,
+    ) -> io::Result<Self> {
+        // A header that breaks the policy is bad input, not misuse
+        policy.check(header).map_err(invalid_data)?;
+        let decryptor =
+            StreamingDecryptor::begin(key, header, associated_data, policy).map_err(io_error)?;
+        let tag_size = decryptor.algorithm().sizes().tag.unwrap_or(0);
+        let chunk_size = decryptor.chunk_size() + tag_size;
+        Ok(Self {
+            decryptor: Some(decryptor),
+            chunk_size,
+            pending: vec![0u8; chunk_size + 1],
+            filled: 0,
+            plaintext: Vec::new(),
+            position: 0,
+            failed: false,
+        })
+    }
+
+    /// Whether all plaintext, including the final chunk's, has been read.
+    fn is_done(&self) -> bool {
+        !self.failed && self.decryptor.is_none() && self.position == self.plaintext.len()
+    }
+
+    /// Whether more ciphertext is needed before [`decrypt`](Self::decrypt),
+    /// unless the input has ended.
+    fn needs_input(&self) -> bool {
+        self.decryptor.is_some() && self.filled < self.pending.len()
+    }
+
+    /// Where to read more ciphertext.
+    fn spare(&mut self) -> &mut [u8] {
+        &mut self.pending[self.filled..]
+    }
+
+    /// Record that `read` bytes were read into [`spare`](Self::spare).
+    fn filled(&mut self, read: usize) {
+        self.filled += read;
+    }
+
+    /// Decrypt the next chunk: a full one if more ciphertext follows it,
+    /// otherwise the final one.
+    fn decrypt(&mut self) -> io::Result<()> {
+        let decrypted = match self.decryptor.as_mut() {
+            _ if self.failed => Err(MisuseError::InvalidState.into()),
+            None => return Ok(()),
+            Some(decryptor) if self.filled > self.chunk_size => {
+                let plaintext = decryptor.decrypt_chunk(&self.pending[..self.chunk_size]);
+                self.pending.copy_within(self.chunk_size..self.filled, 0);
+                self.filled -= self.chunk_size;
+                plaintext
+            }
+            Some(_) => {
+                let decryptor = self.decryptor.take().expect("matched above");
+                decryptor.finish(&self.pending[..self.filled])
+            }
+        };
+        match decrypted {
+            Ok(plaintext) => {
+                self.plaintext = plaintext;
+                self.position = 0;
+                Ok(())
+            }
+            Err(error) => {
+                self.failed = true;
+                Err(invalid_data(error))
+            }
+        }
+    }
+
+    /// Copy decrypted plaintext into `buf`.
+    fn read_plaintext(&mut self, buf: &mut [u8]) -> usize {
+        let available = &self.plaintext[self.position..];
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.position += len;
+        len
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Debug for ChunkReader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChunkReader")
+            .field("decryptor", &self.decryptor)
+            .field("pending", &self.filled)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Misuse becomes [`io::ErrorKind::InvalidInput`], anything else
+/// [`io::ErrorKind::Other`].
+#[cfg(feature = "std")]
+fn io_error(error: Error) -> io::Error {
+    if error.misuse().is_some() {
+        io::Error::new(io::ErrorKind::InvalidInput, error)
+    } else {
+        io::Error::other(error)
+    }
+}
+
+/// A chunk that fails to decrypt, including one too short to hold a tag,
+/// means the stream is corrupt or truncated.
+#[cfg(feature = "std")]
+fn invalid_data(error: Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+/// The async counterpart of [`EncryptingWriter`], for tokio.
+///
+/// Shut the writer down (`AsyncWriteExt::shutdown`) after the last write:
+/// that writes the final chunk, without which the stream reads as
+/// truncated. At most one chunk of plaintext and one of ciphertext are
+/// buffered, so a slow `W` applies backpressure.
+#[cfg(feature = "async")]
+pub struct AsyncEncryptingWriter<W: AsyncWrite + Unpin> {
+    inner: W,
+    /// `None` once the final chunk has been encrypted.
+    encryptor: Option<StreamingEncryptor>,
+    buffer: Vec<u8>,
+    /// Ciphertext not yet written to `inner`, from `written` on.
+    output: Vec<u8>,
+    written: usize,
+}
+
+#[cfg(feature = "async")]
+impl<W: AsyncWrite + Unpin> AsyncEncryptingWriter<W> {
+    /// Start a stream of [`DEFAULT_CHUNK_SIZE`] chunks on `inner`. The
+    /// header is written with the first chunk.
+    ///
+    /// # Errors
+    ///
+    /// - [`io::ErrorKind::InvalidInput`]: If `algorithm` or `key` is
+    ///   invalid, as for [`StreamingEncryptor::new`]
+    pub fn new(
+        inner: W,
+        algorithm: AlgorithmId,
+        key: &[u8],
+        associated_data: &[u8],
+    ) -> io::Result<Self> {
+        Self::with_chunk_size(inner, algorithm, key, associated_data, DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Start a stream of `chunk_size` chunks on `inner`. The header is
+    /// written with the first chunk.
+    ///
+    /// # Errors
+    ///
+    /// - [`io::ErrorKind::InvalidInput`]: If `algorithm`, `key` or
+    ///   `chunk_size` is invalid, as for
+    ///   [`StreamingEncryptor::with_chunk_size`]
+    pub fn with_chunk_size(
+        inner: W,
+        algorithm: AlgorithmId,
+        key: &[u8],
+        associated_data: &[u8],
+        chunk_size: usize,
+    ) -> io::Result<Self> {
+        let encryptor =
+            StreamingEncryptor::with_chunk_size(algorithm, key, associated_data, chunk_size)
+                .map_err(io_error)?;
+        Ok(Self {
+            inner,
+            output: encryptor.header().to_vec(),
+            encryptor: Some(encryptor),
+            buffer: Vec::with_capacity(chunk_size),
+            written: 0,
+        })
+    }
+
+    /// The inner writer. Only a complete stream if the writer was shut
+    /// down first.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Write out buffered ciphertext.
+    fn poll_output(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while self.written < self.output.len() {
+            let written =
+                ready!(Pin::new(&mut self.inner).poll_write(cx, &self.output[self.written..]))?;
+            if written == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+            self.written += written;
+        }
+        self.output.clear();
+        self.written = 0;
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "async")]
+impl<W: AsyncWrite + Unpin> AsyncWrite for AsyncEncryptingWriter<W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        ready!(this.poll_output(cx))?;
+        let Some(encryptor) = this.encryptor.as_mut() else {
+            return Poll::Ready(Err(io_error(MisuseError::InvalidState.into())));
+        };
+        // As for the sync writer, a full chunk is encrypted before `buf` is
+        // touched, so neither an error nor `Pending` consumes anything
+        let chunk_size = encryptor.chunk_size();
+        if this.buffer.len() == chunk_size && !buf.is_empty() {
+            this.output = encryptor.encrypt_chunk(&this.buffer).map_err(io_error)?;
+            this.buffer.clear();
+            ready!(this.poll_output(cx))?;
+        }
+        let accepted = buf.len().min(chunk_size - this.buffer.len());
+        this.buffer.extend_from_slice(&buf[..accepted]);
+        Poll::Ready(Ok(accepted))
+    }
+
+    /// Write out encrypted chunks and flush the inner writer. A partial
+    /// chunk stays buffered until it fills or the writer is shut down.
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_output(cx))?;
+        Pin::new(&mut this.inner).poll_flush(cx)
+    }
+
+    /// Encrypt and write the final chunk, then shut the inner writer down.
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_output(cx))?;
+        if let Some(encryptor) = this.encryptor.take() {
+            this.output = encryptor.finish(&this.buffer).map_err(io_error)?;
+            this.buffer.clear();
+            ready!(this.poll_output(cx))?;
+        }
+        Pin::new(&mut this.inner).poll_shutdown(cx)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<W: AsyncWrite + Unpin> fmt::Debug for AsyncEncryptingWriter<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AsyncEncryptingWriter")
+            .field("encryptor", &self.encryptor)
+            .field("buffered", &self.buffer.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// The async counterpart of [`DecryptingReader`], for tokio.
+///
+/// As with the sync reader, end of file is reported only after the final
+/// chunk verified, and corrupt or truncated ciphertext is reported as
+/// [`io::ErrorKind::InvalidData`].
+#[cfg(feature = "async")]
+pub struct AsyncDecryptingReader<R: AsyncRead + Unpin> {
+    inner: R,
+    chunks: ChunkReader,
+}
+
+#[cfg(feature = "async")]
+impl<R: AsyncRead + Unpin> AsyncDecryptingReader<R> {
+    /// Read the stream header from `inner` and prepare to decrypt a stream
+    /// encrypted with `algorithm`.
+    ///
+    /// # Errors
+    ///
+    /// As for [`with_policy`](Self::with_policy) with a policy accepting
+    /// only `algorithm`.
+    pub async fn new(
+        inner: R,
+        algorithm: AlgorithmId,
+        key: &[u8],
+        associated_data: &[u8],
+    ) -> io::Result<Self> {
+        let algorithms = [algorithm];
+        let policy = StreamPolicy::new(&algorithms);
+        Self::with_policy(inner, key, associated_data, &policy).await
+    }
+
+    /// Read the stream header from `inner`, check it against `policy`, and
+    /// prepare to decrypt. Nothing past the header is read before the
+    /// check.
+    ///
+    /// # Errors
+    ///
+    /// - [`io::ErrorKind::UnexpectedEof`]: If `inner` ends within the header
+    /// - [`io::ErrorKind::InvalidData`]: If the header is malformed or
+    ///   breaks the policy, as for [`StreamPolicy::check`]
+    /// - [`io::ErrorKind::InvalidInput`]: If the algorithm or `key` is
+    ///   invalid, as for [`StreamingDecryptor::begin`]
+    /// - Any error reading from `inner`
+    pub async fn with_policy(
+        mut inner: R,
+        key: &[u8],
+        associated_data: &[u8],
+        policy: &StreamPolicy<'_>,
+    ) -> io::Result<Self> {
+        let mut header = [0u8; HEADER_SIZE];
+        let mut filled = 0;
+        while filled < HEADER_SIZE {
+            let mut buf = ReadBuf::new(&mut header[filled..]);
+            poll_fn(|cx| Pin::new(&mut inner).poll_read(cx, &mut buf)).await?;
+            if buf.filled().is_empty() {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            filled += buf.filled().len();
+        }
+        let chunks = ChunkReader::new(key, &header, associated_data, policy)?;
+        Ok(Self { inner, chunks })
+    }
+
+    /// The inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+#[cfg(feature = "async")]
+impl<R: AsyncRead + Unpin> AsyncRead for AsyncDecryptingReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        loop {
+            let read = this.chunks.read_plaintext(buf.initialize_unfilled());
+            buf.advance(read);
+            if read > 0 || buf.remaining() == 0 || this.chunks.is_done() {
+                return Poll::Ready(Ok(()));
+            }
+            while this.chunks.needs_input() {
+                let mut spare = ReadBuf::new(this.chunks.spare());
+                ready!(Pin::new(&mut this.inner).poll_read(cx, &mut spare))?;
+                let read = spare.filled().len();
+                if read == 0 {
+                    break;
+                }
+                this.chunks.filled(read);
+            }
+            this.chunks.decrypt()?;
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<R: AsyncRead + Unpin> fmt::Debug for AsyncDecryptingReader<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AsyncDecryptingReader")
+            .field("chunks", &self.chunks)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(all(test, feature = "aes-gcm-siv"))]
+mod tests {
+    use super::*;
+    use crate::errors::CryptoError;
+
+    const ALGORITHM: AlgorithmId = AlgorithmId::Aes256GcmSiv;
+    const KEY: [u8; 32] = [3u8; 32];
+
+    fn encrypt(chunks: &[&[u8]]) -> ([u8; HEADER_SIZE], Vec<Vec<u8>>) {
+        let mut encryptor = StreamingEncryptor::new(ALGORITHM, &KEY, b"file").unwrap();
+        let header = *encryptor.header();
+        let (last, rest) = chunks.split_last().unwrap();
+        let mut sealed: Vec<_> = rest
+            .iter()
+            .map(|chunk| encryptor.encrypt_chunk(chunk).unwrap())
+            .collect();
+        sealed.push(encryptor.finish(last).unwrap());
+        (header, sealed)
+    }
+
+    fn decrypt(header: &[u8], sealed: &[Vec<u8>]) -> Result<Vec<u8>> {
+        let mut decryptor = StreamingDecryptor::new(ALGORITHM, &KEY, header, b"file")?;
+        let (last, rest) = sealed.split_last().unwrap();
+        let mut plaintext = Vec::new();
+        for chunk in rest {
+            plaintext.extend(decryptor.decrypt_chunk(chunk)?);
+        }
+        plaintext.extend(decryptor.finish(last)?);
+        Ok(plaintext)
+    }
+
+    fn crypto(result: Result<Vec<u8>>) -> Option<CryptoError> {
+        result.unwrap_err().crypto()
+    }
+
+    #[test]
+    fn round_trip() {
+        let (header, sealed) = encrypt(&[b"first ", b"second ", b"", b"last"]);
+        assert_eq!(sealed[0].len(), 6 + 16);
+        assert_eq!(decrypt(&header, &sealed).unwrap(), b"first second last");
+
+        let (header, sealed) = encrypt(&[b""]);
+        assert_eq!(decrypt(&header, &sealed).unwrap(), b"");
+    }
+
+    #[test]
+    fn detects_truncation_reordering_and_tampering() {
+        let (header, sealed) = encrypt(&[b"a", b"b", b"c"]);
+
+        // Cut after the second chunk
+        assert_eq!(
+            crypto(decrypt(&header, &sealed[..2])),
+            Some(CryptoError::DecryptionFailed)
+        );
+        // Swap the first two chunks
+        let swapped = vec![sealed[1].clone(), sealed[0].clone(), sealed[2].clone()];
+        assert_eq!(
+            crypto(decrypt(&header, &swapped)),
+            Some(CryptoError::DecryptionFailed)
+        );
+        // Drop a middle chunk
+        let dropped = vec![sealed[0].clone(), sealed[2].clone()];
+        assert_eq!(
+            crypto(decrypt(&header, &dropped)),
+            Some(CryptoError::DecryptionFailed)
+        );
+        // Another stream's header derives another key
+        let (other_header, _) = encrypt(&[b"x"]);
+        assert_eq!(
+            crypto(decrypt(&other_header, &sealed)),
+            Some(CryptoError::DecryptionFailed)
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn io_adapters_round_trip() {
+        for len in [0, 1, DEFAULT_CHUNK_SIZE, 2 * DEFAULT_CHUNK_SIZE + 5] {
+            let data: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            let mut writer = EncryptingWriter::new(Vec::new(), ALGORITHM, &KEY, b"file").unwrap();
+            for piece in data.chunks(1000) {
+                writer.write_all(piece).unwrap();
+            }
+            let sealed = writer.finish().unwrap();
+            let chunks = len.div_ceil(DEFAULT_CHUNK_SIZE).max(1);
+            assert_eq!(sealed.len(), HEADER_SIZE + len + 16 * chunks);
+
+            let mut reader = DecryptingReader::new(&sealed[..], ALGORITHM, &KEY, b"file").unwrap();
+            let mut plaintext = Vec::new();
+            reader.read_to_end(&mut plaintext).unwrap();
+            assert_eq!(plaintext, data);
+
+            // Cutting the final chunk is an error, not a short read
+            let cut = &sealed[..sealed.len() - 1];
+            let mut reader = DecryptingReader::new(cut, ALGORITHM, &KEY, b"file").unwrap();
+            assert_eq!(
+                reader.read_to_end(&mut Vec::new()).unwrap_err().kind(),
+                io::ErrorKind::InvalidData
+            );
+        }
+
+        // Dropping a whole trailing chunk is detected too
+        let mut writer = EncryptingWriter::new(Vec::new(), ALGORITHM, &KEY, b"").unwrap();
+        writer.write_all(&[0u8; DEFAULT_CHUNK_SIZE + 1]).unwrap();
+        let sealed = writer.finish().unwrap();
+        let cut = &sealed[..HEADER_SIZE + DEFAULT_CHUNK_SIZE + 16];
+        let mut reader = DecryptingReader::new(cut, ALGORITHM, &KEY, b"").unwrap();
+        assert_eq!(
+            reader.read_to_end(&mut Vec::new()).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn records_the_chunk_size_in_the_header() {
+        for chunk_size in [MIN_CHUNK_SIZE - 1, MAX_CHUNK_SIZE + 1] {
+            assert_eq!(
+                StreamingEncryptor::with_chunk_size(ALGORITHM, &KEY, b"", chunk_size)
+                    .unwrap_err()
+                    .misuse(),
+                Some(MisuseError::InvalidParameterSet)
+            );
+        }
+        let mut encryptor =
+            StreamingEncryptor::with_chunk_size(ALGORITHM, &KEY, b"", MIN_CHUNK_SIZE).unwrap();
+        assert_eq!(
+            encryptor
+                .encrypt_chunk(&[0u8; MIN_CHUNK_SIZE + 1])
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::InvalidPlaintextLength)
+        );
+
+        let data: Vec<u8> = (0..10_000).map(|i| i as u8).collect();
+        let mut writer =
+            EncryptingWriter::with_chunk_size(Vec::new(), ALGORITHM, &KEY, b"file", 4096).unwrap();
+        writer.write_all(&data).unwrap();
+        let sealed = writer.finish().unwrap();
+        assert_eq!(sealed.len(), HEADER_SIZE + data.len() + 16 * 3);
+        assert_eq!(sealed[2..6], 4096u32.to_be_bytes());
+
+        // The reader takes the chunk size from the header
+        let mut plaintext = Vec::new();
+        DecryptingReader::new(&sealed[..], ALGORITHM, &KEY, b"file")
+            .unwrap()
+            .read_to_end(&mut plaintext)
+            .unwrap();
+        assert_eq!(plaintext, data);
+
+        // A header with another chunk size derives another key, and one out
+        // of bounds is rejected before anything is allocated for it
+        for (size, error) in [
+            (8192u32, None),
+            (u32::MAX, Some(MisuseError::InvalidEncoding)),
+        ] {
+            let mut tampered = sealed.clone();
+            tampered[2..6].copy_from_slice(&size.to_be_bytes());
+            let result = DecryptingReader::new(&tampered[..], ALGORITHM, &KEY, b"file")
+                .and_then(|mut reader| reader.read_to_end(&mut Vec::new()));
+            let failure = result.unwrap_err();
+            assert_eq!(failure.kind(), io::ErrorKind::InvalidData);
+            let header = tampered[..HEADER_SIZE].to_vec();
+            assert_eq!(
+                StreamingDecryptor::new(ALGORITHM, &KEY, &header, b"file")
+                    .err()
+                    .and_then(|error| error.misuse()),
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn checks_the_header_against_a_policy() {
+        let (header, sealed) = encrypt(&[b"a", b"b", b"c"]);
+        let misuse = |policy: StreamPolicy<'_>| policy.check(&header).unwrap_err().misuse();
+
+        assert!(StreamPolicy::new(&[ALGORITHM]).check(&header).is_ok());
+        assert_eq!(
+            misuse(StreamPolicy::new(&[AlgorithmId::Aegis256])),
+            Some(MisuseError::UnsupportedAlgorithm)
+        );
+        let small = ParseBudget::DEFAULT.with_max_output_len(MIN_CHUNK_SIZE);
+        assert_eq!(
+            misuse(StreamPolicy::new(&[ALGORITHM]).with_budget(small)),
+            Some(MisuseError::ParseBudgetExceeded)
+        );
+        assert_eq!(
+            StreamingDecryptor::new(AlgorithmId::Aegis256, &KEY, &header, b"file")
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::UnsupportedAlgorithm)
+        );
+
+        // The chunk budget stops the stream at the first chunk past it
+        let policy =
+            StreamPolicy::new(&[ALGORITHM]).with_budget(ParseBudget::DEFAULT.with_max_chunks(2));
+        let mut decryptor = StreamingDecryptor::begin(&KEY, &header, b"file", &policy).unwrap();
+        assert_eq!(decryptor.algorithm(), ALGORITHM);
+        decryptor.decrypt_chunk(&sealed[0]).unwrap();
+        decryptor.decrypt_chunk(&sealed[1]).unwrap();
+        assert_eq!(
+            decryptor.finish(&sealed[2]).unwrap_err().misuse(),
+            Some(MisuseError::ParseBudgetExceeded)
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn readers_reject_a_stream_after_its_header() {
+        let mut writer = EncryptingWriter::new(Vec::new(), ALGORITHM, &KEY, b"file").unwrap();
+        writer.write_all(&[0u8; 100]).unwrap();
+        let sealed = writer.finish().unwrap();
+
+        let mut input = &sealed[..];
+        let policy = StreamPolicy::new(&[AlgorithmId::Aegis256]);
+        let error = DecryptingReader::with_policy(&mut input, &KEY, b"file", &policy).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(input.len(), sealed.len() - HEADER_SIZE);
+    }
+
+    /// Accepts at most 1000 bytes per write, and fails every third of the
+    /// first hundred writes.
+    #[cfg(feature = "std")]
+    struct Flaky {
+        written: Vec<u8>,
+        calls: usize,
+    }
+
+    #[cfg(feature = "std")]
+    impl Write for Flaky {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.calls += 1;
+            if self.calls.is_multiple_of(3) && self.calls < 100 {
+                return Err(io::ErrorKind::BrokenPipe.into());
+            }
+            let len = buf.len().min(1000);
+            self.written.extend_from_slice(&buf[..len]);
+            Ok(len)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn retries_after_inner_write_errors() {
+        let data: Vec<u8> = (0..2 * DEFAULT_CHUNK_SIZE + 5).map(|i| i as u8).collect();
+        let flaky = Flaky {
+            written: Vec::new(),
+            calls: 1,
+        };
+        let mut writer = EncryptingWriter::new(flaky, ALGORITHM, &KEY, b"file").unwrap();
+        let mut rest = &data[..];
+        let mut errors = 0;
+        while !rest.is_empty() {
+            match writer.write(rest) {
+                Ok(written) => rest = &rest[written..],
+                Err(_) => errors += 1,
+            }
+        }
+        // An error consumed nothing, so retrying loses and duplicates nothing
+        assert!(errors > 0);
+        let sealed = writer.finish().unwrap().written;
+
+        let mut plaintext = Vec::new();
+        DecryptingReader::new(&sealed[..], ALGORITHM, &KEY, b"file")
+            .unwrap()
+            .read_to_end(&mut plaintext)
+            .unwrap();
+        assert_eq!(plaintext, data);
+    }
+
+    /// Alternately yields `Pending` and at most 1000 bytes, to exercise
+    /// resumption.
+    #[cfg(feature = "async")]
+    struct Trickle<'a> {
+        data: &'a [u8],
+        ready: bool,
+    }
+
+    #[cfg(feature = "async")]
+    impl AsyncRead for Trickle<'_> {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            self.ready = !self.ready;
+            if !self.ready {
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            let len = self.data.len().min(buf.remaining()).min(1000);
+            buf.put_slice(&self.data[..len]);
+            self.data = &self.data[len..];
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[cfg(feature = "async")]
+    fn block_on<F: core::future::Future>(future: F) -> F::Output {
+        let mut future = core::pin::pin!(future);
+        let mut cx = Context::from_waker(core::task::Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn async_adapters_match_sync_format() {
+        let data: Vec<u8> = (0..2 * DEFAULT_CHUNK_SIZE + 5).map(|i| i as u8).collect();
+        let mut writer = AsyncEncryptingWriter::new(Vec::new(), ALGORITHM, &KEY, b"file").unwrap();
+        for piece in data.chunks(1000) {
+            let mut piece = piece;
+            while !piece.is_empty() {
+                let written =
+                    block_on(poll_fn(|cx| Pin::new(&mut writer).poll_write(cx, piece))).unwrap();
+                piece = &piece[written..];
+            }
+        }
+        block_on(poll_fn(|cx| Pin::new(&mut writer).poll_shutdown(cx))).unwrap();
+        let sealed = writer.into_inner();
+
+        // The sync reader reads what the async writer wrote, and back
+        let mut plaintext = Vec::new();
+        DecryptingReader::new(&sealed[..], ALGORITHM, &KEY, b"file")
+            .unwrap()
+            .read_to_end(&mut plaintext)
+            .unwrap();
+        assert_eq!(plaintext, data);
+
+        let read_all = |sealed: &[u8]| {
+            block_on(async {
+                let trickle = Trickle {
+                    data: sealed,
+                    ready: false,
+                };
+                let mut reader =
+                    AsyncDecryptingReader::new(trickle, ALGORITHM, &KEY, b"file").await?;
+                let mut plaintext = Vec::new();
+                let mut buf = [0u8; 4096];
+                loop {
+                    let mut read = ReadBuf::new(&mut buf);
+                    poll_fn(|cx| Pin::new(&mut reader).poll_read(cx, &mut read)).await?;
+                    if read.filled().is_empty() {
+                        return Ok::<_, io::Error>(plaintext);
+                    }
+                    plaintext.extend_from_slice(read.filled());
+                }
+            })
+        };
+        assert_eq!(read_all(&sealed).unwrap(), data);
+        assert_eq!(
+            read_all(&sealed[..sealed.len() - 1]).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn refuses_chunks_after_a_failure() {
+        let (header, sealed) = encrypt(&[b"a", b"b", b"c"]);
+        let mut decryptor = StreamingDecryptor::new(ALGORITHM, &KEY, &header, b"file").unwrap();
+        assert!(decryptor.decrypt_chunk(&sealed[1]).is_err());
+        assert_eq!(
+            decryptor.decrypt_chunk(&sealed[0]).unwrap_err().misuse(),
+            Some(MisuseError::InvalidState)
+        );
+        assert_eq!(
+            StreamingDecryptor::new(ALGORITHM, &KEY[..16], &header, b"")
+                .unwrap_err()
+                .misuse(),
+            Some(MisuseError::InvalidKeyLength)
+        );
+    }
+}
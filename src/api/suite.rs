@@ -0,0 +1,496 @@
+//! Cipher-suite presets.
+//!
+//! # Purpose
+//!
+//! Composing a KEM, a combiner, a KDF and an AEAD by hand leaves many ways
+//! to get one of them wrong. A [`Suite`] fixes all of them: generate a
+//! keypair for the suite, then [`Suite::seal`] messages to the public key
+//! and [`Suite::open`] them with the secret key. Applications pick one
+//! preset instead of composing primitives.
+//!
+//! # Suites
+//!
+//! - [`Suite::PqStandard`] (ID `0x0001`): X25519 + ML-KEM-768 hybrid with
+//!   [`Sha3Combiner`], HKDF over HMAC-SHA3-384, AES-256-GCM-SIV
+//! - [`Suite::PqOnly`] (ID `0x0002`): ML-KEM-768 alone, HKDF over
+//!   HMAC-SHA3-384, AES-256-GCM-SIV
+//!
+//! Requires the `mlkem` and `aes-gcm-siv` features.
+//!
+//! # Format
+//!
+//! Sealed messages are [envelopes](crate::envelope) whose `suite` field is
+//! the suite ID and whose body is `kem_ciphertext || ciphertext || tag`;
+//! the envelope header, followed by the caller's associated data, is the
+//! AEAD's associated data. Encoded public keys are prefixed with the
+//! 2-byte big-endian suite ID. [`Suite::from_header`] reads the suite of a
+//! message before opening it. IDs are stable, never reused, and below
+//! `0x0100`, clear of the [`AlgorithmId`](crate::algorithm::AlgorithmId)
+//! codes sharing the envelope field.
+//!
+//! # Security
+//!
+//! - Every message encapsulates a fresh shared secret, so the AEAD key and
+//!   nonce are derived per message and never repeat
+//! - The suite ID is bound into the key derivation and authenticated with
+//!   the rest of the header, so a header rewritten to another suite fails
+//!   to open
+//! - Sealing is anonymous: anyone with the public key can seal. Sign the
+//!   message as well when the recipient must know who sent it
+//!
+//! # Example
+//!
+//! ```ignore
+//! use citadel::suite::Suite;
+//!
+//! let (public_key, secret_key) = Suite::PqStandard.generate_keypair()?;
+//! let sealed = Suite::seal(&public_key, b"message", b"header")?;
+//! let plaintext = Suite::open(&secret_key, &sealed, b"header")?;
+//! ```
+
+use core::fmt;
+
+use crate::advisor::{AeadChoice, KemChoice};
+use crate::algorithms::classical::aes256_gcm_siv as aead_imp;
+use crate::algorithms::classical::hkdf;
+use crate::algorithms::classical::keccak::Sha3;
+use crate::algorithms::classical::x25519::{self, X25519};
+use crate::algorithms::pq::ml_kem_768 as kem_imp;
+use crate::context;
+use crate::envelope;
+use crate::errors::{MisuseError, Result};
+use crate::hybrid::{HybridCiphertext, HybridKem, HybridPublicKey, HybridSecretKey, Sha3Combiner};
+use crate::internal::traits::DhKem;
+use crate::kem::{
+    MlKem768, MlKem768Ciphertext, MlKem768PublicKey, MlKem768SecretKey, SharedSecret,
+};
+use crate::memory::SensitiveBytes;
+
+type HybridPk = HybridPublicKey<{ kem_imp::PUBLIC_KEY_SIZE }, { x25519::PUBLIC_KEY_SIZE }>;
+type HybridSk = HybridSecretKey<
+    { kem_imp::SECRET_KEY_SIZE },
+    { x25519::SECRET_KEY_SIZE },
+    { kem_imp::PUBLIC_KEY_SIZE },
+    { x25519::PUBLIC_KEY_SIZE },
+>;
+type HybridCt = HybridCiphertext<{ kem_imp::CIPHERTEXT_SIZE }, { x25519::PUBLIC_KEY_SIZE }>;
+
+const HYBRID: HybridKem<kem_imp::MlKem768, DhKem<X25519>> =
+    HybridKem::new(kem_imp::MlKem768, DhKem(X25519), Sha3Combiner);
+
+/// Key derivation label; followed by the suite ID.
+const LABEL: &[u8] = b"citadel suite v1";
+
+/// Derived AEAD key followed by the AEAD nonce.
+const OKM_SIZE: usize = aead_imp::KEY_SIZE + aead_imp::NONCE_SIZE;
+
+/// Cipher-suite preset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Suite {
+    /// X25519 + ML-KEM-768 hybrid, HKDF-SHA3-384, AES-256-GCM-SIV.
+    PqStandard,
+    /// ML-KEM-768 alone, HKDF-SHA3-384, AES-256-GCM-SIV.
+    PqOnly,
+}
+
+impl Suite {
+    /// Every suite, in ID order.
+    pub const ALL: &[Suite] = &[Suite::PqStandard, Suite::PqOnly];
+    /// Size of the suite ID prefix on encoded public keys.
+    pub const HEADER_SIZE: usize = 2;
+
+    /// Stable numeric ID, as serialized in headers.
+    pub const fn id(self) -> u16 {
+        match self {
+            Suite::PqStandard => 0x0001,
+            Suite::PqOnly => 0x0002,
+        }
+    }
+
+    /// The suite with ID `id`, if any.
+    pub const fn from_id(id: u16) -> Option<Self> {
+        match id {
+            0x0001 => Some(Suite::PqStandard),
+            0x0002 => Some(Suite::PqOnly),
+            _ => None,
+        }
+    }
+
+    /// The suite named by the envelope header of `sealed`.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidEncoding`: If `sealed` does not start with a
+    ///   valid envelope header
+    /// - `MisuseError::InvalidAlgorithmIdentifier`: If the ID is not a
+    ///   known suite
+    pub fn from_header(sealed: &[u8]) -> Result<Self> {
+        let (suite, _, _) = envelope::split(sealed)?;
+        Self::from_bytes(suite)
+    }
+
+    fn from_bytes(id: [u8; 2]) -> Result<Self> {
+        Self::from_id(u16::from_be_bytes(id))
+            .ok_or_else(|| MisuseError::InvalidAlgorithmIdentifier.into())
+    }
+
+    /// The key encapsulation this suite uses.
+    pub const fn kem(self) -> KemChoice {
+        match self {
+            Suite::PqStandard => KemChoice::X25519MlKem768,
+            Suite::PqOnly => KemChoice::MlKem768,
+        }
+    }
+
+    /// The AEAD this suite uses.
+    pub const fn aead(self) -> AeadChoice {
+        AeadChoice::Aes256GcmSiv
+    }
+
+    /// Generate a keypair for this suite.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+    /// - `CryptoError::RandomnessFailure`: If the system RNG fails a health test
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    pub fn generate_keypair(self) -> Result<(SuitePublicKey, SuiteSecretKey)> {
+        context::checkpoint()?;
+        Ok(match self {
+            Suite::PqStandard => {
+                let (public_key, secret_key) = HYBRID.generate_keypair()?;
+                (
+                    SuitePublicKey(PublicKeyInner::PqStandard(public_key)),
+                    SuiteSecretKey(SecretKeyInner::PqStandard(Box::new(secret_key))),
+                )
+            }
+            Suite::PqOnly => {
+                let (public_key, secret_key) = MlKem768::generate_keypair()?;
+                (
+                    SuitePublicKey(PublicKeyInner::PqOnly(public_key)),
+                    SuiteSecretKey(SecretKeyInner::PqOnly(Box::new(secret_key))),
+                )
+            }
+        })
+    }
+
+    /// Encrypt `plaintext` to `public_key` under its suite, authenticating
+    /// `associated_data` with it.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidPlaintextLength`: If `plaintext` exceeds 2^36
+    ///   bytes
+    /// - `MisuseError::AssociatedDataTooLong`: If `associated_data` exceeds
+    ///   2^36 bytes
+    /// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+    /// - `CryptoError::RandomnessFailure`: If the system RNG fails a health test
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    pub fn seal(
+        public_key: &SuitePublicKey,
+        plaintext: &[u8],
+        associated_data: &[u8],
+    ) -> Result<Vec<u8>> {
+        context::checkpoint()?;
+        let suite = public_key.suite();
+        let body_len = suite.kem_ciphertext_size() + plaintext.len() + aead_imp::TAG_SIZE;
+        let mut sealed = envelope::header(suite.id().to_be_bytes(), &[], body_len)?;
+        let aad = [sealed.as_slice(), associated_data].concat();
+        let shared = match &public_key.0 {
+            PublicKeyInner::PqStandard(key) => {
+                let (ciphertext, shared) = HYBRID.encapsulate(key)?;
+                sealed.extend_from_slice(&ciphertext.to_bytes());
+                shared
+            }
+            PublicKeyInner::PqOnly(key) => {
+                let (ciphertext, shared) = MlKem768::encapsulate(key)?;
+                sealed.extend_from_slice(ciphertext.as_bytes());
+                shared
+            }
+        };
+        let okm = derive(suite, &shared);
+        let (key, nonce) = split(&okm);
+        let header = sealed.len();
+        sealed.resize(header + plaintext.len() + aead_imp::TAG_SIZE, 0);
+        aead_imp::Aes256GcmSiv::encrypt_with(key, nonce, plaintext, &aad, &mut sealed[header..])?;
+        Ok(sealed)
+    }
+
+    /// Verify and decrypt `sealed` with `secret_key`.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::DecryptionFailed`: If the message, key or associated
+    ///   data do not match
+    /// - `CryptoError::KeyEncapsulationFailed`: If a hybrid component fails
+    /// - `MisuseError::InvalidEncoding`: If `sealed` is not an envelope
+    /// - `MisuseError::InvalidAlgorithmIdentifier`: If the header names
+    ///   another suite than `secret_key`'s
+    /// - `MisuseError::InvalidCiphertextLength`: If `sealed` is too short
+    ///   for its suite
+    /// - `MisuseError::Cancelled` / `MisuseError::DeadlineExceeded`: If the
+    ///   current [`OpContext`](crate::context::OpContext) has expired
+    pub fn open(
+        secret_key: &SuiteSecretKey,
+        sealed: &[u8],
+        associated_data: &[u8],
+    ) -> Result<Vec<u8>> {
+        context::checkpoint()?;
+        let suite = secret_key.suite();
+        let (id, header, body) = envelope::split(sealed)?;
+        if Self::from_bytes(id)? != suite {
+            return Err(MisuseError::InvalidAlgorithmIdentifier.into());
+        }
+        let kem_size = suite.kem_ciphertext_size();
+        if body.len() < kem_size + aead_imp::TAG_SIZE {
+            return Err(MisuseError::InvalidCiphertextLength.into());
+        }
+        let (kem_ciphertext, aead_ciphertext) = body.split_at(kem_size);
+        let shared = match &secret_key.0 {
+            SecretKeyInner::PqStandard(key) => {
+                HYBRID.decapsulate(key, &HybridCt::from_bytes(kem_ciphertext)?)?
+            }
+            SecretKeyInner::PqOnly(key) => {
+                MlKem768::decapsulate(key, &MlKem768Ciphertext::from_bytes(kem_ciphertext)?)?
+            }
+        };
+        let okm = derive(suite, &shared);
+        let (key, nonce) = split(&okm);
+        let aad = [header, associated_data].concat();
+        let mut plaintext = vec![0u8; aead_ciphertext.len() - aead_imp::TAG_SIZE];
+        aead_imp::Aes256GcmSiv::decrypt_with(key, nonce, aead_ciphertext, &aad, &mut plaintext)?;
+        Ok(plaintext)
+    }
+
+    /// Size of this suite's KEM ciphertext in bytes.
+    const fn kem_ciphertext_size(self) -> usize {
+        match self {
+            Suite::PqStandard => HybridCt::SIZE,
+            Suite::PqOnly => kem_imp::CIPHERTEXT_SIZE,
+        }
+    }
+}
+
+/// AEAD key and nonce: HKDF-SHA3-384 of the shared secret, with the label
+/// and suite ID as info.
+fn derive(suite: Suite, shared: &SharedSecret) -> SensitiveBytes<OKM_SIZE> {
+    let prk = SensitiveBytes::new(hkdf::extract::<Sha3<48>, 48>(&[], shared.as_bytes()));
+    let mut info = [0u8; LABEL.len() + Suite::HEADER_SIZE];
+    info[..LABEL.len()].copy_from_slice(LABEL);
+    info[LABEL.len()..].copy_from_slice(&suite.id().to_be_bytes());
+    let mut okm = SensitiveBytes::zeroed();
+    hkdf::expand::<Sha3<48>, 48>(prk.as_bytes(), &info, okm.as_bytes_mut());
+    okm
+}
+
+fn split(
+    okm: &SensitiveBytes<OKM_SIZE>,
+) -> (&[u8; aead_imp::KEY_SIZE], &[u8; aead_imp::NONCE_SIZE]) {
+    let (key, nonce) = okm.as_bytes().split_at(aead_imp::KEY_SIZE);
+    (
+        key.try_into().expect("key size"),
+        nonce.try_into().expect("nonce size"),
+    )
+}
+
+#[derive(Clone, PartialEq, Eq)]
+enum PublicKeyInner {
+    PqStandard(HybridPk),
+    PqOnly(MlKem768PublicKey),
+}
+
+/// Public key of a [`Suite`].
+#[derive(Clone, PartialEq, Eq)]
+pub struct SuitePublicKey(PublicKeyInner);
+
+impl SuitePublicKey {
+    /// Parse `suite_id || key`.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidAlgorithmIdentifier`: If the suite ID is
+    ///   unknown
+    /// - `MisuseError::InvalidPublicKeyLength`: If the key has the wrong
+    ///   length for its suite
+    /// - `MisuseError::InvalidEncoding`: If the key of a
+    ///   [`PqOnly`](Suite::PqOnly) suite fails the FIPS 203 modulus check
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let Some((&id, key)) = bytes.split_first_chunk::<{ Suite::HEADER_SIZE }>() else {
+            return Err(MisuseError::InvalidPublicKeyLength.into());
+        };
+        let suite = Suite::from_bytes(id)?;
+        Ok(Self(match suite {
+            Suite::PqStandard => PublicKeyInner::PqStandard(HybridPk::from_bytes(key)?),
+            Suite::PqOnly => PublicKeyInner::PqOnly(MlKem768PublicKey::from_bytes(key)?),
+        }))
+    }
+
+    /// Encoded `suite_id || key`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.suite().id().to_be_bytes().to_vec();
+        match &self.0 {
+            PublicKeyInner::PqStandard(key) => bytes.extend_from_slice(&key.to_bytes()),
+            PublicKeyInner::PqOnly(key) => bytes.extend_from_slice(key.as_bytes()),
+        }
+        bytes
+    }
+
+    /// The suite this key belongs to.
+    pub fn suite(&self) -> Suite {
+        match self.0 {
+            PublicKeyInner::PqStandard(_) => Suite::PqStandard,
+            PublicKeyInner::PqOnly(_) => Suite::PqOnly,
+        }
+    }
+}
+
+impl fmt::Debug for SuitePublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SuitePublicKey")
+            .field("suite", &self.suite())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Boxed to keep the key small to move; both keys zeroize on drop.
+enum SecretKeyInner {
+    PqStandard(Box<HybridSk>),
+    PqOnly(Box<MlKem768SecretKey>),
+}
+
+/// Secret key of a [`Suite`], zeroized on drop.
+pub struct SuiteSecretKey(SecretKeyInner);
+
+crate::memory::never_serialize!([] SuiteSecretKey);
+
+impl SuiteSecretKey {
+    /// The suite this key belongs to.
+    pub fn suite(&self) -> Suite {
+        match self.0 {
+            SecretKeyInner::PqStandard(_) => Suite::PqStandard,
+            SecretKeyInner::PqOnly(_) => Suite::PqOnly,
+        }
+    }
+
+    /// The matching public key.
+    pub fn public_key(&self) -> SuitePublicKey {
+        SuitePublicKey(match &self.0 {
+            SecretKeyInner::PqStandard(key) => PublicKeyInner::PqStandard(key.public_key().clone()),
+            SecretKeyInner::PqOnly(key) => PublicKeyInner::PqOnly(key.public_key()),
+        })
+    }
+}
+
+impl fmt::Debug for SuiteSecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SuiteSecretKey { <redacted> }")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::ParseBudget;
+    use crate::errors::CryptoError;
+
+    #[test]
+    fn round_trip_every_suite() {
+        for &suite in Suite::ALL {
+            let (public_key, secret_key) = suite.generate_keypair().unwrap();
+            assert!(secret_key.public_key() == public_key);
+            let sealed = Suite::seal(&public_key, b"message", b"header").unwrap();
+            assert_eq!(Suite::from_header(&sealed).unwrap(), suite);
+            assert_eq!(
+                sealed.len(),
+                envelope::FIXED_HEADER_SIZE + suite.kem_ciphertext_size() + 7 + aead_imp::TAG_SIZE
+            );
+            assert_eq!(
+                Suite::open(&secret_key, &sealed, b"header").unwrap(),
+                b"message"
+            );
+
+            let error = Suite::open(&secret_key, &sealed, b"other").unwrap_err();
+            assert_eq!(error.crypto(), Some(CryptoError::DecryptionFailed));
+
+            let parsed = SuitePublicKey::from_bytes(&public_key.to_bytes()).unwrap();
+            assert!(parsed == public_key);
+        }
+    }
+
+    #[test]
+    fn rejects_unreduced_ml_kem_keys() {
+        // Every 12-bit coefficient is 4095, above q = 3329
+        let mut bytes = Suite::PqOnly.id().to_be_bytes().to_vec();
+        bytes.extend_from_slice(&[0xff; kem_imp::PUBLIC_KEY_SIZE]);
+        assert_eq!(
+            SuitePublicKey::from_bytes(&bytes).unwrap_err().misuse(),
+            Some(MisuseError::InvalidEncoding)
+        );
+    }
+
+    #[test]
+    fn ids_are_stable() {
+        assert_eq!(Suite::PqStandard.id(), 0x0001);
+        assert_eq!(Suite::PqOnly.id(), 0x0002);
+        for &suite in Suite::ALL {
+            assert_eq!(Suite::from_id(suite.id()), Some(suite));
+        }
+        assert_eq!(Suite::from_id(0), None);
+    }
+
+    #[test]
+    fn sealed_messages_are_envelopes() {
+        // Suite IDs stay clear of the AlgorithmId codes in the same field
+        assert!(Suite::ALL.iter().all(|suite| suite.id() < 0x0100));
+
+        let (public_key, secret_key) = Suite::PqOnly.generate_keypair().unwrap();
+        let sealed = Suite::seal(&public_key, b"message", b"").unwrap();
+        let (id, header, _) = envelope::split(&sealed).unwrap();
+        assert_eq!(id, Suite::PqOnly.id().to_be_bytes());
+        assert_eq!(header.len(), envelope::FIXED_HEADER_SIZE);
+        let error = envelope::Envelope::parse(&sealed, &ParseBudget::DEFAULT).unwrap_err();
+        assert_eq!(
+            error.misuse(),
+            Some(MisuseError::InvalidAlgorithmIdentifier)
+        );
+
+        // The header is authenticated
+        let mut tampered = sealed.clone();
+        tampered[envelope::FIXED_HEADER_SIZE - 1] = 1;
+        tampered.insert(envelope::FIXED_HEADER_SIZE, 0);
+        let error = Suite::open(&secret_key, &tampered, b"").unwrap_err();
+        assert_eq!(error.crypto(), Some(CryptoError::DecryptionFailed));
+    }
+
+    #[test]
+    fn rejects_foreign_and_malformed_headers() {
+        let (public_key, secret_key) = Suite::PqOnly.generate_keypair().unwrap();
+        let mut sealed = Suite::seal(&public_key, b"", b"").unwrap();
+        let suite_field = 5..7;
+
+        sealed[suite_field.clone()].copy_from_slice(&Suite::PqStandard.id().to_be_bytes());
+        let error = Suite::open(&secret_key, &sealed, b"").unwrap_err();
+        assert_eq!(
+            error.misuse(),
+            Some(MisuseError::InvalidAlgorithmIdentifier)
+        );
+
+        sealed[suite_field.clone()].copy_from_slice(&[0xff, 0xff]);
+        let error = Suite::from_header(&sealed).unwrap_err();
+        assert_eq!(
+            error.misuse(),
+            Some(MisuseError::InvalidAlgorithmIdentifier)
+        );
+
+        let error = Suite::from_header(&sealed[..4]).unwrap_err();
+        assert_eq!(error.misuse(), Some(MisuseError::InvalidEncoding));
+        sealed[suite_field].copy_from_slice(&Suite::PqOnly.id().to_be_bytes());
+        let error = Suite::open(&secret_key, &sealed[..100], b"").unwrap_err();
+        assert_eq!(error.misuse(), Some(MisuseError::InvalidCiphertextLength));
+        let error = SuitePublicKey::from_bytes(&[0x00]).unwrap_err();
+        assert_eq!(error.misuse(), Some(MisuseError::InvalidPublicKeyLength));
+    }
+}
@@ -0,0 +1,280 @@
+//! Runtime self-benchmark.
+//!
+//! # Purpose
+//!
+//! [`quick_profile`] measures rough throughput of the compiled algorithms on
+//! the current machine, so deployments can choose parameter sets or chunk
+//! sizes from measured capability instead of guessing.
+//!
+//! Results are indicative only: they run single-threaded on fixed,
+//! non-secret inputs and are sensitive to frequency scaling and load. Do not
+//! use them for anything security-relevant.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let profile = citadel::bench::quick_profile();
+//! if profile.ops_per_sec(Benchmark::MlKem768Decapsulate) < 1000.0 {
+//!     // fall back to a cheaper handshake schedule
+//! }
+//! ```
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use crate::algorithm::AlgorithmId;
+use crate::algorithms::classical::ed25519::Ed25519;
+use crate::algorithms::classical::keccak::sha3_256;
+use crate::algorithms::classical::x25519;
+#[cfg(feature = "mlkem")]
+use crate::algorithms::pq::ml_kem_768::MlKem768;
+use crate::errors::Result;
+use crate::stream::StreamingEncryptor;
+
+/// Time spent on each benchmark by [`quick_profile`].
+pub const DEFAULT_BUDGET: Duration = Duration::from_millis(20);
+
+/// Size of the message hashed by [`Benchmark::Sha3_256Chunk`].
+pub const HASH_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Stream chunk sizes tried by [`tune_chunk_size`], smallest first.
+pub const TUNED_CHUNK_SIZES: [usize; 6] = [
+    4 * 1024,
+    16 * 1024,
+    64 * 1024,
+    256 * 1024,
+    1024 * 1024,
+    4 * 1024 * 1024,
+];
+
+/// An operation measured by the profile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Benchmark {
+    /// ML-KEM-768 key generation from a seed.
+    MlKem768Keygen,
+    /// ML-KEM-768 encapsulation.
+    MlKem768Encapsulate,
+    /// ML-KEM-768 decapsulation.
+    MlKem768Decapsulate,
+    /// One X25519 scalar multiplication.
+    X25519,
+    /// Ed25519 signing of a 32-byte message.
+    Ed25519Sign,
+    /// Ed25519 verification of a 32-byte message.
+    Ed25519Verify,
+    /// SHA3-256 over [`HASH_CHUNK_SIZE`] bytes.
+    Sha3_256Chunk,
+}
+
+impl Benchmark {
+    /// All benchmarks, in the order they are run.
+    pub const ALL: [Benchmark; 7] = [
+        Benchmark::MlKem768Keygen,
+        Benchmark::MlKem768Encapsulate,
+        Benchmark::MlKem768Decapsulate,
+        Benchmark::X25519,
+        Benchmark::Ed25519Sign,
+        Benchmark::Ed25519Verify,
+        Benchmark::Sha3_256Chunk,
+    ];
+}
+
+/// One measured benchmark.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Measurement {
+    /// What was measured.
+    pub benchmark: Benchmark,
+    /// Completed operations per second.
+    pub ops_per_sec: f64,
+}
+
+/// Results of [`quick_profile`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profile {
+    measurements: Vec<Measurement>,
+}
+
+impl Profile {
+    /// All measurements, in [`Benchmark::ALL`] order. Benchmarks of
+    /// algorithms disabled at compile time are absent.
+    pub fn measurements(&self) -> &[Measurement] {
+        &self.measurements
+    }
+
+    /// Operations per second for `benchmark`, or 0.0 if it was not run.
+    pub fn ops_per_sec(&self, benchmark: Benchmark) -> f64 {
+        self.measurements
+            .iter()
+            .find(|m| m.benchmark == benchmark)
+            .map_or(0.0, |m| m.ops_per_sec)
+    }
+}
+
+/// Measure every benchmark for [`DEFAULT_BUDGET`].
+///
+/// Takes roughly `DEFAULT_BUDGET * Benchmark::ALL.len()`.
+pub fn quick_profile() -> Profile {
+    profile_with_budget(DEFAULT_BUDGET)
+}
+
+/// Measure every benchmark for about `budget` each.
+pub fn profile_with_budget(budget: Duration) -> Profile {
+    let measurements = Benchmark::ALL
+        .iter()
+        .filter_map(|&benchmark| {
+            Some(Measurement {
+                benchmark,
+                ops_per_sec: measure(budget, runner(benchmark)?),
+            })
+        })
+        .collect();
+    Profile { measurements }
+}
+
+/// Pick a [`stream`](crate::stream) chunk size for `algorithm` from
+/// measured throughput.
+///
+/// Encrypts chunks of each of [`TUNED_CHUNK_SIZES`] for about `budget` and
+/// returns the smallest size within 5% of the best throughput: past that,
+/// larger chunks only make readers buffer more.
+///
+/// # Errors
+///
+/// - As for [`StreamingEncryptor::new`], if `algorithm` is not an enabled
+///   AEAD
+pub fn tune_chunk_size(algorithm: AlgorithmId, budget: Duration) -> Result<usize> {
+    let key = vec![0u8; algorithm.sizes().secret_key.unwrap_or(0)];
+    let mut throughputs = [0.0; TUNED_CHUNK_SIZES.len()];
+    for (&chunk_size, throughput) in TUNED_CHUNK_SIZES.iter().zip(&mut throughputs) {
+        let mut encryptor = StreamingEncryptor::with_chunk_size(algorithm, &key, &[], chunk_size)?;
+        let chunk = vec![0u8; chunk_size];
+        let op = Box::new(move || {
+            let _ = black_box(encryptor.encrypt_chunk(black_box(&chunk)));
+        });
+        *throughput = measure(budget, op) * chunk_size as f64;
+    }
+    let best = throughputs.iter().copied().fold(0.0, f64::max);
+    let tuned = TUNED_CHUNK_SIZES
+        .iter()
+        .zip(throughputs)
+        .find(|&(_, throughput)| throughput >= 0.95 * best)
+        .map_or(TUNED_CHUNK_SIZES[0], |(&chunk_size, _)| chunk_size);
+    Ok(tuned)
+}
+
+/// The operation behind `benchmark`, or `None` if its algorithm is
+/// disabled at compile time.
+fn runner(benchmark: Benchmark) -> Option<Box<dyn FnMut()>> {
+    let op: Box<dyn FnMut()> = match benchmark {
+        #[cfg(feature = "mlkem")]
+        Benchmark::MlKem768Keygen => Box::new(|| {
+            black_box(MlKem768::keypair_from_seed(black_box(&[1u8; 64])));
+        }),
+        #[cfg(feature = "mlkem")]
+        Benchmark::MlKem768Encapsulate => {
+            let (ek, _) = MlKem768::keypair_from_seed(&[1u8; 64]);
+            Box::new(move || {
+                let _ = black_box(MlKem768::encapsulate_with(&ek, black_box(&[2u8; 32])));
+            })
+        }
+        #[cfg(feature = "mlkem")]
+        Benchmark::MlKem768Decapsulate => {
+            let (ek, dk) = MlKem768::keypair_from_seed(&[1u8; 64]);
+            let (ct, _) = MlKem768::encapsulate_with(&ek, &[2u8; 32])
+                .expect("key derived from seed is valid");
+            Box::new(move || {
+                black_box(MlKem768::decapsulate_with_status(
+                    dk.as_bytes(),
+                    black_box(&ct),
+                ));
+            })
+        }
+        Benchmark::X25519 => Box::new(|| {
+            black_box(x25519::scalar_mult(black_box(&[3u8; 32]), &[9u8; 32]));
+        }),
+        #[cfg(feature = "signing")]
+        Benchmark::Ed25519Sign => Box::new(|| {
+            black_box(Ed25519::sign_with(black_box(&[4u8; 32]), &[5u8; 32]));
+        }),
+        Benchmark::Ed25519Verify => Box::new(|| {
+            black_box(Ed25519::verify_with(
+                &ED25519_PUBLIC_KEY,
+                &[],
+                black_box(&ED25519_SIGNATURE),
+            ));
+        }),
+        Benchmark::Sha3_256Chunk => {
+            let chunk = vec![0u8; HASH_CHUNK_SIZE];
+            Box::new(move || {
+                black_box(sha3_256(&[black_box(&chunk)]));
+            })
+        }
+        #[cfg(not(feature = "mlkem"))]
+        Benchmark::MlKem768Keygen
+        | Benchmark::MlKem768Encapsulate
+        | Benchmark::MlKem768Decapsulate => return None,
+        #[cfg(not(feature = "signing"))]
+        Benchmark::Ed25519Sign => return None,
+    };
+    Some(op)
+}
+
+/// RFC 8032 test 1 public key, so verification can be measured without
+/// signing support.
+const ED25519_PUBLIC_KEY: [u8; 32] = [
+    0xd7, 0x5a, 0x98, 0x01, 0x82, 0xb1, 0x0a, 0xb7, 0xd5, 0x4b, 0xfe, 0xd3, 0xc9, 0x64, 0x07, 0x3a,
+    0x0e, 0xe1, 0x72, 0xf3, 0xda, 0xa6, 0x23, 0x25, 0xaf, 0x02, 0x1a, 0x68, 0xf7, 0x07, 0x51, 0x1a,
+];
+
+/// RFC 8032 test 1 signature over the empty message.
+const ED25519_SIGNATURE: [u8; 64] = [
+    0xe5, 0x56, 0x43, 0x00, 0xc3, 0x60, 0xac, 0x72, 0x90, 0x86, 0xe2, 0xcc, 0x80, 0x6e, 0x82, 0x8a,
+    0x84, 0x87, 0x7f, 0x1e, 0xb8, 0xe5, 0xd9, 0x74, 0xd8, 0x73, 0xe0, 0x65, 0x22, 0x49, 0x01, 0x55,
+    0x5f, 0xb8, 0x82, 0x15, 0x90, 0xa3, 0x3b, 0xac, 0xc6, 0x1e, 0x39, 0x70, 0x1c, 0xf9, 0xb4, 0x6b,
+    0xd2, 0x5b, 0xf5, 0xf0, 0x59, 0x5b, 0xbe, 0x24, 0x65, 0x51, 0x41, 0x43, 0x8e, 0x7a, 0x10, 0x0b,
+];
+
+/// Run `op` repeatedly for about `budget`; at least once.
+fn measure(budget: Duration, mut op: Box<dyn FnMut()>) -> f64 {
+    let start = Instant::now();
+    let mut iterations = 0u64;
+    loop {
+        op();
+        iterations += 1;
+        let elapsed = start.elapsed();
+        if elapsed >= budget {
+            return iterations as f64 / elapsed.as_secs_f64();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_covers_all_benchmarks() {
+        let profile = profile_with_budget(Duration::from_millis(1));
+        for measurement in profile.measurements() {
+            assert!(measurement.ops_per_sec > 0.0);
+        }
+        #[cfg(all(feature = "mlkem", feature = "signing"))]
+        assert_eq!(profile.measurements().len(), Benchmark::ALL.len());
+        #[cfg(not(feature = "mlkem"))]
+        assert_eq!(profile.ops_per_sec(Benchmark::MlKem768Keygen), 0.0);
+        #[cfg(not(feature = "signing"))]
+        assert_eq!(profile.ops_per_sec(Benchmark::Ed25519Sign), 0.0);
+    }
+
+    #[test]
+    fn tunes_stream_chunk_sizes() {
+        let budget = Duration::from_millis(1);
+        #[cfg(feature = "aes-gcm-siv")]
+        assert!(
+            TUNED_CHUNK_SIZES
+                .contains(&tune_chunk_size(AlgorithmId::Aes256GcmSiv, budget).unwrap())
+        );
+        assert!(tune_chunk_size(AlgorithmId::MlKem768, budget).is_err());
+    }
+}
@@ -0,0 +1,166 @@
+//! Algorithm sizes as compile-time constants.
+//!
+//! # Purpose
+//!
+//! One place for every key, ciphertext, nonce, tag and signature size the
+//! crate uses. Each algorithm is an uninhabited type carrying its sizes as
+//! associated constants, compiled regardless of which algorithm features
+//! are enabled, so parsers and wire formats can size buffers for
+//! algorithms they do not implement.
+//!
+//! The algorithm implementations take their sizes from here, as do the
+//! public types in [`kem`](crate::kem), [`sign`](mod@crate::sign) and
+//! [`aead`](crate::aead).
+//!
+//! # Static Assertions
+//!
+//! [`static_assert_size!`](crate::static_assert_size) fails the build when
+//! two sizes differ, and
+//! [`static_assert_size_of!`](crate::static_assert_size_of) when a type's
+//! in-memory size differs from the expected one:
+//!
+//! ```
+//! use citadel::consts::{Ed25519, MlDsa87};
+//! use citadel::{static_assert_size, static_assert_size_of};
+//!
+//! const HYBRID_SIGNATURE_SIZE: usize = MlDsa87::SIGNATURE_SIZE + Ed25519::SIGNATURE_SIZE;
+//!
+//! static_assert_size!(HYBRID_SIGNATURE_SIZE, 4691);
+//! static_assert_size_of!([u8; Ed25519::SIGNATURE_SIZE], 64);
+//! ```
+//!
+//! ```compile_fail
+//! use citadel::consts::MlKem768;
+//! use citadel::static_assert_size;
+//!
+//! static_assert_size!(MlKem768::CIPHERTEXT_SIZE, 1568);
+//! ```
+
+/// ML-KEM-768 (FIPS 203).
+#[derive(Debug)]
+pub enum MlKem768 {}
+
+impl MlKem768 {
+    /// Encapsulation key size in bytes.
+    pub const PUBLIC_KEY_SIZE: usize = 1184;
+    /// Decapsulation key size in bytes.
+    pub const SECRET_KEY_SIZE: usize = 2400;
+    /// Ciphertext size in bytes.
+    pub const CIPHERTEXT_SIZE: usize = 1088;
+    /// Shared secret size in bytes.
+    pub const SHARED_SECRET_SIZE: usize = 32;
+    /// Key generation seed size in bytes (`d || z`).
+    pub const SEED_SIZE: usize = 64;
+}
+
+/// X25519 (RFC 7748).
+#[derive(Debug)]
+pub enum X25519 {}
+
+impl X25519 {
+    /// Public key (u-coordinate) size in bytes.
+    pub const PUBLIC_KEY_SIZE: usize = 32;
+    /// Secret scalar size in bytes.
+    pub const SECRET_KEY_SIZE: usize = 32;
+    /// Shared secret size in bytes.
+    pub const SHARED_SECRET_SIZE: usize = 32;
+}
+
+/// ML-DSA-87 (FIPS 204).
+#[derive(Debug)]
+pub enum MlDsa87 {}
+
+impl MlDsa87 {
+    /// Public (verification) key size in bytes.
+    pub const PUBLIC_KEY_SIZE: usize = 2592;
+    /// Secret (signing) key size in bytes.
+    pub const SECRET_KEY_SIZE: usize = 4896;
+    /// Signature size in bytes.
+    pub const SIGNATURE_SIZE: usize = 4627;
+    /// Key generation seed size in bytes (ξ).
+    pub const SEED_SIZE: usize = 32;
+}
+
+/// Ed25519 (RFC 8032).
+#[derive(Debug)]
+pub enum Ed25519 {}
+
+impl Ed25519 {
+    /// Public key size in bytes.
+    pub const PUBLIC_KEY_SIZE: usize = 32;
+    /// Secret key (seed) size in bytes.
+    pub const SECRET_KEY_SIZE: usize = 32;
+    /// Signature size in bytes.
+    pub const SIGNATURE_SIZE: usize = 64;
+}
+
+/// AES-256-GCM-SIV (RFC 8452).
+#[derive(Debug)]
+pub enum Aes256GcmSiv {}
+
+impl Aes256GcmSiv {
+    /// Key size in bytes.
+    pub const KEY_SIZE: usize = 32;
+    /// Nonce size in bytes.
+    pub const NONCE_SIZE: usize = 12;
+    /// Tag size in bytes.
+    pub const TAG_SIZE: usize = 16;
+}
+
+/// AEGIS-256.
+#[derive(Debug)]
+pub enum Aegis256 {}
+
+impl Aegis256 {
+    /// Key size in bytes.
+    pub const KEY_SIZE: usize = 32;
+    /// Nonce size in bytes.
+    pub const NONCE_SIZE: usize = 32;
+    /// Tag size in bytes.
+    pub const TAG_SIZE: usize = 32;
+}
+
+/// Fail the build unless two sizes are equal.
+///
+/// Both arguments must be constant expressions of type `usize`. Use it to
+/// pin a size a wire format depends on, so that a change to it breaks the
+/// build instead of old data.
+///
+/// # Example
+///
+/// ```
+/// use citadel::consts::MlKem768;
+///
+/// citadel::static_assert_size!(MlKem768::CIPHERTEXT_SIZE, 1088);
+/// ```
+#[macro_export]
+macro_rules! static_assert_size {
+    ($size:expr, $expected:expr $(,)?) => {
+        const _: () = ::core::assert!(
+            $size == $expected,
+            ::core::concat!(
+                "size mismatch: ",
+                ::core::stringify!($size),
+                " != ",
+                ::core::stringify!($expected)
+            )
+        );
+    };
+}
+
+/// Fail the build unless `core::mem::size_of::<$type>()` equals the
+/// expected size.
+///
+/// # Example
+///
+/// ```
+/// use citadel::consts::MlDsa87;
+///
+/// citadel::static_assert_size_of!([u8; MlDsa87::SIGNATURE_SIZE], 4627);
+/// ```
+#[macro_export]
+macro_rules! static_assert_size_of {
+    ($type:ty, $expected:expr $(,)?) => {
+        $crate::static_assert_size!(::core::mem::size_of::<$type>(), $expected);
+    };
+}
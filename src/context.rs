@@ -0,0 +1,290 @@
+//! Per-thread operation context: deadlines, cancellation and entropy.
+//!
+//! # Purpose
+//!
+//! Servers need to bound the latency of cryptographic work and abandon it
+//! when a request is cancelled, without killing threads. An [`OpContext`]
+//! installed with [`OpContext::run`] applies to every Citadel call made on
+//! the current thread inside the closure; Citadel checks it at entry to
+//! high-level operations and between units of work in long-running ones.
+//!
+//! Code that never installs a context is unaffected.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let token = CancellationToken::new();
+//! let ctx = OpContext::new()
+//!     .with_timeout(Duration::from_millis(50))
+//!     .with_cancellation(token.clone());
+//!
+//! let secret = ctx.run(|| MlKem768::decapsulate(&sk, &ct))?;
+//! ```
+//!
+//! # Async Code
+//!
+//! The context is thread-local. Futures that migrate between threads must
+//! enter the context around each poll or carry the checks themselves.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::errors::{MisuseError, Result};
+use crate::internal::entropy;
+
+thread_local! {
+    static CURRENT: RefCell<Option<OpContext>> = const { RefCell::new(None) };
+}
+
+/// A cloneable flag for cooperative cancellation.
+///
+/// Clones share the same flag: cancel from one thread, observe on all.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Create a token that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request cancellation of all operations observing this token.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+
+    /// Whether cancellation has been requested.
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+/// A source of cryptographic randomness replacing the OS CSPRNG, e.g. a
+/// hardware RNG or an HSM.
+///
+/// # Security
+///
+/// Every key, nonce and randomized signature generated inside the context
+/// comes from this source. It MUST be a CSPRNG of at least 256-bit
+/// security; a weak source silently breaks everything built on it.
+pub trait EntropySource: Send + Sync {
+    /// Fill `buf` with random bytes.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If the source fails
+    /// - `CryptoError::RandomnessFailure`: If the source's output fails a
+    ///   health test
+    fn fill(&self, buf: &mut [u8]) -> Result<()>;
+}
+
+/// The OS CSPRNG via `getrandom`: the source used when no context
+/// supplies one.
+///
+/// Output passes startup and continuous health tests (repetition count
+/// and adaptive proportion, NIST SP 800-90B); after a failure every fill
+/// returns `CryptoError::RandomnessFailure`.
+///
+/// Name it to wrap OS randomness in a custom [`EntropySource`], or to
+/// draw from the OS regardless of the installed context.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OsEntropy;
+
+impl EntropySource for OsEntropy {
+    fn fill(&self, buf: &mut [u8]) -> Result<()> {
+        entropy::os_fill(buf)
+    }
+}
+
+/// Deadline, cancellation and entropy settings for operations on the
+/// current thread.
+///
+/// All settings are optional; the default context imposes no limits and
+/// uses OS randomness.
+#[derive(Clone, Default)]
+pub struct OpContext {
+    deadline: Option<Instant>,
+    cancellation: Option<CancellationToken>,
+    entropy: Option<Arc<dyn EntropySource>>,
+}
+
+impl OpContext {
+    /// A context with no deadline, no cancellation and OS randomness.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Abort operations still running at `deadline`.
+    #[inline]
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Abort operations still running `timeout` from now.
+    #[inline]
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        self.with_deadline(Instant::now() + timeout)
+    }
+
+    /// Abort operations once `token` is cancelled.
+    #[inline]
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Draw randomness from `source` instead of the OS.
+    #[inline]
+    pub fn with_entropy(mut self, source: Arc<dyn EntropySource>) -> Self {
+        self.entropy = Some(source);
+        self
+    }
+
+    /// Check whether work under this context may continue.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::Cancelled`: If the cancellation token was triggered
+    /// - `MisuseError::DeadlineExceeded`: If the deadline has passed
+    pub fn check(&self) -> Result<()> {
+        if self
+            .cancellation
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+        {
+            return Err(MisuseError::Cancelled.into());
+        }
+        if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            return Err(MisuseError::DeadlineExceeded.into());
+        }
+        Ok(())
+    }
+
+    /// Run `f` with this context installed on the current thread.
+    ///
+    /// Nested calls replace the outer context for their duration; the
+    /// previous context is restored when `f` returns or unwinds.
+    pub fn run<R>(&self, f: impl FnOnce() -> R) -> R {
+        struct Restore(Option<OpContext>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                let previous = self.0.take();
+                CURRENT.with(|current| *current.borrow_mut() = previous);
+            }
+        }
+
+        let previous = CURRENT.with(|current| current.replace(Some(self.clone())));
+        let _restore = Restore(previous);
+        f()
+    }
+}
+
+impl fmt::Debug for OpContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OpContext")
+            .field("deadline", &self.deadline)
+            .field("cancellation", &self.cancellation)
+            .field("custom_entropy", &self.entropy.is_some())
+            .finish()
+    }
+}
+
+/// Check the context installed on the current thread, if any.
+///
+/// Long-running loops call this between units of work; callers may use it
+/// in their own loops around Citadel operations.
+///
+/// # Errors
+///
+/// See [`OpContext::check`].
+pub fn checkpoint() -> Result<()> {
+    CURRENT.with(|current| match &*current.borrow() {
+        Some(ctx) => ctx.check(),
+        None => Ok(()),
+    })
+}
+
+/// The entropy source of the current context, if one was configured.
+pub(crate) fn entropy_source() -> Option<Arc<dyn EntropySource>> {
+    CURRENT.with(|current| current.borrow().as_ref()?.entropy.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fixed(u8);
+
+    impl EntropySource for Fixed {
+        fn fill(&self, buf: &mut [u8]) -> Result<()> {
+            buf.fill(self.0);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn no_context_means_no_limits() {
+        assert!(checkpoint().is_ok());
+        assert!(entropy_source().is_none());
+    }
+
+    #[test]
+    fn cancellation_is_observed() {
+        let token = CancellationToken::new();
+        let ctx = OpContext::new().with_cancellation(token.clone());
+        ctx.run(|| {
+            assert!(checkpoint().is_ok());
+            token.cancel();
+            assert_eq!(
+                checkpoint().unwrap_err().misuse(),
+                Some(MisuseError::Cancelled)
+            );
+        });
+        assert!(checkpoint().is_ok());
+    }
+
+    #[test]
+    fn expired_deadline_is_observed() {
+        let ctx = OpContext::new().with_deadline(Instant::now());
+        assert_eq!(
+            ctx.run(checkpoint).unwrap_err().misuse(),
+            Some(MisuseError::DeadlineExceeded)
+        );
+        let ctx = OpContext::new().with_timeout(Duration::from_secs(3600));
+        assert!(ctx.run(checkpoint).is_ok());
+    }
+
+    #[test]
+    fn os_entropy_ignores_context() {
+        let ctx = OpContext::new().with_entropy(Arc::new(Fixed(7)));
+        let mut buf = [7u8; 32];
+        ctx.run(|| OsEntropy.fill(&mut buf)).unwrap();
+        assert_ne!(buf, [7; 32]);
+    }
+
+    #[test]
+    fn nested_contexts_restore_outer() {
+        let outer = OpContext::new().with_entropy(Arc::new(Fixed(1)));
+        let inner = OpContext::new().with_entropy(Arc::new(Fixed(2)));
+        outer.run(|| {
+            inner.run(|| {
+                let mut buf = [0u8; 4];
+                entropy_source().unwrap().fill(&mut buf).unwrap();
+                assert_eq!(buf, [2; 4]);
+            });
+            let mut buf = [0u8; 4];
+            entropy_source().unwrap().fill(&mut buf).unwrap();
+            assert_eq!(buf, [1; 4]);
+        });
+        assert!(entropy_source().is_none());
+    }
+}
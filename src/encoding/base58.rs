@@ -0,0 +1,241 @@
+//! Base58 and Base58Check encoding.
+//!
+//! Uses the Bitcoin alphabet, which omits the visually ambiguous characters
+//! `0`, `O`, `I` and `l`. Leading zero bytes are encoded as leading `1`
+//! characters.
+//!
+//! Base58Check appends the first 4 bytes of `SHA-256(SHA-256(payload))` as a
+//! checksum, which is verified in constant time on decode.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let text = base58::encode_check(&fingerprint);
+//! let bytes = base58::decode_check(&text, &ParseBudget::DEFAULT)?;
+//! ```
+//!
+//! # Untrusted Input
+//!
+//! Decoding is quadratic in the input length, so keep
+//! `ParseBudget::max_input_len` small (identifiers are rarely longer than a
+//! few hundred characters).
+
+use super::ParseBudget;
+use crate::algorithms::classical::sha256::Sha256;
+use crate::errors::{MisuseError, Result};
+use crate::memory::{constant_time_eq, ct};
+
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Length of the Base58Check checksum in bytes.
+pub const CHECKSUM_LENGTH: usize = 4;
+
+/// Encode bytes as Base58.
+pub fn encode(data: &[u8]) -> String {
+    // log(256) / log(58) ~= 1.366
+    let capacity = data.len() * 138 / 100 + 1;
+    let mut digits = vec![0u8; capacity];
+
+    // Full-width carry propagation: iteration count depends only on length
+    for &byte in data {
+        let mut carry = byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+    }
+
+    let zeros = leading_count(data, 0);
+    let significant = capacity - leading_count_rev(&digits, 0);
+
+    let mut out = String::with_capacity(zeros + significant);
+    for _ in 0..zeros {
+        out.push('1');
+    }
+    for &digit in digits[..significant].iter().rev() {
+        out.push(ct::lookup_byte(ALPHABET, digit.into()) as char);
+    }
+    out
+}
+
+/// Decode a Base58 string.
+///
+/// # Errors
+///
+/// - `MisuseError::ParseBudgetExceeded`: If the input or its maximum decoded
+///   size exceeds `budget`
+/// - `MisuseError::InvalidEncoding`: If the string contains characters
+///   outside the Base58 alphabet
+pub fn decode(encoded: &str, budget: &ParseBudget) -> Result<Vec<u8>> {
+    let input = encoded.as_bytes();
+    budget.check_input(input.len())?;
+    // log(58) / log(256) ~= 0.733
+    let capacity = input.len() * 733 / 1000 + 1;
+    budget.check_output(capacity)?;
+    let mut bytes = vec![0u8; capacity];
+    let mut valid = 0xFFu8;
+
+    for &c in input {
+        let (value, found) = ct::position(ALPHABET, c);
+        valid &= found;
+        let mut carry = value as u32;
+        for byte in bytes.iter_mut() {
+            carry += (*byte as u32) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+    }
+
+    if valid != 0xFF {
+        return Err(MisuseError::InvalidEncoding.into());
+    }
+
+    let zeros = leading_count(input, b'1');
+    let significant = capacity - leading_count_rev(&bytes, 0);
+
+    let mut out = vec![0u8; zeros];
+    out.extend(bytes[..significant].iter().rev());
+    Ok(out)
+}
+
+/// Encode bytes as Base58Check (payload followed by a 4-byte checksum).
+pub fn encode_check(payload: &[u8]) -> String {
+    let mut data = Vec::with_capacity(payload.len() + CHECKSUM_LENGTH);
+    data.extend_from_slice(payload);
+    data.extend_from_slice(&checksum(payload));
+    encode(&data)
+}
+
+/// Decode a Base58Check string and verify its checksum.
+///
+/// # Errors
+///
+/// - `MisuseError::ParseBudgetExceeded`: If the input exceeds `budget`
+/// - `MisuseError::InvalidEncoding`: If the string is not valid Base58, is
+///   shorter than the checksum, or the checksum does not match
+pub fn decode_check(encoded: &str, budget: &ParseBudget) -> Result<Vec<u8>> {
+    let mut data = decode(encoded, budget)?;
+    if data.len() < CHECKSUM_LENGTH {
+        return Err(MisuseError::InvalidEncoding.into());
+    }
+
+    let split = data.len() - CHECKSUM_LENGTH;
+    let expected = checksum(&data[..split]);
+    if !constant_time_eq(&expected, &data[split..]) {
+        return Err(MisuseError::InvalidEncoding.into());
+    }
+    data.truncate(split);
+    Ok(data)
+}
+
+fn checksum(payload: &[u8]) -> [u8; CHECKSUM_LENGTH] {
+    let digest = Sha256::digest(&Sha256::digest(payload));
+    let mut out = [0u8; CHECKSUM_LENGTH];
+    out.copy_from_slice(&digest[..CHECKSUM_LENGTH]);
+    out
+}
+
+/// Count leading occurrences of `value` without an early exit.
+fn leading_count(data: &[u8], value: u8) -> usize {
+    let mut still_leading = 1usize;
+    let mut count = 0usize;
+    for &byte in data {
+        still_leading &= (ct::eq_mask(byte.into(), value.into()) & 1) as usize;
+        count += still_leading;
+    }
+    count
+}
+
+/// Count trailing occurrences of `value` without an early exit.
+fn leading_count_rev(data: &[u8], value: u8) -> usize {
+    let mut still_leading = 1usize;
+    let mut count = 0usize;
+    for &byte in data.iter().rev() {
+        still_leading &= (ct::eq_mask(byte.into(), value.into()) & 1) as usize;
+        count += still_leading;
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unhex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn known_vectors() {
+        let vectors = [
+            ("", ""),
+            ("61", "2g"),
+            ("626262", "a3gV"),
+            ("636363", "aPEr"),
+            (
+                "73696d706c792061206c6f6e6720737472696e67",
+                "2cFupjhnEsSn59qHXstmK2ffpLv2",
+            ),
+            (
+                "00eb15231dfceb60925886b67d065299925915aeb172c06647",
+                "1NS17iag9jJgTHD1VXjvLCEnZuQ3rJDE9L",
+            ),
+            ("516b6fcd0f", "ABnLTmg"),
+            ("bf4f89001e670274dd", "3SEo3LWLoPntC"),
+            ("572e4794", "3EFU7m"),
+            ("ecac89cad93923c02321", "EJDM8drfXA6uyA"),
+            ("10c8511e", "Rt5zm"),
+            ("00000000000000000000", "1111111111"),
+        ];
+        for (hex, text) in vectors {
+            let bytes = unhex(hex);
+            assert_eq!(encode(&bytes), text);
+            assert_eq!(decode(text, &ParseBudget::DEFAULT).unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    fn check_known_vector() {
+        // Bitcoin P2PKH address: version byte 0x00 + HASH160
+        let payload = unhex("00f54a5851e9372b87810a8e60cdd2e7cfd80b6e31");
+        let encoded = encode_check(&payload);
+        assert_eq!(encoded, "1PMycacnJaSqwwJqjawXBErnLsZ7RkXUAs");
+        assert_eq!(
+            decode_check(&encoded, &ParseBudget::DEFAULT).unwrap(),
+            payload
+        );
+    }
+
+    #[test]
+    fn check_detects_corruption() {
+        let encoded = encode_check(b"citadel fingerprint");
+        let mut chars: Vec<char> = encoded.chars().collect();
+        chars[3] = if chars[3] == 'z' { 'y' } else { 'z' };
+        let corrupted: String = chars.into_iter().collect();
+        assert!(decode_check(&corrupted, &ParseBudget::DEFAULT).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_characters() {
+        let budget = ParseBudget::DEFAULT;
+        assert!(decode("0OIl", &budget).is_err());
+        assert!(decode("abc!", &budget).is_err());
+        assert!(decode_check("1", &budget).is_err());
+    }
+
+    #[test]
+    fn decode_enforces_budget() {
+        let encoded = encode(&[0xFF; 64]);
+        let budget = ParseBudget::DEFAULT.with_max_input_len(encoded.len() - 1);
+        assert_eq!(
+            decode(&encoded, &budget).unwrap_err().misuse(),
+            Some(MisuseError::ParseBudgetExceeded)
+        );
+        let budget = ParseBudget::DEFAULT.with_max_output_len(32);
+        assert!(decode(&encoded, &budget).is_err());
+    }
+}
@@ -0,0 +1,238 @@
+//! Base64 encoding (RFC 4648).
+//!
+//! Encodes with the standard alphabet and `=` padding. Decoding accepts
+//! both the standard and the URL-safe alphabet, with or without padding,
+//! as DSSE and JOSE-adjacent formats require of verifiers.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let text = base64::encode(&signature);
+//! let bytes = base64::decode(&text, &ParseBudget::DEFAULT)?;
+//! ```
+//!
+//! # Timing
+//!
+//! Unlike `bech32` and `base58`, character mapping in [`encode`] and
+//! [`decode`] branches on the data. Use them for payloads and signatures,
+//! never for secrets. The crate-internal `encode_secret` and
+//! `decode_secret`, used for PEM secret keys, map characters through
+//! constant-time table scans instead and check validity only once the whole
+//! input has been processed.
+
+use super::ParseBudget;
+use crate::errors::{MisuseError, Result};
+use crate::memory::ct;
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encode bytes as padded standard Base64.
+pub fn encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let mut block = [0u8; 3];
+        block[..chunk.len()].copy_from_slice(chunk);
+        let bits = u32::from_be_bytes([0, block[0], block[1], block[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(bits >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Decode standard or URL-safe Base64, padded or not.
+///
+/// Non-zero trailing bits and misplaced padding are rejected, so every
+/// byte string has exactly one accepted encoding per alphabet and padding
+/// choice.
+///
+/// # Errors
+///
+/// - `MisuseError::ParseBudgetExceeded`: If the input or its decoded size
+///   exceeds `budget`
+/// - `MisuseError::InvalidEncoding`: If `encoded` is not valid Base64
+pub fn decode(encoded: &str, budget: &ParseBudget) -> Result<Vec<u8>> {
+    budget.check_input(encoded.len())?;
+    let input = encoded.as_bytes();
+    let unpadded = match input {
+        [rest @ .., b'=', b'='] | [rest @ .., b'='] if input.len().is_multiple_of(4) => rest,
+        _ => input,
+    };
+    if unpadded.len() % 4 == 1 {
+        return Err(MisuseError::InvalidEncoding.into());
+    }
+    let output_len = unpadded.len() * 3 / 4;
+    budget.check_output(output_len)?;
+
+    let mut out = Vec::with_capacity(output_len);
+    for chunk in unpadded.chunks(4) {
+        let mut bits = 0u32;
+        for (i, &c) in chunk.iter().enumerate() {
+            bits |= (sextet(c).ok_or(MisuseError::InvalidEncoding)? as u32) << (18 - 6 * i);
+        }
+        let bytes = bits.to_be_bytes();
+        let produced = chunk.len() - 1;
+        // Bits below the last full byte must be zero
+        if bits & (0xff_ffff >> (8 * produced)) != 0 {
+            return Err(MisuseError::InvalidEncoding.into());
+        }
+        out.extend_from_slice(&bytes[1..=produced]);
+    }
+    Ok(out)
+}
+
+/// [`encode`] for secrets: appends padded standard Base64 of `data` to
+/// `out`, touching every alphabet entry for every character.
+pub(crate) fn encode_secret(data: &[u8], out: &mut Vec<u8>) {
+    for chunk in data.chunks(3) {
+        let mut block = [0u8; 3];
+        block[..chunk.len()].copy_from_slice(chunk);
+        let bits = u32::from_be_bytes([0, block[0], block[1], block[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ct::lookup_byte(
+                    ALPHABET,
+                    (bits >> (18 - 6 * i) & 0x3f) as usize,
+                ));
+            } else {
+                out.push(b'=');
+            }
+        }
+    }
+}
+
+/// [`decode`] for secrets: decodes padded standard Base64 into `out`,
+/// which must be exactly the decoded length.
+///
+/// Only the input length and the position of the padding, both public,
+/// affect control flow; invalid characters and non-zero trailing bits are
+/// accumulated and reported after the last character.
+///
+/// # Errors
+///
+/// - `MisuseError::InvalidEncoding`: If `encoded` is not padded standard
+///   Base64 of `out.len()` bytes
+pub(crate) fn decode_secret(encoded: &[u8], out: &mut [u8]) -> Result<()> {
+    if !encoded.len().is_multiple_of(4) || decoded_len(encoded) != Some(out.len()) {
+        return Err(MisuseError::InvalidEncoding.into());
+    }
+    let mut valid = 0xffu8;
+    let mut trailing = 0u32;
+    for (chunk, out) in encoded.chunks(4).zip(out.chunks_mut(3)) {
+        let mut bits = 0u32;
+        for (i, &c) in chunk[..=out.len()].iter().enumerate() {
+            let (value, found) = ct::position(ALPHABET, c);
+            valid &= found;
+            bits |= (value as u32) << (18 - 6 * i);
+        }
+        for &c in &chunk[out.len() + 1..] {
+            valid &= ct::eq_mask(c.into(), b'='.into());
+        }
+        trailing |= bits & (0xff_ffff >> (8 * out.len()));
+        out.copy_from_slice(&bits.to_be_bytes()[1..=out.len()]);
+    }
+    if valid != 0xff || trailing != 0 {
+        return Err(MisuseError::InvalidEncoding.into());
+    }
+    Ok(())
+}
+
+/// Decoded length of padded Base64, from the length and padding alone.
+pub(crate) fn decoded_len(encoded: &[u8]) -> Option<usize> {
+    let padding = match encoded {
+        [.., b'=', b'='] => 2,
+        [.., b'='] => 1,
+        _ => 0,
+    };
+    (encoded.len() / 4 * 3).checked_sub(padding)
+}
+
+fn sextet(c: u8) -> Option<u8> {
+    match c {
+        b'A'..=b'Z' => Some(c - b'A'),
+        b'a'..=b'z' => Some(c - b'a' + 26),
+        b'0'..=b'9' => Some(c - b'0' + 52),
+        b'+' | b'-' => Some(62),
+        b'/' | b'_' => Some(63),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rfc4648_vectors() {
+        for (plain, encoded) in [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ] {
+            assert_eq!(encode(plain.as_bytes()), encoded);
+            assert_eq!(
+                decode(encoded, &ParseBudget::DEFAULT).unwrap(),
+                plain.as_bytes()
+            );
+            let unpadded = encoded.trim_end_matches('=');
+            assert_eq!(
+                decode(unpadded, &ParseBudget::DEFAULT).unwrap(),
+                plain.as_bytes()
+            );
+        }
+    }
+
+    #[test]
+    fn accepts_url_safe_alphabet() {
+        let data = [0xfb, 0xff, 0xbf];
+        assert_eq!(encode(&data), "+/+/");
+        assert_eq!(decode("-_-_", &ParseBudget::DEFAULT).unwrap(), data);
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        for input in ["Z", "Zg=", "Zh==", "Zm9v=", "Zg==Zg==", "Z===", "Zm9*"] {
+            assert_eq!(
+                decode(input, &ParseBudget::DEFAULT).unwrap_err().misuse(),
+                Some(MisuseError::InvalidEncoding),
+                "{input}"
+            );
+        }
+        let budget = ParseBudget::DEFAULT.with_max_output_len(2);
+        assert_eq!(
+            decode("Zm9v", &budget).unwrap_err().misuse(),
+            Some(MisuseError::ParseBudgetExceeded)
+        );
+    }
+
+    #[test]
+    fn secret_path_matches() {
+        let data: Vec<u8> = (0..=255).collect();
+        for len in [0, 1, 2, 3, 4, 5, 64, 256] {
+            let mut encoded = Vec::new();
+            encode_secret(&data[..len], &mut encoded);
+            assert_eq!(encoded, encode(&data[..len]).as_bytes());
+            let mut decoded = vec![0u8; len];
+            decode_secret(&encoded, &mut decoded).unwrap();
+            assert_eq!(decoded, &data[..len]);
+        }
+        for input in ["Zg", "Zh==", "Zg=A", "Zm9*", "Z===", "-_-_"] {
+            let mut out = vec![0u8; decoded_len(input.as_bytes()).unwrap_or(0)];
+            assert_eq!(
+                decode_secret(input.as_bytes(), &mut out)
+                    .unwrap_err()
+                    .misuse(),
+                Some(MisuseError::InvalidEncoding),
+                "{input}"
+            );
+        }
+    }
+}
@@ -0,0 +1,296 @@
+//! Bech32m encoding (BIP-350).
+//!
+//! Encodes bytes as `<hrp>1<data><checksum>`, where the human-readable part
+//! (HRP) identifies what the string is (e.g., `citadelfp` for a key
+//! fingerprint) and the 6-character checksum detects up to 4 substitution
+//! errors.
+//!
+//! Only the Bech32m checksum constant is accepted. Legacy Bech32 strings are
+//! rejected.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let text = bech32::encode("citadelfp", &fingerprint)?;
+//! let bytes = bech32::decode_with_hrp("citadelfp", &text, &ParseBudget::DEFAULT)?;
+//! ```
+
+use super::ParseBudget;
+use crate::errors::{MisuseError, Result};
+use crate::memory::ct;
+
+/// Maximum total length of an encoded string (BIP-173).
+pub const MAX_LENGTH: usize = 90;
+
+/// Maximum length of the human-readable part.
+pub const MAX_HRP_LENGTH: usize = 83;
+
+const CHECKSUM_LENGTH: usize = 6;
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+const BECH32M_CONST: u32 = 0x2bc830a3;
+
+/// Encode `data` under the human-readable prefix `hrp`.
+///
+/// The HRP is lowercased. The output is always lowercase.
+///
+/// # Errors
+///
+/// - `MisuseError::InvalidEncoding`: If the HRP is empty, too long, contains
+///   characters outside ASCII 33..=126, or the result would exceed
+///   [`MAX_LENGTH`]
+pub fn encode(hrp: &str, data: &[u8]) -> Result<String> {
+    let hrp = validate_hrp(hrp)?;
+    let values = convert_bits(data, 8, 5, true)?;
+
+    if hrp.len() + 1 + values.len() + CHECKSUM_LENGTH > MAX_LENGTH {
+        return Err(MisuseError::InvalidEncoding.into());
+    }
+
+    let checksum = create_checksum(hrp.as_bytes(), &values);
+
+    let mut out = String::with_capacity(hrp.len() + 1 + values.len() + CHECKSUM_LENGTH);
+    out.push_str(&hrp);
+    out.push('1');
+    for value in values.iter().chain(checksum.iter()) {
+        out.push(ct::lookup_byte(CHARSET, (*value).into()) as char);
+    }
+    Ok(out)
+}
+
+/// Decode a Bech32m string into its (lowercase) HRP and payload bytes.
+///
+/// # Errors
+///
+/// - `MisuseError::ParseBudgetExceeded`: If the input or decoded payload
+///   exceeds `budget`
+/// - `MisuseError::InvalidEncoding`: If the string has invalid length, mixed
+///   case, invalid characters, invalid padding, or a bad checksum
+pub fn decode(encoded: &str, budget: &ParseBudget) -> Result<(String, Vec<u8>)> {
+    let bytes = encoded.as_bytes();
+    budget.check_input(bytes.len())?;
+    if bytes.len() > MAX_LENGTH || bytes.len() < 1 + 1 + CHECKSUM_LENGTH {
+        return Err(MisuseError::InvalidEncoding.into());
+    }
+
+    let has_lower = bytes.iter().any(u8::is_ascii_lowercase);
+    let has_upper = bytes.iter().any(u8::is_ascii_uppercase);
+    if has_lower && has_upper {
+        return Err(MisuseError::InvalidEncoding.into());
+    }
+    let lowered = encoded.to_ascii_lowercase();
+
+    let separator = lowered.rfind('1').ok_or(MisuseError::InvalidEncoding)?;
+    let (hrp, data) = (&lowered[..separator], &lowered.as_bytes()[separator + 1..]);
+    let hrp = validate_hrp(hrp)?;
+    if data.len() < CHECKSUM_LENGTH {
+        return Err(MisuseError::InvalidEncoding.into());
+    }
+    budget.check_output((data.len() - CHECKSUM_LENGTH) * 5 / 8)?;
+
+    // Map every character before judging validity
+    let mut values = Vec::with_capacity(data.len());
+    let mut valid = 0xFFu8;
+    for &c in data {
+        let (value, found) = ct::position(CHARSET, c);
+        values.push(value as u8);
+        valid &= found;
+    }
+
+    let checksum_ok = polymod_with_hrp(hrp.as_bytes(), &values) == BECH32M_CONST;
+    if valid != 0xFF || !checksum_ok {
+        return Err(MisuseError::InvalidEncoding.into());
+    }
+
+    let payload = convert_bits(&values[..values.len() - CHECKSUM_LENGTH], 5, 8, false)?;
+    Ok((hrp, payload))
+}
+
+/// Decode a Bech32m string and require a specific HRP.
+///
+/// # Errors
+///
+/// - `MisuseError::ParseBudgetExceeded`: If the input exceeds `budget`
+/// - `MisuseError::InvalidEncoding`: If decoding fails or the HRP differs
+///   from `expected_hrp` (compared case-insensitively)
+pub fn decode_with_hrp(expected_hrp: &str, encoded: &str, budget: &ParseBudget) -> Result<Vec<u8>> {
+    let (hrp, payload) = decode(encoded, budget)?;
+    if !hrp.eq_ignore_ascii_case(expected_hrp) {
+        return Err(MisuseError::InvalidEncoding.into());
+    }
+    Ok(payload)
+}
+
+fn validate_hrp(hrp: &str) -> Result<String> {
+    let bytes = hrp.as_bytes();
+    if bytes.is_empty()
+        || bytes.len() > MAX_HRP_LENGTH
+        || bytes.iter().any(|&c| !(33..=126).contains(&c))
+    {
+        return Err(MisuseError::InvalidEncoding.into());
+    }
+    Ok(hrp.to_ascii_lowercase())
+}
+
+fn polymod(values: impl Iterator<Item = u8>) -> u32 {
+    let mut chk = 1u32;
+    for value in values {
+        let top = chk >> 25;
+        chk = ((chk & 0x1ffffff) << 5) ^ value as u32;
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            chk ^= generator & 0u32.wrapping_sub((top >> i) & 1);
+        }
+    }
+    chk
+}
+
+fn polymod_with_hrp(hrp: &[u8], values: &[u8]) -> u32 {
+    let expanded = hrp
+        .iter()
+        .map(|c| c >> 5)
+        .chain(core::iter::once(0))
+        .chain(hrp.iter().map(|c| c & 31));
+    polymod(expanded.chain(values.iter().copied()))
+}
+
+fn create_checksum(hrp: &[u8], values: &[u8]) -> [u8; CHECKSUM_LENGTH] {
+    let mut padded = Vec::with_capacity(values.len() + CHECKSUM_LENGTH);
+    padded.extend_from_slice(values);
+    padded.extend_from_slice(&[0u8; CHECKSUM_LENGTH]);
+    let residue = polymod_with_hrp(hrp, &padded) ^ BECH32M_CONST;
+
+    let mut checksum = [0u8; CHECKSUM_LENGTH];
+    for (i, value) in checksum.iter_mut().enumerate() {
+        *value = ((residue >> (5 * (5 - i))) & 31) as u8;
+    }
+    checksum
+}
+
+fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Result<Vec<u8>> {
+    let max_value = (1u32 << to) - 1;
+    let mut accumulator = 0u32;
+    let mut bits = 0u32;
+    let mut out = Vec::with_capacity(data.len() * from as usize / to as usize + 1);
+
+    for &value in data {
+        accumulator = (accumulator << from) | value as u32;
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            out.push(((accumulator >> bits) & max_value) as u8);
+        }
+    }
+
+    if pad {
+        if bits > 0 {
+            out.push(((accumulator << (to - bits)) & max_value) as u8);
+        }
+    } else if bits >= from || ((accumulator << (to - bits)) & max_value) != 0 {
+        return Err(MisuseError::InvalidEncoding.into());
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::Error;
+
+    #[test]
+    fn bip350_valid_vectors() {
+        // Vectors whose payload is not a whole number of bytes are omitted:
+        // this decoder returns bytes, so their padding is rejected.
+        let valid = [
+            "A1LQFN3A",
+            "a1lqfn3a",
+            "an83characterlonghumanreadablepartthatcontainsthetheexcludedcharactersbioandnumber11sg7hg6",
+            "abcdef1l7aum6echk45nj3s0wdvt2fg8x9yrzpqzd3ryx",
+            "split1checkupstagehandshakeupstreamerranterredcaperredlc445v",
+            "?1v759aa",
+        ];
+        for vector in valid {
+            assert!(decode(vector, &ParseBudget::DEFAULT).is_ok(), "{vector}");
+        }
+    }
+
+    #[test]
+    fn bip350_invalid_vectors() {
+        let invalid = [
+            "\u{20}1xj0phk",
+            "\u{7F}1g6xzxy",
+            "an84characterslonghumanreadablepartthatcontainsthetheexcludedcharactersbioandnumber11858tn",
+            "qyrz8wqd2c9m",
+            "1qyrz8wqd2c9m",
+            "y1b0jsk6g",
+            "lt1igcx5c0",
+            "in1muywd",
+            "mm1crxm3i",
+            "au1s5cgom",
+            "M1VUXWEZ",
+            "16plkw9",
+            "1p2gdwpf",
+            // Legacy Bech32 checksum
+            "a12uel5l",
+        ];
+        for vector in invalid {
+            assert_eq!(
+                decode(vector, &ParseBudget::DEFAULT).unwrap_err(),
+                Error::Misuse(MisuseError::InvalidEncoding),
+                "{vector}"
+            );
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let fingerprint: Vec<u8> = (0u8..32).collect();
+        let encoded = encode("citadelfp", &fingerprint).unwrap();
+        assert!(encoded.starts_with("citadelfp1"));
+        assert_eq!(
+            decode_with_hrp("citadelfp", &encoded, &ParseBudget::DEFAULT).unwrap(),
+            fingerprint
+        );
+        assert_eq!(
+            decode_with_hrp(
+                "CITADELFP",
+                &encoded.to_ascii_uppercase(),
+                &ParseBudget::DEFAULT
+            )
+            .unwrap(),
+            fingerprint
+        );
+    }
+
+    #[test]
+    fn hrp_mismatch_rejected() {
+        let encoded = encode("citadelfp", &[1, 2, 3]).unwrap();
+        assert!(decode_with_hrp("other", &encoded, &ParseBudget::DEFAULT).is_err());
+    }
+
+    #[test]
+    fn single_substitution_detected() {
+        let encoded = encode("citadelfp", &[0xAB; 20]).unwrap();
+        let mut bytes = encoded.into_bytes();
+        let last = bytes.len() - 1;
+        bytes[last] = if bytes[last] == b'q' { b'p' } else { b'q' };
+        assert!(decode(core::str::from_utf8(&bytes).unwrap(), &ParseBudget::DEFAULT).is_err());
+    }
+
+    #[test]
+    fn decode_enforces_budget() {
+        let encoded = encode("citadelfp", &[0xAB; 32]).unwrap();
+        let budget = ParseBudget::DEFAULT.with_max_output_len(31);
+        assert_eq!(
+            decode(&encoded, &budget).unwrap_err(),
+            Error::Misuse(MisuseError::ParseBudgetExceeded)
+        );
+        let budget = ParseBudget::DEFAULT.with_max_input_len(encoded.len() - 1);
+        assert!(decode(&encoded, &budget).is_err());
+    }
+
+    #[test]
+    fn encode_rejects_oversized_payload() {
+        assert!(encode("citadelfp", &[0u8; 64]).is_err());
+        assert!(encode("", &[0u8; 4]).is_err());
+    }
+}
@@ -0,0 +1,206 @@
+//! Resource budgets for decoding untrusted input.
+//!
+//! Every decoder in Citadel takes a [`ParseBudget`] and checks it before
+//! allocating or doing work proportional to attacker-controlled sizes.
+//! This bounds memory and CPU for services that parse untrusted encodings,
+//! envelopes and key files.
+//!
+//! The binary formats share a [`Reader`] cursor, which turns truncation
+//! into `MisuseError::InvalidEncoding`.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let budget = ParseBudget::DEFAULT.with_max_input_len(4096);
+//! let bytes = base58::decode(untrusted, &budget)?;
+//! ```
+
+use crate::errors::{MisuseError, Result};
+
+/// Limits enforced while decoding untrusted input.
+///
+/// Exceeding any limit returns `MisuseError::ParseBudgetExceeded` before the
+/// corresponding allocation or processing happens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseBudget {
+    /// Maximum length of the encoded input in bytes.
+    pub max_input_len: usize,
+    /// Maximum length of the decoded output in bytes.
+    pub max_output_len: usize,
+    /// Maximum number of recipients in a multi-recipient structure.
+    pub max_recipients: usize,
+    /// Maximum number of chunks or parts in a chunked structure.
+    pub max_chunks: usize,
+}
+
+impl ParseBudget {
+    /// Conservative defaults suitable for keys, identifiers and small
+    /// envelopes.
+    pub const DEFAULT: Self = Self {
+        max_input_len: 64 * 1024,
+        max_output_len: 64 * 1024,
+        max_recipients: 16,
+        max_chunks: 1024,
+    };
+
+    /// Set the maximum encoded input length.
+    #[inline]
+    pub const fn with_max_input_len(mut self, max_input_len: usize) -> Self {
+        self.max_input_len = max_input_len;
+        self
+    }
+
+    /// Set the maximum decoded output length.
+    #[inline]
+    pub const fn with_max_output_len(mut self, max_output_len: usize) -> Self {
+        self.max_output_len = max_output_len;
+        self
+    }
+
+    /// Set the maximum recipient count.
+    #[inline]
+    pub const fn with_max_recipients(mut self, max_recipients: usize) -> Self {
+        self.max_recipients = max_recipients;
+        self
+    }
+
+    /// Set the maximum chunk count.
+    #[inline]
+    pub const fn with_max_chunks(mut self, max_chunks: usize) -> Self {
+        self.max_chunks = max_chunks;
+        self
+    }
+
+    /// Check an encoded input length against the budget.
+    #[inline]
+    pub fn check_input(&self, len: usize) -> Result<()> {
+        check(len, self.max_input_len)
+    }
+
+    /// Check a decoded output length against the budget.
+    #[inline]
+    pub fn check_output(&self, len: usize) -> Result<()> {
+        check(len, self.max_output_len)
+    }
+
+    /// Check a recipient count against the budget.
+    #[inline]
+    pub fn check_recipients(&self, count: usize) -> Result<()> {
+        check(count, self.max_recipients)
+    }
+
+    /// Check a chunk count against the budget.
+    #[inline]
+    pub fn check_chunks(&self, count: usize) -> Result<()> {
+        check(count, self.max_chunks)
+    }
+}
+
+impl Default for ParseBudget {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[inline]
+fn check(value: usize, limit: usize) -> Result<()> {
+    if value <= limit {
+        Ok(())
+    } else {
+        Err(MisuseError::ParseBudgetExceeded.into())
+    }
+}
+
+/// Cursor over the unparsed rest of a binary structure. Reading past the
+/// end is `MisuseError::InvalidEncoding`.
+pub(crate) struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    pub(crate) const fn new(bytes: &'a [u8]) -> Self {
+        Self(bytes)
+    }
+
+    /// The next `len` bytes.
+    pub(crate) fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let (taken, rest) = self
+            .0
+            .split_at_checked(len)
+            .ok_or(MisuseError::InvalidEncoding)?;
+        self.0 = rest;
+        Ok(taken)
+    }
+
+    /// The next byte.
+    pub(crate) fn byte(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    /// The next byte, without consuming it.
+    pub(crate) fn peek(&self) -> Option<u8> {
+        self.0.first().copied()
+    }
+
+    /// The bytes not yet read.
+    pub(crate) const fn rest(&self) -> &'a [u8] {
+        self.0
+    }
+
+    /// Fail unless every byte has been read.
+    pub(crate) fn finish(self) -> Result<()> {
+        if !self.0.is_empty() {
+            return Err(MisuseError::InvalidEncoding.into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_overrides_defaults() {
+        let budget = ParseBudget::DEFAULT
+            .with_max_input_len(10)
+            .with_max_output_len(20)
+            .with_max_recipients(3)
+            .with_max_chunks(4);
+        assert_eq!(
+            budget,
+            ParseBudget {
+                max_input_len: 10,
+                max_output_len: 20,
+                max_recipients: 3,
+                max_chunks: 4,
+            }
+        );
+    }
+
+    #[test]
+    fn reader_rejects_truncation() {
+        let mut reader = Reader::new(b"abcd");
+        assert_eq!(reader.take(2).unwrap(), b"ab");
+        assert_eq!(reader.peek(), Some(b'c'));
+        assert_eq!(reader.byte().unwrap(), b'c');
+        assert_eq!(reader.rest(), b"d");
+        assert_eq!(
+            reader.take(2).unwrap_err().misuse(),
+            Some(MisuseError::InvalidEncoding)
+        );
+        assert_eq!(
+            reader.finish().unwrap_err().misuse(),
+            Some(MisuseError::InvalidEncoding)
+        );
+        Reader::new(b"").finish().unwrap();
+    }
+
+    #[test]
+    fn checks_are_inclusive() {
+        let budget = ParseBudget::DEFAULT.with_max_input_len(10);
+        assert!(budget.check_input(10).is_ok());
+        assert_eq!(
+            budget.check_input(11).unwrap_err().misuse(),
+            Some(MisuseError::ParseBudgetExceeded)
+        );
+    }
+}
@@ -0,0 +1,516 @@
+//! JSON Canonicalization Scheme (RFC 8785).
+//!
+//! Produces a byte-exact canonical form of a JSON document so it can be
+//! signed and verified independently of how it was formatted:
+//!
+//! - No insignificant whitespace
+//! - Object members sorted by the UTF-16 code units of their names
+//! - Strings with the minimal escaping of ECMAScript `JSON.stringify`
+//! - Numbers as IEEE 754 doubles in ECMAScript `Number.toString` form
+//!
+//! # Strictness
+//!
+//! Input must be I-JSON (RFC 7493): duplicate member names, lone
+//! surrogates and numbers outside the double range are rejected rather
+//! than silently normalized, since two parties could otherwise canonicalize
+//! the same text differently.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let canonical = jcs::canonicalize(r#"{ "b": 1.50, "a": [true] }"#, &ParseBudget::DEFAULT)?;
+//! assert_eq!(canonical, r#"{"a":[true],"b":1.5}"#);
+//! ```
+
+use core::fmt::Write;
+
+use super::ParseBudget;
+use crate::errors::{MisuseError, Result};
+
+/// Maximum nesting depth of arrays and objects.
+pub const MAX_DEPTH: usize = 128;
+
+/// Canonicalize the JSON document `json`.
+///
+/// # Errors
+///
+/// - `MisuseError::ParseBudgetExceeded`: If `json` or its canonical form
+///   exceeds `budget`, or nesting exceeds [`MAX_DEPTH`]
+/// - `MisuseError::InvalidEncoding`: If `json` is not valid I-JSON
+pub fn canonicalize(json: &str, budget: &ParseBudget) -> Result<String> {
+    let out = parse(json, budget)?.to_canonical();
+    budget.check_output(out.len())?;
+    Ok(out)
+}
+
+/// Parse `json` into a [`Value`] with members in canonical order.
+///
+/// Errors as [`canonicalize`], except that the output length is not
+/// checked.
+pub(crate) fn parse(json: &str, budget: &ParseBudget) -> Result<Value> {
+    budget.check_input(json.len())?;
+    let mut parser = Parser {
+        input: json.as_bytes(),
+        position: 0,
+    };
+    parser.skip_whitespace();
+    let value = parser.value(0)?;
+    parser.skip_whitespace();
+    if parser.position != json.len() {
+        return Err(MisuseError::InvalidEncoding.into());
+    }
+    Ok(value)
+}
+
+/// A parsed I-JSON value. Object members are sorted and unique.
+pub(crate) enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// Build an object, sorting `members` into canonical order.
+    ///
+    /// Member names must be unique.
+    pub(crate) fn object(mut members: Vec<(String, Value)>) -> Self {
+        sort_members(&mut members);
+        debug_assert!(!has_duplicates(&members));
+        Value::Object(members)
+    }
+
+    /// The member `name`, if this is an object that has one.
+    pub(crate) fn get(&self, name: &str) -> Option<&Value> {
+        match self {
+            Value::Object(members) => members
+                .iter()
+                .find_map(|(key, value)| (key == name).then_some(value)),
+            _ => None,
+        }
+    }
+
+    /// The RFC 8785 serialization of this value.
+    pub(crate) fn to_canonical(&self) -> String {
+        let mut out = String::new();
+        self.write(&mut out);
+        out
+    }
+
+    fn write(&self, out: &mut String) {
+        match self {
+            Value::Null => out.push_str("null"),
+            Value::Bool(true) => out.push_str("true"),
+            Value::Bool(false) => out.push_str("false"),
+            Value::Number(number) => write_number(*number, out),
+            Value::String(string) => write_string(string, out),
+            Value::Array(items) => {
+                out.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    item.write(out);
+                }
+                out.push(']');
+            }
+            Value::Object(members) => {
+                out.push('{');
+                for (i, (name, value)) in members.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    write_string(name, out);
+                    out.push(':');
+                    value.write(out);
+                }
+                out.push('}');
+            }
+        }
+    }
+}
+
+struct Parser<'a> {
+    input: &'a [u8],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Result<u8> {
+        let byte = self.peek().ok_or(MisuseError::InvalidEncoding)?;
+        self.position += 1;
+        Ok(byte)
+    }
+
+    fn expect(&mut self, literal: &[u8]) -> Result<()> {
+        if !self.input[self.position..].starts_with(literal) {
+            return Err(MisuseError::InvalidEncoding.into());
+        }
+        self.position += literal.len();
+        Ok(())
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.position += 1;
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Value> {
+        match self.peek() {
+            Some(b'n') => self.expect(b"null").map(|()| Value::Null),
+            Some(b't') => self.expect(b"true").map(|()| Value::Bool(true)),
+            Some(b'f') => self.expect(b"false").map(|()| Value::Bool(false)),
+            Some(b'"') => self.string().map(Value::String),
+            Some(b'[') => self.array(depth + 1),
+            Some(b'{') => self.object(depth + 1),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            _ => Err(MisuseError::InvalidEncoding.into()),
+        }
+    }
+
+    fn array(&mut self, depth: usize) -> Result<Value> {
+        if depth > MAX_DEPTH {
+            return Err(MisuseError::ParseBudgetExceeded.into());
+        }
+        self.expect(b"[")?;
+        self.skip_whitespace();
+        let mut items = Vec::new();
+        if self.peek() == Some(b']') {
+            self.position += 1;
+            return Ok(Value::Array(items));
+        }
+        loop {
+            self.skip_whitespace();
+            items.push(self.value(depth)?);
+            self.skip_whitespace();
+            match self.next()? {
+                b',' => continue,
+                b']' => return Ok(Value::Array(items)),
+                _ => return Err(MisuseError::InvalidEncoding.into()),
+            }
+        }
+    }
+
+    fn object(&mut self, depth: usize) -> Result<Value> {
+        if depth > MAX_DEPTH {
+            return Err(MisuseError::ParseBudgetExceeded.into());
+        }
+        self.expect(b"{")?;
+        self.skip_whitespace();
+        let mut members = Vec::new();
+        if self.peek() == Some(b'}') {
+            self.position += 1;
+        } else {
+            loop {
+                self.skip_whitespace();
+                let name = self.string()?;
+                self.skip_whitespace();
+                self.expect(b":")?;
+                self.skip_whitespace();
+                members.push((name, self.value(depth)?));
+                self.skip_whitespace();
+                match self.next()? {
+                    b',' => continue,
+                    b'}' => break,
+                    _ => return Err(MisuseError::InvalidEncoding.into()),
+                }
+            }
+        }
+
+        sort_members(&mut members);
+        if has_duplicates(&members) {
+            return Err(MisuseError::InvalidEncoding.into());
+        }
+        Ok(Value::Object(members))
+    }
+
+    fn string(&mut self) -> Result<String> {
+        self.expect(b"\"")?;
+        let mut out = Vec::new();
+        loop {
+            match self.next()? {
+                b'"' => break,
+                b'\\' => {
+                    let escaped = match self.next()? {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => self.unicode_escape()?,
+                        _ => return Err(MisuseError::InvalidEncoding.into()),
+                    };
+                    out.extend_from_slice(escaped.encode_utf8(&mut [0; 4]).as_bytes());
+                }
+                0x00..=0x1f => return Err(MisuseError::InvalidEncoding.into()),
+                byte => out.push(byte),
+            }
+        }
+        // The input is a `str` and escapes produce whole characters
+        String::from_utf8(out).map_err(|_| MisuseError::InvalidEncoding.into())
+    }
+
+    /// The character of a `\u` escape (after the `u`), combining
+    /// surrogate pairs.
+    fn unicode_escape(&mut self) -> Result<char> {
+        let high = self.hex4()?;
+        let code = match high {
+            0xd800..=0xdbff => {
+                self.expect(b"\\u")?;
+                let low = self.hex4()?;
+                if !(0xdc00..=0xdfff).contains(&low) {
+                    return Err(MisuseError::InvalidEncoding.into());
+                }
+                0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+            }
+            0xdc00..=0xdfff => return Err(MisuseError::InvalidEncoding.into()),
+            _ => high,
+        };
+        char::from_u32(code).ok_or_else(|| MisuseError::InvalidEncoding.into())
+    }
+
+    fn hex4(&mut self) -> Result<u32> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = (self.next()? as char)
+                .to_digit(16)
+                .ok_or(MisuseError::InvalidEncoding)?;
+            code = code << 4 | digit;
+        }
+        Ok(code)
+    }
+
+    fn number(&mut self) -> Result<Value> {
+        let start = self.position;
+        if self.peek() == Some(b'-') {
+            self.position += 1;
+        }
+        match self.next()? {
+            b'0' => {}
+            b'1'..=b'9' => self.digits(),
+            _ => return Err(MisuseError::InvalidEncoding.into()),
+        }
+        if self.peek() == Some(b'.') {
+            self.position += 1;
+            self.required_digits()?;
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            self.position += 1;
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.position += 1;
+            }
+            self.required_digits()?;
+        }
+
+        let text = core::str::from_utf8(&self.input[start..self.position])
+            .map_err(|_| MisuseError::InvalidEncoding)?;
+        let number: f64 = text.parse().map_err(|_| MisuseError::InvalidEncoding)?;
+        if !number.is_finite() {
+            return Err(MisuseError::InvalidEncoding.into());
+        }
+        Ok(Value::Number(number))
+    }
+
+    fn digits(&mut self) {
+        while matches!(self.peek(), Some(b'0'..=b'9')) {
+            self.position += 1;
+        }
+    }
+
+    fn required_digits(&mut self) -> Result<()> {
+        if !matches!(self.peek(), Some(b'0'..=b'9')) {
+            return Err(MisuseError::InvalidEncoding.into());
+        }
+        self.digits();
+        Ok(())
+    }
+}
+
+fn sort_members(members: &mut [(String, Value)]) {
+    members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+}
+
+fn has_duplicates(sorted: &[(String, Value)]) -> bool {
+    sorted.windows(2).any(|pair| pair[0].0 == pair[1].0)
+}
+
+/// Serialize a string with `JSON.stringify` escaping.
+fn write_string(string: &str, out: &mut String) {
+    out.push('"');
+    for c in string.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{0}'..='\u{1f}' => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            _ => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Serialize a finite double like ECMAScript `Number.prototype.toString`.
+fn write_number(number: f64, out: &mut String) {
+    if number == 0.0 {
+        // Includes -0
+        out.push('0');
+        return;
+    }
+    if number < 0.0 {
+        out.push('-');
+    }
+
+    // `{:e}` yields the shortest round-tripping digits: "d[.ddd]e<exp>"
+    let scientific = format!("{:e}", number.abs());
+    let (mantissa, exponent) = scientific
+        .split_once('e')
+        .expect("LowerExp always has an exponent");
+    let digits: String = mantissa.chars().filter(|&c| c != '.').collect();
+    let k = digits.len() as i32;
+    // ECMAScript's n: the value is 0.digits * 10^n
+    let n = exponent
+        .parse::<i32>()
+        .expect("LowerExp exponent is an integer")
+        + 1;
+
+    if k <= n && n <= 21 {
+        out.push_str(&digits);
+        out.extend(core::iter::repeat_n('0', (n - k) as usize));
+    } else if 0 < n && n <= 21 {
+        out.push_str(&digits[..n as usize]);
+        out.push('.');
+        out.push_str(&digits[n as usize..]);
+    } else if -6 < n && n <= 0 {
+        out.push_str("0.");
+        out.extend(core::iter::repeat_n('0', (-n) as usize));
+        out.push_str(&digits);
+    } else {
+        out.push_str(&digits[..1]);
+        if k > 1 {
+            out.push('.');
+            out.push_str(&digits[1..]);
+        }
+        let _ = write!(out, "e{}{}", if n > 0 { '+' } else { '-' }, (n - 1).abs());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canonical(json: &str) -> Result<String> {
+        canonicalize(json, &ParseBudget::DEFAULT)
+    }
+
+    #[test]
+    fn rfc8785_example() {
+        let input = r#"{
+            "numbers": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001],
+            "string": "\u20ac$\u000F\u000aA'\u0042\u0022\u005c\\\"\/",
+            "literals": [null, true, false]
+        }"#;
+        assert_eq!(
+            canonical(input).unwrap(),
+            r#"{"literals":[null,true,false],"numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"string":"€$\u000f\nA'B\"\\\\\"/"}"#
+        );
+    }
+
+    #[test]
+    fn rfc8785_sorting_uses_utf16() {
+        let input = r#"{
+            "\u20ac": "Euro Sign",
+            "\r": "Carriage Return",
+            "\ufb33": "Hebrew Letter Dalet With Dagesh",
+            "1": "One",
+            "\ud83d\ude00": "Emoji: Grinning Face",
+            "\u0080": "Control",
+            "\u00f6": "Latin Small Letter O With Diaeresis"
+        }"#;
+        assert_eq!(
+            canonical(input).unwrap(),
+            concat!(
+                "{\"\\r\":\"Carriage Return\",\"1\":\"One\",\"\u{80}\":\"Control\",",
+                "\"ö\":\"Latin Small Letter O With Diaeresis\",\"€\":\"Euro Sign\",",
+                "\"😀\":\"Emoji: Grinning Face\",\"\u{fb33}\":\"Hebrew Letter Dalet With Dagesh\"}"
+            )
+        );
+    }
+
+    #[test]
+    fn numbers_use_ecmascript_form() {
+        for (input, expected) in [
+            ("1e21", "1e+21"),
+            ("1e20", "100000000000000000000"),
+            ("5e-324", "5e-324"),
+            ("1.7976931348623157e308", "1.7976931348623157e+308"),
+            ("0.1", "0.1"),
+            ("1e-7", "1e-7"),
+            ("1e-6", "0.000001"),
+            ("123e-20", "1.23e-18"),
+            ("-0", "0"),
+            ("-1.5", "-1.5"),
+            ("100", "100"),
+        ] {
+            assert_eq!(canonical(input).unwrap(), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_documents() {
+        for input in [
+            "",
+            "{",
+            "[1,]",
+            "{\"a\":1,}",
+            "01",
+            "1.",
+            "+1",
+            "1e400",
+            "nul",
+            "\"\\ud800\"",
+            "\"\\udc00\"",
+            "\"tab\there\"",
+            "{\"a\":1,\"a\":2}",
+            "[1] [2]",
+        ] {
+            assert_eq!(
+                canonical(input).unwrap_err().misuse(),
+                Some(MisuseError::InvalidEncoding),
+                "{input:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn enforces_budget_and_depth() {
+        let nested = "[".repeat(MAX_DEPTH + 1) + &"]".repeat(MAX_DEPTH + 1);
+        assert_eq!(
+            canonical(&nested).unwrap_err().misuse(),
+            Some(MisuseError::ParseBudgetExceeded)
+        );
+        let nested = "[".repeat(MAX_DEPTH) + &"]".repeat(MAX_DEPTH);
+        assert!(canonical(&nested).is_ok());
+
+        let budget = ParseBudget::DEFAULT.with_max_input_len(4);
+        assert!(canonicalize("[1,2]", &budget).is_err());
+        // Canonical numbers can be longer than their input
+        let budget = ParseBudget::DEFAULT.with_max_output_len(8);
+        assert!(canonicalize("1e20", &budget).is_err());
+    }
+}
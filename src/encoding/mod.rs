@@ -0,0 +1,44 @@
+//! Text encodings for public identifiers.
+//!
+//! # Purpose
+//!
+//! Key fingerprints and other public identifiers are exchanged by humans
+//! (copied, read aloud, typed). This module provides checksummed,
+//! address-style encodings for them:
+//!
+//! - `bech32`: Bech32m (BIP-350) with a human-readable prefix
+//! - `base58`: Base58 and Base58Check
+//! - `base64`: RFC 4648 Base64, for payloads and signatures in JSON formats
+//! - `multipart`: ordered, checksummed splitting of large payloads (e.g.,
+//!   post-quantum public keys) across QR codes or NFC taps
+//! - `jcs`: RFC 8785 canonical JSON, the signing input for
+//!   [`MlDsa87::sign_json`](crate::sign::MlDsa87::sign_json)
+//! - `pem`: RFC 7468 PEM armor around DER keys
+//!
+//! # Timing
+//!
+//! In `bech32` and `base58`, character mapping goes through
+//! [`memory::ct`](crate::memory::ct) and never indexes a table with
+//! data-dependent values, and checksum and radix-conversion loops run a
+//! number of iterations that depends only on the input length. The length
+//! of the produced output is inherently data-dependent for Base58 (leading
+//! zeros) and is not hidden.
+//!
+//! These encodings are intended for public data. Do not use them to
+//! transport secret keys; the exception is `pem`, whose Base64 body is
+//! decoded in constant time.
+//!
+//! # Untrusted Input
+//!
+//! Every decoder takes a [`ParseBudget`] and enforces it before allocating.
+
+pub mod base58;
+pub mod base64;
+pub mod bech32;
+mod budget;
+pub mod jcs;
+pub mod multipart;
+pub mod pem;
+
+pub use budget::ParseBudget;
+pub(crate) use budget::Reader;
@@ -0,0 +1,465 @@
+//! Ordered, checksummed multi-part encoding for out-of-band transfer.
+//!
+//! Post-quantum public keys are too large for a single QR code (an ML-DSA-87
+//! public key is 2592 bytes). This module splits a payload into a sequence of
+//! self-describing text parts that can be shown as a QR sequence or sent via
+//! NFC taps, and reassembles them in any order on the receiving side.
+//!
+//! # Part Format
+//!
+//! ```text
+//! CTD1/<index>/<total>/<set id>/<data>/<crc>
+//! ```
+//!
+//! - `index`, `total`: 1-based decimal position and part count
+//! - `set id`: Base32 of the first 5 bytes of `SHA-256(payload)`; binds every
+//!   part to the same payload and is verified after reassembly
+//! - `data`: Base32 (RFC 4648, no padding) chunk of the payload
+//! - `crc`: Base32 of the CRC-32 over everything before it, catching scan
+//!   errors in individual parts
+//!
+//! Every character is in the QR alphanumeric set (`0-9`, `A-Z`, `/`), so QR
+//! encoders can use the dense alphanumeric mode.
+//!
+//! # Integrity
+//!
+//! The set id and CRC detect accidental corruption and mixing of parts from
+//! different payloads. They are NOT a security mechanism: authenticate the
+//! reassembled key out of band (e.g., by comparing fingerprints).
+//!
+//! # Example
+//!
+//! ```ignore
+//! let parts = multipart::split(&public_key, 256)?;
+//! let mut reassembler = multipart::Reassembler::new(ParseBudget::DEFAULT);
+//! for part in scanned_parts {
+//!     reassembler.push(&part)?;
+//! }
+//! let public_key = reassembler.finish()?;
+//! ```
+
+use super::ParseBudget;
+use crate::algorithms::classical::sha256::Sha256;
+use crate::errors::{MisuseError, Result};
+
+/// Prefix identifying the format and its version.
+pub const PREFIX: &str = "CTD1";
+
+/// Maximum number of parts in a set.
+pub const MAX_PARTS: usize = 9999;
+
+const SET_ID_LENGTH: usize = 5;
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Split `data` into text parts carrying at most `chunk_size` payload bytes
+/// each.
+///
+/// # Errors
+///
+/// - `MisuseError::BufferTooSmall`: If `data` is empty or `chunk_size` is 0
+/// - `MisuseError::InvalidPlaintextLength`: If more than [`MAX_PARTS`] parts
+///   would be needed
+pub fn split(data: &[u8], chunk_size: usize) -> Result<Vec<String>> {
+    if data.is_empty() || chunk_size == 0 {
+        return Err(MisuseError::BufferTooSmall.into());
+    }
+
+    let total = data.len().div_ceil(chunk_size);
+    if total > MAX_PARTS {
+        return Err(MisuseError::InvalidPlaintextLength.into());
+    }
+
+    let set_id = set_id(data);
+    let set_id_text = base32_encode(&set_id);
+
+    let parts = data
+        .chunks(chunk_size)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let body = format!(
+                "{}/{}/{}/{}/{}",
+                PREFIX,
+                i + 1,
+                total,
+                set_id_text,
+                base32_encode(chunk)
+            );
+            let crc = crc32(body.as_bytes()).to_be_bytes();
+            format!("{}/{}", body, base32_encode(&crc))
+        })
+        .collect();
+    Ok(parts)
+}
+
+/// A single decoded part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Part {
+    /// 1-based position of this part.
+    pub index: usize,
+    /// Total number of parts in the set.
+    pub total: usize,
+    /// Identifier shared by all parts of one payload.
+    pub set_id: [u8; SET_ID_LENGTH],
+    /// Payload bytes carried by this part.
+    pub data: Vec<u8>,
+}
+
+impl Part {
+    /// Parse and checksum-verify a single text part.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::ParseBudgetExceeded`: If the part is longer, or
+    ///   declares more parts, than `budget` allows
+    /// - `MisuseError::InvalidEncoding`: If the part is malformed or its CRC
+    ///   does not match
+    pub fn parse(text: &str, budget: &ParseBudget) -> Result<Self> {
+        let text = text.trim();
+        budget.check_input(text.len())?;
+        let (body, crc_text) = text.rsplit_once('/').ok_or(MisuseError::InvalidEncoding)?;
+        let crc = base32_decode(crc_text)?;
+        if crc.as_slice() != crc32(body.as_bytes()).to_be_bytes() {
+            return Err(MisuseError::InvalidEncoding.into());
+        }
+
+        let mut fields = body.split('/');
+        let (Some(prefix), Some(index), Some(total), Some(set_id), Some(data), None) = (
+            fields.next(),
+            fields.next(),
+            fields.next(),
+            fields.next(),
+            fields.next(),
+            fields.next(),
+        ) else {
+            return Err(MisuseError::InvalidEncoding.into());
+        };
+
+        if prefix != PREFIX {
+            return Err(MisuseError::InvalidEncoding.into());
+        }
+        let index = parse_count(index)?;
+        let total = parse_count(total)?;
+        budget.check_chunks(total)?;
+        if index > total {
+            return Err(MisuseError::InvalidEncoding.into());
+        }
+        let set_id: [u8; SET_ID_LENGTH] = base32_decode(set_id)?
+            .try_into()
+            .map_err(|_| MisuseError::InvalidEncoding)?;
+        let data = base32_decode(data)?;
+        if data.is_empty() {
+            return Err(MisuseError::InvalidEncoding.into());
+        }
+
+        Ok(Self {
+            index,
+            total,
+            set_id,
+            data,
+        })
+    }
+}
+
+/// Collects parts in any order and reassembles the payload.
+///
+/// Duplicate parts (e.g., the same QR code scanned twice) are accepted if
+/// they are identical to the part already received.
+///
+/// The part count and the total reassembled size are bounded by the
+/// [`ParseBudget`] given at construction.
+#[derive(Debug, Default)]
+pub struct Reassembler {
+    budget: ParseBudget,
+    set_id: Option<[u8; SET_ID_LENGTH]>,
+    parts: Vec<Option<Vec<u8>>>,
+    received: usize,
+    received_bytes: usize,
+}
+
+impl Reassembler {
+    /// Create an empty reassembler enforcing `budget`.
+    pub fn new(budget: ParseBudget) -> Self {
+        Self {
+            budget,
+            ..Self::default()
+        }
+    }
+
+    /// Add a text part.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::ParseBudgetExceeded`: If the part, the declared part
+    ///   count, or the payload received so far exceeds the budget
+    /// - `MisuseError::InvalidEncoding`: If the part is malformed, belongs to
+    ///   a different set, or conflicts with a part already received
+    pub fn push(&mut self, text: &str) -> Result<()> {
+        let part = Part::parse(text, &self.budget)?;
+        self.push_part(part)
+    }
+
+    /// Add an already-parsed part.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`push`](Reassembler::push).
+    pub fn push_part(&mut self, part: Part) -> Result<()> {
+        match self.set_id {
+            None => {
+                // Checked here too: `part` may not have come from `Part::parse`
+                self.budget.check_chunks(part.total)?;
+                self.set_id = Some(part.set_id);
+                self.parts = vec![None; part.total];
+            }
+            Some(set_id) if set_id != part.set_id || self.parts.len() != part.total => {
+                return Err(MisuseError::InvalidEncoding.into());
+            }
+            Some(_) => {}
+        }
+
+        if part.index == 0 || part.index > self.parts.len() {
+            return Err(MisuseError::InvalidEncoding.into());
+        }
+        let slot = &mut self.parts[part.index - 1];
+        match slot {
+            Some(existing) if *existing != part.data => Err(MisuseError::InvalidEncoding.into()),
+            Some(_) => Ok(()),
+            None => {
+                let received_bytes = self.received_bytes.saturating_add(part.data.len());
+                self.budget.check_output(received_bytes)?;
+                *slot = Some(part.data);
+                self.received += 1;
+                self.received_bytes = received_bytes;
+                Ok(())
+            }
+        }
+    }
+
+    /// Number of distinct parts received so far.
+    pub fn received(&self) -> usize {
+        self.received
+    }
+
+    /// Total number of parts expected (0 until the first part arrives).
+    pub fn total(&self) -> usize {
+        self.parts.len()
+    }
+
+    /// 1-based indices of parts not yet received.
+    pub fn missing(&self) -> Vec<usize> {
+        self.parts
+            .iter()
+            .enumerate()
+            .filter(|(_, part)| part.is_none())
+            .map(|(i, _)| i + 1)
+            .collect()
+    }
+
+    /// Check whether every part has been received.
+    pub fn is_complete(&self) -> bool {
+        !self.parts.is_empty() && self.received == self.parts.len()
+    }
+
+    /// Reassemble the payload and verify it against the set id.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidState`: If parts are still missing
+    /// - `MisuseError::InvalidEncoding`: If the reassembled payload does not
+    ///   match the set id
+    pub fn finish(self) -> Result<Vec<u8>> {
+        if !self.is_complete() {
+            return Err(MisuseError::InvalidState.into());
+        }
+
+        let data: Vec<u8> = self.parts.into_iter().flatten().flatten().collect();
+        if Some(set_id(&data)) != self.set_id {
+            return Err(MisuseError::InvalidEncoding.into());
+        }
+        Ok(data)
+    }
+}
+
+fn set_id(data: &[u8]) -> [u8; SET_ID_LENGTH] {
+    let digest = Sha256::digest(data);
+    let mut id = [0u8; SET_ID_LENGTH];
+    id.copy_from_slice(&digest[..SET_ID_LENGTH]);
+    id
+}
+
+fn parse_count(text: &str) -> Result<usize> {
+    if text.is_empty() || text.len() > 4 || !text.bytes().all(|c| c.is_ascii_digit()) {
+        return Err(MisuseError::InvalidEncoding.into());
+    }
+    let value: usize = text.parse().map_err(|_| MisuseError::InvalidEncoding)?;
+    if value == 0 || value > MAX_PARTS {
+        return Err(MisuseError::InvalidEncoding.into());
+    }
+    Ok(value)
+}
+
+fn base32_encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(5) * 8);
+    let mut accumulator = 0u32;
+    let mut bits = 0u32;
+    for &byte in data {
+        accumulator = (accumulator << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(BASE32_ALPHABET[((accumulator >> bits) & 31) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(BASE32_ALPHABET[((accumulator << (5 - bits)) & 31) as usize] as char);
+    }
+    out
+}
+
+fn base32_decode(text: &str) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(text.len() * 5 / 8);
+    let mut accumulator = 0u32;
+    let mut bits = 0u32;
+    for c in text.bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'2'..=b'7' => c - b'2' + 26,
+            _ => return Err(MisuseError::InvalidEncoding.into()),
+        };
+        accumulator = (accumulator << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((accumulator >> bits) as u8);
+        }
+    }
+    // Reject non-canonical trailing bits
+    if bits >= 5 || accumulator & ((1 << bits) - 1) != 0 {
+        return Err(MisuseError::InvalidEncoding.into());
+    }
+    Ok(out)
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB8_8320 & 0u32.wrapping_sub(crc & 1));
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 31 + 7) as u8).collect()
+    }
+
+    #[test]
+    fn crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn base32_rfc4648_vectors() {
+        let vectors = [
+            ("", ""),
+            ("f", "MY"),
+            ("fo", "MZXQ"),
+            ("foo", "MZXW6"),
+            ("foob", "MZXW6YQ"),
+            ("fooba", "MZXW6YTB"),
+            ("foobar", "MZXW6YTBOI"),
+        ];
+        for (plain, encoded) in vectors {
+            assert_eq!(base32_encode(plain.as_bytes()), encoded);
+            assert_eq!(base32_decode(encoded).unwrap(), plain.as_bytes());
+        }
+    }
+
+    #[test]
+    fn parts_use_qr_alphanumeric_charset() {
+        for part in split(&sample(300), 64).unwrap() {
+            assert!(
+                part.bytes()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == b'/')
+            );
+        }
+    }
+
+    #[test]
+    fn round_trip_out_of_order_with_duplicates() {
+        let data = sample(crate::consts::MlDsa87::PUBLIC_KEY_SIZE);
+        let parts = split(&data, 300).unwrap();
+        assert_eq!(parts.len(), 9);
+
+        let mut reassembler = Reassembler::new(ParseBudget::DEFAULT);
+        for part in parts.iter().rev() {
+            reassembler.push(part).unwrap();
+        }
+        reassembler.push(&parts[4]).unwrap();
+        assert!(reassembler.is_complete());
+        assert_eq!(reassembler.finish().unwrap(), data);
+    }
+
+    #[test]
+    fn reports_missing_parts() {
+        let parts = split(&sample(100), 10).unwrap();
+        let mut reassembler = Reassembler::new(ParseBudget::DEFAULT);
+        for (i, part) in parts.iter().enumerate() {
+            if i != 2 && i != 7 {
+                reassembler.push(part).unwrap();
+            }
+        }
+        assert_eq!(reassembler.missing(), vec![3, 8]);
+        assert!(reassembler.finish().is_err());
+    }
+
+    #[test]
+    fn rejects_corrupted_part() {
+        let parts = split(&sample(100), 50).unwrap();
+        let corrupted = parts[0].replacen('A', "B", 1);
+        assert_ne!(corrupted, parts[0]);
+        assert!(Part::parse(&corrupted, &ParseBudget::DEFAULT).is_err());
+    }
+
+    #[test]
+    fn rejects_parts_from_different_sets() {
+        let first = split(&sample(100), 50).unwrap();
+        let second = split(&sample(101), 50).unwrap();
+        let mut reassembler = Reassembler::new(ParseBudget::DEFAULT);
+        reassembler.push(&first[0]).unwrap();
+        assert!(reassembler.push(&second[1]).is_err());
+    }
+
+    #[test]
+    fn enforces_parse_budget() {
+        let parts = split(&sample(100), 10).unwrap();
+
+        let budget = ParseBudget::DEFAULT.with_max_chunks(9);
+        assert_eq!(
+            Part::parse(&parts[0], &budget).unwrap_err().misuse(),
+            Some(MisuseError::ParseBudgetExceeded)
+        );
+
+        let mut reassembler = Reassembler::new(ParseBudget::DEFAULT.with_max_output_len(50));
+        for part in &parts[..5] {
+            reassembler.push(part).unwrap();
+        }
+        assert_eq!(
+            reassembler.push(&parts[5]).unwrap_err().misuse(),
+            Some(MisuseError::ParseBudgetExceeded)
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_split_arguments() {
+        assert!(split(&[], 10).is_err());
+        assert!(split(&[1, 2, 3], 0).is_err());
+        assert!(split(&sample(10_000), 1).is_err());
+    }
+}
@@ -0,0 +1,161 @@
+//! PEM armor (RFC 7468) around DER.
+//!
+//! Writes the strict form: a `-----BEGIN <label>-----` line, Base64 in
+//! lines of 64 characters, and a matching `-----END <label>-----` line.
+//! Reading is lax about whitespace, as RFC 7468 asks of parsers, but not
+//! about anything else: the label must be the expected one, and text
+//! before or after the armor is rejected.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let text = pem::encode("PUBLIC KEY", &spki);
+//! let der = pem::decode(&text, "PUBLIC KEY", &ParseBudget::DEFAULT)?;
+//! ```
+//!
+//! # Timing
+//!
+//! The Base64 body goes through the constant-time `base64` routines, so
+//! PEM is safe for secret keys: only the body length and the positions of
+//! its line breaks affect timing.
+
+use super::{ParseBudget, base64};
+use crate::errors::{MisuseError, Result};
+use crate::memory::SecureBuffer;
+
+const LINE_LENGTH: usize = 64;
+
+/// Armor `der` under `label`, e.g. `"PUBLIC KEY"`.
+pub fn encode(label: &str, der: &[u8]) -> String {
+    String::from_utf8(armor(label, der)).expect("PEM is ASCII")
+}
+
+/// Remove the armor labelled `label` and return the DER inside.
+///
+/// # Errors
+///
+/// - `MisuseError::ParseBudgetExceeded`: If the input or its decoded size
+///   exceeds `budget`
+/// - `MisuseError::InvalidEncoding`: If `pem` is not a single PEM block
+///   labelled `label`, or its body is not valid Base64
+pub fn decode(pem: &str, label: &str, budget: &ParseBudget) -> Result<Vec<u8>> {
+    unarmor(pem.as_bytes(), label, budget).map(SecureBuffer::into_vec)
+}
+
+/// [`encode`] into zeroizing storage, for secret keys.
+pub(crate) fn encode_secret(label: &str, der: &[u8]) -> SecureBuffer {
+    SecureBuffer::new(armor(label, der))
+}
+
+/// [`decode`] into zeroizing storage, for secret keys.
+pub(crate) fn decode_secret(pem: &str, label: &str, budget: &ParseBudget) -> Result<SecureBuffer> {
+    unarmor(pem.as_bytes(), label, budget)
+}
+
+fn armor(label: &str, der: &[u8]) -> Vec<u8> {
+    let body = der.len().div_ceil(3) * 4;
+    let lines = body.div_ceil(LINE_LENGTH);
+    let length = 2 * label.len() + 32 + body + lines;
+    // Sized up front so the contents are never copied by a reallocation
+    let mut out = Vec::with_capacity(length);
+    out.extend_from_slice(b"-----BEGIN ");
+    out.extend_from_slice(label.as_bytes());
+    out.extend_from_slice(b"-----\n");
+    for chunk in der.chunks(LINE_LENGTH / 4 * 3) {
+        base64::encode_secret(chunk, &mut out);
+        out.push(b'\n');
+    }
+    out.extend_from_slice(b"-----END ");
+    out.extend_from_slice(label.as_bytes());
+    out.extend_from_slice(b"-----\n");
+    debug_assert_eq!(out.len(), length);
+    out
+}
+
+fn unarmor(pem: &[u8], label: &str, budget: &ParseBudget) -> Result<SecureBuffer> {
+    budget.check_input(pem.len())?;
+    let body = pem
+        .trim_ascii()
+        .strip_prefix(b"-----BEGIN ")
+        .and_then(|rest| rest.strip_prefix(label.as_bytes()))
+        .and_then(|rest| rest.strip_prefix(b"-----"))
+        .and_then(|rest| rest.strip_suffix(b"-----"))
+        .and_then(|rest| rest.strip_suffix(label.as_bytes()))
+        .and_then(|rest| rest.strip_suffix(b"-----END "))
+        .ok_or(MisuseError::InvalidEncoding)?;
+
+    // Whitespace sits between lines, so skipping it branches on the layout
+    // only, not on the encoded bytes
+    let mut base64 = SecureBuffer::zeroed(body.len());
+    let mut length = 0;
+    for &c in body {
+        if !c.is_ascii_whitespace() {
+            base64.as_mut_slice()[length] = c;
+            length += 1;
+        }
+    }
+    let base64 = &base64.as_slice()[..length];
+    let decoded_len = base64::decoded_len(base64).ok_or(MisuseError::InvalidEncoding)?;
+    budget.check_output(decoded_len)?;
+    let mut der = SecureBuffer::zeroed(decoded_len);
+    base64::decode_secret(base64, der.as_mut_slice())?;
+    Ok(der)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_and_line_length() {
+        let der: Vec<u8> = (0..=255).collect();
+        let pem = encode("PUBLIC KEY", &der);
+        assert!(pem.starts_with("-----BEGIN PUBLIC KEY-----\n"));
+        assert!(pem.ends_with("\n-----END PUBLIC KEY-----\n"));
+        assert!(pem.lines().all(|line| line.len() <= LINE_LENGTH));
+        assert_eq!(
+            decode(&pem, "PUBLIC KEY", &ParseBudget::DEFAULT).unwrap(),
+            der
+        );
+
+        let secret = encode_secret("PRIVATE KEY", &der[..40]);
+        let secret = core::str::from_utf8(secret.as_slice()).unwrap();
+        assert_eq!(secret, encode("PRIVATE KEY", &der[..40]));
+        let decoded = decode_secret(secret, "PRIVATE KEY", &ParseBudget::DEFAULT).unwrap();
+        assert_eq!(decoded.as_slice(), &der[..40]);
+
+        // CRLF line endings and surrounding whitespace
+        let crlf = format!("\n{}\n", pem.replace('\n', "\r\n"));
+        assert_eq!(
+            decode(&crlf, "PUBLIC KEY", &ParseBudget::DEFAULT).unwrap(),
+            der
+        );
+    }
+
+    #[test]
+    fn rejects_other_labels_and_malformed_armor() {
+        let pem = encode("PUBLIC KEY", b"key");
+        for (input, label) in [
+            (pem.as_str(), "PRIVATE KEY"),
+            (&pem[..pem.len() - 2], "PUBLIC KEY"),
+            (
+                "-----BEGIN PUBLIC KEY-----\nS2V*\n-----END PUBLIC KEY-----",
+                "PUBLIC KEY",
+            ),
+            (&format!("comment\n{pem}"), "PUBLIC KEY"),
+        ] {
+            assert_eq!(
+                decode(input, label, &ParseBudget::DEFAULT)
+                    .unwrap_err()
+                    .misuse(),
+                Some(MisuseError::InvalidEncoding),
+                "{input}"
+            );
+        }
+        let budget = ParseBudget::DEFAULT.with_max_input_len(16);
+        assert_eq!(
+            decode(&pem, "PUBLIC KEY", &budget).unwrap_err().misuse(),
+            Some(MisuseError::ParseBudgetExceeded)
+        );
+    }
+}
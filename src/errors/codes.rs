@@ -0,0 +1,204 @@
+//! Stable numeric codes and names for every error variant.
+//!
+//! # Purpose
+//!
+//! FFI layers and network protocols cannot carry Rust enums. Every
+//! [`CryptoError`] and [`MisuseError`] variant has a `u16` code and an
+//! upper-case name here, available as Rust constants, as a C header
+//! ([`c_header`]) and as JSON ([`json`]). The generated files are checked
+//! in under `include/` and a test fails when they fall out of date.
+//!
+//! # Stability
+//!
+//! - Codes are never reused or renumbered. New variants take the next free
+//!   code of their class
+//! - Crypto errors use `0x01xx`, misuse errors `0x02xx`; [`OK`] (zero) is
+//!   reserved for success
+//! - Adding a variant without a code is a compile error
+//!
+//! # Example
+//!
+//! ```ignore
+//! let frame_code = error.code();
+//! // ...on the other side of the wire
+//! let error = Error::from_code(frame_code).unwrap_or(CryptoError::OperationFailed.into());
+//! ```
+
+use super::{CryptoError, Error, MisuseError};
+use crate::encoding::jcs::Value;
+
+/// Success, for FFI return values.
+pub const OK: u16 = 0x0000;
+
+/// One row of the translation table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ErrorCode {
+    /// Stable numeric code.
+    pub code: u16,
+    /// Stable upper-case name, e.g. `VERIFICATION_FAILED`.
+    pub name: &'static str,
+    /// The error itself.
+    pub error: Error,
+}
+
+macro_rules! codes {
+    ($($name:ident = $code:literal => $class:ident($kind:ident::$variant:ident),)*) => {
+        $(
+            #[doc = concat!("Code of `", stringify!($kind), "::", stringify!($variant), "`.")]
+            pub const $name: u16 = $code;
+        )*
+
+        /// Every error code, in ascending order.
+        pub const ALL: &[ErrorCode] = &[
+            $(ErrorCode {
+                code: $code,
+                name: stringify!($name),
+                error: Error::$class($kind::$variant),
+            },)*
+        ];
+
+        impl Error {
+            /// Stable numeric code of this error.
+            pub const fn code(&self) -> u16 {
+                match self {
+                    $(Error::$class($kind::$variant) => $code,)*
+                }
+            }
+
+            /// Stable upper-case name of this error, e.g.
+            /// `VERIFICATION_FAILED`.
+            pub const fn name(&self) -> &'static str {
+                match self {
+                    $(Error::$class($kind::$variant) => stringify!($name),)*
+                }
+            }
+
+            /// The error with numeric code `code`, if any.
+            pub const fn from_code(code: u16) -> Option<Self> {
+                match code {
+                    $($code => Some(Error::$class($kind::$variant)),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+codes! {
+    VERIFICATION_FAILED = 0x0101 => Crypto(CryptoError::VerificationFailed),
+    DECRYPTION_FAILED = 0x0102 => Crypto(CryptoError::DecryptionFailed),
+    INVALID_CIPHERTEXT = 0x0103 => Crypto(CryptoError::InvalidCiphertext),
+    KEY_ENCAPSULATION_FAILED = 0x0104 => Crypto(CryptoError::KeyEncapsulationFailed),
+    INTERNAL_FAILURE = 0x0105 => Crypto(CryptoError::InternalFailure),
+    OPERATION_FAILED = 0x0106 => Crypto(CryptoError::OperationFailed),
+    RANDOMNESS_UNAVAILABLE = 0x0107 => Crypto(CryptoError::RandomnessUnavailable),
+    SIGNER_UNAVAILABLE = 0x0108 => Crypto(CryptoError::SignerUnavailable),
+    RANDOMNESS_FAILURE = 0x0109 => Crypto(CryptoError::RandomnessFailure),
+    INVALID_KEY_LENGTH = 0x0201 => Misuse(MisuseError::InvalidKeyLength),
+    INVALID_SIGNATURE_LENGTH = 0x0202 => Misuse(MisuseError::InvalidSignatureLength),
+    INVALID_CIPHERTEXT_LENGTH = 0x0203 => Misuse(MisuseError::InvalidCiphertextLength),
+    INVALID_PUBLIC_KEY_LENGTH = 0x0204 => Misuse(MisuseError::InvalidPublicKeyLength),
+    INVALID_SECRET_KEY_LENGTH = 0x0205 => Misuse(MisuseError::InvalidSecretKeyLength),
+    INVALID_SHARED_SECRET_LENGTH = 0x0206 => Misuse(MisuseError::InvalidSharedSecretLength),
+    INVALID_PLAINTEXT_LENGTH = 0x0207 => Misuse(MisuseError::InvalidPlaintextLength),
+    INVALID_NONCE_LENGTH = 0x0208 => Misuse(MisuseError::InvalidNonceLength),
+    INVALID_TAG_LENGTH = 0x0209 => Misuse(MisuseError::InvalidTagLength),
+    BUFFER_TOO_SMALL = 0x020a => Misuse(MisuseError::BufferTooSmall),
+    UNSUPPORTED_ALGORITHM = 0x020b => Misuse(MisuseError::UnsupportedAlgorithm),
+    UNSUPPORTED_HYBRID_MODE = 0x020c => Misuse(MisuseError::UnsupportedHybridMode),
+    INVALID_PARAMETER_SET = 0x020d => Misuse(MisuseError::InvalidParameterSet),
+    INVALID_ALGORITHM_IDENTIFIER = 0x020e => Misuse(MisuseError::InvalidAlgorithmIdentifier),
+    CONTEXT_TOO_LONG = 0x020f => Misuse(MisuseError::ContextTooLong),
+    ASSOCIATED_DATA_TOO_LONG = 0x0210 => Misuse(MisuseError::AssociatedDataTooLong),
+    FEATURE_NOT_ENABLED = 0x0211 => Misuse(MisuseError::FeatureNotEnabled),
+    INVALID_STATE = 0x0212 => Misuse(MisuseError::InvalidState),
+    INVALID_ENCODING = 0x0213 => Misuse(MisuseError::InvalidEncoding),
+    PARSE_BUDGET_EXCEEDED = 0x0214 => Misuse(MisuseError::ParseBudgetExceeded),
+    NONCE_EXHAUSTED = 0x0215 => Misuse(MisuseError::NonceExhausted),
+    DEADLINE_EXCEEDED = 0x0216 => Misuse(MisuseError::DeadlineExceeded),
+    CANCELLED = 0x0217 => Misuse(MisuseError::Cancelled),
+}
+
+/// The table as a C header defining `CITADEL_OK` and one
+/// `CITADEL_E_<NAME>` macro per error.
+pub fn c_header() -> String {
+    let mut header = String::from(
+        "/* Generated by citadel::errors::codes::c_header(). Do not edit. */\n\
+         #ifndef CITADEL_ERRORS_H\n\
+         #define CITADEL_ERRORS_H\n\n",
+    );
+    header.push_str(&format!("#define CITADEL_OK 0x{OK:04x}\n"));
+    for entry in ALL {
+        let (_, message) = class_and_message(entry.error);
+        header.push_str(&format!(
+            "#define CITADEL_E_{} 0x{:04x} /* {message} */\n",
+            entry.name, entry.code
+        ));
+    }
+    header.push_str("\n#endif /* CITADEL_ERRORS_H */\n");
+    header
+}
+
+/// The table as canonical JSON: an array of objects with `code`, `name`,
+/// `class` (`"crypto"` or `"misuse"`) and `message` members.
+pub fn json() -> String {
+    let entries = ALL
+        .iter()
+        .map(|entry| {
+            let (class, message) = class_and_message(entry.error);
+            Value::object(vec![
+                ("code".into(), Value::Number(entry.code.into())),
+                ("name".into(), Value::String(entry.name.into())),
+                ("class".into(), Value::String(class.into())),
+                ("message".into(), Value::String(message)),
+            ])
+        })
+        .collect();
+    Value::Array(entries).to_canonical() + "\n"
+}
+
+fn class_and_message(error: Error) -> (&'static str, String) {
+    match error {
+        Error::Crypto(e) => ("crypto", e.to_string()),
+        Error::Misuse(e) => ("misuse", e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_is_consistent() {
+        assert!(ALL.windows(2).all(|pair| pair[0].code < pair[1].code));
+        for entry in ALL {
+            assert_eq!(entry.error.code(), entry.code);
+            assert_eq!(entry.error.name(), entry.name);
+            assert_eq!(Error::from_code(entry.code), Some(entry.error));
+            let class = if entry.error.is_crypto() { 0x01 } else { 0x02 };
+            assert_eq!(entry.code >> 8, class);
+        }
+        assert_eq!(Error::from_code(OK), None);
+        assert_eq!(Error::from(MisuseError::Cancelled).code(), super::CANCELLED);
+    }
+
+    /// Set `CITADEL_REGENERATE=1` to rewrite the checked-in files.
+    #[test]
+    fn generated_files_are_current() {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/include");
+        for (file, contents) in [
+            ("citadel_errors.h", c_header()),
+            ("citadel_errors.json", json()),
+        ] {
+            let path = format!("{dir}/{file}");
+            if std::env::var_os("CITADEL_REGENERATE").is_some() {
+                std::fs::write(&path, &contents).unwrap();
+            }
+            let on_disk = std::fs::read_to_string(&path).unwrap_or_default();
+            assert!(
+                on_disk == contents,
+                "{path} is out of date; rerun with CITADEL_REGENERATE=1"
+            );
+        }
+    }
+}
@@ -16,6 +16,7 @@ use core::fmt;
 /// These are coarse-grained by design. Multiple internal failure modes
 /// map to the same error variant to prevent information leakage.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum CryptoError {
     /// Signature or MAC verification failed.
     ///
@@ -56,6 +57,26 @@ pub enum CryptoError {
     /// Used for operations that don't fit other categories or
     /// where more specific categorization would leak information.
     OperationFailed,
+
+    /// The system random number generator failed.
+    ///
+    /// Operations that need fresh randomness (key generation,
+    /// encapsulation, randomized signing) refuse to proceed without it.
+    RandomnessUnavailable,
+
+    /// The random number generator's output failed a health test.
+    ///
+    /// The source looks stuck or badly biased. Operations that need
+    /// fresh randomness refuse to proceed. The source must pass its startup
+    /// test again before the next operation uses it, so a broken source
+    /// keeps failing while a one-off false positive clears on retry.
+    RandomnessFailure,
+
+    /// An external signer (remote service or HSM) failed to produce a
+    /// signature.
+    ///
+    /// Transport and policy failures of the signer are not distinguished.
+    SignerUnavailable,
 }
 
 impl CryptoError {
@@ -70,6 +91,16 @@ impl CryptoError {
     pub const fn is_decryption_failure(&self) -> bool {
         matches!(self, CryptoError::DecryptionFailed)
     }
+
+    /// Returns true if this error comes from the random number generator
+    /// rather than from the data or keys involved.
+    #[inline]
+    pub const fn is_randomness_failure(&self) -> bool {
+        matches!(
+            self,
+            CryptoError::RandomnessUnavailable | CryptoError::RandomnessFailure
+        )
+    }
 }
 
 impl fmt::Display for CryptoError {
@@ -83,6 +114,9 @@ impl fmt::Display for CryptoError {
             CryptoError::KeyEncapsulationFailed => "key encapsulation failed",
             CryptoError::InternalFailure => "internal process failed",
             CryptoError::OperationFailed => "cryptographic operation failed",
+            CryptoError::RandomnessUnavailable => "randomness unavailable",
+            CryptoError::RandomnessFailure => "randomness health test failed",
+            CryptoError::SignerUnavailable => "signer unavailable",
         };
         f.write_str(msg)
     }
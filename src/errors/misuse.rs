@@ -14,6 +14,7 @@ use core::fmt;
 /// These are deterministic and should be caught during development.
 /// They do not represent cryptographic failures.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum MisuseError {
     /// Key has invalid length for the specified algorithm.
     ///
@@ -89,6 +90,30 @@ pub enum MisuseError {
     /// The object is not in the correct state to perform
     /// the requested operation. Check API usage.
     InvalidState,
+
+    /// Encoded input is malformed.
+    ///
+    /// The text or byte encoding contains invalid characters, has an
+    /// invalid length or structure, or fails its checksum.
+    InvalidEncoding,
+
+    /// Untrusted input exceeds the configured parse budget.
+    ///
+    /// The input is larger, or declares more recipients or chunks, than
+    /// the `ParseBudget` passed to the decoder allows.
+    ParseBudgetExceeded,
+
+    /// A nonce sequence has produced every nonce it can.
+    ///
+    /// Rekey before encrypting further messages under the same key.
+    NonceExhausted,
+
+    /// The operation context's deadline passed before the operation
+    /// completed.
+    DeadlineExceeded,
+
+    /// The operation context's cancellation token was triggered.
+    Cancelled,
 }
 
 impl MisuseError {
@@ -107,6 +132,7 @@ impl MisuseError {
                 | MisuseError::InvalidNonceLength
                 | MisuseError::InvalidTagLength
                 | MisuseError::BufferTooSmall
+                | MisuseError::ParseBudgetExceeded
         )
     }
 
@@ -146,6 +172,11 @@ impl fmt::Display for MisuseError {
             MisuseError::AssociatedDataTooLong => "associated data exceeds maximum length",
             MisuseError::FeatureNotEnabled => "feature not enabled at compile time",
             MisuseError::InvalidState => "invalid state for operation",
+            MisuseError::InvalidEncoding => "malformed encoded input",
+            MisuseError::ParseBudgetExceeded => "input exceeds parse budget",
+            MisuseError::NonceExhausted => "nonce sequence exhausted",
+            MisuseError::DeadlineExceeded => "operation deadline exceeded",
+            MisuseError::Cancelled => "operation cancelled",
         };
         f.write_str(msg)
     }
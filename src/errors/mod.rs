@@ -26,9 +26,15 @@
 //!
 //! Display implementations avoid dynamic formatting and algorithm details.
 //! In security-sensitive contexts, consider treating all errors uniformly.
+//!
+//! ## Error Codes
+//!
+//! [`codes`] assigns every variant a stable `u16` code and name for FFI
+//! layers and wire protocols; see [`Error::code`] and [`Error::from_code`].
 
 use core::fmt;
 
+pub mod codes;
 mod crypto;
 mod misuse;
 
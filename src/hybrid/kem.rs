@@ -0,0 +1,504 @@
+//! Hybrid key encapsulation.
+//!
+//! [`HybridKem`] runs a post-quantum and a classical KEM side by side and
+//! derives one shared secret from both with a [`KemCombiner`].
+//!
+//! # Example
+//!
+//! ```ignore
+//! let kem = HybridKem::new(MlKem768, DhKem(X25519), Sha3Combiner);
+//! let (public_key, secret_key) = kem.generate_keypair()?;
+//! let (ciphertext, sender_secret) = kem.encapsulate(&public_key)?;
+//! let recipient_secret = kem.decapsulate(&secret_key, &ciphertext)?;
+//! ```
+
+use core::fmt;
+
+use crate::algorithms::classical::keccak::sha3_256;
+use crate::api::kem::SharedSecret;
+use crate::errors::{CryptoError, MisuseError, Result};
+use crate::internal::traits::KeyEncapsulation;
+use crate::memory::SensitiveBytes;
+
+/// Everything a combiner may bind into the hybrid shared secret.
+///
+/// Secrets are borrowed from zeroizing storage; combiners must not copy
+/// them anywhere that outlives the call.
+#[derive(Clone, Copy)]
+pub struct CombinerInput<'a> {
+    /// Post-quantum shared secret.
+    pub pq_shared_secret: &'a [u8; 32],
+    /// Classical shared secret.
+    pub classical_shared_secret: &'a [u8; 32],
+    /// Post-quantum ciphertext.
+    pub pq_ciphertext: &'a [u8],
+    /// Classical ciphertext.
+    pub classical_ciphertext: &'a [u8],
+    /// Recipient's post-quantum public key.
+    pub pq_public_key: &'a [u8],
+    /// Recipient's classical public key.
+    pub classical_public_key: &'a [u8],
+}
+
+impl fmt::Debug for CombinerInput<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CombinerInput { <redacted> }")
+    }
+}
+
+/// Derives the hybrid shared secret from both component outputs.
+pub trait KemCombiner {
+    /// Combine both shared secrets and their context into 32 bytes.
+    fn combine(&self, input: &CombinerInput<'_>) -> [u8; 32];
+}
+
+/// Default combiner.
+///
+/// `SHA3-256(label || ss_pq || ss_classical || ct_pq || ct_classical)`
+/// with label `"citadel hybrid-kem v1"`. Every field has a fixed size for a
+/// given instantiation, so the concatenation is unambiguous.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Sha3Combiner;
+
+const SHA3_COMBINER_LABEL: &[u8] = b"citadel hybrid-kem v1";
+
+impl KemCombiner for Sha3Combiner {
+    fn combine(&self, input: &CombinerInput<'_>) -> [u8; 32] {
+        sha3_256(&[
+            SHA3_COMBINER_LABEL,
+            input.pq_shared_secret,
+            input.classical_shared_secret,
+            input.pq_ciphertext,
+            input.classical_ciphertext,
+        ])
+    }
+}
+
+/// The X-Wing combiner (draft-connolly-cfrg-xwing-kem).
+///
+/// `SHA3-256(ss_pq || ss_classical || ct_classical || pk_classical || label)`
+/// with the 6-byte label `\.//^\`. It omits the post-quantum ciphertext,
+/// relying on ML-KEM's ciphertext binding, so use it only with ML-KEM.
+///
+/// X-Wing itself feeds the raw X25519 output in as `ss_classical`; pairing
+/// this combiner with [`DhKem`](crate::internal::traits::DhKem), which
+/// already hashes the DH output, does not produce X-Wing-compatible secrets.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct XWingCombiner;
+
+const XWING_LABEL: &[u8] = b"\\.//^\\";
+
+impl KemCombiner for XWingCombiner {
+    fn combine(&self, input: &CombinerInput<'_>) -> [u8; 32] {
+        sha3_256(&[
+            input.pq_shared_secret,
+            input.classical_shared_secret,
+            input.classical_ciphertext,
+            input.classical_public_key,
+            XWING_LABEL,
+        ])
+    }
+}
+
+/// Hybrid public key: both component public keys.
+#[derive(Clone, PartialEq, Eq)]
+pub struct HybridPublicKey<const PQ: usize, const CLASSICAL: usize> {
+    pq: [u8; PQ],
+    classical: [u8; CLASSICAL],
+}
+
+impl<const PQ: usize, const CLASSICAL: usize> HybridPublicKey<PQ, CLASSICAL> {
+    /// Encoded size in bytes.
+    pub const SIZE: usize = PQ + CLASSICAL;
+
+    /// Parse `pq || classical`.
+    ///
+    /// Only the length is checked; each component KEM validates its own key
+    /// when it is used.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidPublicKeyLength`: If `bytes` is not
+    ///   [`Self::SIZE`] bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != Self::SIZE {
+            return Err(MisuseError::InvalidPublicKeyLength.into());
+        }
+        let (pq, classical) = bytes.split_at(PQ);
+        Ok(Self {
+            pq: pq.try_into().expect("length checked"),
+            classical: classical.try_into().expect("length checked"),
+        })
+    }
+
+    /// Encode as `pq || classical`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [&self.pq[..], &self.classical[..]].concat()
+    }
+
+    /// Post-quantum component.
+    #[inline]
+    pub fn pq(&self) -> &[u8; PQ] {
+        &self.pq
+    }
+
+    /// Classical component.
+    #[inline]
+    pub fn classical(&self) -> &[u8; CLASSICAL] {
+        &self.classical
+    }
+}
+
+impl<const PQ: usize, const CLASSICAL: usize> fmt::Debug for HybridPublicKey<PQ, CLASSICAL> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("HybridPublicKey { .. }")
+    }
+}
+
+/// Hybrid secret key: both component secret keys plus the public key,
+/// which combiners may bind into the shared secret. Zeroized on drop.
+pub struct HybridSecretKey<
+    const PQ_SK: usize,
+    const CLASSICAL_SK: usize,
+    const PQ_PK: usize,
+    const CLASSICAL_PK: usize,
+> {
+    pq: SensitiveBytes<PQ_SK>,
+    classical: SensitiveBytes<CLASSICAL_SK>,
+    public_key: HybridPublicKey<PQ_PK, CLASSICAL_PK>,
+}
+
+crate::memory::never_serialize!([
+    const PQ_SK: usize,
+    const CLASSICAL_SK: usize,
+    const PQ_PK: usize,
+    const CLASSICAL_PK: usize,
+] HybridSecretKey<PQ_SK, CLASSICAL_SK, PQ_PK, CLASSICAL_PK>);
+
+impl<const PQ_SK: usize, const CLASSICAL_SK: usize, const PQ_PK: usize, const CLASSICAL_PK: usize>
+    HybridSecretKey<PQ_SK, CLASSICAL_SK, PQ_PK, CLASSICAL_PK>
+{
+    /// Assemble a secret key from its components.
+    ///
+    /// The components are not checked against each other; a mismatched
+    /// public key yields shared secrets that do not match the sender's.
+    pub fn from_parts(
+        pq: [u8; PQ_SK],
+        classical: [u8; CLASSICAL_SK],
+        public_key: HybridPublicKey<PQ_PK, CLASSICAL_PK>,
+    ) -> Self {
+        Self {
+            pq: SensitiveBytes::new(pq),
+            classical: SensitiveBytes::new(classical),
+            public_key,
+        }
+    }
+
+    /// The matching public key.
+    #[inline]
+    pub fn public_key(&self) -> &HybridPublicKey<PQ_PK, CLASSICAL_PK> {
+        &self.public_key
+    }
+}
+
+impl<const PQ_SK: usize, const CLASSICAL_SK: usize, const PQ_PK: usize, const CLASSICAL_PK: usize>
+    fmt::Debug for HybridSecretKey<PQ_SK, CLASSICAL_SK, PQ_PK, CLASSICAL_PK>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("HybridSecretKey { <redacted> }")
+    }
+}
+
+/// Hybrid ciphertext: both component ciphertexts.
+#[derive(Clone, PartialEq, Eq)]
+pub struct HybridCiphertext<const PQ: usize, const CLASSICAL: usize> {
+    pq: [u8; PQ],
+    classical: [u8; CLASSICAL],
+}
+
+impl<const PQ: usize, const CLASSICAL: usize> HybridCiphertext<PQ, CLASSICAL> {
+    /// Encoded size in bytes.
+    pub const SIZE: usize = PQ + CLASSICAL;
+
+    /// Parse `pq || classical`.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidCiphertextLength`: If `bytes` is not
+    ///   [`Self::SIZE`] bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != Self::SIZE {
+            return Err(MisuseError::InvalidCiphertextLength.into());
+        }
+        let (pq, classical) = bytes.split_at(PQ);
+        Ok(Self {
+            pq: pq.try_into().expect("length checked"),
+            classical: classical.try_into().expect("length checked"),
+        })
+    }
+
+    /// Encode as `pq || classical`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [&self.pq[..], &self.classical[..]].concat()
+    }
+}
+
+impl<const PQ: usize, const CLASSICAL: usize> fmt::Debug for HybridCiphertext<PQ, CLASSICAL> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("HybridCiphertext { .. }")
+    }
+}
+
+/// A post-quantum KEM and a classical KEM combined into one.
+///
+/// Component sizes are inferred from the [`KeyEncapsulation`]
+/// implementations; both must produce 32-byte shared secrets.
+///
+/// # Type Parameters
+///
+/// - `PQ`: Post-quantum KEM
+/// - `C`: Classical KEM, e.g. `DhKem<X25519>`
+/// - `K`: Shared-secret combiner, [`Sha3Combiner`] by default
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HybridKem<PQ, C, K = Sha3Combiner> {
+    pq: PQ,
+    classical: C,
+    combiner: K,
+}
+
+impl<PQ, C, K: KemCombiner> HybridKem<PQ, C, K> {
+    /// Compose `pq` and `classical` with `combiner`.
+    pub const fn new(pq: PQ, classical: C, combiner: K) -> Self {
+        Self {
+            pq,
+            classical,
+            combiner,
+        }
+    }
+
+    /// Generate a keypair for both components.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If RNG fails
+    /// - `CryptoError::RandomnessFailure`: If RNG fails a health test
+    #[allow(clippy::type_complexity)]
+    pub fn generate_keypair<
+        const PQ_PK: usize,
+        const PQ_SK: usize,
+        const PQ_CT: usize,
+        const C_PK: usize,
+        const C_SK: usize,
+        const C_CT: usize,
+    >(
+        &self,
+    ) -> Result<(
+        HybridPublicKey<PQ_PK, C_PK>,
+        HybridSecretKey<PQ_SK, C_SK, PQ_PK, C_PK>,
+    )>
+    where
+        PQ: KeyEncapsulation<PQ_PK, PQ_SK, PQ_CT, 32>,
+        C: KeyEncapsulation<C_PK, C_SK, C_CT, 32>,
+    {
+        let (pq_pk, pq_sk) = self.pq.generate_keypair()?;
+        let pq_sk = SensitiveBytes::new(pq_sk);
+        let (c_pk, c_sk) = self.classical.generate_keypair()?;
+        let public_key = HybridPublicKey {
+            pq: pq_pk,
+            classical: c_pk,
+        };
+        Ok((
+            public_key.clone(),
+            HybridSecretKey {
+                pq: pq_sk,
+                classical: SensitiveBytes::new(c_sk),
+                public_key,
+            },
+        ))
+    }
+
+    /// Encapsulate a fresh hybrid shared secret to `public_key`.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If RNG fails
+    /// - `CryptoError::RandomnessFailure`: If RNG fails a health test
+    /// - Any error from either component's encapsulation
+    pub fn encapsulate<
+        const PQ_PK: usize,
+        const PQ_SK: usize,
+        const PQ_CT: usize,
+        const C_PK: usize,
+        const C_SK: usize,
+        const C_CT: usize,
+    >(
+        &self,
+        public_key: &HybridPublicKey<PQ_PK, C_PK>,
+    ) -> Result<(HybridCiphertext<PQ_CT, C_CT>, SharedSecret)>
+    where
+        PQ: KeyEncapsulation<PQ_PK, PQ_SK, PQ_CT, 32>,
+        C: KeyEncapsulation<C_PK, C_SK, C_CT, 32>,
+    {
+        let (pq_ct, pq_ss) = self.pq.encapsulate(&public_key.pq)?;
+        let pq_ss = SensitiveBytes::new(pq_ss);
+        let (c_ct, c_ss) = self.classical.encapsulate(&public_key.classical)?;
+        let c_ss = SensitiveBytes::new(c_ss);
+        let ciphertext = HybridCiphertext {
+            pq: pq_ct,
+            classical: c_ct,
+        };
+        let shared = self.combine(public_key, &ciphertext, &pq_ss, &c_ss);
+        Ok((ciphertext, shared))
+    }
+
+    /// Recover the hybrid shared secret from `ciphertext`.
+    ///
+    /// Both components are always decapsulated before either result is
+    /// inspected, so the failing component is not revealed by timing.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::KeyEncapsulationFailed`: If either component fails
+    pub fn decapsulate<
+        const PQ_PK: usize,
+        const PQ_SK: usize,
+        const PQ_CT: usize,
+        const C_PK: usize,
+        const C_SK: usize,
+        const C_CT: usize,
+    >(
+        &self,
+        secret_key: &HybridSecretKey<PQ_SK, C_SK, PQ_PK, C_PK>,
+        ciphertext: &HybridCiphertext<PQ_CT, C_CT>,
+    ) -> Result<SharedSecret>
+    where
+        PQ: KeyEncapsulation<PQ_PK, PQ_SK, PQ_CT, 32>,
+        C: KeyEncapsulation<C_PK, C_SK, C_CT, 32>,
+    {
+        let pq_result = self
+            .pq
+            .decapsulate(secret_key.pq.as_bytes(), &ciphertext.pq);
+        let c_result = self
+            .classical
+            .decapsulate(secret_key.classical.as_bytes(), &ciphertext.classical);
+
+        match (pq_result, c_result) {
+            (Ok(pq_ss), Ok(c_ss)) => {
+                let pq_ss = SensitiveBytes::new(pq_ss);
+                let c_ss = SensitiveBytes::new(c_ss);
+                Ok(self.combine(&secret_key.public_key, ciphertext, &pq_ss, &c_ss))
+            }
+            (pq_result, c_result) => {
+                drop(pq_result.map(SensitiveBytes::new));
+                drop(c_result.map(SensitiveBytes::new));
+                Err(CryptoError::KeyEncapsulationFailed.into())
+            }
+        }
+    }
+
+    fn combine<const PQ_PK: usize, const C_PK: usize, const PQ_CT: usize, const C_CT: usize>(
+        &self,
+        public_key: &HybridPublicKey<PQ_PK, C_PK>,
+        ciphertext: &HybridCiphertext<PQ_CT, C_CT>,
+        pq_shared_secret: &SensitiveBytes<32>,
+        classical_shared_secret: &SensitiveBytes<32>,
+    ) -> SharedSecret {
+        SharedSecret(SensitiveBytes::new(self.combiner.combine(&CombinerInput {
+            pq_shared_secret: pq_shared_secret.as_bytes(),
+            classical_shared_secret: classical_shared_secret.as_bytes(),
+            pq_ciphertext: &ciphertext.pq,
+            classical_ciphertext: &ciphertext.classical,
+            pq_public_key: &public_key.pq,
+            classical_public_key: &public_key.classical,
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "mlkem")]
+    use crate::algorithms::classical::x25519::X25519;
+    #[cfg(feature = "mlkem")]
+    use crate::algorithms::pq::ml_kem_768::MlKem768;
+    #[cfg(feature = "mlkem")]
+    use crate::internal::traits::DhKem;
+
+    fn unhex(hex: &str) -> [u8; 32] {
+        let bytes: Vec<u8> = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect();
+        bytes.try_into().unwrap()
+    }
+
+    fn sample_input() -> CombinerInput<'static> {
+        CombinerInput {
+            pq_shared_secret: &[0x01; 32],
+            classical_shared_secret: &[0x02; 32],
+            pq_ciphertext: &[0x03; 48],
+            classical_ciphertext: &[0x04; 32],
+            pq_public_key: &[0x05; 64],
+            classical_public_key: &[0x06; 32],
+        }
+    }
+
+    #[test]
+    fn sha3_combiner_known_answer() {
+        assert_eq!(
+            Sha3Combiner.combine(&sample_input()),
+            unhex("f4ea66e796c64323f6c54e13a9609f4eecd9c165a3ea74524a6e0648fc34440b")
+        );
+    }
+
+    #[test]
+    fn xwing_combiner_known_answer() {
+        assert_eq!(
+            XWingCombiner.combine(&sample_input()),
+            unhex("c635d299c366a4d19699ee8294f3fa85e4602821f53c1f92c3902ce3a16750a3")
+        );
+    }
+
+    #[cfg(feature = "mlkem")]
+    #[test]
+    fn ml_kem_x25519_round_trip() {
+        let kem = HybridKem::new(MlKem768, DhKem(X25519), Sha3Combiner);
+        let (public_key, secret_key) = kem.generate_keypair().unwrap();
+        let (ciphertext, sender) = kem.encapsulate(&public_key).unwrap();
+        let recipient = kem.decapsulate(&secret_key, &ciphertext).unwrap();
+        assert_eq!(sender.as_bytes(), recipient.as_bytes());
+
+        let parsed = HybridCiphertext::from_bytes(&ciphertext.to_bytes()).unwrap();
+        assert!(parsed == ciphertext);
+        let parsed = HybridPublicKey::from_bytes(&public_key.to_bytes()).unwrap();
+        assert!(&parsed == secret_key.public_key());
+    }
+
+    #[cfg(feature = "mlkem")]
+    #[test]
+    fn tampering_either_component_changes_or_fails() {
+        let kem = HybridKem::new(MlKem768, DhKem(X25519), XWingCombiner);
+        let (public_key, secret_key) = kem.generate_keypair().unwrap();
+        let (ciphertext, sender) = kem.encapsulate(&public_key).unwrap();
+
+        // ML-KEM implicitly rejects: a different secret, no error
+        let mut tampered = ciphertext.clone();
+        tampered.pq[0] ^= 1;
+        let recipient = kem.decapsulate(&secret_key, &tampered).unwrap();
+        assert_ne!(sender.as_bytes(), recipient.as_bytes());
+
+        // A low-order classical ciphertext is rejected outright
+        let mut tampered = ciphertext;
+        tampered.classical = [0u8; 32];
+        assert!(kem.decapsulate(&secret_key, &tampered).is_err());
+    }
+
+    #[test]
+    fn parsing_checks_lengths() {
+        assert!(HybridPublicKey::<4, 2>::from_bytes(&[0u8; 5]).is_err());
+        assert!(HybridCiphertext::<4, 2>::from_bytes(&[0u8; 7]).is_err());
+        let ct = HybridCiphertext::<4, 2>::from_bytes(&[1, 2, 3, 4, 5, 6]).unwrap();
+        assert_eq!(ct.pq, [1, 2, 3, 4]);
+        assert_eq!(ct.classical, [5, 6]);
+    }
+}
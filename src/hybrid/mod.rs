@@ -0,0 +1,34 @@
+//! Hybrid constructions.
+//!
+//! # Purpose
+//!
+//! Hybrids pair a post-quantum scheme with a classical one so the result
+//! stays secure as long as either component does. They are wrapper types
+//! over the [`internal::traits`](crate::internal::traits) abstractions, so
+//! any pair of implementations can be composed.
+//!
+//! # Constructions
+//!
+//! - [`HybridKem`]: two KEMs and a shared-secret combiner
+//! - [`HybridSigner`]: two signature schemes, both of which must verify
+//!
+//! # Security
+//!
+//! A hybrid KEM only survives the break of one component if the combiner
+//! binds both shared secrets and both ciphertexts. Use [`Sha3Combiner`]
+//! unless interoperating with a specific published construction.
+//!
+//! A hybrid signature is only as strong as the stronger component if
+//! verifiers reject it when either component fails; [`HybridSigner`] has no
+//! "either" mode.
+
+mod kem;
+mod sig;
+
+pub use kem::{
+    CombinerInput, HybridCiphertext, HybridKem, HybridPublicKey, HybridSecretKey, KemCombiner,
+    Sha3Combiner, XWingCombiner,
+};
+pub use sig::{HybridSignature, HybridSigner, HybridVerifyingKey};
+#[cfg(feature = "signing")]
+pub use sig::HybridSigningKey;
@@ -0,0 +1,381 @@
+//! Hybrid signatures.
+//!
+//! [`HybridSigner`] signs every message with a post-quantum and a classical
+//! scheme and only accepts a [`HybridSignature`] if both components verify.
+//!
+//! Both components sign `"citadel hybrid-sig v1" || message` rather than
+//! the bare message, so a component cannot be stripped from a hybrid
+//! signature and passed off as a standalone signature over `message`.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let signer = HybridSigner::new(MlDsa87, Ed25519);
+//! let (verifying_key, signing_key) = signer.generate_keypair()?;
+//! let signature = signer.sign(&signing_key, b"release manifest")?;
+//! signer.verify(&verifying_key, b"release manifest", &signature)?;
+//! ```
+
+use core::fmt;
+
+use crate::errors::{CryptoError, MisuseError, Result};
+#[cfg(feature = "signing")]
+use crate::internal::traits::SignatureScheme;
+use crate::internal::traits::SignatureVerifier;
+#[cfg(feature = "signing")]
+use crate::memory::SensitiveBytes;
+
+/// Prefix signed by both components.
+const DOMAIN: &[u8] = b"citadel hybrid-sig v1";
+
+/// Hybrid verifying key: both component public keys.
+#[derive(Clone, PartialEq, Eq)]
+pub struct HybridVerifyingKey<const PQ: usize, const CLASSICAL: usize> {
+    pq: [u8; PQ],
+    classical: [u8; CLASSICAL],
+}
+
+impl<const PQ: usize, const CLASSICAL: usize> HybridVerifyingKey<PQ, CLASSICAL> {
+    /// Encoded size in bytes.
+    pub const SIZE: usize = PQ + CLASSICAL;
+
+    /// Parse `pq || classical`.
+    ///
+    /// Only the length is checked; each component scheme validates its own
+    /// key during verification.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidPublicKeyLength`: If `bytes` is not
+    ///   [`Self::SIZE`] bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != Self::SIZE {
+            return Err(MisuseError::InvalidPublicKeyLength.into());
+        }
+        let (pq, classical) = bytes.split_at(PQ);
+        Ok(Self {
+            pq: pq.try_into().expect("length checked"),
+            classical: classical.try_into().expect("length checked"),
+        })
+    }
+
+    /// Encode as `pq || classical`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [&self.pq[..], &self.classical[..]].concat()
+    }
+
+    /// Post-quantum component.
+    #[inline]
+    pub fn pq(&self) -> &[u8; PQ] {
+        &self.pq
+    }
+
+    /// Classical component.
+    #[inline]
+    pub fn classical(&self) -> &[u8; CLASSICAL] {
+        &self.classical
+    }
+}
+
+impl<const PQ: usize, const CLASSICAL: usize> fmt::Debug for HybridVerifyingKey<PQ, CLASSICAL> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("HybridVerifyingKey { .. }")
+    }
+}
+
+/// Hybrid signing key: both component secret keys. Zeroized on drop.
+#[cfg(feature = "signing")]
+pub struct HybridSigningKey<const PQ: usize, const CLASSICAL: usize> {
+    pq: SensitiveBytes<PQ>,
+    classical: SensitiveBytes<CLASSICAL>,
+}
+
+#[cfg(feature = "signing")]
+crate::memory::never_serialize!([const PQ: usize, const CLASSICAL: usize] HybridSigningKey<PQ, CLASSICAL>);
+
+#[cfg(feature = "signing")]
+impl<const PQ: usize, const CLASSICAL: usize> HybridSigningKey<PQ, CLASSICAL> {
+    /// Assemble a signing key from its components.
+    ///
+    /// The components are not checked against each other; a mismatched
+    /// pair produces signatures that never verify.
+    pub fn from_parts(pq: [u8; PQ], classical: [u8; CLASSICAL]) -> Self {
+        Self {
+            pq: SensitiveBytes::new(pq),
+            classical: SensitiveBytes::new(classical),
+        }
+    }
+}
+
+#[cfg(feature = "signing")]
+impl<const PQ: usize, const CLASSICAL: usize> fmt::Debug for HybridSigningKey<PQ, CLASSICAL> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("HybridSigningKey { <redacted> }")
+    }
+}
+
+/// Composite signature: both component signatures.
+///
+/// The encoding is the fixed-size concatenation `pq || classical`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct HybridSignature<const PQ: usize, const CLASSICAL: usize> {
+    pq: Box<[u8; PQ]>,
+    classical: Box<[u8; CLASSICAL]>,
+}
+
+impl<const PQ: usize, const CLASSICAL: usize> HybridSignature<PQ, CLASSICAL> {
+    /// Encoded size in bytes.
+    pub const SIZE: usize = PQ + CLASSICAL;
+
+    /// Parse `pq || classical`.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidSignatureLength`: If `bytes` is not
+    ///   [`Self::SIZE`] bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != Self::SIZE {
+            return Err(MisuseError::InvalidSignatureLength.into());
+        }
+        let (pq, classical) = bytes.split_at(PQ);
+        Ok(Self {
+            pq: Box::new(pq.try_into().expect("length checked")),
+            classical: Box::new(classical.try_into().expect("length checked")),
+        })
+    }
+
+    /// Encode as `pq || classical`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [&self.pq[..], &self.classical[..]].concat()
+    }
+}
+
+impl<const PQ: usize, const CLASSICAL: usize> fmt::Debug for HybridSignature<PQ, CLASSICAL> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("HybridSignature { .. }")
+    }
+}
+
+/// A post-quantum and a classical signature scheme combined into one.
+///
+/// A hybrid signature is valid only if both components are valid, so
+/// forging one requires breaking both schemes. Component sizes are
+/// inferred from the [`SignatureScheme`](crate::internal::traits::SignatureScheme)
+/// and [`SignatureVerifier`] implementations. Key generation and signing
+/// need the `signing` feature; verification is always available.
+///
+/// # Type Parameters
+///
+/// - `PQ`: Post-quantum scheme, e.g. [`sign::MlDsa87`](crate::sign::MlDsa87)
+/// - `C`: Classical scheme, e.g. [`sign::Ed25519`](crate::sign::Ed25519)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HybridSigner<PQ, C> {
+    pq: PQ,
+    classical: C,
+}
+
+impl<PQ, C> HybridSigner<PQ, C> {
+    /// Compose `pq` and `classical`.
+    pub const fn new(pq: PQ, classical: C) -> Self {
+        Self { pq, classical }
+    }
+
+    /// Generate a keypair for both components.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If RNG fails
+    /// - `CryptoError::RandomnessFailure`: If RNG fails a health test
+    #[cfg(feature = "signing")]
+    #[allow(clippy::type_complexity)]
+    pub fn generate_keypair<
+        const PQ_PK: usize,
+        const PQ_SK: usize,
+        const PQ_SIG: usize,
+        const C_PK: usize,
+        const C_SK: usize,
+        const C_SIG: usize,
+    >(
+        &self,
+    ) -> Result<(
+        HybridVerifyingKey<PQ_PK, C_PK>,
+        HybridSigningKey<PQ_SK, C_SK>,
+    )>
+    where
+        PQ: SignatureScheme<PQ_PK, PQ_SK, PQ_SIG>,
+        C: SignatureScheme<C_PK, C_SK, C_SIG>,
+    {
+        let (pq_pk, pq_sk) = self.pq.generate_keypair()?;
+        let pq_sk = SensitiveBytes::new(pq_sk);
+        let (c_pk, c_sk) = self.classical.generate_keypair()?;
+        Ok((
+            HybridVerifyingKey {
+                pq: pq_pk,
+                classical: c_pk,
+            },
+            HybridSigningKey {
+                pq: pq_sk,
+                classical: SensitiveBytes::new(c_sk),
+            },
+        ))
+    }
+
+    /// Sign `message` with both components.
+    ///
+    /// # Errors
+    ///
+    /// - Any error from either component's signing
+    #[cfg(feature = "signing")]
+    pub fn sign<
+        const PQ_PK: usize,
+        const PQ_SK: usize,
+        const PQ_SIG: usize,
+        const C_PK: usize,
+        const C_SK: usize,
+        const C_SIG: usize,
+    >(
+        &self,
+        signing_key: &HybridSigningKey<PQ_SK, C_SK>,
+        message: &[u8],
+    ) -> Result<HybridSignature<PQ_SIG, C_SIG>>
+    where
+        PQ: SignatureScheme<PQ_PK, PQ_SK, PQ_SIG>,
+        C: SignatureScheme<C_PK, C_SK, C_SIG>,
+    {
+        let bound = bind(message);
+        Ok(HybridSignature {
+            pq: Box::new(self.pq.sign(signing_key.pq.as_bytes(), &bound)?),
+            classical: Box::new(
+                self.classical
+                    .sign(signing_key.classical.as_bytes(), &bound)?,
+            ),
+        })
+    }
+
+    /// Verify both components of `signature` over `message`.
+    ///
+    /// Both components are always verified before either result is
+    /// inspected, so the failing component is not revealed by timing.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::VerificationFailed`: If either component is invalid
+    pub fn verify<const PQ_PK: usize, const PQ_SIG: usize, const C_PK: usize, const C_SIG: usize>(
+        &self,
+        verifying_key: &HybridVerifyingKey<PQ_PK, C_PK>,
+        message: &[u8],
+        signature: &HybridSignature<PQ_SIG, C_SIG>,
+    ) -> Result<()>
+    where
+        PQ: SignatureVerifier<PQ_PK, PQ_SIG>,
+        C: SignatureVerifier<C_PK, C_SIG>,
+    {
+        let bound = bind(message);
+        let pq_result = self.pq.verify(&verifying_key.pq, &bound, &signature.pq);
+        let c_result =
+            self.classical
+                .verify(&verifying_key.classical, &bound, &signature.classical);
+        match (pq_result, c_result) {
+            (Ok(()), Ok(())) => Ok(()),
+            _ => Err(CryptoError::VerificationFailed.into()),
+        }
+    }
+}
+
+/// The message each component signs.
+fn bind(message: &[u8]) -> Vec<u8> {
+    [DOMAIN, message].concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::Error;
+    #[cfg(all(feature = "mldsa", feature = "signing"))]
+    use crate::sign::{Ed25519, MlDsa87};
+
+    #[cfg(all(feature = "mldsa", feature = "signing"))]
+    #[test]
+    fn ml_dsa_ed25519_round_trip() {
+        let signer = HybridSigner::new(MlDsa87, Ed25519);
+        let (verifying_key, signing_key) = signer.generate_keypair().unwrap();
+        let signature = signer.sign(&signing_key, b"release").unwrap();
+        signer
+            .verify(&verifying_key, b"release", &signature)
+            .unwrap();
+        assert_eq!(
+            signer
+                .verify(&verifying_key, b"relea5e", &signature)
+                .unwrap_err(),
+            Error::Crypto(CryptoError::VerificationFailed)
+        );
+
+        let encoded = signature.to_bytes();
+        assert_eq!(
+            encoded.len(),
+            MlDsa87::SIGNATURE_SIZE + Ed25519::SIGNATURE_SIZE
+        );
+        let parsed = HybridSignature::from_bytes(&encoded).unwrap();
+        assert!(parsed == signature);
+        let parsed = HybridVerifyingKey::from_bytes(&verifying_key.to_bytes()).unwrap();
+        signer.verify(&parsed, b"release", &signature).unwrap();
+    }
+
+    #[cfg(all(feature = "mldsa", feature = "signing"))]
+    #[test]
+    fn either_component_failing_rejects() {
+        let signer = HybridSigner::new(MlDsa87, Ed25519);
+        let (verifying_key, signing_key) = signer.generate_keypair().unwrap();
+        let signature = signer.sign(&signing_key, b"release").unwrap();
+
+        let mut tampered = signature.clone();
+        tampered.pq[0] ^= 1;
+        assert!(
+            signer
+                .verify(&verifying_key, b"release", &tampered)
+                .is_err()
+        );
+
+        let mut tampered = signature;
+        tampered.classical[0] ^= 1;
+        assert!(
+            signer
+                .verify(&verifying_key, b"release", &tampered)
+                .is_err()
+        );
+    }
+
+    #[cfg(all(feature = "mldsa", feature = "signing"))]
+    #[test]
+    fn components_do_not_verify_standalone() {
+        let signer = HybridSigner::new(MlDsa87, Ed25519);
+        let (verifying_key, signing_key) = signer.generate_keypair().unwrap();
+        let signature = signer.sign(&signing_key, b"release").unwrap();
+        assert!(
+            Ed25519
+                .verify(verifying_key.classical(), b"release", &signature.classical)
+                .is_err()
+        );
+        assert!(
+            Ed25519
+                .verify(
+                    verifying_key.classical(),
+                    &bind(b"release"),
+                    &signature.classical
+                )
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn parsing_checks_lengths() {
+        assert!(HybridVerifyingKey::<4, 2>::from_bytes(&[0u8; 5]).is_err());
+        assert_eq!(
+            HybridSignature::<4, 2>::from_bytes(&[0u8; 7]).unwrap_err(),
+            Error::Misuse(MisuseError::InvalidSignatureLength)
+        );
+        let sig = HybridSignature::<4, 2>::from_bytes(&[1, 2, 3, 4, 5, 6]).unwrap();
+        assert_eq!(*sig.pq, [1, 2, 3, 4]);
+        assert_eq!(*sig.classical, [5, 6]);
+    }
+}
@@ -0,0 +1,135 @@
+//! AEAD dispatch by [`AlgorithmId`] with caller-chosen nonces.
+//!
+//! Constructions that derive their own nonces (HPKE, STREAM) need to
+//! encrypt under an exact nonce with an algorithm chosen at run time. The
+//! public [`DynAead`](crate::dynamic::DynAead) always draws a random nonce,
+//! so they go through here instead. Ciphertexts are `ciphertext || tag`.
+
+use crate::algorithm::AlgorithmId;
+#[cfg(feature = "aegis256")]
+use crate::algorithms::classical::aegis256;
+#[cfg(feature = "aes-gcm-siv")]
+use crate::algorithms::classical::aes256_gcm_siv;
+use crate::errors::{MisuseError, Result};
+#[cfg(any(feature = "aes-gcm-siv", feature = "aegis256"))]
+use crate::internal::traits::AeadCipher;
+#[cfg(any(feature = "aes-gcm-siv", feature = "aegis256"))]
+use crate::nonce::Nonce;
+
+/// Encrypt `plaintext` under `key` and `nonce`.
+///
+/// # Errors
+///
+/// - `MisuseError::InvalidAlgorithmIdentifier`: If `algorithm` is not an
+///   AEAD
+/// - `MisuseError::FeatureNotEnabled`: If the algorithm's feature is off
+/// - `MisuseError::InvalidKeyLength` / `MisuseError::InvalidNonceLength`:
+///   If `key` or `nonce` has the wrong length
+#[cfg_attr(
+    not(any(feature = "aes-gcm-siv", feature = "aegis256")),
+    allow(unused_variables)
+)]
+pub(crate) fn seal(
+    algorithm: AlgorithmId,
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    plaintext: &[u8],
+) -> Result<Vec<u8>> {
+    match algorithm {
+        #[cfg(feature = "aes-gcm-siv")]
+        AlgorithmId::Aes256GcmSiv => {
+            seal_with(aes256_gcm_siv::Aes256GcmSiv, key, nonce, aad, plaintext)
+        }
+        #[cfg(feature = "aegis256")]
+        AlgorithmId::Aegis256 => seal_with(aegis256::Aegis256, key, nonce, aad, plaintext),
+        other => Err(unavailable(other)),
+    }
+}
+
+/// Verify and decrypt `ciphertext` under `key` and `nonce`.
+///
+/// # Errors
+///
+/// - `CryptoError::DecryptionFailed`: If the tag does not verify
+/// - `MisuseError::InvalidCiphertextLength`: If `ciphertext` is shorter
+///   than a tag
+/// - As [`seal`]
+#[cfg_attr(
+    not(any(feature = "aes-gcm-siv", feature = "aegis256")),
+    allow(unused_variables)
+)]
+pub(crate) fn open(
+    algorithm: AlgorithmId,
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    ciphertext: &[u8],
+) -> Result<Vec<u8>> {
+    match algorithm {
+        #[cfg(feature = "aes-gcm-siv")]
+        AlgorithmId::Aes256GcmSiv => {
+            open_with(aes256_gcm_siv::Aes256GcmSiv, key, nonce, aad, ciphertext)
+        }
+        #[cfg(feature = "aegis256")]
+        AlgorithmId::Aegis256 => open_with(aegis256::Aegis256, key, nonce, aad, ciphertext),
+        other => Err(unavailable(other)),
+    }
+}
+
+/// Why `algorithm` has no arm: disabled, or not an AEAD.
+fn unavailable(algorithm: AlgorithmId) -> crate::errors::Error {
+    match algorithm {
+        AlgorithmId::Aes256GcmSiv | AlgorithmId::Aegis256 => MisuseError::FeatureNotEnabled.into(),
+        _ => MisuseError::InvalidAlgorithmIdentifier.into(),
+    }
+}
+
+#[cfg(any(feature = "aes-gcm-siv", feature = "aegis256"))]
+fn seal_with<A, const KEY: usize, const NONCE: usize, const TAG: usize>(
+    aead: A,
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    plaintext: &[u8],
+) -> Result<Vec<u8>>
+where
+    A: AeadCipher<KEY, NONCE, TAG>,
+{
+    let key = key.try_into().map_err(|_| MisuseError::InvalidKeyLength)?;
+    let nonce = nonce
+        .try_into()
+        .map_err(|_| MisuseError::InvalidNonceLength)?;
+    let mut ciphertext = vec![0u8; plaintext.len() + TAG];
+    aead.encrypt(
+        key,
+        Nonce::unsafe_from_bytes(nonce),
+        plaintext,
+        aad,
+        &mut ciphertext,
+    )?;
+    Ok(ciphertext)
+}
+
+#[cfg(any(feature = "aes-gcm-siv", feature = "aegis256"))]
+fn open_with<A, const KEY: usize, const NONCE: usize, const TAG: usize>(
+    aead: A,
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    ciphertext: &[u8],
+) -> Result<Vec<u8>>
+where
+    A: AeadCipher<KEY, NONCE, TAG>,
+{
+    let key = key.try_into().map_err(|_| MisuseError::InvalidKeyLength)?;
+    let nonce = nonce
+        .try_into()
+        .map_err(|_| MisuseError::InvalidNonceLength)?;
+    let Some(plaintext_len) = ciphertext.len().checked_sub(TAG) else {
+        return Err(MisuseError::InvalidCiphertextLength.into());
+    };
+    let mut plaintext = vec![0u8; plaintext_len];
+    aead.decrypt(key, nonce, ciphertext, aad, &mut plaintext)?;
+    Ok(plaintext)
+}
@@ -0,0 +1,185 @@
+//! Constant-time range checks for scalar encodings.
+//!
+//! # Purpose
+//!
+//! Signature verification must reject scalars that are not canonically
+//! encoded: `S >= L` for Ed25519, `s = 0` or `s >= n` for ECDSA, and the
+//! high-`s` twin of every ECDSA signature where non-malleability is
+//! required. Written ad hoc, these checks tend to return at the first
+//! differing limb, leaking the position through timing.
+//!
+//! Every function here touches each byte exactly once, whatever the
+//! values, and combines results without branching.
+//!
+//! # Encodings
+//!
+//! Integers are fixed-length unsigned byte arrays. The `_le` functions take
+//! little-endian input (Ed25519, X25519); the `_be` functions take
+//! big-endian input (SEC 1, ECDSA).
+//!
+//! # Example
+//!
+//! ```ignore
+//! if !ct::is_canonical_le(&signature_s, &GROUP_ORDER_LE) {
+//!     return false;
+//! }
+//! ```
+
+/// Return the borrow out of `a - b`, consuming bytes least significant
+/// first.
+#[inline]
+fn borrow_out<'a>(pairs: impl Iterator<Item = (&'a u8, &'a u8)>) -> u16 {
+    let mut borrow = 0u16;
+    for (&x, &y) in pairs {
+        let difference = u16::from(x).wrapping_sub(u16::from(y)).wrapping_sub(borrow);
+        borrow = difference >> 15;
+    }
+    borrow
+}
+
+/// Whether `a < b`, both little-endian.
+#[inline]
+pub fn lt_le<const N: usize>(a: &[u8; N], b: &[u8; N]) -> bool {
+    borrow_out(a.iter().zip(b.iter())) == 1
+}
+
+/// Whether `a < b`, both big-endian.
+#[inline]
+pub fn lt_be<const N: usize>(a: &[u8; N], b: &[u8; N]) -> bool {
+    borrow_out(a.iter().rev().zip(b.iter().rev())) == 1
+}
+
+/// Whether every byte of `value` is zero.
+#[inline]
+pub fn is_zero<const N: usize>(value: &[u8; N]) -> bool {
+    let accumulated = value.iter().fold(0u8, |acc, &byte| acc | byte);
+    (u16::from(accumulated).wrapping_sub(1) >> 15) == 1
+}
+
+/// Whether the little-endian `value` is reduced modulo `modulus`
+/// (`value < modulus`).
+#[inline]
+pub fn is_canonical_le<const N: usize>(value: &[u8; N], modulus: &[u8; N]) -> bool {
+    lt_le(value, modulus)
+}
+
+/// Whether the big-endian `value` is in `[1, modulus)`, the valid range
+/// for ECDSA `r` and `s`.
+#[inline]
+pub fn is_nonzero_canonical_be<const N: usize>(value: &[u8; N], modulus: &[u8; N]) -> bool {
+    !is_zero(value) & lt_be(value, modulus)
+}
+
+/// Whether the big-endian `value` is at most `(modulus - 1) / 2`, the
+/// "low-s" half used to rule out ECDSA signature malleability.
+///
+/// `modulus` must be odd, as every prime group order is.
+#[inline]
+pub fn is_low_half_be<const N: usize>(value: &[u8; N], modulus: &[u8; N]) -> bool {
+    debug_assert!(modulus[N - 1] & 1 == 1);
+    // value <= (m - 1) / 2  <=>  2 * value < m for odd m
+    let mut doubled = [0u8; N];
+    let mut carry = 0u8;
+    for (out, &byte) in doubled.iter_mut().zip(value.iter()).rev() {
+        *out = (byte << 1) | carry;
+        carry = byte >> 7;
+    }
+    (carry == 0) & lt_be(&doubled, modulus)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ed25519 group order L, little-endian.
+    const ED25519_L: [u8; 32] = [
+        0xed, 0xd3, 0xf5, 0x5c, 0x1a, 0x63, 0x12, 0x58, 0xd6, 0x9c, 0xf7, 0xa2, 0xde, 0xf9, 0xde,
+        0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x10,
+    ];
+
+    /// P-256 group order n, big-endian.
+    const P256_N: [u8; 32] = [
+        0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0xbc, 0xe6, 0xfa, 0xad, 0xa7, 0x17, 0x9e, 0x84, 0xf3, 0xb9, 0xca, 0xc2, 0xfc, 0x63,
+        0x25, 0x51,
+    ];
+
+    fn add_le(value: &[u8; 32], delta: i16) -> [u8; 32] {
+        let mut out = *value;
+        let mut carry = delta;
+        for byte in out.iter_mut() {
+            let sum = i16::from(*byte) + carry;
+            *byte = sum.rem_euclid(256) as u8;
+            carry = sum.div_euclid(256);
+        }
+        out
+    }
+
+    fn reversed(value: [u8; 32]) -> [u8; 32] {
+        let mut out = value;
+        out.reverse();
+        out
+    }
+
+    #[test]
+    fn ed25519_boundaries() {
+        assert!(!is_canonical_le(&ED25519_L, &ED25519_L));
+        assert!(is_canonical_le(&add_le(&ED25519_L, -1), &ED25519_L));
+        assert!(!is_canonical_le(&add_le(&ED25519_L, 1), &ED25519_L));
+        assert!(is_canonical_le(&[0u8; 32], &ED25519_L));
+        assert!(!is_canonical_le(&[0xff; 32], &ED25519_L));
+        // Differs from L only in the lowest byte
+        let mut low = ED25519_L;
+        low[0] = 0;
+        assert!(is_canonical_le(&low, &ED25519_L));
+    }
+
+    #[test]
+    fn ecdsa_ranges() {
+        let n_le = reversed(P256_N);
+        let n_minus_one = reversed(add_le(&n_le, -1));
+        assert!(is_nonzero_canonical_be(&n_minus_one, &P256_N));
+        assert!(!is_nonzero_canonical_be(&P256_N, &P256_N));
+        assert!(!is_nonzero_canonical_be(&[0u8; 32], &P256_N));
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        assert!(is_nonzero_canonical_be(&one, &P256_N));
+
+        // (n - 1) / 2 is the largest low-s value
+        let mut half = [0u8; 32];
+        let mut remainder = 0u8;
+        for (out, &byte) in half.iter_mut().zip(n_minus_one.iter()) {
+            *out = (remainder << 7) | (byte >> 1);
+            remainder = byte & 1;
+        }
+        assert!(is_low_half_be(&half, &P256_N));
+        let above_half = reversed(add_le(&reversed(half), 1));
+        assert!(!is_low_half_be(&above_half, &P256_N));
+        assert!(!is_low_half_be(&[0xff; 32], &P256_N));
+        assert!(is_low_half_be(&one, &P256_N));
+    }
+
+    #[test]
+    fn matches_naive_comparison() {
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        for _ in 0..1000 {
+            let a: [u8; 8] = next().to_le_bytes();
+            // Share high bytes often so later bytes decide the result
+            let mut b: [u8; 8] = next().to_le_bytes();
+            let shared = (next() % 9) as usize;
+            b[8 - shared..].copy_from_slice(&a[8 - shared..]);
+            let (x, y) = (u64::from_le_bytes(a), u64::from_le_bytes(b));
+            assert_eq!(lt_le(&a, &b), x < y);
+            assert_eq!(lt_be(&x.to_be_bytes(), &y.to_be_bytes()), x < y);
+            assert_eq!(is_zero(&a), x == 0);
+        }
+        assert!(is_zero(&[0u8; 8]));
+    }
+}
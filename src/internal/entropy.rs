@@ -0,0 +1,157 @@
+//! System randomness.
+//!
+//! All fresh randomness in Citadel comes from the operating system CSPRNG
+//! via `getrandom`, unless the current `OpContext` supplies an explicit
+//! `EntropySource` or the caller passes one to a `*_with_rng` API. There is deliberately no user-space fallback: if the
+//! source fails, the operation fails.
+//!
+//! OS output passes the health tests in `internal::health`: a startup test
+//! over the first 1024 bytes the process draws, then continuous tests on
+//! every request. A continuous failure fails that request and sends the
+//! source back through the startup test before its next use: a stuck or
+//! biased source keeps failing it, while the rare false positive of
+//! tests run on conditioned output (2^-40 per sample) does not disable
+//! randomness for the rest of a long-running process.
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::health::{HealthTests, STARTUP_SAMPLES};
+use crate::context::{self, EntropySource, OsEntropy};
+use crate::errors::{CryptoError, Result};
+use crate::r#unsafe::memory::zeroize_slice;
+
+/// Set once the startup test has passed; cleared when a continuous test
+/// fails, so the source is retested before its next use.
+static STARTED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static CONTINUOUS: RefCell<HealthTests> = RefCell::new(HealthTests::default());
+}
+
+/// Fill `buf` from the OS CSPRNG, running the health tests.
+///
+/// # Errors
+///
+/// - `CryptoError::RandomnessUnavailable`: If the OS source fails
+/// - `CryptoError::RandomnessFailure`: If its output fails a health test,
+///   or the startup retest after an earlier failure
+pub(crate) fn os_fill(buf: &mut [u8]) -> Result<()> {
+    tested_fill(buf, getrandom)
+}
+
+/// [`os_fill`] from `source`.
+fn tested_fill(buf: &mut [u8], source: fn(&mut [u8]) -> Result<()>) -> Result<()> {
+    if !STARTED.load(Ordering::Acquire) {
+        let mut samples = [0u8; STARTUP_SAMPLES];
+        let checked = source(&mut samples)
+            .and_then(|()| health_check(&mut HealthTests::default(), &mut samples));
+        // SAFETY: `samples` is a local array about to go out of scope
+        unsafe { zeroize_slice(&mut samples) };
+        checked?;
+        STARTED.store(true, Ordering::Release);
+    }
+    source(buf)?;
+    CONTINUOUS.with(|tests| {
+        let mut tests = tests.borrow_mut();
+        let checked = health_check(&mut tests, buf);
+        if checked.is_err() {
+            // Start over from a fresh startup test
+            *tests = HealthTests::default();
+            STARTED.store(false, Ordering::Release);
+        }
+        checked
+    })
+}
+
+fn getrandom(buf: &mut [u8]) -> Result<()> {
+    getrandom::getrandom(buf).map_err(|_| CryptoError::RandomnessUnavailable.into())
+}
+
+/// Run `tests` over `buf`, clearing `buf` on failure so the rejected
+/// bytes cannot be used by mistake.
+fn health_check(tests: &mut HealthTests, buf: &mut [u8]) -> Result<()> {
+    let checked = tests.check(buf);
+    if checked.is_err() {
+        // SAFETY: `buf` is an exclusively borrowed, initialized slice
+        unsafe { zeroize_slice(buf) };
+    }
+    checked
+}
+
+/// Fill `buf` with randomness from the current context's source, or the OS.
+///
+/// # Errors
+///
+/// - `CryptoError::RandomnessUnavailable`: If the source fails
+/// - `CryptoError::RandomnessFailure`: If OS output fails a health test
+pub(crate) fn fill(buf: &mut [u8]) -> Result<()> {
+    if let Some(source) = context::entropy_source() {
+        return source.fill(buf);
+    }
+    OsEntropy.fill(buf)
+}
+
+/// [`fill`] as an [`EntropySource`], for randomized APIs that delegate to
+/// their `*_with_rng` variant.
+pub(crate) struct ContextEntropy;
+
+impl EntropySource for ContextEntropy {
+    fn fill(&self, buf: &mut [u8]) -> Result<()> {
+        fill(buf)
+    }
+}
+
+/// Deterministic counter bytes, for known-answer tests of `*_with_rng`
+/// APIs.
+#[cfg(test)]
+pub(crate) struct CounterEntropy(core::sync::atomic::AtomicU8);
+
+#[cfg(test)]
+impl CounterEntropy {
+    pub(crate) fn new(start: u8) -> Self {
+        Self(core::sync::atomic::AtomicU8::new(start))
+    }
+}
+
+#[cfg(test)]
+impl EntropySource for CounterEntropy {
+    fn fill(&self, buf: &mut [u8]) -> Result<()> {
+        for byte in buf {
+            *byte = self.0.fetch_add(1, core::sync::atomic::Ordering::Relaxed);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_buffer() {
+        let mut a = [0u8; 32];
+        let mut b = [0u8; 32];
+        fill(&mut a).unwrap();
+        fill(&mut b).unwrap();
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn recovers_after_a_passing_retest() {
+        fn stuck(buf: &mut [u8]) -> Result<()> {
+            buf.fill(0x42);
+            Ok(())
+        }
+
+        let mut buf = [0u8; 32];
+        let error = tested_fill(&mut buf, stuck).unwrap_err();
+        assert_eq!(error.crypto(), Some(CryptoError::RandomnessFailure));
+        assert_eq!(buf, [0; 32]);
+        // A source that stays broken keeps failing the retest
+        assert!(tested_fill(&mut buf, stuck).is_err());
+        // One that recovers passes it and is usable again
+        tested_fill(&mut buf, getrandom).unwrap();
+        assert_ne!(buf, [0; 32]);
+    }
+}
@@ -0,0 +1,107 @@
+//! Entropy health tests (NIST SP 800-90B §4.4).
+//!
+//! The repetition count test catches a source stuck on one value; the
+//! adaptive proportion test catches one value becoming far too frequent.
+//! Samples are bytes assessed at full entropy (8 bits each), with a false
+//! positive rate of 2^-40 per test.
+//!
+//! Samples are secret, so both tests update their counters without
+//! branching on sample values; only the final pass/fail is branched on.
+
+use crate::errors::{CryptoError, Result};
+
+/// Repetition count cutoff: `1 + ceil(40 / 8)`.
+const RCT_CUTOFF: u32 = 6;
+
+/// Adaptive proportion window size for non-binary sources.
+const APT_WINDOW: u32 = 512;
+
+/// Adaptive proportion cutoff: `1 + CRITBINOM(512, 2^-8, 1 - 2^-40)`.
+const APT_CUTOFF: u32 = 19;
+
+/// Samples checked before a source is first used.
+pub(crate) const STARTUP_SAMPLES: usize = 1024;
+
+/// Continuous test state, carried across calls.
+#[derive(Debug, Default)]
+pub(crate) struct HealthTests {
+    rct_value: u8,
+    rct_count: u32,
+    apt_value: u8,
+    apt_count: u32,
+    apt_position: u32,
+}
+
+/// 1 if `a == b`, 0 otherwise, without branching.
+#[inline]
+fn same(a: u8, b: u8) -> u32 {
+    u32::from(a ^ b).wrapping_sub(1) >> 31
+}
+
+/// 1 if `count >= cutoff`, 0 otherwise, for counts below 2^31.
+#[inline]
+fn reached(count: u32, cutoff: u32) -> u32 {
+    (cutoff - 1).wrapping_sub(count) >> 31
+}
+
+impl HealthTests {
+    /// Run both tests over `samples`.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessFailure`: If either test fails
+    pub(crate) fn check(&mut self, samples: &[u8]) -> Result<()> {
+        let mut failed = 0u32;
+        for &sample in samples {
+            let repeated = same(sample, self.rct_value);
+            self.rct_count = repeated * self.rct_count + 1;
+            self.rct_value = sample;
+            failed |= reached(self.rct_count, RCT_CUTOFF);
+
+            // The window position is public; only the sample is secret
+            if self.apt_position == 0 {
+                self.apt_value = sample;
+                self.apt_count = 1;
+            } else {
+                self.apt_count += same(sample, self.apt_value);
+                failed |= reached(self.apt_count, APT_CUTOFF);
+            }
+            self.apt_position = (self.apt_position + 1) % APT_WINDOW;
+        }
+        if failed != 0 {
+            return Err(CryptoError::RandomnessFailure.into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn passes_random_output() {
+        let mut samples = vec![0u8; 1 << 16];
+        getrandom::getrandom(&mut samples).unwrap();
+        assert!(HealthTests::default().check(&samples).is_ok());
+    }
+
+    #[test]
+    fn catches_repetition_across_calls() {
+        let mut tests = HealthTests::default();
+        assert!(tests.check(&[1, 7, 7, 7]).is_ok());
+        assert!(tests.check(&[7, 7]).is_ok());
+        let error = tests.check(&[7]).unwrap_err();
+        assert_eq!(error.crypto(), Some(CryptoError::RandomnessFailure));
+    }
+
+    #[test]
+    fn catches_biased_proportion() {
+        // Every fourth byte is 0xaa: no long runs, but far too frequent
+        let samples: Vec<u8> = (0..APT_WINDOW)
+            .map(|i| if i % 4 == 0 { 0xaa } else { i as u8 | 1 })
+            .collect();
+        assert!(HealthTests::default().check(&samples).is_err());
+        assert!(HealthTests::default().check(&samples[..4 * 18]).is_ok());
+    }
+}
@@ -1 +1,6 @@
-pub mod traits;
\ No newline at end of file
+pub(crate) mod aead;
+pub mod ct;
+pub(crate) mod entropy;
+pub(crate) mod health;
+pub mod precomputed;
+pub mod traits;
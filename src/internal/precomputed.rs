@@ -0,0 +1,199 @@
+//! Lazily-initialized, shared read-only tables.
+//!
+//! # Purpose
+//!
+//! Some algorithms need large read-only tables (expanded matrices, twiddle
+//! factors, SIMD constants) that are identical for every instance. Computing
+//! them per instance wastes time in servers that create many instances, while
+//! computing them eagerly wastes time for programs that never use the
+//! algorithm.
+//!
+//! [`Precomputed`] holds such a table in a `static`, builds it on first access
+//! and hands out plain shared references afterwards. It needs neither `std`
+//! nor `alloc`, and readers never touch a reference count.
+//!
+//! # Security
+//!
+//! Only public parameters may be stored here. The value is never zeroized
+//! and lives for the remainder of the program.
+//!
+//! # Example
+//!
+//! ```ignore
+//! static ZETAS: Precomputed<[u16; 128]> = Precomputed::new(compute_zetas);
+//!
+//! fn ntt(poly: &mut [u16; 256]) {
+//!     let zetas = ZETAS.get();
+//!     // ...
+//! }
+//! ```
+
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicU8, Ordering};
+
+const UNINIT: u8 = 0;
+const RUNNING: u8 = 1;
+const READY: u8 = 2;
+
+/// A once-initialized holder for public, read-only parameters.
+///
+/// The initializer runs at most once to completion. Concurrent callers of
+/// [`get`](Precomputed::get) during initialization spin until the value is
+/// published. If the initializer panics, the holder returns to the
+/// uninitialized state and the next caller retries.
+pub struct Precomputed<T> {
+    state: AtomicU8,
+    init: fn() -> T,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+// SAFETY: the value is written exactly once, before `READY` is published with
+// release ordering, and only shared references are handed out afterwards.
+unsafe impl<T: Send + Sync> Sync for Precomputed<T> {}
+
+impl<T> Precomputed<T> {
+    /// Create an uninitialized holder with the given initializer.
+    ///
+    /// Usable in `static` items.
+    #[inline]
+    pub const fn new(init: fn() -> T) -> Self {
+        Self {
+            state: AtomicU8::new(UNINIT),
+            init,
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+
+    /// Get the value, computing it on first access.
+    #[inline]
+    pub fn get(&self) -> &T {
+        if self.state.load(Ordering::Acquire) != READY {
+            self.initialize();
+        }
+        // SAFETY: state is READY, so the value has been written and published.
+        unsafe { (*self.value.get()).assume_init_ref() }
+    }
+
+    /// Get the value only if it has already been computed.
+    #[inline]
+    pub fn get_if_ready(&self) -> Option<&T> {
+        if self.state.load(Ordering::Acquire) == READY {
+            // SAFETY: state is READY, so the value has been written and published.
+            Some(unsafe { (*self.value.get()).assume_init_ref() })
+        } else {
+            None
+        }
+    }
+
+    /// Check whether the value has been computed.
+    #[inline]
+    pub fn is_ready(&self) -> bool {
+        self.state.load(Ordering::Acquire) == READY
+    }
+
+    #[cold]
+    fn initialize(&self) {
+        loop {
+            match self
+                .state
+                .compare_exchange(UNINIT, RUNNING, Ordering::Acquire, Ordering::Acquire)
+            {
+                Ok(_) => {
+                    // Reset to UNINIT if the initializer unwinds
+                    struct Reset<'a>(&'a AtomicU8);
+                    impl Drop for Reset<'_> {
+                        fn drop(&mut self) {
+                            self.0.store(UNINIT, Ordering::Release);
+                        }
+                    }
+
+                    let reset = Reset(&self.state);
+                    let value = (self.init)();
+                    // SAFETY: we hold the RUNNING state, so no other thread
+                    // reads or writes the cell until READY is published.
+                    unsafe { (*self.value.get()).write(value) };
+                    core::mem::forget(reset);
+                    self.state.store(READY, Ordering::Release);
+                    return;
+                }
+                Err(READY) => return,
+                Err(_) => core::hint::spin_loop(),
+            }
+        }
+    }
+}
+
+impl<T> Drop for Precomputed<T> {
+    fn drop(&mut self) {
+        if *self.state.get_mut() == READY {
+            // SAFETY: state is READY, so the value is initialized.
+            unsafe { self.value.get_mut().assume_init_drop() };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::sync::atomic::AtomicUsize;
+
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    fn build_table() -> [u32; 256] {
+        CALLS.fetch_add(1, Ordering::SeqCst);
+        let mut table = [0u32; 256];
+        for (i, entry) in table.iter_mut().enumerate() {
+            *entry = (i as u32).wrapping_mul(17);
+        }
+        table
+    }
+
+    static TABLE: Precomputed<[u32; 256]> = Precomputed::new(build_table);
+
+    #[test]
+    fn initializes_once_across_threads() {
+        let handles: Vec<_> = (0..8)
+            .map(|_| std::thread::spawn(|| TABLE.get()[255]))
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), 255 * 17);
+        }
+        assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+        assert!(TABLE.is_ready());
+    }
+
+    #[test]
+    fn lazy_until_first_access() {
+        let holder = Precomputed::new(|| [7u8; 64]);
+        assert!(!holder.is_ready());
+        assert!(holder.get_if_ready().is_none());
+        assert_eq!(holder.get()[0], 7);
+        assert_eq!(holder.get_if_ready(), Some(&[7u8; 64]));
+    }
+
+    #[test]
+    fn retries_after_panicking_initializer() {
+        static ATTEMPTS: AtomicUsize = AtomicUsize::new(0);
+
+        fn flaky() -> u64 {
+            if ATTEMPTS.fetch_add(1, Ordering::SeqCst) == 0 {
+                panic!("first attempt fails");
+            }
+            42
+        }
+
+        let holder = Precomputed::new(flaky);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| *holder.get()));
+        assert!(result.is_err());
+        assert!(!holder.is_ready());
+        assert_eq!(*holder.get(), 42);
+    }
+
+    #[test]
+    fn drops_initialized_value() {
+        let holder = Precomputed::new(|| vec![1u8, 2, 3]);
+        assert_eq!(holder.get().len(), 3);
+        drop(holder);
+    }
+}
@@ -0,0 +1,91 @@
+//! Key derivation function trait.
+//!
+//! # Security Properties
+//!
+//! Implementations MUST:
+//! - Produce deterministic output for the same salt, input and info
+//! - Make outputs for distinct `info` labels independent
+//! - Reject output lengths outside the construction's bounds
+//! - Zeroize intermediate keys when done
+//!
+//! Implementations MUST NOT:
+//! - Be used to stretch passwords (use a password hash for that)
+//! - Log or expose the pseudorandom key
+//!
+//! # Const Generics
+//!
+//! - `PRK_SIZE`: Size of the extracted pseudorandom key in bytes
+
+use crate::errors::Result;
+use crate::memory::SensitiveBytes;
+
+/// Extract-then-expand key derivation function.
+///
+/// `extract` concentrates the entropy of non-uniform input key material
+/// into a pseudorandom key; `expand` derives any number of independent
+/// outputs from it, one per `info` label.
+///
+/// # Example
+///
+/// ```ignore
+/// fn session_keys<K: Kdf<32>>(kdf: &K, shared: &[u8]) -> Result<([u8; 32], [u8; 32])> {
+///     let prk = kdf.extract(b"", shared);
+///     let mut send = [0u8; 32];
+///     let mut receive = [0u8; 32];
+///     kdf.expand(&prk, b"send", &mut send)?;
+///     kdf.expand(&prk, b"receive", &mut receive)?;
+///     Ok((send, receive))
+/// }
+/// ```
+pub trait Kdf<const PRK_SIZE: usize> {
+    /// Extract a pseudorandom key from `ikm`, salted with `salt` (which
+    /// may be empty).
+    fn extract(&self, salt: &[u8], ikm: &[u8]) -> SensitiveBytes<PRK_SIZE>;
+
+    /// Fill `output` with key material for the purpose named by `info`.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::BufferTooSmall`: If `output` is empty or longer than
+    ///   the construction allows
+    fn expand(&self, prk: &SensitiveBytes<PRK_SIZE>, info: &[u8], output: &mut [u8]) -> Result<()>;
+
+    /// Extract and expand in one call.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::BufferTooSmall`: If `output` is empty or longer than
+    ///   the construction allows
+    fn derive(&self, salt: &[u8], ikm: &[u8], info: &[u8], output: &mut [u8]) -> Result<()> {
+        let prk = self.extract(salt, ikm);
+        self.expand(&prk, info, output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct MockKdf;
+
+    impl Kdf<32> for MockKdf {
+        fn extract(&self, _salt: &[u8], _ikm: &[u8]) -> SensitiveBytes<32> {
+            unimplemented!("mock")
+        }
+
+        fn expand(
+            &self,
+            _prk: &SensitiveBytes<32>,
+            _info: &[u8],
+            _output: &mut [u8],
+        ) -> Result<()> {
+            unimplemented!("mock")
+        }
+    }
+
+    #[test]
+    fn trait_is_object_safe() {
+        fn assert_object_safe(_: &dyn Kdf<32>) {}
+        assert_object_safe(&MockKdf);
+    }
+}
@@ -37,9 +37,16 @@ use crate::errors::Result;
 /// # Example
 ///
 /// ```ignore
-/// fn use_kem<K>(kem: &K, public_key: &[u8; 1568]) 
+/// use citadel::consts::MlKem768 as Sizes;
+///
+/// fn use_kem<K>(kem: &K, public_key: &[u8; Sizes::PUBLIC_KEY_SIZE])
 /// where
-///     K: KeyEncapsulation<1568, 3168, 1568, 32>
+///     K: KeyEncapsulation<
+///         { Sizes::PUBLIC_KEY_SIZE },
+///         { Sizes::SECRET_KEY_SIZE },
+///         { Sizes::CIPHERTEXT_SIZE },
+///         { Sizes::SHARED_SECRET_SIZE },
+///     >,
 /// {
 ///     let (ciphertext, shared_secret) = kem.encapsulate(public_key).unwrap();
 ///     // Use shared_secret for key derivation...
@@ -126,27 +133,33 @@ pub trait KeyEncapsulation<
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::consts::MlKem768 as Sizes;
+
+    const PK: usize = Sizes::PUBLIC_KEY_SIZE;
+    const SK: usize = Sizes::SECRET_KEY_SIZE;
+    const CT: usize = Sizes::CIPHERTEXT_SIZE;
+    const SS: usize = Sizes::SHARED_SECRET_SIZE;
 
     // Compile-time size verification
     struct MockKem;
 
-    impl KeyEncapsulation<1568, 3168, 1568, 32> for MockKem {
-        fn generate_keypair(&self) -> Result<([u8; 1568], [u8; 3168])> {
+    impl KeyEncapsulation<PK, SK, CT, SS> for MockKem {
+        fn generate_keypair(&self) -> Result<([u8; PK], [u8; SK])> {
             unimplemented!("mock")
         }
 
         fn encapsulate(
             &self,
-            _public_key: &[u8; 1568],
-        ) -> Result<([u8; 1568], [u8; 32])> {
+            _public_key: &[u8; PK],
+        ) -> Result<([u8; CT], [u8; SS])> {
             unimplemented!("mock")
         }
 
         fn decapsulate(
             &self,
-            _secret_key: &[u8; 3168],
-            _ciphertext: &[u8; 1568],
-        ) -> Result<[u8; 32]> {
+            _secret_key: &[u8; SK],
+            _ciphertext: &[u8; CT],
+        ) -> Result<[u8; SS]> {
             unimplemented!("mock")
         }
     }
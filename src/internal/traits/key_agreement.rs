@@ -0,0 +1,194 @@
+//! Key Agreement (Diffie-Hellman) trait and KEM adapter.
+//!
+//! # Security Properties
+//!
+//! Implementations MUST:
+//! - Use cryptographically secure randomness for key generation
+//! - Perform scalar multiplication in constant time
+//! - Reject shared secrets that reveal a low-order or invalid peer key
+//!   (use CryptoError::KeyEncapsulationFailed)
+//! - Zeroize sensitive material when dropped
+//!
+//! Implementations MUST NOT:
+//! - Return the raw shared secret for use as a key (hash it first, e.g.
+//!   via [`DhKem`] or a hybrid combiner)
+//! - Log or expose intermediate values
+//!
+//! # Key Agreement vs. KEM
+//!
+//! Key agreement is symmetric: both parties hold a keypair and combine
+//! their secret key with the peer's public key. [`DhKem`] turns any key
+//! agreement into a [`KeyEncapsulation`] by using an ephemeral keypair as
+//! the ciphertext, so it composes with ML-KEM in hybrid wrappers.
+//!
+//! # Const Generics
+//!
+//! - `PUBLIC_KEY_SIZE`: Size of public key in bytes
+//! - `SECRET_KEY_SIZE`: Size of secret key in bytes
+//! - `SHARED_SECRET_SIZE`: Size of shared secret in bytes
+
+use crate::algorithms::classical::keccak::sha3_256;
+use crate::errors::Result;
+use crate::internal::traits::KeyEncapsulation;
+use crate::memory::SensitiveBytes;
+
+/// Key Agreement trait.
+///
+/// Provides key generation, public key derivation, and shared secret
+/// computation. All sizes are compile-time constants enforced through
+/// const generics.
+///
+/// # Type Parameters
+///
+/// - `PUBLIC_KEY_SIZE`: Public key size in bytes
+/// - `SECRET_KEY_SIZE`: Secret key size in bytes
+/// - `SHARED_SECRET_SIZE`: Raw shared secret size in bytes
+///
+/// # Example
+///
+/// ```ignore
+/// fn agree<A>(kex: &A, sk: &[u8; 32], peer: &[u8; 32]) -> Result<[u8; 32]>
+/// where
+///     A: KeyAgreement<32, 32, 32>
+/// {
+///     kex.agree(sk, peer)
+/// }
+/// ```
+pub trait KeyAgreement<
+    const PUBLIC_KEY_SIZE: usize,
+    const SECRET_KEY_SIZE: usize,
+    const SHARED_SECRET_SIZE: usize,
+>: Sized
+{
+    /// Generate a new keypair.
+    ///
+    /// # Returns
+    ///
+    /// A tuple of (public_key, secret_key).
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If RNG fails
+    /// - `CryptoError::RandomnessFailure`: If RNG fails a health test
+    ///
+    /// # Security
+    ///
+    /// - Uses cryptographically secure randomness
+    /// - Secret key must be zeroized when no longer needed
+    fn generate_keypair(&self) -> Result<([u8; PUBLIC_KEY_SIZE], [u8; SECRET_KEY_SIZE])>;
+
+    /// Derive the public key for a secret key.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidSecretKeyLength`: If the secret key is invalid
+    ///   for the scheme
+    fn public_key(&self, secret_key: &[u8; SECRET_KEY_SIZE]) -> Result<[u8; PUBLIC_KEY_SIZE]>;
+
+    /// Compute the raw shared secret with a peer.
+    ///
+    /// # Arguments
+    ///
+    /// - `secret_key`: Own secret key
+    /// - `peer_public_key`: Peer's public key
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::KeyEncapsulationFailed`: If the peer key is invalid
+    ///   or of low order
+    ///
+    /// # Security
+    ///
+    /// - Constant time with respect to the secret key
+    /// - The result is not uniformly random; pass it through a KDF
+    fn agree(
+        &self,
+        secret_key: &[u8; SECRET_KEY_SIZE],
+        peer_public_key: &[u8; PUBLIC_KEY_SIZE],
+    ) -> Result<[u8; SHARED_SECRET_SIZE]>;
+}
+
+/// Domain separation label for [`DhKem`] shared secrets.
+const DH_KEM_LABEL: &[u8] = b"citadel dh-kem v1";
+
+/// Adapter turning a [`KeyAgreement`] into a [`KeyEncapsulation`].
+///
+/// Encapsulation generates an ephemeral keypair and sends its public key as
+/// the ciphertext. The shared secret is
+/// `SHA3-256(label || dh || ciphertext || recipient_public_key)`, which
+/// binds it to both public keys.
+///
+/// # Example
+///
+/// ```ignore
+/// let kem = DhKem(X25519);
+/// let (ct, ss) = kem.encapsulate(&recipient_pk)?;
+/// ```
+pub struct DhKem<A>(pub A);
+
+impl<A, const PUBLIC_KEY_SIZE: usize, const SECRET_KEY_SIZE: usize>
+    KeyEncapsulation<PUBLIC_KEY_SIZE, SECRET_KEY_SIZE, PUBLIC_KEY_SIZE, 32> for DhKem<A>
+where
+    A: KeyAgreement<PUBLIC_KEY_SIZE, SECRET_KEY_SIZE, 32>,
+{
+    fn generate_keypair(&self) -> Result<([u8; PUBLIC_KEY_SIZE], [u8; SECRET_KEY_SIZE])> {
+        self.0.generate_keypair()
+    }
+
+    fn encapsulate(
+        &self,
+        public_key: &[u8; PUBLIC_KEY_SIZE],
+    ) -> Result<([u8; PUBLIC_KEY_SIZE], [u8; 32])> {
+        let (ephemeral_public, ephemeral_secret) = self.0.generate_keypair()?;
+        let ephemeral_secret = SensitiveBytes::new(ephemeral_secret);
+        let dh = SensitiveBytes::new(self.0.agree(ephemeral_secret.as_bytes(), public_key)?);
+        let shared = sha3_256(&[DH_KEM_LABEL, dh.as_bytes(), &ephemeral_public, public_key]);
+        Ok((ephemeral_public, shared))
+    }
+
+    fn decapsulate(
+        &self,
+        secret_key: &[u8; SECRET_KEY_SIZE],
+        ciphertext: &[u8; PUBLIC_KEY_SIZE],
+    ) -> Result<[u8; 32]> {
+        let public_key = self.0.public_key(secret_key)?;
+        let dh = SensitiveBytes::new(self.0.agree(secret_key, ciphertext)?);
+        Ok(sha3_256(&[
+            DH_KEM_LABEL,
+            dh.as_bytes(),
+            ciphertext,
+            &public_key,
+        ]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Toy agreement over u8 multiplication: dh(a, g^b) = a * b * g.
+    struct MockAgreement;
+
+    impl KeyAgreement<1, 1, 32> for MockAgreement {
+        fn generate_keypair(&self) -> Result<([u8; 1], [u8; 1])> {
+            Ok(([3u8.wrapping_mul(5)], [5]))
+        }
+
+        fn public_key(&self, secret_key: &[u8; 1]) -> Result<[u8; 1]> {
+            Ok([3u8.wrapping_mul(secret_key[0])])
+        }
+
+        fn agree(&self, secret_key: &[u8; 1], peer_public_key: &[u8; 1]) -> Result<[u8; 32]> {
+            Ok([secret_key[0].wrapping_mul(peer_public_key[0]); 32])
+        }
+    }
+
+    #[test]
+    fn dh_kem_round_trip() {
+        let kem = DhKem(MockAgreement);
+        let secret_key = [7u8];
+        let public_key = MockAgreement.public_key(&secret_key).unwrap();
+        let (ct, ss) = kem.encapsulate(&public_key).unwrap();
+        assert_eq!(kem.decapsulate(&secret_key, &ct).unwrap(), ss);
+    }
+}
@@ -0,0 +1,81 @@
+//! Message authentication code trait.
+//!
+//! # Security Properties
+//!
+//! Implementations MUST:
+//! - Produce deterministic tags for the same key and input
+//! - Compare tags in constant time when verifying
+//! - Zeroize keyed state when dropped
+//!
+//! Implementations MUST NOT:
+//! - Expose the key or keyed state through `Debug` or other formatting
+//! - Return a partially computed tag on verification failure
+//!
+//! # Const Generics
+//!
+//! - `TAG_SIZE`: Size of the tag in bytes
+
+use crate::errors::{CryptoError, Result};
+use crate::memory::constant_time_eq;
+
+/// Incremental message authentication code.
+///
+/// An implementation is a keyed context: construction takes the key, so
+/// it is not part of this trait.
+///
+/// # Example
+///
+/// ```ignore
+/// fn check<M: Mac<32>>(mut mac: M, record: &[u8], tag: &[u8; 32]) -> Result<()> {
+///     mac.update(record);
+///     mac.verify(tag)
+/// }
+/// ```
+pub trait Mac<const TAG_SIZE: usize>: Sized {
+    /// Absorb more input.
+    fn update(&mut self, data: &[u8]);
+
+    /// Finish and return the tag.
+    fn finalize(self) -> [u8; TAG_SIZE];
+
+    /// Finish and compare against `tag` in constant time.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::VerificationFailed`: If the tags differ
+    fn verify(self, tag: &[u8; TAG_SIZE]) -> Result<()> {
+        if !constant_time_eq(&self.finalize(), tag) {
+            return Err(CryptoError::VerificationFailed.into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct MockMac([u8; 4]);
+
+    impl Mac<4> for MockMac {
+        fn update(&mut self, data: &[u8]) {
+            for (i, byte) in data.iter().enumerate() {
+                self.0[i % 4] ^= byte;
+            }
+        }
+
+        fn finalize(self) -> [u8; 4] {
+            self.0
+        }
+    }
+
+    #[test]
+    fn default_verify_compares_tags() {
+        let mut mac = MockMac([0; 4]);
+        mac.update(b"abcd");
+        assert!(mac.verify(b"abcd").is_ok());
+
+        let error = MockMac([0; 4]).verify(b"abcd").unwrap_err();
+        assert_eq!(error.crypto(), Some(CryptoError::VerificationFailed));
+    }
+}
@@ -25,6 +25,9 @@
 //! Types SHOULD implement zeroization in their Drop implementation as a
 //! defense-in-depth measure, but callers should not rely solely on this.
 
+use crate::consts;
+use crate::r#unsafe::memory::zeroize_slice;
+
 /// Secure memory handling trait for types containing sensitive data.
 ///
 /// This trait marks types that contain cryptographic secrets (keys, plaintexts,
@@ -60,9 +63,6 @@
 ///
 /// Types MAY implement `Drop` to call `zeroize()` as defense-in-depth, but
 /// this should not be the primary zeroization mechanism.
-
-use crate::r#unsafe::memory::zeroize_slice;
-
 pub trait SecureMemory {
     /// Securely overwrite this value's memory with zeros.
     ///
@@ -124,7 +124,9 @@ macro_rules! impl_secure_memory_array {
 
 // Common cryptographic key sizes
 impl_secure_memory_array!(
-    16, 24, 48, 64, 96, 128, 256, 512, 1024, 2048, 3168, 4032
+    16, 24, 48, 64, 96, 128, 256, 512, 1024, 2048,
+    consts::MlKem768::SECRET_KEY_SIZE,
+    consts::MlDsa87::SECRET_KEY_SIZE
 );
 
 #[cfg(test)]
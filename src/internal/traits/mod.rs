@@ -26,9 +26,13 @@
 //! # Structure
 //!
 //! - `kem`: Key encapsulation mechanism traits
+//! - `key_agreement`: Diffie-Hellman key agreement and the DH-to-KEM adapter
 //! - `signature`: Digital signature scheme traits  
 //! - `symmetric`: Symmetric cipher traits (AEAD)
 //! - `hash`: Cryptographic hash function traits
+//! - `xof`: Extendable-output function traits
+//! - `kdf`: Key derivation function traits
+//! - `mac`: Message authentication code traits
 //! - `memory`: Secure memory handling traits
 //! - `validation`: Parameter validation functions
 //!
@@ -36,17 +40,26 @@
 //!
 //! Hybrid constructions are implemented through wrapper types that compose
 //! post-quantum and classical implementations, NOT through trait extension.
+//! See [`crate::hybrid`].
 
 pub mod kem;
+pub mod key_agreement;
 pub mod signature;
 pub mod symmetric;
 pub mod hash;
+pub mod xof;
+pub mod kdf;
+pub mod mac;
 pub mod memory;
 pub mod validation;
 
 // Re-export commonly used types
 pub use kem::KeyEncapsulation;
-pub use signature::SignatureScheme;
+pub use key_agreement::{DhKem, KeyAgreement};
+pub use signature::{SignatureScheme, SignatureVerifier};
 pub use symmetric::AeadCipher;
 pub use hash::{HashFunction, HashContext};
+pub use xof::Xof;
+pub use kdf::Kdf;
+pub use mac::Mac;
 pub use memory::SecureMemory;
\ No newline at end of file
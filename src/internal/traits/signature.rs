@@ -23,9 +23,59 @@
 
 use crate::errors::Result;
 
+/// Signature verification.
+///
+/// The public half of a [`SignatureScheme`], implemented on its own by
+/// builds without the `signing` feature.
+///
+/// # Type Parameters
+///
+/// - `PUBLIC_KEY_SIZE`: Public key size in bytes
+/// - `SIGNATURE_SIZE`: Signature size in bytes
+pub trait SignatureVerifier<const PUBLIC_KEY_SIZE: usize, const SIGNATURE_SIZE: usize>:
+    Sized
+{
+    /// Verify a signature on a message with the public key.
+    ///
+    /// # Arguments
+    ///
+    /// - `public_key`: Signer's public key
+    /// - `message`: Message that was signed
+    /// - `signature`: Signature to verify
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if verification succeeds.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::VerificationFailed`: If signature is invalid
+    /// - `MisuseError::InvalidPublicKey`: If public key format is invalid
+    ///
+    /// # Security
+    ///
+    /// - Performs constant-time operations where possible
+    /// - Completes all verification steps before returning error
+    /// - Returns same error for all failure modes (no information leakage)
+    /// - No early exit on format errors (timing consistency)
+    ///
+    /// # Important
+    ///
+    /// Signature verification failure is NOT an exceptional condition in
+    /// adversarial contexts. Callers must handle `VerificationFailed` as
+    /// an expected case.
+    fn verify(
+        &self,
+        public_key: &[u8; PUBLIC_KEY_SIZE],
+        message: &[u8],
+        signature: &[u8; SIGNATURE_SIZE],
+    ) -> Result<()>;
+}
+
 /// Digital Signature Scheme trait.
 ///
-/// Provides key generation, signing, and verification operations.
+/// Provides key generation and signing; verification comes from the
+/// [`SignatureVerifier`] supertrait.
 /// All sizes are compile-time constants enforced through const generics.
 ///
 /// # Type Parameters
@@ -42,9 +92,15 @@ use crate::errors::Result;
 /// # Example
 ///
 /// ```ignore
-/// fn use_signature<S>(scheme: &S, message: &[u8], public_key: &[u8; 2592])
+/// use citadel::consts::MlDsa87 as Sizes;
+///
+/// fn use_signature<S>(scheme: &S, message: &[u8], public_key: &[u8; Sizes::PUBLIC_KEY_SIZE])
 /// where
-///     S: SignatureScheme<2592, 4032, 4627>
+///     S: SignatureScheme<
+///         { Sizes::PUBLIC_KEY_SIZE },
+///         { Sizes::SECRET_KEY_SIZE },
+///         { Sizes::SIGNATURE_SIZE },
+///     >,
 /// {
 ///     scheme.verify(public_key, message, &signature).unwrap();
 /// }
@@ -53,7 +109,7 @@ pub trait SignatureScheme<
     const PUBLIC_KEY_SIZE: usize,
     const SECRET_KEY_SIZE: usize,
     const SIGNATURE_SIZE: usize,
->: Sized
+>: SignatureVerifier<PUBLIC_KEY_SIZE, SIGNATURE_SIZE>
 {
     /// Generate a new signing keypair.
     ///
@@ -100,70 +156,41 @@ pub trait SignatureScheme<
         secret_key: &[u8; SECRET_KEY_SIZE],
         message: &[u8],
     ) -> Result<[u8; SIGNATURE_SIZE]>;
-
-    /// Verify a signature on a message with the public key.
-    ///
-    /// # Arguments
-    ///
-    /// - `public_key`: Signer's public key
-    /// - `message`: Message that was signed
-    /// - `signature`: Signature to verify
-    ///
-    /// # Returns
-    ///
-    /// `Ok(())` if verification succeeds.
-    ///
-    /// # Errors
-    ///
-    /// - `CryptoError::VerificationFailed`: If signature is invalid
-    /// - `MisuseError::InvalidPublicKey`: If public key format is invalid
-    ///
-    /// # Security
-    ///
-    /// - Performs constant-time operations where possible
-    /// - Completes all verification steps before returning error
-    /// - Returns same error for all failure modes (no information leakage)
-    /// - No early exit on format errors (timing consistency)
-    ///
-    /// # Important
-    ///
-    /// Signature verification failure is NOT an exceptional condition in
-    /// adversarial contexts. Callers must handle `VerificationFailed` as
-    /// an expected case.
-    fn verify(
-        &self,
-        public_key: &[u8; PUBLIC_KEY_SIZE],
-        message: &[u8],
-        signature: &[u8; SIGNATURE_SIZE],
-    ) -> Result<()>;
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::consts::MlDsa87 as Sizes;
+
+    const PK: usize = Sizes::PUBLIC_KEY_SIZE;
+    const SK: usize = Sizes::SECRET_KEY_SIZE;
+    const SIG: usize = Sizes::SIGNATURE_SIZE;
 
     // Compile-time size verification
     struct MockSignature;
 
-    impl SignatureScheme<2592, 4032, 4627> for MockSignature {
-        fn generate_keypair(&self) -> Result<([u8; 2592], [u8; 4032])> {
+    impl SignatureVerifier<PK, SIG> for MockSignature {
+        fn verify(
+            &self,
+            _public_key: &[u8; PK],
+            _message: &[u8],
+            _signature: &[u8; SIG],
+        ) -> Result<()> {
             unimplemented!("mock")
         }
+    }
 
-        fn sign(
-            &self,
-            _secret_key: &[u8; 4032],
-            _message: &[u8],
-        ) -> Result<[u8; 4627]> {
+    impl SignatureScheme<PK, SK, SIG> for MockSignature {
+        fn generate_keypair(&self) -> Result<([u8; PK], [u8; SK])> {
             unimplemented!("mock")
         }
 
-        fn verify(
+        fn sign(
             &self,
-            _public_key: &[u8; 2592],
+            _secret_key: &[u8; SK],
             _message: &[u8],
-            _signature: &[u8; 4627],
-        ) -> Result<()> {
+        ) -> Result<[u8; SIG]> {
             unimplemented!("mock")
         }
     }
@@ -24,6 +24,7 @@
 //! - `TAG_SIZE`: Size of authentication tag in bytes
 
 use crate::errors::Result;
+use crate::nonce::Nonce;
 
 /// Authenticated Encryption with Associated Data cipher trait.
 ///
@@ -39,8 +40,11 @@ use crate::errors::Result;
 /// # Nonce Uniqueness
 ///
 /// Callers MUST ensure that each (key, nonce) pair is used at most once.
-/// Nonce reuse completely breaks security. Consider using a counter or random
-/// nonces with sufficient size.
+/// Nonce reuse completely breaks security. `encrypt` therefore consumes a
+/// [`Nonce`], obtainable only from a `NonceSequence`, `Nonce::random`, or
+/// the explicit `Nonce::unsafe_from_bytes` escape hatch. `decrypt` takes the
+/// raw nonce received with the message, since decryption cannot cause
+/// reuse.
 ///
 /// # Output Format
 ///
@@ -54,9 +58,9 @@ use crate::errors::Result;
 /// where
 ///     A: AeadCipher<32, 12, 16>
 /// {
-///     let nonce = generate_nonce(); // Caller ensures uniqueness
+///     let nonce = Nonce::<12>::random().unwrap();
 ///     let mut ciphertext = vec![0u8; plaintext.len() + 16];
-///     cipher.encrypt(key, &nonce, plaintext, &[], &mut ciphertext).unwrap();
+///     cipher.encrypt(key, nonce, plaintext, &[], &mut ciphertext).unwrap();
 ///     ciphertext
 /// }
 /// ```
@@ -71,7 +75,7 @@ pub trait AeadCipher<
     /// # Arguments
     ///
     /// - `key`: Encryption key
-    /// - `nonce`: Nonce (must be unique for this key)
+    /// - `nonce`: Nonce (must be unique for this key); consumed
     /// - `plaintext`: Data to encrypt
     /// - `associated_data`: Additional authenticated data (not encrypted)
     /// - `output`: Buffer for ciphertext + tag (must be `plaintext.len() + TAG_SIZE`)
@@ -99,7 +103,7 @@ pub trait AeadCipher<
     fn encrypt(
         &self,
         key: &[u8; KEY_SIZE],
-        nonce: &[u8; NONCE_SIZE],
+        nonce: Nonce<NONCE_SIZE>,
         plaintext: &[u8],
         associated_data: &[u8],
         output: &mut [u8],
@@ -160,7 +164,7 @@ mod tests {
         fn encrypt(
             &self,
             _key: &[u8; 32],
-            _nonce: &[u8; 12],
+            _nonce: Nonce<12>,
             _plaintext: &[u8],
             _associated_data: &[u8],
             _output: &mut [u8],
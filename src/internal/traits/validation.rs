@@ -147,7 +147,7 @@ pub fn validate_nonce_size<const N: usize>(nonce: &[u8]) -> Result<()> {
 /// # Example
 ///
 /// ```ignore
-/// validate_public_key_size::<1568>(public_key)?;
+/// validate_public_key_size::<{ MlKem768::PUBLIC_KEY_SIZE }>(public_key)?;
 /// ```
 #[inline]
 pub fn validate_public_key_size<const N: usize>(public_key: &[u8]) -> Result<()> {
@@ -179,7 +179,7 @@ pub fn validate_public_key_size<const N: usize>(public_key: &[u8]) -> Result<()>
 /// # Example
 ///
 /// ```ignore
-/// validate_secret_key_size::<3168>(secret_key)?;
+/// validate_secret_key_size::<{ MlKem768::SECRET_KEY_SIZE }>(secret_key)?;
 /// ```
 #[inline]
 pub fn validate_secret_key_size<const N: usize>(secret_key: &[u8]) -> Result<()> {
@@ -240,7 +240,7 @@ pub fn validate_ciphertext_min_size(ciphertext: &[u8], min_size: usize) -> Resul
 /// # Example
 ///
 /// ```ignore
-/// validate_signature_size::<4627>(signature)?;
+/// validate_signature_size::<{ MlDsa87::SIGNATURE_SIZE }>(signature)?;
 /// ```
 #[inline]
 pub fn validate_signature_size<const N: usize>(signature: &[u8]) -> Result<()> {
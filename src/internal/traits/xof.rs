@@ -0,0 +1,79 @@
+//! Extendable-output function (XOF) trait.
+//!
+//! # Security Properties
+//!
+//! Implementations MUST:
+//! - Produce deterministic output for the same absorbed input
+//! - Produce a consistent stream: squeezing `a` then `b` bytes yields the
+//!   same bytes as squeezing `a + b` at once
+//! - Reject absorption after squeezing has started
+//! - Zeroize internal state when dropped
+//!
+//! Implementations MUST NOT:
+//! - Use keyed hashing (use KMAC/HKDF for that)
+//! - Log or expose intermediate state
+//!
+//! # Security Level
+//!
+//! An XOF's strength is capped by its capacity, not by the output length:
+//! squeezing more bytes from SHAKE128 never yields more than 128-bit
+//! security.
+
+use crate::errors::Result;
+
+/// Extendable-output function.
+///
+/// Absorb any amount of input, then squeeze any amount of output. The
+/// two phases do not interleave.
+///
+/// # Example
+///
+/// ```ignore
+/// fn expand<X: Xof>(xof: &mut X, seed: &[u8], out: &mut [u8]) -> Result<()> {
+///     xof.absorb(seed)?;
+///     xof.squeeze(out);
+///     Ok(())
+/// }
+/// ```
+pub trait Xof {
+    /// Absorb more input.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::InvalidState`: If output has already been squeezed
+    fn absorb(&mut self, data: &[u8]) -> Result<()>;
+
+    /// Fill `output` with the next bytes of the output stream, finishing
+    /// absorption on the first call.
+    fn squeeze(&mut self, output: &mut [u8]);
+
+    /// Reset to the initial state, clearing all absorbed input.
+    fn reset(&mut self);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct MockXof;
+
+    impl Xof for MockXof {
+        fn absorb(&mut self, _data: &[u8]) -> Result<()> {
+            unimplemented!("mock")
+        }
+
+        fn squeeze(&mut self, _output: &mut [u8]) {
+            unimplemented!("mock")
+        }
+
+        fn reset(&mut self) {
+            unimplemented!("mock")
+        }
+    }
+
+    #[test]
+    fn trait_is_object_safe() {
+        fn assert_object_safe(_: &dyn Xof) {}
+        assert_object_safe(&MockXof);
+    }
+}
@@ -1,4 +1,108 @@
+//! Citadel: CNSA 2.0-aligned post-quantum and hybrid cryptography.
+//!
+//! # Modules
+//!
+//! Each primitive family has its own module of concrete types, with key,
+//! ciphertext and signature sizes as associated constants:
+//!
+//! - [`kem`]: key encapsulation (ML-KEM-768)
+//! - [`sign`](mod@sign): signatures (ML-DSA-87, Ed25519)
+//! - [`aead`]: authenticated encryption (AES-256-GCM-SIV, AEGIS-256)
+//! - [`stream`]: chunked authenticated encryption for large data
+//! - [`hash`]: hashing and XOFs (SHA-3, BLAKE3, KangarooTwelve)
+//! - [`hybrid`]: post-quantum plus classical combinations of the above
+//! - [`hpke`]: public-key encryption (RFC 9180) over the KEMs above
+//! - [`signcrypt`]: signed public-key encryption in one call
+//!
+//! Key agreement, MACs, KDFs and higher-level formats live in [`kex`],
+//! [`mac`], [`kdf`], [`envelope`], [`dek`], [`file`](mod@file) and [`dsse`]. Applications that
+//! would rather pick one preset than compose primitives use [`suite`], or
+//! [`level`] to get parameter sets matched to one security level.
+//! [`dynamic`] selects algorithms by name at run time. [`consts`] lists
+//! every algorithm's sizes, usable without enabling the algorithm, and
+//! [`algorithm`] their stable identifiers for wire formats. [`keys`] binds
+//! encoded keys, ciphertexts and signatures to their algorithm in the type.
+//!
+//! # API Stability
+//!
+//! Public items fall into three tiers:
+//!
+//! - **Stable**: everything reachable from the crate root except the modules
+//!   listed below. Follows semver. Enums that are expected to grow (error
+//!   kinds, algorithm identifiers, format versions) are `#[non_exhaustive]`,
+//!   so new variants are not breaking changes; match them with a catch-all
+//!   arm.
+//! - **Unstable**: `citadel::unstable`, compiled only with the `api-unstable`
+//!   feature. Experimental APIs land here first and may change or disappear
+//!   in any release.
+//! - **Internal**: [`internal`] and `citadel::r#unsafe`. Public for testing and
+//!   auditing only; not covered by any stability guarantee.
+//!
+//! # Cargo Features
+//!
+//! Each of these algorithms is behind its own feature, all enabled by
+//! default: `mlkem`, `mldsa`, `aes-gcm-siv`, `blake3`, `k12`, `argon2` and
+//! `scrypt`. Builds with a code-size budget can disable default features and
+//! enable `std` plus the algorithms they use. The types of a disabled
+//! algorithm are not compiled; APIs that pick an algorithm at run time, such
+//! as [`kdf::password::verify`], return `MisuseError::FeatureNotEnabled`,
+//! and [`bench`](mod@bench) skips its benchmarks.
+//!
+//! SHA-2, SHA-3, X25519, Ed25519 and the hybrid combiners are always
+//! compiled: the rest of the crate is built on them.
+//!
+//! Key generation, signing and signing-key types of every signature scheme
+//! are behind `signing`, also on by default. Bootloaders and client-side
+//! verifiers can build with `default-features = false, features =
+//! ["verify-only"]`: Ed25519, ML-DSA-87 and hybrid signature verification,
+//! hashing and DSSE envelope verification, with nothing that can sign.
+//!
+//! `conformance` adds `citadel::conformance`, which checks that a
+//! storage or transport stack preserves the tamper, truncation, downgrade
+//! and replay detection of the envelope, data-key envelope and stream
+//! formats.
+
 pub mod errors;
 pub mod internal;
 pub mod r#unsafe;
-pub mod memory;
\ No newline at end of file
+pub mod memory;
+pub mod encoding;
+pub mod nonce;
+pub mod consts;
+pub mod context;
+pub mod bench;
+
+mod algorithms;
+mod api;
+pub mod hybrid;
+
+pub use api::advisor;
+pub use api::algorithm;
+pub use api::aead;
+pub use api::beacon;
+#[cfg(feature = "conformance")]
+pub use api::conformance;
+pub use api::dek;
+pub use api::drbg;
+pub use api::dsse;
+pub use api::dynamic;
+pub use api::envelope;
+#[cfg(feature = "std")]
+pub use api::file;
+pub use api::harden;
+pub use api::hash;
+pub use api::hpke;
+pub use api::kdf;
+pub use api::kem;
+pub use api::keys;
+pub use api::kex;
+pub use api::level;
+pub use api::mac;
+pub use api::sign;
+pub use api::signcrypt;
+pub use api::stream;
+#[cfg(all(feature = "mlkem", feature = "aes-gcm-siv"))]
+pub use api::suite;
+
+#[cfg(feature = "api-unstable")]
+pub mod unstable;
@@ -0,0 +1,102 @@
+//! Allocator handles for heap-backed secret storage.
+//!
+//! # Purpose
+//!
+//! By default [`SecureBuffer`](super::SecureBuffer) allocates from the global
+//! heap. Deployments that keep secrets in a dedicated region (an `mlock`ed
+//! arena, a guarded page pool, a separate embedded heap) implement
+//! [`SecureAllocator`] and construct buffers with the `*_in` constructors.
+//!
+//! The trait mirrors `core::alloc::GlobalAlloc` rather than the unstable
+//! `Allocator` API so it works on stable Rust.
+//!
+//! # Example
+//!
+//! ```ignore
+//! static POOL: LockedPool = LockedPool::new();
+//!
+//! let mut key = SecureBuffer::zeroed_in(32, &POOL);
+//! ```
+
+use core::alloc::Layout;
+use core::ptr::NonNull;
+
+/// Source of memory for secure buffers.
+///
+/// Buffers only request byte layouts (alignment 1) and never request a
+/// zero-sized allocation. Every allocation is zeroized before it is handed
+/// back to [`deallocate`](SecureAllocator::deallocate).
+///
+/// # Safety
+///
+/// Implementations must return memory that is valid for reads and writes
+/// of `layout.size()` bytes, aligned to `layout.align()`, and not aliased
+/// by any other allocation until it is passed to `deallocate`.
+pub unsafe trait SecureAllocator {
+    /// Allocate a block for `layout`.
+    ///
+    /// Returns `None` if the allocator is exhausted. Buffers treat this
+    /// like `Vec` does and call `std::alloc::handle_alloc_error`.
+    fn allocate(&self, layout: Layout) -> Option<NonNull<u8>>;
+
+    /// Release a block previously returned by `allocate`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by `allocate` on this allocator with
+    /// the same `layout`, and must not be used afterwards.
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout);
+}
+
+// SAFETY: forwards to the referenced allocator unchanged
+unsafe impl<A: SecureAllocator + ?Sized> SecureAllocator for &A {
+    #[inline]
+    fn allocate(&self, layout: Layout) -> Option<NonNull<u8>> {
+        (**self).allocate(layout)
+    }
+
+    #[inline]
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        // SAFETY: forwarded contract
+        unsafe { (**self).deallocate(ptr, layout) }
+    }
+}
+
+/// The global heap, used by buffers unless another allocator is given.
+///
+/// Allocations are layout-compatible with `Vec<u8>`, so buffers on the
+/// global heap can adopt and release `Vec` storage without copying.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GlobalHeap;
+
+// SAFETY: delegates to the global allocator, which upholds the contract
+unsafe impl SecureAllocator for GlobalHeap {
+    #[inline]
+    fn allocate(&self, layout: Layout) -> Option<NonNull<u8>> {
+        debug_assert!(layout.size() > 0);
+        // SAFETY: buffers never request zero-sized layouts
+        NonNull::new(unsafe { std::alloc::alloc(layout) })
+    }
+
+    #[inline]
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        // SAFETY: `ptr` came from `allocate` with this layout
+        unsafe { std::alloc::dealloc(ptr.as_ptr(), layout) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn global_heap_round_trip() {
+        let layout = Layout::array::<u8>(64).unwrap();
+        let ptr = GlobalHeap.allocate(layout).unwrap();
+        unsafe {
+            ptr.as_ptr().write_bytes(0x42, 64);
+            assert_eq!(*ptr.as_ptr().add(63), 0x42);
+            GlobalHeap.deallocate(ptr, layout);
+        }
+    }
+}
@@ -0,0 +1,196 @@
+//! Chunked processing of secret buffers.
+//!
+//! # Purpose
+//!
+//! Chunked pipelines (encrypting a large secret block by block, feeding a
+//! KDF piecewise) tend to copy each chunk into a local array or `Vec`
+//! that nobody zeroizes. [`SecretChunks`] hands out mutable views of a
+//! [`SecureBuffer`] in place, each paired with a scratch buffer of the
+//! same size that is zeroized before the next chunk is handed out.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let mut chunks = SecretChunks::new(&mut plaintext, 4096);
+//! while let Some(mut chunk) = chunks.next_chunk() {
+//!     let (data, scratch) = chunk.parts();
+//!     cipher.encrypt_block(data, scratch)?;
+//!     data.copy_from_slice(&scratch[..data.len()]);
+//! }
+//! ```
+//!
+//! # Caveat
+//!
+//! `next_chunk` lends each chunk for one iteration, so `SecretChunks` is
+//! not a [`std::iter::Iterator`]; use `while let` or
+//! [`SecretChunks::try_for_each`].
+
+use core::fmt;
+
+use super::{SecureAllocator, SecureBuffer};
+use crate::r#unsafe::memory::zeroize_slice;
+
+/// Mutable chunk views of a secret buffer, with zeroized scratch space.
+pub struct SecretChunks<'a> {
+    rest: &'a mut [u8],
+    chunk_size: usize,
+    scratch: SecureBuffer,
+    index: usize,
+}
+
+impl<'a> SecretChunks<'a> {
+    /// Chunks of `chunk_size` bytes over `buffer`; the last may be
+    /// shorter.
+    ///
+    /// # Panics
+    ///
+    /// If `chunk_size` is zero.
+    pub fn new<A: SecureAllocator>(buffer: &'a mut SecureBuffer<A>, chunk_size: usize) -> Self {
+        assert!(chunk_size != 0, "chunk size must be non-zero");
+        Self {
+            rest: buffer.as_mut_slice(),
+            chunk_size,
+            scratch: SecureBuffer::zeroed(chunk_size),
+            index: 0,
+        }
+    }
+
+    /// The next chunk, or `None` once the buffer is exhausted.
+    ///
+    /// The chunk's scratch space is zeroized when it is dropped.
+    pub fn next_chunk(&mut self) -> Option<SecretChunk<'_>> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        let rest = core::mem::take(&mut self.rest);
+        let (data, tail) = rest.split_at_mut(self.chunk_size.min(rest.len()));
+        self.rest = tail;
+        let index = self.index;
+        self.index += 1;
+        Some(SecretChunk {
+            index,
+            scratch: &mut self.scratch.as_mut_slice()[..data.len()],
+            data,
+        })
+    }
+
+    /// Run `f` on every remaining chunk, stopping at the first error.
+    ///
+    /// # Errors
+    ///
+    /// - The first error `f` returns
+    pub fn try_for_each<E>(
+        mut self,
+        mut f: impl FnMut(SecretChunk<'_>) -> Result<(), E>,
+    ) -> Result<(), E> {
+        while let Some(chunk) = self.next_chunk() {
+            f(chunk)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for SecretChunks<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecretChunks")
+            .field("chunk_size", &self.chunk_size)
+            .field("remaining", &self.rest.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// One chunk lent by [`SecretChunks::next_chunk`].
+pub struct SecretChunk<'s> {
+    index: usize,
+    data: &'s mut [u8],
+    scratch: &'s mut [u8],
+}
+
+impl SecretChunk<'_> {
+    /// Position of this chunk, counting from zero.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The chunk itself, a view into the underlying buffer.
+    #[inline]
+    pub fn data(&mut self) -> &mut [u8] {
+        self.data
+    }
+
+    /// Zeroed scratch space of the chunk's length.
+    #[inline]
+    pub fn scratch(&mut self) -> &mut [u8] {
+        self.scratch
+    }
+
+    /// The chunk and its scratch space at once.
+    #[inline]
+    pub fn parts(&mut self) -> (&mut [u8], &mut [u8]) {
+        (self.data, self.scratch)
+    }
+}
+
+impl Drop for SecretChunk<'_> {
+    fn drop(&mut self) {
+        // SAFETY: `scratch` is an exclusively borrowed, initialized slice
+        unsafe { zeroize_slice(self.scratch) };
+    }
+}
+
+impl fmt::Debug for SecretChunk<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecretChunk")
+            .field("index", &self.index)
+            .field("len", &self.data.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunks_cover_buffer_in_place() {
+        let mut buffer = SecureBuffer::new((0..10).collect());
+        let mut chunks = SecretChunks::new(&mut buffer, 4);
+        let mut lengths = Vec::new();
+        while let Some(mut chunk) = chunks.next_chunk() {
+            lengths.push(chunk.data().len());
+            let index = chunk.index() as u8;
+            for byte in chunk.data() {
+                *byte += 100 * index;
+            }
+        }
+        assert_eq!(lengths, [4, 4, 2]);
+        assert_eq!(
+            buffer.as_slice(),
+            [0, 1, 2, 3, 104, 105, 106, 107, 208, 209]
+        );
+    }
+
+    #[test]
+    fn scratch_is_zeroized_between_chunks() {
+        let mut buffer = SecureBuffer::zeroed(12);
+        let result: Result<(), &str> =
+            SecretChunks::new(&mut buffer, 4).try_for_each(|mut chunk| {
+                let (data, scratch) = chunk.parts();
+                assert!(scratch.iter().all(|&b| b == 0));
+                scratch.fill(0xee);
+                data.copy_from_slice(scratch);
+                Ok(())
+            });
+        assert!(result.is_ok());
+        assert!(buffer.as_slice().iter().all(|&b| b == 0xee));
+
+        let stopped = SecretChunks::new(&mut buffer, 4).try_for_each(|chunk| {
+            if chunk.index() == 1 {
+                return Err("stop");
+            }
+            Ok(())
+        });
+        assert_eq!(stopped, Err("stop"));
+    }
+}
@@ -0,0 +1,159 @@
+//! Constant-time table lookups.
+//!
+//! # Purpose
+//!
+//! Indexing a table with a secret (an S-box input, a window of a secret
+//! scalar, a masked share) loads one cache line chosen by the secret, and
+//! cache-timing attacks recover the index from which line was loaded.
+//! [`lookup`] reads every entry of the table, whatever the index, and keeps
+//! the wanted one with a mask, so the sequence of addresses touched depends
+//! only on the table's length. [`lookup_byte`] does the same for a table of
+//! bytes, such as an encoding alphabet, and [`position`] is the inverse
+//! mapping: it finds a secret value in a table without stopping at it.
+//!
+//! # Cost
+//!
+//! Linear in the table size. Keep tables small (an S-box, a precomputed
+//! window of a few points); large tables belong in code that is not
+//! secret-indexed at all.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use citadel::memory::ct;
+//!
+//! let row = ct::lookup(&SBOX_ROWS, usize::from(secret_byte >> 4));
+//! ```
+
+use core::hint::black_box;
+
+/// Return `table[secret_index]`, reading every entry of `table`.
+///
+/// Returns all zeros if `secret_index` is out of range; the access pattern
+/// is the same either way.
+#[inline]
+pub fn lookup<const W: usize>(table: &[[u8; W]], secret_index: usize) -> [u8; W] {
+    select(table.iter(), secret_index)
+}
+
+/// Return `table[secret_index]` for a table of bytes, reading every entry.
+///
+/// Returns 0 if `secret_index` is out of range.
+#[inline]
+pub fn lookup_byte(table: &[u8], secret_index: usize) -> u8 {
+    let [byte] = select(table.iter().map(core::array::from_ref), secret_index);
+    byte
+}
+
+/// Find `secret_value` in `table`, reading every entry.
+///
+/// Returns `(position, found)`, where `found` is `0xFF` if the value occurs
+/// in `table` and `0x00` otherwise, in which case `position` is 0. With
+/// repeated values, the positions are OR-ed together; use tables of
+/// distinct entries.
+#[inline]
+pub fn position(table: &[u8], secret_value: u8) -> (usize, u8) {
+    let mut position = 0usize;
+    let mut found = 0u8;
+    for (i, &entry) in table.iter().enumerate() {
+        let mask = black_box(eq_mask(entry.into(), secret_value.into()));
+        position |= i & usize::from(mask & 1).wrapping_neg();
+        found |= mask;
+    }
+    (position, found)
+}
+
+/// Combine the entries of `rows`, keeping the one at `secret_index`.
+#[inline]
+fn select<'a, const W: usize>(
+    rows: impl Iterator<Item = &'a [u8; W]>,
+    secret_index: usize,
+) -> [u8; W] {
+    let mut out = [0u8; W];
+    for (i, row) in rows.enumerate() {
+        // Opaque to the optimizer, so the loop cannot be turned back into
+        // a direct index or an early exit
+        let mask = black_box(eq_mask(i, secret_index));
+        for (o, &byte) in out.iter_mut().zip(row) {
+            *o |= byte & mask;
+        }
+    }
+    out
+}
+
+/// `0xFF` if `a == b`, `0x00` otherwise, without branching.
+#[inline]
+pub(crate) fn eq_mask(a: usize, b: usize) -> u8 {
+    let difference = (a ^ b) as u64;
+    let nonzero = (difference | difference.wrapping_neg()) >> 63;
+    (nonzero as u8).wrapping_sub(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> Vec<[u8; 4]> {
+        (0u8..=255).map(|i| [i, !i, i ^ 0x5a, 0x80 | i]).collect()
+    }
+
+    #[test]
+    fn returns_selected_entry() {
+        let table = table();
+        for (index, entry) in table.iter().enumerate() {
+            assert_eq!(lookup(&table, index), *entry);
+        }
+        assert_eq!(lookup(&table, table.len()), [0; 4]);
+        assert_eq!(lookup(&table, usize::MAX), [0; 4]);
+        assert_eq!(lookup::<4>(&[], 0), [0; 4]);
+        assert_eq!(eq_mask(1 << 40, 0), 0x00);
+    }
+
+    #[test]
+    fn byte_tables() {
+        let table = b"abcdef";
+        assert_eq!(lookup_byte(table, 3), b'd');
+        assert_eq!(lookup_byte(table, 6), 0);
+        assert_eq!(position(table, b'a'), (0, 0xFF));
+        assert_eq!(position(table, b'e'), (4, 0xFF));
+        assert_eq!(position(table, b'z'), (0, 0x00));
+        assert_eq!(eq_mask(255, 255), 0xFF);
+        assert_eq!(eq_mask(0, 255), 0x00);
+    }
+
+    #[test]
+    fn reads_every_entry_for_every_index() {
+        let table = table();
+        for index in [0, 1, 77, 255, 256, usize::MAX] {
+            let mut visited = Vec::new();
+            let rows = table.iter().enumerate().map(|(i, row)| {
+                visited.push(i);
+                row
+            });
+            select(rows, index);
+            assert!(visited.iter().copied().eq(0..table.len()));
+        }
+    }
+
+    /// Cache-level check. Run it by hand under cachegrind with two
+    /// different indices; the `D1` and `LL` miss counts must match:
+    ///
+    /// ```text
+    /// CITADEL_CT_INDEX=3 valgrind --tool=cachegrind --cache-sim=yes \
+    ///     target/debug/deps/citadel-<hash> --ignored --exact \
+    ///     memory::ct::tests::cachegrind_access_pattern
+    /// ```
+    #[test]
+    #[ignore = "run under valgrind --tool=cachegrind"]
+    fn cachegrind_access_pattern() {
+        let index = std::env::var("CITADEL_CT_INDEX")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(0);
+        // 64 rows of one cache line each
+        let table: Vec<[u8; 64]> = (0u8..64).map(|i| [i; 64]).collect();
+        for _ in 0..1000 {
+            black_box(lookup(black_box(&table), black_box(index)));
+        }
+    }
+}
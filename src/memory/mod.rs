@@ -15,8 +15,15 @@
 //!
 //! - **Zeroization** - Explicit clearing of sensitive data using volatile writes
 //! - **Sensitivity markers** - Type-level tracking of sensitive data
-//! - **Constant-time operations** - Comparisons resistant to timing attacks
+//! - **Constant-time operations** - Comparisons resistant to timing attacks,
+//!   and table lookups that read every entry ([`ct`])
 //! - **Secure buffers** - RAII wrappers with automatic cleanup
+//! - **Scoped secrets** - Temporary buffers lent to a closure, zeroized on
+//!   every exit path
+//! - **Secret chunks** - In-place chunk views of a buffer with scratch
+//!   space zeroized between chunks
+//! - **Allocator handles** - Route buffer storage into a dedicated pool
+//! - **Taint tracking** - Debug-only leak detection (`taint-debug` feature)
 //!
 //! # Usage Example
 //!
@@ -53,16 +60,54 @@
 //! Core functionality (zeroization, constant-time ops) works on all platforms.
 //! Platform-specific features (memory locking) may have limited availability.
 
+mod alloc;
+mod chunks;
+pub mod ct;
 mod zeroize;
+mod scope;
 mod sensitivity;
+#[cfg(feature = "taint-debug")]
+pub mod taint;
 
 // Re-export public items
+pub use alloc::{GlobalHeap, SecureAllocator};
+pub use chunks::{SecretChunk, SecretChunks};
 pub use zeroize::{
-    constant_time_eq, constant_time_eq_array, constant_time_select, lock_memory,
+    constant_time_eq, constant_time_eq_array, constant_time_select, ct_eq_padded, lock_memory,
     unlock_memory, SecureBuffer, SecureBufferBuilder,
 };
 
-pub use sensitivity::{Sensitive, SensitiveBytes, SensitivityLevel};
+pub use scope::{with_secret, with_secret_array, with_secret_in};
+pub use sensitivity::{NeverSerialize, Sensitive, SensitiveBytes, SensitivityLevel};
+pub(crate) use sensitivity::{never_serialize, sealed};
+
+/// Assert that a value's `Debug` output contains no tracked secret.
+///
+/// With the `taint-debug` feature this checks the value against every
+/// secret read on the current thread and fires a debug assertion on a
+/// match. Without the feature it expands to a no-op, so it can be left in
+/// test and logging code paths.
+#[cfg(feature = "taint-debug")]
+#[macro_export]
+macro_rules! assert_untainted {
+    ($value:expr) => {
+        $crate::memory::taint::assert_untainted(&$value)
+    };
+}
+
+/// Assert that a value's `Debug` output contains no tracked secret.
+///
+/// With the `taint-debug` feature this checks the value against every
+/// secret read on the current thread and fires a debug assertion on a
+/// match. Without the feature it expands to a no-op, so it can be left in
+/// test and logging code paths.
+#[cfg(not(feature = "taint-debug"))]
+#[macro_export]
+macro_rules! assert_untainted {
+    ($value:expr) => {{
+        let _ = &$value;
+    }};
+}
 
 #[cfg(test)]
 mod tests {
@@ -81,6 +126,15 @@ mod tests {
         assert!(constant_time_eq(&a, &b));
     }
 
+    #[test]
+    fn secret_types_are_never_serialize() {
+        fn marked<T: NeverSerialize>() {}
+        marked::<SensitiveBytes<32>>();
+        marked::<SecureBuffer>();
+        marked::<crate::kem::SharedSecret>();
+        marked::<crate::kex::X25519SecretKey>();
+    }
+
     #[test]
     fn internal_unsafe_accessible() {
         // Internal code can access unsafe operations
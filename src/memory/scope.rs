@@ -0,0 +1,124 @@
+//! Scoped temporary secrets.
+//!
+//! # Purpose
+//!
+//! Intermediate secrets (derived keys, decrypted key material, scratch
+//! space) must be zeroized on every exit path, including `?` and panics.
+//! Writing that by hand means a guard or a zeroize call per branch.
+//!
+//! [`with_secret`] and [`with_secret_array`] allocate a zeroed buffer, lend
+//! it to a closure and zeroize it when the closure returns or unwinds. The
+//! closure only ever holds a borrow, so it cannot move the buffer out or
+//! `mem::forget` it.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let tag = memory::with_secret_array::<32, _>(|key| {
+//!     derive_key(password, salt, key)?;
+//!     compute_tag(key, message)
+//! })?;
+//! ```
+//!
+//! # Caveat
+//!
+//! Only the lent buffer is protected. Anything the closure copies out,
+//! including its return value, is the caller's responsibility.
+
+use super::{SecureAllocator, SecureBuffer, SensitiveBytes};
+
+/// Run `f` with a zeroed `len`-byte buffer that is zeroized afterwards.
+///
+/// The buffer lives on the global heap; see [`with_secret_in`] to choose
+/// the allocator.
+pub fn with_secret<R>(len: usize, f: impl FnOnce(&mut SecureBuffer) -> R) -> R {
+    let mut buffer = SecureBuffer::zeroed(len);
+    f(&mut buffer)
+}
+
+/// Like [`with_secret`], with the buffer allocated from `alloc`.
+pub fn with_secret_in<A: SecureAllocator, R>(
+    len: usize,
+    alloc: A,
+    f: impl FnOnce(&mut SecureBuffer<A>) -> R,
+) -> R {
+    let mut buffer = SecureBuffer::zeroed_in(len, alloc);
+    f(&mut buffer)
+}
+
+/// Run `f` with a zeroed `N`-byte array that is zeroized afterwards.
+///
+/// The array lives on the stack; prefer [`with_secret`] for large or
+/// variable-size buffers.
+pub fn with_secret_array<const N: usize, R>(f: impl FnOnce(&mut [u8; N]) -> R) -> R {
+    let mut secret = SensitiveBytes::<N>::zeroed();
+    f(secret.as_bytes_mut())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::{MisuseError, Result};
+    use crate::memory::GlobalHeap;
+    use core::alloc::Layout;
+    use core::cell::Cell;
+    use core::ptr::NonNull;
+
+    /// Counts frees and asserts every freed block was zeroized.
+    struct CheckingPool {
+        freed: Cell<usize>,
+    }
+
+    unsafe impl SecureAllocator for CheckingPool {
+        fn allocate(&self, layout: Layout) -> Option<NonNull<u8>> {
+            GlobalHeap.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            let block = unsafe { core::slice::from_raw_parts(ptr.as_ptr(), layout.size()) };
+            assert!(block.iter().all(|&b| b == 0), "freed without zeroizing");
+            self.freed.set(self.freed.get() + 1);
+            unsafe { GlobalHeap.deallocate(ptr, layout) }
+        }
+    }
+
+    #[test]
+    fn zeroizes_on_every_exit_path() {
+        let pool = CheckingPool {
+            freed: Cell::new(0),
+        };
+
+        let sum = with_secret_in(16, &pool, |buffer| {
+            buffer.as_mut_slice().fill(0x42);
+            buffer.as_slice().iter().map(|&b| u32::from(b)).sum::<u32>()
+        });
+        assert_eq!(sum, 16 * 0x42);
+
+        let early: Result<()> = with_secret_in(16, &pool, |buffer| {
+            buffer.as_mut_slice().fill(0x42);
+            Err(MisuseError::InvalidState)?;
+            unreachable!()
+        });
+        assert_eq!(early.unwrap_err().misuse(), Some(MisuseError::InvalidState));
+
+        let unwound = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            with_secret_in(16, &pool, |buffer| {
+                buffer.as_mut_slice().fill(0x42);
+                panic!("mid-computation failure");
+            })
+        }));
+        assert!(unwound.is_err());
+        assert_eq!(pool.freed.get(), 3);
+    }
+
+    #[test]
+    fn lends_zeroed_buffers() {
+        assert!(with_secret(64, |buffer| buffer.as_slice() == [0u8; 64]));
+        let first = with_secret_array::<32, _>(|key| {
+            assert_eq!(*key, [0u8; 32]);
+            key[0] = 7;
+            key[0]
+        });
+        assert_eq!(first, 7);
+    }
+}
@@ -31,8 +31,9 @@
 
 use core::fmt;
 use core::marker::PhantomData;
-use crate::internal::traits::memory::SecureMemory;
-use crate::r#unsafe::memory::zeroize_array;
+
+use super::zeroize::SecureBuffer;
+use crate::errors::{Error, MisuseError, Result};
 
 /// Marker trait for types containing sensitive cryptographic material.
 ///
@@ -85,6 +86,95 @@ pub trait Sensitive: Sized {
     }
 }
 
+pub(crate) mod sealed {
+    /// Implemented only inside Citadel, by [`never_serialize!`].
+    pub trait Sealed {}
+
+    /// Resolves unambiguously only for types that are not `Display`.
+    pub trait AmbiguousIfDisplay<A> {
+        fn check() {}
+    }
+
+    impl<T: ?Sized> AmbiguousIfDisplay<()> for T {}
+
+    pub struct IsDisplay;
+
+    impl<T: ?Sized + core::fmt::Display> AmbiguousIfDisplay<IsDisplay> for T {}
+}
+
+/// Sealed marker for types holding secrets: keys, shared secrets and
+/// secret buffers.
+///
+/// Every secret type in Citadel carries this marker, and the crate checks
+/// at compile time that none of them implements `Display`. None implements
+/// a serialization trait either, so a downstream struct holding a secret
+/// cannot derive `Display` or `Serialize` through a derive macro: the
+/// generated code needs the field to implement the trait and fails to
+/// compile. The trait is sealed, so it cannot be implemented outside the
+/// crate to claim a type is safe.
+///
+/// Formatting a secret does not compile:
+///
+/// ```compile_fail
+/// use citadel::memory::SensitiveBytes;
+///
+/// let key = SensitiveBytes::new([0x42u8; 32]);
+/// println!("{key}");
+/// ```
+///
+/// Neither does what a `Display` derive expands to for a struct holding
+/// one:
+///
+/// ```compile_fail
+/// use core::fmt;
+/// use citadel::kem::SharedSecret;
+///
+/// struct Session {
+///     secret: SharedSecret,
+/// }
+///
+/// impl fmt::Display for Session {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         write!(f, "{}", self.secret)
+///     }
+/// }
+/// ```
+///
+/// Nor does implementing the marker outside the crate:
+///
+/// ```compile_fail
+/// use citadel::memory::NeverSerialize;
+///
+/// struct Token([u8; 16]);
+///
+/// impl NeverSerialize for Token {}
+/// ```
+pub trait NeverSerialize: sealed::Sealed {}
+
+/// Mark a secret type [`NeverSerialize`] and assert it is not `Display`.
+///
+/// Generic parameters go in brackets: `never_serialize!([const N: usize]
+/// SensitiveBytes<N>)`, or `never_serialize!([] SharedSecret)`.
+macro_rules! never_serialize {
+    ([$($generics:tt)*] $ty:ty) => {
+        impl<$($generics)*> $crate::memory::sealed::Sealed for $ty {}
+
+        impl<$($generics)*> $crate::memory::NeverSerialize for $ty {}
+
+        const _: () = {
+            // Ambiguous, and so a compile error, if `$ty` is `Display`
+            #[allow(dead_code)]
+            fn assert_not_display<$($generics)*>() {
+                let _ = <$ty as $crate::memory::sealed::AmbiguousIfDisplay<_>>::check;
+            }
+        };
+    };
+}
+
+pub(crate) use never_serialize;
+
+never_serialize!([const N: usize] SensitiveBytes<N>);
+
 /// Wrapper type that marks contained data as sensitive.
 ///
 /// This type provides a generic way to mark any data as sensitive
@@ -153,6 +243,8 @@ impl<const N: usize> SensitiveBytes<N> {
     /// - Stored long-term
     #[inline]
     pub fn as_bytes(&self) -> &[u8; N] {
+        #[cfg(feature = "taint-debug")]
+        super::taint::mark_read(&self.data);
         &self.data
     }
 
@@ -163,6 +255,8 @@ impl<const N: usize> SensitiveBytes<N> {
     /// Same as `as_bytes()`.
     #[inline]
     pub fn as_bytes_mut(&mut self) -> &mut [u8; N] {
+        #[cfg(feature = "taint-debug")]
+        super::taint::mark_read(&self.data);
         &mut self.data
     }
 
@@ -173,7 +267,9 @@ impl<const N: usize> SensitiveBytes<N> {
     /// This bypasses automatic zeroization on drop.
     /// Caller is responsible for zeroizing the returned data.
     #[inline]
-    pub fn into_inner(mut self) -> [u8; N] {
+    pub fn into_inner(self) -> [u8; N] {
+        #[cfg(feature = "taint-debug")]
+        super::taint::mark_read(&self.data);
         // Create a copy before drop
         let data = self.data;
         // Prevent our Drop from running
@@ -203,6 +299,8 @@ impl<const N: usize> Sensitive for SensitiveBytes<N> {
 // Implement SecureMemory for SensitiveBytes
 impl<const N: usize> crate::internal::traits::SecureMemory for SensitiveBytes<N> {
     fn zeroize(&mut self) {
+        #[cfg(feature = "taint-debug")]
+        super::taint::release(&self.data);
         // Use the SecureMemory implementation for arrays
         unsafe {
             crate::r#unsafe::memory::zeroize_array(&mut self.data);
@@ -228,6 +326,57 @@ impl<const N: usize> fmt::Debug for SensitiveBytes<N> {
 // Explicitly do NOT implement Copy or Clone
 // (default behavior, but making it explicit for documentation)
 
+/// Move fixed-size sensitive bytes into a heap-backed secure buffer.
+///
+/// The source is consumed and zeroized on drop, so the secret only
+/// survives inside the returned `SecureBuffer`.
+///
+/// # Example
+///
+/// ```ignore
+/// let secret = SensitiveBytes::new([0x42u8; 32]);
+/// let buffer = SecureBuffer::from(secret);
+/// assert_eq!(buffer.len(), 32);
+/// ```
+impl<const N: usize> From<SensitiveBytes<N>> for SecureBuffer {
+    fn from(value: SensitiveBytes<N>) -> Self {
+        let mut buffer = SecureBuffer::zeroed(N);
+        buffer.as_mut_slice().copy_from_slice(&value.data);
+        // `value` drops here and zeroizes its copy
+        buffer
+    }
+}
+
+/// Move a secure buffer into fixed-size sensitive bytes.
+///
+/// The source buffer is consumed and zeroized on drop, including when the
+/// conversion fails.
+///
+/// # Errors
+///
+/// - `MisuseError::InvalidKeyLength`: If the buffer length is not `N`
+///
+/// # Example
+///
+/// ```ignore
+/// let buffer = SecureBuffer::zeroed(32);
+/// let secret = SensitiveBytes::<32>::try_from(buffer)?;
+/// ```
+impl<const N: usize> TryFrom<SecureBuffer> for SensitiveBytes<N> {
+    type Error = Error;
+
+    fn try_from(value: SecureBuffer) -> Result<Self> {
+        if value.len() != N {
+            return Err(MisuseError::InvalidKeyLength.into());
+        }
+
+        let mut out = SensitiveBytes::zeroed();
+        out.data.copy_from_slice(value.as_slice());
+        // `value` drops here and zeroizes its allocation
+        Ok(out)
+    }
+}
+
 /// Sensitivity level classification.
 ///
 /// Provides a way to classify different levels of sensitivity
@@ -325,10 +474,10 @@ mod tests {
             drop(sensitive);
             // Check that memory was zeroized
             unsafe {
-                for i in 0..32 {
+                for (i, byte) in data.iter_mut().enumerate() {
                     // Note: This is UB in general, but useful for testing
                     // In production, we trust the volatile write
-                    data[i] = *ptr.add(i);
+                    *byte = *ptr.add(i);
                 }
             }
         }
@@ -362,6 +511,27 @@ mod tests {
         assert!(empty.is_empty());
     }
 
+    #[test]
+    fn sensitive_bytes_into_secure_buffer() {
+        let sensitive = SensitiveBytes::new([0x42u8; 32]);
+        let buffer = SecureBuffer::from(sensitive);
+        assert_eq!(buffer.as_slice(), &[0x42u8; 32]);
+    }
+
+    #[test]
+    fn secure_buffer_into_sensitive_bytes() {
+        let buffer = SecureBuffer::new(vec![0x42u8; 32]);
+        let sensitive = SensitiveBytes::<32>::try_from(buffer).unwrap();
+        assert_eq!(sensitive.as_bytes(), &[0x42u8; 32]);
+    }
+
+    #[test]
+    fn secure_buffer_into_sensitive_bytes_wrong_length() {
+        let buffer = SecureBuffer::new(vec![0x42u8; 16]);
+        let err = SensitiveBytes::<32>::try_from(buffer).unwrap_err();
+        assert_eq!(err, Error::Misuse(MisuseError::InvalidKeyLength));
+    }
+
     #[test]
     fn sensitivity_level_ordering() {
         assert!(SensitivityLevel::Public < SensitivityLevel::Low);
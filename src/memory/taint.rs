@@ -0,0 +1,267 @@
+//! Debug-only taint tracking for secret data.
+//!
+//! Enabled by the `taint-debug` feature. Intended for test suites of
+//! applications built on Citadel, never for production builds.
+//!
+//! # How It Works
+//!
+//! Every time the contents of a [`SensitiveBytes`](super::SensitiveBytes) are
+//! read through its accessors, the value is recorded in a per-thread
+//! registry. Output produced by non-secret types (`Debug`/`Display` strings,
+//! serialized bytes) can then be checked against the registry; a match fires
+//! a debug assertion naming the leak.
+//!
+//! Values are matched in the encodings secrets most often leak in:
+//! - Raw bytes
+//! - Lowercase and uppercase hex
+//! - Rust slice `Debug` form (`[66, 66, ...]`)
+//!
+//! # Limitations
+//!
+//! - Tracking is per-thread; secrets read on one thread are not known to
+//!   another.
+//! - Secrets shorter than [`MIN_TRACKED_LEN`] bytes and all-zero values are
+//!   not tracked, as they would match innocent output.
+//! - The registry holds copies of the secrets it tracks (zeroized on
+//!   release). This is acceptable for debug builds only.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let key = SensitiveBytes::new(derive_key());
+//! let record = AuditRecord::new(&key); // accidentally captures key bytes
+//! citadel::assert_untainted!(record);  // panics in debug builds
+//! ```
+
+use core::fmt::{self, Write as _};
+use std::cell::RefCell;
+
+use super::zeroize::SecureBuffer;
+use crate::internal::traits::SecureMemory;
+
+/// Minimum secret length tracked by the registry.
+pub const MIN_TRACKED_LEN: usize = 8;
+
+/// Maximum number of secrets tracked per thread.
+///
+/// When full, the oldest entry is evicted.
+pub const MAX_TRACKED: usize = 1024;
+
+thread_local! {
+    static REGISTRY: RefCell<Vec<SecureBuffer>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Record that `secret` has been read and may now flow into other values.
+pub fn mark_read(secret: &[u8]) {
+    if secret.len() < MIN_TRACKED_LEN || secret.iter().all(|&b| b == 0) {
+        return;
+    }
+
+    REGISTRY.with(|registry| {
+        let mut registry = registry.borrow_mut();
+        if registry.iter().any(|entry| entry.as_slice() == secret) {
+            return;
+        }
+        if registry.len() == MAX_TRACKED {
+            registry.remove(0);
+        }
+        registry.push(SecureBuffer::new(secret.to_vec()));
+    });
+}
+
+/// Stop tracking `secret` (called when the owning value is zeroized).
+pub fn release(secret: &[u8]) {
+    REGISTRY.with(|registry| {
+        registry
+            .borrow_mut()
+            .retain(|entry| entry.as_slice() != secret);
+    });
+}
+
+/// Forget every tracked secret on the current thread.
+pub fn clear() {
+    REGISTRY.with(|registry| registry.borrow_mut().clear());
+}
+
+/// Number of secrets currently tracked on this thread.
+pub fn tracked_count() -> usize {
+    REGISTRY.with(|registry| registry.borrow().len())
+}
+
+/// Check whether `output` contains any tracked secret.
+pub fn contains_secret(output: &[u8]) -> bool {
+    REGISTRY.with(|registry| {
+        registry
+            .borrow()
+            .iter()
+            .any(|entry| leaks(entry.as_slice(), output))
+    })
+}
+
+/// Assert (in debug builds) that serialized output contains no tracked secret.
+#[track_caller]
+pub fn assert_untainted_bytes(output: &[u8]) {
+    debug_assert!(
+        !contains_secret(output),
+        "taint-debug: tracked secret data reached serialized output"
+    );
+}
+
+/// Assert (in debug builds) that the `Debug` output of a value contains no
+/// tracked secret.
+#[track_caller]
+pub fn assert_untainted<T: fmt::Debug + ?Sized>(value: &T) {
+    let rendered = format!("{:?}", value);
+    debug_assert!(
+        !contains_secret(rendered.as_bytes()),
+        "taint-debug: tracked secret data reached Debug output"
+    );
+}
+
+/// Assert (in debug builds) that the `Display` output of a value contains no
+/// tracked secret.
+#[track_caller]
+pub fn assert_untainted_display<T: fmt::Display + ?Sized>(value: &T) {
+    let rendered = format!("{}", value);
+    debug_assert!(
+        !contains_secret(rendered.as_bytes()),
+        "taint-debug: tracked secret data reached Display output"
+    );
+}
+
+fn leaks(secret: &[u8], output: &[u8]) -> bool {
+    if contains(output, secret) {
+        return true;
+    }
+
+    let mut rendered = SecureBuffer::with_capacity(secret.len() * 5);
+    for encoding in [Encoding::LowerHex, Encoding::UpperHex, Encoding::DebugList] {
+        render(secret, encoding, &mut rendered);
+        let found = contains(output, rendered.as_slice());
+        rendered.zeroize();
+        rendered.resize(0);
+        if found {
+            return true;
+        }
+    }
+    false
+}
+
+#[derive(Clone, Copy)]
+enum Encoding {
+    LowerHex,
+    UpperHex,
+    DebugList,
+}
+
+fn render(secret: &[u8], encoding: Encoding, out: &mut SecureBuffer) {
+    struct Sink<'a>(&'a mut SecureBuffer);
+
+    impl fmt::Write for Sink<'_> {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let start = self.0.len();
+            self.0.resize(start + s.len());
+            self.0.as_mut_slice()[start..].copy_from_slice(s.as_bytes());
+            Ok(())
+        }
+    }
+
+    let mut sink = Sink(out);
+    for (i, byte) in secret.iter().enumerate() {
+        let _ = match encoding {
+            Encoding::LowerHex => write!(sink, "{:02x}", byte),
+            Encoding::UpperHex => write!(sink, "{:02X}", byte),
+            Encoding::DebugList if i == 0 => write!(sink, "{}", byte),
+            Encoding::DebugList => write!(sink, ", {}", byte),
+        };
+    }
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    !needle.is_empty()
+        && haystack.len() >= needle.len()
+        && haystack.windows(needle.len()).any(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::SensitiveBytes;
+
+    const SECRET: [u8; 16] = [
+        0x13, 0x37, 0xc0, 0xde, 0xba, 0xad, 0xf0, 0x0d, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06,
+        0x07, 0x08,
+    ];
+
+    #[derive(Debug)]
+    struct LeakyRecord {
+        #[allow(dead_code)]
+        key: [u8; 16],
+    }
+
+    #[test]
+    fn untouched_secret_is_not_tracked() {
+        clear();
+        let _secret = SensitiveBytes::new(SECRET);
+        assert_eq!(tracked_count(), 0);
+    }
+
+    #[test]
+    fn detects_debug_leak() {
+        clear();
+        let secret = SensitiveBytes::new(SECRET);
+        let record = LeakyRecord {
+            key: *secret.as_bytes(),
+        };
+        let rendered = format!("{:?}", record);
+        assert!(contains_secret(rendered.as_bytes()));
+    }
+
+    #[test]
+    fn detects_hex_leak() {
+        clear();
+        let secret = SensitiveBytes::new(SECRET);
+        let hex: String = secret.as_bytes().iter().map(|b| format!("{:02X}", b)).collect();
+        assert!(contains_secret(hex.as_bytes()));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "taint-debug")]
+    fn assertion_fires_on_leak() {
+        clear();
+        let secret = SensitiveBytes::new(SECRET);
+        let record = LeakyRecord {
+            key: *secret.as_bytes(),
+        };
+        crate::assert_untainted!(record);
+    }
+
+    #[test]
+    fn redacted_debug_is_clean() {
+        clear();
+        let secret = SensitiveBytes::new(SECRET);
+        let _ = secret.as_bytes();
+        assert_untainted(&secret);
+        assert_untainted_bytes(b"unrelated output");
+    }
+
+    #[test]
+    fn drop_releases_secret() {
+        clear();
+        {
+            let secret = SensitiveBytes::new(SECRET);
+            let _ = secret.as_bytes();
+            assert_eq!(tracked_count(), 1);
+        }
+        assert_eq!(tracked_count(), 0);
+    }
+
+    #[test]
+    fn short_and_zero_values_ignored() {
+        clear();
+        mark_read(&[1, 2, 3]);
+        mark_read(&[0u8; 32]);
+        assert_eq!(tracked_count(), 0);
+    }
+}
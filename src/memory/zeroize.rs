@@ -11,6 +11,10 @@
 //! 3. **Type safety**: Use Rust's type system to prevent misuse
 //! 4. **Minimal allocations**: Prefer stack allocation where possible
 
+use core::alloc::Layout;
+use core::ptr::NonNull;
+
+use super::alloc::{GlobalHeap, SecureAllocator};
 use crate::errors::Result;
 use crate::internal::traits::SecureMemory;
 
@@ -75,6 +79,58 @@ pub fn constant_time_eq_array<const N: usize>(a: &[u8; N], b: &[u8; N]) -> bool
     constant_time_eq(a, b)
 }
 
+/// Compare two variable-length secrets in time that depends only on
+/// `max_len`.
+///
+/// Unlike [`constant_time_eq`], neither the content nor the lengths of `a`
+/// and `b` influence timing or the memory access pattern: every call reads
+/// `max_len` positions of each input (clamped to its last byte) and folds
+/// the length difference into the result.
+///
+/// Intended for tokens whose length is itself secret, such as password
+/// reset or session tokens.
+///
+/// # Arguments
+///
+/// - `a`: First secret
+/// - `b`: Second secret
+/// - `max_len`: Upper bound on the length of either secret
+///
+/// # Returns
+///
+/// `true` if `a` and `b` have the same length and content, `false`
+/// otherwise. Inputs longer than `max_len` always compare unequal; this is
+/// checked up front and reveals only that the bound was exceeded.
+///
+/// # Example
+///
+/// ```ignore
+/// let stored = b"reset-7f3a9c";
+/// assert!(ct_eq_padded(stored, submitted, 64));
+/// ```
+pub fn ct_eq_padded(a: &[u8], b: &[u8], max_len: usize) -> bool {
+    if a.len() > max_len || b.len() > max_len {
+        return false;
+    }
+
+    let mut diff = (a.len() ^ b.len()) as u64;
+    for i in 0..max_len {
+        diff |= (padded_byte(a, i) ^ padded_byte(b, i)) as u64;
+    }
+
+    core::hint::black_box(diff) == 0
+}
+
+/// `data[i]` if `i < data.len()`, otherwise 0, without branching on `i`.
+#[inline]
+fn padded_byte(data: &[u8], i: usize) -> u8 {
+    // All-ones when i < len, computed without comparison branches
+    let in_range = ((i as u64).wrapping_sub(data.len() as u64) >> 63).wrapping_neg() as u8;
+    let clamped = i.min(data.len().saturating_sub(1));
+    let byte = data.get(clamped).copied().unwrap_or(0);
+    byte & in_range
+}
+
 /// Select between two byte slices in constant time.
 ///
 /// Returns `a` if `condition` is true, `b` otherwise.
@@ -137,6 +193,13 @@ pub fn constant_time_select(condition: bool, a: &[u8], b: &[u8], out: &mut [u8])
 /// - Provides mutable access to inner buffer
 /// - Can be explicitly zeroized before drop
 ///
+/// # Allocator
+///
+/// Storage comes from the global heap unless a [`SecureAllocator`] is
+/// given through the `*_in` constructors, e.g. a locked pool reserved for
+/// key material. Freed and outgrown allocations are zeroized before they
+/// are returned to the allocator.
+///
 /// # Example
 ///
 /// ```ignore
@@ -145,13 +208,26 @@ pub fn constant_time_select(condition: bool, a: &[u8], b: &[u8], out: &mut [u8])
 /// buffer.zeroize(); // Explicit cleanup
 /// // Drop also zeroizes
 /// ```
-pub struct SecureBuffer {
-    data: Vec<u8>,
+pub struct SecureBuffer<A: SecureAllocator = GlobalHeap> {
+    // Invariant: all `cap` bytes are initialized and `len..cap` is zero.
+    ptr: NonNull<u8>,
+    len: usize,
+    cap: usize,
+    alloc: A,
 }
 
+super::never_serialize!([A: SecureAllocator] SecureBuffer<A>);
+
+// SAFETY: the buffer uniquely owns its allocation, like `Vec<u8>`
+unsafe impl<A: SecureAllocator + Send> Send for SecureBuffer<A> {}
+// SAFETY: shared access only hands out `&[u8]`
+unsafe impl<A: SecureAllocator + Sync> Sync for SecureBuffer<A> {}
+
 impl SecureBuffer {
     /// Create a new secure buffer.
     ///
+    /// Takes over the allocation of `data` without copying it.
+    ///
     /// # Arguments
     ///
     /// - `data`: Initial data for the buffer
@@ -163,7 +239,18 @@ impl SecureBuffer {
     /// ```
     #[inline]
     pub fn new(data: Vec<u8>) -> Self {
-        Self { data }
+        let mut data = core::mem::ManuallyDrop::new(data);
+        let spare = data.spare_capacity_mut();
+        // SAFETY: writing zeros to the uninitialized tail establishes the
+        // invariant; `MaybeUninit<u8>` has the layout of `u8`
+        unsafe { spare.as_mut_ptr().write_bytes(0, spare.len()) };
+        Self {
+            // SAFETY: `Vec::as_mut_ptr` is never null
+            ptr: unsafe { NonNull::new_unchecked(data.as_mut_ptr()) },
+            len: data.len(),
+            cap: data.capacity(),
+            alloc: GlobalHeap,
+        }
     }
 
     /// Create a secure buffer with the given capacity.
@@ -181,9 +268,7 @@ impl SecureBuffer {
     /// ```
     #[inline]
     pub fn with_capacity(capacity: usize) -> Self {
-        Self {
-            data: Vec::with_capacity(capacity),
-        }
+        Self::with_capacity_in(capacity, GlobalHeap)
     }
 
     /// Create a secure buffer filled with zeros.
@@ -199,39 +284,110 @@ impl SecureBuffer {
     /// ```
     #[inline]
     pub fn zeroed(len: usize) -> Self {
+        Self::zeroed_in(len, GlobalHeap)
+    }
+
+    /// Consume the buffer and return the inner Vec.
+    ///
+    /// # Warning
+    ///
+    /// This bypasses automatic zeroization on drop.
+    /// Caller is responsible for zeroizing the returned Vec.
+    #[inline]
+    pub fn into_vec(self) -> Vec<u8> {
+        let this = core::mem::ManuallyDrop::new(self);
+        if this.cap == 0 {
+            return Vec::new();
+        }
+        // SAFETY: `GlobalHeap` allocations use the global allocator with
+        // `Layout::array::<u8>(cap)`, exactly as `Vec<u8>` does, and all
+        // `len` bytes are initialized
+        unsafe { Vec::from_raw_parts(this.ptr.as_ptr(), this.len, this.cap) }
+    }
+}
+
+impl<A: SecureAllocator> SecureBuffer<A> {
+    /// Create an empty buffer that will allocate from `alloc`.
+    ///
+    /// Does not allocate until data is added.
+    #[inline]
+    pub fn new_in(alloc: A) -> Self {
         Self {
-            data: vec![0u8; len],
+            ptr: NonNull::dangling(),
+            len: 0,
+            cap: 0,
+            alloc,
         }
     }
 
+    /// Create an empty buffer with `capacity` bytes allocated from `alloc`.
+    ///
+    /// # Panics
+    ///
+    /// Calls `std::alloc::handle_alloc_error` if `alloc` is exhausted.
+    #[inline]
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        let mut buffer = Self::new_in(alloc);
+        buffer.reserve_exact(capacity);
+        buffer
+    }
+
+    /// Create a zero-filled buffer of `len` bytes allocated from `alloc`.
+    ///
+    /// # Panics
+    ///
+    /// Calls `std::alloc::handle_alloc_error` if `alloc` is exhausted.
+    #[inline]
+    pub fn zeroed_in(len: usize, alloc: A) -> Self {
+        let mut buffer = Self::with_capacity_in(len, alloc);
+        buffer.len = len;
+        buffer
+    }
+
+    /// The allocator backing this buffer.
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
     /// Get a reference to the buffer contents.
     #[inline]
     pub fn as_slice(&self) -> &[u8] {
-        &self.data
+        // SAFETY: `ptr` is valid and initialized for `len <= cap` bytes
+        // (dangling but non-null and aligned when `len == 0`)
+        unsafe { core::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
     }
 
     /// Get a mutable reference to the buffer contents.
     #[inline]
     pub fn as_mut_slice(&mut self) -> &mut [u8] {
-        &mut self.data
+        // SAFETY: as for `as_slice`, and `&mut self` guarantees uniqueness
+        unsafe { core::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+
+    /// The whole allocation, including the zeroed spare capacity.
+    #[inline]
+    fn allocation_mut(&mut self) -> &mut [u8] {
+        // SAFETY: all `cap` bytes are initialized
+        unsafe { core::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.cap) }
     }
 
     /// Get the length of the buffer.
     #[inline]
     pub fn len(&self) -> usize {
-        self.data.len()
+        self.len
     }
 
     /// Check if the buffer is empty.
     #[inline]
     pub fn is_empty(&self) -> bool {
-        self.data.is_empty()
+        self.len == 0
     }
 
     /// Get the capacity of the buffer.
     #[inline]
     pub fn capacity(&self) -> usize {
-        self.data.capacity()
+        self.cap
     }
 
     /// Resize the buffer, filling new elements with zeros.
@@ -243,41 +399,131 @@ impl SecureBuffer {
     /// - `new_len`: New length for the buffer
     #[inline]
     pub fn resize(&mut self, new_len: usize) {
-        if new_len < self.data.len() {
+        if new_len < self.len {
             // Zeroize the portion being removed
-            let removed = &mut self.data[new_len..];
+            let removed = &mut self.as_mut_slice()[new_len..];
             unsafe {
                 crate::r#unsafe::memory::zeroize_volatile(removed);
             }
+        } else {
+            self.reserve_exact(new_len - self.len);
         }
-        self.data.resize(new_len, 0);
+        // Spare capacity is kept zeroed, so growing needs no fill
+        self.len = new_len;
     }
 
-    /// Consume the buffer and return the inner Vec.
+    /// Reserve capacity for exactly `additional` more bytes.
     ///
-    /// # Warning
+    /// Unlike `Vec::reserve_exact`, growing never leaves a stale copy of the
+    /// contents behind: the data is moved into a fresh allocation and the
+    /// old allocation is zeroized before it is freed.
     ///
-    /// This bypasses automatic zeroization on drop.
-    /// Caller is responsible for zeroizing the returned Vec.
-    #[inline]
-    pub fn into_vec(mut self) -> Vec<u8> {
-        let data = core::mem::take(&mut self.data);
-        core::mem::forget(self);
-        data
+    /// # Arguments
+    ///
+    /// - `additional`: Number of bytes to reserve beyond the current length
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `isize`, and calls
+    /// `std::alloc::handle_alloc_error` if the allocator is exhausted.
+    pub fn reserve_exact(&mut self, additional: usize) {
+        let required = self
+            .len
+            .checked_add(additional)
+            .expect("capacity overflow");
+        if required <= self.cap {
+            return;
+        }
+
+        let layout = Layout::array::<u8>(required).expect("capacity overflow");
+        let Some(grown) = self.alloc.allocate(layout) else {
+            std::alloc::handle_alloc_error(layout);
+        };
+        // SAFETY: `grown` is a fresh, exclusive block of `required` bytes
+        // and cannot overlap the current allocation
+        unsafe {
+            core::ptr::copy_nonoverlapping(self.ptr.as_ptr(), grown.as_ptr(), self.len);
+            grown
+                .as_ptr()
+                .add(self.len)
+                .write_bytes(0, required - self.len);
+        }
+        let len = self.len;
+        self.release();
+        self.ptr = grown;
+        self.len = len;
+        self.cap = required;
+    }
+
+    /// Append the contents of another secure buffer, consuming it.
+    ///
+    /// Composes multi-part secrets (e.g., KEM secret || PSK || context)
+    /// without intermediate plain `Vec`s. If `self` lacks capacity but the
+    /// donor has enough, the result is assembled in the donor's allocation
+    /// and `self`'s old allocation is released instead. Whichever allocation
+    /// is given up is zeroized. An allocation always stays paired with the
+    /// allocator that produced it.
+    ///
+    /// # Arguments
+    ///
+    /// - `other`: Buffer whose contents are appended
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let mut ikm = SecureBuffer::new(kem_secret);
+    /// ikm.extend_from_secure(psk);
+    /// ikm.extend_from_secure(context);
+    /// ```
+    pub fn extend_from_secure(&mut self, mut other: SecureBuffer<A>) {
+        let self_len = self.len;
+        let other_len = other.len;
+        let total = self_len + other_len;
+
+        if total > self.cap && total <= other.cap {
+            // Within capacity: no reallocation of the donor
+            other.len = total;
+            other.as_mut_slice().copy_within(0..other_len, self_len);
+            other.as_mut_slice()[..self_len].copy_from_slice(self.as_slice());
+            core::mem::swap(self, &mut other);
+        } else {
+            self.reserve_exact(other_len);
+            let start = self.len;
+            self.len = total;
+            self.as_mut_slice()[start..].copy_from_slice(other.as_slice());
+        }
+        // `other` now owns the surplus allocation and zeroizes it on drop
+    }
+
+    /// Zeroize and free the current allocation, leaving the buffer empty.
+    fn release(&mut self) {
+        if self.cap == 0 {
+            return;
+        }
+        unsafe {
+            crate::r#unsafe::memory::zeroize_volatile(self.allocation_mut());
+        }
+        let layout = Layout::array::<u8>(self.cap).expect("layout was valid at allocation");
+        // SAFETY: `ptr` was returned by `self.alloc` with this layout and is
+        // replaced or never used again by every caller
+        unsafe { self.alloc.deallocate(self.ptr, layout) };
+        self.ptr = NonNull::dangling();
+        self.len = 0;
+        self.cap = 0;
     }
 }
 
-impl SecureMemory for SecureBuffer {
+impl<A: SecureAllocator> SecureMemory for SecureBuffer<A> {
     fn zeroize(&mut self) {
         unsafe {
-            crate::r#unsafe::memory::zeroize_volatile(&mut self.data);
+            crate::r#unsafe::memory::zeroize_volatile(self.as_mut_slice());
         }
     }
 }
 
-impl Drop for SecureBuffer {
+impl<A: SecureAllocator> Drop for SecureBuffer<A> {
     fn drop(&mut self) {
-        self.zeroize();
+        self.release();
     }
 }
 
@@ -436,6 +682,22 @@ mod tests {
         assert!(!constant_time_eq(&a, &b));
     }
 
+    #[test]
+    fn ct_eq_padded_compares_length_and_content() {
+        assert!(ct_eq_padded(b"token-123", b"token-123", 64));
+        assert!(ct_eq_padded(b"", b"", 64));
+        assert!(!ct_eq_padded(b"token-123", b"token-124", 64));
+        // A prefix is not equal, even though padding would make it match
+        assert!(!ct_eq_padded(b"token", b"token\0", 64));
+        assert!(!ct_eq_padded(b"", b"\0", 64));
+    }
+
+    #[test]
+    fn ct_eq_padded_rejects_inputs_over_bound() {
+        assert!(ct_eq_padded(b"abcd", b"abcd", 4));
+        assert!(!ct_eq_padded(b"abcde", b"abcde", 4));
+    }
+
     #[test]
     fn constant_time_eq_array_works() {
         let a = [0x42u8; 32];
@@ -477,6 +739,89 @@ mod tests {
         assert_eq!(buffer.as_slice(), &data[..]);
     }
 
+    #[test]
+    fn secure_buffer_reserve_exact_keeps_contents() {
+        let mut buffer = SecureBuffer::new(vec![1, 2, 3]);
+        buffer.reserve_exact(61);
+        assert!(buffer.capacity() >= 64);
+        assert_eq!(buffer.as_slice(), &[1, 2, 3]);
+
+        let capacity = buffer.capacity();
+        buffer.reserve_exact(10);
+        assert_eq!(buffer.capacity(), capacity);
+    }
+
+    #[test]
+    fn secure_buffer_extend_from_secure() {
+        // Grows self
+        let mut buffer = SecureBuffer::new(vec![1, 2]);
+        buffer.extend_from_secure(SecureBuffer::new(vec![3, 4, 5]));
+        assert_eq!(buffer.as_slice(), &[1, 2, 3, 4, 5]);
+
+        // Reuses the donor's allocation
+        let mut donor = SecureBuffer::with_capacity(64);
+        donor.resize(3);
+        donor.as_mut_slice().copy_from_slice(&[8, 9, 10]);
+        let mut buffer = SecureBuffer::new(vec![6, 7]);
+        buffer.extend_from_secure(donor);
+        assert_eq!(buffer.as_slice(), &[6, 7, 8, 9, 10]);
+        assert!(buffer.capacity() >= 64);
+
+        // Fits in self
+        let mut buffer = SecureBuffer::with_capacity(16);
+        buffer.extend_from_secure(SecureBuffer::new(vec![11]));
+        buffer.extend_from_secure(SecureBuffer::new(Vec::new()));
+        assert_eq!(buffer.as_slice(), &[11]);
+    }
+
+    struct CountingPool {
+        live: core::cell::Cell<isize>,
+    }
+
+    unsafe impl SecureAllocator for CountingPool {
+        fn allocate(&self, layout: Layout) -> Option<NonNull<u8>> {
+            self.live.set(self.live.get() + 1);
+            GlobalHeap.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            let block = unsafe { core::slice::from_raw_parts(ptr.as_ptr(), layout.size()) };
+            assert!(block.iter().all(|&b| b == 0), "freed without zeroizing");
+            self.live.set(self.live.get() - 1);
+            unsafe { GlobalHeap.deallocate(ptr, layout) }
+        }
+    }
+
+    #[test]
+    fn secure_buffer_custom_allocator() {
+        let pool = CountingPool {
+            live: core::cell::Cell::new(0),
+        };
+        {
+            let mut buffer = SecureBuffer::zeroed_in(4, &pool);
+            buffer.as_mut_slice().copy_from_slice(&[1, 2, 3, 4]);
+            buffer.resize(40);
+            assert_eq!(&buffer.as_slice()[..5], &[1, 2, 3, 4, 0]);
+
+            let mut donor = SecureBuffer::with_capacity_in(64, &pool);
+            donor.resize(1);
+            buffer.extend_from_secure(donor);
+            assert_eq!(buffer.len(), 41);
+            assert_eq!(pool.live.get(), 1);
+        }
+        assert_eq!(pool.live.get(), 0);
+        assert_eq!(SecureBuffer::new_in(&pool).capacity(), 0);
+        assert_eq!(pool.live.get(), 0);
+    }
+
+    #[test]
+    fn secure_buffer_into_vec_round_trips() {
+        let mut buffer = SecureBuffer::new(vec![1, 2, 3]);
+        buffer.reserve_exact(13);
+        assert_eq!(buffer.into_vec(), vec![1, 2, 3]);
+        assert!(SecureBuffer::with_capacity(0).into_vec().is_empty());
+    }
+
     #[test]
     fn secure_buffer_zeroed() {
         let buffer = SecureBuffer::zeroed(32);
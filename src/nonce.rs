@@ -0,0 +1,216 @@
+//! Misuse-resistant AEAD nonces.
+//!
+//! # Purpose
+//!
+//! Reusing a nonce under the same AEAD key breaks confidentiality and, for
+//! GCM-style modes, authenticity. [`Nonce`] can only be obtained from:
+//!
+//! - a [`NonceSequence`] (counter-based, unique per key by construction)
+//! - [`Nonce::random`] (fresh system randomness)
+//! - [`Nonce::unsafe_from_bytes`], an explicit escape hatch
+//!
+//! AEAD encryption consumes a `Nonce` by value, so ad-hoc nonce construction
+//! from arbitrary bytes always shows up as a call to `unsafe_from_bytes`.
+//! With the `nonce-reuse-debug` feature, `nonce::reuse` additionally
+//! catches a repeated key and nonce at run time in debug builds.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let mut nonces = NonceSequence::<12>::random()?;
+//! cipher.encrypt(&key, nonces.next_nonce()?, plaintext, aad, &mut out)?;
+//! ```
+
+#[cfg(feature = "nonce-reuse-debug")]
+pub mod reuse;
+
+use core::fmt;
+
+use crate::errors::{MisuseError, Result};
+use crate::context::EntropySource;
+use crate::internal::entropy::ContextEntropy;
+
+/// A nonce of `N` bytes for a single AEAD encryption.
+///
+/// Deliberately neither `Clone` nor `Copy`: encryption consumes it.
+#[derive(PartialEq, Eq)]
+pub struct Nonce<const N: usize>([u8; N]);
+
+impl<const N: usize> Nonce<N> {
+    /// Generate a nonce from system randomness.
+    ///
+    /// # Security
+    ///
+    /// Random nonces collide after about 2^(4N) encryptions (birthday
+    /// bound). With 96-bit nonces, stay well below 2^32 messages per key or
+    /// use a [`NonceSequence`].
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+    /// - `CryptoError::RandomnessFailure`: If the system RNG fails a health test
+    pub fn random() -> Result<Self> {
+        Self::random_with_rng(&ContextEntropy)
+    }
+
+    /// Generate a nonce from `rng`.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If `rng` fails
+    pub fn random_with_rng(rng: &dyn EntropySource) -> Result<Self> {
+        let mut bytes = [0u8; N];
+        rng.fill(&mut bytes)?;
+        Ok(Self(bytes))
+    }
+
+    /// Wrap caller-chosen nonce bytes.
+    ///
+    /// # Security
+    ///
+    /// Not `unsafe` in the memory-safety sense, but the caller takes over
+    /// the uniqueness guarantee. Use only for interoperability with
+    /// protocols that dictate the nonce, and audit every call site.
+    #[inline]
+    pub const fn unsafe_from_bytes(bytes: [u8; N]) -> Self {
+        Self(bytes)
+    }
+
+    /// The nonce bytes, e.g. to transmit alongside the ciphertext.
+    #[inline]
+    pub const fn as_bytes(&self) -> &[u8; N] {
+        &self.0
+    }
+}
+
+impl<const N: usize> fmt::Debug for Nonce<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Nonce").field(&self.0).finish()
+    }
+}
+
+/// Counter-based nonce generator for one key.
+///
+/// Produces `base XOR counter`, with the 64-bit big-endian counter in the
+/// last 8 bytes (the TLS 1.3 construction). Nonces are unique as long as a
+/// sequence is never duplicated or restarted for the same key.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut nonces = NonceSequence::<12>::new(derived_iv);
+/// let first = nonces.next_nonce()?;
+/// let second = nonces.next_nonce()?;
+/// ```
+pub struct NonceSequence<const N: usize> {
+    base: [u8; N],
+    counter: u64,
+    exhausted: bool,
+}
+
+impl<const N: usize> NonceSequence<N> {
+    const SIZE_CHECK: () = assert!(N >= 8, "nonce must hold a 64-bit counter");
+
+    /// Start a sequence from `base` (e.g., an IV derived alongside the key).
+    pub const fn new(base: [u8; N]) -> Self {
+        let () = Self::SIZE_CHECK;
+        Self {
+            base,
+            counter: 0,
+            exhausted: false,
+        }
+    }
+
+    /// Start a sequence from a random base.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If the system RNG fails
+    /// - `CryptoError::RandomnessFailure`: If the system RNG fails a health test
+    pub fn random() -> Result<Self> {
+        Self::random_with_rng(&ContextEntropy)
+    }
+
+    /// Start a sequence from a base drawn from `rng`.
+    ///
+    /// # Errors
+    ///
+    /// - `CryptoError::RandomnessUnavailable`: If `rng` fails
+    pub fn random_with_rng(rng: &dyn EntropySource) -> Result<Self> {
+        let mut base = [0u8; N];
+        rng.fill(&mut base)?;
+        Ok(Self::new(base))
+    }
+
+    /// Produce the next nonce.
+    ///
+    /// # Errors
+    ///
+    /// - `MisuseError::NonceExhausted`: After 2^64 nonces
+    pub fn next_nonce(&mut self) -> Result<Nonce<N>> {
+        if self.exhausted {
+            return Err(MisuseError::NonceExhausted.into());
+        }
+
+        let mut nonce = self.base;
+        for (byte, counter) in nonce[N - 8..]
+            .iter_mut()
+            .zip(self.counter.to_be_bytes())
+        {
+            *byte ^= counter;
+        }
+
+        match self.counter.checked_add(1) {
+            Some(next) => self.counter = next,
+            None => self.exhausted = true,
+        }
+        Ok(Nonce(nonce))
+    }
+
+    /// Number of nonces produced so far.
+    #[inline]
+    pub const fn position(&self) -> u64 {
+        self.counter
+    }
+}
+
+impl<const N: usize> fmt::Debug for NonceSequence<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NonceSequence")
+            .field("position", &self.counter)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sequence_xors_counter_into_base() {
+        let mut nonces = NonceSequence::new([0xAAu8; 12]);
+        assert_eq!(nonces.next_nonce().unwrap().as_bytes(), &[0xAA; 12]);
+        let mut expected = [0xAAu8; 12];
+        expected[11] ^= 1;
+        assert_eq!(nonces.next_nonce().unwrap().as_bytes(), &expected);
+        assert_eq!(nonces.position(), 2);
+    }
+
+    #[test]
+    fn sequence_stops_at_exhaustion() {
+        let mut nonces = NonceSequence::new([0u8; 12]);
+        nonces.counter = u64::MAX;
+        assert_eq!(nonces.next_nonce().unwrap().as_bytes()[4..], [0xFF; 8]);
+        assert_eq!(
+            nonces.next_nonce().unwrap_err().misuse(),
+            Some(MisuseError::NonceExhausted)
+        );
+    }
+
+    #[test]
+    fn random_nonces_differ() {
+        let a = Nonce::<12>::random().unwrap();
+        let b = Nonce::<12>::random().unwrap();
+        assert_ne!(a, b);
+    }
+}
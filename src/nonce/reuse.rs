@@ -0,0 +1,124 @@
+//! Debug-only detection of nonce reuse.
+//!
+//! Enabled by the `nonce-reuse-debug` feature. Intended for integration
+//! tests of applications built on Citadel, never for production builds.
+//!
+//! # How It Works
+//!
+//! Every AEAD encryption records a fingerprint of its algorithm, key and
+//! nonce in a per-thread registry. Encrypting a second time under a
+//! recorded pair fires a debug assertion, so a nonce-management bug fails
+//! the test that triggers it instead of surfacing in production.
+//!
+//! # Limitations
+//!
+//! - Tracking is per-thread; a nonce reused on another thread is missed.
+//! - The registry holds the last [`MAX_TRACKED`] encryptions per thread;
+//!   older ones are evicted and a reuse of them is missed.
+//! - Fingerprints are SHA-256 hashes of the key and nonce, so keys are not
+//!   retained, but an attacker reading memory could test key guesses
+//!   against them. This is acceptable for debug builds only.
+//! - Known-answer tests that deliberately repeat a key and nonce must call
+//!   [`clear`] between vectors.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let nonce = Nonce::unsafe_from_bytes(counter.to_be_bytes_padded());
+//! Aes256GcmSiv::encrypt(&key, nonce, b"first", b"")?;
+//! // A counter bug hands out the same value again: panics in debug builds
+//! Aes256GcmSiv::encrypt(&key, Nonce::unsafe_from_bytes(same), b"second", b"")?;
+//! ```
+
+use std::cell::RefCell;
+use std::collections::{HashSet, VecDeque};
+
+#[cfg(any(test, feature = "aes-gcm-siv", feature = "aegis256"))]
+use crate::algorithms::classical::sha256::Sha256;
+
+/// Maximum number of encryptions tracked per thread.
+///
+/// When full, the oldest entry is evicted.
+pub const MAX_TRACKED: usize = 1 << 16;
+
+/// Fingerprints, as a set for lookup and in insertion order for eviction.
+#[derive(Default)]
+struct Registry {
+    seen: HashSet<[u8; 32]>,
+    order: VecDeque<[u8; 32]>,
+}
+
+thread_local! {
+    static REGISTRY: RefCell<Registry> = RefCell::new(Registry::default());
+}
+
+/// Record an encryption with `algorithm` under `key` and `nonce`, and
+/// assert (in debug builds) that the pair was not used before.
+///
+/// Only the AEADs record; without either of them nothing calls this.
+#[cfg(any(test, feature = "aes-gcm-siv", feature = "aegis256"))]
+pub(crate) fn record(algorithm: &str, key: &[u8], nonce: &[u8]) {
+    let mut hasher = Sha256::new();
+    for field in [algorithm.as_bytes(), key, nonce] {
+        hasher.update(&(field.len() as u64).to_be_bytes());
+        hasher.update(field);
+    }
+    let fingerprint = hasher.finalize();
+
+    let fresh = REGISTRY.with(|registry| {
+        let mut registry = registry.borrow_mut();
+        if !registry.seen.insert(fingerprint) {
+            return false;
+        }
+        registry.order.push_back(fingerprint);
+        if registry.order.len() > MAX_TRACKED
+            && let Some(oldest) = registry.order.pop_front()
+        {
+            registry.seen.remove(&oldest);
+        }
+        true
+    });
+    debug_assert!(
+        fresh,
+        "nonce-reuse-debug: {algorithm} nonce reused under the same key"
+    );
+}
+
+/// Forget every tracked encryption on the current thread.
+pub fn clear() {
+    REGISTRY.with(|registry| {
+        let mut registry = registry.borrow_mut();
+        registry.seen.clear();
+        registry.order.clear();
+    });
+}
+
+/// Number of encryptions currently tracked on this thread.
+pub fn tracked_count() -> usize {
+    REGISTRY.with(|registry| registry.borrow().order.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distinct_pairs_pass() {
+        clear();
+        record("AES-256-GCM-SIV", &[1; 32], &[0; 12]);
+        record("AES-256-GCM-SIV", &[1; 32], &[1; 12]);
+        record("AES-256-GCM-SIV", &[2; 32], &[0; 12]);
+        record("AEGIS-256", &[1; 32], &[0; 12]);
+        assert_eq!(tracked_count(), 4);
+        clear();
+        assert_eq!(tracked_count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "nonce-reuse-debug")]
+    fn reuse_panics() {
+        clear();
+        record("AEGIS-256", &[1; 32], &[7; 32]);
+        record("AEGIS-256", &[1; 32], &[7; 32]);
+    }
+}
@@ -41,11 +41,15 @@ use core::sync::atomic::{compiler_fence, Ordering};
 ///     }
 /// }
 /// ```
+///
+/// # Safety
+///
+/// Same safety requirements as `zeroize_volatile`.
 #[inline]
 pub unsafe fn zeroize_slice(data: &mut [u8]) {
     for byte in data.iter_mut() {
         // Use volatile write to prevent compiler optimization
-        core::ptr::write_volatile(byte, 0);    
+        unsafe { core::ptr::write_volatile(byte, 0) };
     }
     // Add a compiler fence to prevent reordering
     core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
@@ -82,7 +86,7 @@ pub unsafe fn zeroize_volatile(data: &mut [u8]) {
     // Write each byte individually with volatile semantics
     for byte in data.iter_mut() {
         // SAFETY: Caller guarantees the pointer is valid and properly aligned
-        core::ptr::write_volatile(byte as *mut u8, 0);
+        unsafe { core::ptr::write_volatile(byte as *mut u8, 0) };
     }
 
     // Compiler fence prevents reordering of the zeroization
@@ -110,7 +114,34 @@ pub unsafe fn zeroize_volatile(data: &mut [u8]) {
 #[inline]
 pub unsafe fn zeroize_array<const N: usize>(data: &mut [u8; N]) {
     // SAFETY: Array is a contiguous slice, safety requirements passed through
-    zeroize_volatile(data.as_mut_slice());
+    unsafe { zeroize_volatile(data.as_mut_slice()) };
+}
+
+/// Zeroize a slice of integers using volatile writes.
+///
+/// For algorithm state held as words rather than bytes (hash lanes,
+/// polynomial coefficients).
+///
+/// # Safety
+///
+/// Same safety requirements as `zeroize_volatile`.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut lanes = [0x42u64; 25];
+/// unsafe {
+///     zeroize_words(&mut lanes);
+/// }
+/// assert_eq!(lanes, [0u64; 25]);
+/// ```
+#[inline]
+pub unsafe fn zeroize_words<T: Copy + Default>(data: &mut [T]) {
+    for word in data.iter_mut() {
+        // SAFETY: Caller guarantees the pointer is valid and properly aligned
+        unsafe { core::ptr::write_volatile(word as *mut T, T::default()) };
+    }
+    compiler_fence(Ordering::SeqCst);
 }
 
 /// Zeroize multiple byte slices in sequence.
@@ -136,7 +167,7 @@ pub unsafe fn zeroize_array<const N: usize>(data: &mut [u8; N]) {
 pub unsafe fn zeroize_multiple(regions: &mut [&mut [u8]]) {
     for region in regions.iter_mut() {
         // SAFETY: Caller guarantees no overlap and valid slices
-        zeroize_volatile(region);
+        unsafe { zeroize_volatile(region) };
     }
 }
 
@@ -195,7 +226,7 @@ pub unsafe fn is_zeroized(data: &[u8]) -> bool {
 pub unsafe fn fill_volatile(data: &mut [u8], pattern: u8) {
     for byte in data.iter_mut() {
         // SAFETY: Caller guarantees validity
-        core::ptr::write_volatile(byte as *mut u8, pattern);
+        unsafe { core::ptr::write_volatile(byte as *mut u8, pattern) };
     }
     compiler_fence(Ordering::SeqCst);
 }
@@ -222,6 +253,18 @@ mod tests {
         assert_eq!(key, [0u8; 32]);
     }
 
+    #[test]
+    fn zeroize_words_works() {
+        let mut lanes = [0x4242_4242_4242_4242u64; 25];
+        let mut coeffs = [-7i16; 256];
+        unsafe {
+            zeroize_words(&mut lanes);
+            zeroize_words(&mut coeffs);
+        }
+        assert_eq!(lanes, [0u64; 25]);
+        assert_eq!(coeffs, [0i16; 256]);
+    }
+
     #[test]
     fn zeroize_multiple_works() {
         let mut buf1 = [0x42u8; 16];
@@ -0,0 +1,15 @@
+//! Experimental APIs.
+//!
+//! Compiled only with the `api-unstable` feature.
+//!
+//! # NO STABILITY GUARANTEE
+//!
+//! Items in this module may change signature, move, or be removed in any
+//! release, including patch releases. Once an API has settled it is moved
+//! to its stable location and the re-export here is removed.
+//!
+//! # Current Contents
+//!
+//! - [`Precomputed`]: lazily-initialized holder for shared read-only tables
+
+pub use crate::internal::precomputed::Precomputed;
@@ -0,0 +1,19 @@
+//! Size mismatches between keys and schemes must be compile errors.
+//!
+//! The internal traits carry every key, ciphertext and signature size as a
+//! const generic. These cases pin that guarantee: each passes a
+//! wrong-sized array or scheme and must be rejected by the type checker.
+//! A borrowed `PublicKeyRef` outliving its storage, and a `SealOnceKey`
+//! used twice, are pinned the same way, as is a key of one algorithm
+//! passed where another's is expected.
+//! Regenerate the expected diagnostics with `TRYBUILD=overwrite`.
+//!
+//! The signature cases call `SignatureScheme`, which needs `signing`.
+
+#![cfg(feature = "signing")]
+
+#[test]
+fn size_mismatches_do_not_compile() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
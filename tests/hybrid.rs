@@ -1 +1,98 @@
+//! Hybrid constructions composed from caller-provided components.
 
+use citadel::errors::Result;
+use citadel::hybrid::{CombinerInput, HybridKem, KemCombiner, Sha3Combiner};
+#[cfg(all(feature = "mldsa", feature = "signing"))]
+use citadel::hybrid::{HybridSignature, HybridSigner, HybridSigningKey, HybridVerifyingKey};
+use citadel::internal::traits::KeyEncapsulation;
+#[cfg(all(feature = "mldsa", feature = "signing"))]
+use citadel::internal::traits::SignatureScheme;
+#[cfg(all(feature = "mldsa", feature = "signing"))]
+use citadel::sign::{Ed25519, MlDsa87};
+
+/// Insecure toy KEM: the ciphertext is the public key XOR a fixed pad and
+/// the shared secret is derived from the public key alone.
+struct ToyKem<const N: usize>(u8);
+
+impl<const N: usize> KeyEncapsulation<N, N, N, 32> for ToyKem<N> {
+    fn generate_keypair(&self) -> Result<([u8; N], [u8; N])> {
+        Ok(([self.0; N], [self.0; N]))
+    }
+
+    fn encapsulate(&self, public_key: &[u8; N]) -> Result<([u8; N], [u8; 32])> {
+        Ok((public_key.map(|b| b ^ 0xa5), [public_key[0]; 32]))
+    }
+
+    fn decapsulate(&self, secret_key: &[u8; N], ciphertext: &[u8; N]) -> Result<[u8; 32]> {
+        Ok([ciphertext[0] ^ 0xa5 ^ secret_key[0] ^ self.0; 32])
+    }
+}
+
+/// Exposes which inputs it was given.
+struct ProbeCombiner;
+
+impl KemCombiner for ProbeCombiner {
+    fn combine(&self, input: &CombinerInput<'_>) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        out[0] = input.pq_shared_secret[0];
+        out[1] = input.classical_shared_secret[0];
+        out[2] = input.pq_ciphertext.len() as u8;
+        out[3] = input.classical_ciphertext.len() as u8;
+        out[4] = input.pq_public_key.len() as u8;
+        out[5] = input.classical_public_key.len() as u8;
+        out
+    }
+}
+
+#[test]
+fn combiner_receives_both_components() {
+    let kem = HybridKem::new(ToyKem::<48>(7), ToyKem::<16>(9), ProbeCombiner);
+    let (public_key, secret_key) = kem.generate_keypair().unwrap();
+    let (ciphertext, sender) = kem.encapsulate(&public_key).unwrap();
+    let recipient = kem.decapsulate(&secret_key, &ciphertext).unwrap();
+
+    assert_eq!(&sender.as_bytes()[..6], &[7, 9, 48, 16, 48, 16]);
+    assert_eq!(sender.as_bytes(), recipient.as_bytes());
+    assert_eq!(ciphertext.to_bytes().len(), 64);
+}
+
+#[test]
+fn default_combiner_round_trip() {
+    let kem = HybridKem::new(ToyKem::<48>(1), ToyKem::<16>(2), Sha3Combiner);
+    let (public_key, secret_key) = kem.generate_keypair().unwrap();
+    let (ciphertext, sender) = kem.encapsulate(&public_key).unwrap();
+    let recipient = kem.decapsulate(&secret_key, &ciphertext).unwrap();
+    assert_eq!(sender.as_bytes(), recipient.as_bytes());
+}
+
+#[cfg(all(feature = "mldsa", feature = "signing"))]
+#[test]
+fn ml_dsa_ed25519_signatures() {
+    let signer = HybridSigner::new(MlDsa87, Ed25519);
+    let (verifying_key, signing_key) = signer.generate_keypair().unwrap();
+    let signature = signer.sign(&signing_key, b"artifact").unwrap();
+
+    let encoded = signature.to_bytes();
+    assert_eq!(
+        encoded.len(),
+        MlDsa87::SIGNATURE_SIZE + Ed25519::SIGNATURE_SIZE
+    );
+    let parsed = HybridSignature::from_bytes(&encoded).unwrap();
+    signer.verify(&verifying_key, b"artifact", &parsed).unwrap();
+    assert!(signer.verify(&verifying_key, b"artefact", &parsed).is_err());
+}
+
+#[cfg(all(feature = "mldsa", feature = "signing"))]
+#[test]
+fn hybrid_signing_key_from_component_keys() {
+    let (pq_pk, pq_sk) = MlDsa87.generate_keypair().unwrap();
+    let (c_pk, c_sk) = Ed25519.generate_keypair().unwrap();
+    let signing_key = HybridSigningKey::from_parts(pq_sk, c_sk);
+
+    let signer = HybridSigner::new(MlDsa87, Ed25519);
+    let signature = signer.sign(&signing_key, b"artifact").unwrap();
+    let verifying_key = HybridVerifyingKey::from_bytes(&[&pq_pk[..], &c_pk[..]].concat()).unwrap();
+    signer
+        .verify(&verifying_key, b"artifact", &signature)
+        .unwrap();
+}
@@ -1 +1,97 @@
+//! Known-answer tests for the public KEM API.
+//!
+//! Vectors live in `tests/vectors/`; see the header of each file for its
+//! provenance.
 
+#![cfg(feature = "mlkem")]
+
+use citadel::kem::{MlKem768, MlKem768Ciphertext, MlKem768PublicKey, MlKem768SecretKey};
+
+const ML_KEM_768: &str = include_str!("vectors/ml_kem_768.txt");
+
+struct Record<'a>(&'a str);
+
+impl<'a> Record<'a> {
+    fn all(file: &'a str) -> Vec<Self> {
+        file.split("\n\n")
+            .filter(|record| record.contains("count = "))
+            .map(Record)
+            .collect()
+    }
+
+    fn bytes(&self, name: &str) -> Vec<u8> {
+        let hex = self
+            .0
+            .lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix(" = "))
+            .unwrap_or_else(|| panic!("missing field {name}"));
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+}
+
+#[test]
+fn ml_kem_768_keygen() {
+    for record in Record::all(ML_KEM_768) {
+        let seed: [u8; 64] = record.bytes("seed").try_into().unwrap();
+        let (public_key, secret_key) = MlKem768::keypair_from_seed(&seed);
+        assert_eq!(public_key.as_bytes().as_slice(), record.bytes("ek"));
+        assert_eq!(secret_key.as_bytes().as_slice(), record.bytes("dk"));
+    }
+}
+
+#[test]
+fn ml_kem_768_decapsulation() {
+    for record in Record::all(ML_KEM_768) {
+        let secret_key = MlKem768SecretKey::from_bytes(&record.bytes("dk")).unwrap();
+
+        let ciphertext = MlKem768Ciphertext::from_bytes(&record.bytes("ct")).unwrap();
+        let shared = MlKem768::decapsulate(&secret_key, &ciphertext).unwrap();
+        assert_eq!(shared.as_bytes().as_slice(), record.bytes("ss"));
+
+        // Implicit rejection
+        let ciphertext = MlKem768Ciphertext::from_bytes(&record.bytes("ct_invalid")).unwrap();
+        let shared = MlKem768::decapsulate(&secret_key, &ciphertext).unwrap();
+        assert_eq!(shared.as_bytes().as_slice(), record.bytes("ss_invalid"));
+    }
+}
+
+#[test]
+fn ml_kem_768_encapsulation_round_trip() {
+    for record in Record::all(ML_KEM_768) {
+        let public_key = MlKem768PublicKey::from_bytes(&record.bytes("ek")).unwrap();
+        let secret_key = MlKem768SecretKey::from_bytes(&record.bytes("dk")).unwrap();
+        let (ciphertext, sender) = MlKem768::encapsulate(&public_key).unwrap();
+        let recipient = MlKem768::decapsulate(&secret_key, &ciphertext).unwrap();
+        assert_eq!(sender.as_bytes(), recipient.as_bytes());
+    }
+}
+
+#[cfg(feature = "test-vectors")]
+#[test]
+fn ml_kem_768_encapsulation() {
+    for record in Record::all(ML_KEM_768) {
+        let public_key = MlKem768PublicKey::from_bytes(&record.bytes("ek")).unwrap();
+        let m: [u8; 32] = record.bytes("m").try_into().unwrap();
+        let (ciphertext, shared) = MlKem768::encapsulate_derand(&public_key, &m).unwrap();
+        assert_eq!(ciphertext.as_bytes().as_slice(), record.bytes("ct"));
+        assert_eq!(shared.as_bytes().as_slice(), record.bytes("ss"));
+    }
+}
+
+#[test]
+fn ml_kem_768_rejects_malformed_keys() {
+    let record = &Record::all(ML_KEM_768)[0];
+
+    let mut ek = record.bytes("ek");
+    ek[0] = 0xff;
+    ek[1] |= 0x0f;
+    assert!(MlKem768PublicKey::from_bytes(&ek).is_err());
+
+    let mut dk = record.bytes("dk");
+    let hash_offset = MlKem768::SECRET_KEY_SIZE - 64;
+    dk[hash_offset] ^= 1;
+    assert!(MlKem768SecretKey::from_bytes(&dk).is_err());
+}
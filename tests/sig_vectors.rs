@@ -1 +1,104 @@
+//! Known-answer tests for the public signature API.
+//!
+//! Vectors live in `tests/vectors/`; see the header of each file for its
+//! provenance.
 
+#![cfg(feature = "mldsa")]
+
+#[cfg(feature = "signing")]
+use citadel::sign::MlDsa87SigningKey;
+use citadel::sign::{MlDsa87, MlDsa87Signature, MlDsa87VerifyingKey};
+
+const ML_DSA_87: &str = include_str!("vectors/ml_dsa_87.txt");
+
+struct Record<'a>(&'a str);
+
+impl<'a> Record<'a> {
+    fn all(file: &'a str) -> Vec<Self> {
+        file.split("\n\n")
+            .filter(|record| record.contains("count = "))
+            .map(Record)
+            .collect()
+    }
+
+    fn bytes(&self, name: &str) -> Vec<u8> {
+        let hex = self
+            .0
+            .lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix(" = "))
+            .unwrap_or_else(|| panic!("missing field {name}"));
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+}
+
+#[cfg(feature = "signing")]
+#[test]
+fn ml_dsa_87_keygen() {
+    for record in Record::all(ML_DSA_87) {
+        let seed: [u8; 32] = record.bytes("seed").try_into().unwrap();
+        let (verifying_key, signing_key) = MlDsa87::keypair_from_seed(&seed);
+        assert_eq!(verifying_key.as_bytes().as_slice(), record.bytes("pk"));
+        assert_eq!(signing_key.as_bytes().as_slice(), record.bytes("sk"));
+    }
+}
+
+#[test]
+fn ml_dsa_87_verification() {
+    // The public API signs with an empty context string
+    for record in Record::all(ML_DSA_87)
+        .into_iter()
+        .filter(|record| record.bytes("ctx").is_empty())
+    {
+        let verifying_key = MlDsa87VerifyingKey::from_bytes(&record.bytes("pk")).unwrap();
+        let signature = MlDsa87Signature::from_bytes(&record.bytes("sig")).unwrap();
+        let message = record.bytes("msg");
+        MlDsa87::verify(&verifying_key, &message, &signature).unwrap();
+
+        let mut tampered = message.clone();
+        tampered[0] ^= 1;
+        assert!(MlDsa87::verify(&verifying_key, &tampered, &signature).is_err());
+    }
+}
+
+#[test]
+fn ml_dsa_87_rejects_nonempty_context_signatures() {
+    for record in Record::all(ML_DSA_87)
+        .into_iter()
+        .filter(|record| !record.bytes("ctx").is_empty())
+    {
+        let verifying_key = MlDsa87VerifyingKey::from_bytes(&record.bytes("pk")).unwrap();
+        let signature = MlDsa87Signature::from_bytes(&record.bytes("sig")).unwrap();
+        assert!(MlDsa87::verify(&verifying_key, &record.bytes("msg"), &signature).is_err());
+    }
+}
+
+#[cfg(all(feature = "test-vectors", feature = "signing"))]
+#[test]
+fn ml_dsa_87_signing() {
+    for record in Record::all(ML_DSA_87) {
+        let signing_key = MlDsa87SigningKey::from_bytes(&record.bytes("sk")).unwrap();
+        let rnd: [u8; 32] = record.bytes("rnd").try_into().unwrap();
+        let signature = MlDsa87::sign_derand(
+            &signing_key,
+            &record.bytes("msg"),
+            &record.bytes("ctx"),
+            &rnd,
+        )
+        .unwrap();
+        assert_eq!(signature.as_bytes().as_slice(), record.bytes("sig"));
+    }
+}
+
+#[cfg(feature = "signing")]
+#[test]
+fn ml_dsa_87_signing_keys_parse() {
+    for record in Record::all(ML_DSA_87) {
+        let signing_key = MlDsa87SigningKey::from_bytes(&record.bytes("sk")).unwrap();
+        let verifying_key = MlDsa87VerifyingKey::from_bytes(&record.bytes("pk")).unwrap();
+        let signature = MlDsa87::sign(&signing_key, b"fresh").unwrap();
+        MlDsa87::verify(&verifying_key, b"fresh", &signature).unwrap();
+    }
+}
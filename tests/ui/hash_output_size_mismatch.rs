@@ -0,0 +1,8 @@
+use citadel::hash::Sha3_256;
+use citadel::internal::traits::{HashContext, HashFunction};
+
+fn main() {
+    // A 32-byte hash where a 64-byte one is required
+    let context: Box<dyn HashContext<64>> = Sha3_256::new().new_context();
+    let _ = context.finalize();
+}
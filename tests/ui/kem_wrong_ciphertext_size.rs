@@ -0,0 +1,24 @@
+use citadel::errors::Result;
+use citadel::internal::traits::KeyEncapsulation;
+
+struct Kem768;
+
+impl KeyEncapsulation<1184, 2400, 1088, 32> for Kem768 {
+    fn generate_keypair(&self) -> Result<([u8; 1184], [u8; 2400])> {
+        unimplemented!()
+    }
+
+    fn encapsulate(&self, _: &[u8; 1184]) -> Result<([u8; 1088], [u8; 32])> {
+        unimplemented!()
+    }
+
+    fn decapsulate(&self, _: &[u8; 2400], _: &[u8; 1088]) -> Result<[u8; 32]> {
+        unimplemented!()
+    }
+}
+
+fn main() {
+    // An ML-KEM-1024 ciphertext handed to an ML-KEM-768 scheme
+    let ciphertext = [0u8; 1568];
+    let _ = Kem768.decapsulate(&[0u8; 2400], &ciphertext);
+}
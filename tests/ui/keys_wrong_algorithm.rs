@@ -0,0 +1,11 @@
+use citadel::keys::PublicKey;
+use citadel::kex::X25519;
+use citadel::sign::Ed25519;
+
+fn trust(_key: &PublicKey<Ed25519>) {}
+
+fn main() {
+    // Both are 32 bytes; only the marker tells them apart
+    let key = PublicKey::<X25519>::from_bytes(&[9; 32]).unwrap();
+    trust(&key);
+}
@@ -0,0 +1,10 @@
+use citadel::sign::{Ed25519, PublicKeyRef};
+
+fn main() {
+    let key = {
+        // A trust store dropped while a view into it is still in use
+        let store = vec![0u8; 32];
+        PublicKeyRef::<Ed25519>::from_bytes(&store).unwrap()
+    };
+    let _ = key.as_bytes();
+}
@@ -0,0 +1,9 @@
+use citadel::aead::SealOnceKey;
+use citadel::algorithm::AlgorithmId;
+
+fn main() {
+    let (key, _key_bytes) = SealOnceKey::generate(AlgorithmId::Aes256GcmSiv).unwrap();
+    let _first = key.seal(b"first", b"").unwrap();
+    // A second message under the same key and nonce
+    let _second = key.seal(b"second", b"").unwrap();
+}
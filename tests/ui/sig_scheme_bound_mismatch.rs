@@ -0,0 +1,11 @@
+use citadel::internal::traits::SignatureScheme;
+use citadel::sign::Ed25519;
+
+fn verify_release<S: SignatureScheme<2592, 4896, 4627>>(scheme: &S, signature: &[u8; 4627]) {
+    let _ = scheme.verify(&[0u8; 2592], b"release", signature);
+}
+
+fn main() {
+    // Code written for ML-DSA-87 sizes does not accept Ed25519
+    verify_release(&Ed25519, &[0u8; 4627]);
+}
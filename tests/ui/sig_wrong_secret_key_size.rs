@@ -0,0 +1,8 @@
+use citadel::internal::traits::SignatureScheme;
+use citadel::sign::Ed25519;
+
+fn main() {
+    // A 64-byte expanded Ed25519 key instead of the 32-byte seed
+    let expanded = [0u8; 64];
+    let _ = Ed25519.sign(&expanded, b"message");
+}